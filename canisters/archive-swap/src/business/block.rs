@@ -88,6 +88,7 @@ mod tests {
                         amm: "swap_v2_0.3%".try_into().unwrap(),
                     },
                     creator: user_id,
+                    pool: None,
                 })),
                 memo: None,
                 created: None,
@@ -62,6 +62,31 @@ fn inner_get_encoded_blocks(args: GetBlocksArgs) -> Result<Vec<EncodedBlock>, Ge
     Ok(response)
 }
 
+/// Blocks in the shape of icrc3 value, which is the callback of `token_icrc3_get_blocks` of swap canister
+#[ic_cdk::query(guard = "has_business_queryable")]
+fn token_icrc3_get_blocks(args: Vec<GetBlocksRequest>) -> GetBlocksResult {
+    let mut blocks = Vec::new();
+    for request in args {
+        let start = u64::try_from(&request.start.0).unwrap_or(u64::MAX);
+        let length = u64::try_from(&request.length.0).unwrap_or(u64::MAX);
+        // blocks out of this archive are ignored
+        let response = with_state(|s| s.business_blocks_get(start, length)).unwrap_or_default();
+        for (i, block) in response.into_iter().enumerate() {
+            let block: TokenBlock = trap(block.try_into());
+            blocks.push(BlockWithId {
+                id: Nat::from(start + i as u64),
+                block: block.into(),
+            });
+        }
+    }
+    let log_length = with_state(|s| s.business_latest_block_index_query()).map_or(0, |index| index + 1);
+    GetBlocksResult {
+        log_length: Nat::from(log_length),
+        blocks,
+        archived_blocks: vec![],
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use candid::Principal;
@@ -21,6 +21,9 @@ pub use ::common::types::{
 #[allow(unused)]
 pub use ::common::utils::pb::{Message, from_proto_bytes, to_proto_bytes};
 
+#[allow(unused)]
+pub use icrc_ledger_types::icrc3::blocks::{BlockWithId, GetBlocksRequest, GetBlocksResult};
+
 #[allow(unused)]
 pub use ic_canister_kit::common::trap;
 
@@ -17,13 +17,7 @@ fn config_token_custom_query() -> Vec<TokenInfo> {
 
 #[ic_cdk::update(guard = "has_business_config_custom_token")]
 async fn config_token_custom_put(token: TokenInfo) {
-    // preset can not modify
-    if with_state(|s| s.business_config_token_preset_query().contains_key(&token.canister_id)) {
-        ic_cdk::trap("can not put preset token");
-    }
-    if with_state(|s| s.business_dummy_tokens_query().contains_key(&token.canister_id)) {
-        ic_cdk::trap("can not put dummy token");
-    }
+    check_custom_token(&token.canister_id);
 
     // check standard
     let service = crate::services::icrc2::Service(token.canister_id);
@@ -59,6 +53,75 @@ async fn config_token_custom_put(token: TokenInfo) {
     with_mut_state(|s| s.business_config_token_custom_put(arg))
 }
 
+// the name, symbol, decimals and fee are fetched from the ledger, the existed custom token is kept unless overwrite
+#[ic_cdk::update(guard = "has_business_config_custom_token")]
+async fn config_token_custom_import(
+    canister_id: CanisterId,
+    overwrite: Option<bool>,
+) -> Result<TokenInfo, BusinessError> {
+    check_custom_token(&canister_id);
+    let overwrite = overwrite.unwrap_or_default();
+    with_state(|s| check_custom_token_exist(&s.business_config_token_custom_query(), &canister_id, overwrite))?;
+
+    // fetch metadata
+    let service = crate::services::icrc2::Service(canister_id);
+    let (name, symbol, decimals, fee, supported) = futures::future::join5(
+        service.icrc_1_name(),
+        service.icrc_1_symbol(),
+        service.icrc_1_decimals(),
+        service.icrc_1_fee(),
+        service.icrc_1_supported_standards(),
+    )
+    .await;
+    let token = imported_token(canister_id, name?, symbol?, decimals?, fee?, &supported?)?;
+
+    // ? check controller
+
+    // ! check again, the token may be put by others during the calls
+    with_state(|s| check_custom_token_exist(&s.business_config_token_custom_query(), &canister_id, overwrite))?;
+
+    let arg = ArgWithMeta::data(token.clone());
+    with_mut_state(|s| s.business_config_token_custom_put(arg));
+    Ok(token)
+}
+
+// the existed custom token can only be replaced by overwrite
+fn check_custom_token_exist(
+    custom: &[TokenInfo],
+    canister_id: &CanisterId,
+    overwrite: bool,
+) -> Result<(), BusinessError> {
+    if !overwrite && custom.iter().any(|t| t.canister_id == *canister_id) {
+        return Err(BusinessError::system_error(format!(
+            "custom token is already exist: [{}]",
+            canister_id.to_text()
+        )));
+    }
+    Ok(())
+}
+
+// the token fetched from ledger, which must support ICRC-2
+fn imported_token(
+    canister_id: CanisterId,
+    name: String,
+    symbol: String,
+    decimals: u8,
+    fee: candid::Nat,
+    supported: &[crate::services::icrc2::StandardRecord],
+) -> Result<TokenInfo, BusinessError> {
+    if !supported.iter().any(|s| s.name == "ICRC-2") {
+        return Err(BusinessError::system_error("token standard not match"));
+    }
+    Ok(TokenInfo {
+        canister_id,
+        name,
+        symbol,
+        decimals,
+        fee,
+        is_lp_token: false,
+    })
+}
+
 #[ic_cdk::update(guard = "has_business_config_custom_token")]
 async fn config_token_custom_remove(canister_id: CanisterId) -> Option<TokenInfo> {
     // preset can not modify
@@ -83,3 +146,81 @@ async fn config_token_custom_remove(canister_id: CanisterId) -> Option<TokenInfo
     let arg = ArgWithMeta::data(canister_id);
     with_mut_state(|s| s.business_config_token_custom_remove(arg))
 }
+
+// preset and dummy token can not be put
+fn check_custom_token(canister_id: &CanisterId) {
+    if with_state(|s| s.business_config_token_preset_query().contains_key(canister_id)) {
+        ic_cdk::trap("can not put preset token");
+    }
+    if with_state(|s| s.business_dummy_tokens_query().contains_key(canister_id)) {
+        ic_cdk::trap("can not put dummy token");
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::services::icrc2::StandardRecord;
+
+    fn standards(names: &[&str]) -> Vec<StandardRecord> {
+        names
+            .iter()
+            .map(|name| StandardRecord {
+                url: String::new(),
+                name: name.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_imported_token() {
+        let canister_id = CanisterId::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai").unwrap();
+
+        let token = imported_token(
+            canister_id,
+            "Internet Computer".into(),
+            "ICP".into(),
+            8,
+            candid::Nat::from(10_000_u64),
+            &standards(&["ICRC-1", "ICRC-2"]),
+        )
+        .unwrap();
+        assert_eq!(token.canister_id, canister_id);
+        assert_eq!(token.name, "Internet Computer");
+        assert_eq!(token.symbol, "ICP");
+        assert_eq!(token.decimals, 8);
+        assert_eq!(token.fee, candid::Nat::from(10_000_u64));
+        assert!(!token.is_lp_token);
+
+        // only ICRC-1 is not enough
+        let result = imported_token(
+            canister_id,
+            "Internet Computer".into(),
+            "ICP".into(),
+            8,
+            candid::Nat::from(10_000_u64),
+            &standards(&["ICRC-1"]),
+        );
+        assert!(matches!(result, Err(BusinessError::SystemError(_))));
+    }
+
+    #[test]
+    fn test_check_custom_token_exist() {
+        let canister_id = CanisterId::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai").unwrap();
+        let other = CanisterId::from_text("mxzaz-hqaaa-aaaar-qaada-cai").unwrap();
+        let custom = vec![TokenInfo {
+            canister_id,
+            name: "Internet Computer".into(),
+            symbol: "ICP".into(),
+            decimals: 8,
+            fee: candid::Nat::from(10_000_u64),
+            is_lp_token: false,
+        }];
+
+        assert!(check_custom_token_exist(&custom, &canister_id, false).is_err());
+        assert!(check_custom_token_exist(&custom, &canister_id, true).is_ok());
+        assert!(check_custom_token_exist(&custom, &other, false).is_ok());
+        assert!(check_custom_token_exist(&[], &canister_id, false).is_ok());
+    }
+}
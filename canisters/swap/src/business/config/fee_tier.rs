@@ -0,0 +1,23 @@
+#[allow(unused)]
+use ic_canister_kit::identity::caller;
+
+#[allow(unused)]
+use crate::stable::*;
+#[allow(unused)]
+use crate::types::*;
+
+// ============================== query ==============================
+
+#[ic_cdk::query]
+fn config_fee_tiers_query() -> Vec<(AmmText, FeeTier)> {
+    with_state(|s| s.business_config_fee_tiers_query())
+}
+
+// ============================== register ==============================
+
+#[ic_cdk::update(guard = "has_business_config_maintaining")]
+fn config_fee_tier_register(amm: AmmText, protocol_fee: Option<SwapRatio>) -> Result<FeeTier, BusinessError> {
+    let amm: Amm = amm.as_ref().try_into()?; // parse amm, such as "swap_v2_0.25%"
+    let protocol_fee = protocol_fee.map(|pf| SwapRatio::new(pf.numerator, pf.denominator));
+    with_mut_state(|s| s.business_config_fee_tier_register(amm, protocol_fee))
+}
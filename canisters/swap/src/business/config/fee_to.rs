@@ -41,3 +41,155 @@ fn config_protocol_fee_replace(subaccount: Subaccount, protocol_fee: Option<Swap
     };
     with_mut_state(|s| s.business_config_protocol_fee_replace(&lock, &pa, protocol_fee))
 }
+
+// ============================== protocol fees ==============================
+
+#[ic_cdk::query(guard = "has_business_config_fee_to")]
+fn config_protocol_fee_recipients_query() -> Vec<ProtocolFeeRecipient> {
+    with_state(|s| s.business_config_protocol_fee_recipients_query())
+}
+
+#[ic_cdk::update(guard = "has_business_config_fee_to")]
+fn config_protocol_fee_recipients_replace(
+    recipients: Vec<ProtocolFeeRecipient>,
+) -> Result<Vec<ProtocolFeeRecipient>, BusinessError> {
+    with_mut_state(|s| s.business_config_protocol_fee_recipients_replace(recipients))
+}
+
+impl CheckArgs for ProtocolFeesCollectArgs {
+    type Result = (
+        TimestampNanos,
+        Vec<CanisterId>,
+        Vec<TokenAccount>,
+        SelfCanister,
+        Caller,
+        ProtocolFeesCollectArg,
+    );
+    fn check_args(&self) -> Result<Self::Result, BusinessError> {
+        let (self_canister, _) = check_self_canister();
+        let caller = Caller::get();
+
+        // check fee to
+        let FeeTo {
+            token_fee_to,
+            swap_fee_to,
+        } = with_state(|s| s.business_config_fee_to_query());
+        let swap_fee_to = swap_fee_to.ok_or_else(|| BusinessError::Swap("PROTOCOL_FEE_TO_NOT_SET".into()))?;
+
+        // check recipients
+        let recipients = with_state(|s| s.business_config_protocol_fee_recipients_query());
+        if recipients.is_empty() {
+            return Err(BusinessError::Swap("PROTOCOL_FEE_RECIPIENTS_NOT_SET".into()));
+        }
+
+        // check pools, lp of swap fee to is burned
+        let mut fee_tokens = vec![];
+        let mut required = vec![];
+        let mut pas = Vec::with_capacity(self.pools.len());
+        for pool in &self.pools {
+            // ! refuse all action about frozen token
+            with_state(|s| s.business_token_alive(&pool.token.0))?;
+            with_state(|s| s.business_token_alive(&pool.token.1))?;
+            let (pa, dummy_tokens, pool_required) = check_pool(pool, &self_canister, Some(&swap_fee_to))?;
+            if pas.contains(&pa) {
+                return Err(BusinessError::Swap("DUPLICATED_POOL".into()));
+            }
+            pas.push(pa);
+            fee_tokens.extend(dummy_tokens);
+            fee_tokens.push(pa.pair.get_token0());
+            fee_tokens.push(pa.pair.get_token1());
+            required.extend(pool_required);
+        }
+
+        // check tokens, transfer fees of token fee to are split
+        for token in &self.tokens {
+            with_state(|s| s.business_token_alive(token))?;
+            if !fee_tokens.contains(token) {
+                fee_tokens.push(*token);
+            }
+        }
+        if pas.is_empty() && (self.tokens.is_empty() || token_fee_to.is_none()) {
+            return Err(BusinessError::Swap("NOTHING_TO_COLLECT".into()));
+        }
+
+        // all recipients would receive every token
+        fee_tokens.sort();
+        fee_tokens.dedup();
+        for token in &fee_tokens {
+            for recipient in &recipients {
+                required.push(TokenAccount::new(*token, recipient.account));
+            }
+        }
+
+        // check meta
+        let now = check_meta(&self.memo, &self.created)?;
+
+        let arg = ProtocolFeesCollectArg {
+            self_canister,
+            swap_fee_to,
+            token_fee_to,
+            pas,
+            tokens: self.tokens.clone(),
+            recipients,
+        };
+
+        Ok((now, fee_tokens, required, self_canister, caller, arg))
+    }
+}
+
+// burn lp and split fees of protocol
+#[ic_cdk::update(guard = "has_business_config_fee_to")]
+async fn protocol_fees_collect(args: ProtocolFeesCollectArgs, retries: Option<u8>) -> ProtocolFeesCollectResult {
+    inner_protocol_fees_collect(args, retries).await.into()
+}
+#[inline]
+async fn inner_protocol_fees_collect(
+    args: ProtocolFeesCollectArgs,
+    retries: Option<u8>,
+) -> Result<ProtocolFeesCollectSuccess, BusinessError> {
+    // 1. check args
+    let (now, fee_tokens, required, self_canister, caller, arg) = args.check_args()?;
+
+    // 2. lock
+    let locks = match super::super::lock_token_block_chain_and_swap_block_chain_and_token_balances_and_token_pairs(
+        fee_tokens,
+        required,
+        arg.pas.clone(),
+        retries.unwrap_or_default(),
+    )? {
+        LockResult::Locked(locks) => locks,
+        LockResult::Retry(retries) => {
+            return retry_protocol_fees_collect(self_canister.id(), args, retries).await;
+        }
+    };
+
+    // * 3. do business
+    let success = with_mut_state(|s| {
+        s.business_protocol_fees_collect(
+            &locks,
+            ArgWithMeta {
+                now,
+                caller,
+                arg,
+                memo: args.memo,
+                created: args.created,
+            },
+        )
+    })?;
+
+    // Asynchronously triggers synchronization tasks
+    crate::business::config::push::inner_push_blocks(true, true);
+
+    Ok(success)
+}
+// ! This implicitly contains self_canister_id, which can be called again through permission checks and replaces caller.
+#[inline]
+async fn retry_protocol_fees_collect(
+    self_canister_id: CanisterId,
+    args: ProtocolFeesCollectArgs,
+    retries: u8,
+) -> Result<ProtocolFeesCollectSuccess, BusinessError> {
+    ic_cdk::println!("🔄 retry_protocol_fees_collect: {}", retries);
+    let service_swap = crate::services::swap::Service(self_canister_id);
+    return service_swap.protocol_fees_collect(args, Some(retries)).await;
+}
@@ -33,3 +33,120 @@ fn config_maintain_pools() -> String {
         Err(e) => e.to_string(),
     }
 }
+
+// ============================== amplification ==============================
+
+#[ic_cdk::update(guard = "has_business_config_maintaining")]
+fn config_amplification_replace(subaccount: Subaccount, amplification: u64) -> Result<u64, BusinessError> {
+    let now = TimestampNanos::now();
+    let pa = with_state(|s| {
+        s.business_token_pair_pools_query()
+            .into_iter()
+            .find(|(pa, _)| pa.get_subaccount() == subaccount)
+            .map(|(pa, _)| pa)
+    })
+    .ok_or_else(|| BusinessError::Swap("INVALID_POOL".into()))?;
+
+    let required = vec![pa];
+    let locks = match super::super::lock_swap_block_chain_and_token_pairs(required, 0)? {
+        LockResult::Locked(locks) => locks,
+        LockResult::Retry(_) => return Err(BusinessError::SwapBlockChainLocked),
+    };
+    let old = with_mut_state(|s| s.business_config_amplification_replace(&locks, now, &pa, amplification))?;
+
+    // Asynchronously triggers synchronization tasks
+    crate::business::config::push::inner_push_blocks(false, true);
+
+    Ok(old)
+}
+
+// ============================== pmm price ==============================
+
+#[ic_cdk::update(guard = "has_business_config_maintaining")]
+fn config_pmm_v1_price_replace(subaccount: Subaccount, i: Nat, k: Option<Nat>) -> Result<(Nat, Nat), BusinessError> {
+    let now = TimestampNanos::now();
+    let pa = with_state(|s| {
+        s.business_token_pair_pools_query()
+            .into_iter()
+            .find(|(pa, _)| pa.get_subaccount() == subaccount)
+            .map(|(pa, _)| pa)
+    })
+    .ok_or_else(|| BusinessError::Swap("INVALID_POOL".into()))?;
+
+    let required = vec![pa];
+    let locks = match super::super::lock_swap_block_chain_and_token_pairs(required, 0)? {
+        LockResult::Locked(locks) => locks,
+        LockResult::Retry(_) => return Err(BusinessError::SwapBlockChainLocked),
+    };
+    let old = with_mut_state(|s| s.business_config_pmm_v1_price_replace(&locks, now, &pa, i, k))?;
+
+    // Asynchronously triggers synchronization tasks
+    crate::business::config::push::inner_push_blocks(false, true);
+
+    Ok(old)
+}
+
+// ============================== dynamic fee ==============================
+
+#[ic_cdk::update(guard = "has_business_config_maintaining")]
+fn config_dynamic_fee_replace(
+    subaccount: Subaccount,
+    dynamic_fee: Option<SwapV2DynamicFeeArg>,
+) -> Result<Option<SwapV2DynamicFee>, BusinessError> {
+    let pa = with_state(|s| {
+        s.business_token_pair_pools_query()
+            .into_iter()
+            .find(|(pa, _)| pa.get_subaccount() == subaccount)
+            .map(|(pa, _)| pa)
+    })
+    .ok_or_else(|| BusinessError::Swap("INVALID_POOL".into()))?;
+
+    let required = vec![pa];
+    let lock = match super::super::lock_token_pairs(required, 0)? {
+        LockResult::Locked(lock) => lock,
+        LockResult::Retry(_) => unreachable!(),
+    };
+    with_mut_state(|s| s.business_config_dynamic_fee_replace(&lock, &pa, dynamic_fee))
+}
+
+// ============================== outer lp ==============================
+
+// hand the lp of an empty pool to an icrc1 ledger, whose minting account must be the swap canister
+#[ic_cdk::update(guard = "has_business_config_maintaining")]
+async fn config_outer_lp_replace(subaccount: Subaccount, token_canister_id: CanisterId) -> Result<(), BusinessError> {
+    let (self_canister, _) = check_self_canister();
+    let pa = with_state(|s| {
+        s.business_token_pair_pools_query()
+            .into_iter()
+            .find(|(pa, _)| pa.get_subaccount() == subaccount)
+            .map(|(pa, _)| pa)
+    })
+    .ok_or_else(|| BusinessError::Swap("INVALID_POOL".into()))?;
+    let lp = with_state(|s| s.business_token_query_by_pa(&pa))
+        .ok_or_else(|| BusinessError::Swap("OUTER_LP_NOT_SUPPORTED".into()))?;
+
+    // check ledger
+    let service_icrc2 = crate::services::icrc2::Service(token_canister_id);
+    let minting_account = service_icrc2.icrc_1_minting_account().await?;
+    if minting_account
+        != Some(Account {
+            owner: self_canister.id(),
+            subaccount: None,
+        })
+    {
+        return Err(BusinessError::Swap("INVALID_OUTER_LP_MINTING_ACCOUNT".into()));
+    }
+    if service_icrc2.icrc_1_decimals().await? != lp.decimals {
+        return Err(BusinessError::Swap("INVALID_OUTER_LP_DECIMALS".into()));
+    }
+    if service_icrc2.icrc_1_total_supply().await? != ::common::utils::math::zero() {
+        return Err(BusinessError::Swap("INVALID_OUTER_LP_TOTAL_SUPPLY".into()));
+    }
+
+    let required = vec![pa];
+    let lock = match super::super::lock_token_pairs(required, 0)? {
+        LockResult::Locked(lock) => lock,
+        LockResult::Retry(_) => unreachable!(),
+    };
+    with_mut_state(|s| s.business_config_outer_lp_replace(&lock, &pa, token_canister_id))
+}
@@ -8,12 +8,20 @@ use crate::types::*;
 
 mod fee_to;
 
+mod fee_tier;
+
+mod router;
+
 mod blockchain;
 
 mod maintain;
 
 mod frozen;
 
+pub mod reconcile;
+
 mod custom;
 
+pub mod token_fee;
+
 pub mod push;
@@ -0,0 +1,158 @@
+#[allow(unused)]
+use ic_canister_kit::identity::caller;
+
+#[allow(unused)]
+use crate::stable::*;
+#[allow(unused)]
+use crate::types::*;
+
+// ============================== query ==============================
+
+#[ic_cdk::query]
+fn config_token_reconcile_query() -> TokenReconcileConfig {
+    with_state(|s| {
+        let reconciliation = s.business_config_token_reconcile_query();
+        TokenReconcileConfig {
+            deficit_threshold: reconciliation.deficit_threshold.clone(),
+            checking_interval_ns: reconciliation.checking_interval_ns,
+        }
+    })
+}
+
+#[ic_cdk::query]
+fn token_reconcile_reports_query() -> Vec<TokenReconcileReport> {
+    with_state(|s| s.business_config_token_reconcile_query().get_reports())
+}
+
+// ============================== update ==============================
+
+#[ic_cdk::update(guard = "has_business_config_maintaining")]
+fn config_token_reconcile_set(config: TokenReconcileConfig) {
+    with_mut_state(|s| s.business_config_token_reconcile_set(config))
+}
+
+#[ic_cdk::update(guard = "has_business_config_maintaining")]
+async fn config_token_reconcile_trigger() -> Result<Vec<TokenReconcileReport>, BusinessError> {
+    reconcile_tokens().await
+}
+
+// ============================== reconcile ==============================
+
+/// Compare internal balances of each token with the balance of swap canister on the real ledger.
+/// The token whose deficit exceeds the threshold is frozen.
+pub async fn reconcile_tokens() -> Result<Vec<TokenReconcileReport>, BusinessError> {
+    with_state(|s| s.pause_must_be_running()).map_err(BusinessError::system_error)?;
+
+    // ! marked before any await, the check is never run twice at the same time
+    let now = TimestampNanos::now();
+    if !with_mut_state(|s| s.business_config_token_reconcile_start(now)) {
+        return Err(BusinessError::Swap("TOKEN_RECONCILE_IN_PROGRESS".into()));
+    }
+    let result = inner_reconcile_tokens(now).await;
+    with_mut_state(|s| match &result {
+        Ok(_) => s.business_config_token_reconcile_checked(now), // ! only a completed check moves the trigger forward
+        Err(_) => s.business_config_token_reconcile_stop(),
+    });
+    result
+}
+async fn inner_reconcile_tokens(now: TimestampNanos) -> Result<Vec<TokenReconcileReport>, BusinessError> {
+    let (self_canister, caller) = check_self_canister();
+    let tokens = with_state(|s| s.business_tokens_query().keys().copied().collect::<Vec<_>>());
+
+    // 1. no token business in progress, the internal balances are settled
+    let height = idle_token_business(&tokens)?;
+
+    // 2. query ledgers
+    let self_account = Account {
+        owner: self_canister.id(),
+        subaccount: None,
+    };
+    let ledgers = futures::future::join_all(tokens.iter().map(|token| async move {
+        crate::services::icrc2::Service(*token)
+            .icrc_1_balance_of(self_account)
+            .await
+    }))
+    .await;
+
+    // 3. ! any token business started or done during querying, the ledgers can not be compared
+    if idle_token_business(&tokens)? != height {
+        return Err(BusinessError::Swap("TOKEN_BLOCK_CHAIN_CHANGED".into()));
+    }
+
+    // 4. compare
+    let pools = with_state(|s| {
+        s.business_token_pair_pools_query()
+            .into_iter()
+            .map(|(_, maker)| maker)
+            .chain(s.business_token_pools_query().into_iter().map(|(_, maker)| maker))
+            .flat_map(|maker| maker.accounts(&self_canister))
+            .collect::<HashSet<_>>()
+    });
+    let mut reports = Vec::with_capacity(tokens.len());
+    for (token, ledger) in tokens.into_iter().zip(ledgers) {
+        let ledger = match ledger {
+            Ok(ledger) => ledger,
+            Err(err) => {
+                ic_cdk::println!("reconcile token [{}] failed: {err:?}", token.to_text());
+                continue;
+            }
+        };
+        let (accounts, pools) = with_state(|s| s.business_token_balances_sum(token, &pools))
+            .ok_or_else(|| BusinessError::Swap("TOKEN_BALANCES_NOT_COUNTED".into()))?;
+        let internal = accounts.clone() + pools.clone();
+        let deficit = if ledger < internal {
+            internal.clone() - ledger.clone()
+        } else {
+            Nat::default()
+        };
+
+        // ! pause the token automatically
+        let frozen = with_state(|s| {
+            s.business_token_alive(&token).is_ok()
+                && s.business_config_token_reconcile_query()
+                    .is_deficit_exceeded(&internal, &deficit)
+        });
+        if frozen {
+            ic_cdk::println!(
+                "reconcile token [{}] deficit: {deficit}, internal: {internal}, ledger: {ledger}",
+                token.to_text()
+            );
+            with_mut_state(|s| {
+                s.business_config_token_frozen(ArgWithMeta::simple(now, caller, TokenFrozenArg { token, frozen }))
+            });
+        }
+
+        let report = TokenReconcileReport {
+            token,
+            accounts,
+            pools,
+            ledger,
+            deficit,
+            frozen,
+            checked_at: now,
+        };
+        with_mut_state(|s| s.business_token_reconcile_report(report.clone()));
+        reports.push(report);
+    }
+
+    Ok(reports)
+}
+
+// the height of next token block, if neither the balances of tokens nor the token block chain is locked
+fn idle_token_business(tokens: &[CanisterId]) -> Result<BlockIndex, BusinessError> {
+    let locked = with_state(|s| s.business_token_balance_locked_accounts())
+        .into_iter()
+        .filter(|token_account| tokens.contains(&token_account.token))
+        .collect::<Vec<_>>();
+    if !locked.is_empty() {
+        return Err(BusinessError::TokenAccountsLocked(locked));
+    }
+
+    let lock = match super::super::lock_token_block_chain(0)? {
+        LockResult::Locked(lock) => lock,
+        LockResult::Retry(_) => unreachable!(),
+    };
+    let height = with_state(|s| s.business_config_token_block_chain_query().next_block_index);
+    drop(lock);
+    Ok(height)
+}
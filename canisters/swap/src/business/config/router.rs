@@ -0,0 +1,21 @@
+#[allow(unused)]
+use ic_canister_kit::identity::caller;
+
+#[allow(unused)]
+use crate::stable::*;
+#[allow(unused)]
+use crate::types::*;
+
+// ============================== query ==============================
+
+#[ic_cdk::query]
+fn config_router_max_hops_query() -> u8 {
+    with_state(|s| s.business_config_router_max_hops_query())
+}
+
+// ============================== update ==============================
+
+#[ic_cdk::update(guard = "has_business_config_maintaining")]
+fn config_router_max_hops_set(max_hops: u8) -> Result<u8, BusinessError> {
+    with_mut_state(|s| s.business_config_router_max_hops_set(max_hops))
+}
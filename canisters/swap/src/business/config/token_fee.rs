@@ -0,0 +1,65 @@
+#[allow(unused)]
+use ic_canister_kit::identity::caller;
+
+#[allow(unused)]
+use crate::stable::*;
+#[allow(unused)]
+use crate::types::*;
+
+// ============================== query ==============================
+
+// the fee changed by ledger, which overrides the listed fee
+#[ic_cdk::query]
+fn config_token_fee_changed_query() -> HashMap<CanisterId, Nat> {
+    with_state(|s| s.business_config_token_fee_query().clone())
+}
+
+// ============================== update ==============================
+
+#[ic_cdk::update(guard = "has_business_config_maintaining")]
+fn config_token_fee_checking_set(checking_interval_ns: u64) {
+    with_mut_state(|s| s.business_config_token_fee_checking_set(checking_interval_ns))
+}
+
+#[ic_cdk::update(guard = "has_business_config_maintaining")]
+async fn config_token_fee_check() -> HashMap<CanisterId, Nat> {
+    check_token_fees().await
+}
+
+// ============================== check ==============================
+
+/// Poll the fee of every listed token, and update the fee changed by ledger.
+/// Return the old fee of changed tokens.
+pub async fn check_token_fees() -> HashMap<CanisterId, Nat> {
+    let tokens = with_state(|s| s.business_tokens_query().keys().copied().collect::<Vec<_>>());
+    let fees = futures::future::join_all(
+        tokens
+            .iter()
+            .map(|token| async move { crate::services::icrc2::Service(*token).icrc_1_fee().await }),
+    )
+    .await;
+
+    let (_, caller) = check_self_canister();
+    let now = TimestampNanos::now();
+    let mut changed = HashMap::new();
+    for (token, fee) in tokens.into_iter().zip(fees) {
+        match fee {
+            Ok(fee) => {
+                let arg = ArgWithMeta::simple(
+                    now,
+                    caller,
+                    TokenFeeUpdateArg {
+                        token,
+                        fee: fee.clone(),
+                    },
+                );
+                if let Some(old) = with_mut_state(|s| s.business_config_token_fee_update(arg)) {
+                    ic_cdk::println!("token [{}] fee changed: {old} -> {fee}", token.to_text());
+                    changed.insert(token, old);
+                }
+            }
+            Err(err) => ic_cdk::println!("check token [{}] fee failed: {err:?}", token.to_text()),
+        }
+    }
+    changed
+}
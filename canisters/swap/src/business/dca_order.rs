@@ -0,0 +1,294 @@
+#[allow(unused)]
+use ic_canister_kit::identity::caller;
+
+#[allow(unused)]
+use crate::stable::*;
+#[allow(unused)]
+use crate::types::*;
+
+use ::common::utils::math::zero;
+
+// ============================== query ==============================
+
+// anyone can query
+#[ic_cdk::query]
+fn dca_orders_query(owner: Account) -> Vec<DcaOrder> {
+    with_state(|s| s.business_dca_orders_query(Some(&owner)))
+}
+
+#[ic_cdk::query]
+fn dca_order_get(id: u64) -> Option<DcaOrder> {
+    with_state(|s| s.business_dca_order_get(id))
+}
+
+// ============================== place ==============================
+
+impl CheckArgs for DcaOrderPlaceArgs {
+    type Result = (TimestampNanos, SelfCanister, Caller, DcaOrder);
+    fn check_args(&self) -> Result<Self::Result, BusinessError> {
+        // ! refuse all action about frozen token
+        for p in &self.path {
+            with_state(|s| s.business_token_alive(&p.token.0))?;
+            with_state(|s| s.business_token_alive(&p.token.1))?;
+        }
+
+        // check owner
+        let (self_canister, caller) = check_caller(&self.from.owner)?;
+
+        // check pools
+        let mut pas = vec![];
+        for pool in &self.path {
+            let (pa, _, _) = check_pool(pool, &self_canister, None)?;
+            pas.push(pa);
+        }
+
+        // check path
+        check_path(&self.path)?;
+
+        // check slices
+        if self.slices == 0 || MAX_DCA_ORDER_SLICES < self.slices {
+            return Err(BusinessError::Swap("INVALID_DCA_ORDER_SLICES".into()));
+        }
+        let slice_amount_in = self.amount_in.clone() / self.slices;
+        if slice_amount_in == zero() {
+            return Err(BusinessError::Swap("INSUFFICIENT_INPUT_AMOUNT".into()));
+        }
+
+        // check balance in
+        let token_in = self.path[0].token.0;
+        let balance_in = with_state(|s| s.business_token_balance_of(token_in, self.from));
+        if balance_in < self.amount_in {
+            return Err(BusinessError::insufficient_balance(token_in, balance_in));
+        }
+
+        // check meta
+        let now = check_meta(&self.memo, &self.created)?;
+
+        // check interval
+        let interval_ns = self.interval_ns(now)?;
+
+        // the path must be swappable, no matter the price
+        with_state(|s| {
+            s.business_token_pair_swap_fixed_in_checking(&TokenPairSwapExactTokensForTokensArg {
+                self_canister,
+                pas,
+                from: self.from,
+                amount_in: slice_amount_in,
+                amount_out_min: zero(),
+                path: self.path.clone(),
+                to: self.to,
+            })
+        })?;
+
+        let order = DcaOrder {
+            id: 0, // set by placing
+            owner: self.from,
+            amount_in: self.amount_in.clone(),
+            slices: self.slices,
+            slice_amount_out_min: self.slice_amount_out_min.clone(),
+            path: self.path.clone(),
+            to: self.to,
+            interval_ns,
+            executed_slices: 0,
+            executed_amount_in: zero(),
+            executed_amount_out: zero(),
+            next_execution: now, // the first slice is executed by next schedule
+            placed: now,
+        };
+
+        Ok((now, self_canister, caller, order))
+    }
+}
+
+// check forbidden
+#[ic_cdk::update(guard = "has_business_token_pair_swap")]
+async fn dca_order_place(args: DcaOrderPlaceArgs, retries: Option<u8>) -> DcaOrderResult {
+    inner_dca_order_place(args, retries).await.into()
+}
+#[inline]
+async fn inner_dca_order_place(args: DcaOrderPlaceArgs, retries: Option<u8>) -> Result<DcaOrder, BusinessError> {
+    // 1. check args
+    let (now, self_canister, caller, order) = args.check_args()?;
+
+    // 2. some value
+    let fee_tokens = vec![]; // ! no fee for escrow
+    let token_in = args.path[0].token.0;
+    let token_account_from = TokenAccount::new(token_in, args.from);
+    let token_account_escrow = TokenAccount::new(token_in, DcaOrder::escrow(self_canister.id()));
+    let required = vec![token_account_from, token_account_escrow];
+
+    // 3. lock
+    let locks =
+        match super::lock_token_block_chain_and_token_balances(fee_tokens, required, retries.unwrap_or_default())? {
+            LockResult::Locked(locks) => locks,
+            LockResult::Retry(retries) => {
+                return retry_dca_order_place(self_canister.id(), args, retries).await;
+            }
+        };
+
+    // * 4. do business
+    let order = with_mut_state(|s| {
+        s.business_dca_order_place(
+            &locks,
+            ArgWithMeta {
+                now,
+                caller,
+                arg: order,
+                memo: args.memo,
+                created: args.created,
+            },
+        )
+    })?;
+
+    // Asynchronously triggers synchronization tasks
+    crate::business::config::push::inner_push_blocks(true, false);
+
+    Ok(order)
+}
+// ! This implicitly contains self_canister_id, which can be called again through permission checks and replaces caller.
+#[inline]
+async fn retry_dca_order_place(
+    self_canister_id: CanisterId,
+    args: DcaOrderPlaceArgs,
+    retries: u8,
+) -> Result<DcaOrder, BusinessError> {
+    ic_cdk::println!("🔄 retry_dca_order_place: {}", retries);
+    let service_swap = crate::services::swap::Service(self_canister_id);
+    return service_swap.dca_order_place(args, Some(retries)).await;
+}
+
+// ============================== cancel ==============================
+
+// check forbidden
+#[ic_cdk::update(guard = "has_business_token_pair_swap")]
+async fn dca_order_cancel(id: u64, retries: Option<u8>) -> TokenChangedResult {
+    inner_dca_order_cancel(id, retries).await.into()
+}
+#[inline]
+async fn inner_dca_order_cancel(id: u64, retries: Option<u8>) -> Result<Nat, BusinessError> {
+    // 1. check args
+    let order = with_state(|s| s.business_dca_order_get(id))
+        .ok_or_else(|| BusinessError::Swap("DCA_ORDER_NOT_EXIST".into()))?;
+    // ! refuse all action about frozen token
+    with_state(|s| s.business_token_alive(&order.path[0].token.0))?;
+    // check owner
+    let (self_canister, caller) = check_caller(&order.owner.owner)?;
+    let now = TimestampNanos::now();
+
+    // 2. lock
+    let token_in = order.path[0].token.0;
+    let token_account_escrow = TokenAccount::new(token_in, DcaOrder::escrow(self_canister.id()));
+    let token_account_owner = TokenAccount::new(token_in, order.owner);
+    let locks = match super::lock_token_block_chain_and_token_balances(
+        vec![],
+        vec![token_account_escrow, token_account_owner],
+        retries.unwrap_or_default(),
+    )? {
+        LockResult::Locked(locks) => locks,
+        LockResult::Retry(retries) => {
+            return retry_dca_order_cancel(self_canister.id(), id, retries).await;
+        }
+    };
+
+    // * 3. do business, the unexecuted token in is returned
+    let changed = with_mut_state(|s| s.business_dca_order_cancel(&locks, ArgWithMeta::simple(now, caller, order)))?;
+
+    // Asynchronously triggers synchronization tasks
+    crate::business::config::push::inner_push_blocks(true, false);
+
+    Ok(changed)
+}
+// ! This implicitly contains self_canister_id, which can be called again through permission checks and replaces caller.
+#[inline]
+async fn retry_dca_order_cancel(self_canister_id: CanisterId, id: u64, retries: u8) -> Result<Nat, BusinessError> {
+    ic_cdk::println!("🔄 retry_dca_order_cancel: {}", retries);
+    let service_swap = crate::services::swap::Service(self_canister_id);
+    return service_swap.dca_order_cancel(id, Some(retries)).await;
+}
+
+// ============================== schedule ==============================
+
+/// Execute one slice of the due dca orders
+pub fn schedule_dca_orders() {
+    if with_state(|s| s.pause_must_be_running()).is_err() {
+        return;
+    }
+
+    let now = TimestampNanos::now();
+    let (self_canister, caller) = check_self_canister();
+    let orders = with_mut_state(|s| s.business_dca_orders_next_batch(now));
+
+    let mut executed = false;
+    for order in orders {
+        // ! refuse all action about frozen token
+        if order.path.iter().any(|p| {
+            with_state(|s| s.business_token_alive(&p.token.0).is_err() || s.business_token_alive(&p.token.1).is_err())
+        }) {
+            continue;
+        }
+
+        let id = order.id;
+        match execute_dca_order(now, self_canister, caller, order) {
+            Ok(done) => executed = executed || done,
+            Err(err) => ic_cdk::println!("schedule dca order {id} failed: {err:?}"),
+        }
+    }
+
+    // Asynchronously triggers synchronization tasks
+    if executed {
+        crate::business::config::push::inner_push_blocks(true, true);
+    }
+}
+
+fn execute_dca_order(
+    now: TimestampNanos,
+    self_canister: SelfCanister,
+    caller: Caller,
+    order: DcaOrder,
+) -> Result<bool, BusinessError> {
+    // check pools
+    let mut pas = vec![];
+    let mut fee_tokens = vec![];
+    let mut required = vec![];
+    for pool in &order.path {
+        let (pa, _fee_tokens, _required) = check_pool(pool, &self_canister, None)?;
+        pas.push(pa);
+        fee_tokens.extend(_fee_tokens);
+        required.extend(_required);
+    }
+
+    // the slice of escrowed token in is swapped to order.to, same as pay exact
+    let escrow = DcaOrder::escrow(self_canister.id());
+    let arg = TokenPairSwapExactTokensForTokensArg {
+        self_canister,
+        pas,
+        from: escrow,
+        amount_in: order.next_slice_amount_in(),
+        amount_out_min: order.next_slice_amount_out_min(),
+        path: order.path.clone(),
+        to: order.to,
+    };
+
+    // price guard, wait for next schedule
+    if with_state(|s| s.business_token_pair_swap_fixed_in_checking(&arg)).is_err() {
+        return Ok(false);
+    }
+
+    required.push(TokenAccount::new(order.path[0].token.0, escrow));
+    required.push(TokenAccount::new(order.path[order.path.len() - 1].token.1, order.to));
+
+    // ! no retry in schedule, try again next time
+    let locks = match super::lock_token_block_chain_and_swap_block_chain_and_token_balances_and_token_pairs(
+        fee_tokens,
+        required,
+        arg.pas.clone(),
+        0,
+    )? {
+        LockResult::Locked(locks) => locks,
+        LockResult::Retry(_) => unreachable!(),
+    };
+
+    with_mut_state(|s| s.business_dca_order_execute(&locks, order.id, ArgWithMeta::simple(now, caller, arg)))?;
+
+    Ok(true)
+}
@@ -0,0 +1,377 @@
+#[allow(unused)]
+use ic_canister_kit::identity::caller;
+
+#[allow(unused)]
+use crate::stable::*;
+#[allow(unused)]
+use crate::types::*;
+
+use ::common::utils::math::zero;
+
+// ============================== query ==============================
+
+// anyone can query
+#[ic_cdk::query]
+fn farms_query() -> Vec<FarmStatus> {
+    with_state(|s| s.business_farms_query(None))
+}
+
+#[ic_cdk::query]
+fn farm_get(id: u64) -> Option<FarmStatus> {
+    with_state(|s| s.business_farm_get(id))
+}
+
+#[ic_cdk::query]
+fn farm_staker_get(id: u64, account: Account) -> Option<FarmStaker> {
+    with_state(|s| s.business_farm_staker_get(id, &account, TimestampNanos::now()))
+}
+
+// ============================== create ==============================
+
+impl CheckArgs for FarmCreateArgs {
+    type Result = (TimestampNanos, SelfCanister, Caller, Farm);
+    fn check_args(&self) -> Result<Self::Result, BusinessError> {
+        // ! refuse all action about frozen token
+        with_state(|s| s.business_token_alive(&self.swap_pair.token.0))?;
+        with_state(|s| s.business_token_alive(&self.swap_pair.token.1))?;
+        with_state(|s| s.business_token_alive(&self.reward_token))?;
+
+        // check owner
+        let (self_canister, caller) = check_caller(&self.from.owner)?;
+
+        // check pool, only inner lp can be staked
+        let (pa, dummy_tokens, _) = check_pool(&self.swap_pair, &self_canister, None)?;
+        let [lp_token] = dummy_tokens[..] else {
+            return Err(BusinessError::Swap("FARM_LP_NOT_SUPPORTED".into()));
+        };
+
+        // check meta
+        let now = check_meta(&self.memo, &self.created)?;
+
+        // check time window and rate
+        if self.end.into_inner() <= self.start.into_inner().max(now.into_inner()) || self.reward_per_second == zero() {
+            return Err(BusinessError::Swap("INVALID_FARM".into()));
+        }
+
+        let farm = Farm {
+            id: 0, // set by creating
+            pa,
+            lp_token,
+            reward_token: self.reward_token,
+            reward_per_second: self.reward_per_second.clone(),
+            start: self.start,
+            end: self.end,
+            funder: self.from,
+            created: now,
+        };
+
+        // check balance of rewards
+        let balance = with_state(|s| s.business_token_balance_of(farm.reward_token, farm.funder));
+        if balance < farm.total_reward() {
+            return Err(BusinessError::insufficient_balance(farm.reward_token, balance));
+        }
+
+        Ok((now, self_canister, caller, farm))
+    }
+}
+
+// check forbidden
+#[ic_cdk::update(guard = "has_business_config_maintaining")]
+async fn farm_create(args: FarmCreateArgs, retries: Option<u8>) -> FarmResult {
+    inner_farm_create(args, retries).await.into()
+}
+#[inline]
+async fn inner_farm_create(args: FarmCreateArgs, retries: Option<u8>) -> Result<Farm, BusinessError> {
+    // 1. check args
+    let (now, self_canister, caller, farm) = args.check_args()?;
+
+    // 2. lock
+    let locks = match lock_farm_escrow(
+        &self_canister,
+        farm.reward_token,
+        farm.funder,
+        retries.unwrap_or_default(),
+    )? {
+        LockResult::Locked(locks) => locks,
+        LockResult::Retry(retries) => {
+            return retry_farm_create(self_canister.id(), args, retries).await;
+        }
+    };
+
+    // * 3. do business
+    let farm = with_mut_state(|s| {
+        s.business_farm_create(
+            &locks,
+            ArgWithMeta {
+                now,
+                caller,
+                arg: farm,
+                memo: args.memo,
+                created: args.created,
+            },
+        )
+    })?;
+
+    // Asynchronously triggers synchronization tasks
+    crate::business::config::push::inner_push_blocks(true, false);
+
+    Ok(farm)
+}
+// ! This implicitly contains self_canister_id, which can be called again through permission checks and replaces caller.
+#[inline]
+async fn retry_farm_create(
+    self_canister_id: CanisterId,
+    args: FarmCreateArgs,
+    retries: u8,
+) -> Result<Farm, BusinessError> {
+    ic_cdk::println!("🔄 retry_farm_create: {}", retries);
+    let service_swap = crate::services::swap::Service(self_canister_id);
+    return service_swap.farm_create(args, Some(retries)).await;
+}
+
+// ============================== refund ==============================
+
+// return the unallocated rewards to funder after end
+#[ic_cdk::update(guard = "has_business_config_maintaining")]
+async fn farm_refund(id: u64, retries: Option<u8>) -> TokenChangedResult {
+    inner_farm_refund(id, retries).await.into()
+}
+#[inline]
+async fn inner_farm_refund(id: u64, retries: Option<u8>) -> Result<Nat, BusinessError> {
+    // 1. check args
+    let farm = with_state(|s| s.business_farm_get(id))
+        .map(|status| status.farm)
+        .ok_or_else(|| BusinessError::Swap("FARM_NOT_EXIST".into()))?;
+    // ! refuse all action about frozen token
+    with_state(|s| s.business_token_alive(&farm.reward_token))?;
+    let (self_canister, _) = check_self_canister();
+    let caller = Caller::get();
+    let now = TimestampNanos::now();
+
+    // 2. lock
+    let locks = match lock_farm_escrow(
+        &self_canister,
+        farm.reward_token,
+        farm.funder,
+        retries.unwrap_or_default(),
+    )? {
+        LockResult::Locked(locks) => locks,
+        LockResult::Retry(retries) => {
+            return retry_farm_refund(self_canister.id(), id, retries).await;
+        }
+    };
+
+    // * 3. do business
+    let refund = with_mut_state(|s| s.business_farm_refund(&locks, ArgWithMeta::simple(now, caller, farm)))?;
+
+    // Asynchronously triggers synchronization tasks
+    crate::business::config::push::inner_push_blocks(true, false);
+
+    Ok(refund)
+}
+// ! This implicitly contains self_canister_id, which can be called again through permission checks and replaces caller.
+#[inline]
+async fn retry_farm_refund(self_canister_id: CanisterId, id: u64, retries: u8) -> Result<Nat, BusinessError> {
+    ic_cdk::println!("🔄 retry_farm_refund: {}", retries);
+    let service_swap = crate::services::swap::Service(self_canister_id);
+    return service_swap.farm_refund(id, Some(retries)).await;
+}
+
+// ============================== stake ==============================
+
+impl CheckArgs for FarmStakeArgs {
+    type Result = (TimestampNanos, SelfCanister, Caller, Farm, FarmStakeArg);
+    fn check_args(&self) -> Result<Self::Result, BusinessError> {
+        let farm = with_state(|s| s.business_farm_get(self.farm))
+            .map(|status| status.farm)
+            .ok_or_else(|| BusinessError::Swap("FARM_NOT_EXIST".into()))?;
+
+        // ! refuse all action about frozen token
+        with_state(|s| s.business_token_alive(&farm.pa.pair.get_token0()))?;
+        with_state(|s| s.business_token_alive(&farm.pa.pair.get_token1()))?;
+
+        // check owner
+        let (self_canister, caller) = check_caller(&self.from.owner)?;
+
+        // check amount
+        if self.amount == zero() {
+            return Err(BusinessError::Swap("INVALID_STAKE_AMOUNT".into()));
+        }
+
+        // check meta
+        let now = check_meta(&self.memo, &self.created)?;
+
+        let arg = FarmStakeArg {
+            farm: farm.id,
+            account: self.from,
+            amount: self.amount.clone(),
+        };
+
+        Ok((now, self_canister, caller, farm, arg))
+    }
+}
+
+// check forbidden
+#[ic_cdk::update(guard = "has_business_token_transfer")]
+async fn farm_stake(args: FarmStakeArgs, retries: Option<u8>) -> TokenChangedResult {
+    inner_farm_stake(args, retries).await.into()
+}
+#[inline]
+async fn inner_farm_stake(args: FarmStakeArgs, retries: Option<u8>) -> Result<Nat, BusinessError> {
+    // 1. check args
+    let (now, self_canister, caller, farm, arg) = args.check_args()?;
+
+    // 2. lock
+    let locks = match lock_farm_escrow(&self_canister, farm.lp_token, args.from, retries.unwrap_or_default())? {
+        LockResult::Locked(locks) => locks,
+        LockResult::Retry(retries) => {
+            return retry_farm_stake(self_canister.id(), args, retries).await;
+        }
+    };
+
+    // * 3. do business
+    let changed = with_mut_state(|s| {
+        s.business_farm_stake(
+            &locks,
+            ArgWithMeta {
+                now,
+                caller,
+                arg,
+                memo: args.memo,
+                created: args.created,
+            },
+        )
+    })?;
+
+    // Asynchronously triggers synchronization tasks
+    crate::business::config::push::inner_push_blocks(true, false);
+
+    Ok(changed)
+}
+// ! This implicitly contains self_canister_id, which can be called again through permission checks and replaces caller.
+#[inline]
+async fn retry_farm_stake(
+    self_canister_id: CanisterId,
+    args: FarmStakeArgs,
+    retries: u8,
+) -> Result<Nat, BusinessError> {
+    ic_cdk::println!("🔄 retry_farm_stake: {}", retries);
+    let service_swap = crate::services::swap::Service(self_canister_id);
+    return service_swap.farm_stake(args, Some(retries)).await;
+}
+
+// ============================== unstake ==============================
+
+// check forbidden
+#[ic_cdk::update(guard = "has_business_token_transfer")]
+async fn farm_unstake(args: FarmStakeArgs, retries: Option<u8>) -> TokenChangedResult {
+    inner_farm_unstake(args, retries).await.into()
+}
+#[inline]
+async fn inner_farm_unstake(args: FarmStakeArgs, retries: Option<u8>) -> Result<Nat, BusinessError> {
+    // 1. check args
+    let (now, self_canister, caller, farm, arg) = args.check_args()?;
+
+    // 2. lock
+    let locks = match lock_farm_escrow(&self_canister, farm.lp_token, args.from, retries.unwrap_or_default())? {
+        LockResult::Locked(locks) => locks,
+        LockResult::Retry(retries) => {
+            return retry_farm_unstake(self_canister.id(), args, retries).await;
+        }
+    };
+
+    // * 3. do business
+    let changed = with_mut_state(|s| {
+        s.business_farm_unstake(
+            &locks,
+            ArgWithMeta {
+                now,
+                caller,
+                arg,
+                memo: args.memo,
+                created: args.created,
+            },
+        )
+    })?;
+
+    // Asynchronously triggers synchronization tasks
+    crate::business::config::push::inner_push_blocks(true, false);
+
+    Ok(changed)
+}
+// ! This implicitly contains self_canister_id, which can be called again through permission checks and replaces caller.
+#[inline]
+async fn retry_farm_unstake(
+    self_canister_id: CanisterId,
+    args: FarmStakeArgs,
+    retries: u8,
+) -> Result<Nat, BusinessError> {
+    ic_cdk::println!("🔄 retry_farm_unstake: {}", retries);
+    let service_swap = crate::services::swap::Service(self_canister_id);
+    return service_swap.farm_unstake(args, Some(retries)).await;
+}
+
+// ============================== claim ==============================
+
+// check forbidden
+#[ic_cdk::update(guard = "has_business_token_transfer")]
+async fn farm_claim(id: u64, from: Account, retries: Option<u8>) -> TokenChangedResult {
+    inner_farm_claim(id, from, retries).await.into()
+}
+#[inline]
+async fn inner_farm_claim(id: u64, from: Account, retries: Option<u8>) -> Result<Nat, BusinessError> {
+    // 1. check args
+    let farm = with_state(|s| s.business_farm_get(id))
+        .map(|status| status.farm)
+        .ok_or_else(|| BusinessError::Swap("FARM_NOT_EXIST".into()))?;
+    // ! refuse all action about frozen token
+    with_state(|s| s.business_token_alive(&farm.reward_token))?;
+    // check owner
+    let (self_canister, caller) = check_caller(&from.owner)?;
+    let now = TimestampNanos::now();
+    let arg = FarmStakeArg {
+        farm: id,
+        account: from,
+        amount: zero(), // set by claiming
+    };
+
+    // 2. lock
+    let locks = match lock_farm_escrow(&self_canister, farm.reward_token, from, retries.unwrap_or_default())? {
+        LockResult::Locked(locks) => locks,
+        LockResult::Retry(retries) => {
+            return retry_farm_claim(self_canister.id(), id, from, retries).await;
+        }
+    };
+
+    // * 3. do business
+    let reward = with_mut_state(|s| s.business_farm_claim(&locks, ArgWithMeta::simple(now, caller, arg)))?;
+
+    // Asynchronously triggers synchronization tasks
+    crate::business::config::push::inner_push_blocks(true, false);
+
+    Ok(reward)
+}
+// ! This implicitly contains self_canister_id, which can be called again through permission checks and replaces caller.
+#[inline]
+async fn retry_farm_claim(
+    self_canister_id: CanisterId,
+    id: u64,
+    from: Account,
+    retries: u8,
+) -> Result<Nat, BusinessError> {
+    ic_cdk::println!("🔄 retry_farm_claim: {}", retries);
+    let service_swap = crate::services::swap::Service(self_canister_id);
+    return service_swap.farm_claim(id, from, Some(retries)).await;
+}
+
+#[inline]
+fn lock_farm_escrow(
+    self_canister: &SelfCanister,
+    token: CanisterId,
+    account: Account,
+    retries: u8,
+) -> Result<LockResult<(TokenBlockChainLock, TokenBalancesLock)>, BusinessError> {
+    let token_account_escrow = TokenAccount::new(token, Farm::escrow(self_canister.id()));
+    let token_account = TokenAccount::new(token, account);
+    super::lock_token_block_chain_and_token_balances(vec![], vec![token_account_escrow, token_account], retries)
+}
@@ -14,6 +14,14 @@ pub mod token;
 
 pub mod pair;
 
+pub mod pool;
+
+pub mod limit_order;
+
+pub mod dca_order;
+
+pub mod farm;
+
 #[inline]
 fn check_retries(retries: u8) {
     assert!(retries < 10, "Too many retries");
@@ -96,7 +104,7 @@ fn lock_token_pairs(required: Vec<TokenPairAmm>, retries: u8) -> Result<LockResu
 
 #[allow(unused)]
 #[inline(always)]
-fn lock_token_block_chain_and_token_balances(
+pub(crate) fn lock_token_block_chain_and_token_balances(
     fee_tokens: Vec<CanisterId>,
     mut required: Vec<TokenAccount>,
     retries: u8,
@@ -9,9 +9,10 @@ use crate::types::*;
 // ========================== create ==========================
 
 // create
+#[allow(clippy::type_complexity)]
 fn check_pair_create_args(
     _self: &TokenPairCreateOrRemoveArgs,
-) -> Result<(TimestampNanos, Caller, TokenPairAmm), BusinessError> {
+) -> Result<(TimestampNanos, Caller, TokenPairAmm, Option<(u32, u32)>, Option<Nat>), BusinessError> {
     // ! refuse all action about frozen token
     with_state(|s| s.business_token_alive(&_self.pool.token0))?;
     with_state(|s| s.business_token_alive(&_self.pool.token1))?;
@@ -23,10 +24,40 @@ fn check_pair_create_args(
     } = &_self.pool;
     let pair = TokenPair::new(*token_a, *token_b);
     check_token_pair_args(&pair)?; // check supported token
-    let amm: Amm = amm.as_ref().try_into()?; // parse amm
+    let amm: Amm = check_amm_args(amm)?; // parse amm and check fee tier
+    if amm.is_multi_asset() {
+        return Err(BusinessError::Swap("MULTI_ASSET_AMM_NOT_SUPPORTED".into())); // use pool instead
+    }
 
     let pa = TokenPairAmm { pair, amm };
 
+    // check weights, align to sorted token0 and token1
+    let weights = match (amm.is_weighted(), _self.weights) {
+        (true, Some((weight_a, weight_b))) => {
+            let weights = if pair.get_token0() == *token_a {
+                (weight_a, weight_b)
+            } else {
+                (weight_b, weight_a)
+            };
+            WeightedMarketMaker::check_weights(weights)?;
+            Some(weights)
+        }
+        (true, None) => return Err(BusinessError::Swap("WEIGHTS_REQUIRED".into())),
+        (false, Some(_)) => return Err(BusinessError::Swap("WEIGHTS_NOT_SUPPORTED".into())),
+        (false, None) => None,
+    };
+
+    // check initial price, align to sorted token0 and token1
+    let sqrt_price_x96 = match (amm.is_concentrated(), &_self.sqrt_price_x96) {
+        (true, Some(sqrt_price_x96)) => Some(Nat::from(SwapV3MarketMaker::initial_sqrt_price(
+            sqrt_price_x96,
+            pair.get_token0() != *token_a,
+        )?)),
+        (true, None) => return Err(BusinessError::Swap("SQRT_PRICE_REQUIRED".into())),
+        (false, Some(_)) => return Err(BusinessError::Swap("SQRT_PRICE_NOT_SUPPORTED".into())),
+        (false, None) => None,
+    };
+
     // check exist
     if with_state(|s| s.business_token_pair_pool_get(&pa).is_some()) {
         return Err(BusinessError::TokenPairAmmExist(pa));
@@ -35,7 +66,7 @@ fn check_pair_create_args(
     // check meta
     let now = check_meta(&_self.memo, &_self.created)?;
 
-    Ok((now, Caller::get(), pa))
+    Ok((now, Caller::get(), pa, weights, sqrt_price_x96))
 }
 
 // check forbidden
@@ -45,7 +76,7 @@ async fn pair_create(args: TokenPairCreateOrRemoveArgs) -> TokenPairCreateOrRemo
 }
 async fn inner_pair_create(args: TokenPairCreateOrRemoveArgs) -> Result<MarketMaker, BusinessError> {
     // 1. check args
-    let (now, caller, pa) = check_pair_create_args(&args)?;
+    let (now, caller, pa, weights, sqrt_price_x96) = check_pair_create_args(&args)?;
 
     // 2. some value
 
@@ -68,6 +99,8 @@ async fn inner_pair_create(args: TokenPairCreateOrRemoveArgs) -> Result<MarketMa
                         memo: args.memo,
                         created: args.created,
                     },
+                    weights,
+                    sqrt_price_x96,
                 )
             })?
         }
@@ -0,0 +1,241 @@
+#[allow(unused)]
+use ic_canister_kit::identity::caller;
+
+#[allow(unused)]
+use crate::stable::*;
+#[allow(unused)]
+use crate::types::*;
+
+use ::common::utils::math::zero;
+
+// ============================== query ==============================
+
+// anyone can query
+#[ic_cdk::query]
+fn pair_flash_loans_query(borrower: Option<Account>) -> Vec<FlashLoan> {
+    with_state(|s| s.business_token_pair_flash_loans_query(borrower.as_ref()))
+}
+
+#[ic_cdk::query]
+fn pair_flash_loan_get(id: u64) -> Option<FlashLoan> {
+    with_state(|s| s.business_token_pair_flash_loan_get(id))
+}
+
+// ============================== lend ==============================
+
+impl CheckArgs for TokenPairFlashLoanArgs {
+    type Result = (
+        TimestampNanos,
+        Vec<CanisterId>,
+        Vec<TokenAccount>,
+        SelfCanister,
+        Caller,
+        FlashLoan,
+    );
+    fn check_args(&self) -> Result<Self::Result, BusinessError> {
+        // ! refuse all action about frozen token
+        with_state(|s| s.business_token_alive(&self.swap_pair.token.0))?;
+        with_state(|s| s.business_token_alive(&self.swap_pair.token.1))?;
+
+        // check owner
+        let (self_canister, caller) = check_caller(&self.from.owner)?;
+
+        // check pool
+        let (pa, mut fee_tokens, required) = check_pool(&self.swap_pair, &self_canister, None)?;
+        fee_tokens.push(self.token); // protocol fee is charged in lent token
+
+        // check token
+        if self.token != pa.pair.get_token0() && self.token != pa.pair.get_token1() {
+            return Err(BusinessError::Swap("INVALID_TOKEN".into()));
+        }
+
+        // check amount
+        if self.amount == zero() {
+            return Err(BusinessError::Swap("INSUFFICIENT_LOAN_AMOUNT".into()));
+        }
+
+        // check callback
+        if self.callback.is_empty() {
+            return Err(BusinessError::Swap("INVALID_CALLBACK".into()));
+        }
+
+        // ! only one outstanding loan of pool or borrower
+        if with_state(|s| {
+            s.business_token_pair_flash_loan_lending(&pa) || s.business_token_pair_flash_loan_borrowing(&self.from)
+        }) {
+            return Err(BusinessError::Swap("FLASH_LOAN_NOT_REPAID".into()));
+        }
+
+        // ! only swap v2 pools lend, their reserves are synced to balances after settled
+        let maker = with_state(|s| s.business_token_pair_pool_get(&pa)).ok_or(pa.not_exist())?;
+        if !matches!(maker, MarketMaker::SwapV2(_)) {
+            return Err(BusinessError::Swap("FLASH_LOAN_NOT_SUPPORTED".into()));
+        }
+
+        // the fee is charged by the fee rate of pool, rounding up
+        let fee_rate = maker.swap_fee_rate();
+        let denominator = Nat::from(fee_rate.denominator);
+        let fee = (self.amount.clone() * fee_rate.numerator + denominator.clone() - Nat::from(1_u32)) / denominator;
+
+        // check meta
+        let now = check_meta(&self.memo, &self.created)?;
+
+        let loan = FlashLoan {
+            id: 0, // set by lending
+            pa,
+            token: self.token,
+            borrower: self.from,
+            amount: self.amount.clone(),
+            fee,
+            lent: now,
+        };
+
+        Ok((now, fee_tokens, required, self_canister, caller, loan))
+    }
+}
+
+// check forbidden
+#[ic_cdk::update(guard = "has_business_token_pair_swap")]
+async fn pair_flash_loan(args: TokenPairFlashLoanArgs, retries: Option<u8>) -> TokenPairFlashLoanResult {
+    inner_pair_flash_loan(args, retries).await.into()
+}
+#[inline]
+async fn inner_pair_flash_loan(args: TokenPairFlashLoanArgs, retries: Option<u8>) -> Result<FlashLoan, BusinessError> {
+    // 1. check args
+    let (now, fee_tokens, mut required, self_canister, caller, loan) = args.check_args()?;
+
+    // 2. some value
+    let token_account_borrower = TokenAccount::new(loan.token, loan.borrower);
+    required.push(token_account_borrower);
+
+    // 3. lock
+    let locks = match super::super::lock_token_block_chain_and_swap_block_chain_and_token_balances_and_token_pairs(
+        fee_tokens.clone(),
+        required.clone(),
+        vec![loan.pa],
+        retries.unwrap_or_default(),
+    )? {
+        LockResult::Locked(locks) => locks,
+        LockResult::Retry(retries) => {
+            return retry_pair_flash_loan(self_canister.id(), args, retries).await;
+        }
+    };
+
+    // * 4. do lend, nothing is taken from borrower
+    let loan = with_mut_state(|s| {
+        s.business_token_pair_flash_loan_lend(
+            &locks,
+            ArgWithMeta {
+                now,
+                caller,
+                arg: loan,
+                memo: args.memo,
+                created: args.created,
+            },
+        )
+    })?;
+
+    // ! no lock is kept during callback, the pool keeps working with the lent amount counted in
+    drop(locks);
+
+    // 5. callback, the repayment is checked no matter what the callback returns or timeout
+    // ! the changes during callback can not be rolled back, the loan is defaulted if not repaid
+    let service_flash_loan = crate::services::flash_loan::Service(loan.borrower.owner);
+    let callback_args = TokenPairFlashLoanCallbackArgs {
+        loan: loan.clone(),
+        data: args.data,
+    };
+    if let Err(err) = service_flash_loan.callback(&args.callback, callback_args).await {
+        ic_cdk::println!("flash loan {} callback failed: {err:?}", loan.id);
+    }
+
+    // 6. settle
+    // ! no retry by calling self, the busy loan is settled by schedule
+    let locks = match super::super::lock_token_block_chain_and_swap_block_chain_and_token_balances_and_token_pairs(
+        fee_tokens,
+        required,
+        vec![loan.pa],
+        0,
+    ) {
+        Ok(LockResult::Locked(locks)) => locks,
+        Ok(LockResult::Retry(_)) => unreachable!(),
+        Err(err) => {
+            ic_cdk::println!("flash loan {} will be settled later: {err:?}", loan.id);
+            return Err(err);
+        }
+    };
+    if !settle_flash_loan(&locks, caller, loan.clone())? {
+        return Err(BusinessError::Swap("FLASH_LOAN_NOT_REPAID".into()));
+    }
+    Ok(loan)
+}
+// ! This implicitly contains self_canister_id, which can be called again through permission checks and replaces caller.
+#[inline]
+async fn retry_pair_flash_loan(
+    self_canister_id: CanisterId,
+    args: TokenPairFlashLoanArgs,
+    retries: u8,
+) -> Result<FlashLoan, BusinessError> {
+    ic_cdk::println!("🔄 retry_pair_flash_loan: {}", retries);
+    let service_swap = crate::services::swap::Service(self_canister_id);
+    return service_swap.pair_flash_loan(args, Some(retries)).await;
+}
+
+/// Repay the loan from borrower, or take what is left in borrower if not repaid.
+/// Return whether it is repaid by borrower.
+fn settle_flash_loan(locks: &AllLocks, caller: Caller, loan: FlashLoan) -> Result<bool, BusinessError> {
+    let arg = ArgWithMeta::simple(TimestampNanos::now(), caller, loan);
+    let repaid = match with_mut_state(|s| s.business_token_pair_flash_loan_repay(locks, arg.clone())) {
+        Ok(_) => true,
+        Err(err) => {
+            // ! not repaid, the loss of lp is bounded by the cap of loan
+            ic_cdk::println!("flash loan {} is not repaid: {err:?}", arg.arg.id);
+            with_mut_state(|s| s.business_token_pair_flash_loan_default(locks, arg))?;
+            false
+        }
+    };
+
+    // Asynchronously triggers synchronization tasks
+    crate::business::config::push::inner_push_blocks(true, true);
+
+    Ok(repaid)
+}
+
+/// Settle the loans left by lending, whose locks were busy after callback.
+/// The loan may be in callback until timeout, it is skipped and settled by lending.
+pub fn schedule_flash_loans() {
+    if with_state(|s| s.pause_must_be_running()).is_err() {
+        return;
+    }
+
+    let (self_canister, caller) = check_self_canister();
+    let now = TimestampNanos::now().into_inner();
+    let timeout = crate::services::flash_loan::CALLBACK_TIMEOUT_SECONDS as u64 * 1_000_000_000;
+    let loans = with_state(|s| s.business_token_pair_flash_loans_query(None));
+    for loan in loans {
+        // ! twice the timeout, the reply of callback may be delayed
+        if now < loan.lent.into_inner() + timeout * 2 {
+            continue;
+        }
+        let id = loan.id;
+        let result = check_pool_accounts(&loan.pa, &self_canister, None).and_then(|(mut fee_tokens, mut required)| {
+            fee_tokens.push(loan.token);
+            required.push(TokenAccount::new(loan.token, loan.borrower));
+            // ! no retry in schedule, try again next time
+            let locks =
+                match super::super::lock_token_block_chain_and_swap_block_chain_and_token_balances_and_token_pairs(
+                    fee_tokens,
+                    required,
+                    vec![loan.pa],
+                    0,
+                )? {
+                    LockResult::Locked(locks) => locks,
+                    LockResult::Retry(_) => unreachable!(),
+                };
+            settle_flash_loan(&locks, caller, loan)
+        });
+        if let Err(err) = result {
+            ic_cdk::println!("schedule flash loan {id} failed: {err:?}");
+        }
+    }
+}
@@ -40,6 +40,7 @@ impl CheckArgs for TokenPairLiquidityAddArgs {
             amount_a_min: self.amount_min.0.clone(),
             amount_b_min: self.amount_min.1.clone(),
             to: self.to,
+            tick_range: self.tick_range,
         };
 
         // check amount
@@ -77,6 +78,19 @@ async fn inner_pair_liquidity_add(
     args: TokenPairLiquidityAddArgs,
     retries: Option<u8>,
 ) -> Result<TokenPairLiquidityAddSuccess, BusinessError> {
+    // 0. approved spender, the swap canister adds liquidity on behalf of owner
+    let spends = vec![
+        (args.swap_pair.token.0, args.amount_desired.0.clone()),
+        (args.swap_pair.token.1, args.amount_desired.1.clone()),
+    ];
+    if let Some(spending) = spend_allowance(&args.from, spends)? {
+        let service_swap = crate::services::swap::Service(self_canister_id());
+        let result = service_swap.pair_liquidity_add(args, retries).await;
+        return spending.settle(result, |success| {
+            vec![success.amount.0.clone(), success.amount.1.clone()]
+        });
+    }
+
     // 1. check args
     let (now, fee_tokens, mut required, self_canister, caller, arg) = args.check_args()?;
 
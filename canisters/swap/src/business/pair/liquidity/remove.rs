@@ -29,6 +29,44 @@ impl CheckArgs for TokenPairLiquidityRemoveArgs {
         // check pool
         let (pa, fee_tokens, required) = check_pool(&self.swap_pair, &self_canister, Some(&self.from))?;
 
+        // concentrated liquidity position, no lp token to burn
+        if let Some(tick_range) = self.tick_range {
+            let arg = TokenPairLiquidityRemoveArg {
+                self_canister,
+                pa,
+                from: self.from,
+                token_a: self.swap_pair.token.0,
+                token_b: self.swap_pair.token.1,
+                liquidity_without_fee: self.liquidity_without_fee.clone(),
+                amount_a_min: self.amount_min.0.clone(),
+                amount_b_min: self.amount_min.1.clone(),
+                to: self.to,
+                fee: None,
+                tick_range: Some(tick_range),
+                exit_token: self.exit_token,
+            };
+
+            // check amount
+            arg.check_args()?;
+
+            // check position liquidity
+            with_state(|s| {
+                s.business_token_pair_pool_get(&pa)
+                    .ok_or_else(|| pa.not_exist())?
+                    .check_position_removable(&arg.from, &tick_range, &arg.liquidity_without_fee)
+            })?;
+
+            // check deadline
+            if let Some(deadline) = &self.deadline {
+                deadline.check_args()?;
+            }
+
+            // check meta
+            let now = check_meta(&self.memo, &self.created)?;
+
+            return Ok((now, fee_tokens, required, self_canister, caller, arg));
+        }
+
         // check liquidity balance and fee
         let token = with_state(|s| s.business_token_query_by_pa(&pa)).ok_or_else(|| pa.not_exist())?;
         let (balance, mut fee_to) =
@@ -53,6 +91,8 @@ impl CheckArgs for TokenPairLiquidityRemoveArgs {
                 fee: token.fee.clone(),
                 fee_to,
             }),
+            tick_range: None,
+            exit_token: self.exit_token,
         };
 
         // check amount
@@ -88,6 +128,21 @@ pub(super) async fn inner_pair_liquidity_remove(
     args: TokenPairLiquidityRemoveArgs,
     retries: Option<u8>,
 ) -> Result<TokenPairLiquidityRemoveSuccess, BusinessError> {
+    // 0. approved spender, the swap canister removes liquidity on behalf of owner
+    // ! position of concentrated liquidity has no lp token to approve
+    let spends = check_pool(&args.swap_pair, &check_self_canister().0, None)
+        .ok()
+        .filter(|_| args.tick_range.is_none())
+        .and_then(|(pa, _, _)| with_state(|s| s.business_token_query_by_pa(&pa)))
+        .map(|token| vec![(token.canister_id, args.liquidity_without_fee.clone())])
+        .unwrap_or_default();
+    if let Some(spending) = spend_allowance(&args.from, spends)? {
+        let liquidity = args.liquidity_without_fee.clone();
+        let service_swap = crate::services::swap::Service(self_canister_id());
+        let result = service_swap.pair_liquidity_remove(args, retries).await;
+        return spending.settle(result, |_| vec![liquidity]);
+    }
+
     // 1. check args
     let (now, fee_tokens, mut required, self_canister, caller, arg) = args.check_args()?;
 
@@ -15,3 +15,5 @@ mod remove;
 mod liquidity;
 
 mod swap;
+
+pub mod flash_loan;
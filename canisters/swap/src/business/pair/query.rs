@@ -40,3 +40,34 @@ fn pair_query(pool: TokenPairPool) -> Option<MarketMakerView> {
 
     with_state(|s| s.business_token_pair_pool_get(&pa).map(|maker| maker.clone().into()))
 }
+
+// ========================== twap ==========================
+
+// anyone can query
+#[ic_cdk::query]
+fn pair_twap(pool: TokenPairPool, window_seconds: u64) -> Result<SwapV2Twap, BusinessError> {
+    let pair = TokenPair::new(pool.token0, pool.token1);
+    let amm: Amm = pool.amm.as_ref().try_into()?; // parse amm
+
+    let pa = TokenPairAmm { pair, amm };
+
+    let maker = with_state(|s| s.business_token_pair_pool_get(&pa)).ok_or_else(|| pa.not_exist())?;
+    maker.twap(
+        TimestampNanos::now().into_inner(),
+        window_seconds.saturating_mul(1_000_000_000),
+    )
+}
+
+// ========================== lp earnings ==========================
+
+// anyone can query
+#[ic_cdk::query]
+fn pair_lp_earnings_of(account: Account) -> Vec<LpEarnings> {
+    ::common::utils::owner::check_owner_for_token_balance_of(&account.owner); // ! must be owner or self canister
+    pair_lp_earnings_by(account)
+}
+
+#[ic_cdk::query(guard = "has_business_token_balance_by")]
+fn pair_lp_earnings_by(account: Account) -> Vec<LpEarnings> {
+    with_state(|s| s.business_token_pair_lp_earnings_query(&account))
+}
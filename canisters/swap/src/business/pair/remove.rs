@@ -24,6 +24,9 @@ fn check_pair_remove_args(
     let pair = TokenPair::new(*token_a, *token_b);
     check_token_pair_args(&pair)?; // check supported token
     let amm: Amm = amm.as_ref().try_into()?; // parse amm
+    if amm.is_multi_asset() {
+        return Err(BusinessError::Swap("MULTI_ASSET_AMM_NOT_SUPPORTED".into())); // use pool instead
+    }
 
     let pa = TokenPairAmm { pair, amm };
 
@@ -9,4 +9,7 @@ use crate::types::*;
 mod got_exact;
 mod pay_exact;
 mod pay_exact_by_loan;
+mod pay_exact_split;
 mod pay_exact_with_deposit;
+mod quote;
+mod router;
@@ -86,6 +86,18 @@ async fn inner_pair_swap_tokens_for_exact_tokens(
     args: TokenPairSwapTokensForExactTokensArgs,
     retries: Option<u8>,
 ) -> Result<TokenPairSwapTokensSuccess, BusinessError> {
+    // 0. approved spender, the swap canister swaps on behalf of owner
+    let spends = args
+        .path
+        .first()
+        .map(|p| vec![(p.token.0, args.amount_in_max.clone())])
+        .unwrap_or_default();
+    if let Some(spending) = spend_allowance(&args.from, spends)? {
+        let service_swap = crate::services::swap::Service(self_canister_id());
+        let result = service_swap.pair_swap_tokens_for_exact_tokens(args, retries).await;
+        return spending.settle(result, |success| success.amounts.iter().take(1).cloned().collect());
+    }
+
     // 1. check args
     let (now, fee_tokens, mut required, self_canister, caller, arg) = args.check_args()?;
 
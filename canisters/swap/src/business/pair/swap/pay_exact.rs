@@ -111,6 +111,19 @@ pub async fn inner_pair_swap_exact_tokens_for_tokens(
     retries: Option<u8>,
     push: bool,
 ) -> Result<TokenPairSwapTokensSuccess, BusinessError> {
+    // 0. approved spender, the swap canister swaps on behalf of owner
+    let spends = args
+        .path
+        .first()
+        .map(|p| vec![(p.token.0, args.amount_in.clone())])
+        .unwrap_or_default();
+    if let Some(spending) = spend_allowance(&args.from, spends)? {
+        let amount_in = args.amount_in.clone();
+        let service_swap = crate::services::swap::Service(self_canister_id());
+        let result = service_swap.pair_swap_exact_tokens_for_tokens(args, retries).await;
+        return spending.settle(result, |_| vec![amount_in]);
+    }
+
     // 1. check args
     let (now, fee_tokens, mut required, self_canister, caller, arg, _checking) = args.check_args()?;
 
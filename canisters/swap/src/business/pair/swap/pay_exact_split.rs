@@ -0,0 +1,202 @@
+#[allow(unused)]
+use ic_canister_kit::identity::caller;
+
+#[allow(unused)]
+use crate::stable::*;
+#[allow(unused)]
+use crate::types::*;
+
+use ::common::utils::math::zero;
+
+// ========================== pay exact by split paths ==========================
+
+// pay extra tokens by split paths
+impl CheckArgs for TokenPairSwapSplitArgs {
+    type Result = (
+        TimestampNanos,
+        Vec<CanisterId>,
+        Vec<TokenAccount>,
+        SelfCanister,
+        Caller,
+        TokenPairSwapSplitArg,
+    );
+    fn check_args(&self) -> Result<Self::Result, BusinessError> {
+        if self.paths.is_empty() || MAX_SPLIT_PATHS < self.paths.len() {
+            return Err(BusinessError::Swap("INVALID_SPLIT_PATHS".into()));
+        }
+        if self.paths.iter().any(|p| p.weight == 0 || p.path.is_empty()) {
+            return Err(BusinessError::Swap("INVALID_SPLIT_PATHS".into()));
+        }
+
+        // ! all paths must be the same tokens
+        let token_in = self.paths[0].path[0].token.0;
+        let token_out = self.paths[0].path[self.paths[0].path.len() - 1].token.1;
+        for p in &self.paths {
+            if p.path[0].token.0 != token_in || p.path[p.path.len() - 1].token.1 != token_out {
+                return Err(BusinessError::Swap("INVALID_SPLIT_PATHS".into()));
+            }
+        }
+
+        // ! refuse all action about frozen token
+        for p in self.paths.iter().flat_map(|p| &p.path) {
+            with_state(|s| s.business_token_alive(&p.token.0))?;
+            with_state(|s| s.business_token_alive(&p.token.1))?;
+        }
+
+        // check owner
+        let (self_canister, caller) = check_caller(&self.from.owner)?;
+
+        // split amount in by weights, the last one takes the rest
+        let weights = self.paths.iter().map(|p| p.weight).collect::<Vec<_>>();
+        let amounts_in = split_amount_in(&self.amount_in, &weights);
+        let mut legs = vec![];
+        let mut fee_tokens = vec![];
+        let mut required = vec![];
+        for (p, amount_in) in self.paths.iter().zip(amounts_in) {
+            // check pools
+            let mut pas = vec![];
+            for pool in &p.path {
+                let (pa, _fee_tokens, _required) = check_pool(pool, &self_canister, None)?;
+                pas.push(pa);
+                fee_tokens.extend(_fee_tokens);
+                required.extend(_required);
+            }
+
+            // check path
+            check_path(&p.path)?;
+
+            legs.push(TokenPairSwapSplitLeg {
+                pas,
+                amount_in,
+                path: p.path.clone(),
+            });
+        }
+
+        let arg = TokenPairSwapSplitArg {
+            self_canister,
+            from: self.from,
+            amount_in: self.amount_in.clone(),
+            amount_out_min: self.amount_out_min.clone(),
+            legs,
+            to: self.to,
+        };
+
+        // check balance in
+        let balance_in = with_state(|s| s.business_token_balance_of(token_in, self.from));
+        if balance_in < self.amount_in {
+            return Err(BusinessError::insufficient_balance(token_in, balance_in));
+        }
+
+        // check deadline
+        if let Some(deadline) = &self.deadline {
+            deadline.check_args()?;
+        }
+
+        // check meta
+        let now = check_meta(&self.memo, &self.created)?;
+
+        // check arg again, legs through the same pool are checked separately
+        let mut amounts_out = vec![];
+        for leg in &arg.legs {
+            let (amounts, _) = with_state(|s| {
+                s.business_token_pair_swap_fixed_in_checking(&TokenPairSwapExactTokensForTokensArg {
+                    self_canister,
+                    pas: leg.pas.clone(),
+                    from: arg.from,
+                    amount_in: leg.amount_in.clone(),
+                    amount_out_min: zero(),
+                    path: leg.path.clone(),
+                    to: arg.to,
+                })
+            })?;
+            amounts_out.push(amounts[amounts.len() - 1].clone());
+        }
+        check_split_amount_out(&amounts_out, &arg.amount_out_min)?;
+
+        Ok((now, fee_tokens, required, self_canister, caller, arg))
+    }
+}
+
+// check forbidden
+#[ic_cdk::update(guard = "has_business_token_pair_swap")]
+async fn pair_swap_split(args: TokenPairSwapSplitArgs, retries: Option<u8>) -> TokenPairSwapSplitResult {
+    inner_pair_swap_split(args, retries).await.into()
+}
+#[inline]
+async fn inner_pair_swap_split(
+    args: TokenPairSwapSplitArgs,
+    retries: Option<u8>,
+) -> Result<TokenPairSwapSplitSuccess, BusinessError> {
+    // 0. approved spender, the swap canister swaps on behalf of owner
+    let spends = args
+        .paths
+        .first()
+        .and_then(|p| p.path.first())
+        .map(|p| vec![(p.token.0, args.amount_in.clone())])
+        .unwrap_or_default();
+    if let Some(spending) = spend_allowance(&args.from, spends)? {
+        let amount_in = args.amount_in.clone();
+        let service_swap = crate::services::swap::Service(self_canister_id());
+        let result = service_swap.pair_swap_split(args, retries).await;
+        return spending.settle(result, |_| vec![amount_in]);
+    }
+
+    // 1. check args
+    let (now, fee_tokens, mut required, self_canister, caller, arg) = args.check_args()?;
+
+    // 2. some value
+    let path = &args.paths[0].path;
+    let token_account_in = TokenAccount::new(path[0].token.0, args.from);
+    let token_account_out = TokenAccount::new(path[path.len() - 1].token.1, args.to);
+    required.push(token_account_in);
+    required.push(token_account_out);
+    let pas = arg.legs.iter().flat_map(|leg| leg.pas.iter().cloned()).collect();
+
+    let success = {
+        // 3. lock
+        let locks =
+            match super::super::super::lock_token_block_chain_and_swap_block_chain_and_token_balances_and_token_pairs(
+                fee_tokens,
+                required,
+                pas,
+                retries.unwrap_or_default(),
+            )? {
+                LockResult::Locked(locks) => locks,
+                LockResult::Retry(retries) => {
+                    return retry_pair_swap_split(self_canister.id(), args, retries).await;
+                }
+            };
+
+        // * 4. do business
+        {
+            with_mut_state(|s| {
+                s.business_token_pair_swap_split(
+                    &locks,
+                    ArgWithMeta {
+                        now,
+                        caller,
+                        arg,
+                        memo: args.memo,
+                        created: args.created,
+                    },
+                )
+            })?
+        }
+    };
+
+    // Asynchronously triggers synchronization tasks
+    crate::business::config::push::inner_push_blocks(true, true);
+
+    Ok(success)
+}
+// ! This implicitly contains self_canister_id, which can be called again through permission checks and replaces caller.
+#[inline]
+async fn retry_pair_swap_split(
+    self_canister_id: CanisterId,
+    args: TokenPairSwapSplitArgs,
+    retries: u8,
+) -> Result<TokenPairSwapSplitSuccess, BusinessError> {
+    ic_cdk::println!("🔄 retry_pair_swap_split: {}", retries);
+    let service_swap = crate::services::swap::Service(self_canister_id);
+    return service_swap.pair_swap_split(args, Some(retries)).await;
+}
@@ -0,0 +1,134 @@
+#[allow(unused)]
+use ic_canister_kit::identity::caller;
+
+#[allow(unused)]
+use crate::stable::*;
+#[allow(unused)]
+use crate::types::*;
+
+use ::common::utils::math::{nat_to_f64, zero};
+
+// ========================== quote ==========================
+
+fn check_quote_path(path: &[SwapTokenPair]) -> Result<(SelfCanister, Vec<TokenPairAmm>), BusinessError> {
+    // ! refuse all action about frozen token
+    for p in path {
+        with_state(|s| s.business_token_alive(&p.token.0))?;
+        with_state(|s| s.business_token_alive(&p.token.1))?;
+    }
+
+    let (self_canister, _) = check_caller(&caller())?;
+
+    // check pools
+    let mut pas = vec![];
+    for pool in path {
+        let (pa, _, _) = check_pool(pool, &self_canister, None)?;
+        pas.push(pa);
+    }
+
+    // check path
+    check_path(path)?;
+
+    Ok((self_canister, pas))
+}
+
+/// Details of each hop by the amounts calculated by swap
+fn inner_quote(
+    path: &[SwapTokenPair],
+    pas: &[TokenPairAmm],
+    amounts: Vec<Nat>,
+) -> Result<TokenPairSwapQuote, BusinessError> {
+    with_state(|s| {
+        let mut hops = Vec::with_capacity(path.len());
+        let mut ratio = 1_f64;
+        for (i, (pool, pa)) in path.iter().zip(pas.iter()).enumerate() {
+            let maker = s.business_token_pair_pool_get(pa).ok_or_else(|| pa.not_exist())?;
+            let (token_in, token_out) = pool.token;
+            let amount_in = amounts[i].clone();
+            let amount_out = amounts[i + 1].clone();
+
+            let fee_rate = maker.swap_fee_rate();
+            let fee = amount_in.clone() * fee_rate.numerator / fee_rate.denominator;
+
+            // got by mid price without fee
+            if let Some(mid) = maker
+                .swap_to_token(&token_in, &token_out, nat_to_f64(&(amount_in.clone() - fee.clone())))
+                .filter(|mid| 0_f64 < *mid)
+            {
+                ratio *= nat_to_f64(&amount_out) / mid;
+            }
+
+            let (reserve_in, reserve_out) = maker
+                .get_reserves(&token_in, &token_out)
+                .ok_or_else(|| pa.not_exist())?;
+            let reserve_out = if amount_out < reserve_out {
+                reserve_out - amount_out.clone()
+            } else {
+                zero()
+            };
+
+            hops.push(TokenPairSwapQuoteHop {
+                pool: pool.clone(),
+                amount_in: amount_in.clone(),
+                amount_out,
+                fee,
+                reserves: (reserve_in + amount_in, reserve_out),
+            });
+        }
+        Ok(TokenPairSwapQuote {
+            amounts,
+            hops,
+            price_impact: (1_f64 - ratio).max(0_f64),
+        })
+    })
+}
+
+// anyone can query
+#[ic_cdk::query]
+fn pair_quote_exact_tokens_for_tokens(path: Vec<SwapTokenPair>, amount_in: Nat) -> TokenPairSwapQuoteResult {
+    inner_pair_quote_exact_tokens_for_tokens(path, amount_in).into()
+}
+fn inner_pair_quote_exact_tokens_for_tokens(
+    path: Vec<SwapTokenPair>,
+    amount_in: Nat,
+) -> Result<TokenPairSwapQuote, BusinessError> {
+    let (self_canister, pas) = check_quote_path(&path)?;
+    // same as checking of pay exact
+    let (amounts, _) = with_state(|s| {
+        s.business_token_pair_swap_fixed_in_checking(&TokenPairSwapExactTokensForTokensArg {
+            self_canister,
+            pas: pas.clone(),
+            from: Account::from(self_canister.id()),
+            amount_in,
+            amount_out_min: zero(),
+            path: path.clone(),
+            to: Account::from(self_canister.id()),
+        })
+    })?;
+    inner_quote(&path, &pas, amounts)
+}
+
+// anyone can query
+#[ic_cdk::query]
+fn pair_quote_tokens_for_exact_tokens(path: Vec<SwapTokenPair>, amount_out: Nat) -> TokenPairSwapQuoteResult {
+    inner_pair_quote_tokens_for_exact_tokens(path, amount_out).into()
+}
+fn inner_pair_quote_tokens_for_exact_tokens(
+    path: Vec<SwapTokenPair>,
+    amount_out: Nat,
+) -> Result<TokenPairSwapQuote, BusinessError> {
+    let (self_canister, pas) = check_quote_path(&path)?;
+    // same as checking of got exact
+    let (amounts, _) = with_state(|s| {
+        s.business_token_pair_swap_fixed_out_checking(&TokenPairSwapTokensForExactTokensArg {
+            self_canister,
+            pas: pas.clone(),
+            from: Account::from(self_canister.id()),
+            amount_out,
+            amount_in_max: Nat::from(u128::MAX),
+            path: path.clone(),
+            to: Account::from(self_canister.id()),
+        })
+    })?;
+    inner_quote(&path, &pas, amounts)
+}
@@ -0,0 +1,288 @@
+#[allow(unused)]
+use ic_canister_kit::identity::caller;
+
+#[allow(unused)]
+use crate::stable::*;
+#[allow(unused)]
+use crate::types::*;
+
+use ::common::utils::math::{nat_to_f64, zero};
+
+// ========================== router ==========================
+
+// Candidates with the best estimated output, which are calculated exactly
+const ROUTER_CANDIDATES: usize = 5;
+// Stop searching more paths
+const ROUTER_MAX_PATHS: usize = 4096;
+// Stop estimating more hops, so a dense graph is bounded by the instruction limit of query
+const ROUTER_MAX_VISITS: usize = 10_000;
+
+struct RouterSearching<'a> {
+    // token -> (token, pair amm, index of maker)
+    edges: HashMap<CanisterId, Vec<(CanisterId, TokenPairAmm, usize)>>,
+    makers: &'a [MarketMaker],
+    token_out: CanisterId,
+    max_hops: usize,
+    // count of estimated hops
+    visited: usize,
+    // estimated output and path
+    found: Vec<(f64, Vec<SwapTokenPair>)>,
+}
+
+impl RouterSearching<'_> {
+    fn search(&mut self, token: CanisterId, amount: f64, tokens: &mut Vec<CanisterId>, path: &mut Vec<SwapTokenPair>) {
+        if self.max_hops <= path.len() {
+            return;
+        }
+        let count = self.edges.get(&token).map_or(0, |edges| edges.len());
+        for i in 0..count {
+            if ROUTER_MAX_PATHS <= self.found.len() || ROUTER_MAX_VISITS <= self.visited {
+                return;
+            }
+            let (next, pa, index) = self.edges[&token][i];
+            if tokens.contains(&next) {
+                continue; // no loop
+            }
+            // estimate by mid price, fee is deducted
+            self.visited += 1;
+            let maker = &self.makers[index];
+            let Some(got) = maker.swap_to_token(&token, &next, amount - maker.get_fee(amount)) else {
+                continue;
+            };
+            if got <= 0_f64 {
+                continue;
+            }
+            path.push(SwapTokenPair {
+                token: (token, next),
+                amm: pa.amm.into_text(),
+            });
+            if next == self.token_out {
+                self.found.push((got, path.clone()));
+            } else {
+                tokens.push(next);
+                self.search(next, got, tokens, path);
+                tokens.pop();
+            }
+            path.pop();
+        }
+    }
+}
+
+/// Search paths in all pools, and pick the best output
+fn find_best_route(
+    self_canister: SelfCanister,
+    token_in: CanisterId,
+    token_out: CanisterId,
+    amount_in: &Nat,
+    max_hops: Option<u8>,
+) -> Result<TokenPairRoute, BusinessError> {
+    if token_in == token_out {
+        return Err(BusinessError::Swap("INVALID_PATH".into()));
+    }
+    if *amount_in == zero() {
+        return Err(BusinessError::Swap("INSUFFICIENT_INPUT_AMOUNT".into()));
+    }
+
+    with_state(|s| {
+        let max_hops = max_hops
+            .unwrap_or(s.business_config_router_max_hops_query())
+            .clamp(1, ROUTER_MAX_HOPS_LIMIT) as usize;
+
+        // all pools, members of multi-asset pool share the maker
+        let mut makers = vec![];
+        let mut edges: HashMap<CanisterId, Vec<(CanisterId, TokenPairAmm, usize)>> = HashMap::new();
+        let pairs = s
+            .business_token_pair_pools_query()
+            .into_iter()
+            .map(|(pa, maker)| (vec![pa], maker));
+        let pools = s
+            .business_token_pools_query()
+            .into_iter()
+            .map(|(pool, maker)| (pool.member_pairs(), maker));
+        for (pas, maker) in pairs.chain(pools) {
+            let index = makers.len();
+            makers.push(maker);
+            for pa in pas {
+                let (token0, token1) = (pa.pair.get_token0(), pa.pair.get_token1());
+                // ! refuse all action about frozen token
+                if s.business_token_alive(&token0).is_err() || s.business_token_alive(&token1).is_err() {
+                    continue;
+                }
+                edges.entry(token0).or_default().push((token1, pa, index));
+                edges.entry(token1).or_default().push((token0, pa, index));
+            }
+        }
+
+        let mut searching = RouterSearching {
+            edges,
+            makers: &makers,
+            token_out,
+            max_hops,
+            visited: 0,
+            found: vec![],
+        };
+        searching.search(token_in, nat_to_f64(amount_in), &mut vec![token_in], &mut vec![]);
+
+        // calculate the best candidates exactly, same as pay exact
+        let mut found = searching.found;
+        found.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let mut best: Option<TokenPairRoute> = None;
+        for (_, path) in found.into_iter().take(ROUTER_CANDIDATES) {
+            if check_path(&path).is_err() {
+                continue;
+            }
+            let pas = path
+                .iter()
+                .map(|pool| check_pool(pool, &self_canister, None).map(|(pa, _, _)| pa))
+                .collect::<Result<Vec<_>, _>>();
+            let Ok(pas) = pas else {
+                continue;
+            };
+            let Ok((amounts, _)) =
+                s.business_token_pair_swap_fixed_in_checking(&TokenPairSwapExactTokensForTokensArg {
+                    self_canister,
+                    pas,
+                    from: Account::from(self_canister.id()),
+                    amount_in: amount_in.clone(),
+                    amount_out_min: zero(),
+                    path: path.clone(),
+                    to: Account::from(self_canister.id()),
+                })
+            else {
+                continue;
+            };
+            if best
+                .as_ref()
+                .is_none_or(|best| best.amounts[best.amounts.len() - 1] < amounts[amounts.len() - 1])
+            {
+                best = Some(TokenPairRoute { path, amounts });
+            }
+        }
+        best.ok_or_else(|| BusinessError::Swap("NO_ROUTE".into()))
+    })
+}
+
+// anyone can query
+#[ic_cdk::query]
+fn pair_route_query(
+    token_in: CanisterId,
+    token_out: CanisterId,
+    amount_in: Nat,
+    max_hops: Option<u8>,
+) -> TokenPairRouteResult {
+    check_caller(&caller())
+        .and_then(|(self_canister, _)| find_best_route(self_canister, token_in, token_out, &amount_in, max_hops))
+        .into()
+}
+
+// check forbidden
+#[ic_cdk::update(guard = "has_business_token_pair_swap")]
+async fn pair_route_swap_exact_tokens_for_tokens(
+    args: TokenPairRouteSwapArgs,
+    retries: Option<u8>,
+) -> TokenPairSwapTokensResult {
+    inner_pair_route_swap_exact_tokens_for_tokens(args, retries)
+        .await
+        .into()
+}
+async fn inner_pair_route_swap_exact_tokens_for_tokens(
+    args: TokenPairRouteSwapArgs,
+    retries: Option<u8>,
+) -> Result<TokenPairSwapTokensSuccess, BusinessError> {
+    // 1. find path, owner or approved spender is checked by pay exact
+    let (self_canister, _) = check_self_canister();
+    let route = find_best_route(
+        self_canister,
+        args.token_in,
+        args.token_out,
+        &args.amount_in,
+        args.max_hops,
+    )?;
+
+    // 2. swap by the path, lock and check as pay exact
+    let args = TokenPairSwapExactTokensForTokensArgs {
+        from: args.from,
+        amount_in: args.amount_in,
+        amount_out_min: args.amount_out_min,
+        path: route.path,
+        to: args.to,
+        deadline: args.deadline,
+        memo: args.memo,
+        created: args.created,
+    };
+    super::pay_exact::inner_pair_swap_exact_tokens_for_tokens(args, retries, true).await
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::stable::testing::{pool, token};
+
+    fn search(pools: Vec<(TokenPairAmm, MarketMaker)>, max_hops: usize) -> (Vec<(f64, Vec<SwapTokenPair>)>, usize) {
+        let mut makers = vec![];
+        let mut edges: HashMap<CanisterId, Vec<(CanisterId, TokenPairAmm, usize)>> = HashMap::new();
+        for (pa, maker) in pools {
+            let (token0, token1) = (pa.pair.get_token0(), pa.pair.get_token1());
+            edges.entry(token0).or_default().push((token1, pa, makers.len()));
+            edges.entry(token1).or_default().push((token0, pa, makers.len()));
+            makers.push(maker);
+        }
+        let mut searching = RouterSearching {
+            edges,
+            makers: &makers,
+            token_out: token(3),
+            max_hops,
+            visited: 0,
+            found: vec![],
+        };
+        searching.search(token(1), 10_000_f64, &mut vec![token(1)], &mut vec![]);
+        let mut found = searching.found;
+        found.sort_by(|a, b| b.0.total_cmp(&a.0));
+        (found, searching.visited)
+    }
+
+    #[test]
+    fn test_router_search() {
+        // estimated by mid price, the direct pool is worse than the pools through token 2
+        let pools = vec![
+            pool(1, 3, 100_000, 90_000),
+            pool(1, 2, 10_000_000, 10_000_000),
+            pool(2, 3, 10_000_000, 10_000_000),
+            pool(2, 4, 10_000_000, 10_000_000), // never reaches token 3
+        ];
+
+        let (found, _) = search(pools.clone(), 3);
+        assert_eq!(found.len(), 2);
+        let hops = |path: &[SwapTokenPair]| path.iter().map(|p| p.token).collect::<Vec<_>>();
+        assert_eq!(hops(&found[0].1), vec![(token(1), token(2)), (token(2), token(3))]);
+        assert_eq!(hops(&found[1].1), vec![(token(1), token(3))]);
+        // the estimation is the output of single hops one by one
+        let (_, got) = pools[1]
+            .1
+            .swap_to(&token(1), 10_000_f64 - pools[1].1.get_fee(10_000_f64))
+            .unwrap();
+        let (_, got) = pools[2].1.swap_to(&token(2), got - pools[2].1.get_fee(got)).unwrap();
+        assert_eq!(found[0].0, got);
+
+        // only the direct pool by 1 hop
+        let (found, _) = search(pools, 1);
+        assert_eq!(found.len(), 1);
+        assert_eq!(hops(&found[0].1), vec![(token(1), token(3))]);
+    }
+
+    #[test]
+    fn test_router_search_bounded() {
+        // every 2 of 40 tokens has a pool, 4 hops are far more than the bound
+        let mut pools = vec![];
+        for a in 1..=40 {
+            for b in (a + 1)..=40 {
+                pools.push(pool(a, b, 10_000_000, 10_000_000));
+            }
+        }
+        let (found, visited) = search(pools, 4);
+        assert_eq!(visited, ROUTER_MAX_VISITS);
+        assert!(!found.is_empty());
+        assert!(found.len() <= ROUTER_MAX_PATHS);
+    }
+}
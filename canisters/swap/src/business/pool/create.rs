@@ -0,0 +1,81 @@
+#[allow(unused)]
+use ic_canister_kit::identity::caller;
+
+#[allow(unused)]
+use crate::stable::*;
+#[allow(unused)]
+use crate::types::*;
+
+// ========================== create ==========================
+
+// create
+fn check_pool_create_args(
+    _self: &TokenPoolCreateOrRemoveArgs,
+) -> Result<(TimestampNanos, Caller, TokenPoolAmm), BusinessError> {
+    // ! refuse all action about frozen token
+    for token in &_self.tokens {
+        with_state(|s| s.business_token_alive(token))?;
+    }
+
+    let pool = TokenPool::new(&_self.tokens)?;
+    for pair in pool.pairs() {
+        check_token_pair_args(&pair)?; // check supported token
+    }
+    let amm: Amm = check_amm_args(&_self.amm)?; // parse amm and check fee tier
+    if !amm.is_multi_asset() {
+        return Err(BusinessError::Swap("NOT_MULTI_ASSET_AMM".into()));
+    }
+
+    let pool = TokenPoolAmm { pool, amm };
+
+    // check exist
+    if with_state(|s| s.business_token_pool_get(&pool).is_some()) {
+        return Err(BusinessError::TokenPoolAmmExist(pool));
+    }
+
+    // check meta
+    let now = check_meta(&_self.memo, &_self.created)?;
+
+    Ok((now, Caller::get(), pool))
+}
+
+// check forbidden
+#[ic_cdk::update(guard = "has_business_token_pair_create_or_remove")]
+async fn pool_create(args: TokenPoolCreateOrRemoveArgs) -> TokenPairCreateOrRemoveResult {
+    inner_pool_create(args).await.map(|m| m.into()).into()
+}
+async fn inner_pool_create(args: TokenPoolCreateOrRemoveArgs) -> Result<MarketMaker, BusinessError> {
+    // 1. check args
+    let (now, caller, pool) = check_pool_create_args(&args)?;
+
+    // 2. some value
+
+    let maker = {
+        // 3. lock
+        let lock = match super::super::lock_swap_block_chain(0)? {
+            LockResult::Locked(lock) => lock,
+            LockResult::Retry(_) => return Err(BusinessError::SwapBlockChainLocked),
+        };
+
+        // * 4. do business
+        {
+            with_mut_state(|s| {
+                s.business_token_pool_create(
+                    &lock,
+                    ArgWithMeta {
+                        now,
+                        caller,
+                        arg: pool,
+                        memo: args.memo,
+                        created: args.created,
+                    },
+                )
+            })?
+        }
+    };
+
+    // Asynchronously triggers synchronization tasks
+    crate::business::config::push::inner_push_blocks(false, true);
+
+    Ok(maker)
+}
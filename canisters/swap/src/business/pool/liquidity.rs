@@ -0,0 +1,313 @@
+#[allow(unused)]
+use ic_canister_kit::identity::caller;
+
+#[allow(unused)]
+use crate::stable::*;
+#[allow(unused)]
+use crate::types::*;
+
+// amounts in order of args tokens to amounts in order of pool tokens
+fn sort_pool_amounts(tokens: &[CanisterId], pool: &TokenPoolAmm, amounts: &[Nat]) -> Result<Vec<Nat>, BusinessError> {
+    if tokens.len() != amounts.len() {
+        return Err(BusinessError::Liquidity("INVALID_AMOUNTS".into()));
+    }
+    let mut sorted = vec![Nat::from(0_u8); tokens.len()];
+    for (token, amount) in tokens.iter().zip(amounts.iter()) {
+        let index = pool
+            .pool
+            .index_of(token)
+            .ok_or_else(|| BusinessError::Liquidity("INVALID_AMOUNTS".into()))?;
+        sorted[index] = amount.clone();
+    }
+    Ok(sorted)
+}
+
+// ========================== add ==========================
+
+// liquidity add
+impl CheckArgs for TokenPoolLiquidityAddArgs {
+    type Result = (
+        TimestampNanos,
+        Vec<CanisterId>,
+        Vec<TokenAccount>,
+        SelfCanister,
+        Caller,
+        TokenPoolLiquidityAddArg,
+    );
+    fn check_args(&self) -> Result<Self::Result, BusinessError> {
+        // ! refuse all action about frozen token
+        for token in &self.tokens {
+            with_state(|s| s.business_token_alive(token))?;
+        }
+
+        // check owner
+        let (self_canister, caller) = check_caller(&self.from.owner)?;
+
+        // check pool
+        let (pool, fee_tokens, required) = check_token_pool(&self.tokens, &self.amm, &self_canister, Some(&self.to))?;
+
+        let arg = TokenPoolLiquidityAddArg {
+            self_canister,
+            pa: pool.anchor(),
+            from: self.from,
+            amounts_desired: sort_pool_amounts(&self.tokens, &pool, &self.amounts_desired)?,
+            liquidity_min: self.liquidity_min.clone(),
+            to: self.to,
+            pool,
+        };
+
+        // check amount
+        arg.check_args()?;
+
+        // check balance
+        for (token, amount) in arg.pool.pool.get_tokens().iter().zip(arg.amounts_desired.iter()) {
+            let balance = with_state(|s| s.business_token_balance_of(*token, arg.from));
+            if balance < *amount {
+                return Err(BusinessError::insufficient_balance(*token, balance));
+            }
+        }
+
+        // check deadline
+        if let Some(deadline) = &self.deadline {
+            deadline.check_args()?;
+        }
+
+        // check meta
+        let now = check_meta(&self.memo, &self.created)?;
+
+        Ok((now, fee_tokens, required, self_canister, caller, arg))
+    }
+}
+
+// check forbidden
+#[ic_cdk::update(guard = "has_business_token_pair_liquidity_add")]
+async fn pool_liquidity_add(args: TokenPoolLiquidityAddArgs, retries: Option<u8>) -> TokenPoolLiquidityAddResult {
+    inner_pool_liquidity_add(args, retries).await.into()
+}
+#[inline]
+async fn inner_pool_liquidity_add(
+    args: TokenPoolLiquidityAddArgs,
+    retries: Option<u8>,
+) -> Result<TokenPoolLiquidityAddSuccess, BusinessError> {
+    // 0. approved spender, the swap canister adds liquidity on behalf of owner
+    let pool = check_token_pool(&args.tokens, &args.amm, &check_self_canister().0, None)
+        .ok()
+        .map(|(pool, _, _)| pool);
+    let spends = pool
+        .and_then(|pool| {
+            let amounts = sort_pool_amounts(&args.tokens, &pool, &args.amounts_desired).ok()?;
+            Some(pool.pool.get_tokens().iter().copied().zip(amounts).collect())
+        })
+        .unwrap_or_default();
+    if let Some(spending) = spend_allowance(&args.from, spends)? {
+        let service_swap = crate::services::swap::Service(self_canister_id());
+        let result = service_swap.pool_liquidity_add(args, retries).await;
+        return spending.settle(result, |success| success.amounts.clone()); // in order of pool tokens
+    }
+
+    // 1. check args
+    let (now, fee_tokens, mut required, self_canister, caller, arg) = args.check_args()?;
+
+    // 2. some value
+    for token in arg.pool.pool.get_tokens() {
+        required.push(TokenAccount::new(*token, arg.from));
+    }
+
+    let success = {
+        // 3. lock
+        let locks = match super::super::lock_token_block_chain_and_swap_block_chain_and_token_balances_and_token_pairs(
+            fee_tokens,
+            required,
+            vec![arg.pa],
+            retries.unwrap_or_default(),
+        )? {
+            LockResult::Locked(locks) => locks,
+            LockResult::Retry(retries) => {
+                return retry_pool_liquidity_add(self_canister.id(), args, retries).await;
+            }
+        };
+
+        // * 4. do business
+        {
+            with_mut_state(|s| {
+                s.business_token_pool_liquidity_add(
+                    &locks,
+                    ArgWithMeta {
+                        now,
+                        caller,
+                        arg,
+                        memo: args.memo,
+                        created: args.created,
+                    },
+                )
+            })?
+        }
+    };
+
+    // Asynchronously triggers synchronization tasks
+    crate::business::config::push::inner_push_blocks(true, true);
+
+    Ok(success)
+}
+// ! This implicitly contains self_canister_id, which can be called again through permission checks and replaces caller.
+#[inline]
+async fn retry_pool_liquidity_add(
+    self_canister_id: CanisterId,
+    args: TokenPoolLiquidityAddArgs,
+    retries: u8,
+) -> Result<TokenPoolLiquidityAddSuccess, BusinessError> {
+    ic_cdk::println!("🔄 retry_pool_liquidity_add: {}", retries);
+    let service_swap = crate::services::swap::Service(self_canister_id);
+    return service_swap.pool_liquidity_add(args, Some(retries)).await;
+}
+
+// ========================== remove ==========================
+
+// liquidity remove
+impl CheckArgs for TokenPoolLiquidityRemoveArgs {
+    type Result = (
+        TimestampNanos,
+        Vec<CanisterId>,
+        Vec<TokenAccount>,
+        SelfCanister,
+        Caller,
+        TokenPoolLiquidityRemoveArg,
+    );
+    fn check_args(&self) -> Result<Self::Result, BusinessError> {
+        // ! refuse all action about frozen token
+        for token in &self.tokens {
+            with_state(|s| s.business_token_alive(token))?;
+        }
+
+        // check owner
+        let (self_canister, caller) = check_caller(&self.from.owner)?;
+
+        // check pool
+        let (pool, fee_tokens, required) = check_token_pool(&self.tokens, &self.amm, &self_canister, Some(&self.from))?;
+        let pa = pool.anchor();
+
+        // check liquidity balance and fee
+        let token = with_state(|s| s.business_token_query_by_pa(&pa)).ok_or_else(|| pool.not_exist())?;
+        let (balance, mut fee_to) =
+            with_state(|s| s.business_token_balance_of_with_fee_to(token.canister_id, self.from));
+        fee_to = caller.fee_to(fee_to, self.to); // ! check token fee to required or not
+        let amount = self.liquidity_without_fee.clone() + fee_to.map(|_| token.fee.clone()).unwrap_or_default();
+        if balance < amount {
+            return Err(BusinessError::Liquidity("INSUFFICIENT_LIQUIDITY".into()));
+        }
+
+        let arg = TokenPoolLiquidityRemoveArg {
+            self_canister,
+            pa,
+            from: self.from,
+            liquidity_without_fee: self.liquidity_without_fee.clone(),
+            amounts_min: sort_pool_amounts(&self.tokens, &pool, &self.amounts_min)?,
+            to: self.to,
+            fee: fee_to.map(|fee_to| BurnFee {
+                fee: token.fee.clone(),
+                fee_to,
+            }),
+            pool,
+        };
+
+        // check amount
+        arg.check_args()?;
+
+        // check liquidity balance
+        with_state(|s| {
+            s.business_token_pair_check_liquidity_removable(&pa, &arg.from, &arg.liquidity_without_fee, fee_to)
+        })?;
+
+        // check deadline
+        if let Some(deadline) = &self.deadline {
+            deadline.check_args()?;
+        }
+
+        // check meta
+        let now = check_meta(&self.memo, &self.created)?;
+
+        Ok((now, fee_tokens, required, self_canister, caller, arg))
+    }
+}
+
+// check forbidden
+#[ic_cdk::update(guard = "has_business_token_pair_liquidity_remove")]
+async fn pool_liquidity_remove(
+    args: TokenPoolLiquidityRemoveArgs,
+    retries: Option<u8>,
+) -> TokenPoolLiquidityRemoveResult {
+    inner_pool_liquidity_remove(args, retries).await.into()
+}
+#[inline]
+async fn inner_pool_liquidity_remove(
+    args: TokenPoolLiquidityRemoveArgs,
+    retries: Option<u8>,
+) -> Result<TokenPoolLiquidityRemoveSuccess, BusinessError> {
+    // 0. approved spender, the swap canister removes liquidity on behalf of owner
+    let spends = check_token_pool(&args.tokens, &args.amm, &check_self_canister().0, None)
+        .ok()
+        .and_then(|(pool, _, _)| with_state(|s| s.business_token_query_by_pa(&pool.anchor())))
+        .map(|token| vec![(token.canister_id, args.liquidity_without_fee.clone())])
+        .unwrap_or_default();
+    if let Some(spending) = spend_allowance(&args.from, spends)? {
+        let liquidity = args.liquidity_without_fee.clone();
+        let service_swap = crate::services::swap::Service(self_canister_id());
+        let result = service_swap.pool_liquidity_remove(args, retries).await;
+        return spending.settle(result, |_| vec![liquidity]);
+    }
+
+    // 1. check args
+    let (now, fee_tokens, mut required, self_canister, caller, arg) = args.check_args()?;
+
+    // 2. some value
+    for token in arg.pool.pool.get_tokens() {
+        required.push(TokenAccount::new(*token, arg.to));
+    }
+
+    let success = {
+        // 3. lock
+        let locks = match super::super::lock_token_block_chain_and_swap_block_chain_and_token_balances_and_token_pairs(
+            fee_tokens,
+            required,
+            vec![arg.pa],
+            retries.unwrap_or_default(),
+        )? {
+            LockResult::Locked(locks) => locks,
+            LockResult::Retry(retries) => {
+                return retry_pool_liquidity_remove(self_canister.id(), args, retries).await;
+            }
+        };
+
+        // * 4. do business
+        {
+            with_mut_state(|s| {
+                s.business_token_pool_liquidity_remove(
+                    &locks,
+                    ArgWithMeta {
+                        now,
+                        caller,
+                        arg,
+                        memo: args.memo,
+                        created: args.created,
+                    },
+                )
+            })?
+        }
+    };
+
+    // Asynchronously triggers synchronization tasks
+    crate::business::config::push::inner_push_blocks(true, true);
+
+    Ok(success)
+}
+// ! This implicitly contains self_canister_id, which can be called again through permission checks and replaces caller.
+#[inline]
+async fn retry_pool_liquidity_remove(
+    self_canister_id: CanisterId,
+    args: TokenPoolLiquidityRemoveArgs,
+    retries: u8,
+) -> Result<TokenPoolLiquidityRemoveSuccess, BusinessError> {
+    ic_cdk::println!("🔄 retry_pool_liquidity_remove: {}", retries);
+    let service_swap = crate::services::swap::Service(self_canister_id);
+    return service_swap.pool_liquidity_remove(args, Some(retries)).await;
+}
@@ -0,0 +1,15 @@
+#[allow(unused)]
+use ic_canister_kit::identity::caller;
+
+#[allow(unused)]
+use crate::stable::*;
+#[allow(unused)]
+use crate::types::*;
+
+mod query;
+
+mod create;
+
+mod remove;
+
+mod liquidity;
@@ -0,0 +1,31 @@
+#[allow(unused)]
+use ic_canister_kit::identity::caller;
+
+#[allow(unused)]
+use crate::stable::*;
+#[allow(unused)]
+use crate::types::*;
+
+// ========================== query pools ==========================
+
+// anyone can query
+#[ic_cdk::query]
+fn pools_query() -> Vec<(TokenPool, AmmText, MarketMakerView)> {
+    with_state(|s| {
+        s.business_token_pools_query()
+            .into_iter()
+            .map(|(pool, maker)| (pool.pool, pool.amm.into(), maker.into()))
+            .collect()
+    })
+}
+
+// anyone can query
+#[ic_cdk::query]
+fn pool_query(tokens: Vec<CanisterId>, amm: AmmText) -> Option<MarketMakerView> {
+    let pool = TokenPool::new(&tokens).ok()?;
+    let amm: Amm = amm.as_ref().try_into().ok()?; // parse amm
+
+    let pool = TokenPoolAmm { pool, amm };
+
+    with_state(|s| s.business_token_pool_get(&pool).map(|maker| maker.into()))
+}
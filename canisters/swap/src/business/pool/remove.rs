@@ -0,0 +1,78 @@
+#[allow(unused)]
+use ic_canister_kit::identity::caller;
+
+#[allow(unused)]
+use crate::stable::*;
+#[allow(unused)]
+use crate::types::*;
+
+// ========================== remove ==========================
+
+// remove
+fn check_pool_remove_args(
+    _self: &TokenPoolCreateOrRemoveArgs,
+) -> Result<(TimestampNanos, Caller, TokenPoolAmm), BusinessError> {
+    // ! refuse all action about frozen token
+    for token in &_self.tokens {
+        with_state(|s| s.business_token_alive(token))?;
+    }
+
+    let pool = TokenPool::new(&_self.tokens)?;
+    let amm: Amm = _self.amm.as_ref().try_into()?; // parse amm
+
+    let pool = TokenPoolAmm { pool, amm };
+
+    // check exist and removable
+    match with_state(|s| s.business_token_pool_get(&pool)) {
+        Some(maker) if !maker.removable() => return Err(BusinessError::TokenPoolAmmStillAlive(pool)),
+        None => return Err(pool.not_exist()),
+        _ => {}
+    }
+
+    // check meta
+    let now = check_meta(&_self.memo, &_self.created)?;
+
+    Ok((now, Caller::get(), pool))
+}
+
+// check forbidden
+#[ic_cdk::update(guard = "has_business_token_pair_create_or_remove")]
+async fn pool_remove(args: TokenPoolCreateOrRemoveArgs) -> TokenPairCreateOrRemoveResult {
+    inner_pool_remove(args).await.map(|m| m.into()).into()
+}
+async fn inner_pool_remove(args: TokenPoolCreateOrRemoveArgs) -> Result<MarketMaker, BusinessError> {
+    // 1. check args
+    let (now, caller, pool) = check_pool_remove_args(&args)?;
+
+    // 2. some value
+    let required = vec![pool.anchor()]; // all member pairs are locked together
+
+    let maker = {
+        // 3. lock
+        let locks = match super::super::lock_swap_block_chain_and_token_pairs(required, 0)? {
+            LockResult::Locked(locks) => locks,
+            LockResult::Retry(_) => return Err(BusinessError::SwapBlockChainLocked),
+        };
+
+        // * 4. do business
+        {
+            with_mut_state(|s| {
+                s.business_token_pool_remove(
+                    &locks,
+                    ArgWithMeta {
+                        now,
+                        caller,
+                        arg: pool,
+                        memo: args.memo,
+                        created: args.created,
+                    },
+                )
+            })?
+        }
+    };
+
+    // Asynchronously triggers synchronization tasks
+    crate::business::config::push::inner_push_blocks(false, true);
+
+    Ok(maker)
+}
@@ -6,6 +6,9 @@ use crate::stable::*;
 #[allow(unused)]
 use crate::types::*;
 
+use ::common::types::AccountIdentifier;
+use icrc_ledger_types::icrc1::transfer::TransferError;
+
 // ========================== deposit ==========================
 
 // deposit
@@ -15,8 +18,11 @@ impl CheckArgs for TokenDepositArgs {
         // ! refuse all action about frozen token
         with_state(|s| s.business_token_alive(&self.token))?;
 
-        // ! must be token, can not be dummy LP token
-        if !with_state(|s| s.business_tokens_query().contains_key(&self.token)) {
+        // ! must be token or outer lp token (burned by deposit), can not be dummy LP token
+        if !with_state(|s| {
+            s.business_tokens_query().contains_key(&self.token)
+                || s.business_outer_lp_tokens_query().contains_key(&self.token)
+        }) {
             return Err(BusinessError::NotSupportedToken(self.token));
         }
 
@@ -70,8 +76,6 @@ pub async fn inner_token_deposit(
 
         // * 4. do business
         {
-            let service_icrc2 = crate::services::icrc2::Service(args.token);
-
             // ? 1. transfer token to self
             let self_account = Account {
                 owner: self_canister.id(),
@@ -93,7 +97,7 @@ pub async fn inner_token_deposit(
                 display_account(&transfer_from_arg.to),
                 transfer_from_arg.amount.to_string(),
             );
-            let height = service_icrc2.icrc_2_transfer_from(transfer_from_arg).await??;
+            let height = super::icrc2_transfer_from_with_fee(args.token, transfer_from_arg).await?;
 
             // ? 2. record changed
             let amount = args.deposit_amount_without_fee; // ! Actual deposit
@@ -136,3 +140,311 @@ async fn retry_token_deposit(
     let service_swap = crate::services::swap::Service(self_canister_id);
     return service_swap.token_deposit(args, Some(retries)).await;
 }
+
+// ========================== deposit by icrc1 transfer ==========================
+
+// anyone can query
+#[ic_cdk::query]
+fn token_deposit_subaccount(account: Account) -> Account {
+    token_deposit_account(self_canister_id(), &account)
+}
+
+// anyone can query, the deposit account as legacy account identifier for ICP ledger
+#[ic_cdk::query]
+fn token_deposit_account_id(account: Account) -> AccountIdentifier {
+    AccountIdentifier::new(&token_deposit_account(self_canister_id(), &account))
+}
+
+// anyone can notify, the tokens arrived at the deposit account are swept to the account
+#[ic_cdk::update(guard = "has_business_token_deposit")]
+async fn token_deposit_notify(token: CanisterId, account: Account, retries: Option<u8>) -> TokenChangedResult {
+    inner_token_deposit_notify(token, account, retries).await.into()
+}
+#[inline]
+async fn inner_token_deposit_notify(
+    token: CanisterId,
+    account: Account,
+    retries: Option<u8>,
+) -> Result<candid::Nat, BusinessError> {
+    // 1. check args
+    // ! refuse all action about frozen token
+    with_state(|s| s.business_token_alive(&token))?;
+
+    // ! must be token, can not be lp token
+    let info = with_state(|s| {
+        s.business_tokens_query()
+            .get(&token)
+            .map(|token| token.clone().into_owned())
+    })
+    .ok_or(BusinessError::NotSupportedToken(token))?;
+
+    let (self_canister, _) = check_self_canister();
+    let caller = Caller::get();
+    if account.owner == self_canister.id() {
+        return Err(BusinessError::Swap("TO_ACCOUNT_CAN_NOT_BE_SWAP_CANISTER".into()));
+    }
+    let now = TimestampNanos::now();
+
+    // 2. some value
+    let fee_tokens = vec![];
+    let required = vec![TokenAccount::new(token, account)];
+
+    let height = {
+        // 3. lock
+        let locks = match super::super::lock_token_block_chain_and_token_balances(
+            fee_tokens,
+            required,
+            retries.unwrap_or_default(),
+        )? {
+            LockResult::Locked(locks) => locks,
+            LockResult::Retry(retries) => {
+                return retry_token_deposit_notify(self_canister.id(), token, account, retries).await;
+            }
+        };
+
+        // * 4. do business
+        {
+            let deposit_account = token_deposit_account(self_canister.id(), &account);
+            let self_account = Account {
+                owner: self_canister.id(),
+                subaccount: None,
+            };
+            let sweep = |sweeping: &TokenDepositSweeping| {
+                with_mut_state(|s| {
+                    s.business_token_deposit_sweeping_set(&locks, token, account, Some(sweeping.clone()))
+                });
+                sweep_deposit(token, deposit_account, self_account, sweeping.clone())
+            };
+            let forget = || with_mut_state(|s| s.business_token_deposit_sweeping_set(&locks, token, account, None));
+
+            // ? 1. the sweep sent before is not credited, the ledger tells whether it is done by deduplication
+            let mut swept = None;
+            if let Some(sweeping) = with_state(|s| s.business_token_deposit_sweeping(token, account)) {
+                match sweep(&sweeping).await? {
+                    Ok(height) => swept = Some((sweeping, height)),
+                    Err(err) => {
+                        // ! the error only tells this transfer is refused, such as TooOld out of deduplication window.
+                        // ! the sweep is forgotten only if the ledger proves it is never done.
+                        ic_cdk::println!("sweeping of deposit account is refused: {err:?}");
+                        match lookup_sweep(token, deposit_account, self_account, &sweeping).await? {
+                            Some(height) => swept = Some((sweeping, height)),
+                            None => forget(), // * the sweep is never done, sweep the arrived tokens again
+                        }
+                    }
+                }
+            }
+
+            let (sweeping, height) = match swept {
+                Some(swept) => swept,
+                None => {
+                    // ? 2. query arrived tokens
+                    let balance = crate::services::icrc2::Service(token)
+                        .icrc_1_balance_of(deposit_account)
+                        .await?;
+                    // the sweep is looked up from here if the result of it is lost
+                    let log_length = ledger_log_length(token).await;
+                    let make_sweeping = |fee: &candid::Nat| {
+                        if balance <= *fee {
+                            return Err(BusinessError::insufficient_balance(token, balance.clone()));
+                        }
+                        Ok(TokenDepositSweeping {
+                            amount: balance.clone() - fee.clone(),
+                            fee: fee.clone(),
+                            created_at_time: now.into_inner(),
+                            log_length: log_length.clone(),
+                        })
+                    };
+
+                    // ? 3. sweep to self, ! the sweep is recorded before sending, the result of call may be lost
+                    let sweeping = make_sweeping(&info.fee)?;
+                    match sweep(&sweeping).await? {
+                        Ok(height) => (sweeping, height),
+                        Err(TransferError::BadFee { expected_fee }) => {
+                            ic_cdk::println!("🔄 retry sweeping with expected fee: {expected_fee}");
+                            super::update_ledger_fee(token, expected_fee.clone());
+                            let sweeping = make_sweeping(&expected_fee).inspect_err(|_| forget())?;
+                            match sweep(&sweeping).await? {
+                                Ok(height) => (sweeping, height),
+                                Err(err) => {
+                                    forget();
+                                    return Err(err.into());
+                                }
+                            }
+                        }
+                        Err(err) => {
+                            forget();
+                            return Err(err.into());
+                        }
+                    }
+                }
+            };
+            let amount = sweeping.amount; // ! Actual deposit
+
+            // ? 3. record changed
+            with_mut_state(|s| {
+                s.business_token_deposit_notify(
+                    &locks,
+                    ArgWithMeta::simple(
+                        now,
+                        caller,
+                        DepositToken {
+                            token,
+                            from: deposit_account,
+                            amount,
+                            to: account,
+                        },
+                    ),
+                    height,
+                )
+            })?
+        }
+    };
+
+    // Asynchronously triggers synchronization tasks
+    crate::business::config::push::inner_push_blocks(true, false);
+
+    Ok(height)
+}
+// ! This implicitly contains self_canister_id, which can be called again through permission checks and replaces caller.
+#[inline]
+async fn retry_token_deposit_notify(
+    self_canister_id: CanisterId,
+    token: CanisterId,
+    account: Account,
+    retries: u8,
+) -> Result<candid::Nat, BusinessError> {
+    ic_cdk::println!("🔄 retry_token_deposit_notify: {}", retries);
+    let service_swap = crate::services::swap::Service(self_canister_id);
+    return service_swap.token_deposit_notify(token, account, Some(retries)).await;
+}
+
+// Transfer the arrived tokens from deposit account to self.
+// The outer error is the failed call, the sweep may be done or not.
+async fn sweep_deposit(
+    token: CanisterId,
+    deposit_account: Account,
+    self_account: Account,
+    sweeping: TokenDepositSweeping,
+) -> Result<Result<candid::Nat, TransferError>, BusinessError> {
+    let transfer_arg = crate::services::icrc2::TransferArg {
+        from_subaccount: deposit_account
+            .subaccount
+            .map(|s| serde_bytes::ByteBuf::from(s.to_vec())),
+        to: self_account,
+        amount: sweeping.amount,
+        fee: Some(sweeping.fee),
+        memo: None,
+        created_at_time: Some(sweeping.created_at_time), // * deduplicated by ledger
+    };
+    ic_cdk::println!(
+        "*CallIcrc1Transfer* `token:[{}], from:({}), amount:{}, fee:{}`",
+        token.to_string(),
+        display_account(&deposit_account),
+        transfer_arg.amount.to_string(),
+        transfer_arg.fee.as_ref().map(|fee| fee.to_string()).unwrap_or_default()
+    );
+    let result = crate::services::icrc2::Service(token)
+        .icrc_1_transfer(transfer_arg)
+        .await?;
+    Ok(match result {
+        // ! the same sweep is done before
+        Err(TransferError::Duplicate { duplicate_of }) => Ok(duplicate_of),
+        result => result,
+    })
+}
+
+// The length of ledger transactions, none if the ledger can not tell.
+async fn ledger_log_length(token: CanisterId) -> Option<candid::Nat> {
+    crate::services::icrc2::Service(token)
+        .get_transactions(crate::services::icrc2::GetBlocksRequest {
+            start: candid::Nat::from(0_u64),
+            length: candid::Nat::from(0_u64),
+        })
+        .await
+        .inspect_err(|err| ic_cdk::println!("query log length of token [{}] failed: {err:?}", token.to_text()))
+        .ok()
+        .map(|response| response.log_length)
+}
+
+const SWEEP_LOOKUP_LENGTH: u64 = 1_000;
+const SWEEP_LOOKUP_CALLS: usize = 20;
+
+// Look up the sweep in the ledger transactions appended after it is recorded.
+// The height of sweep if it is done, none if all transactions are looked up and it is never done.
+// The error is that the ledger can not tell, the sweep must be kept.
+async fn lookup_sweep(
+    token: CanisterId,
+    deposit_account: Account,
+    self_account: Account,
+    sweeping: &TokenDepositSweeping,
+) -> Result<Option<candid::Nat>, BusinessError> {
+    use crate::services::icrc2::{GetBlocksRequest, Service, Transaction, TransactionRange};
+
+    let unconfirmed = || BusinessError::Swap("DEPOSIT_SWEEPING_UNCONFIRMED".into());
+    let mut next = sweeping.log_length.clone().ok_or_else(unconfirmed)?;
+    let find = |first_index: &candid::Nat, transactions: &[Transaction]| {
+        transactions.iter().enumerate().find_map(|(i, transaction)| {
+            transaction
+                .transfer
+                .as_ref()
+                .filter(|t| {
+                    sweeping.is_swept_by(
+                        &deposit_account,
+                        &self_account,
+                        (&t.from, &t.to, &t.amount, t.created_at_time),
+                    )
+                })
+                .map(|_| first_index.clone() + candid::Nat::from(i))
+        })
+    };
+
+    let mut calls = 0;
+    while calls < SWEEP_LOOKUP_CALLS {
+        calls += 1;
+        let response = Service(token)
+            .get_transactions(GetBlocksRequest {
+                start: next.clone(),
+                length: candid::Nat::from(SWEEP_LOOKUP_LENGTH),
+            })
+            .await?;
+
+        // the archived transactions are before the transactions in ledger
+        let mut ranges = Vec::with_capacity(response.archived_transactions.len() + 1);
+        for archived in response.archived_transactions {
+            calls += 1;
+            let range: TransactionRange =
+                ic_cdk::call::Call::unbounded_wait(archived.callback.0.principal, &archived.callback.0.method)
+                    .with_arg(GetBlocksRequest {
+                        start: archived.start.clone(),
+                        length: archived.length,
+                    })
+                    .await?
+                    .candid()?;
+            ranges.push((archived.start, range.transactions));
+        }
+        ranges.push((response.first_index, response.transactions));
+
+        let start = next.clone();
+        for (first_index, transactions) in ranges {
+            if next < first_index {
+                break; // ! the gap is looked up again
+            }
+            if let Some(height) = find(&first_index, &transactions) {
+                return Ok(Some(height));
+            }
+            let end = first_index + candid::Nat::from(transactions.len());
+            if next < end {
+                next = end;
+            }
+        }
+
+        if response.log_length <= next {
+            return Ok(None); // * all transactions after the sweep are looked up
+        }
+        if next == start {
+            break; // ! nothing is looked up
+        }
+    }
+
+    Err(unconfirmed())
+}
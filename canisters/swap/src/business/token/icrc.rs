@@ -0,0 +1,731 @@
+#[allow(unused)]
+use ic_canister_kit::identity::caller;
+
+#[allow(unused)]
+use crate::stable::*;
+#[allow(unused)]
+use crate::types::*;
+
+// The swap canister keeps internal balances of many tokens, so it can not be a standard icrc ledger of one token.
+// These endpoints only follow the shapes of icrc1, icrc2 and icrc3, the token is given as the first argument,
+// and they are prefixed by `token_` so that they are never taken for standard methods by wallets.
+// All tokens share the internal balances and the token block chain, whose hashes are not icrc3 hashes.
+
+fn icrc_token(token: &CanisterId) -> TokenInfo {
+    let token = with_state(|s| {
+        s.business_all_tokens_with_dummy_query()
+            .remove(token)
+            .map(|token| token.into_owned())
+    });
+    trap(token.ok_or("token is not supported"))
+}
+
+// the fee charged by internal transfer, nothing is charged if there is no token fee to
+fn icrc_fee(token: &TokenInfo) -> Nat {
+    with_state(|s| s.business_config_fee_to_query().token_fee_to)
+        .map(|_| token.fee.clone())
+        .unwrap_or_default()
+}
+
+// the same transaction in the transaction window is deduplicated, only if created_at_time is given
+fn icrc_transaction_hash<T: candid::CandidType>(transaction: &T) -> Result<String, BusinessError> {
+    let bytes = candid::encode_one(transaction).map_err(|err| BusinessError::system_error(err.to_string()))?;
+    Ok(hex::encode(::common::utils::hash::hash_sha256(&bytes)))
+}
+fn check_icrc_duplicate(dedup: &Option<(String, TimestampNanos)>) -> Result<(), BusinessError> {
+    match dedup
+        .as_ref()
+        .and_then(|(hash, _)| with_state(|s| s.business_token_icrc_transaction_get(hash)))
+    {
+        Some(duplicate_of) => Err(BusinessError::DuplicateTransaction {
+            duplicate_of: Nat::from(duplicate_of),
+        }),
+        None => Ok(()),
+    }
+}
+
+// ============================== icrc1 ==============================
+
+// anyone can query
+#[ic_cdk::query]
+fn token_icrc1_name(token: CanisterId) -> String {
+    icrc_token(&token).name
+}
+
+// anyone can query
+#[ic_cdk::query]
+fn token_icrc1_symbol(token: CanisterId) -> String {
+    icrc_token(&token).symbol
+}
+
+// anyone can query
+#[ic_cdk::query]
+fn token_icrc1_decimals(token: CanisterId) -> u8 {
+    icrc_token(&token).decimals
+}
+
+// anyone can query
+#[ic_cdk::query]
+fn token_icrc1_fee(token: CanisterId) -> Nat {
+    icrc_fee(&icrc_token(&token))
+}
+
+// anyone can query
+#[ic_cdk::query]
+fn token_icrc1_metadata(token: CanisterId) -> Vec<(String, MetadataValue)> {
+    let token = icrc_token(&token);
+    vec![
+        MetadataValue::entry("icrc1:name", token.name.clone()),
+        MetadataValue::entry("icrc1:symbol", token.symbol.clone()),
+        MetadataValue::entry("icrc1:decimals", Nat::from(token.decimals)),
+        MetadataValue::entry("icrc1:fee", icrc_fee(&token)),
+    ]
+}
+
+// there is no minting account, tokens are minted by deposit and burned by withdraw
+#[ic_cdk::query]
+fn token_icrc1_minting_account(token: CanisterId) -> Option<Account> {
+    icrc_token(&token);
+    None
+}
+
+#[ic_cdk::query]
+fn token_icrc1_balance_of(token: CanisterId, account: Account) -> Nat {
+    ::common::utils::owner::check_owner_for_token_balance_of(&account.owner); // ! must be owner or self canister
+    icrc_token(&token);
+    with_state(|s| s.business_token_balance_of(token, account))
+}
+
+// check forbidden
+#[ic_cdk::update(guard = "has_business_token_transfer")]
+fn token_icrc1_transfer(token: CanisterId, arg: TransferArg) -> Result<Nat, TransferError> {
+    let from = Account {
+        owner: caller(),
+        subaccount: arg.from_subaccount,
+    };
+    inner_icrc_transfer(
+        token,
+        None,
+        from,
+        arg.to,
+        arg.amount,
+        arg.fee,
+        arg.memo.map(|memo| memo.0.into_vec()),
+        arg.created_at_time.map(TimestampNanos::from_inner),
+    )
+    .map_err(icrc1_transfer_error)
+}
+fn icrc1_transfer_error(err: BusinessError) -> TransferError {
+    match err {
+        BusinessError::InsufficientBalance { balance, .. } => TransferError::InsufficientFunds { balance },
+        BusinessError::BadTransferFee { expected_fee } => TransferError::BadFee { expected_fee },
+        BusinessError::InvalidCreated { .. } => TransferError::TooOld,
+        BusinessError::DuplicateTransaction { duplicate_of } => TransferError::Duplicate { duplicate_of },
+        BusinessError::TokenAccountsLocked(_)
+        | BusinessError::TokenBlockChainLocked
+        | BusinessError::SwapBlockChainLocked => TransferError::TemporarilyUnavailable,
+        err => TransferError::GenericError {
+            error_code: Nat::from(0_u8),
+            message: err.to_string(),
+        },
+    }
+}
+
+// ============================== icrc2 ==============================
+
+#[ic_cdk::query]
+fn token_icrc2_allowance(token: CanisterId, arg: AllowanceArgs) -> Allowance {
+    let owner = caller();
+    if owner != arg.account.owner && owner != arg.spender.owner && owner != self_canister_id() {
+        ic_cdk::trap("You can only query your own allowance")
+    }
+    let allowance =
+        with_state(|s| s.business_token_allowance_get(&token, &arg.account, &arg.spender, TimestampNanos::now()));
+    Allowance {
+        allowance: allowance.allowance,
+        expires_at: allowance.expires_at,
+    }
+}
+
+// check forbidden
+#[ic_cdk::update(guard = "has_business_token_transfer")]
+fn token_icrc2_approve(token: CanisterId, arg: ApproveArgs) -> Result<Nat, ApproveError> {
+    inner_icrc_approve(token, arg).map_err(icrc2_approve_error)
+}
+fn icrc2_approve_error(err: BusinessError) -> ApproveError {
+    match err {
+        BusinessError::BadTransferFee { expected_fee } => ApproveError::BadFee { expected_fee },
+        BusinessError::AllowanceChanged { allowance, .. } => ApproveError::AllowanceChanged {
+            current_allowance: allowance,
+        },
+        BusinessError::Expired { system, .. } => ApproveError::Expired { ledger_time: system },
+        BusinessError::InvalidCreated { .. } => ApproveError::TooOld,
+        BusinessError::DuplicateTransaction { duplicate_of } => ApproveError::Duplicate { duplicate_of },
+        BusinessError::TokenAccountsLocked(_) | BusinessError::TokenBlockChainLocked => {
+            ApproveError::TemporarilyUnavailable
+        }
+        err => ApproveError::GenericError {
+            error_code: Nat::from(0_u8),
+            message: err.to_string(),
+        },
+    }
+}
+fn inner_icrc_approve(token: CanisterId, arg: ApproveArgs) -> Result<Nat, BusinessError> {
+    // ! refuse all action about frozen token
+    with_state(|s| s.business_token_alive(&token))?;
+    icrc_token(&token);
+
+    let caller = Caller::get();
+    let from = Account {
+        owner: caller.id(),
+        subaccount: arg.from_subaccount,
+    };
+    if arg.spender.owner == from.owner {
+        return Err(BusinessError::Swap("SPENDER_CAN_NOT_BE_OWNER".into()));
+    }
+
+    // approve is free
+    if arg.fee.as_ref().is_some_and(|fee| *fee != 0_u8) {
+        return Err(BusinessError::BadTransferFee {
+            expected_fee: Nat::from(0_u8),
+        });
+    }
+
+    let memo = arg.memo.map(|memo| memo.0.into_vec());
+    let created = arg.created_at_time.map(TimestampNanos::from_inner);
+    let now = check_meta(&memo, &created)?;
+    let dedup = created
+        .map(|created| {
+            let transaction = (
+                "approve",
+                token,
+                from,
+                arg.spender,
+                &arg.amount,
+                &arg.expected_allowance,
+                arg.expires_at,
+                &arg.fee,
+                &memo,
+                created,
+            );
+            icrc_transaction_hash(&transaction).map(|hash| (hash, created))
+        })
+        .transpose()?;
+    check_icrc_duplicate(&dedup)?;
+
+    // lock without retry, the caller is told to try again
+    let locks =
+        match super::super::lock_token_block_chain_and_token_balances(vec![], vec![TokenAccount::new(token, from)], 0)?
+        {
+            LockResult::Locked(locks) => locks,
+            LockResult::Retry(_) => unreachable!(),
+        };
+
+    let block_index = with_mut_state(|s| {
+        let block_index = s.business_token_approve(
+            &locks,
+            ArgWithMeta {
+                now,
+                caller,
+                arg: ApproveToken {
+                    token,
+                    from,
+                    amount: arg.amount,
+                    spender: arg.spender,
+                    expected_allowance: arg.expected_allowance,
+                    expires_at: arg.expires_at,
+                },
+                memo,
+                created,
+            },
+        )?;
+        if let Some((hash, created)) = dedup {
+            s.business_token_icrc_transaction_insert(hash, created, block_index, now);
+        }
+        Ok::<_, BusinessError>(block_index)
+    })?;
+
+    // Asynchronously triggers synchronization tasks
+    crate::business::config::push::inner_push_blocks(true, false);
+
+    Ok(Nat::from(block_index))
+}
+
+// check forbidden
+#[ic_cdk::update(guard = "has_business_token_transfer")]
+fn token_icrc2_transfer_from(token: CanisterId, arg: TransferFromArgs) -> Result<Nat, TransferFromError> {
+    let spender = Account {
+        owner: caller(),
+        subaccount: arg.spender_subaccount,
+    };
+    inner_icrc_transfer(
+        token,
+        Some(spender),
+        arg.from,
+        arg.to,
+        arg.amount,
+        arg.fee,
+        arg.memo.map(|memo| memo.0.into_vec()),
+        arg.created_at_time.map(TimestampNanos::from_inner),
+    )
+    .map_err(icrc2_transfer_from_error)
+}
+fn icrc2_transfer_from_error(err: BusinessError) -> TransferFromError {
+    match err {
+        BusinessError::InsufficientBalance { balance, .. } => TransferFromError::InsufficientFunds { balance },
+        BusinessError::InsufficientAllowance { allowance, .. } => {
+            TransferFromError::InsufficientAllowance { allowance }
+        }
+        BusinessError::BadTransferFee { expected_fee } => TransferFromError::BadFee { expected_fee },
+        BusinessError::InvalidCreated { .. } => TransferFromError::TooOld,
+        BusinessError::DuplicateTransaction { duplicate_of } => TransferFromError::Duplicate { duplicate_of },
+        BusinessError::TokenAccountsLocked(_)
+        | BusinessError::TokenBlockChainLocked
+        | BusinessError::SwapBlockChainLocked => TransferFromError::TemporarilyUnavailable,
+        err => TransferFromError::GenericError {
+            error_code: Nat::from(0_u8),
+            message: err.to_string(),
+        },
+    }
+}
+
+// transfer by owner, or by spender with allowance of owner
+#[allow(clippy::too_many_arguments)]
+fn inner_icrc_transfer(
+    token: CanisterId,
+    spender: Option<Account>,
+    from: Account,
+    to: Account,
+    amount: Nat,
+    fee: Option<Nat>,
+    memo: Option<Vec<u8>>,
+    created: Option<TimestampNanos>,
+) -> Result<Nat, BusinessError> {
+    // ! refuse all action about frozen token
+    with_state(|s| s.business_token_alive(&token))?;
+    let token = icrc_token(&token);
+
+    let caller = Caller::get();
+    if to.owner == self_canister_id() {
+        return Err(BusinessError::Swap("TO_ACCOUNT_CAN_NOT_BE_SWAP_CANISTER".into()));
+    }
+    if from == to {
+        return Err(BusinessError::Swap("TO_ACCOUNT_CAN_NOT_BE_FROM_ACCOUNT".into())); // other subaccounts of owner are fine
+    }
+
+    // check meta, the duplicate is told before balance and allowance
+    let now = check_meta(&memo, &created)?;
+    let dedup = created
+        .map(|created| {
+            let transaction = (
+                "transfer",
+                token.canister_id,
+                spender,
+                from,
+                to,
+                &amount,
+                &fee,
+                &memo,
+                created,
+            );
+            icrc_transaction_hash(&transaction).map(|hash| (hash, created))
+        })
+        .transpose()?;
+    check_icrc_duplicate(&dedup)?;
+
+    // check fee
+    let (balance, fee_to) = with_state(|s| s.business_token_balance_of_with_fee_to(token.canister_id, from));
+    let fee_to = caller.fee_to(fee_to, to); // ! check token fee to required or not
+    let expected_fee = fee_to.map(|_| token.fee.clone()).unwrap_or_default();
+    if fee.is_some_and(|fee| fee != expected_fee) {
+        return Err(BusinessError::BadTransferFee { expected_fee });
+    }
+
+    // check balance
+    let changed = amount.clone() + expected_fee;
+    if balance < changed {
+        return Err(BusinessError::insufficient_balance(token.canister_id, balance));
+    }
+
+    // check allowance
+    if let Some(spender) = &spender {
+        with_state(|s| s.business_token_allowance_check_spend(&token.canister_id, &from, spender, &changed, now))?;
+    }
+
+    let fee_tokens = vec![token.canister_id]; // ! There is a handling fee for this operation
+    let required = vec![
+        TokenAccount::new(token.canister_id, from),
+        TokenAccount::new(token.canister_id, to),
+    ];
+    let arg = ArgWithMeta {
+        now,
+        caller,
+        arg: TransferToken {
+            token: token.canister_id,
+            from,
+            amount,
+            to,
+            fee: fee_to.map(|fee_to| TransferFee {
+                fee: token.fee.clone(),
+                fee_to,
+            }),
+            spender, // * who spent the allowance
+        },
+        memo,
+        created,
+    };
+
+    // lock without retry, the caller is told to try again
+    let block_index = if token.is_lp_token {
+        // ? LP token should produce 'swap transaction block'
+        let locks = match super::super::lock_token_block_chain_and_swap_block_chain_and_token_balances(
+            fee_tokens, required, 0,
+        )? {
+            LockResult::Locked(locks) => locks,
+            LockResult::Retry(_) => unreachable!(),
+        };
+        with_mut_state(|s| {
+            s.business_token_transfer_lp(&locks, arg)?;
+            if let Some(spender) = &spender {
+                s.business_token_allowance_spend(&token.canister_id, &from, spender, &changed);
+            }
+            let block_index = s.business_config_token_block_chain_query().next_block_index - 1;
+            if let Some((hash, created)) = dedup {
+                s.business_token_icrc_transaction_insert(hash, created, block_index, now);
+            }
+            Ok::<_, BusinessError>(block_index)
+        })?
+    } else {
+        let locks = match super::super::lock_token_block_chain_and_token_balances(fee_tokens, required, 0)? {
+            LockResult::Locked(locks) => locks,
+            LockResult::Retry(_) => unreachable!(),
+        };
+        with_mut_state(|s| {
+            s.business_token_transfer(&locks, arg)?;
+            if let Some(spender) = &spender {
+                s.business_token_allowance_spend(&token.canister_id, &from, spender, &changed);
+            }
+            let block_index = s.business_config_token_block_chain_query().next_block_index - 1;
+            if let Some((hash, created)) = dedup {
+                s.business_token_icrc_transaction_insert(hash, created, block_index, now);
+            }
+            Ok::<_, BusinessError>(block_index)
+        })?
+    };
+
+    // Asynchronously triggers synchronization tasks
+    crate::business::config::push::inner_push_blocks(true, token.is_lp_token);
+
+    Ok(Nat::from(block_index))
+}
+
+// ============================== icrc3 ==============================
+
+// anyone can query
+#[ic_cdk::query]
+fn token_icrc3_supported_block_types() -> Vec<SupportedBlockType> {
+    let icrc1 = "https://github.com/dfinity/ICRC-1/tree/main/standards/ICRC-1";
+    let icrc2 = "https://github.com/dfinity/ICRC-1/tree/main/standards/ICRC-2";
+    [
+        ("1mint", icrc1),
+        ("1burn", icrc1),
+        ("1xfer", icrc1),
+        ("2approve", icrc2),
+    ]
+    .into_iter()
+    .map(|(block_type, url)| SupportedBlockType {
+        block_type: block_type.into(),
+        url: url.into(),
+    })
+    .collect()
+}
+
+/// Blocks cached in this canister, archived blocks are got by `token_icrc3_get_blocks` of archive canisters
+#[ic_cdk::query(guard = "has_business_token_queryable")]
+fn token_icrc3_get_blocks(args: Vec<GetBlocksRequest>) -> GetBlocksResult {
+    let (log_length, archives) = with_state(|s| {
+        let block_chain = s.business_config_token_block_chain_query();
+        (block_chain.next_block_index, block_chain.archives())
+    });
+    let (cached, archived) = icrc3_split_requests(&archives, log_length, args);
+
+    let mut blocks = Vec::new();
+    for (start, end) in cached {
+        // cached blocks, at most MAX_BLOCKS_PER_REQUEST for each request
+        let got = with_state(|s| s.business_token_blocks_get(start));
+        for (block_height, block) in got.into_iter().take_while(|(block_height, _)| *block_height < end) {
+            if let QueryBlockResult::Block(block) = block {
+                let block: TokenBlock = trap(block.try_into());
+                blocks.push(BlockWithId {
+                    id: Nat::from(block_height),
+                    block: block.into(),
+                });
+            }
+        }
+    }
+
+    GetBlocksResult {
+        log_length: Nat::from(log_length),
+        blocks,
+        archived_blocks: archived
+            .into_iter()
+            .map(|(canister_id, args)| icrc_ledger_types::icrc3::blocks::ArchivedBlocks {
+                args,
+                callback: QueryArchiveFn::new(canister_id, "token_icrc3_get_blocks"),
+            })
+            .collect(),
+    }
+}
+
+// The ranges of blocks cached in this canister, and the requests of blocks told by each archive canister
+#[allow(clippy::type_complexity)]
+fn icrc3_split_requests(
+    archives: &[(CanisterId, u64, u64)],
+    log_length: u64,
+    args: Vec<GetBlocksRequest>,
+) -> (Vec<(u64, u64)>, Vec<(CanisterId, Vec<GetBlocksRequest>)>) {
+    let archived_end = archives
+        .iter()
+        .map(|(_, offset, length)| offset + length)
+        .max()
+        .unwrap_or_default();
+
+    let mut cached = Vec::new();
+    let mut archived: Vec<(CanisterId, Vec<GetBlocksRequest>)> = Vec::new();
+    for request in args {
+        let start = u64::try_from(&request.start.0).unwrap_or(u64::MAX);
+        let length = u64::try_from(&request.length.0).unwrap_or(u64::MAX);
+        let end = start.saturating_add(length).min(log_length);
+
+        // archived blocks are told by the callback of archive canister
+        for (canister_id, offset, length) in archives {
+            let (from, to) = (start.max(*offset), end.min(offset + length));
+            if to <= from {
+                continue;
+            }
+            let request = GetBlocksRequest {
+                start: Nat::from(from),
+                length: Nat::from(to - from),
+            };
+            match archived.iter_mut().find(|(id, _)| id == canister_id) {
+                Some((_, requests)) => requests.push(request),
+                None => archived.push((*canister_id, vec![request])),
+            }
+        }
+
+        let start = start.max(archived_end);
+        if start < end {
+            cached.push((start, end));
+        }
+    }
+    (cached, archived)
+}
+
+/// Archive canisters after the given one, the end of range is included
+#[ic_cdk::query(guard = "has_business_token_queryable")]
+fn token_icrc3_get_archives(args: GetArchivesArgs) -> Vec<ICRC3ArchiveInfo> {
+    let archives = with_state(|s| s.business_config_token_block_chain_query().archives());
+    icrc3_archives_after(archives, args)
+}
+fn icrc3_archives_after(archives: Vec<(CanisterId, u64, u64)>, args: GetArchivesArgs) -> Vec<ICRC3ArchiveInfo> {
+    let skip = args
+        .from
+        .and_then(|from| archives.iter().position(|(canister_id, ..)| *canister_id == from))
+        .map_or(0, |position| position + 1);
+    archives
+        .into_iter()
+        .skip(skip)
+        .filter(|(_, _, length)| 0 < *length)
+        .map(|(canister_id, offset, length)| ICRC3ArchiveInfo {
+            canister_id,
+            start: Nat::from(offset),
+            end: Nat::from(offset + length - 1),
+        })
+        .collect()
+}
+
+/// Certificate of the tips of the token and swap block chains, labeled by `last_token_block_hash` and so on.
+/// The hashes are of the internal block chains, so it is not an icrc3 tip certificate.
+#[ic_cdk::query(guard = "has_business_token_queryable")]
+fn token_block_chain_tip_certificate() -> Option<ICRC3DataCertificate> {
+    let certificate = ic_cdk::api::data_certificate()?;
+    let hash_tree = with_state(|s| s.business_certified_tree());
+    let hash_tree = trap(ic_canister_kit::functions::stable::to_bytes(&hash_tree));
+    Some(ICRC3DataCertificate {
+        certificate: serde_bytes::ByteBuf::from(certificate),
+        hash_tree: serde_bytes::ByteBuf::from(hash_tree),
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn canister(id: u8) -> CanisterId {
+        CanisterId::from_slice(&[id])
+    }
+    fn account(id: u8) -> Account {
+        Account {
+            owner: canister(id),
+            subaccount: None,
+        }
+    }
+    fn request(start: u64, length: u64) -> GetBlocksRequest {
+        GetBlocksRequest {
+            start: Nat::from(start),
+            length: Nat::from(length),
+        }
+    }
+
+    #[test]
+    fn test_icrc_transaction_hash() {
+        let transfer = |amount: u64, memo: Option<Vec<u8>>| {
+            let transaction = (
+                "transfer",
+                canister(1),
+                None::<Account>,
+                account(2),
+                account(3),
+                Nat::from(amount),
+                None::<Nat>,
+                memo,
+                TimestampNanos::from_inner(7),
+            );
+            icrc_transaction_hash(&transaction).unwrap()
+        };
+
+        // the same transaction is deduplicated
+        assert_eq!(transfer(100, None), transfer(100, None));
+        assert_eq!(transfer(100, None).len(), 64);
+        // other transactions are never taken as duplicate
+        assert_ne!(transfer(100, None), transfer(101, None));
+        assert_ne!(transfer(100, None), transfer(100, Some(vec![])));
+        assert_ne!(transfer(100, Some(vec![1])), transfer(100, Some(vec![2])));
+    }
+
+    #[test]
+    fn test_icrc1_transfer_error() {
+        assert_eq!(
+            icrc1_transfer_error(BusinessError::insufficient_balance(canister(1), Nat::from(5_u64))),
+            TransferError::InsufficientFunds {
+                balance: Nat::from(5_u64)
+            }
+        );
+        assert_eq!(
+            icrc1_transfer_error(BusinessError::BadTransferFee {
+                expected_fee: Nat::from(10_u64)
+            }),
+            TransferError::BadFee {
+                expected_fee: Nat::from(10_u64)
+            }
+        );
+        assert_eq!(
+            icrc1_transfer_error(BusinessError::DuplicateTransaction {
+                duplicate_of: Nat::from(3_u64)
+            }),
+            TransferError::Duplicate {
+                duplicate_of: Nat::from(3_u64)
+            }
+        );
+        assert_eq!(
+            icrc1_transfer_error(BusinessError::TokenBlockChainLocked),
+            TransferError::TemporarilyUnavailable
+        );
+        assert!(matches!(
+            icrc1_transfer_error(BusinessError::Swap("ANY".into())),
+            TransferError::GenericError { .. }
+        ));
+    }
+
+    #[test]
+    fn test_icrc2_errors() {
+        assert_eq!(
+            icrc2_transfer_from_error(BusinessError::InsufficientAllowance {
+                token: canister(1),
+                allowance: Nat::from(5_u64)
+            }),
+            TransferFromError::InsufficientAllowance {
+                allowance: Nat::from(5_u64)
+            }
+        );
+        assert_eq!(
+            icrc2_transfer_from_error(BusinessError::insufficient_balance(canister(1), Nat::from(6_u64))),
+            TransferFromError::InsufficientFunds {
+                balance: Nat::from(6_u64)
+            }
+        );
+        assert_eq!(
+            icrc2_approve_error(BusinessError::BadTransferFee {
+                expected_fee: Nat::from(0_u64)
+            }),
+            ApproveError::BadFee {
+                expected_fee: Nat::from(0_u64)
+            }
+        );
+        assert_eq!(
+            icrc2_approve_error(BusinessError::TokenAccountsLocked(vec![])),
+            ApproveError::TemporarilyUnavailable
+        );
+    }
+
+    #[test]
+    fn test_icrc3_split_requests() {
+        // [0, 10) in archive 1, [10, 15) in archive 2, [15, 20) cached
+        let archives = vec![(canister(1), 0, 10), (canister(2), 10, 5)];
+
+        let (cached, archived) = icrc3_split_requests(&archives, 20, vec![request(5, 12)]);
+        assert_eq!(cached, vec![(15, 17)]);
+        assert_eq!(archived.len(), 2);
+        assert_eq!(archived[0].0, canister(1));
+        assert_eq!(archived[0].1, vec![request(5, 5)]);
+        assert_eq!(archived[1].0, canister(2));
+        assert_eq!(archived[1].1, vec![request(10, 5)]);
+
+        // the end of request is bounded by log length
+        let (cached, archived) = icrc3_split_requests(&archives, 20, vec![request(18, 100), request(1, 2)]);
+        assert_eq!(cached, vec![(18, 20)]);
+        assert_eq!(archived, vec![(canister(1), vec![request(1, 2)])]);
+
+        // nothing out of the chain
+        let (cached, archived) = icrc3_split_requests(&archives, 20, vec![request(20, 5), request(u64::MAX, 1)]);
+        assert!(cached.is_empty());
+        assert!(archived.is_empty());
+    }
+
+    #[test]
+    fn test_icrc3_archives_after() {
+        let archives = vec![(canister(1), 0, 10), (canister(2), 10, 5), (canister(3), 15, 0)];
+
+        let all = icrc3_archives_after(archives.clone(), GetArchivesArgs { from: None });
+        assert_eq!(all.len(), 2); // the empty archive is not listed
+        assert_eq!(all[0].canister_id, canister(1));
+        assert_eq!(
+            (all[0].start.clone(), all[0].end.clone()),
+            (Nat::from(0_u64), Nat::from(9_u64))
+        );
+        assert_eq!(
+            (all[1].start.clone(), all[1].end.clone()),
+            (Nat::from(10_u64), Nat::from(14_u64))
+        );
+
+        let after = icrc3_archives_after(
+            archives.clone(),
+            GetArchivesArgs {
+                from: Some(canister(1)),
+            },
+        );
+        assert_eq!(after.len(), 1);
+        assert_eq!(after[0].canister_id, canister(2));
+
+        assert!(
+            icrc3_archives_after(
+                archives,
+                GetArchivesArgs {
+                    from: Some(canister(2))
+                }
+            )
+            .is_empty()
+        );
+    }
+}
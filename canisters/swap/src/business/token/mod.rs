@@ -13,3 +13,221 @@ pub mod deposit;
 pub mod withdraw;
 
 mod transfer;
+
+mod icrc;
+
+// ========================== fee changed by ledger ==========================
+
+// the fee told by ledger is recorded by swap canister
+fn update_ledger_fee(token: CanisterId, fee: candid::Nat) {
+    let (_, caller) = check_self_canister();
+    let arg = ArgWithMeta::simple(TimestampNanos::now(), caller, TokenFeeUpdateArg { token, fee });
+    with_mut_state(|s| s.business_config_token_fee_update(arg));
+}
+
+// Transfer with the fee, the fee told by ledger is recorded by `changed`.
+// Only when `retry`, transfer again once with the expected fee.
+// Return the height and the fee used.
+async fn transfer_with_expected_fee<H, E, Fut>(
+    fee: Option<candid::Nat>,
+    retry: bool,
+    bad_fee: impl Fn(&E) -> Option<candid::Nat>,
+    changed: impl Fn(candid::Nat),
+    transfer: impl Fn(Option<candid::Nat>) -> Fut,
+) -> Result<(H, Option<candid::Nat>), BusinessError>
+where
+    E: Into<BusinessError>,
+    Fut: std::future::Future<Output = Result<Result<H, E>, BusinessError>>,
+{
+    let err = match transfer(fee.clone()).await? {
+        Ok(height) => return Ok((height, fee)),
+        Err(err) => err,
+    };
+    let expected_fee = match bad_fee(&err) {
+        Some(expected_fee) => expected_fee,
+        None => return Err(err.into()),
+    };
+    changed(expected_fee.clone());
+    if !retry {
+        return Err(BusinessError::BadTransferFee { expected_fee });
+    }
+    ic_cdk::println!("🔄 retry transfer with expected fee: {expected_fee}");
+    let height = transfer(Some(expected_fee.clone())).await?.map_err(Into::into)?;
+    Ok((height, Some(expected_fee)))
+}
+
+/// Transfer by icrc1, the arg is made by fee.
+/// If the fee is changed by ledger, the fee of token is updated.
+/// Only when the caller does not give the fee, transfer again once with the expected fee.
+/// Return the height and the fee used.
+async fn icrc1_transfer_with_fee(
+    token: CanisterId,
+    fee: Option<candid::Nat>,
+    token_fee: candid::Nat,
+    make_arg: impl Fn(&candid::Nat) -> Result<crate::services::icrc2::TransferArg, BusinessError> + Sync,
+) -> Result<(candid::Nat, candid::Nat), BusinessError> {
+    use icrc_ledger_types::icrc1::transfer::TransferError;
+
+    let retry = fee.is_none();
+    let fee = fee.unwrap_or(token_fee);
+    let service_icrc2 = crate::services::icrc2::Service(token);
+    let (height, used) = transfer_with_expected_fee(
+        Some(fee.clone()),
+        retry,
+        |err| match err {
+            TransferError::BadFee { expected_fee } => Some(expected_fee.clone()),
+            _ => None,
+        },
+        |expected_fee| update_ledger_fee(token, expected_fee),
+        |fee| {
+            let arg = make_arg(&fee.unwrap_or_default());
+            let service_icrc2 = &service_icrc2;
+            async move { service_icrc2.icrc_1_transfer(arg?).await }
+        },
+    )
+    .await?;
+    Ok((height, used.unwrap_or(fee)))
+}
+
+/// Transfer by icrc2, if the fee is changed by ledger, the fee of token is updated.
+/// The deposit does not care the fee, so transfer again once with the expected fee.
+async fn icrc2_transfer_from_with_fee(
+    token: CanisterId,
+    arg: crate::services::icrc2::TransferFromArgs,
+) -> Result<candid::Nat, BusinessError> {
+    use icrc_ledger_types::icrc2::transfer_from::TransferFromError;
+
+    let service_icrc2 = crate::services::icrc2::Service(token);
+    let (height, _) = transfer_with_expected_fee(
+        arg.fee.clone(),
+        true,
+        |err| match err {
+            TransferFromError::BadFee { expected_fee } => Some(expected_fee.clone()),
+            _ => None,
+        },
+        |expected_fee| update_ledger_fee(token, expected_fee),
+        |fee| {
+            let arg = crate::services::icrc2::TransferFromArgs { fee, ..arg.clone() };
+            let service_icrc2 = &service_icrc2;
+            async move { service_icrc2.icrc_2_transfer_from(arg).await }
+        },
+    )
+    .await?;
+    Ok(height)
+}
+
+/// Transfer by legacy ICP ledger, the arg is made by fee.
+/// The same as icrc1, transfer again once with the expected fee only when the caller does not give the fee.
+/// Return the height and the fee used.
+async fn icp_transfer_with_fee(
+    token: CanisterId,
+    fee: Option<candid::Nat>,
+    token_fee: candid::Nat,
+    make_arg: impl Fn(&candid::Nat) -> Result<crate::services::icp::TransferArgs, BusinessError> + Sync,
+) -> Result<(candid::Nat, candid::Nat), BusinessError> {
+    use crate::services::icp::TransferError;
+
+    let retry = fee.is_none();
+    let fee = fee.unwrap_or(token_fee);
+    let service_icp = crate::services::icp::Service(token);
+    let (height, used) = transfer_with_expected_fee(
+        Some(fee.clone()),
+        retry,
+        |err| match err {
+            TransferError::BadFee { expected_fee } => Some(candid::Nat::from(expected_fee.e8s)),
+            _ => None,
+        },
+        |expected_fee| update_ledger_fee(token, expected_fee),
+        |fee| {
+            let arg = make_arg(&fee.unwrap_or_default());
+            let service_icp = &service_icp;
+            async move { service_icp.transfer(arg?).await }
+        },
+    )
+    .await?;
+    Ok((height.into(), used.unwrap_or(fee)))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use icrc_ledger_types::icrc1::transfer::TransferError;
+    use std::cell::RefCell;
+
+    type Transferred = Result<(candid::Nat, Option<candid::Nat>), BusinessError>;
+
+    // the ledger answers each transfer in order, the fee of each transfer is recorded
+    fn transfer(
+        fee: Option<u64>,
+        retry: bool,
+        answers: Vec<Result<u64, TransferError>>,
+    ) -> (Transferred, Vec<Option<candid::Nat>>, Vec<candid::Nat>) {
+        let answers = RefCell::new(answers.into_iter());
+        let sent = RefCell::new(vec![]);
+        let changed = RefCell::new(vec![]);
+        let result = futures::executor::block_on(transfer_with_expected_fee(
+            fee.map(candid::Nat::from),
+            retry,
+            |err| match err {
+                TransferError::BadFee { expected_fee } => Some(expected_fee.clone()),
+                _ => None,
+            },
+            |expected_fee| changed.borrow_mut().push(expected_fee),
+            |fee| {
+                sent.borrow_mut().push(fee);
+                let answer = answers.borrow_mut().next().unwrap().map(candid::Nat::from);
+                async move { Ok(answer) }
+            },
+        ));
+        (result, sent.into_inner(), changed.into_inner())
+    }
+    fn bad_fee(expected_fee: u64) -> TransferError {
+        TransferError::BadFee {
+            expected_fee: candid::Nat::from(expected_fee),
+        }
+    }
+    fn nat(n: u64) -> candid::Nat {
+        candid::Nat::from(n)
+    }
+
+    #[test]
+    fn test_transfer_with_expected_fee() {
+        // done at once
+        let (result, sent, changed) = transfer(Some(10), true, vec![Ok(1)]);
+        assert_eq!(result.unwrap(), (nat(1), Some(nat(10))));
+        assert_eq!(sent, vec![Some(nat(10))]);
+        assert!(changed.is_empty());
+
+        // the fee is changed by ledger, transfer again with the expected fee
+        let (result, sent, changed) = transfer(Some(10), true, vec![Err(bad_fee(20)), Ok(2)]);
+        assert_eq!(result.unwrap(), (nat(2), Some(nat(20))));
+        assert_eq!(sent, vec![Some(nat(10)), Some(nat(20))]);
+        assert_eq!(changed, vec![nat(20)]);
+
+        // the fee given by caller is not retried, but the fee of token is updated
+        let (result, sent, changed) = transfer(Some(10), false, vec![Err(bad_fee(20))]);
+        assert!(matches!(result, Err(BusinessError::BadTransferFee { expected_fee }) if expected_fee == nat(20)));
+        assert_eq!(sent, vec![Some(nat(10))]);
+        assert_eq!(changed, vec![nat(20)]);
+
+        // only retried once
+        let (result, sent, changed) = transfer(Some(10), true, vec![Err(bad_fee(20)), Err(bad_fee(30))]);
+        assert!(result.is_err());
+        assert_eq!(sent.len(), 2);
+        assert_eq!(changed, vec![nat(20)]);
+
+        // other errors are never retried
+        let (result, sent, changed) = transfer(
+            None,
+            true,
+            vec![Err(TransferError::InsufficientFunds { balance: nat(5) })],
+        );
+        assert!(matches!(
+            result,
+            Err(BusinessError::TransferError(TransferError::InsufficientFunds { .. }))
+        ));
+        assert_eq!(sent, vec![None]);
+        assert!(changed.is_empty());
+    }
+}
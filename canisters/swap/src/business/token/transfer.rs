@@ -100,6 +100,7 @@ async fn inner_token_transfer(args: TokenTransferArgs, retries: Option<u8>) -> R
                             amount: args.transfer_amount_without_fee,
                             to: args.to,
                             fee: fee_to.map(|fee_to| TransferFee { fee: token.fee, fee_to }),
+                            spender: None,
                         },
                         memo: args.memo,
                         created: args.created,
@@ -135,6 +136,7 @@ async fn inner_token_transfer(args: TokenTransferArgs, retries: Option<u8>) -> R
                             amount: args.transfer_amount_without_fee,
                             to: args.to,
                             fee: fee_to.map(|fee_to| TransferFee { fee: token.fee, fee_to }),
+                            spender: None,
                         },
                         memo: args.memo,
                         created: args.created,
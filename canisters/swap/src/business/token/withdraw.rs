@@ -8,6 +8,8 @@ use crate::types::*;
 
 pub mod many;
 
+mod account_id;
+
 // ========================== withdraw ==========================
 
 // withdraw
@@ -18,11 +20,19 @@ impl CheckArgs for TokenWithdrawArgs {
         // ! refuse all action about frozen token
         with_state(|s| s.business_token_alive(&self.token))?;
 
-        // ! must be token, can not be dummy lp token
+        // ! must be token or outer lp token, can not be dummy lp token
         let token = with_state(|s| {
             s.business_tokens_query()
                 .get(&self.token)
                 .map(|t| t.clone().into_owned())
+                .or_else(|| {
+                    s.business_outer_lp_tokens_query()
+                        .remove(&self.token)
+                        .map(|lp| TokenInfo {
+                            fee: ::common::utils::math::zero(), // minted by swap canister without fee
+                            ..lp
+                        })
+                })
         })
         .ok_or(BusinessError::NotSupportedToken(self.token))?;
 
@@ -58,6 +68,12 @@ impl CheckArgs for TokenWithdrawArgs {
     }
 }
 
+// the fee of withdraw paid by owner, outer lp token is minted without fee
+fn withdraw_fee(token: &CanisterId) -> candid::Nat {
+    with_state(|s| s.business_tokens_query().get(token).map(|t| t.fee.clone()))
+        .unwrap_or_else(::common::utils::math::zero)
+}
+
 // check forbidden
 #[ic_cdk::update(guard = "has_business_token_withdraw")]
 async fn token_withdraw(args: TokenWithdrawArgs, retries: Option<u8>) -> TokenChangedResult {
@@ -69,6 +85,14 @@ pub async fn inner_token_withdraw(
     retries: Option<u8>,
     push: bool,
 ) -> Result<candid::Nat, BusinessError> {
+    // 0. approved spender, the swap canister withdraws on behalf of owner
+    let amount = args.withdraw_amount_without_fee.clone() + withdraw_fee(&args.token); // ! the fee is paid by owner too
+    if let Some(spending) = spend_allowance(&args.from, vec![(args.token, amount.clone())])? {
+        let service_swap = crate::services::swap::Service(self_canister_id());
+        let result = service_swap.token_withdraw(args, retries).await;
+        return spending.settle(result, |_| vec![amount]);
+    }
+
     // 1. check args
     let (now, self_canister, caller, token) = args.check_args()?;
 
@@ -92,26 +116,32 @@ pub async fn inner_token_withdraw(
 
         // * 4. do business
         {
-            let service_icrc2 = crate::services::icrc2::Service(args.token);
-
             // ? 1. transfer token to user
-            let fee = args.fee.unwrap_or(token.fee.clone());
-            let transfer_arg = crate::services::icrc2::TransferArg {
-                from_subaccount: None,
-                to: args.to,
-                amount: args.withdraw_amount_without_fee.clone(),
-                fee: Some(fee.clone()), // withdraw action should care fee
-                memo: None,
-                created_at_time: None,
-            };
-            ic_cdk::println!(
-                "*CallIcrc1Transfer* `token:[{}], to:({}), amount:{}, fee:{}`",
-                args.token.to_string(),
-                display_account(&transfer_arg.to),
-                transfer_arg.amount.to_string(),
-                token.fee.to_string()
-            );
-            let height = service_icrc2.icrc_1_transfer(transfer_arg).await??;
+            let (height, fee) =
+                super::icrc1_transfer_with_fee(args.token, args.fee.clone(), token.fee.clone(), |fee| {
+                    // ! the fee changed by ledger must be paid by balance
+                    let balance = with_state(|s| s.business_token_balance_of(args.token, args.from));
+                    if balance < args.withdraw_amount_without_fee.clone() + fee.clone() {
+                        return Err(BusinessError::insufficient_balance(args.token, balance));
+                    }
+                    let transfer_arg = crate::services::icrc2::TransferArg {
+                        from_subaccount: None,
+                        to: args.to,
+                        amount: args.withdraw_amount_without_fee.clone(),
+                        fee: Some(fee.clone()), // withdraw action should care fee
+                        memo: None,
+                        created_at_time: None,
+                    };
+                    ic_cdk::println!(
+                        "*CallIcrc1Transfer* `token:[{}], to:({}), amount:{}, fee:{}`",
+                        args.token.to_string(),
+                        display_account(&transfer_arg.to),
+                        transfer_arg.amount.to_string(),
+                        fee.to_string()
+                    );
+                    Ok(transfer_arg)
+                })
+                .await?;
 
             // ? 2. record changed
             let amount = args.withdraw_amount_without_fee + fee; // Total withdrawal
@@ -126,6 +156,7 @@ pub async fn inner_token_withdraw(
                             from: args.from,
                             amount, // include fee
                             to: args.to,
+                            to_account_id: None,
                         },
                         memo: args.memo,
                         created: args.created,
@@ -0,0 +1,182 @@
+#[allow(unused)]
+use ic_canister_kit::identity::caller;
+
+#[allow(unused)]
+use crate::stable::*;
+#[allow(unused)]
+use crate::types::*;
+
+use ::common::types::AccountIdentifier;
+
+// ========================== withdraw to account identifier ==========================
+
+// withdraw ICP to legacy account identifier
+
+impl CheckArgs for TokenWithdrawToAccountIdArgs {
+    type Result = (TimestampNanos, SelfCanister, Caller, TokenInfo);
+    fn check_args(&self) -> Result<Self::Result, BusinessError> {
+        // ! refuse all action about frozen token
+        with_state(|s| s.business_token_alive(&self.token))?;
+
+        // ! only ICP ledger supports account identifier
+        if self.token.to_text() != ICP_LEDGER_CANISTER_ID {
+            return Err(BusinessError::NotSupportedToken(self.token));
+        }
+        let token = with_state(|s| {
+            s.business_tokens_query()
+                .get(&self.token)
+                .map(|t| t.clone().into_owned())
+        })
+        .ok_or(BusinessError::NotSupportedToken(self.token))?;
+
+        // check owner
+        let (self_canister, caller) = check_caller(&self.from.owner)?;
+
+        // check to
+        if !self.to.is_valid() {
+            return Err(BusinessError::Swap("INVALID_ACCOUNT_IDENTIFIER".into()));
+        }
+        let self_account_id = AccountIdentifier::new(&Account {
+            owner: self_canister.id(),
+            subaccount: None,
+        });
+        assert!(self.to != self_account_id, "to account can not be swap canister");
+
+        // check fee
+        if self
+            .fee
+            .as_ref()
+            .is_some_and(|fee| *fee != *::common::utils::math::ZERO && *fee != token.fee)
+        {
+            return Err(BusinessError::BadTransferFee {
+                expected_fee: token.fee,
+            });
+        }
+
+        // check balance
+        let balance = with_state(|s| s.business_token_balance_of(self.token, self.from));
+        let amount = self.withdraw_amount_without_fee.clone() + token.fee.clone();
+        if balance < amount {
+            return Err(BusinessError::insufficient_balance(self.token, balance));
+        }
+
+        // check meta
+        let now = check_meta(&self.memo, &self.created)?;
+
+        Ok((now, self_canister, caller, token))
+    }
+}
+
+// check forbidden
+#[ic_cdk::update(guard = "has_business_token_withdraw")]
+async fn token_withdraw_to_account_id(args: TokenWithdrawToAccountIdArgs, retries: Option<u8>) -> TokenChangedResult {
+    inner_token_withdraw_to_account_id(args, retries).await.into()
+}
+#[inline]
+async fn inner_token_withdraw_to_account_id(
+    args: TokenWithdrawToAccountIdArgs,
+    retries: Option<u8>,
+) -> Result<candid::Nat, BusinessError> {
+    // 0. approved spender, the swap canister withdraws on behalf of owner
+    let amount = args.withdraw_amount_without_fee.clone() + super::withdraw_fee(&args.token); // ! the fee is paid by owner too
+    if let Some(spending) = spend_allowance(&args.from, vec![(args.token, amount.clone())])? {
+        let service_swap = crate::services::swap::Service(self_canister_id());
+        let result = service_swap.token_withdraw_to_account_id(args, retries).await;
+        return spending.settle(result, |_| vec![amount]);
+    }
+
+    // 1. check args
+    let (now, self_canister, caller, token) = args.check_args()?;
+
+    // 2. some value
+    let fee_tokens = vec![];
+    let token_account_from = TokenAccount::new(args.token, args.from);
+    let required = vec![token_account_from];
+
+    let height = {
+        // 3. lock
+        let locks = match super::super::super::lock_token_block_chain_and_token_balances(
+            fee_tokens,
+            required,
+            retries.unwrap_or_default(),
+        )? {
+            LockResult::Locked(locks) => locks,
+            LockResult::Retry(retries) => {
+                return retry_token_withdraw_to_account_id(self_canister.id(), args, retries).await;
+            }
+        };
+
+        // * 4. do business
+        {
+            // ? 1. transfer token to account identifier
+            let e8s = |amount: &candid::Nat| {
+                u64::try_from(&amount.0).map_err(|_| BusinessError::Swap("AMOUNT_TOO_LARGE".into()))
+            };
+            let (height, fee) =
+                super::super::icp_transfer_with_fee(args.token, args.fee.clone(), token.fee.clone(), |fee| {
+                    // ! the fee changed by ledger must be paid by balance
+                    let balance = with_state(|s| s.business_token_balance_of(args.token, args.from));
+                    if balance < args.withdraw_amount_without_fee.clone() + fee.clone() {
+                        return Err(BusinessError::insufficient_balance(args.token, balance));
+                    }
+                    let transfer_arg = crate::services::icp::TransferArgs {
+                        to: serde_bytes::ByteBuf::from(args.to.as_bytes().to_vec()),
+                        fee: crate::services::icp::Tokens { e8s: e8s(fee)? }, // withdraw action should care fee
+                        memo: 0,
+                        from_subaccount: None,
+                        created_at_time: None,
+                        amount: crate::services::icp::Tokens {
+                            e8s: e8s(&args.withdraw_amount_without_fee)?,
+                        },
+                    };
+                    ic_cdk::println!(
+                        "*CallIcpTransfer* `token:[{}], to:{}, amount:{}, fee:{}`",
+                        args.token.to_string(),
+                        args.to,
+                        transfer_arg.amount.e8s,
+                        fee.to_string()
+                    );
+                    Ok(transfer_arg)
+                })
+                .await?;
+
+            // ? 2. record changed
+            let amount = args.withdraw_amount_without_fee + fee; // Total withdrawal
+            with_mut_state(|s| {
+                s.business_token_withdraw(
+                    &locks,
+                    ArgWithMeta {
+                        now,
+                        caller,
+                        arg: WithdrawToken {
+                            token: args.token,
+                            from: args.from,
+                            amount, // include fee
+                            to: args.from,
+                            to_account_id: Some(args.to),
+                        },
+                        memo: args.memo,
+                        created: args.created,
+                    },
+                    height,
+                )
+            })?
+        }
+    };
+
+    // Asynchronously triggers synchronization tasks
+    crate::business::config::push::inner_push_blocks(true, false);
+
+    Ok(height)
+}
+// ! This implicitly contains self_canister_id, which can be called again through permission checks and replaces caller.
+#[inline]
+async fn retry_token_withdraw_to_account_id(
+    self_canister_id: CanisterId,
+    args: TokenWithdrawToAccountIdArgs,
+    retries: u8,
+) -> Result<candid::Nat, BusinessError> {
+    ic_cdk::println!("🔄 retry_token_withdraw_to_account_id: {}", retries);
+    let service_swap = crate::services::swap::Service(self_canister_id);
+    return service_swap.token_withdraw_to_account_id(args, Some(retries)).await;
+}
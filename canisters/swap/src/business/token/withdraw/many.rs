@@ -83,26 +83,32 @@ pub async fn inner_token_withdraw_many(
             .map(|(args, (now, _self_canister, caller, token))| async move {
                 // * 4. do business
                 {
-                    let service_icrc2 = crate::services::icrc2::Service(args.token);
-
                     // ? 1. transfer token to user
-                    let fee = args.fee.unwrap_or(token.fee.clone());
-                    let transfer_arg = crate::services::icrc2::TransferArg {
-                        from_subaccount: None,
-                        to: args.to,
-                        amount: args.withdraw_amount_without_fee.clone(),
-                        fee: Some(fee.clone()), // withdraw action should care fee
-                        memo: None,
-                        created_at_time: None,
-                    };
-                    ic_cdk::println!(
-                        "*CallIcrc1Transfer* `token:[{}], to:({}), amount:{}, fee:{}`",
-                        args.token.to_string(),
-                        display_account(&transfer_arg.to),
-                        transfer_arg.amount.to_string(),
-                        token.fee.to_string()
-                    );
-                    let height = service_icrc2.icrc_1_transfer(transfer_arg).await??;
+                    let (height, fee) =
+                        super::super::icrc1_transfer_with_fee(args.token, args.fee.clone(), token.fee.clone(), |fee| {
+                            // ! the fee changed by ledger must be paid by balance
+                            let balance = with_state(|s| s.business_token_balance_of(args.token, args.from));
+                            if balance < args.withdraw_amount_without_fee.clone() + fee.clone() {
+                                return Err(BusinessError::insufficient_balance(args.token, balance));
+                            }
+                            let transfer_arg = crate::services::icrc2::TransferArg {
+                                from_subaccount: None,
+                                to: args.to,
+                                amount: args.withdraw_amount_without_fee.clone(),
+                                fee: Some(fee.clone()), // withdraw action should care fee
+                                memo: None,
+                                created_at_time: None,
+                            };
+                            ic_cdk::println!(
+                                "*CallIcrc1Transfer* `token:[{}], to:({}), amount:{}, fee:{}`",
+                                args.token.to_string(),
+                                display_account(&transfer_arg.to),
+                                transfer_arg.amount.to_string(),
+                                fee.to_string()
+                            );
+                            Ok(transfer_arg)
+                        })
+                        .await?;
 
                     // ? 2. record changed
                     let amount = args.withdraw_amount_without_fee + fee; // Total withdrawal
@@ -117,6 +123,7 @@ pub async fn inner_token_withdraw_many(
                                     from: args.from,
                                     amount,
                                     to: args.to,
+                                    to_account_id: None,
                                 },
                                 memo: args.memo,
                                 created: args.created,
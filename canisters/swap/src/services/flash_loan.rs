@@ -0,0 +1,21 @@
+#![allow(dead_code, unused_imports)]
+use candid::{self, CandidType, Decode, Deserialize, Encode, Nat, Principal};
+
+use crate::types::{BusinessError, TokenPairFlashLoanCallbackArgs};
+
+type CallResult<T> = Result<T, BusinessError>;
+
+/// The borrower must reply in time, the loan is settled no matter what
+pub const CALLBACK_TIMEOUT_SECONDS: u32 = 60;
+
+pub struct Service(pub Principal);
+impl Service {
+    /// The result of callback is ignored, only the repayment matters
+    pub async fn callback(&self, method: &str, args: TokenPairFlashLoanCallbackArgs) -> CallResult<()> {
+        ic_cdk::call::Call::bounded_wait(self.0, method)
+            .change_timeout(CALLBACK_TIMEOUT_SECONDS)
+            .with_arg(args)
+            .await?;
+        Ok(())
+    }
+}
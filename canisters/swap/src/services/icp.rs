@@ -0,0 +1,68 @@
+// This is an experimental feature to generate Rust binding from Candid.
+// You may want to manually adjust some of the types.
+#![allow(dead_code, unused_imports)]
+use candid::{self, CandidType, Decode, Deserialize, Encode, Principal};
+use common::types::BusinessError;
+
+#[derive(CandidType, Deserialize)]
+pub struct Tokens {
+    pub e8s: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct TimeStamp {
+    pub timestamp_nanos: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct TransferArgs {
+    pub to: serde_bytes::ByteBuf,
+    pub fee: Tokens,
+    pub memo: u64,
+    pub from_subaccount: Option<serde_bytes::ByteBuf>,
+    pub created_at_time: Option<TimeStamp>,
+    pub amount: Tokens,
+}
+
+#[derive(CandidType, Deserialize)]
+pub enum TransferError {
+    TxTooOld { allowed_window_nanos: u64 },
+    BadFee { expected_fee: Tokens },
+    TxDuplicate { duplicate_of: u64 },
+    TxCreatedInFuture,
+    InsufficientFunds { balance: Tokens },
+}
+
+impl From<TransferError> for BusinessError {
+    fn from(value: TransferError) -> Self {
+        use icrc_ledger_types::icrc1::transfer::TransferError as Icrc1TransferError;
+        let error = match value {
+            TransferError::TxTooOld { .. } => Icrc1TransferError::TooOld,
+            TransferError::BadFee { expected_fee } => Icrc1TransferError::BadFee {
+                expected_fee: expected_fee.e8s.into(),
+            },
+            TransferError::TxDuplicate { duplicate_of } => Icrc1TransferError::Duplicate {
+                duplicate_of: duplicate_of.into(),
+            },
+            TransferError::TxCreatedInFuture => Icrc1TransferError::CreatedInFuture { ledger_time: 0 },
+            TransferError::InsufficientFunds { balance } => Icrc1TransferError::InsufficientFunds {
+                balance: balance.e8s.into(),
+            },
+        };
+        BusinessError::TransferError(error)
+    }
+}
+
+pub type Result_ = Result<u64, TransferError>;
+
+type CallResult<T> = Result<T, BusinessError>;
+
+pub struct Service(pub Principal);
+impl Service {
+    pub async fn transfer(&self, arg0: TransferArgs) -> CallResult<Result_> {
+        Ok(ic_cdk::call::Call::unbounded_wait(self.0, "transfer")
+            .with_arg(arg0)
+            .await?
+            .candid()?)
+    }
+}
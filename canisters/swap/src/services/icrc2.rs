@@ -339,7 +339,7 @@ pub enum Result2 {
     Err(ApproveError),
 }
 
-#[derive(Debug, CandidType, Deserialize)]
+#[derive(Debug, Clone, CandidType, Deserialize)]
 pub struct TransferFromArgs {
     pub to: Account,
     pub fee: Option<candid::Nat>,
@@ -4,3 +4,9 @@ pub mod swap;
 pub mod archive;
 
 pub mod icrc2;
+
+// legacy ledger of ICP
+pub mod icp;
+
+// borrower canister of flash loan
+pub mod flash_loan;
@@ -4,11 +4,16 @@
 use candid::{self, CandidType, Decode, Deserialize, Encode, Nat, Principal};
 
 use crate::types::{
-    BusinessError, ManyTokenChangedResult, TokenChangedResult, TokenDepositArgs, TokenPairLiquidityAddArgs,
+    Account, BusinessError, DcaOrder, DcaOrderPlaceArgs, Farm, FarmCreateArgs, FarmStakeArgs, FlashLoan, LimitOrder,
+    LimitOrderPlaceArgs, ManyTokenChangedResult, ProtocolFeesCollectArgs, ProtocolFeesCollectSuccess,
+    TokenChangedResult, TokenDepositArgs, TokenPairFlashLoanArgs, TokenPairLiquidityAddArgs,
     TokenPairLiquidityAddResult, TokenPairLiquidityAddSuccess, TokenPairLiquidityRemoveArgs,
     TokenPairLiquidityRemoveResult, TokenPairLiquidityRemoveSuccess, TokenPairSwapByLoanArgs,
-    TokenPairSwapExactTokensForTokensArgs, TokenPairSwapTokensForExactTokensArgs, TokenPairSwapTokensResult,
-    TokenPairSwapTokensSuccess, TokenTransferArgs, TokenWithdrawArgs, TokenWithdrawManyArgs,
+    TokenPairSwapExactTokensForTokensArgs, TokenPairSwapSplitArgs, TokenPairSwapSplitSuccess,
+    TokenPairSwapTokensForExactTokensArgs, TokenPairSwapTokensResult, TokenPairSwapTokensSuccess,
+    TokenPoolLiquidityAddArgs, TokenPoolLiquidityAddSuccess, TokenPoolLiquidityRemoveArgs,
+    TokenPoolLiquidityRemoveSuccess, TokenTransferArgs, TokenWithdrawArgs, TokenWithdrawManyArgs,
+    TokenWithdrawToAccountIdArgs,
 };
 
 type CallResult<T> = Result<T, BusinessError>;
@@ -17,32 +22,59 @@ pub struct Service(pub Principal);
 impl Service {
     // token
     pub async fn token_deposit(&self, args: TokenDepositArgs, retries: Option<u8>) -> CallResult<Nat> {
-        Ok(ic_cdk::call::Call::unbounded_wait(self.0, "token_deposit")
+        ic_cdk::call::Call::unbounded_wait(self.0, "token_deposit")
             .with_args(&(args, retries))
-            .await?
-            .candid()?)
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
+    }
+    pub async fn token_deposit_notify(
+        &self,
+        token: Principal,
+        account: Account,
+        retries: Option<u8>,
+    ) -> CallResult<Nat> {
+        ic_cdk::call::Call::unbounded_wait(self.0, "token_deposit_notify")
+            .with_args(&(token, account, retries))
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
+    }
+    pub async fn token_withdraw_to_account_id(
+        &self,
+        args: TokenWithdrawToAccountIdArgs,
+        retries: Option<u8>,
+    ) -> CallResult<Nat> {
+        ic_cdk::call::Call::unbounded_wait(self.0, "token_withdraw_to_account_id")
+            .with_args(&(args, retries))
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
     }
     pub async fn token_withdraw(&self, args: TokenWithdrawArgs, retries: Option<u8>) -> CallResult<Nat> {
-        Ok(ic_cdk::call::Call::unbounded_wait(self.0, "token_withdraw")
+        ic_cdk::call::Call::unbounded_wait(self.0, "token_withdraw")
             .with_args(&(args, retries))
-            .await?
-            .candid()?)
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
     }
     pub async fn token_withdraw_many(
         &self,
         args: TokenWithdrawManyArgs,
         retries: Option<u8>,
     ) -> CallResult<Vec<CallResult<Nat>>> {
-        Ok(ic_cdk::call::Call::unbounded_wait(self.0, "token_withdraw_many")
+        ic_cdk::call::Call::unbounded_wait(self.0, "token_withdraw_many")
             .with_args(&(args, retries))
-            .await?
-            .candid()?)
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
     }
     pub async fn token_transfer(&self, args: TokenTransferArgs, retries: Option<u8>) -> CallResult<Nat> {
-        Ok(ic_cdk::call::Call::unbounded_wait(self.0, "token_transfer")
+        ic_cdk::call::Call::unbounded_wait(self.0, "token_transfer")
             .with_args(&(args, retries))
-            .await?
-            .candid()?)
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
     }
 
     // pair liquidity
@@ -51,20 +83,46 @@ impl Service {
         args: TokenPairLiquidityAddArgs,
         retries: Option<u8>,
     ) -> CallResult<TokenPairLiquidityAddSuccess> {
-        Ok(ic_cdk::call::Call::unbounded_wait(self.0, "pair_liquidity_add")
+        ic_cdk::call::Call::unbounded_wait(self.0, "pair_liquidity_add")
             .with_args(&(args, retries))
-            .await?
-            .candid()?)
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
     }
     pub async fn pair_liquidity_remove(
         &self,
         args: TokenPairLiquidityRemoveArgs,
         retries: Option<u8>,
     ) -> CallResult<TokenPairLiquidityRemoveSuccess> {
-        Ok(ic_cdk::call::Call::unbounded_wait(self.0, "pair_liquidity_remove")
+        ic_cdk::call::Call::unbounded_wait(self.0, "pair_liquidity_remove")
+            .with_args(&(args, retries))
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
+    }
+
+    // pool liquidity
+    pub async fn pool_liquidity_add(
+        &self,
+        args: TokenPoolLiquidityAddArgs,
+        retries: Option<u8>,
+    ) -> CallResult<TokenPoolLiquidityAddSuccess> {
+        ic_cdk::call::Call::unbounded_wait(self.0, "pool_liquidity_add")
             .with_args(&(args, retries))
-            .await?
-            .candid()?)
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
+    }
+    pub async fn pool_liquidity_remove(
+        &self,
+        args: TokenPoolLiquidityRemoveArgs,
+        retries: Option<u8>,
+    ) -> CallResult<TokenPoolLiquidityRemoveSuccess> {
+        ic_cdk::call::Call::unbounded_wait(self.0, "pool_liquidity_remove")
+            .with_args(&(args, retries))
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
     }
 
     // pair swap
@@ -73,33 +131,134 @@ impl Service {
         args: TokenPairSwapExactTokensForTokensArgs,
         retries: Option<u8>,
     ) -> CallResult<TokenPairSwapTokensSuccess> {
-        Ok(
-            ic_cdk::call::Call::unbounded_wait(self.0, "pair_swap_exact_tokens_for_tokens")
-                .with_args(&(args, retries))
-                .await?
-                .candid()?,
-        )
+        ic_cdk::call::Call::unbounded_wait(self.0, "pair_swap_exact_tokens_for_tokens")
+            .with_args(&(args, retries))
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
     }
     pub async fn pair_swap_tokens_for_exact_tokens(
         &self,
         args: TokenPairSwapTokensForExactTokensArgs,
         retries: Option<u8>,
     ) -> CallResult<TokenPairSwapTokensSuccess> {
-        Ok(
-            ic_cdk::call::Call::unbounded_wait(self.0, "pair_swap_tokens_for_exact_tokens")
-                .with_args(&(args, retries))
-                .await?
-                .candid()?,
-        )
+        ic_cdk::call::Call::unbounded_wait(self.0, "pair_swap_tokens_for_exact_tokens")
+            .with_args(&(args, retries))
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
     }
     pub async fn pair_swap_by_loan(
         &self,
         args: TokenPairSwapByLoanArgs,
         retries: Option<u8>,
     ) -> CallResult<TokenPairSwapTokensSuccess> {
-        Ok(ic_cdk::call::Call::unbounded_wait(self.0, "pair_swap_by_loan")
+        ic_cdk::call::Call::unbounded_wait(self.0, "pair_swap_by_loan")
             .with_args(&(args, retries))
-            .await?
-            .candid()?)
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
+    }
+    pub async fn pair_swap_split(
+        &self,
+        args: TokenPairSwapSplitArgs,
+        retries: Option<u8>,
+    ) -> CallResult<TokenPairSwapSplitSuccess> {
+        ic_cdk::call::Call::unbounded_wait(self.0, "pair_swap_split")
+            .with_args(&(args, retries))
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
+    }
+
+    // pair flash loan
+    pub async fn pair_flash_loan(&self, args: TokenPairFlashLoanArgs, retries: Option<u8>) -> CallResult<FlashLoan> {
+        ic_cdk::call::Call::unbounded_wait(self.0, "pair_flash_loan")
+            .with_args(&(args, retries))
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
+    }
+
+    // limit order
+    pub async fn limit_order_place(&self, args: LimitOrderPlaceArgs, retries: Option<u8>) -> CallResult<LimitOrder> {
+        ic_cdk::call::Call::unbounded_wait(self.0, "limit_order_place")
+            .with_args(&(args, retries))
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
+    }
+    pub async fn limit_order_cancel(&self, id: u64, retries: Option<u8>) -> CallResult<Nat> {
+        ic_cdk::call::Call::unbounded_wait(self.0, "limit_order_cancel")
+            .with_args(&(id, retries))
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
+    }
+
+    // dca order
+    pub async fn dca_order_place(&self, args: DcaOrderPlaceArgs, retries: Option<u8>) -> CallResult<DcaOrder> {
+        ic_cdk::call::Call::unbounded_wait(self.0, "dca_order_place")
+            .with_args(&(args, retries))
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
+    }
+    pub async fn dca_order_cancel(&self, id: u64, retries: Option<u8>) -> CallResult<Nat> {
+        ic_cdk::call::Call::unbounded_wait(self.0, "dca_order_cancel")
+            .with_args(&(id, retries))
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
+    }
+
+    // protocol fees
+    pub async fn protocol_fees_collect(
+        &self,
+        args: ProtocolFeesCollectArgs,
+        retries: Option<u8>,
+    ) -> CallResult<ProtocolFeesCollectSuccess> {
+        ic_cdk::call::Call::unbounded_wait(self.0, "protocol_fees_collect")
+            .with_args(&(args, retries))
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
+    }
+
+    // farm
+    pub async fn farm_create(&self, args: FarmCreateArgs, retries: Option<u8>) -> CallResult<Farm> {
+        ic_cdk::call::Call::unbounded_wait(self.0, "farm_create")
+            .with_args(&(args, retries))
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
+    }
+    pub async fn farm_refund(&self, id: u64, retries: Option<u8>) -> CallResult<Nat> {
+        ic_cdk::call::Call::unbounded_wait(self.0, "farm_refund")
+            .with_args(&(id, retries))
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
+    }
+    pub async fn farm_stake(&self, args: FarmStakeArgs, retries: Option<u8>) -> CallResult<Nat> {
+        ic_cdk::call::Call::unbounded_wait(self.0, "farm_stake")
+            .with_args(&(args, retries))
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
+    }
+    pub async fn farm_unstake(&self, args: FarmStakeArgs, retries: Option<u8>) -> CallResult<Nat> {
+        ic_cdk::call::Call::unbounded_wait(self.0, "farm_unstake")
+            .with_args(&(args, retries))
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
+    }
+    pub async fn farm_claim(&self, id: u64, from: Account, retries: Option<u8>) -> CallResult<Nat> {
+        ic_cdk::call::Call::unbounded_wait(self.0, "farm_claim")
+            .with_args(&(id, from, retries))
+            .await
+            .map_err(crate::types::self_call_failed)?
+            .candid::<CallResult<_>>()?
     }
 }
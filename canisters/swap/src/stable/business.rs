@@ -26,6 +26,15 @@ pub trait Business:
     fn business_config_fee_to_replace(&mut self, fee_to: FeeTo) -> FeeTo {
         ic_cdk::trap("Not supported operation by this version.")
     }
+    fn business_config_protocol_fee_recipients_query(&self) -> Vec<ProtocolFeeRecipient> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_config_protocol_fee_recipients_replace(
+        &mut self,
+        recipients: Vec<ProtocolFeeRecipient>,
+    ) -> Result<Vec<ProtocolFeeRecipient>, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
     fn business_config_protocol_fee_replace(
         &mut self,
         lock: &TokenPairsLock,
@@ -34,6 +43,66 @@ pub trait Business:
     ) -> Option<SwapRatio> {
         ic_cdk::trap("Not supported operation by this version.")
     }
+    fn business_config_amplification_replace(
+        &mut self,
+        locks: &(SwapBlockChainLock, TokenPairsLock),
+        now: TimestampNanos,
+        pa: &TokenPairAmm,
+        amplification: u64,
+    ) -> Result<u64, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_config_pmm_v1_price_replace(
+        &mut self,
+        locks: &(SwapBlockChainLock, TokenPairsLock),
+        now: TimestampNanos,
+        pa: &TokenPairAmm,
+        i: Nat,
+        k: Option<Nat>,
+    ) -> Result<(Nat, Nat), BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+
+    fn business_config_dynamic_fee_replace(
+        &mut self,
+        lock: &TokenPairsLock,
+        pa: &TokenPairAmm,
+        dynamic_fee: Option<SwapV2DynamicFeeArg>,
+    ) -> Result<Option<SwapV2DynamicFee>, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+
+    fn business_config_outer_lp_replace(
+        &mut self,
+        lock: &TokenPairsLock,
+        pa: &TokenPairAmm,
+        token_canister_id: CanisterId,
+    ) -> Result<(), BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+
+    // fee tier
+    fn business_config_fee_tiers_query(&self) -> Vec<(AmmText, FeeTier)> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_config_fee_tier_get(&self, amm: &Amm) -> Option<FeeTier> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_config_fee_tier_register(
+        &mut self,
+        amm: Amm,
+        protocol_fee: Option<SwapRatio>,
+    ) -> Result<FeeTier, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+
+    // router
+    fn business_config_router_max_hops_query(&self) -> u8 {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_config_router_max_hops_set(&mut self, max_hops: u8) -> Result<u8, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
 
     // archive canister
     // token
@@ -141,6 +210,49 @@ pub trait Business:
         ic_cdk::trap("Not supported operation by this version.")
     }
 
+    // token reconcile
+    fn business_config_token_reconcile_query(&self) -> &TokenReconciliation {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_config_token_reconcile_set(&mut self, config: TokenReconcileConfig) {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_config_token_reconcile_trigger(&self, now: TimestampNanos) -> bool {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_config_token_reconcile_start(&mut self, now: TimestampNanos) -> bool {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_config_token_reconcile_checked(&mut self, now: TimestampNanos) {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_config_token_reconcile_stop(&mut self) {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_token_balances_sum(&self, token: CanisterId, pools: &HashSet<Account>) -> Option<(Nat, Nat)> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_token_balance_locked_accounts(&self) -> Vec<TokenAccount> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_token_reconcile_report(&mut self, report: TokenReconcileReport) {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+
+    // token fee
+    fn business_config_token_fee_query(&self) -> &HashMap<CanisterId, Nat> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_config_token_fee_update(&mut self, arg: ArgWithMeta<TokenFeeUpdateArg>) -> Option<Nat> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_config_token_fee_checking_set(&mut self, checking_interval_ns: u64) {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_config_token_fee_trigger(&mut self, now: TimestampNanos) -> bool {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+
     // token frozen
     fn business_config_token_frozen_query(&self) -> &HashSet<CanisterId> {
         ic_cdk::trap("Not supported operation by this version.")
@@ -164,6 +276,9 @@ pub trait Business:
     }
 
     // set_certified_data
+    fn business_certified_tree(&self) -> HashTree {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
     fn business_certified_data_refresh(&self) {
         ic_cdk::trap("Not supported operation by this version.")
     }
@@ -230,6 +345,9 @@ pub trait Business:
     fn business_dummy_tokens_query(&self) -> HashMap<CanisterId, TokenInfo> {
         ic_cdk::trap("Not supported operation by this version.")
     }
+    fn business_outer_lp_tokens_query(&self) -> HashMap<CanisterId, TokenInfo> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
     fn business_all_tokens_with_dummy_query(&self) -> HashMap<CanisterId, Cow<'_, TokenInfo>> {
         ic_cdk::trap("Not supported operation by this version.")
     }
@@ -260,6 +378,26 @@ pub trait Business:
     ) -> Result<Nat, BusinessError> {
         ic_cdk::trap("Not supported operation by this version.")
     }
+    fn business_token_deposit_sweeping(&self, token: CanisterId, account: Account) -> Option<TokenDepositSweeping> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_token_deposit_sweeping_set(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        token: CanisterId,
+        account: Account,
+        sweeping: Option<TokenDepositSweeping>,
+    ) {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_token_deposit_notify(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<DepositToken>,
+        height: Nat,
+    ) -> Result<Nat, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
     fn business_token_withdraw(
         &mut self,
         locks: &(TokenBlockChainLock, TokenBalancesLock),
@@ -283,6 +421,70 @@ pub trait Business:
         ic_cdk::trap("Not supported operation by this version.")
     }
 
+    // allowance
+    fn business_token_allowance_get(
+        &self,
+        token: &CanisterId,
+        from: &Account,
+        spender: &Account,
+        now: TimestampNanos,
+    ) -> TokenAllowance {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_token_allowance_check_spend(
+        &self,
+        token: &CanisterId,
+        from: &Account,
+        spender: &Account,
+        amount: &Nat,
+        now: TimestampNanos,
+    ) -> Result<(), BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_token_allowance_spend(&mut self, token: &CanisterId, from: &Account, spender: &Account, amount: &Nat) {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_token_allowance_spending(
+        &self,
+        token: &CanisterId,
+        from: &Account,
+        spender: &Account,
+        amount: &Nat,
+        now: TimestampNanos,
+    ) -> Result<ApproveToken, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_token_allowance_restoring(
+        &self,
+        token: &CanisterId,
+        from: &Account,
+        spender: &Account,
+        unused: &Nat,
+        block: BlockIndex,
+        now: TimestampNanos,
+    ) -> Option<ApproveToken> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_token_icrc_transaction_get(&self, hash: &str) -> Option<BlockIndex> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_token_icrc_transaction_insert(
+        &mut self,
+        hash: String,
+        created: TimestampNanos,
+        block_index: BlockIndex,
+        now: TimestampNanos,
+    ) {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_token_approve(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<ApproveToken>,
+    ) -> Result<BlockIndex, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+
     // ======================== swap block chain ========================
 
     // ======================== token pair swap ========================
@@ -294,11 +496,16 @@ pub trait Business:
     fn business_token_pair_pool_get(&self, pa: &TokenPairAmm) -> Option<MarketMaker> {
         ic_cdk::trap("Not supported operation by this version.")
     }
+    fn business_token_pair_lp_earnings_query(&self, account: &Account) -> Vec<LpEarnings> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
     // create and remove
     fn business_token_pair_pool_create(
         &mut self,
         lock: &SwapBlockChainLock,
         arg: ArgWithMeta<TokenPairAmm>,
+        weights: Option<(u32, u32)>,
+        sqrt_price_x96: Option<Nat>,
     ) -> Result<MarketMaker, BusinessError> {
         ic_cdk::trap("Not supported operation by this version.")
     }
@@ -368,6 +575,227 @@ pub trait Business:
     ) -> Result<TokenPairSwapTokensSuccess, BusinessError> {
         ic_cdk::trap("Not supported operation by this version.")
     }
+    fn business_token_pair_swap_split(
+        &mut self,
+        locks: &AllLocks,
+        arg: ArgWithMeta<TokenPairSwapSplitArg>,
+    ) -> Result<TokenPairSwapSplitSuccess, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+
+    // ======================== token pool ========================
+
+    // query
+    fn business_token_pools_query(&self) -> Vec<(TokenPoolAmm, MarketMaker)> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_token_pool_get(&self, pool: &TokenPoolAmm) -> Option<MarketMaker> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_token_pool_find(&self, pa: &TokenPairAmm) -> Option<TokenPoolAmm> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    // create and remove
+    fn business_token_pool_create(
+        &mut self,
+        lock: &SwapBlockChainLock,
+        arg: ArgWithMeta<TokenPoolAmm>,
+    ) -> Result<MarketMaker, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_token_pool_remove(
+        &mut self,
+        locks: &(SwapBlockChainLock, TokenPairsLock),
+        arg: ArgWithMeta<TokenPoolAmm>,
+    ) -> Result<MarketMaker, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    // liquidity
+    fn business_token_pool_liquidity_add(
+        &mut self,
+        locks: &AllLocks,
+        arg: ArgWithMeta<TokenPoolLiquidityAddArg>,
+    ) -> Result<TokenPoolLiquidityAddSuccess, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_token_pool_liquidity_remove(
+        &mut self,
+        locks: &AllLocks,
+        arg: ArgWithMeta<TokenPoolLiquidityRemoveArg>,
+    ) -> Result<TokenPoolLiquidityRemoveSuccess, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+
+    // ======================== token pair flash loan ========================
+
+    // query
+    fn business_token_pair_flash_loans_query(&self, borrower: Option<&Account>) -> Vec<FlashLoan> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_token_pair_flash_loan_get(&self, id: u64) -> Option<FlashLoan> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_token_pair_flash_loan_borrowing(&self, account: &Account) -> bool {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_token_pair_flash_loan_lending(&self, pa: &TokenPairAmm) -> bool {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    // lend and settle
+    fn business_token_pair_flash_loan_lend(
+        &mut self,
+        locks: &AllLocks,
+        arg: ArgWithMeta<FlashLoan>,
+    ) -> Result<FlashLoan, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_token_pair_flash_loan_repay(
+        &mut self,
+        locks: &AllLocks,
+        arg: ArgWithMeta<FlashLoan>,
+    ) -> Result<FlashLoan, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_token_pair_flash_loan_default(
+        &mut self,
+        locks: &AllLocks,
+        arg: ArgWithMeta<FlashLoan>,
+    ) -> Result<FlashLoan, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+
+    // ======================== limit order ========================
+
+    // query
+    fn business_limit_orders_query(&self, owner: Option<&Account>) -> Vec<LimitOrder> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_limit_order_get(&self, id: u64) -> Option<LimitOrder> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    // place and cancel
+    fn business_limit_order_place(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<LimitOrder>,
+    ) -> Result<LimitOrder, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_limit_order_cancel(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<LimitOrder>,
+    ) -> Result<Nat, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    // fill
+    fn business_limit_orders_next_batch(&mut self) -> Vec<LimitOrder> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_limit_order_fill(
+        &mut self,
+        locks: &AllLocks,
+        id: u64,
+        arg: ArgWithMeta<TokenPairSwapExactTokensForTokensArg>,
+    ) -> Result<TokenPairSwapTokensSuccess, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+
+    // ======================== dca order ========================
+
+    // query
+    fn business_dca_orders_query(&self, owner: Option<&Account>) -> Vec<DcaOrder> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_dca_order_get(&self, id: u64) -> Option<DcaOrder> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    // place and cancel
+    fn business_dca_order_place(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<DcaOrder>,
+    ) -> Result<DcaOrder, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_dca_order_cancel(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<DcaOrder>,
+    ) -> Result<Nat, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    // execute
+    fn business_dca_orders_next_batch(&mut self, now: TimestampNanos) -> Vec<DcaOrder> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_dca_order_execute(
+        &mut self,
+        locks: &AllLocks,
+        id: u64,
+        arg: ArgWithMeta<TokenPairSwapExactTokensForTokensArg>,
+    ) -> Result<TokenPairSwapTokensSuccess, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+
+    // ======================== protocol fees ========================
+
+    fn business_protocol_fees_collect(
+        &mut self,
+        locks: &AllLocks,
+        arg: ArgWithMeta<ProtocolFeesCollectArg>,
+    ) -> Result<ProtocolFeesCollectSuccess, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+
+    // ======================== farm ========================
+
+    // query
+    fn business_farms_query(&self, pa: Option<&TokenPairAmm>) -> Vec<FarmStatus> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_farm_get(&self, id: u64) -> Option<FarmStatus> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_farm_staker_get(&self, id: u64, account: &Account, now: TimestampNanos) -> Option<FarmStaker> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    // update
+    fn business_farm_create(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<Farm>,
+    ) -> Result<Farm, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_farm_refund(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<Farm>,
+    ) -> Result<Nat, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_farm_stake(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<FarmStakeArg>,
+    ) -> Result<Nat, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_farm_unstake(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<FarmStakeArg>,
+    ) -> Result<Nat, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
+    fn business_farm_claim(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<FarmStakeArg>,
+    ) -> Result<Nat, BusinessError> {
+        ic_cdk::trap("Not supported operation by this version.")
+    }
 
     // ======================== blocks query ========================
 
@@ -429,6 +857,16 @@ impl Business for State {
     fn business_config_fee_to_replace(&mut self, fee_to: FeeTo) -> FeeTo {
         self.get_mut().business_config_fee_to_replace(fee_to)
     }
+    fn business_config_protocol_fee_recipients_query(&self) -> Vec<ProtocolFeeRecipient> {
+        self.get().business_config_protocol_fee_recipients_query()
+    }
+    fn business_config_protocol_fee_recipients_replace(
+        &mut self,
+        recipients: Vec<ProtocolFeeRecipient>,
+    ) -> Result<Vec<ProtocolFeeRecipient>, BusinessError> {
+        self.get_mut()
+            .business_config_protocol_fee_recipients_replace(recipients)
+    }
     fn business_config_protocol_fee_replace(
         &mut self,
         lock: &TokenPairsLock,
@@ -438,6 +876,70 @@ impl Business for State {
         self.get_mut()
             .business_config_protocol_fee_replace(lock, pa, protocol_fee)
     }
+    fn business_config_amplification_replace(
+        &mut self,
+        locks: &(SwapBlockChainLock, TokenPairsLock),
+        now: TimestampNanos,
+        pa: &TokenPairAmm,
+        amplification: u64,
+    ) -> Result<u64, BusinessError> {
+        self.get_mut()
+            .business_config_amplification_replace(locks, now, pa, amplification)
+    }
+    fn business_config_pmm_v1_price_replace(
+        &mut self,
+        locks: &(SwapBlockChainLock, TokenPairsLock),
+        now: TimestampNanos,
+        pa: &TokenPairAmm,
+        i: Nat,
+        k: Option<Nat>,
+    ) -> Result<(Nat, Nat), BusinessError> {
+        self.get_mut()
+            .business_config_pmm_v1_price_replace(locks, now, pa, i, k)
+    }
+
+    fn business_config_dynamic_fee_replace(
+        &mut self,
+        lock: &TokenPairsLock,
+        pa: &TokenPairAmm,
+        dynamic_fee: Option<SwapV2DynamicFeeArg>,
+    ) -> Result<Option<SwapV2DynamicFee>, BusinessError> {
+        self.get_mut()
+            .business_config_dynamic_fee_replace(lock, pa, dynamic_fee)
+    }
+
+    fn business_config_outer_lp_replace(
+        &mut self,
+        lock: &TokenPairsLock,
+        pa: &TokenPairAmm,
+        token_canister_id: CanisterId,
+    ) -> Result<(), BusinessError> {
+        self.get_mut()
+            .business_config_outer_lp_replace(lock, pa, token_canister_id)
+    }
+
+    // fee tier
+    fn business_config_fee_tiers_query(&self) -> Vec<(AmmText, FeeTier)> {
+        self.get().business_config_fee_tiers_query()
+    }
+    fn business_config_fee_tier_get(&self, amm: &Amm) -> Option<FeeTier> {
+        self.get().business_config_fee_tier_get(amm)
+    }
+    fn business_config_fee_tier_register(
+        &mut self,
+        amm: Amm,
+        protocol_fee: Option<SwapRatio>,
+    ) -> Result<FeeTier, BusinessError> {
+        self.get_mut().business_config_fee_tier_register(amm, protocol_fee)
+    }
+
+    // router
+    fn business_config_router_max_hops_query(&self) -> u8 {
+        self.get().business_config_router_max_hops_query()
+    }
+    fn business_config_router_max_hops_set(&mut self, max_hops: u8) -> Result<u8, BusinessError> {
+        self.get_mut().business_config_router_max_hops_set(max_hops)
+    }
 
     // archive canister
     // token
@@ -553,6 +1055,50 @@ impl Business for State {
             .business_config_maintain_archives_cycles_recharged(canister_id, cycles)
     }
 
+    // token reconcile
+    fn business_config_token_reconcile_query(&self) -> &TokenReconciliation {
+        self.get().business_config_token_reconcile_query()
+    }
+    fn business_config_token_reconcile_set(&mut self, config: TokenReconcileConfig) {
+        self.get_mut().business_config_token_reconcile_set(config)
+    }
+    fn business_config_token_reconcile_trigger(&self, now: TimestampNanos) -> bool {
+        self.get().business_config_token_reconcile_trigger(now)
+    }
+    fn business_config_token_reconcile_start(&mut self, now: TimestampNanos) -> bool {
+        self.get_mut().business_config_token_reconcile_start(now)
+    }
+    fn business_config_token_reconcile_checked(&mut self, now: TimestampNanos) {
+        self.get_mut().business_config_token_reconcile_checked(now)
+    }
+    fn business_config_token_reconcile_stop(&mut self) {
+        self.get_mut().business_config_token_reconcile_stop()
+    }
+    fn business_token_balances_sum(&self, token: CanisterId, pools: &HashSet<Account>) -> Option<(Nat, Nat)> {
+        self.get().business_token_balances_sum(token, pools)
+    }
+    fn business_token_balance_locked_accounts(&self) -> Vec<TokenAccount> {
+        self.get().business_token_balance_locked_accounts()
+    }
+    fn business_token_reconcile_report(&mut self, report: TokenReconcileReport) {
+        self.get_mut().business_token_reconcile_report(report)
+    }
+
+    // token fee
+    fn business_config_token_fee_query(&self) -> &HashMap<CanisterId, Nat> {
+        self.get().business_config_token_fee_query()
+    }
+    fn business_config_token_fee_update(&mut self, arg: ArgWithMeta<TokenFeeUpdateArg>) -> Option<Nat> {
+        self.get_mut().business_config_token_fee_update(arg)
+    }
+    fn business_config_token_fee_checking_set(&mut self, checking_interval_ns: u64) {
+        self.get_mut()
+            .business_config_token_fee_checking_set(checking_interval_ns)
+    }
+    fn business_config_token_fee_trigger(&mut self, now: TimestampNanos) -> bool {
+        self.get_mut().business_config_token_fee_trigger(now)
+    }
+
     // token frozen
     fn business_config_token_frozen_query(&self) -> &HashSet<CanisterId> {
         self.get().business_config_token_frozen_query()
@@ -576,6 +1122,9 @@ impl Business for State {
     }
 
     // set_certified_data
+    fn business_certified_tree(&self) -> HashTree {
+        self.get().business_certified_tree()
+    }
     fn business_certified_data_refresh(&self) {
         self.get().business_certified_data_refresh()
     }
@@ -642,6 +1191,9 @@ impl Business for State {
     fn business_dummy_tokens_query(&self) -> HashMap<CanisterId, TokenInfo> {
         self.get().business_dummy_tokens_query()
     }
+    fn business_outer_lp_tokens_query(&self) -> HashMap<CanisterId, TokenInfo> {
+        self.get().business_outer_lp_tokens_query()
+    }
     fn business_all_tokens_with_dummy_query(&self) -> HashMap<CanisterId, Cow<'_, TokenInfo>> {
         self.get().business_all_tokens_with_dummy_query()
     }
@@ -672,6 +1224,27 @@ impl Business for State {
     ) -> Result<Nat, BusinessError> {
         self.get_mut().business_token_deposit(locks, arg, height)
     }
+    fn business_token_deposit_sweeping(&self, token: CanisterId, account: Account) -> Option<TokenDepositSweeping> {
+        self.get().business_token_deposit_sweeping(token, account)
+    }
+    fn business_token_deposit_sweeping_set(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        token: CanisterId,
+        account: Account,
+        sweeping: Option<TokenDepositSweeping>,
+    ) {
+        self.get_mut()
+            .business_token_deposit_sweeping_set(locks, token, account, sweeping)
+    }
+    fn business_token_deposit_notify(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<DepositToken>,
+        height: Nat,
+    ) -> Result<Nat, BusinessError> {
+        self.get_mut().business_token_deposit_notify(locks, arg, height)
+    }
     fn business_token_withdraw(
         &mut self,
         locks: &(TokenBlockChainLock, TokenBalancesLock),
@@ -695,6 +1268,75 @@ impl Business for State {
         self.get_mut().business_token_transfer_lp(locks, arg)
     }
 
+    // allowance
+    fn business_token_allowance_get(
+        &self,
+        token: &CanisterId,
+        from: &Account,
+        spender: &Account,
+        now: TimestampNanos,
+    ) -> TokenAllowance {
+        self.get().business_token_allowance_get(token, from, spender, now)
+    }
+    fn business_token_allowance_check_spend(
+        &self,
+        token: &CanisterId,
+        from: &Account,
+        spender: &Account,
+        amount: &Nat,
+        now: TimestampNanos,
+    ) -> Result<(), BusinessError> {
+        self.get()
+            .business_token_allowance_check_spend(token, from, spender, amount, now)
+    }
+    fn business_token_allowance_spend(&mut self, token: &CanisterId, from: &Account, spender: &Account, amount: &Nat) {
+        self.get_mut()
+            .business_token_allowance_spend(token, from, spender, amount)
+    }
+    fn business_token_allowance_spending(
+        &self,
+        token: &CanisterId,
+        from: &Account,
+        spender: &Account,
+        amount: &Nat,
+        now: TimestampNanos,
+    ) -> Result<ApproveToken, BusinessError> {
+        self.get()
+            .business_token_allowance_spending(token, from, spender, amount, now)
+    }
+    fn business_token_allowance_restoring(
+        &self,
+        token: &CanisterId,
+        from: &Account,
+        spender: &Account,
+        unused: &Nat,
+        block: BlockIndex,
+        now: TimestampNanos,
+    ) -> Option<ApproveToken> {
+        self.get()
+            .business_token_allowance_restoring(token, from, spender, unused, block, now)
+    }
+    fn business_token_icrc_transaction_get(&self, hash: &str) -> Option<BlockIndex> {
+        self.get().business_token_icrc_transaction_get(hash)
+    }
+    fn business_token_icrc_transaction_insert(
+        &mut self,
+        hash: String,
+        created: TimestampNanos,
+        block_index: BlockIndex,
+        now: TimestampNanos,
+    ) {
+        self.get_mut()
+            .business_token_icrc_transaction_insert(hash, created, block_index, now)
+    }
+    fn business_token_approve(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<ApproveToken>,
+    ) -> Result<BlockIndex, BusinessError> {
+        self.get_mut().business_token_approve(locks, arg)
+    }
+
     // ======================== swap block chain ========================
 
     // ======================== token pair swap ========================
@@ -706,13 +1348,19 @@ impl Business for State {
     fn business_token_pair_pool_get(&self, pa: &TokenPairAmm) -> Option<MarketMaker> {
         self.get().business_token_pair_pool_get(pa)
     }
+    fn business_token_pair_lp_earnings_query(&self, account: &Account) -> Vec<LpEarnings> {
+        self.get().business_token_pair_lp_earnings_query(account)
+    }
     // create and remove
     fn business_token_pair_pool_create(
         &mut self,
         lock: &SwapBlockChainLock,
         arg: ArgWithMeta<TokenPairAmm>,
+        weights: Option<(u32, u32)>,
+        sqrt_price_x96: Option<Nat>,
     ) -> Result<MarketMaker, BusinessError> {
-        self.get_mut().business_token_pair_pool_create(lock, arg)
+        self.get_mut()
+            .business_token_pair_pool_create(lock, arg, weights, sqrt_price_x96)
     }
     fn business_token_pair_pool_remove(
         &mut self,
@@ -783,6 +1431,227 @@ impl Business for State {
     ) -> Result<TokenPairSwapTokensSuccess, BusinessError> {
         self.get_mut().business_token_pair_swap_by_loan(locks, arg)
     }
+    fn business_token_pair_swap_split(
+        &mut self,
+        locks: &AllLocks,
+        arg: ArgWithMeta<TokenPairSwapSplitArg>,
+    ) -> Result<TokenPairSwapSplitSuccess, BusinessError> {
+        self.get_mut().business_token_pair_swap_split(locks, arg)
+    }
+
+    // ======================== token pool ========================
+
+    // query
+    fn business_token_pools_query(&self) -> Vec<(TokenPoolAmm, MarketMaker)> {
+        self.get().business_token_pools_query()
+    }
+    fn business_token_pool_get(&self, pool: &TokenPoolAmm) -> Option<MarketMaker> {
+        self.get().business_token_pool_get(pool)
+    }
+    fn business_token_pool_find(&self, pa: &TokenPairAmm) -> Option<TokenPoolAmm> {
+        self.get().business_token_pool_find(pa)
+    }
+    // create and remove
+    fn business_token_pool_create(
+        &mut self,
+        lock: &SwapBlockChainLock,
+        arg: ArgWithMeta<TokenPoolAmm>,
+    ) -> Result<MarketMaker, BusinessError> {
+        self.get_mut().business_token_pool_create(lock, arg)
+    }
+    fn business_token_pool_remove(
+        &mut self,
+        locks: &(SwapBlockChainLock, TokenPairsLock),
+        arg: ArgWithMeta<TokenPoolAmm>,
+    ) -> Result<MarketMaker, BusinessError> {
+        self.get_mut().business_token_pool_remove(locks, arg)
+    }
+    // liquidity
+    fn business_token_pool_liquidity_add(
+        &mut self,
+        locks: &AllLocks,
+        arg: ArgWithMeta<TokenPoolLiquidityAddArg>,
+    ) -> Result<TokenPoolLiquidityAddSuccess, BusinessError> {
+        self.get_mut().business_token_pool_liquidity_add(locks, arg)
+    }
+    fn business_token_pool_liquidity_remove(
+        &mut self,
+        locks: &AllLocks,
+        arg: ArgWithMeta<TokenPoolLiquidityRemoveArg>,
+    ) -> Result<TokenPoolLiquidityRemoveSuccess, BusinessError> {
+        self.get_mut().business_token_pool_liquidity_remove(locks, arg)
+    }
+
+    // ======================== token pair flash loan ========================
+
+    // query
+    fn business_token_pair_flash_loans_query(&self, borrower: Option<&Account>) -> Vec<FlashLoan> {
+        self.get().business_token_pair_flash_loans_query(borrower)
+    }
+    fn business_token_pair_flash_loan_get(&self, id: u64) -> Option<FlashLoan> {
+        self.get().business_token_pair_flash_loan_get(id)
+    }
+    fn business_token_pair_flash_loan_borrowing(&self, account: &Account) -> bool {
+        self.get().business_token_pair_flash_loan_borrowing(account)
+    }
+    fn business_token_pair_flash_loan_lending(&self, pa: &TokenPairAmm) -> bool {
+        self.get().business_token_pair_flash_loan_lending(pa)
+    }
+    // lend and settle
+    fn business_token_pair_flash_loan_lend(
+        &mut self,
+        locks: &AllLocks,
+        arg: ArgWithMeta<FlashLoan>,
+    ) -> Result<FlashLoan, BusinessError> {
+        self.get_mut().business_token_pair_flash_loan_lend(locks, arg)
+    }
+    fn business_token_pair_flash_loan_repay(
+        &mut self,
+        locks: &AllLocks,
+        arg: ArgWithMeta<FlashLoan>,
+    ) -> Result<FlashLoan, BusinessError> {
+        self.get_mut().business_token_pair_flash_loan_repay(locks, arg)
+    }
+    fn business_token_pair_flash_loan_default(
+        &mut self,
+        locks: &AllLocks,
+        arg: ArgWithMeta<FlashLoan>,
+    ) -> Result<FlashLoan, BusinessError> {
+        self.get_mut().business_token_pair_flash_loan_default(locks, arg)
+    }
+
+    // ======================== limit order ========================
+
+    // query
+    fn business_limit_orders_query(&self, owner: Option<&Account>) -> Vec<LimitOrder> {
+        self.get().business_limit_orders_query(owner)
+    }
+    fn business_limit_order_get(&self, id: u64) -> Option<LimitOrder> {
+        self.get().business_limit_order_get(id)
+    }
+    // place and cancel
+    fn business_limit_order_place(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<LimitOrder>,
+    ) -> Result<LimitOrder, BusinessError> {
+        self.get_mut().business_limit_order_place(locks, arg)
+    }
+    fn business_limit_order_cancel(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<LimitOrder>,
+    ) -> Result<Nat, BusinessError> {
+        self.get_mut().business_limit_order_cancel(locks, arg)
+    }
+    // fill
+    fn business_limit_orders_next_batch(&mut self) -> Vec<LimitOrder> {
+        self.get_mut().business_limit_orders_next_batch()
+    }
+    fn business_limit_order_fill(
+        &mut self,
+        locks: &AllLocks,
+        id: u64,
+        arg: ArgWithMeta<TokenPairSwapExactTokensForTokensArg>,
+    ) -> Result<TokenPairSwapTokensSuccess, BusinessError> {
+        self.get_mut().business_limit_order_fill(locks, id, arg)
+    }
+
+    // ======================== dca order ========================
+
+    // query
+    fn business_dca_orders_query(&self, owner: Option<&Account>) -> Vec<DcaOrder> {
+        self.get().business_dca_orders_query(owner)
+    }
+    fn business_dca_order_get(&self, id: u64) -> Option<DcaOrder> {
+        self.get().business_dca_order_get(id)
+    }
+    // place and cancel
+    fn business_dca_order_place(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<DcaOrder>,
+    ) -> Result<DcaOrder, BusinessError> {
+        self.get_mut().business_dca_order_place(locks, arg)
+    }
+    fn business_dca_order_cancel(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<DcaOrder>,
+    ) -> Result<Nat, BusinessError> {
+        self.get_mut().business_dca_order_cancel(locks, arg)
+    }
+    // execute
+    fn business_dca_orders_next_batch(&mut self, now: TimestampNanos) -> Vec<DcaOrder> {
+        self.get_mut().business_dca_orders_next_batch(now)
+    }
+    fn business_dca_order_execute(
+        &mut self,
+        locks: &AllLocks,
+        id: u64,
+        arg: ArgWithMeta<TokenPairSwapExactTokensForTokensArg>,
+    ) -> Result<TokenPairSwapTokensSuccess, BusinessError> {
+        self.get_mut().business_dca_order_execute(locks, id, arg)
+    }
+
+    // ======================== protocol fees ========================
+
+    fn business_protocol_fees_collect(
+        &mut self,
+        locks: &AllLocks,
+        arg: ArgWithMeta<ProtocolFeesCollectArg>,
+    ) -> Result<ProtocolFeesCollectSuccess, BusinessError> {
+        self.get_mut().business_protocol_fees_collect(locks, arg)
+    }
+
+    // ======================== farm ========================
+
+    // query
+    fn business_farms_query(&self, pa: Option<&TokenPairAmm>) -> Vec<FarmStatus> {
+        self.get().business_farms_query(pa)
+    }
+    fn business_farm_get(&self, id: u64) -> Option<FarmStatus> {
+        self.get().business_farm_get(id)
+    }
+    fn business_farm_staker_get(&self, id: u64, account: &Account, now: TimestampNanos) -> Option<FarmStaker> {
+        self.get().business_farm_staker_get(id, account, now)
+    }
+    // update
+    fn business_farm_create(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<Farm>,
+    ) -> Result<Farm, BusinessError> {
+        self.get_mut().business_farm_create(locks, arg)
+    }
+    fn business_farm_refund(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<Farm>,
+    ) -> Result<Nat, BusinessError> {
+        self.get_mut().business_farm_refund(locks, arg)
+    }
+    fn business_farm_stake(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<FarmStakeArg>,
+    ) -> Result<Nat, BusinessError> {
+        self.get_mut().business_farm_stake(locks, arg)
+    }
+    fn business_farm_unstake(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<FarmStakeArg>,
+    ) -> Result<Nat, BusinessError> {
+        self.get_mut().business_farm_unstake(locks, arg)
+    }
+    fn business_farm_claim(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<FarmStakeArg>,
+    ) -> Result<Nat, BusinessError> {
+        self.get_mut().business_farm_claim(locks, arg)
+    }
 
     // ======================== blocks query ========================
 
@@ -1,6 +1,8 @@
 use super::super::business::*;
 use super::types::*;
 
+use ::common::utils::math::zero;
+
 impl Business for InnerState {
     fn business_updated(&self) -> u64 {
         self.business_data.updated.into_inner()
@@ -15,6 +17,27 @@ impl Business for InnerState {
     fn business_config_fee_to_replace(&mut self, fee_to: FeeTo) -> FeeTo {
         self.updated(|s| std::mem::replace(&mut s.business_data.fee_to, fee_to))
     }
+    fn business_config_protocol_fee_recipients_query(&self) -> Vec<ProtocolFeeRecipient> {
+        self.business_data.protocol_fee_recipients.clone()
+    }
+    fn business_config_protocol_fee_recipients_replace(
+        &mut self,
+        recipients: Vec<ProtocolFeeRecipient>,
+    ) -> Result<Vec<ProtocolFeeRecipient>, BusinessError> {
+        // ! weights must be positive and recipients must be different
+        if MAX_PROTOCOL_FEE_RECIPIENTS < recipients.len()
+            || recipients.iter().any(|r| r.weight == 0)
+            || recipients.iter().map(|r| r.account).collect::<HashSet<_>>().len() != recipients.len()
+        {
+            return Err(BusinessError::Swap("INVALID_PROTOCOL_FEE_RECIPIENTS".into()));
+        }
+        self.updated(|s| {
+            Ok(std::mem::replace(
+                &mut s.business_data.protocol_fee_recipients,
+                recipients,
+            ))
+        })
+    }
     fn business_config_protocol_fee_replace(
         &mut self,
         lock: &TokenPairsLock,
@@ -28,6 +51,102 @@ impl Business for InnerState {
             protocol_fee
         })
     }
+    fn business_config_amplification_replace(
+        &mut self,
+        locks: &(SwapBlockChainLock, TokenPairsLock),
+        now: TimestampNanos,
+        pa: &TokenPairAmm,
+        amplification: u64,
+    ) -> Result<u64, BusinessError> {
+        self.updated(|s| {
+            let mut swap_guard = s.swap_block_chain.be_guard(&locks.0);
+            let mut pairs_guard = s.token_pairs.be_guard(&locks.1);
+            let old = pairs_guard.replace_amplification(&mut swap_guard, now, pa, amplification)?;
+            pairs_guard.dump(); // * save stable data
+            swap_guard.dump(); // * save stable data
+            s.business_certified_data_refresh(); // set certified data
+            Ok(old)
+        })
+    }
+    fn business_config_pmm_v1_price_replace(
+        &mut self,
+        locks: &(SwapBlockChainLock, TokenPairsLock),
+        now: TimestampNanos,
+        pa: &TokenPairAmm,
+        i: Nat,
+        k: Option<Nat>,
+    ) -> Result<(Nat, Nat), BusinessError> {
+        self.updated(|s| {
+            let mut swap_guard = s.swap_block_chain.be_guard(&locks.0);
+            let mut pairs_guard = s.token_pairs.be_guard(&locks.1);
+            let old = pairs_guard.replace_pmm_v1_price(&mut swap_guard, now, pa, i, k)?;
+            pairs_guard.dump(); // * save stable data
+            swap_guard.dump(); // * save stable data
+            s.business_certified_data_refresh(); // set certified data
+            Ok(old)
+        })
+    }
+
+    fn business_config_dynamic_fee_replace(
+        &mut self,
+        lock: &TokenPairsLock,
+        pa: &TokenPairAmm,
+        dynamic_fee: Option<SwapV2DynamicFeeArg>,
+    ) -> Result<Option<SwapV2DynamicFee>, BusinessError> {
+        self.updated(|s| {
+            let mut guard = s.token_pairs.be_guard(lock);
+            let old = guard.replace_dynamic_fee(pa, dynamic_fee)?;
+            guard.dump(); // * save stable data
+            Ok(old)
+        })
+    }
+
+    fn business_config_outer_lp_replace(
+        &mut self,
+        lock: &TokenPairsLock,
+        pa: &TokenPairAmm,
+        token_canister_id: CanisterId,
+    ) -> Result<(), BusinessError> {
+        // the lp token of farms can not be changed
+        if !self.business_data.farms.query(Some(pa)).is_empty() {
+            return Err(BusinessError::Swap("FARM_EXIST".into()));
+        }
+        if self.business_token_query(&token_canister_id).is_some() {
+            return Err(BusinessError::Swap("OUTER_LP_CANISTER_EXIST".into()));
+        }
+        self.updated(|s| {
+            let mut guard = s.token_pairs.be_guard(lock);
+            guard.replace_outer_lp(pa, token_canister_id)?;
+            guard.dump(); // * save stable data
+            Ok(())
+        })
+    }
+
+    // fee tier
+    fn business_config_fee_tiers_query(&self) -> Vec<(AmmText, FeeTier)> {
+        self.business_data.fee_tiers.query()
+    }
+    fn business_config_fee_tier_get(&self, amm: &Amm) -> Option<FeeTier> {
+        self.business_data.fee_tiers.get(amm).cloned()
+    }
+    fn business_config_fee_tier_register(
+        &mut self,
+        amm: Amm,
+        protocol_fee: Option<SwapRatio>,
+    ) -> Result<FeeTier, BusinessError> {
+        self.updated(|s| s.business_data.fee_tiers.register(amm, protocol_fee))
+    }
+
+    // router
+    fn business_config_router_max_hops_query(&self) -> u8 {
+        self.business_data.router_max_hops
+    }
+    fn business_config_router_max_hops_set(&mut self, max_hops: u8) -> Result<u8, BusinessError> {
+        if max_hops == 0 || ROUTER_MAX_HOPS_LIMIT < max_hops {
+            return Err(BusinessError::Swap("INVALID_MAX_HOPS".into()));
+        }
+        self.updated(|s| Ok(std::mem::replace(&mut s.business_data.router_max_hops, max_hops)))
+    }
 
     // archive canister
     // token
@@ -140,6 +259,66 @@ impl Business for InnerState {
         self.updated(|s| s.business_data.maintain_archives.cycles_recharged(canister_id, cycles))
     }
 
+    // token reconcile
+    fn business_config_token_reconcile_query(&self) -> &TokenReconciliation {
+        &self.business_data.token_reconciliation
+    }
+    fn business_config_token_reconcile_set(&mut self, config: TokenReconcileConfig) {
+        self.updated(|s| s.business_data.token_reconciliation.update_config(config))
+    }
+    fn business_config_token_reconcile_trigger(&self, now: TimestampNanos) -> bool {
+        self.business_data.token_reconciliation.is_trigger(now)
+    }
+    fn business_config_token_reconcile_start(&mut self, now: TimestampNanos) -> bool {
+        self.updated(|s| s.business_data.token_reconciliation.start(now))
+    }
+    fn business_config_token_reconcile_checked(&mut self, now: TimestampNanos) {
+        self.updated(|s| s.business_data.token_reconciliation.checked(now))
+    }
+    fn business_config_token_reconcile_stop(&mut self) {
+        self.updated(|s| s.business_data.token_reconciliation.stop())
+    }
+    fn business_token_balances_sum(&self, token: CanisterId, pools: &HashSet<Account>) -> Option<(Nat, Nat)> {
+        self.token_balances.token_balances_sum(token, pools)
+    }
+    fn business_token_balance_locked_accounts(&self) -> Vec<TokenAccount> {
+        self.token_balances.locked_accounts()
+    }
+    fn business_token_reconcile_report(&mut self, report: TokenReconcileReport) {
+        self.updated(|s| s.business_data.token_reconciliation.report(report))
+    }
+
+    // token fee
+    fn business_config_token_fee_query(&self) -> &HashMap<CanisterId, Nat> {
+        self.tokens.get_ledger_fees()
+    }
+    fn business_config_token_fee_update(&mut self, arg: ArgWithMeta<TokenFeeUpdateArg>) -> Option<Nat> {
+        // ! the unchanged fee polled from ledger is not recorded
+        self.tokens.changed_fee(&arg.arg.token, &arg.arg.fee)?;
+        let mut trace = ic_canister_kit::common::trap(self.request_traces.be_guard_by(arg.clone().into()));
+        ic_canister_kit::common::trap(trace.handle(
+            |trace| {
+                trace.trace(format!(
+                    "*TokenFeeUpdate* `token: [{}], fee: {}`",
+                    arg.arg.token.to_text(),
+                    arg.arg.fee
+                ));
+                let old = self.tokens.update_fee(arg.arg.token, arg.arg.fee);
+                if let Some(old) = &old {
+                    trace.trace(format!("*TokenFeeUpdate* `old fee: {old}`"));
+                }
+                Ok(old)
+            },
+            |_| "Update Token Fee Done".to_string(),
+        ))
+    }
+    fn business_config_token_fee_checking_set(&mut self, checking_interval_ns: u64) {
+        self.updated(|s| s.business_data.token_fee_checking.checking_interval_ns = checking_interval_ns)
+    }
+    fn business_config_token_fee_trigger(&mut self, now: TimestampNanos) -> bool {
+        self.updated(|s| s.business_data.token_fee_checking.is_trigger(now))
+    }
+
     // token frozen
     fn business_config_token_frozen_query(&self) -> &HashSet<CanisterId> {
         self.tokens.get_frozen_tokens()
@@ -212,14 +391,33 @@ impl Business for InnerState {
     }
 
     // set_certified_data
+    fn business_certified_tree(&self) -> HashTree {
+        use ic_certification::{empty, fork, labeled, leaf};
+
+        // labels must be sorted, the hashes are of the internal block chains, they are not icrc3 hashes
+        let mut nodes = Vec::with_capacity(4);
+        if let Some(index) = self.swap_block_chain.get_latest_index() {
+            nodes.push(labeled(
+                "last_swap_block_hash",
+                leaf(self.swap_block_chain.get_latest_hash()),
+            ));
+            nodes.push(labeled("last_swap_block_index", leaf(leb128(index))));
+        }
+        if let Some(index) = self.token_block_chain.get_latest_index() {
+            nodes.push(labeled(
+                "last_token_block_hash",
+                leaf(self.token_block_chain.get_latest_hash()),
+            ));
+            nodes.push(labeled("last_token_block_index", leaf(leb128(index))));
+        }
+        nodes
+            .into_iter()
+            .rev()
+            .reduce(|right, left| fork(left, right))
+            .unwrap_or_else(empty)
+    }
     fn business_certified_data_refresh(&self) {
-        let token_hash = self.token_block_chain.get_latest_hash();
-        let swap_hash = self.swap_block_chain.get_latest_hash();
-        let mut data = Vec::with_capacity(token_hash.len() + swap_hash.len());
-        data.extend_from_slice(token_hash);
-        data.extend_from_slice(swap_hash);
-        let hash = common::utils::hash::hash_sha256(&data);
-        ic_cdk::api::certified_data_set(hash);
+        ic_cdk::api::certified_data_set(self.business_certified_tree().digest());
     }
 
     // ======================== locks ========================
@@ -285,6 +483,9 @@ impl Business for InnerState {
     fn business_dummy_tokens_query(&self) -> HashMap<CanisterId, TokenInfo> {
         self.token_pairs.query_dummy_tokens(&self.business_tokens_query())
     }
+    fn business_outer_lp_tokens_query(&self) -> HashMap<CanisterId, TokenInfo> {
+        self.token_pairs.query_outer_lp_tokens(&self.business_tokens_query())
+    }
     fn business_all_tokens_with_dummy_query(&self) -> HashMap<CanisterId, Cow<'_, TokenInfo>> {
         self.business_tokens_query()
             .into_iter()
@@ -335,6 +536,43 @@ impl Business for InnerState {
             Ok(height)
         })
     }
+    fn business_token_deposit_sweeping(&self, token: CanisterId, account: Account) -> Option<TokenDepositSweeping> {
+        self.business_data
+            .token_deposit_sweeping
+            .get(&(token, account))
+            .cloned()
+    }
+    fn business_token_deposit_sweeping_set(
+        &mut self,
+        _locks: &(TokenBlockChainLock, TokenBalancesLock),
+        token: CanisterId,
+        account: Account,
+        sweeping: Option<TokenDepositSweeping>,
+    ) {
+        self.updated(|s| match sweeping {
+            Some(sweeping) => s
+                .business_data
+                .token_deposit_sweeping
+                .insert((token, account), sweeping),
+            None => s.business_data.token_deposit_sweeping.remove(&(token, account)),
+        });
+    }
+    fn business_token_deposit_notify(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<DepositToken>,
+        height: Nat,
+    ) -> Result<Nat, BusinessError> {
+        let key = (arg.arg.token, arg.arg.to);
+        self.updated(|s| {
+            let mut guard = s.get_token_guard(locks, arg.clone(), None)?;
+            let height = guard.token_deposit(arg, height)?; // do deposit
+            guard.dump(); // * save stable data
+            s.business_data.token_deposit_sweeping.remove(&key); // * the sweep is credited
+            s.business_certified_data_refresh(); // set certified data
+            Ok(height)
+        })
+    }
     fn business_token_withdraw(
         &mut self,
         locks: &(TokenBlockChainLock, TokenBalancesLock),
@@ -371,6 +609,12 @@ impl Business for InnerState {
             .token_pairs
             .query_all_token_pair_pools()
             .into_iter()
+            .chain(
+                self.token_pairs
+                    .query_all_token_pools()
+                    .into_iter()
+                    .map(|(pool, maker)| (pool.anchor(), maker)), // lp of pool is transferred by anchor pair
+            )
             .find(|(_, maker)| maker.dummy_canisters().contains(&arg.arg.token))
             .map(|(pa, _)| pa)
             .ok_or(BusinessError::SystemError("must be lp token".to_string()))?;
@@ -383,6 +627,90 @@ impl Business for InnerState {
         })
     }
 
+    // allowance
+    fn business_token_allowance_get(
+        &self,
+        token: &CanisterId,
+        from: &Account,
+        spender: &Account,
+        now: TimestampNanos,
+    ) -> TokenAllowance {
+        self.business_data.token_allowances.get(token, from, spender, now)
+    }
+    fn business_token_allowance_check_spend(
+        &self,
+        token: &CanisterId,
+        from: &Account,
+        spender: &Account,
+        amount: &Nat,
+        now: TimestampNanos,
+    ) -> Result<(), BusinessError> {
+        self.business_data
+            .token_allowances
+            .check_spend(token, from, spender, amount, now)
+    }
+    fn business_token_allowance_spend(&mut self, token: &CanisterId, from: &Account, spender: &Account, amount: &Nat) {
+        self.updated(|s| s.business_data.token_allowances.spend(token, from, spender, amount))
+    }
+    fn business_token_allowance_spending(
+        &self,
+        token: &CanisterId,
+        from: &Account,
+        spender: &Account,
+        amount: &Nat,
+        now: TimestampNanos,
+    ) -> Result<ApproveToken, BusinessError> {
+        self.business_data
+            .token_allowances
+            .spending(token, from, spender, amount, now)
+    }
+    fn business_token_allowance_restoring(
+        &self,
+        token: &CanisterId,
+        from: &Account,
+        spender: &Account,
+        unused: &Nat,
+        block: BlockIndex,
+        now: TimestampNanos,
+    ) -> Option<ApproveToken> {
+        self.business_data
+            .token_allowances
+            .restoring(token, from, spender, unused, block, now)
+    }
+    fn business_token_icrc_transaction_get(&self, hash: &str) -> Option<BlockIndex> {
+        self.business_data.icrc_transactions.get(hash)
+    }
+    fn business_token_icrc_transaction_insert(
+        &mut self,
+        hash: String,
+        created: TimestampNanos,
+        block_index: BlockIndex,
+        now: TimestampNanos,
+    ) {
+        self.updated(|s| {
+            s.business_data
+                .icrc_transactions
+                .insert(hash, created, block_index, now)
+        })
+    }
+    fn business_token_approve(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<ApproveToken>,
+    ) -> Result<BlockIndex, BusinessError> {
+        self.business_data.token_allowances.check_approve(&arg.arg, arg.now)?;
+        self.updated(|s| {
+            let approve = arg.arg.clone();
+            let mut guard = s.get_token_guard(locks, arg.clone(), None)?;
+            guard.token_approve(arg)?; // do approve
+            guard.dump(); // * save stable data
+            let block_index = s.token_block_chain.get_token_block_chain().next_block_index - 1;
+            s.business_data.token_allowances.approve(&approve, block_index);
+            s.business_certified_data_refresh(); // set certified data
+            Ok(block_index)
+        })
+    }
+
     // ======================== swap block chain ========================
 
     // ======================== token pair swap ========================
@@ -394,11 +722,33 @@ impl Business for InnerState {
     fn business_token_pair_pool_get(&self, pa: &TokenPairAmm) -> Option<MarketMaker> {
         self.token_pairs.get_token_pair_pool(pa)
     }
+    fn business_token_pair_lp_earnings_query(&self, account: &Account) -> Vec<LpEarnings> {
+        self.token_pairs
+            .query_all_token_pair_pools()
+            .into_iter()
+            .filter_map(|(pa, maker)| match maker {
+                MarketMaker::SwapV2(maker) => {
+                    let basis = self.lp_cost_bases.query(account, &pa)?;
+                    // lp staked in farms is still held by the account
+                    let lp_token = pa.get_subaccount_and_dummy_canister_id().1.id();
+                    let balance = self
+                        .token_balances
+                        .token_balance_of(lp_token, *account)
+                        .unwrap_or_default();
+                    let held = balance + self.business_data.farms.staked(&pa, account);
+                    Some(basis.earnings(pa, &maker, &held))
+                }
+                _ => None, // cost basis is tracked for swap v2 only
+            })
+            .collect()
+    }
     // create and remove
     fn business_token_pair_pool_create(
         &mut self,
         lock: &SwapBlockChainLock,
         arg: ArgWithMeta<TokenPairAmm>,
+        weights: Option<(u32, u32)>,
+        sqrt_price_x96: Option<Nat>,
     ) -> Result<MarketMaker, BusinessError> {
         self.updated(|s| {
             let tokens = s.business_tokens_query();
@@ -412,6 +762,11 @@ impl Business for InnerState {
                 .ok_or(BusinessError::NotSupportedToken(arg.arg.pair.get_token1()))?
                 .clone()
                 .into_owned();
+            let protocol_fee = s
+                .business_data
+                .fee_tiers
+                .get(&arg.arg.amm)
+                .and_then(|tier| tier.protocol_fee.clone());
 
             let mut swap_guard = s.swap_block_chain.be_guard(lock);
             let mut trace_guard = s.request_traces.be_guard(
@@ -422,9 +777,16 @@ impl Business for InnerState {
                 None,
                 None,
             )?;
-            let maker =
-                s.token_pairs
-                    .create_token_pair_pool(&mut swap_guard, &mut trace_guard, arg, &token0, &token1)?;
+            let maker = s.token_pairs.create_token_pair_pool(
+                &mut swap_guard,
+                &mut trace_guard,
+                arg,
+                &token0,
+                &token1,
+                weights,
+                sqrt_price_x96,
+                protocol_fee,
+            )?;
             swap_guard.dump(); // * save stable data
             s.business_certified_data_refresh(); // set certified data
             Ok(maker)
@@ -461,8 +823,24 @@ impl Business for InnerState {
     ) -> Result<TokenPairLiquidityAddSuccess, BusinessError> {
         self.updated(|s| {
             let mut guard = s.get_pair_swap_guard(locks, arg.clone(), None)?;
-            let success = guard.add_liquidity(arg)?;
+            let success = guard.add_liquidity(arg.clone())?;
             guard.dump(); // * save stable data
+            // * record cost basis of liquidity
+            if let Some(MarketMaker::SwapV2(maker)) = s.token_pairs.get_token_pair_pool(&arg.arg.pa) {
+                let amount = if arg.arg.token_a == maker.token0 {
+                    success.amount.clone()
+                } else {
+                    (success.amount.1.clone(), success.amount.0.clone())
+                };
+                let deposit = LpDeposit {
+                    block: s.swap_block_chain.get_swap_block_chain().next_block_index - 1, // the mint block
+                    liquidity: success.liquidity.clone(),
+                    amount,
+                    reserves: (maker.reserve0.clone(), maker.reserve1.clone()),
+                    total_supply: maker.lp.get_total_supply(),
+                };
+                s.lp_cost_bases.mint(arg.arg.to, arg.arg.pa, deposit);
+            }
             s.business_certified_data_refresh(); // set certified data
             Ok(success)
         })
@@ -484,8 +862,15 @@ impl Business for InnerState {
     ) -> Result<TokenPairLiquidityRemoveSuccess, BusinessError> {
         self.updated(|s| {
             let mut guard = s.get_pair_swap_guard(locks, arg.clone(), None)?;
-            let success = guard.remove_liquidity(arg)?;
+            let success = guard.remove_liquidity(arg.clone())?;
             guard.dump(); // * save stable data
+            // * reduce cost basis of liquidity, burn fee is paid by liquidity too
+            let fee = arg.arg.fee.as_ref().map(|f| f.fee.clone()).unwrap_or_default();
+            s.lp_cost_bases.burn(
+                &arg.arg.from,
+                &arg.arg.pa,
+                &(arg.arg.liquidity_without_fee.clone() + fee),
+            );
             s.business_certified_data_refresh(); // set certified data
             Ok(success)
         })
@@ -555,6 +940,602 @@ impl Business for InnerState {
             Ok(success)
         })
     }
+    fn business_token_pair_swap_split(
+        &mut self,
+        locks: &AllLocks,
+        arg: ArgWithMeta<TokenPairSwapSplitArg>,
+    ) -> Result<TokenPairSwapSplitSuccess, BusinessError> {
+        self.updated(|s| {
+            let mut guard = s.get_pair_swap_guard(locks, arg.clone(), None)?;
+            let success = guard.swap_split(arg)?;
+            guard.dump(); // * save stable data
+            s.business_certified_data_refresh(); // set certified data
+            Ok(success)
+        })
+    }
+
+    // ======================== token pool ========================
+
+    // query
+    fn business_token_pools_query(&self) -> Vec<(TokenPoolAmm, MarketMaker)> {
+        self.token_pairs.query_all_token_pools()
+    }
+    fn business_token_pool_get(&self, pool: &TokenPoolAmm) -> Option<MarketMaker> {
+        self.token_pairs.get_token_pool(pool)
+    }
+    fn business_token_pool_find(&self, pa: &TokenPairAmm) -> Option<TokenPoolAmm> {
+        self.token_pairs.find_token_pool(pa)
+    }
+    // create and remove
+    fn business_token_pool_create(
+        &mut self,
+        lock: &SwapBlockChainLock,
+        arg: ArgWithMeta<TokenPoolAmm>,
+    ) -> Result<MarketMaker, BusinessError> {
+        self.updated(|s| {
+            let tokens = s.business_tokens_query();
+            let tokens = arg
+                .arg
+                .pool
+                .get_tokens()
+                .iter()
+                .map(|token| {
+                    tokens
+                        .get(token)
+                        .map(|info| info.clone().into_owned())
+                        .ok_or(BusinessError::NotSupportedToken(*token))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let tokens = tokens.iter().collect::<Vec<_>>();
+            let protocol_fee = s
+                .business_data
+                .fee_tiers
+                .get(&arg.arg.amm)
+                .and_then(|tier| tier.protocol_fee.clone());
+
+            let mut swap_guard = s.swap_block_chain.be_guard(lock);
+            let mut trace_guard = s.request_traces.be_guard(
+                arg.clone().into_pool_create_request_args(),
+                None,
+                Some(&swap_guard),
+                None,
+                None,
+                None,
+            )?;
+            let maker =
+                s.token_pairs
+                    .create_token_pool(&mut swap_guard, &mut trace_guard, arg, &tokens, protocol_fee)?;
+            swap_guard.dump(); // * save stable data
+            s.business_certified_data_refresh(); // set certified data
+            Ok(maker)
+        })
+    }
+    fn business_token_pool_remove(
+        &mut self,
+        locks: &(SwapBlockChainLock, TokenPairsLock),
+        arg: ArgWithMeta<TokenPoolAmm>,
+    ) -> Result<MarketMaker, BusinessError> {
+        self.updated(|s| {
+            let mut swap_guard = s.swap_block_chain.be_guard(&locks.0);
+            let mut pairs_guard = s.token_pairs.be_guard(&locks.1);
+            let mut trace_guard = s.request_traces.be_guard(
+                arg.clone().into_pool_remove_request_args(),
+                None,
+                Some(&swap_guard),
+                None,
+                None,
+                None,
+            )?;
+            let maker = pairs_guard.remove_token_pool(&mut swap_guard, &mut trace_guard, arg)?;
+            pairs_guard.dump(); // * save stable data
+            swap_guard.dump(); // * save stable data
+            s.business_certified_data_refresh(); // set certified data
+            Ok(maker)
+        })
+    }
+    // liquidity
+    fn business_token_pool_liquidity_add(
+        &mut self,
+        locks: &AllLocks,
+        arg: ArgWithMeta<TokenPoolLiquidityAddArg>,
+    ) -> Result<TokenPoolLiquidityAddSuccess, BusinessError> {
+        self.updated(|s| {
+            let mut guard = s.get_pair_swap_guard(locks, arg.clone(), None)?;
+            let success = guard.add_pool_liquidity(arg)?;
+            guard.dump(); // * save stable data
+            s.business_certified_data_refresh(); // set certified data
+            Ok(success)
+        })
+    }
+    fn business_token_pool_liquidity_remove(
+        &mut self,
+        locks: &AllLocks,
+        arg: ArgWithMeta<TokenPoolLiquidityRemoveArg>,
+    ) -> Result<TokenPoolLiquidityRemoveSuccess, BusinessError> {
+        self.updated(|s| {
+            let mut guard = s.get_pair_swap_guard(locks, arg.clone(), None)?;
+            let success = guard.remove_pool_liquidity(arg)?;
+            guard.dump(); // * save stable data
+            s.business_certified_data_refresh(); // set certified data
+            Ok(success)
+        })
+    }
+
+    // ======================== token pair flash loan ========================
+
+    // query
+    fn business_token_pair_flash_loans_query(&self, borrower: Option<&Account>) -> Vec<FlashLoan> {
+        self.business_data.flash_loans.query(borrower)
+    }
+    fn business_token_pair_flash_loan_get(&self, id: u64) -> Option<FlashLoan> {
+        self.business_data.flash_loans.get(id).cloned()
+    }
+    fn business_token_pair_flash_loan_borrowing(&self, account: &Account) -> bool {
+        self.business_data.flash_loans.is_borrowing(account)
+    }
+    fn business_token_pair_flash_loan_lending(&self, pa: &TokenPairAmm) -> bool {
+        self.business_data.flash_loans.is_lending(pa)
+    }
+    // lend and settle
+    fn business_token_pair_flash_loan_lend(
+        &mut self,
+        locks: &AllLocks,
+        mut arg: ArgWithMeta<FlashLoan>,
+    ) -> Result<FlashLoan, BusinessError> {
+        self.updated(|s| {
+            if s.business_data.flash_loans.is_lending(&arg.arg.pa) {
+                return Err(BusinessError::Swap("FLASH_LOAN_NOT_REPAID".into()));
+            }
+            arg.arg.id = s.business_data.flash_loans.next_id();
+            let mut guard = s.get_pair_swap_guard(locks, arg.clone().into_pair_flash_loan_lend_request_args(), None)?;
+            let loan = guard.flash_loan_lend(arg)?;
+            guard.dump(); // * save stable data
+            s.business_data.flash_loans.insert(loan.clone());
+            s.business_certified_data_refresh(); // set certified data
+            Ok(loan)
+        })
+    }
+    fn business_token_pair_flash_loan_repay(
+        &mut self,
+        locks: &AllLocks,
+        arg: ArgWithMeta<FlashLoan>,
+    ) -> Result<FlashLoan, BusinessError> {
+        self.updated(|s| {
+            if s.business_data.flash_loans.get(arg.arg.id).is_none() {
+                return Err(BusinessError::Swap("FLASH_LOAN_NOT_EXIST".into()));
+            }
+            let trace = format!("*FlashLoanRepay* `id:{}`", arg.arg.id);
+            let mut guard = s.get_pair_swap_guard(
+                locks,
+                arg.clone().into_pair_flash_loan_repay_request_args(),
+                Some(trace),
+            )?;
+            let loan = guard.flash_loan_repay(arg)?;
+            guard.dump(); // * save stable data
+            s.business_data.flash_loans.remove(loan.id);
+            s.business_certified_data_refresh(); // set certified data
+            Ok(loan)
+        })
+    }
+    fn business_token_pair_flash_loan_default(
+        &mut self,
+        locks: &AllLocks,
+        arg: ArgWithMeta<FlashLoan>,
+    ) -> Result<FlashLoan, BusinessError> {
+        self.updated(|s| {
+            if s.business_data.flash_loans.get(arg.arg.id).is_none() {
+                return Err(BusinessError::Swap("FLASH_LOAN_NOT_EXIST".into()));
+            }
+            let trace = format!("*FlashLoanDefault* `id:{}`", arg.arg.id);
+            let mut guard = s.get_pair_swap_guard(
+                locks,
+                arg.clone().into_pair_flash_loan_default_request_args(),
+                Some(trace),
+            )?;
+            let loan = guard.flash_loan_default(arg)?;
+            guard.dump(); // * save stable data
+            s.business_data.flash_loans.remove(loan.id);
+            s.business_certified_data_refresh(); // set certified data
+            Ok(loan)
+        })
+    }
+
+    // ======================== limit order ========================
+
+    // query
+    fn business_limit_orders_query(&self, owner: Option<&Account>) -> Vec<LimitOrder> {
+        self.business_data.limit_orders.query(owner)
+    }
+    fn business_limit_order_get(&self, id: u64) -> Option<LimitOrder> {
+        self.business_data.limit_orders.get(id).cloned()
+    }
+    // place and cancel
+    fn business_limit_order_place(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        mut arg: ArgWithMeta<LimitOrder>,
+    ) -> Result<LimitOrder, BusinessError> {
+        self.updated(|s| {
+            s.business_data.limit_orders.check_capacity(&arg.arg.owner)?;
+            arg.arg.id = s.business_data.limit_orders.next_id();
+            let order = arg.arg.clone();
+            let mut guard = s.get_token_guard(locks, arg.clone().into_limit_order_place_request_args(), None)?;
+            // escrow token in
+            guard.token_transfer(ArgWithMeta {
+                now: arg.now,
+                caller: arg.caller,
+                arg: limit_order_escrow_transfer(
+                    &order,
+                    LimitOrder::escrow(ic_canister_kit::identity::self_canister_id()),
+                ),
+                memo: arg.memo,
+                created: arg.created,
+            })?;
+            guard.dump(); // * save stable data
+            s.business_data.limit_orders.insert(order.clone());
+            s.business_certified_data_refresh(); // set certified data
+            Ok(order)
+        })
+    }
+    fn business_limit_order_cancel(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<LimitOrder>,
+    ) -> Result<Nat, BusinessError> {
+        self.updated(|s| {
+            let order = arg.arg.clone();
+            if s.business_data.limit_orders.get(order.id).is_none() {
+                return Err(BusinessError::Swap("LIMIT_ORDER_NOT_EXIST".into()));
+            }
+            let mut guard = s.get_token_guard(locks, arg.clone().into_limit_order_cancel_request_args(), None)?;
+            // return escrowed token in
+            let changed = guard.token_transfer(ArgWithMeta {
+                now: arg.now,
+                caller: arg.caller,
+                arg: limit_order_refund_transfer(
+                    &order,
+                    LimitOrder::escrow(ic_canister_kit::identity::self_canister_id()),
+                ),
+                memo: arg.memo,
+                created: arg.created,
+            })?;
+            guard.dump(); // * save stable data
+            s.business_data.limit_orders.remove(order.id);
+            s.business_certified_data_refresh(); // set certified data
+            Ok(changed)
+        })
+    }
+    // fill
+    fn business_limit_orders_next_batch(&mut self) -> Vec<LimitOrder> {
+        self.updated(|s| s.business_data.limit_orders.next_batch())
+    }
+    fn business_limit_order_fill(
+        &mut self,
+        locks: &AllLocks,
+        id: u64,
+        arg: ArgWithMeta<TokenPairSwapExactTokensForTokensArg>,
+    ) -> Result<TokenPairSwapTokensSuccess, BusinessError> {
+        self.updated(|s| {
+            if s.business_data.limit_orders.get(id).is_none() {
+                return Err(BusinessError::Swap("LIMIT_ORDER_NOT_EXIST".into()));
+            }
+            let trace = format!("*LimitOrderFill* `id:{id}`");
+            let mut guard = s.get_pair_swap_guard(locks, arg.clone(), Some(trace))?;
+            let success = guard.swap_exact_tokens_for_tokens(arg)?;
+            guard.dump(); // * save stable data
+            s.business_data.limit_orders.remove(id);
+            s.business_certified_data_refresh(); // set certified data
+            Ok(success)
+        })
+    }
+
+    // ======================== dca order ========================
+
+    // query
+    fn business_dca_orders_query(&self, owner: Option<&Account>) -> Vec<DcaOrder> {
+        self.business_data.dca_orders.query(owner)
+    }
+    fn business_dca_order_get(&self, id: u64) -> Option<DcaOrder> {
+        self.business_data.dca_orders.get(id).cloned()
+    }
+    // place and cancel
+    fn business_dca_order_place(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        mut arg: ArgWithMeta<DcaOrder>,
+    ) -> Result<DcaOrder, BusinessError> {
+        self.updated(|s| {
+            s.business_data.dca_orders.check_capacity(&arg.arg.owner)?;
+            arg.arg.id = s.business_data.dca_orders.next_id();
+            let order = arg.arg.clone();
+            let mut guard = s.get_token_guard(locks, arg.clone().into_dca_order_place_request_args(), None)?;
+            // escrow token in
+            guard.token_transfer(ArgWithMeta {
+                now: arg.now,
+                caller: arg.caller,
+                arg: dca_order_escrow_transfer(&order, DcaOrder::escrow(ic_canister_kit::identity::self_canister_id())),
+                memo: arg.memo,
+                created: arg.created,
+            })?;
+            guard.dump(); // * save stable data
+            s.business_data.dca_orders.insert(order.clone());
+            s.business_certified_data_refresh(); // set certified data
+            Ok(order)
+        })
+    }
+    fn business_dca_order_cancel(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<DcaOrder>,
+    ) -> Result<Nat, BusinessError> {
+        self.updated(|s| {
+            let order = s
+                .business_data
+                .dca_orders
+                .get(arg.arg.id)
+                .cloned()
+                .ok_or_else(|| BusinessError::Swap("DCA_ORDER_NOT_EXIST".into()))?;
+            let mut guard = s.get_token_guard(locks, arg.clone().into_dca_order_cancel_request_args(), None)?;
+            // return unexecuted token in
+            let changed = guard.token_transfer(ArgWithMeta {
+                now: arg.now,
+                caller: arg.caller,
+                arg: dca_order_refund_transfer(&order, DcaOrder::escrow(ic_canister_kit::identity::self_canister_id())),
+                memo: arg.memo,
+                created: arg.created,
+            })?;
+            guard.dump(); // * save stable data
+            s.business_data.dca_orders.remove(order.id);
+            s.business_certified_data_refresh(); // set certified data
+            Ok(changed)
+        })
+    }
+    // execute
+    fn business_dca_orders_next_batch(&mut self, now: TimestampNanos) -> Vec<DcaOrder> {
+        self.updated(|s| s.business_data.dca_orders.next_batch(now))
+    }
+    fn business_dca_order_execute(
+        &mut self,
+        locks: &AllLocks,
+        id: u64,
+        arg: ArgWithMeta<TokenPairSwapExactTokensForTokensArg>,
+    ) -> Result<TokenPairSwapTokensSuccess, BusinessError> {
+        self.updated(|s| {
+            let order = s
+                .business_data
+                .dca_orders
+                .get(id)
+                .ok_or_else(|| BusinessError::Swap("DCA_ORDER_NOT_EXIST".into()))?;
+            let slice = order.executed_slices + 1;
+            let next_execution = order.next_execution_after(arg.now)?; // ! check before swapping
+            let amount_in = arg.arg.amount_in.clone();
+            let trace = format!("*DcaOrderSlice* `id:{id}, slice:{slice}`");
+            let mut guard = s.get_pair_swap_guard(locks, arg.clone(), Some(trace))?;
+            let success = guard.swap_exact_tokens_for_tokens(arg)?;
+            guard.dump(); // * save stable data
+            let amount_out = success.amounts[success.amounts.len() - 1].clone();
+            if s.business_data
+                .dca_orders
+                .get_mut(id)
+                .is_some_and(|order| order.executed(next_execution, amount_in, amount_out))
+            {
+                s.business_data.dca_orders.remove(id); // all slices are executed
+            }
+            s.business_certified_data_refresh(); // set certified data
+            Ok(success)
+        })
+    }
+
+    // ======================== protocol fees ========================
+
+    fn business_protocol_fees_collect(
+        &mut self,
+        locks: &AllLocks,
+        arg: ArgWithMeta<ProtocolFeesCollectArg>,
+    ) -> Result<ProtocolFeesCollectSuccess, BusinessError> {
+        self.updated(|s| {
+            let mut guard = s.get_pair_swap_guard(locks, arg.clone(), None)?;
+            let success = guard.protocol_fees_collect(arg.clone())?;
+            guard.dump(); // * save stable data
+            // * reduce cost basis of liquidity
+            for burned in &success.burned {
+                s.lp_cost_bases
+                    .burn(&arg.arg.swap_fee_to, &burned.pa, &burned.liquidity);
+            }
+            s.business_certified_data_refresh(); // set certified data
+            Ok(success)
+        })
+    }
+
+    // ======================== farm ========================
+
+    // query
+    fn business_farms_query(&self, pa: Option<&TokenPairAmm>) -> Vec<FarmStatus> {
+        self.business_data.farms.query(pa)
+    }
+    fn business_farm_get(&self, id: u64) -> Option<FarmStatus> {
+        self.business_data.farms.get(id).map(|state| state.status.clone())
+    }
+    fn business_farm_staker_get(&self, id: u64, account: &Account, now: TimestampNanos) -> Option<FarmStaker> {
+        self.business_data.farms.get(id)?.staker(account, now)
+    }
+    // update
+    fn business_farm_create(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        mut arg: ArgWithMeta<Farm>,
+    ) -> Result<Farm, BusinessError> {
+        self.updated(|s| {
+            arg.arg.id = s.business_data.farms.next_id();
+            let farm = arg.arg.clone();
+            let mut guard = s.get_token_guard(locks, arg.clone().into_farm_create_request_args(), None)?;
+            // escrow all rewards
+            guard.token_transfer(ArgWithMeta {
+                now: arg.now,
+                caller: arg.caller,
+                arg: TransferToken {
+                    token: farm.reward_token,
+                    from: farm.funder,
+                    amount: farm.total_reward(),
+                    to: Farm::escrow(ic_canister_kit::identity::self_canister_id()),
+                    fee: None,
+                    spender: None,
+                },
+                memo: arg.memo,
+                created: arg.created,
+            })?;
+            guard.dump(); // * save stable data
+            s.business_data.farms.create(farm.clone());
+            s.business_certified_data_refresh(); // set certified data
+            Ok(farm)
+        })
+    }
+    fn business_farm_refund(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<Farm>,
+    ) -> Result<Nat, BusinessError> {
+        self.updated(|s| {
+            let mut state = s
+                .business_data
+                .farms
+                .get(arg.arg.id)
+                .cloned()
+                .ok_or_else(|| BusinessError::Swap("FARM_NOT_EXIST".into()))?;
+            let refund = state.refund(arg.now)?;
+            if refund != zero() {
+                let mut guard = s.get_token_guard(locks, arg.clone().into_farm_refund_request_args(), None)?;
+                // return unallocated rewards
+                guard.token_transfer(ArgWithMeta {
+                    now: arg.now,
+                    caller: arg.caller,
+                    arg: TransferToken {
+                        token: arg.arg.reward_token,
+                        from: Farm::escrow(ic_canister_kit::identity::self_canister_id()),
+                        amount: refund.clone(),
+                        to: arg.arg.funder,
+                        fee: None,
+                        spender: None,
+                    },
+                    memo: arg.memo,
+                    created: arg.created,
+                })?;
+                guard.dump(); // * save stable data
+            }
+            s.business_data.farms.replace(state);
+            s.business_certified_data_refresh(); // set certified data
+            Ok(refund)
+        })
+    }
+    fn business_farm_stake(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<FarmStakeArg>,
+    ) -> Result<Nat, BusinessError> {
+        self.updated(|s| {
+            let mut state = s
+                .business_data
+                .farms
+                .get(arg.arg.farm)
+                .cloned()
+                .ok_or_else(|| BusinessError::Swap("FARM_NOT_EXIST".into()))?;
+            state.stake(&arg.arg.account, &arg.arg.amount, arg.now)?;
+            let mut guard = s.get_token_guard(locks, arg.clone().into_farm_stake_request_args(), None)?;
+            // escrow staked lp
+            let changed = guard.token_transfer(ArgWithMeta {
+                now: arg.now,
+                caller: arg.caller,
+                arg: TransferToken {
+                    token: state.status.farm.lp_token,
+                    from: arg.arg.account,
+                    amount: arg.arg.amount.clone(),
+                    to: Farm::escrow(ic_canister_kit::identity::self_canister_id()),
+                    fee: None,
+                    spender: None,
+                },
+                memo: arg.memo,
+                created: arg.created,
+            })?;
+            guard.dump(); // * save stable data
+            s.business_data.farms.replace(state);
+            s.business_certified_data_refresh(); // set certified data
+            Ok(changed)
+        })
+    }
+    fn business_farm_unstake(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        arg: ArgWithMeta<FarmStakeArg>,
+    ) -> Result<Nat, BusinessError> {
+        self.updated(|s| {
+            let mut state = s
+                .business_data
+                .farms
+                .get(arg.arg.farm)
+                .cloned()
+                .ok_or_else(|| BusinessError::Swap("FARM_NOT_EXIST".into()))?;
+            state.unstake(&arg.arg.account, &arg.arg.amount, arg.now)?;
+            let mut guard = s.get_token_guard(locks, arg.clone().into_farm_unstake_request_args(), None)?;
+            // return staked lp
+            let changed = guard.token_transfer(ArgWithMeta {
+                now: arg.now,
+                caller: arg.caller,
+                arg: TransferToken {
+                    token: state.status.farm.lp_token,
+                    from: Farm::escrow(ic_canister_kit::identity::self_canister_id()),
+                    amount: arg.arg.amount.clone(),
+                    to: arg.arg.account,
+                    fee: None,
+                    spender: None,
+                },
+                memo: arg.memo,
+                created: arg.created,
+            })?;
+            guard.dump(); // * save stable data
+            s.business_data.farms.replace(state);
+            s.business_certified_data_refresh(); // set certified data
+            Ok(changed)
+        })
+    }
+    fn business_farm_claim(
+        &mut self,
+        locks: &(TokenBlockChainLock, TokenBalancesLock),
+        mut arg: ArgWithMeta<FarmStakeArg>,
+    ) -> Result<Nat, BusinessError> {
+        self.updated(|s| {
+            let mut state = s
+                .business_data
+                .farms
+                .get(arg.arg.farm)
+                .cloned()
+                .ok_or_else(|| BusinessError::Swap("FARM_NOT_EXIST".into()))?;
+            let reward = state.claim(&arg.arg.account, arg.now)?;
+            if reward == zero() {
+                return Err(BusinessError::Swap("NO_FARM_REWARD".into()));
+            }
+            arg.arg.amount = reward.clone(); // * trace the claimed rewards
+            let mut guard = s.get_token_guard(locks, arg.clone().into_farm_claim_request_args(), None)?;
+            // pay rewards
+            guard.token_transfer(ArgWithMeta {
+                now: arg.now,
+                caller: arg.caller,
+                arg: TransferToken {
+                    token: state.status.farm.reward_token,
+                    from: Farm::escrow(ic_canister_kit::identity::self_canister_id()),
+                    amount: reward.clone(),
+                    to: arg.arg.account,
+                    fee: None,
+                    spender: None,
+                },
+                memo: arg.memo,
+                created: arg.created,
+            })?;
+            guard.dump(); // * save stable data
+            s.business_data.farms.replace(state);
+            s.business_certified_data_refresh(); // set certified data
+            Ok(reward)
+        })
+    }
 
     // ======================== blocks query ========================
 
@@ -606,3 +1587,17 @@ impl Business for InnerState {
         Ok(())
     }
 }
+
+// unsigned leb128 encoding of block index
+fn leb128(mut value: u64) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(10);
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            return bytes;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
@@ -22,13 +22,46 @@ async fn inner_task(caller: Option<CallerId>) {
     ic_cdk::println!("do something: {:?}", caller.map(|c| c.to_text()));
 
     let now = TimestampNanos::now();
+
+    // ? the tasks without waiting for other canisters run first
+
+    // settle flash loans left by lending
+    crate::business::pair::flash_loan::schedule_flash_loans();
+
+    // fill or return limit orders
+    crate::business::limit_order::schedule_limit_orders();
+
+    // execute slices of dca orders
+    crate::business::dca_order::schedule_dca_orders();
+
+    // ? the tasks waiting for other canisters run independently, none of them holds the others back
+
     if with_mut_state(|s| s.business_config_maintain_trigger(now)) {
-        let mut trace = RequestTrace::from_args(RequestArgs::CanistersMaintaining);
-        let result = maintaining_canisters(&mut trace).await;
-        with_mut_state(|s| s.business_request_trace_insert(trace));
-        if let Err(err) = result {
-            ic_cdk::println!("maintaining_canisters err: {err:?}");
-        }
+        ic_cdk::futures::spawn(async {
+            let mut trace = RequestTrace::from_args(RequestArgs::CanistersMaintaining);
+            let result = maintaining_canisters(&mut trace).await;
+            with_mut_state(|s| s.business_request_trace_insert(trace));
+            if let Err(err) = result {
+                ic_cdk::println!("maintaining_canisters err: {err:?}");
+            }
+        });
+    }
+
+    // poll the fee of ledgers
+    if with_mut_state(|s| s.business_config_token_fee_trigger(now)) {
+        ic_cdk::futures::spawn(async {
+            crate::business::config::token_fee::check_token_fees().await;
+        });
+    }
+
+    // compare internal balances with ledgers
+    if with_state(|s| s.business_config_token_reconcile_trigger(now)) {
+        ic_cdk::futures::spawn(async {
+            let result = crate::business::config::reconcile::reconcile_tokens().await;
+            if let Err(err) = result {
+                ic_cdk::println!("reconcile_tokens err: {err:?}");
+            }
+        });
     }
 }
 
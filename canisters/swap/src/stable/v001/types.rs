@@ -36,40 +36,74 @@ pub use crate::types::business::*;
 pub use crate::types::common::*;
 #[allow(unused)]
 pub use crate::types::{
-    Account, AllLocks, Amm, AmmText, ArgWithMeta, BlockIndex, BurnFee, BusinessError, Caller, CandidBlock,
-    DepositToken, DoHash, DummyCanisterId, EncodedBlock, HashOf, MarketMaker, MarketMakerView, Nat, PairCreate,
-    PairOperation, PairRemove, PairSwapToken, QueryBlockResult, QuerySwapBlockResult, QueryTokenBlockResult,
-    RequestArgs, RequestIndex, RequestTrace, SelfCanister, SwapBlock, SwapOperation, SwapRatio, SwapTransaction,
-    SwapV2BurnToken, SwapV2MarketMaker, SwapV2MintFeeToken, SwapV2MintToken, SwapV2Operation, SwapV2State,
-    SwapV2TransferToken, TimestampNanos, TokenAccount, TokenBlock, TokenFrozenArg, TokenInfo, TokenOperation,
-    TokenPair, TokenPairAmm, TokenPairLiquidityAddArg, TokenPairLiquidityAddSuccessView, TokenPairLiquidityRemoveArg,
-    TokenPairPool, TokenPairSwapByLoanArg, TokenPairSwapExactTokensForTokensArg, TokenPairSwapTokensForExactTokensArg,
-    TokenTransaction, TransferFee, TransferToken, UserId, WithdrawToken, display_account, proto,
+    Account, AllLocks, Amm, AmmText, ApproveToken, ArgWithMeta, BlockIndex, BurnFee, BusinessError, Caller,
+    CandidBlock, DcaOrder, DepositToken, DoHash, DummyCanisterId, EncodedBlock, Farm, FarmStakeArg, FlashLoan, HashOf,
+    HashTree, LimitOrder, MarketMaker, MarketMakerView, Nat, PairCreate, PairFlashLoan, PairOperation, PairRemove,
+    PairSwapToken, PmmV1MarketMaker, PmmV1Operation, PmmV1Price, PmmV1RStatus, PmmV1State, ProtocolFeeRecipient,
+    ProtocolFeesCollectArg, QueryBlockResult, QuerySwapBlockResult, QueryTokenBlockResult, RequestArgs, RequestIndex,
+    RequestTrace, SelfCanister, StablePoolBurnToken, StablePoolMarketMaker, StablePoolMintToken, StablePoolOperation,
+    StablePoolState, StableSwapMarketMaker, StableSwapOperation, StableSwapState, SwapBlock, SwapOperation, SwapRatio,
+    SwapTransaction, SwapV2BurnToken, SwapV2DynamicFee, SwapV2DynamicFeeArg, SwapV2MarketMaker, SwapV2MintFeeToken,
+    SwapV2MintToken, SwapV2Operation, SwapV2State, SwapV2TransferToken, SwapV2Twap, SwapV3BurnToken, SwapV3MarketMaker,
+    SwapV3MintToken, SwapV3Operation, SwapV3Positions, SwapV3SwapToken, SwapV3Ticks, TickRange, TimestampNanos,
+    TokenAccount, TokenBlock, TokenFeeUpdateArg, TokenFrozenArg, TokenInfo, TokenOperation, TokenPair, TokenPairAmm,
+    TokenPairLiquidityAddArg, TokenPairLiquidityAddSuccessView, TokenPairLiquidityRemoveArg, TokenPairPool,
+    TokenPairSwapByLoanArg, TokenPairSwapExactTokensForTokensArg, TokenPairSwapSplitArg, TokenPairSwapSplitLeg,
+    TokenPairSwapTokensForExactTokensArg, TokenPool, TokenPoolAmm, TokenPoolLiquidityAddArg,
+    TokenPoolLiquidityRemoveArg, TokenTransaction, TransferFee, TransferToken, UserId, WeightedMarketMaker,
+    WeightedOperation, WeightedState, WithdrawToken, display_account, proto,
 };
 
 mod common;
 #[allow(unused)]
 pub use common::*;
 
+mod allowance;
 mod balance;
 mod blockchain;
+mod dca_order;
+mod farm;
+mod fee_tier;
 mod fee_to;
+mod flash_loan;
+mod limit_order;
+mod lp_cost;
 mod maintain;
 mod pair;
+mod reconcile;
 mod request;
 mod token;
 
+#[cfg(test)]
+pub mod testing;
+
+#[allow(unused)]
+pub use allowance::*;
 #[allow(unused)]
 pub use balance::*;
 #[allow(unused)]
 pub use blockchain::*;
 #[allow(unused)]
+pub use dca_order::*;
+#[allow(unused)]
+pub use farm::*;
+#[allow(unused)]
+pub use fee_tier::*;
+#[allow(unused)]
 pub use fee_to::*;
 #[allow(unused)]
+pub use flash_loan::*;
+#[allow(unused)]
+pub use limit_order::*;
+#[allow(unused)]
+pub use lp_cost::*;
+#[allow(unused)]
 pub use maintain::*;
 #[allow(unused)]
 pub use pair::*;
 #[allow(unused)]
+pub use reconcile::*;
+#[allow(unused)]
 pub use request::*;
 #[allow(unused)]
 pub use token::*;
@@ -87,6 +121,36 @@ pub struct BusinessData {
     pub updated: TimestampNanos,             // Record the last update time of the canister
     pub fee_to: FeeTo, // Record the agreement fee collector account, lp token transfer also requires the collection of transfer fees
     pub maintain_archives: MaintainArchives, // Maintain canister information
+    #[serde(default)]
+    pub fee_tiers: FeeTiers, // Fee tiers registered by maintainers
+    #[serde(default = "default_router_max_hops")]
+    pub router_max_hops: u8, // Max hops of path searched by router
+    #[serde(default)]
+    pub limit_orders: LimitOrders, // Resting limit orders, token in is escrowed
+    #[serde(default)]
+    pub dca_orders: DcaOrders, // Running dca orders, token in is escrowed
+    #[serde(default)]
+    pub flash_loans: FlashLoans, // Outstanding flash loans, settled after callback
+    #[serde(default)]
+    pub protocol_fee_recipients: Vec<ProtocolFeeRecipient>, // Collected protocol fees are split by weights
+    #[serde(default)]
+    pub farms: Farms, // Reward farms of lp, staked lp and rewards are escrowed
+    #[serde(default)]
+    pub token_allowances: TokenAllowances, // Allowances of spenders on internal balances
+    #[serde(default)]
+    pub icrc_transactions: IcrcTransactions, // Recent icrc transactions with created_at_time, for deduplication
+    #[serde(default)]
+    pub token_deposit_sweeping: std::collections::BTreeMap<(CanisterId, Account), TokenDepositSweeping>, // Sweeps sent to ledger but not credited
+    #[serde(default)]
+    pub token_reconciliation: TokenReconciliation, // Internal balances compared with real ledgers
+    #[serde(default)]
+    pub token_fee_checking: TokenFeeChecking, // Poll the fee of ledgers
+}
+
+// Default max hops of router
+const DEFAULT_ROUTER_MAX_HOPS: u8 = 3;
+fn default_router_max_hops() -> u8 {
+    DEFAULT_ROUTER_MAX_HOPS
 }
 
 impl Default for BusinessData {
@@ -95,6 +159,18 @@ impl Default for BusinessData {
             updated: TimestampNanos::from_inner(0),
             fee_to: Default::default(),
             maintain_archives: Default::default(),
+            fee_tiers: Default::default(),
+            router_max_hops: DEFAULT_ROUTER_MAX_HOPS,
+            limit_orders: Default::default(),
+            dca_orders: Default::default(),
+            flash_loans: Default::default(),
+            protocol_fee_recipients: Default::default(),
+            farms: Default::default(),
+            token_allowances: Default::default(),
+            icrc_transactions: Default::default(),
+            token_deposit_sweeping: Default::default(),
+            token_reconciliation: Default::default(),
+            token_fee_checking: Default::default(),
         }
     }
 }
@@ -118,6 +194,8 @@ pub struct InnerState {
 
     pub token_pairs: TokenPairs, // Business data, Record transaction pair data //  ? Heap memory Serialization Stable memory
     pub token_balances: TokenBalances, // Business data, Record account balance data //  ? Heap memory Serialization Stable memory
+    #[serde(default)]
+    pub lp_cost_bases: LpCostBases, // Business data, Record cost basis of liquidity minted by accounts //  ? Stable memory
 }
 
 impl Default for InnerState {
@@ -136,6 +214,7 @@ impl Default for InnerState {
 
             token_pairs: Default::default(),
             token_balances: Default::default(),
+            lp_cost_bases: Default::default(),
         }
     }
 }
@@ -155,6 +234,10 @@ const MEMORY_ID_SWAP_WASM_MODULE: MemoryId = MemoryId::new(17); // token blocks
 
 const MEMORY_ID_TOKEN_PAIRS: MemoryId = MemoryId::new(24); // token pairs
 const MEMORY_ID_TOKEN_BALANCES: MemoryId = MemoryId::new(25); // token balances
+const MEMORY_ID_TOKEN_POOLS: MemoryId = MemoryId::new(26); // token pools
+const MEMORY_ID_SWAP_V3_TICKS: MemoryId = MemoryId::new(27); // ticks of swap v3 pools
+const MEMORY_ID_SWAP_V3_POSITIONS: MemoryId = MemoryId::new(28); // positions of swap v3 pools
+const MEMORY_ID_LP_COST_BASES: MemoryId = MemoryId::new(29); // cost basis of liquidity
 
 fn init_request_traces() -> StableBTreeMap<RequestIndex, RequestTrace> {
     stable::init_map_data(MEMORY_ID_REQUEST_TRACES)
@@ -183,6 +266,18 @@ fn init_token_pairs() -> StableBTreeMap<TokenPairAmm, MarketMaker> {
 fn init_token_balances() -> StableBTreeMap<TokenAccount, TokenBalance> {
     stable::init_map_data(MEMORY_ID_TOKEN_BALANCES)
 }
+fn init_token_pools() -> StableBTreeMap<TokenPoolAmm, MarketMaker> {
+    stable::init_map_data(MEMORY_ID_TOKEN_POOLS)
+}
+fn init_swap_v3_ticks() -> StableBTreeMap<TokenPairAmm, SwapV3Ticks> {
+    stable::init_map_data(MEMORY_ID_SWAP_V3_TICKS)
+}
+fn init_swap_v3_positions() -> StableBTreeMap<TokenPairAmm, SwapV3Positions> {
+    stable::init_map_data(MEMORY_ID_SWAP_V3_POSITIONS)
+}
+fn init_lp_cost_bases() -> StableBTreeMap<TokenAccount, LpCostBasis> {
+    stable::init_map_data(MEMORY_ID_LP_COST_BASES)
+}
 
 impl InnerState {
     pub fn do_init(&mut self, arg: InitArgV1) {
@@ -208,6 +303,7 @@ impl InnerState {
         // maybe do something
         let _ = self.token_block_chain.init_wasm_module();
         let _ = self.swap_block_chain.init_wasm_module();
+        self.token_balances.init_totals(); // ! count the sums of balances once, reconcile needs them
 
         self.updated(|_| {});
     }
@@ -0,0 +1,374 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use ::common::utils::math::zero;
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// Allowance of spender on the internal balance of owner
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct TokenAllowance {
+    pub allowance: Nat,
+    pub expires_at: Option<u64>,
+    #[serde(default)]
+    pub block: Option<BlockIndex>, // the approve block which set the allowance, none if spent by transfer since
+}
+
+impl TokenAllowance {
+    fn is_expired(&self, now: TimestampNanos) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now.into_inner())
+    }
+}
+
+/// Allowances by token, owner and spender
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TokenAllowances(BTreeMap<CanisterId, BTreeMap<(Account, Account), TokenAllowance>>);
+
+impl TokenAllowances {
+    /// Expired allowance is zero
+    pub fn get(&self, token: &CanisterId, from: &Account, spender: &Account, now: TimestampNanos) -> TokenAllowance {
+        self.0
+            .get(token)
+            .and_then(|allowances| allowances.get(&(*from, *spender)))
+            .filter(|allowance| !allowance.is_expired(now))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn check_approve(&self, arg: &ApproveToken, now: TimestampNanos) -> Result<(), BusinessError> {
+        if arg.expires_at.is_some_and(|expires_at| expires_at <= now.into_inner()) {
+            return Err(BusinessError::Expired {
+                system: now.into_inner(),
+                deadline: arg.expires_at.unwrap_or_default(),
+            });
+        }
+        let current = self.get(&arg.token, &arg.from, &arg.spender, now).allowance;
+        if arg
+            .expected_allowance
+            .as_ref()
+            .is_some_and(|expected| *expected != current)
+        {
+            return Err(BusinessError::AllowanceChanged {
+                token: arg.token,
+                allowance: current,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn approve(&mut self, arg: &ApproveToken, block: BlockIndex) {
+        let allowances = self.0.entry(arg.token).or_default();
+        if arg.amount == zero() {
+            allowances.remove(&(arg.from, arg.spender));
+        } else {
+            allowances.insert(
+                (arg.from, arg.spender),
+                TokenAllowance {
+                    allowance: arg.amount.clone(),
+                    expires_at: arg.expires_at,
+                    block: Some(block),
+                },
+            );
+        }
+        if allowances.is_empty() {
+            self.0.remove(&arg.token);
+        }
+    }
+
+    pub fn check_spend(
+        &self,
+        token: &CanisterId,
+        from: &Account,
+        spender: &Account,
+        amount: &Nat,
+        now: TimestampNanos,
+    ) -> Result<(), BusinessError> {
+        let allowance = self.get(token, from, spender, now).allowance;
+        if allowance < *amount {
+            return Err(BusinessError::InsufficientAllowance {
+                token: *token,
+                allowance,
+            });
+        }
+        Ok(())
+    }
+
+    /// Must be checked before spending
+    pub fn spend(&mut self, token: &CanisterId, from: &Account, spender: &Account, amount: &Nat) {
+        let Some(allowances) = self.0.get_mut(token) else {
+            return;
+        };
+        if let Some(allowance) = allowances.get_mut(&(*from, *spender)) {
+            if allowance.allowance <= *amount {
+                allowances.remove(&(*from, *spender));
+            } else {
+                allowance.allowance -= amount.clone();
+                allowance.block = None; // ! spent by transfer, the spending before is not given back
+            }
+        }
+        if allowances.is_empty() {
+            self.0.remove(token);
+        }
+    }
+
+    /// The approve of the rest allowance, which spends the amount before calling on behalf of owner
+    pub fn spending(
+        &self,
+        token: &CanisterId,
+        from: &Account,
+        spender: &Account,
+        amount: &Nat,
+        now: TimestampNanos,
+    ) -> Result<ApproveToken, BusinessError> {
+        self.check_spend(token, from, spender, amount, now)?;
+        let current = self.get(token, from, spender, now);
+        Ok(ApproveToken {
+            token: *token,
+            from: *from,
+            amount: current.allowance.clone() - amount.clone(),
+            spender: *spender,
+            expected_allowance: Some(current.allowance),
+            expires_at: current.expires_at,
+        })
+    }
+
+    /// The approve which gives back the unused allowance.
+    /// ! Only the allowance unchanged since the block of spending is given back,
+    /// the removed, expired or approved again by owner is left alone.
+    pub fn restoring(
+        &self,
+        token: &CanisterId,
+        from: &Account,
+        spender: &Account,
+        unused: &Nat,
+        block: BlockIndex,
+        now: TimestampNanos,
+    ) -> Option<ApproveToken> {
+        let current = self
+            .0
+            .get(token)
+            .and_then(|allowances| allowances.get(&(*from, *spender)))
+            .filter(|allowance| allowance.block == Some(block) && !allowance.is_expired(now))?;
+        Some(ApproveToken {
+            token: *token,
+            from: *from,
+            amount: current.allowance.clone() + unused.clone(),
+            spender: *spender,
+            expected_allowance: Some(current.allowance.clone()),
+            expires_at: current.expires_at,
+        })
+    }
+}
+
+/// Icrc transactions with created_at_time, the same transaction in the transaction window is deduplicated
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IcrcTransactions {
+    transactions: BTreeMap<String, BlockIndex>, // hash of transaction -> block index
+    expiring: BTreeSet<(u64, String)>,          // created and hash of transaction, the earliest expires first
+}
+
+impl IcrcTransactions {
+    pub fn get(&self, hash: &str) -> Option<BlockIndex> {
+        self.transactions.get(hash).copied()
+    }
+
+    pub fn insert(&mut self, hash: String, created: TimestampNanos, block_index: BlockIndex, now: TimestampNanos) {
+        // ! transactions out of the window are refused by created, no need to keep them
+        let earliest = now
+            .into_inner()
+            .saturating_sub(::common::types::TRANSACTION_WINDOW.as_nanos() as u64);
+        while let Some((created, hash)) = self.expiring.first().cloned() {
+            if earliest <= created {
+                break;
+            }
+            self.expiring.pop_first();
+            self.transactions.remove(&hash);
+        }
+
+        self.expiring.insert((created.into_inner(), hash.clone()));
+        self.transactions.insert(hash, block_index);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn account(id: u8) -> Account {
+        Account {
+            owner: CanisterId::from_slice(&[id, id]),
+            subaccount: None,
+        }
+    }
+
+    fn approve(amount: u32, expires_at: Option<u64>) -> ApproveToken {
+        ApproveToken {
+            token: CanisterId::from_slice(&[9]),
+            from: account(1),
+            amount: Nat::from(amount),
+            spender: account(2),
+            expected_allowance: None,
+            expires_at,
+        }
+    }
+
+    // the approve is checked and written as the block
+    fn apply(allowances: &mut TokenAllowances, arg: &ApproveToken, block: BlockIndex, now: TimestampNanos) {
+        allowances.check_approve(arg, now).unwrap();
+        allowances.approve(arg, block);
+    }
+
+    #[test]
+    fn test_token_allowance_spend_and_restore() {
+        let (token, owner, spender) = (CanisterId::from_slice(&[9]), account(1), account(2));
+        let now = TimestampNanos::from_inner(10);
+        let mut allowances = TokenAllowances::default();
+        apply(&mut allowances, &approve(100, Some(20)), 1, now);
+
+        // spend by the approve of the rest allowance
+        assert!(
+            allowances
+                .spending(&token, &owner, &spender, &Nat::from(101_u32), now)
+                .is_err()
+        );
+        let spending = allowances
+            .spending(&token, &owner, &spender, &Nat::from(60_u32), now)
+            .unwrap();
+        assert_eq!(spending.amount, Nat::from(40_u32));
+        assert_eq!(spending.expected_allowance, Some(Nat::from(100_u32)));
+        assert_eq!(spending.expires_at, Some(20));
+        apply(&mut allowances, &spending, 2, now);
+
+        // the unused is given back on the allowance unchanged since spending
+        let restoring = allowances
+            .restoring(&token, &owner, &spender, &Nat::from(25_u32), 2, now)
+            .unwrap();
+        assert_eq!(restoring.amount, Nat::from(65_u32));
+        assert_eq!(restoring.expected_allowance, Some(Nat::from(40_u32)));
+        assert_eq!(restoring.expires_at, Some(20));
+        apply(&mut allowances, &restoring, 3, now);
+        assert_eq!(
+            allowances.get(&token, &owner, &spender, now).allowance,
+            Nat::from(65_u32)
+        );
+
+        // the expired is never given back
+        assert!(
+            allowances
+                .restoring(
+                    &token,
+                    &owner,
+                    &spender,
+                    &Nat::from(1_u32),
+                    3,
+                    TimestampNanos::from_inner(20)
+                )
+                .is_none()
+        );
+
+        // all allowance is spent, the removed is never created again
+        let spending = allowances
+            .spending(&token, &owner, &spender, &Nat::from(65_u32), now)
+            .unwrap();
+        assert_eq!(spending.amount, zero());
+        apply(&mut allowances, &spending, 4, now);
+        assert!(
+            allowances
+                .restoring(&token, &owner, &spender, &Nat::from(65_u32), 4, now)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_token_allowance_changed_during_call() {
+        let (token, owner, spender) = (CanisterId::from_slice(&[9]), account(1), account(2));
+        let now = TimestampNanos::from_inner(10);
+        let unused = Nat::from(60_u32);
+        let spend = |allowances: &mut TokenAllowances, block: BlockIndex| {
+            let spending = allowances.spending(&token, &owner, &spender, &unused, now).unwrap();
+            apply(allowances, &spending, block, now);
+        };
+
+        // revoked by owner during the call
+        let mut allowances = TokenAllowances::default();
+        apply(&mut allowances, &approve(100, None), 1, now);
+        spend(&mut allowances, 2);
+        apply(&mut allowances, &approve(0, None), 3, now);
+        assert!(
+            allowances
+                .restoring(&token, &owner, &spender, &unused, 2, now)
+                .is_none()
+        );
+        assert_eq!(allowances.get(&token, &owner, &spender, now).allowance, zero());
+
+        // approved again by owner during the call, even the same amount
+        let mut allowances = TokenAllowances::default();
+        apply(&mut allowances, &approve(100, None), 1, now);
+        spend(&mut allowances, 2);
+        let again = ApproveToken {
+            expected_allowance: Some(Nat::from(40_u32)),
+            ..approve(40, None)
+        };
+        apply(&mut allowances, &again, 3, now);
+        assert!(
+            allowances
+                .restoring(&token, &owner, &spender, &unused, 2, now)
+                .is_none()
+        );
+        assert_eq!(
+            allowances.get(&token, &owner, &spender, now).allowance,
+            Nat::from(40_u32)
+        );
+
+        // spent by transfer from during the call
+        let mut allowances = TokenAllowances::default();
+        apply(&mut allowances, &approve(100, None), 1, now);
+        spend(&mut allowances, 2);
+        allowances
+            .check_spend(&token, &owner, &spender, &Nat::from(10_u32), now)
+            .unwrap();
+        allowances.spend(&token, &owner, &spender, &Nat::from(10_u32));
+        assert!(
+            allowances
+                .restoring(&token, &owner, &spender, &unused, 2, now)
+                .is_none()
+        );
+        assert_eq!(
+            allowances.get(&token, &owner, &spender, now).allowance,
+            Nat::from(30_u32)
+        );
+    }
+
+    #[test]
+    fn test_icrc_transactions() {
+        let day = ::common::types::TRANSACTION_WINDOW.as_nanos() as u64;
+        let mut transactions = IcrcTransactions::default();
+
+        transactions.insert(
+            "a".into(),
+            TimestampNanos::from_inner(day),
+            1,
+            TimestampNanos::from_inner(day),
+        );
+        transactions.insert(
+            "b".into(),
+            TimestampNanos::from_inner(day + 1),
+            2,
+            TimestampNanos::from_inner(day + 1),
+        );
+        assert_eq!(transactions.get("a"), Some(1));
+        assert_eq!(transactions.get("b"), Some(2));
+
+        // a is out of the window
+        transactions.insert(
+            "c".into(),
+            TimestampNanos::from_inner(day * 2 + 1),
+            3,
+            TimestampNanos::from_inner(day * 2 + 1),
+        );
+        assert_eq!(transactions.get("a"), None);
+        assert_eq!(transactions.get("b"), Some(2));
+        assert_eq!(transactions.get("c"), Some(3));
+    }
+}
@@ -38,6 +38,9 @@ pub struct TokenBalances {
     #[serde(skip, default = "init_token_balances")]
     balances: StableBTreeMap<TokenAccount, TokenBalance>,
     locks: RwLock<HashMap<TokenAccount, bool>>,
+    /// Sum of internal balances of each token, none before it is counted
+    #[serde(default)]
+    totals: Option<HashMap<CanisterId, candid::Nat>>,
 }
 
 impl Default for TokenBalances {
@@ -45,6 +48,7 @@ impl Default for TokenBalances {
         Self {
             balances: init_token_balances(),
             locks: Default::default(),
+            totals: Some(HashMap::new()),
         }
     }
 }
@@ -55,7 +59,45 @@ impl TokenBalances {
         Ok(self.balances.get(&token_account).map(|b| b.0).unwrap_or_default())
     }
 
+    /// Count the sums of internal balances once, which is kept by changes of balances after
+    pub fn init_totals(&mut self) {
+        if self.totals.is_some() {
+            return;
+        }
+        let mut totals: HashMap<CanisterId, candid::Nat> = HashMap::new();
+        for (token_account, balance) in self.balances.iter() {
+            *totals.entry(token_account.token).or_default() += balance.0;
+        }
+        self.totals = Some(totals);
+    }
+
+    /// Sum of internal balances of token, which is (accounts, pools), none before the sums are counted.
+    /// Only balances of pools are looked up, the accounts are the rest of the total.
+    pub fn token_balances_sum(
+        &self,
+        token: CanisterId,
+        pools: &HashSet<Account>,
+    ) -> Option<(candid::Nat, candid::Nat)> {
+        let total = self.totals.as_ref()?.get(&token).cloned().unwrap_or_default();
+        let mut pooled = candid::Nat::default();
+        for pool in pools {
+            if let Some(balance) = self.balances.get(&TokenAccount::new(token, *pool)) {
+                pooled += balance.0;
+            }
+        }
+        Some((total - pooled.clone(), pooled))
+    }
+
     // locks
+    pub fn locked_accounts(&self) -> Vec<TokenAccount> {
+        let locks = trap(self.locks.read()); // ! what if failed ?
+        locks
+            .iter()
+            .filter(|(_, lock)| **lock)
+            .map(|(token_account, _)| token_account.clone())
+            .collect()
+    }
+
     pub fn lock(&mut self, required: Vec<TokenAccount>) -> Result<TokenBalancesLock, Vec<TokenAccount>> {
         let mut locks = trap(self.locks.write()); // ! what if failed ?
 
@@ -115,7 +157,7 @@ impl TokenBalances {
     }
 
     pub fn be_guard<'a>(&'a mut self, lock: &'a TokenBalancesLock) -> TokenBalancesGuard<'a> {
-        TokenBalancesGuard::new(&mut self.balances, lock)
+        TokenBalancesGuard::new(&mut self.balances, &mut self.totals, lock)
     }
 }
 
@@ -143,6 +185,7 @@ mod guard {
     use super::*;
     pub struct TokenBalancesGuard<'a> {
         stable_balances: &'a mut StableBTreeMap<TokenAccount, TokenBalance>,
+        totals: &'a mut Option<HashMap<CanisterId, candid::Nat>>,
         lock: &'a TokenBalancesLock,
         // stack data
         stack_balances: HashMap<TokenAccount, TokenBalance>,
@@ -156,6 +199,7 @@ mod guard {
     impl<'a> TokenBalancesGuard<'a> {
         pub(super) fn new(
             stable_balances: &'a mut StableBTreeMap<TokenAccount, TokenBalance>,
+            totals: &'a mut Option<HashMap<CanisterId, candid::Nat>>,
             lock: &'a TokenBalancesLock,
         ) -> Self {
             let stack_balances = lock
@@ -170,6 +214,7 @@ mod guard {
                 .collect();
             Self {
                 stable_balances,
+                totals,
                 lock,
                 stack_balances,
             }
@@ -206,6 +251,13 @@ mod guard {
 
         pub fn dump(self) {
             for (token_account, balance) in self.stack_balances.iter() {
+                // * keep the total of token
+                if let Some(totals) = self.totals.as_mut() {
+                    let old = self.stable_balances.get(token_account).unwrap_or_default();
+                    let total = totals.entry(token_account.token).or_default();
+                    *total += balance.0.clone();
+                    *total -= old.0; // ! the total always contains the old balance
+                }
                 if balance.0 == 0_u64 {
                     self.stable_balances.remove(token_account);
                 } else {
@@ -325,6 +377,22 @@ impl TokenBalancesGuard<'_> {
         })?;
         Ok(changed)
     }
+    // approve token, balances are not changed
+    pub fn token_approve(
+        &mut self,
+        guard: &mut TokenBlockChainGuard,
+        arg: ArgWithMeta<ApproveToken>,
+    ) -> Result<(), BusinessError> {
+        // 1. get token block
+        let transaction = TokenTransaction {
+            operation: TokenOperation::Approve(arg.arg.clone()),
+            memo: arg.memo,
+            created: arg.created,
+        };
+        // 2. mint block
+        guard.mint_block(arg.now, transaction, |_| Ok(()))?;
+        Ok(())
+    }
     // transfer lp token
     pub fn token_lp_transfer(
         &mut self,
@@ -382,6 +450,7 @@ impl TokenBalancesGuard<'_> {
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
 
@@ -410,4 +479,63 @@ mod tests {
 
         assert_eq!(balance, balance2);
     }
+
+    #[test]
+    fn test_token_balances_sum() {
+        let token = CanisterId::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai").unwrap();
+        let other = CanisterId::from_text("mxzaz-hqaaa-aaaar-qaada-cai").unwrap();
+        let account = |id: u8| Account {
+            owner: Principal::from_slice(&[id]),
+            subaccount: None,
+        };
+        let (alice, bob, pool) = (account(1), account(2), account(3));
+        let pools = [pool].into_iter().collect::<HashSet<_>>();
+        let nat = |n: u64| Nat::from(n);
+
+        let mut balances = TokenBalances::default();
+        let required = vec![
+            TokenAccount::new(token, alice),
+            TokenAccount::new(token, bob),
+            TokenAccount::new(token, pool),
+            TokenAccount::new(other, alice),
+        ];
+        let lock = TokenBalancesLock {
+            locked: required.iter().cloned().collect(),
+            required,
+        };
+        assert_eq!(balances.token_balances_sum(token, &pools), Some((nat(0), nat(0))));
+
+        // deposit
+        let mut guard = balances.be_guard(&lock);
+        guard.do_token_deposit(token, alice, nat(100)).unwrap();
+        guard.do_token_deposit(token, pool, nat(50)).unwrap();
+        guard.do_token_deposit(other, alice, nat(7)).unwrap();
+        guard.dump();
+        assert_eq!(balances.token_balances_sum(token, &pools), Some((nat(100), nat(50))));
+        assert_eq!(balances.token_balances_sum(other, &pools), Some((nat(7), nat(0))));
+
+        // transfer keeps the total, withdraw lowers it, the balance of zero is removed
+        let mut guard = balances.be_guard(&lock);
+        guard.do_token_transfer(token, alice, nat(30), bob, None).unwrap();
+        guard.do_token_transfer(token, alice, nat(20), pool, None).unwrap();
+        guard.do_token_withdraw(token, bob, nat(30)).unwrap();
+        guard.dump();
+        assert_eq!(balances.token_balances_sum(token, &pools), Some((nat(50), nat(70))));
+        assert_eq!(balances.token_balance_of(token, bob).unwrap(), nat(0));
+
+        // the guard not dumped changes nothing
+        let mut guard = balances.be_guard(&lock);
+        guard.do_token_deposit(token, bob, nat(1_000)).unwrap();
+        drop(guard);
+        assert_eq!(balances.token_balances_sum(token, &pools), Some((nat(50), nat(70))));
+
+        // the state before sums is counted once
+        balances.totals = None;
+        assert_eq!(balances.token_balances_sum(token, &pools), None);
+        balances.init_totals();
+        assert_eq!(balances.token_balances_sum(token, &pools), Some((nat(50), nat(70))));
+        assert_eq!(balances.token_balances_sum(other, &pools), Some((nat(7), nat(0))));
+
+        std::mem::forget(lock); // ! never locked by state
+    }
 }
@@ -129,6 +129,23 @@ impl<T> BlockChain<T> {
         Ok(())
     }
 
+    /// Archive canisters and the range of blocks in them, in order of block height
+    pub fn archives(&self) -> Vec<(CanisterId, BlockIndex, u64)> {
+        let mut archives = self
+            .archived
+            .iter()
+            .map(|a| (a.canister_id, a.block_height_offset, a.length))
+            .collect::<Vec<_>>();
+        if let Some(current_archiving) = &self.current_archiving {
+            archives.push((
+                current_archiving.canister_id,
+                current_archiving.block_height_offset,
+                current_archiving.length,
+            ));
+        }
+        archives
+    }
+
     fn get_maintain_canisters(&self) -> Vec<CanisterId> {
         let mut canisters = self.archived.iter().map(|a| a.canister_id).collect::<Vec<_>>();
         if let Some(current_archiving) = &self.current_archiving {
@@ -185,6 +185,10 @@ impl SwapBlockChain {
     pub fn get_latest_hash(&self) -> &[u8] {
         self.block_chain.latest_block_hash.as_slice()
     }
+
+    pub fn get_latest_index(&self) -> Option<BlockIndex> {
+        self.block_chain.next_block_index.checked_sub(1)
+    }
 }
 
 // ============================ lock ============================
@@ -0,0 +1,343 @@
+/// Concentrated liquidity market maker（Concentrated Liquidity AMM）
+/// - Formula：(x + L / √Pb) * (y + L * √Pa) = L²（inside the price range [Pa, Pb] of each position）
+/// - Representative Project：Uniswap V3、PancakeSwap V3
+/// - Features
+///   - LPs provide liquidity in chosen price ranges, which improves capital efficiency.
+///   - Swap fees are accrued per unit of in range liquidity and tracked by ticks.
+use ::common::{
+    types::{SwapV3MarketMaker, SwapV3PositionChanged},
+    utils::math::{ZERO, zero},
+};
+use num_bigint::BigInt;
+
+use super::*;
+
+use crate::types::{
+    BusinessError, SelfCanister, TokenPairLiquidityAddArg, TokenPairLiquidityAddSuccess, TokenPairLiquidityRemoveArg,
+    TokenPairLiquidityRemoveSuccess,
+};
+
+struct InnerSwapResult {
+    zero_for_one: bool,
+    amount_in: Nat,
+    amount_out: Nat,
+    fee: Nat,
+    balance: (Nat, Nat),
+}
+
+fn pool_account(_self: &SwapV3MarketMaker, self_canister: &SelfCanister) -> Account {
+    Account {
+        owner: self_canister.id(),
+        subaccount: Some(_self.subaccount),
+    }
+}
+
+/// Sort amounts of token a and token b to token0 and token1
+fn sort_amounts<'a>(_self: &SwapV3MarketMaker, token_a: CanisterId, a: &'a Nat, b: &'a Nat) -> (&'a Nat, &'a Nat) {
+    if token_a == _self.token0 { (a, b) } else { (b, a) }
+}
+
+fn update<T: TokenPairArg>(
+    _self: &mut SwapV3MarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, T>,
+    balance0: Nat,
+    balance1: Nat,
+) {
+    _self.reserve0 = balance0;
+    _self.reserve1 = balance1;
+    _self.block_timestamp_last = guard.arg.now.into_inner();
+}
+
+pub fn add_liquidity(
+    _self: &mut SwapV3MarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, TokenPairLiquidityAddArg>,
+) -> Result<TokenPairLiquidityAddSuccess, BusinessError> {
+    // ! check balance
+    let tick_range = {
+        let arg = &guard.arg.arg;
+        let tick_range = arg
+            .tick_range
+            .ok_or_else(|| BusinessError::Liquidity("TICK_RANGE_REQUIRED".into()))?;
+        _self.check_tick_range(&tick_range)?;
+        guard.assert_token_balance(arg.token_a, arg.from, &arg.amount_a_desired)?;
+        guard.assert_token_balance(arg.token_b, arg.from, &arg.amount_b_desired)?;
+        guard.trace(format!(
+            "*PairLiquidityAdd* `tokenA:[{}], tokenB:[{}], amm:{}, range:{tick_range}, required: {} <= amount_a <= {} && {} <= amount_b <= {}`",
+            arg.token_a.to_text(),
+            arg.token_b.to_text(),
+            arg.pa.amm.into_text().as_ref(),
+            arg.amount_a_min,
+            arg.amount_a_desired,
+            arg.amount_b_min,
+            arg.amount_b_desired,
+        )); // * trace
+        tick_range
+    };
+
+    // calculate liquidity and amount
+    let arg = &guard.arg.arg;
+    let (amount0_desired, amount1_desired) =
+        sort_amounts(_self, arg.token_a, &arg.amount_a_desired, &arg.amount_b_desired);
+    if !_self.is_initialized() {
+        return Err(BusinessError::Liquidity("NOT_INITIALIZED".into())); // price is given at creation
+    }
+    let liquidity = _self.get_liquidity_for_amounts(&tick_range, amount0_desired, amount1_desired)?;
+    if liquidity == *ZERO {
+        return Err(BusinessError::Liquidity("INSUFFICIENT_LIQUIDITY_MINTED".into()));
+    }
+    let SwapV3PositionChanged { amount0, amount1 } =
+        _self.modify_position(arg.to, tick_range, BigInt::from(liquidity.0.clone()))?;
+    if *amount0_desired < amount0 || *amount1_desired < amount1 {
+        return Err(BusinessError::Liquidity("INSUFFICIENT_AMOUNT_DESIRED".into()));
+    }
+    let (amount_a, amount_b) = if arg.token_a == _self.token0 {
+        (amount0.clone(), amount1.clone())
+    } else {
+        (amount1.clone(), amount0.clone())
+    };
+    if amount_a < arg.amount_a_min {
+        return Err(BusinessError::Liquidity("INSUFFICIENT_A_AMOUNT".into()));
+    }
+    if amount_b < arg.amount_b_min {
+        return Err(BusinessError::Liquidity("INSUFFICIENT_B_AMOUNT".into()));
+    }
+
+    // Pool token account
+    let message = format!("*PairLiquidityAdd* `amount_a:{amount_a}, amount_b:{amount_b}`");
+    let pool_account = pool_account(_self, &arg.self_canister);
+    let (token0, token1) = (_self.token0, _self.token1);
+    for (token, amount) in [(token0, &amount0), (token1, &amount1)] {
+        if *amount == *ZERO {
+            continue; // The position is out of range, single token is required
+        }
+        let arg = &guard.arg.arg;
+        guard.token_transfer(TransferToken {
+            token,
+            from: arg.from,
+            amount: amount.clone(),
+            to: pool_account,
+            fee: None,
+            spender: None,
+        })?; // * transfer and trace
+    }
+    guard.trace(message); // * trace
+
+    guard.token_position_mint(tick_range, (token0, token1), (amount0, amount1), liquidity.clone())?;
+
+    // Update the current balance
+    let balance0 = guard.token_balance_of(token0, pool_account)?;
+    let balance1 = guard.token_balance_of(token1, pool_account)?;
+    update(_self, guard, balance0, balance1);
+
+    Ok(TokenPairLiquidityAddSuccess {
+        amount: (amount_a, amount_b),
+        liquidity,
+    })
+}
+
+pub fn remove_liquidity(
+    _self: &mut SwapV3MarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, TokenPairLiquidityRemoveArg>,
+) -> Result<TokenPairLiquidityRemoveSuccess, BusinessError> {
+    // ! check position
+    let tick_range = {
+        let arg = &guard.arg.arg;
+        let tick_range = arg
+            .tick_range
+            .ok_or_else(|| BusinessError::Liquidity("TICK_RANGE_REQUIRED".into()))?;
+        _self.check_position_removable(&arg.from, &tick_range, &arg.liquidity_without_fee)?;
+        guard.trace(format!(
+            "*PairLiquidityRemove* `tokenA:[{}], tokenB:[{}], amm:{}, range:{tick_range}, liquidity:{}, required: {} <= amount_a && {} <= amount_b`",
+            arg.token_a.to_text(),
+            arg.token_b.to_text(),
+            arg.pa.amm.into_text().as_ref(),
+            arg.liquidity_without_fee,
+            arg.amount_a_min,
+            arg.amount_b_min,
+        )); // * trace
+        tick_range
+    };
+
+    // burn liquidity, the principal and fees earned are collected together
+    let arg = &guard.arg.arg;
+    let liquidity = arg.liquidity_without_fee.clone();
+    let burned = _self.modify_position(arg.from, tick_range, -BigInt::from(liquidity.0.clone()))?;
+    _self.owe_position(&arg.from, &tick_range, &burned);
+    let SwapV3PositionChanged { amount0, amount1 } = _self.collect_position(&arg.from, &tick_range);
+
+    // ! check amount before change data
+    if amount0 == *ZERO && amount1 == *ZERO {
+        return Err(BusinessError::Liquidity("INSUFFICIENT_LIQUIDITY_BURNED".into()));
+    }
+    let (amount_a, amount_b) = if arg.token_a == _self.token0 {
+        (amount0.clone(), amount1.clone())
+    } else {
+        (amount1.clone(), amount0.clone())
+    };
+    if amount_a < arg.amount_a_min {
+        return Err(BusinessError::Liquidity("INSUFFICIENT_A_AMOUNT".into()));
+    }
+    if amount_b < arg.amount_b_min {
+        return Err(BusinessError::Liquidity("INSUFFICIENT_B_AMOUNT".into()));
+    }
+
+    guard.token_position_burn(
+        tick_range,
+        liquidity,
+        (_self.token0, _self.token1),
+        (amount0.clone(), amount1.clone()),
+    )?;
+
+    // return token
+    let message = format!("*PairLiquidityRemove* `amount0:{amount0}, amount1:{amount1}`");
+    let pool_account = pool_account(_self, &guard.arg.arg.self_canister);
+    let (token0, token1) = (_self.token0, _self.token1);
+    for (token, amount) in [(token0, amount0), (token1, amount1)] {
+        if amount == *ZERO {
+            continue;
+        }
+        let arg = &guard.arg.arg;
+        guard.token_transfer(TransferToken {
+            token,
+            from: pool_account,
+            amount,
+            to: arg.to,
+            fee: None,
+            spender: None,
+        })?; // * transfer and trace
+    }
+    guard.trace(message); // * trace
+
+    // Update the current balance
+    let balance0 = guard.token_balance_of(token0, pool_account)?;
+    let balance1 = guard.token_balance_of(token1, pool_account)?;
+    update(_self, guard, balance0, balance1);
+
+    Ok(TokenPairLiquidityRemoveSuccess {
+        amount: (amount_a, amount_b),
+    })
+}
+
+fn inner_swap<T: TokenPairArg>(
+    _self: &mut SwapV3MarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, &T>,
+    self_canister: &SelfCanister,
+    amount0_out: Nat,
+    amount1_out: Nat,
+    to: Account,
+) -> Result<InnerSwapResult, BusinessError> {
+    // Pool's account
+    let pool_account = pool_account(_self, self_canister);
+
+    // Only one token can be output
+    let zero_for_one = match (amount0_out == *ZERO, amount1_out == *ZERO) {
+        (true, false) => true,
+        (false, true) => false,
+        _ => return Err(BusinessError::Swap("INSUFFICIENT_OUTPUT_AMOUNT".into())),
+    };
+    let (token_in, token_out, amount_out) = if zero_for_one {
+        (_self.token0, _self.token1, amount1_out)
+    } else {
+        (_self.token1, _self.token0, amount0_out)
+    };
+    if to.owner == token_in || to.owner == token_out {
+        return Err(BusinessError::Swap("INVALID_TO".into())); // The output token target address cannot be the token itself
+    }
+
+    // The input token should be transferred in advance before calling this function.
+    let reserve_in = if zero_for_one { &_self.reserve0 } else { &_self.reserve1 };
+    let balance_in = guard.token_balance_of(token_in, pool_account)?;
+    let received = if balance_in > *reserve_in {
+        balance_in - reserve_in.clone()
+    } else {
+        zero()
+    };
+    if received == *ZERO {
+        return Err(BusinessError::Swap("INSUFFICIENT_INPUT_AMOUNT".into()));
+    }
+
+    // swap through ranges
+    let fee_to = guard.get_swap_fee_to();
+    let fee_on = fee_to.is_some() && _self.protocol_fee.as_ref().is_some_and(|fee| !fee.is_zero());
+    let mut next = _self.clone();
+    let swapped = next.swap(zero_for_one, false, &amount_out, fee_on)?;
+    if received < swapped.amount_in {
+        return Err(BusinessError::Swap("K".into()));
+    }
+    // Extra paid input is earned by in range positions
+    next.donate(zero_for_one, &(received.clone() - swapped.amount_in.clone()));
+
+    // do transfer out
+    guard.token_transfer(TransferToken {
+        token: token_out,
+        from: pool_account,
+        amount: amount_out.clone(),
+        to,
+        fee: None,
+        spender: None,
+    })?; // * transfer and trace
+    if let (Some(fee_to), true) = (fee_to, swapped.protocol_fee > *ZERO) {
+        guard.token_transfer(TransferToken {
+            token: token_in,
+            from: pool_account,
+            amount: swapped.protocol_fee.clone(),
+            to: fee_to,
+            fee: None,
+            spender: None,
+        })?; // * transfer and trace
+    }
+    let balance0 = guard.token_balance_of(_self.token0, pool_account)?;
+    let balance1 = guard.token_balance_of(_self.token1, pool_account)?;
+
+    *_self = next;
+
+    Ok(InnerSwapResult {
+        zero_for_one,
+        amount_in: received,
+        amount_out,
+        fee: swapped.fee,
+        balance: (balance0, balance1),
+    })
+}
+
+/// Be sure to transfer the corresponding token first, and then call this method to transfer the token
+#[inline]
+#[allow(clippy::too_many_arguments)]
+pub fn swap<T: TokenPairArg>(
+    _self: &mut SwapV3MarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, T>,
+    transaction: SwapTransaction,
+    trace: String,
+    self_canister: &SelfCanister,
+    amount0_out: Nat,
+    amount1_out: Nat,
+    to: Account,
+) -> Result<(), BusinessError> {
+    let InnerSwapResult {
+        zero_for_one,
+        amount_in,
+        amount_out,
+        fee,
+        balance: (balance0, balance1),
+    } = guard.mint_swap_block(
+        guard.arg.now,
+        transaction,
+        |guard| inner_swap(_self, guard, self_canister, amount0_out, amount1_out, to),
+        trace,
+    )?;
+
+    update(_self, guard, balance0, balance1);
+    guard.push_swap_v3_swap(
+        zero_for_one,
+        amount_in,
+        amount_out,
+        fee,
+        _self.sqrt_price_x96.clone(),
+        _self.tick,
+        _self.liquidity.clone(),
+        _self.reserve0.clone(),
+        _self.reserve1.clone(),
+    )?;
+
+    Ok(())
+}
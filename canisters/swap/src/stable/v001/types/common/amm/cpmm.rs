@@ -105,6 +105,22 @@ fn mint_fee<T: SelfCanisterArg + TokenPairArg>(
     Ok(fee_on)
 }
 
+/// Balance of pool, the amount lent by flash loan and not settled is counted in
+fn pool_balance_of<T>(
+    _self: &SwapV2MarketMaker,
+    guard: &InnerTokenPairSwapGuard<'_, '_, '_, T>,
+    token: CanisterId,
+    pool_account: &Account,
+) -> Result<Nat, BusinessError> {
+    let balance = guard.token_balance_of(token, *pool_account)?;
+    let lent = if token == _self.token0 {
+        &_self.lent0
+    } else {
+        &_self.lent1
+    };
+    Ok(balance + lent.clone())
+}
+
 fn update<T: TokenPairArg>(
     _self: &mut SwapV2MarketMaker,
     guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, T>,
@@ -126,6 +142,8 @@ fn update<T: TokenPairArg>(
     _self.reserve0 = balance0;
     _self.reserve1 = balance1;
     _self.block_timestamp_last = block_timestamp;
+    _self.observe(); // observation of twap
+    _self.update_dynamic_fee(); // fee of next swap
     guard.push_state(
         _self.reserve0.clone(),
         _self.reserve1.clone(),
@@ -150,8 +168,8 @@ fn mint(
     // Get the current hold
     let (_reserve0, _reserve1) = _self.get_reserves(token0, token1);
     // Get the current balance
-    let balance0 = guard.token_balance_of(token0, *pool_account)?;
-    let balance1 = guard.token_balance_of(token1, *pool_account)?;
+    let balance0 = pool_balance_of(_self, guard, token0, pool_account)?;
+    let balance1 = pool_balance_of(_self, guard, token1, pool_account)?;
     // Calculate the increase amount
     let amount0 = balance0.clone() - _reserve0.clone();
     let amount1 = balance1.clone() - _reserve1.clone();
@@ -222,6 +240,7 @@ pub fn add_liquidity(
         amount: amount_a.clone(),
         to: pool_account,
         fee: None,
+        spender: None,
     })?; // * transfer and trace
     let arg = &guard.arg.arg;
     guard.token_transfer(TransferToken {
@@ -230,6 +249,7 @@ pub fn add_liquidity(
         amount: amount_b.clone(),
         to: pool_account,
         fee: None,
+        spender: None,
     })?; // * transfer and trace
     guard.trace(message); // * trace
 
@@ -251,8 +271,8 @@ fn burn(
     let (_reserve0, _reserve1) = _self.get_reserves(token0, token1);
     let _token0 = token0;
     let _token1 = token1;
-    let balance0 = guard.token_balance_of(_token0, *pool_account)?;
-    let balance1 = guard.token_balance_of(_token1, *pool_account)?;
+    let balance0 = pool_balance_of(_self, guard, _token0, pool_account)?;
+    let balance1 = pool_balance_of(_self, guard, _token1, pool_account)?;
     let liquidity_without_fee = arg.liquidity_without_fee.clone();
 
     let fee_on = mint_fee(_self, guard, pool_account, &_reserve0, &_reserve1)?;
@@ -306,6 +326,7 @@ fn burn(
         amount: amount0,
         to: arg.to,
         fee: None,
+        spender: None,
     })?; // * transfer and trace
     let arg = &guard.arg.arg;
     guard.token_transfer(TransferToken {
@@ -314,11 +335,12 @@ fn burn(
         amount: amount1,
         to: arg.to,
         fee: None,
+        spender: None,
     })?; // * transfer and trace
     guard.trace(message); // * trace
 
-    let balance0 = guard.token_balance_of(_token0, *pool_account)?;
-    let balance1 = guard.token_balance_of(_token1, *pool_account)?;
+    let balance0 = pool_balance_of(_self, guard, _token0, pool_account)?;
+    let balance1 = pool_balance_of(_self, guard, _token1, pool_account)?;
 
     // Update the current balance
     update(_self, guard, balance0, balance1, _reserve0, _reserve1)?;
@@ -403,6 +425,7 @@ fn inner_swap<T: TokenPairArg>(
                 amount: amount0_out.clone(),
                 to,
                 fee: None,
+                spender: None,
             })?; // * transfer and trace
         }
         if amount1_out > *ZERO {
@@ -412,10 +435,11 @@ fn inner_swap<T: TokenPairArg>(
                 amount: amount1_out.clone(),
                 to,
                 fee: None,
+                spender: None,
             })?; // * transfer and trace
         }
-        let balance0 = guard.token_balance_of(_token0, pool_account)?;
-        let balance1 = guard.token_balance_of(_token1, pool_account)?;
+        let balance0 = pool_balance_of(_self, guard, _token0, &pool_account)?;
+        let balance1 = pool_balance_of(_self, guard, _token1, &pool_account)?;
         (balance0, balance1)
     };
 
@@ -440,8 +464,9 @@ fn inner_swap<T: TokenPairArg>(
 
     // check after changed
     {
-        let n = _self.fee_rate.numerator;
-        let d = _self.fee_rate.denominator;
+        let fee_rate = _self.current_fee_rate();
+        let n = fee_rate.numerator;
+        let d = fee_rate.denominator;
         let balance0_adjusted = balance0.clone() * d - amount0_in * n;
         let balance1_adjusted = balance1.clone() * d - amount1_in * n;
         if balance0_adjusted * balance1_adjusted < _reserve0.clone() * _reserve1.clone() * d * d {
@@ -455,6 +480,7 @@ fn inner_swap<T: TokenPairArg>(
                     amount: amount0_out.clone(),
                     to: pool_account,
                     fee: None,
+                    spender: None,
                 })?; // * transfer and trace
             }
             if amount1_out > *ZERO {
@@ -464,6 +490,7 @@ fn inner_swap<T: TokenPairArg>(
                     amount: amount1_out.clone(),
                     to: pool_account,
                     fee: None,
+                    spender: None,
                 })?; // * transfer and trace
             }
 
@@ -503,3 +530,150 @@ pub fn swap<T: TokenPairArg>(
 
     Ok(())
 }
+
+// ============================= flash loan =============================
+
+/// Sync reserves to balances of pool after the flash loan is settled.
+/// The protocol fee of flash loan is charged directly, so the changed k is not charged again by mint_fee.
+fn flash_loan_sync(
+    _self: &mut SwapV2MarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, FlashLoan>,
+    pool_account: &Account,
+) -> Result<(), BusinessError> {
+    let balance0 = pool_balance_of(_self, guard, _self.token0, pool_account)?;
+    let balance1 = pool_balance_of(_self, guard, _self.token1, pool_account)?;
+    let (_reserve0, _reserve1) = (_self.reserve0.clone(), _self.reserve1.clone());
+    update(_self, guard, balance0, balance1, _reserve0, _reserve1)?;
+    if _self.k_last != *ZERO {
+        _self.k_last = _self.reserve0.clone() * _self.reserve1.clone();
+    }
+    Ok(())
+}
+
+/// Lend the token of pool to borrower without collateral.
+/// The reserves are not changed, the lent amount is counted in the balance of pool until settled,
+/// so the pool keeps swapping during the callback of borrower.
+pub fn flash_loan_lend(
+    _self: &mut SwapV2MarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, FlashLoan>,
+    self_canister_id: CanisterId,
+) -> Result<(), BusinessError> {
+    let pool_account = Account {
+        owner: self_canister_id,
+        subaccount: Some(_self.subaccount),
+    };
+    let loan = guard.arg.arg.clone();
+    let reserve = if loan.token == _self.token0 {
+        &_self.reserve0
+    } else {
+        &_self.reserve1
+    };
+    let balance = guard.token_balance_of(loan.token, pool_account)?;
+    check_flash_loan_reserve(&loan, reserve, &balance)?;
+    guard.token_transfer(flash_loan_lend_transfer(&loan, pool_account))?; // * transfer and trace
+    if loan.token == _self.token0 {
+        _self.lent0 += loan.amount;
+    } else {
+        _self.lent1 += loan.amount;
+    }
+    Ok(())
+}
+
+/// Settle the flash loan by the amount paid by borrower.
+/// The protocol part of paid fee goes to swap fee_to, the rest goes to liquidity providers.
+/// The part not paid is defaulted and taken by liquidity providers, which is bounded by the cap of loan.
+fn flash_loan_settle(
+    _self: &mut SwapV2MarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, FlashLoan>,
+    self_canister_id: CanisterId,
+    paid: Nat,
+) -> Result<(), BusinessError> {
+    let pool_account = Account {
+        owner: self_canister_id,
+        subaccount: Some(_self.subaccount),
+    };
+    let loan = guard.arg.arg.clone();
+
+    let fee_to = guard
+        .get_swap_fee_to()
+        .filter(|_| _self.protocol_fee.as_ref().is_some_and(|fee| !fee.is_zero()));
+    let protocol_fee = match (fee_to, &_self.protocol_fee) {
+        (Some(_), Some(protocol_fee)) => {
+            Some(flash_loan_paid_fee(&loan, &paid) * protocol_fee.numerator / protocol_fee.denominator)
+        }
+        _ => None,
+    };
+    let defaulted = Some(loan.repayment() - paid.clone()).filter(|defaulted| *ZERO < *defaulted);
+
+    let transaction = SwapTransaction {
+        operation: SwapOperation::Pair(PairOperation::FlashLoan(PairFlashLoan {
+            pa: loan.pa,
+            borrower: loan.borrower,
+            token: loan.token,
+            amount: loan.amount.clone(),
+            fee: loan.fee.clone(),
+            fee_to,
+            protocol_fee: protocol_fee.clone(),
+            defaulted: defaulted.clone(),
+        })),
+        memo: guard.arg.memo.clone(),
+        created: guard.arg.created,
+    };
+    let trace = format!(
+        "*PairFlashLoan* `pa:({}), token:[{}], borrower:({}), amount:{}, fee:{}, paid:{paid}`",
+        loan.pa,
+        loan.token.to_text(),
+        display_account(&loan.borrower),
+        loan.amount,
+        loan.fee,
+    );
+    guard.mint_swap_block(
+        guard.arg.now,
+        transaction,
+        |guard| {
+            let transfers =
+                flash_loan_settle_transfers(&loan, pool_account, fee_to, &protocol_fee.unwrap_or_default(), &paid);
+            for transfer in transfers {
+                guard.token_transfer(transfer)?; // * transfer and trace
+            }
+            Ok(())
+        },
+        trace,
+    )?;
+
+    // the loan is settled, the pool holds the repayment instead of the lent amount
+    let (reserve, lent) = if loan.token == _self.token0 {
+        (&_self.reserve0, &mut _self.lent0)
+    } else {
+        (&_self.reserve1, &mut _self.lent1)
+    };
+    *lent -= loan.amount.clone();
+    // ! check balance of pool after callback, the reserve never goes down unless defaulted
+    if defaulted.is_none() && guard.token_balance_of(loan.token, pool_account)? + lent.clone() < *reserve {
+        return Err(BusinessError::Swap("FLASH_LOAN_NOT_REPAID".into()));
+    }
+
+    flash_loan_sync(_self, guard, &pool_account)
+}
+
+/// The borrower repays the flash loan with fee
+pub fn flash_loan_repay(
+    _self: &mut SwapV2MarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, FlashLoan>,
+    self_canister_id: CanisterId,
+) -> Result<(), BusinessError> {
+    let loan = guard.arg.arg.clone();
+    guard.assert_token_balance(loan.token, loan.borrower, &loan.repayment())?;
+    flash_loan_settle(_self, guard, self_canister_id, loan.repayment())
+}
+
+/// The flash loan is not repaid, what is left in borrower goes to pool
+pub fn flash_loan_default(
+    _self: &mut SwapV2MarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, FlashLoan>,
+    self_canister_id: CanisterId,
+) -> Result<(), BusinessError> {
+    let loan = guard.arg.arg.clone();
+    let paid = guard.token_balance_of(loan.token, loan.borrower)?.min(loan.repayment());
+    flash_loan_settle(_self, guard, self_canister_id, paid)
+}
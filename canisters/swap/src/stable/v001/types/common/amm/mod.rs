@@ -2,7 +2,8 @@ use super::*;
 
 use super::{
     BusinessError, SelfCanister, TokenPairLiquidityAddArg, TokenPairLiquidityAddSuccess, TokenPairLiquidityRemoveArg,
-    TokenPairLiquidityRemoveSuccess,
+    TokenPairLiquidityRemoveSuccess, TokenPoolLiquidityAddArg, TokenPoolLiquidityAddSuccess,
+    TokenPoolLiquidityRemoveArg, TokenPoolLiquidityRemoveSuccess,
 };
 
 /// Automated Market Maker
@@ -10,6 +11,26 @@ mod cpmm;
 #[allow(unused)]
 pub use cpmm::*;
 
+/// Concentrated Liquidity Market Maker
+mod clmm;
+#[allow(unused)]
+pub use clmm::*;
+
+/// Stablecoin Market Maker
+mod smm;
+#[allow(unused)]
+pub use smm::*;
+
+/// Weighted Market Maker
+mod lmm;
+#[allow(unused)]
+pub use lmm::*;
+
+/// Multi-asset Stablecoin Market Maker
+mod msmm;
+#[allow(unused)]
+pub use msmm::*;
+
 /// Proactive Market Maker
 /// https://docs.dodoex.io/zh/product/pmm-algorithm/details-about-pmm
 /// https://dodoex.github.io/cn/docs/
@@ -22,7 +43,32 @@ pub fn add_liquidity(
     guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, TokenPairLiquidityAddArg>,
 ) -> Result<TokenPairLiquidityAddSuccess, BusinessError> {
     match _self {
-        MarketMaker::SwapV2(value) => cpmm::add_liquidity(value, guard),
+        MarketMaker::SwapV2(value) => {
+            if guard.arg.arg.tick_range.is_some() {
+                return Err(BusinessError::Liquidity("TICK_RANGE_NOT_SUPPORTED".into()));
+            }
+            cpmm::add_liquidity(value, guard)
+        }
+        MarketMaker::SwapV3(value) => clmm::add_liquidity(value, guard),
+        MarketMaker::StableSwap(value) => {
+            if guard.arg.arg.tick_range.is_some() {
+                return Err(BusinessError::Liquidity("TICK_RANGE_NOT_SUPPORTED".into()));
+            }
+            smm::add_liquidity(value, guard)
+        }
+        MarketMaker::PmmV1(value) => {
+            if guard.arg.arg.tick_range.is_some() {
+                return Err(BusinessError::Liquidity("TICK_RANGE_NOT_SUPPORTED".into()));
+            }
+            pmm_v1::add_liquidity(value, guard)
+        }
+        MarketMaker::Weighted(value) => {
+            if guard.arg.arg.tick_range.is_some() {
+                return Err(BusinessError::Liquidity("TICK_RANGE_NOT_SUPPORTED".into()));
+            }
+            lmm::add_liquidity(value, guard)
+        }
+        MarketMaker::StablePool(_) => Err(BusinessError::Liquidity("MULTI_ASSET_POOL_NOT_SUPPORTED".into())),
     }
 }
 
@@ -31,7 +77,52 @@ pub fn remove_liquidity(
     guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, TokenPairLiquidityRemoveArg>,
 ) -> Result<TokenPairLiquidityRemoveSuccess, BusinessError> {
     match _self {
-        MarketMaker::SwapV2(value) => cpmm::remove_liquidity(value, guard),
+        MarketMaker::SwapV2(value) => {
+            if guard.arg.arg.tick_range.is_some() {
+                return Err(BusinessError::Liquidity("TICK_RANGE_NOT_SUPPORTED".into()));
+            }
+            cpmm::remove_liquidity(value, guard)
+        }
+        MarketMaker::SwapV3(value) => clmm::remove_liquidity(value, guard),
+        MarketMaker::StableSwap(value) => {
+            if guard.arg.arg.tick_range.is_some() {
+                return Err(BusinessError::Liquidity("TICK_RANGE_NOT_SUPPORTED".into()));
+            }
+            smm::remove_liquidity(value, guard)
+        }
+        MarketMaker::PmmV1(value) => {
+            if guard.arg.arg.tick_range.is_some() {
+                return Err(BusinessError::Liquidity("TICK_RANGE_NOT_SUPPORTED".into()));
+            }
+            pmm_v1::remove_liquidity(value, guard)
+        }
+        MarketMaker::Weighted(value) => {
+            if guard.arg.arg.tick_range.is_some() {
+                return Err(BusinessError::Liquidity("TICK_RANGE_NOT_SUPPORTED".into()));
+            }
+            lmm::remove_liquidity(value, guard)
+        }
+        MarketMaker::StablePool(_) => Err(BusinessError::Liquidity("MULTI_ASSET_POOL_NOT_SUPPORTED".into())),
+    }
+}
+
+pub fn add_pool_liquidity(
+    _self: &mut MarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, TokenPoolLiquidityAddArg>,
+) -> Result<TokenPoolLiquidityAddSuccess, BusinessError> {
+    match _self {
+        MarketMaker::StablePool(value) => msmm::add_liquidity(value, guard),
+        _ => Err(BusinessError::Liquidity("NOT_MULTI_ASSET_POOL".into())),
+    }
+}
+
+pub fn remove_pool_liquidity(
+    _self: &mut MarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, TokenPoolLiquidityRemoveArg>,
+) -> Result<TokenPoolLiquidityRemoveSuccess, BusinessError> {
+    match _self {
+        MarketMaker::StablePool(value) => msmm::remove_liquidity(value, guard),
+        _ => Err(BusinessError::Liquidity("NOT_MULTI_ASSET_POOL".into())),
     }
 }
 
@@ -58,5 +149,89 @@ pub fn swap<T: TokenPairArg>(
             amount1_out,
             to,
         ),
+        MarketMaker::SwapV3(value) => clmm::swap(
+            value,
+            guard,
+            transaction,
+            trace,
+            self_canister,
+            amount0_out,
+            amount1_out,
+            to,
+        ),
+        MarketMaker::StableSwap(value) => smm::swap(
+            value,
+            guard,
+            transaction,
+            trace,
+            self_canister,
+            amount0_out,
+            amount1_out,
+            to,
+        ),
+        MarketMaker::PmmV1(value) => pmm_v1::swap(
+            value,
+            guard,
+            transaction,
+            trace,
+            self_canister,
+            amount0_out,
+            amount1_out,
+            to,
+        ),
+        MarketMaker::Weighted(value) => lmm::swap(
+            value,
+            guard,
+            transaction,
+            trace,
+            self_canister,
+            amount0_out,
+            amount1_out,
+            to,
+        ),
+        MarketMaker::StablePool(value) => msmm::swap(
+            value,
+            guard,
+            transaction,
+            trace,
+            self_canister,
+            amount0_out,
+            amount1_out,
+            to,
+        ),
+    }
+}
+
+/// Only swap v2 pools lend, the other makers can not sync reserves to the balances of pool
+pub fn flash_loan_lend(
+    _self: &mut MarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, FlashLoan>,
+    self_canister_id: CanisterId,
+) -> Result<(), BusinessError> {
+    match _self {
+        MarketMaker::SwapV2(value) => cpmm::flash_loan_lend(value, guard, self_canister_id),
+        _ => Err(BusinessError::Swap("FLASH_LOAN_NOT_SUPPORTED".into())),
+    }
+}
+
+pub fn flash_loan_repay(
+    _self: &mut MarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, FlashLoan>,
+    self_canister_id: CanisterId,
+) -> Result<(), BusinessError> {
+    match _self {
+        MarketMaker::SwapV2(value) => cpmm::flash_loan_repay(value, guard, self_canister_id),
+        _ => Err(BusinessError::Swap("FLASH_LOAN_NOT_SUPPORTED".into())),
+    }
+}
+
+pub fn flash_loan_default(
+    _self: &mut MarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, FlashLoan>,
+    self_canister_id: CanisterId,
+) -> Result<(), BusinessError> {
+    match _self {
+        MarketMaker::SwapV2(value) => cpmm::flash_loan_default(value, guard, self_canister_id),
+        _ => Err(BusinessError::Swap("FLASH_LOAN_NOT_SUPPORTED".into())),
     }
 }
@@ -0,0 +1,321 @@
+/// Multi-asset stablecoin market maker（StableSwap AMM with n tokens）
+/// - Formula：A * n^n * Σx + D = A * D * n^n + D^(n+1) / (n^n * Πx)（A is the amplification coefficient）
+/// - Representative Project：Curve 3pool (USDC/USDT/DAI)
+/// - Features
+///   - All member tokens share one pool account and one lp token.
+///   - Any member pair can be swapped directly inside the pool.
+use ::common::{
+    types::{StablePoolMarketMaker, TokenPool, TokenPoolAmm},
+    utils::math::{ZERO, zero},
+};
+
+use super::*;
+
+use crate::types::{
+    BusinessError, SelfCanister, TokenPoolLiquidityAddArg, TokenPoolLiquidityAddSuccess, TokenPoolLiquidityRemoveArg,
+    TokenPoolLiquidityRemoveSuccess,
+};
+
+fn update<T>(
+    _self: &mut StablePoolMarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, T>,
+    pool: &TokenPoolAmm,
+    reserves: Vec<Nat>,
+) -> Result<(), BusinessError> {
+    _self.reserves = reserves;
+    _self.block_timestamp_last = guard.arg.now.into_inner();
+    let invariant = _self.get_invariant(&_self.reserves)?;
+    guard.push_stable_pool_state(
+        pool,
+        _self.amplification,
+        _self.lp.get_total_supply(),
+        _self.reserves.clone(),
+        invariant,
+    )?;
+    Ok(())
+}
+
+fn balances_of<T>(
+    _self: &StablePoolMarketMaker,
+    guard: &InnerTokenPairSwapGuard<'_, '_, '_, T>,
+    pool_account: Account,
+) -> Result<Vec<Nat>, BusinessError> {
+    _self
+        .tokens
+        .iter()
+        .map(|token| guard.token_balance_of(*token, pool_account))
+        .collect()
+}
+
+pub fn add_liquidity(
+    _self: &mut StablePoolMarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, TokenPoolLiquidityAddArg>,
+) -> Result<TokenPoolLiquidityAddSuccess, BusinessError> {
+    // ! check balance
+    {
+        let arg = &guard.arg.arg;
+        for (token, amount) in _self.tokens.iter().zip(arg.amounts_desired.iter()) {
+            guard.assert_token_balance(*token, arg.from, amount)?;
+        }
+        guard.trace(format!(
+            "*PoolLiquidityAdd* `pool:({}), amplification:{}, amounts:[{}]`",
+            arg.pool,
+            _self.amplification,
+            arg.amounts_desired
+                .iter()
+                .map(|a| a.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        )); // * trace
+    }
+
+    // calculate liquidity, any ratio of tokens is accepted
+    let arg = &guard.arg.arg;
+    let amounts = arg.amounts_desired.clone();
+    let liquidity = _self.get_liquidity_minted(&amounts)?;
+    if liquidity == *ZERO {
+        return Err(BusinessError::Liquidity("INSUFFICIENT_LIQUIDITY_MINTED".into()));
+    }
+    if liquidity < arg.liquidity_min {
+        return Err(BusinessError::Liquidity("INSUFFICIENT_LIQUIDITY_MINTED".into()));
+    }
+
+    // Pool token account
+    let pool = arg.pool.clone();
+    let pool_account = Account {
+        owner: arg.self_canister.id(),
+        subaccount: Some(_self.subaccount),
+    };
+    for (token, amount) in _self.tokens.iter().zip(amounts.iter()) {
+        if *amount == *ZERO {
+            continue;
+        }
+        let arg = &guard.arg.arg;
+        guard.token_transfer(TransferToken {
+            token: *token,
+            from: arg.from,
+            amount: amount.clone(),
+            to: pool_account,
+            fee: None,
+            spender: None,
+        })?; // * transfer and trace
+    }
+
+    // do mint，Mint LP tokens for users
+    let arg_to = guard.arg.arg.to;
+    _self.lp.mint(
+        |token, to, amount| guard.token_pool_liquidity_mint(&amounts, token, pool_account, to, amount),
+        arg_to,
+        liquidity.clone(),
+    )?;
+
+    // Update the current balance
+    let reserves = balances_of(_self, guard, pool_account)?;
+    update(_self, guard, &pool, reserves)?;
+
+    Ok(TokenPoolLiquidityAddSuccess { amounts, liquidity })
+}
+
+pub fn remove_liquidity(
+    _self: &mut StablePoolMarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, TokenPoolLiquidityRemoveArg>,
+) -> Result<TokenPoolLiquidityRemoveSuccess, BusinessError> {
+    // ! check balance
+    {
+        let arg = &guard.arg.arg;
+        _self.lp.check_liquidity_removable(
+            |token| guard.token_balance_of(token, arg.from),
+            &arg.liquidity_without_fee,
+            arg.fee.as_ref().map(|fee| fee.fee_to),
+        )?;
+        guard.trace(format!(
+            "*PoolLiquidityRemove* `pool:({}), liquidity_without_fee:{}, required: [{}] <= amounts`",
+            arg.pool,
+            arg.liquidity_without_fee,
+            arg.amounts_min
+                .iter()
+                .map(|a| a.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        )); // * trace
+    }
+
+    // Transfer token account from the pool
+    let arg = &guard.arg.arg;
+    let pool = arg.pool.clone();
+    let pool_account = Account {
+        owner: arg.self_canister.id(),
+        subaccount: Some(_self.subaccount),
+    };
+    let balances = balances_of(_self, guard, pool_account)?;
+
+    // Remove in proportion to balances
+    let arg = &guard.arg.arg;
+    let liquidity_without_fee = arg.liquidity_without_fee.clone();
+    let _total_supply = _self.lp.get_total_supply();
+    let amounts = balances
+        .into_iter()
+        .map(|balance| liquidity_without_fee.clone() * balance / _total_supply.clone())
+        .collect::<Vec<_>>();
+
+    // ! check amount before change data
+    if amounts.iter().all(|amount| *amount == *ZERO) {
+        return Err(BusinessError::Liquidity("INSUFFICIENT_LIQUIDITY_BURNED".into()));
+    }
+    if amounts
+        .iter()
+        .zip(arg.amounts_min.iter())
+        .any(|(amount, min)| amount < min)
+    {
+        return Err(BusinessError::Liquidity("INSUFFICIENT_AMOUNT".into()));
+    }
+
+    // do burn，Destroy LP tokens for users
+    let arg_from = arg.from;
+    let arg_fee = arg.fee.clone();
+    _self.lp.burn(
+        |token, from, amount_without_fee, fee| {
+            guard.token_pool_liquidity_burn(
+                &amounts,
+                token,
+                from,
+                pool_account,
+                amount_without_fee, // will burn amount_without_fee + fee and mint fee to fee_to
+                fee,
+            )
+        },
+        arg_from,
+        liquidity_without_fee,
+        arg_fee, // burn fee to use token fee to
+    )?;
+
+    // return token
+    for (token, amount) in _self.tokens.iter().zip(amounts.iter()) {
+        if *amount == *ZERO {
+            continue;
+        }
+        let arg = &guard.arg.arg;
+        guard.token_transfer(TransferToken {
+            token: *token,
+            from: pool_account,
+            amount: amount.clone(),
+            to: arg.to,
+            fee: None,
+            spender: None,
+        })?; // * transfer and trace
+    }
+
+    // Update the current balance
+    let reserves = balances_of(_self, guard, pool_account)?;
+    update(_self, guard, &pool, reserves)?;
+
+    Ok(TokenPoolLiquidityRemoveSuccess { amounts })
+}
+
+fn inner_swap<T>(
+    _self: &mut StablePoolMarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, &T>,
+    pa: &TokenPairAmm,
+    self_canister: &SelfCanister,
+    amount0_out: Nat,
+    amount1_out: Nat,
+    to: Account,
+) -> Result<Vec<Nat>, BusinessError> {
+    // Pool's account
+    let pool_account = Account {
+        owner: self_canister.id(),
+        subaccount: Some(_self.subaccount),
+    };
+
+    // Only one token can be output
+    let (token0, token1) = (pa.pair.get_token0(), pa.pair.get_token1());
+    let (token_in, token_out, amount_out) = match (amount0_out == *ZERO, amount1_out == *ZERO) {
+        (true, false) => (token0, token1, amount1_out),
+        (false, true) => (token1, token0, amount0_out),
+        _ => return Err(BusinessError::Swap("INSUFFICIENT_OUTPUT_AMOUNT".into())),
+    };
+    if to.owner == token_in || to.owner == token_out {
+        return Err(BusinessError::Swap("INVALID_TO".into())); // The output token target address cannot be the token itself
+    }
+
+    // The input token should be transferred in advance before calling this function.
+    let reserve_in = _self.reserves[_self.index_of(&token_in)?].clone();
+    let balance_in = guard.token_balance_of(token_in, pool_account)?;
+    let amount_in = if balance_in > reserve_in {
+        balance_in - reserve_in
+    } else {
+        zero()
+    };
+    if amount_in == *ZERO {
+        return Err(BusinessError::Swap("INSUFFICIENT_INPUT_AMOUNT".into()));
+    }
+
+    // check invariant before changed
+    let (max_amount_out, fee) = _self.exchange(&amount_in, token_in, token_out)?;
+    if max_amount_out < amount_out {
+        return Err(BusinessError::Swap("K".into()));
+    }
+
+    // do transfer out
+    guard.token_transfer(TransferToken {
+        token: token_out,
+        from: pool_account,
+        amount: amount_out,
+        to,
+        fee: None,
+        spender: None,
+    })?; // * transfer and trace
+
+    // The protocol fee is split from the swap fee
+    if let (Some(fee_to), Some(protocol_fee)) = (guard.get_swap_fee_to(), &_self.protocol_fee) {
+        let protocol_fee = fee * protocol_fee.numerator / protocol_fee.denominator;
+        if protocol_fee > *ZERO {
+            guard.token_transfer(TransferToken {
+                token: token_out,
+                from: pool_account,
+                amount: protocol_fee,
+                to: fee_to,
+                fee: None,
+                spender: None,
+            })?; // * transfer and trace
+        }
+    }
+
+    // only the balances of 2 members are changed
+    let mut reserves = _self.reserves.clone();
+    for token in [token_in, token_out] {
+        reserves[_self.index_of(&token)?] = guard.token_balance_of(token, pool_account)?;
+    }
+    Ok(reserves)
+}
+
+/// Be sure to transfer the corresponding token first, and then call this method to transfer the token
+#[inline]
+#[allow(clippy::too_many_arguments)]
+pub fn swap<T: TokenPairArg>(
+    _self: &mut StablePoolMarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, T>,
+    transaction: SwapTransaction,
+    trace: String,
+    self_canister: &SelfCanister,
+    amount0_out: Nat,
+    amount1_out: Nat,
+    to: Account,
+) -> Result<(), BusinessError> {
+    let pa = *guard.arg.arg.get_pa();
+    let pool = TokenPoolAmm {
+        pool: TokenPool::new(&_self.tokens)?,
+        amm: pa.amm,
+    };
+
+    let reserves = guard.mint_swap_block(
+        guard.arg.now,
+        transaction,
+        |guard| inner_swap(_self, guard, &pa, self_canister, amount0_out, amount1_out, to),
+        trace,
+    )?;
+
+    update(_self, guard, &pool, reserves)?;
+
+    Ok(())
+}
@@ -1,7 +1,314 @@
 /// Stablecoin market maker（StableSwap AMM）
-/// - Formula：(x + y)^n = k（n is the regulation parameter）
+/// - Formula：A * n^n * Σx + D = A * D * n^n + D^(n+1) / (n^n * Πx)（A is the amplification coefficient）
 /// - Representative Project：Curve、Fei Protocol
 /// - Features
 ///   - Designed for stable coins or similar assets, the slippage is extremely low.
 ///   - Reduce arbitrage opportunities through the "virtual price" mechanism.
 ///   - Curve supports complex pools such as 3pool (USDC/USDT/DAI).
+use ::common::{
+    types::StableSwapMarketMaker,
+    utils::math::{ZERO, zero},
+};
+
+use super::*;
+
+use crate::types::{
+    BusinessError, SelfCanister, TokenPairLiquidityAddArg, TokenPairLiquidityAddSuccess, TokenPairLiquidityRemoveArg,
+    TokenPairLiquidityRemoveSuccess,
+};
+
+fn update<T: TokenPairArg>(
+    _self: &mut StableSwapMarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, T>,
+    balance0: Nat,
+    balance1: Nat,
+) -> Result<(), BusinessError> {
+    _self.reserve0 = balance0;
+    _self.reserve1 = balance1;
+    _self.block_timestamp_last = guard.arg.now.into_inner();
+    let invariant = _self.get_invariant(&_self.reserve0, &_self.reserve1)?;
+    guard.push_stable_swap_state(
+        _self.amplification,
+        _self.lp.get_total_supply(),
+        _self.reserve0.clone(),
+        _self.reserve1.clone(),
+        invariant,
+    )?;
+    Ok(())
+}
+
+pub fn add_liquidity(
+    _self: &mut StableSwapMarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, TokenPairLiquidityAddArg>,
+) -> Result<TokenPairLiquidityAddSuccess, BusinessError> {
+    // ! check balance
+    {
+        let arg = &guard.arg.arg;
+        guard.assert_token_balance(arg.token_a, arg.from, &arg.amount_a_desired)?;
+        guard.assert_token_balance(arg.token_b, arg.from, &arg.amount_b_desired)?;
+        guard.trace(format!(
+            "*PairLiquidityAdd* `tokenA:[{}], tokenB:[{}], amm:{}, amplification:{}, amount_a:{}, amount_b:{}`",
+            arg.token_a.to_text(),
+            arg.token_b.to_text(),
+            arg.pa.amm.into_text().as_ref(),
+            _self.amplification,
+            arg.amount_a_desired,
+            arg.amount_b_desired,
+        )); // * trace
+    }
+
+    // calculate liquidity, any ratio of 2 tokens is accepted
+    let arg = &guard.arg.arg;
+    let (amount_a, amount_b) = (arg.amount_a_desired.clone(), arg.amount_b_desired.clone());
+    let (amount0, amount1) = if arg.token_a == _self.token0 {
+        (amount_a.clone(), amount_b.clone())
+    } else {
+        (amount_b.clone(), amount_a.clone())
+    };
+    let liquidity = _self.get_liquidity_minted(&amount0, &amount1)?;
+    if liquidity == *ZERO {
+        return Err(BusinessError::Liquidity("INSUFFICIENT_LIQUIDITY_MINTED".into()));
+    }
+
+    // Pool token account
+    let message = format!("*PairLiquidityAdd* `amount_a:{amount_a}, amount_b:{amount_b}`");
+    let pool_account = Account {
+        owner: arg.self_canister.id(),
+        subaccount: Some(_self.subaccount),
+    };
+    guard.token_transfer(TransferToken {
+        token: arg.token_a,
+        from: arg.from,
+        amount: amount_a.clone(),
+        to: pool_account,
+        fee: None,
+        spender: None,
+    })?; // * transfer and trace
+    let arg = &guard.arg.arg;
+    guard.token_transfer(TransferToken {
+        token: arg.token_b,
+        from: arg.from,
+        amount: amount_b.clone(),
+        to: pool_account,
+        fee: None,
+        spender: None,
+    })?; // * transfer and trace
+    guard.trace(message); // * trace
+
+    // do mint，Mint LP tokens for users
+    let arg_to = guard.arg.arg.to;
+    _self.lp.mint(
+        |token, to, amount| guard.token_liquidity_mint(&amount_a, &amount_b, token, pool_account, to, amount),
+        arg_to,
+        liquidity.clone(),
+    )?;
+
+    // Update the current balance
+    let balance0 = guard.token_balance_of(_self.token0, pool_account)?;
+    let balance1 = guard.token_balance_of(_self.token1, pool_account)?;
+    update(_self, guard, balance0, balance1)?;
+
+    Ok(TokenPairLiquidityAddSuccess {
+        amount: (amount_a, amount_b),
+        liquidity,
+    })
+}
+
+pub fn remove_liquidity(
+    _self: &mut StableSwapMarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, TokenPairLiquidityRemoveArg>,
+) -> Result<TokenPairLiquidityRemoveSuccess, BusinessError> {
+    // ! check balance
+    {
+        let arg = &guard.arg.arg;
+        _self.lp.check_liquidity_removable(
+            |token| guard.token_balance_of(token, arg.from),
+            &arg.liquidity_without_fee,
+            arg.fee.as_ref().map(|fee| fee.fee_to),
+        )?;
+        guard.trace(format!(
+            "*PairLiquidityRemove* `tokenA:[{}], tokenB:[{}], amm:{}, liquidity_without_fee:{}, required: {} <= amount_a && {} <= amount_b`",
+            arg.token_a.to_text(),
+            arg.token_b.to_text(),
+            arg.pa.amm.into_text().as_ref(),
+            arg.liquidity_without_fee,
+            arg.amount_a_min,
+            arg.amount_b_min,
+        )); // * trace
+    }
+
+    // Transfer token account from the pool
+    let arg = &guard.arg.arg;
+    let pool_account = Account {
+        owner: arg.self_canister.id(),
+        subaccount: Some(_self.subaccount),
+    };
+    let (token0, token1) = (_self.token0, _self.token1);
+    let balance0 = guard.token_balance_of(token0, pool_account)?;
+    let balance1 = guard.token_balance_of(token1, pool_account)?;
+
+    // Remove in proportion to balances
+    let liquidity_without_fee = arg.liquidity_without_fee.clone();
+    let _total_supply = _self.lp.get_total_supply();
+    let amount0 = liquidity_without_fee.clone() * balance0 / _total_supply.clone();
+    let amount1 = liquidity_without_fee.clone() * balance1 / _total_supply;
+
+    // ! check amount before change data
+    if amount0 == *ZERO && amount1 == *ZERO {
+        return Err(BusinessError::Liquidity("INSUFFICIENT_LIQUIDITY_BURNED".into()));
+    }
+    let (amount_a, amount_b) = if arg.token_a == token0 {
+        (amount0.clone(), amount1.clone())
+    } else {
+        (amount1.clone(), amount0.clone())
+    };
+    if amount_a < arg.amount_a_min {
+        return Err(BusinessError::Liquidity("INSUFFICIENT_A_AMOUNT".into()));
+    }
+    if amount_b < arg.amount_b_min {
+        return Err(BusinessError::Liquidity("INSUFFICIENT_B_AMOUNT".into()));
+    }
+
+    // do burn，Destroy LP tokens for users
+    let arg_from = arg.from;
+    let arg_fee = arg.fee.clone();
+    _self.lp.burn(
+        |token, from, amount_without_fee, fee| {
+            guard.token_liquidity_burn(
+                &amount_a,
+                &amount_b,
+                token,
+                from,
+                pool_account,
+                amount_without_fee, // will burn amount_without_fee + fee and mint fee to fee_to
+                fee,
+            )
+        },
+        arg_from,
+        liquidity_without_fee,
+        arg_fee, // burn fee to use token fee to
+    )?;
+
+    // return token
+    let message = format!("*PairLiquidityRemove* `amount0:{amount0}, amount1:{amount1}`");
+    for (token, amount) in [(token0, amount0), (token1, amount1)] {
+        if amount == *ZERO {
+            continue;
+        }
+        let arg = &guard.arg.arg;
+        guard.token_transfer(TransferToken {
+            token,
+            from: pool_account,
+            amount,
+            to: arg.to,
+            fee: None,
+            spender: None,
+        })?; // * transfer and trace
+    }
+    guard.trace(message); // * trace
+
+    // Update the current balance
+    let balance0 = guard.token_balance_of(token0, pool_account)?;
+    let balance1 = guard.token_balance_of(token1, pool_account)?;
+    update(_self, guard, balance0, balance1)?;
+
+    Ok(TokenPairLiquidityRemoveSuccess {
+        amount: (amount_a, amount_b),
+    })
+}
+
+fn inner_swap<T: TokenPairArg>(
+    _self: &mut StableSwapMarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, &T>,
+    self_canister: &SelfCanister,
+    amount0_out: Nat,
+    amount1_out: Nat,
+    to: Account,
+) -> Result<(Nat, Nat), BusinessError> {
+    // Pool's account
+    let pool_account = Account {
+        owner: self_canister.id(),
+        subaccount: Some(_self.subaccount),
+    };
+
+    // Only one token can be output
+    let (token_in, token_out, reserve_in, amount_out) = match (amount0_out == *ZERO, amount1_out == *ZERO) {
+        (true, false) => (_self.token0, _self.token1, _self.reserve0.clone(), amount1_out),
+        (false, true) => (_self.token1, _self.token0, _self.reserve1.clone(), amount0_out),
+        _ => return Err(BusinessError::Swap("INSUFFICIENT_OUTPUT_AMOUNT".into())),
+    };
+    if to.owner == token_in || to.owner == token_out {
+        return Err(BusinessError::Swap("INVALID_TO".into())); // The output token target address cannot be the token itself
+    }
+
+    // The input token should be transferred in advance before calling this function.
+    let balance_in = guard.token_balance_of(token_in, pool_account)?;
+    let amount_in = if balance_in > reserve_in {
+        balance_in - reserve_in
+    } else {
+        zero()
+    };
+    if amount_in == *ZERO {
+        return Err(BusinessError::Swap("INSUFFICIENT_INPUT_AMOUNT".into()));
+    }
+
+    // check invariant before changed
+    let (max_amount_out, fee) = _self.exchange(&amount_in, token_in, token_out)?;
+    if max_amount_out < amount_out {
+        return Err(BusinessError::Swap("K".into()));
+    }
+
+    // do transfer out
+    guard.token_transfer(TransferToken {
+        token: token_out,
+        from: pool_account,
+        amount: amount_out,
+        to,
+        fee: None,
+        spender: None,
+    })?; // * transfer and trace
+
+    // The protocol fee is split from the swap fee
+    if let (Some(fee_to), Some(protocol_fee)) = (guard.get_swap_fee_to(), &_self.protocol_fee) {
+        let protocol_fee = fee * protocol_fee.numerator / protocol_fee.denominator;
+        if protocol_fee > *ZERO {
+            guard.token_transfer(TransferToken {
+                token: token_out,
+                from: pool_account,
+                amount: protocol_fee,
+                to: fee_to,
+                fee: None,
+                spender: None,
+            })?; // * transfer and trace
+        }
+    }
+
+    let balance0 = guard.token_balance_of(_self.token0, pool_account)?;
+    let balance1 = guard.token_balance_of(_self.token1, pool_account)?;
+    Ok((balance0, balance1))
+}
+
+/// Be sure to transfer the corresponding token first, and then call this method to transfer the token
+#[inline]
+#[allow(clippy::too_many_arguments)]
+pub fn swap<T: TokenPairArg>(
+    _self: &mut StableSwapMarketMaker,
+    guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, T>,
+    transaction: SwapTransaction,
+    trace: String,
+    self_canister: &SelfCanister,
+    amount0_out: Nat,
+    amount1_out: Nat,
+    to: Account,
+) -> Result<(), BusinessError> {
+    let (balance0, balance1) = guard.mint_swap_block(
+        guard.arg.now,
+        transaction,
+        |guard| inner_swap(_self, guard, self_canister, amount0_out, amount1_out, to),
+        trace,
+    )?;
+
+    update(_self, guard, balance0, balance1)?;
+
+    Ok(())
+}
@@ -6,13 +6,20 @@ use icrc_ledger_types::icrc1::account::Account;
 use crate::types::{SelfCanisterArg, TokenPairArg};
 
 use super::super::{
-    ArgWithMeta, BusinessError, DepositToken, PairOperation, RequestTraceGuard, SwapBlockChainGuard, SwapOperation,
-    SwapTransaction, SwapV2BurnToken, SwapV2MintFeeToken, SwapV2MintToken, SwapV2Operation, SwapV2State,
-    TokenBalancesGuard, TokenBlockChainGuard, TokenPairAmm, TokenPairLiquidityAddArg, TokenPairLiquidityAddSuccess,
-    TokenPairLiquidityAddSuccessView, TokenPairLiquidityRemoveArg, TokenPairLiquidityRemoveSuccess,
-    TokenPairLiquidityRemoveSuccessView, TokenPairSwapByLoanArg, TokenPairSwapExactTokensForTokensArg,
-    TokenPairSwapTokensForExactTokensArg, TokenPairSwapTokensSuccess, TokenPairSwapTokensSuccessView, TokenPairsGuard,
-    TransferToken, WithdrawToken, display_account,
+    ArgWithMeta, BusinessError, DepositToken, FlashLoan, PairOperation, PmmV1Operation, PmmV1State,
+    ProtocolFeesCollectArg, ProtocolFeesCollectSuccess, ProtocolFeesCollectSuccessView, RequestTraceGuard,
+    StablePoolBurnToken, StablePoolMintToken, StablePoolOperation, StablePoolState, StableSwapOperation,
+    StableSwapState, SwapBlockChainGuard, SwapOperation, SwapTransaction, SwapV2BurnToken, SwapV2MintFeeToken,
+    SwapV2MintToken, SwapV2Operation, SwapV2State, SwapV3BurnToken, SwapV3MintToken, SwapV3Operation, SwapV3SwapToken,
+    TickRange, TokenBalancesGuard, TokenBlockChainGuard, TokenPairAmm, TokenPairLiquidityAddArg,
+    TokenPairLiquidityAddSuccess, TokenPairLiquidityAddSuccessView, TokenPairLiquidityRemoveArg,
+    TokenPairLiquidityRemoveSuccess, TokenPairLiquidityRemoveSuccessView, TokenPairSwapByLoanArg,
+    TokenPairSwapExactTokensForTokensArg, TokenPairSwapSplitArg, TokenPairSwapSplitSuccess,
+    TokenPairSwapSplitSuccessView, TokenPairSwapTokensForExactTokensArg, TokenPairSwapTokensSuccess,
+    TokenPairSwapTokensSuccessView, TokenPairsGuard, TokenPoolAmm, TokenPoolLiquidityAddArg,
+    TokenPoolLiquidityAddSuccess, TokenPoolLiquidityAddSuccessView, TokenPoolLiquidityRemoveArg,
+    TokenPoolLiquidityRemoveSuccess, TokenPoolLiquidityRemoveSuccessView, TransferToken, WeightedOperation,
+    WeightedState, WithdrawToken, display_account,
 };
 
 pub struct TokenPairSwapGuard<'a> {
@@ -112,6 +119,56 @@ impl<'a> TokenPairSwapGuard<'a> {
         )
     }
 
+    pub fn add_pool_liquidity(
+        &mut self,
+        arg: ArgWithMeta<TokenPoolLiquidityAddArg>,
+    ) -> Result<TokenPoolLiquidityAddSuccess, BusinessError> {
+        self.trace_guard.handle(
+            |trace| {
+                let pool = arg.arg.pool.clone();
+                let mut inner = InnerTokenPairSwapGuard {
+                    trace_guard: trace,
+                    balances_guard: &mut self.balances_guard,
+                    token_guard: &mut self.token_guard,
+                    swap_guard: &mut self.swap_guard,
+                    arg,
+                };
+                let data = self.pairs_guard.add_pool_liquidity(&mut inner, &pool)?;
+                trace.trace("Token Pool Add Liquidity Done.".into());
+                Ok(data)
+            },
+            |data| {
+                let view: TokenPoolLiquidityAddSuccessView = data.into();
+                serde_json::to_string(&view).unwrap_or_default()
+            },
+        )
+    }
+
+    pub fn remove_pool_liquidity(
+        &mut self,
+        arg: ArgWithMeta<TokenPoolLiquidityRemoveArg>,
+    ) -> Result<TokenPoolLiquidityRemoveSuccess, BusinessError> {
+        self.trace_guard.handle(
+            |trace| {
+                let pool = arg.arg.pool.clone();
+                let mut inner = InnerTokenPairSwapGuard {
+                    trace_guard: trace,
+                    balances_guard: &mut self.balances_guard,
+                    token_guard: &mut self.token_guard,
+                    swap_guard: &mut self.swap_guard,
+                    arg,
+                };
+                let data = self.pairs_guard.remove_pool_liquidity(&mut inner, &pool)?;
+                trace.trace("Token Pool Remove Liquidity Done.".into());
+                Ok(data)
+            },
+            |data| {
+                let view: TokenPoolLiquidityRemoveSuccessView = data.into();
+                serde_json::to_string(&view).unwrap_or_default()
+            },
+        )
+    }
+
     pub fn swap_exact_tokens_for_tokens(
         &mut self,
         arg: ArgWithMeta<TokenPairSwapExactTokensForTokensArg>,
@@ -186,6 +243,111 @@ impl<'a> TokenPairSwapGuard<'a> {
             },
         )
     }
+
+    pub fn swap_split(
+        &mut self,
+        arg: ArgWithMeta<TokenPairSwapSplitArg>,
+    ) -> Result<TokenPairSwapSplitSuccess, BusinessError> {
+        self.trace_guard.handle(
+            |trace| {
+                let mut inner = InnerTokenPairSwapGuard {
+                    trace_guard: trace,
+                    balances_guard: &mut self.balances_guard,
+                    token_guard: &mut self.token_guard,
+                    swap_guard: &mut self.swap_guard,
+                    arg,
+                };
+                let data = self.pairs_guard.swap_split(&mut inner)?;
+                trace.trace("Token Pair Swap Split Done.".into());
+                Ok(data)
+            },
+            |data| {
+                let view: TokenPairSwapSplitSuccessView = data.into();
+                serde_json::to_string(&view).unwrap_or_default()
+            },
+        )
+    }
+
+    pub fn flash_loan_lend(&mut self, arg: ArgWithMeta<FlashLoan>) -> Result<FlashLoan, BusinessError> {
+        self.trace_guard.handle(
+            |trace| {
+                let loan = arg.arg.clone();
+                let mut inner = InnerTokenPairSwapGuard {
+                    trace_guard: trace,
+                    balances_guard: &mut self.balances_guard,
+                    token_guard: &mut self.token_guard,
+                    swap_guard: &mut self.swap_guard,
+                    arg,
+                };
+                self.pairs_guard.flash_loan_lend(&mut inner)?;
+                trace.trace("Token Pair Flash Loan Lend Done.".into());
+                Ok(loan)
+            },
+            |loan| loan.id.to_string(),
+        )
+    }
+
+    pub fn flash_loan_repay(&mut self, arg: ArgWithMeta<FlashLoan>) -> Result<FlashLoan, BusinessError> {
+        self.trace_guard.handle(
+            |trace| {
+                let loan = arg.arg.clone();
+                let mut inner = InnerTokenPairSwapGuard {
+                    trace_guard: trace,
+                    balances_guard: &mut self.balances_guard,
+                    token_guard: &mut self.token_guard,
+                    swap_guard: &mut self.swap_guard,
+                    arg,
+                };
+                self.pairs_guard.flash_loan_repay(&mut inner)?;
+                trace.trace("Token Pair Flash Loan Repay Done.".into());
+                Ok(loan)
+            },
+            |loan| loan.id.to_string(),
+        )
+    }
+
+    pub fn flash_loan_default(&mut self, arg: ArgWithMeta<FlashLoan>) -> Result<FlashLoan, BusinessError> {
+        self.trace_guard.handle(
+            |trace| {
+                let loan = arg.arg.clone();
+                let mut inner = InnerTokenPairSwapGuard {
+                    trace_guard: trace,
+                    balances_guard: &mut self.balances_guard,
+                    token_guard: &mut self.token_guard,
+                    swap_guard: &mut self.swap_guard,
+                    arg,
+                };
+                self.pairs_guard.flash_loan_default(&mut inner)?;
+                trace.trace("Token Pair Flash Loan Default Done.".into());
+                Ok(loan)
+            },
+            |loan| loan.id.to_string(),
+        )
+    }
+
+    pub fn protocol_fees_collect(
+        &mut self,
+        arg: ArgWithMeta<ProtocolFeesCollectArg>,
+    ) -> Result<ProtocolFeesCollectSuccess, BusinessError> {
+        self.trace_guard.handle(
+            |trace| {
+                let mut inner = InnerTokenPairSwapGuard {
+                    trace_guard: trace,
+                    balances_guard: &mut self.balances_guard,
+                    token_guard: &mut self.token_guard,
+                    swap_guard: &mut self.swap_guard,
+                    arg,
+                };
+                let data = self.pairs_guard.protocol_fees_collect(&mut inner)?;
+                trace.trace("Protocol Fees Collect Done.".into());
+                Ok(data)
+            },
+            |data| {
+                let view: ProtocolFeesCollectSuccessView = data.into();
+                serde_json::to_string(&view).unwrap_or_default()
+            },
+        )
+    }
 }
 
 // ============================== inner guard ==============================
@@ -314,6 +476,42 @@ impl<T> InnerTokenPairSwapGuard<'_, '_, '_, T> {
     }
 }
 
+impl<T> InnerTokenPairSwapGuard<'_, '_, '_, T> {
+    pub fn push_stable_pool_state(
+        &mut self,
+        pool: &TokenPoolAmm,
+        amplification: u64,
+        supply: Nat,
+        reserves: Vec<Nat>,
+        invariant: Nat,
+    ) -> Result<(), BusinessError> {
+        let message = format!(
+            "*StablePoolState* `pool:({pool}), timestamp:{}, amplification:{amplification}, supply:{supply}, reserves:[{}], invariant:{invariant}`",
+            self.arg.now.into_inner(),
+            reserves.iter().map(|r| r.to_string()).collect::<Vec<_>>().join(", "),
+        );
+        // reserves and invariant
+        let transaction = SwapTransaction {
+            operation: SwapOperation::Pair(PairOperation::StablePool(StablePoolOperation::State(StablePoolState {
+                pool: pool.clone(),
+                block_timestamp: self.arg.now,
+                amplification,
+                supply,
+                reserves,
+                invariant,
+            }))),
+            memo: None,
+            created: None,
+        };
+        self.swap_guard.mint_block(self.arg.now, transaction, |_| {
+            // do nothing
+            Ok(())
+        })?;
+        self.trace(message); // * trace
+        Ok(())
+    }
+}
+
 impl<T: TokenPairArg> InnerTokenPairSwapGuard<'_, '_, '_, T> {
     pub fn push_state(
         &mut self,
@@ -353,6 +551,157 @@ impl<T: TokenPairArg> InnerTokenPairSwapGuard<'_, '_, '_, T> {
     }
 }
 
+impl<T: TokenPairArg> InnerTokenPairSwapGuard<'_, '_, '_, T> {
+    pub fn push_stable_swap_state(
+        &mut self,
+        amplification: u64,
+        supply: Nat,
+        reserve0: Nat,
+        reserve1: Nat,
+        invariant: Nat,
+    ) -> Result<(), BusinessError> {
+        let message = format!(
+            "*StableSwapState* `pa:({}), timestamp:{}, amplification:{amplification}, supply:{supply}, reserve0:{reserve0}, reserve1:{reserve1}, invariant:{invariant}`",
+            self.arg.arg.get_pa(),
+            self.arg.now.into_inner(),
+        );
+        // reserve and invariant
+        let transaction = SwapTransaction {
+            operation: SwapOperation::Pair(PairOperation::StableSwap(StableSwapOperation::State(StableSwapState {
+                pa: self.arg.arg.get_pa().to_owned(),
+                block_timestamp: self.arg.now,
+                amplification,
+                supply,
+                reserve0,
+                reserve1,
+                invariant,
+            }))),
+            memo: None,
+            created: None,
+        };
+        self.swap_guard.mint_block(self.arg.now, transaction, |_| {
+            // do nothing
+            Ok(())
+        })?;
+        self.trace(message); // * trace
+        Ok(())
+    }
+
+    pub fn push_pmm_v1_state(
+        &mut self,
+        supply: Nat,
+        reserve0: Nat,
+        reserve1: Nat,
+        target0: Nat,
+        target1: Nat,
+    ) -> Result<(), BusinessError> {
+        let message = format!(
+            "*PmmV1State* `pa:({}), timestamp:{}, supply:{supply}, reserve0:{reserve0}, reserve1:{reserve1}, target0:{target0}, target1:{target1}`",
+            self.arg.arg.get_pa(),
+            self.arg.now.into_inner(),
+        );
+        // reserve and target
+        let transaction = SwapTransaction {
+            operation: SwapOperation::Pair(PairOperation::PmmV1(PmmV1Operation::State(PmmV1State {
+                pa: self.arg.arg.get_pa().to_owned(),
+                block_timestamp: self.arg.now,
+                supply,
+                reserve0,
+                reserve1,
+                target0,
+                target1,
+            }))),
+            memo: None,
+            created: None,
+        };
+        self.swap_guard.mint_block(self.arg.now, transaction, |_| {
+            // do nothing
+            Ok(())
+        })?;
+        self.trace(message); // * trace
+        Ok(())
+    }
+
+    pub fn push_weighted_state(
+        &mut self,
+        weight0: u32,
+        weight1: u32,
+        supply: Nat,
+        reserve0: Nat,
+        reserve1: Nat,
+    ) -> Result<(), BusinessError> {
+        let message = format!(
+            "*WeightedState* `pa:({}), timestamp:{}, weights:({weight0}, {weight1}), supply:{supply}, reserve0:{reserve0}, reserve1:{reserve1}`",
+            self.arg.arg.get_pa(),
+            self.arg.now.into_inner(),
+        );
+        // weights and reserve
+        let transaction = SwapTransaction {
+            operation: SwapOperation::Pair(PairOperation::Weighted(WeightedOperation::State(WeightedState {
+                pa: self.arg.arg.get_pa().to_owned(),
+                block_timestamp: self.arg.now,
+                weight0,
+                weight1,
+                supply,
+                reserve0,
+                reserve1,
+            }))),
+            memo: None,
+            created: None,
+        };
+        self.swap_guard.mint_block(self.arg.now, transaction, |_| {
+            // do nothing
+            Ok(())
+        })?;
+        self.trace(message); // * trace
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn push_swap_v3_swap(
+        &mut self,
+        zero_for_one: bool,
+        amount_in: Nat,
+        amount_out: Nat,
+        fee: Nat,
+        sqrt_price_x96: Nat,
+        tick: i32,
+        liquidity: Nat,
+        reserve0: Nat,
+        reserve1: Nat,
+    ) -> Result<(), BusinessError> {
+        let message = format!(
+            "*SwapV3Swap* `pa:({}), timestamp:{}, zero_for_one:{zero_for_one}, amount_in:{amount_in}, amount_out:{amount_out}, fee:{fee}, sqrt_price_x96:{sqrt_price_x96}, tick:{tick}, liquidity:{liquidity}, reserve0:{reserve0}, reserve1:{reserve1}`",
+            self.arg.arg.get_pa(),
+            self.arg.now.into_inner(),
+        );
+        // price and reserve after swap
+        let transaction = SwapTransaction {
+            operation: SwapOperation::Pair(PairOperation::SwapV3(SwapV3Operation::Swap(SwapV3SwapToken {
+                pa: self.arg.arg.get_pa().to_owned(),
+                block_timestamp: self.arg.now,
+                zero_for_one,
+                amount_in,
+                amount_out,
+                fee,
+                sqrt_price_x96,
+                tick,
+                liquidity,
+                reserve0,
+                reserve1,
+            }))),
+            memo: None,
+            created: None,
+        };
+        self.swap_guard.mint_block(self.arg.now, transaction, |_| {
+            // do nothing
+            Ok(())
+        })?;
+        self.trace(message); // * trace
+        Ok(())
+    }
+}
+
 // ============================== pair liquidity ==============================
 
 impl<T: SelfCanisterArg + TokenPairArg> InnerTokenPairSwapGuard<'_, '_, '_, T> {
@@ -530,6 +879,7 @@ impl InnerTokenPairSwapGuard<'_, '_, '_, TokenPairLiquidityRemoveArg> {
                 from,
                 amount: amount_without_fee.clone() + fee.as_ref().map(|f| f.fee.clone()).unwrap_or_default(), // withdraw sum
                 to: pool_account,
+                to_account_id: None,
             },
         );
         let deposit_fee = fee.map(|BurnFee { fee, fee_to }| {
@@ -575,3 +925,219 @@ impl InnerTokenPairSwapGuard<'_, '_, '_, TokenPairLiquidityRemoveArg> {
         Ok(())
     }
 }
+
+impl InnerTokenPairSwapGuard<'_, '_, '_, TokenPoolLiquidityAddArg> {
+    pub fn token_pool_liquidity_mint(
+        &mut self,
+        amounts: &[Nat],
+        token: CanisterId,
+        pool_account: Account,
+        to: Account,
+        amount: Nat,
+    ) -> Result<(), BusinessError> {
+        // mint
+        let transaction = SwapTransaction {
+            operation: SwapOperation::Pair(PairOperation::StablePool(StablePoolOperation::Mint(
+                StablePoolMintToken {
+                    pool: self.arg.arg.pool.clone(),
+                    from: self.arg.arg.from,
+                    amounts: amounts.to_vec(),
+                    token,
+                    amount: amount.clone(),
+                    to,
+                },
+            ))),
+            memo: self.arg.memo.clone(),
+            created: self.arg.created,
+        };
+        // Mint coins for users and generate a DepositToken event
+        let arg = ArgWithMeta::simple(
+            self.arg.now,
+            self.arg.caller,
+            DepositToken {
+                token,
+                from: pool_account,
+                amount: amount.clone(),
+                to,
+            },
+        );
+        self.swap_guard.mint_block(self.arg.now, transaction, |_| {
+            let trace = format!(
+                "*PoolLiquidityMint(Deposit)* `token:[{}], from[transferred {} tokens]:({}), to[minted liquidity]:({}), amount:{}`",
+                arg.arg.token.to_text(),
+                amounts.len(),
+                display_account(&pool_account),
+                display_account(&arg.arg.to),
+                arg.arg.amount,
+            );
+            self.balances_guard.token_deposit(self.token_guard, arg)?;
+            self.trace_guard.trace(trace); // * trace
+            Ok(())
+        })?;
+        self.trace(format!(
+            "*PoolLiquidityMint* `token:[{}], to:({}), amount:{amount}`",
+            token.to_text(),
+            display_account(&to),
+        )); // * trace
+        Ok(())
+    }
+}
+
+impl InnerTokenPairSwapGuard<'_, '_, '_, TokenPoolLiquidityRemoveArg> {
+    pub fn token_pool_liquidity_burn(
+        &mut self,
+        amounts: &[Nat],
+        token: CanisterId,
+        from: Account,
+        pool_account: Account,
+        amount_without_fee: Nat,
+        fee: Option<BurnFee>,
+    ) -> Result<(), BusinessError> {
+        // burn
+        let transaction = SwapTransaction {
+            operation: SwapOperation::Pair(PairOperation::StablePool(StablePoolOperation::Burn(
+                StablePoolBurnToken {
+                    pool: self.arg.arg.pool.clone(),
+                    from,
+                    token,
+                    amount: amount_without_fee.clone(),
+                    amounts: amounts.to_vec(),
+                    to: self.arg.arg.to,
+                    fee: fee.clone(),
+                },
+            ))),
+            memo: self.arg.memo.clone(),
+            created: self.arg.created,
+        };
+        // Destroy for users, generates a WithdrawToken event
+        let arg = ArgWithMeta::simple(
+            self.arg.now,
+            self.arg.caller,
+            WithdrawToken {
+                token,
+                from,
+                amount: amount_without_fee.clone() + fee.as_ref().map(|f| f.fee.clone()).unwrap_or_default(), // withdraw sum
+                to: pool_account,
+                to_account_id: None,
+            },
+        );
+        let deposit_fee = fee.map(|BurnFee { fee, fee_to }| {
+            ArgWithMeta::simple(
+                self.arg.now,
+                self.arg.caller,
+                DepositToken {
+                    token,
+                    from: pool_account,
+                    amount: fee, // deposit fee
+                    to: fee_to,
+                },
+            )
+        });
+        self.swap_guard.mint_block(self.arg.now, transaction, |_| {
+            let trace = format!(
+                "*PoolLiquidityBurn(Withdraw)* `token:[{}], from[burned liquidity]:({}), to[withdrawn {} tokens]:({}), amount:{}, fee:{}`",
+                arg.arg.token.to_text(),
+                display_account(&arg.arg.from),
+                amounts.len(),
+                display_account(&pool_account),
+                arg.arg.amount,
+                display_option(&deposit_fee.as_ref().map(|d| d.arg.amount.to_string()))
+            );
+            self.balances_guard.token_withdraw(self.token_guard, arg)?;
+            self.trace_guard.trace(trace); // * trace
+            if let Some(deposit_fee) = deposit_fee {
+                let trace = format!(
+                    "*PoolBurnFeeMint(Deposit)* `token:[{}], to:({}), amount:{}`",
+                    deposit_fee.arg.token.to_text(),
+                    display_account(&deposit_fee.arg.to),
+                    deposit_fee.arg.amount,
+                );
+                self.balances_guard.token_deposit(self.token_guard, deposit_fee.clone())?;
+                self.trace_guard.trace(trace); // * trace
+            }
+            Ok(())
+        })?;
+        self.trace(format!(
+            "*PoolLiquidityBurn* `token:[{}], from:({}), amount:{amount_without_fee}`",
+            token.to_text(),
+            display_account(&from),
+        )); // * trace
+        Ok(())
+    }
+}
+
+impl InnerTokenPairSwapGuard<'_, '_, '_, TokenPairLiquidityAddArg> {
+    pub fn token_position_mint(
+        &mut self,
+        tick_range: TickRange,
+        (token0, token1): (CanisterId, CanisterId),
+        (amount0, amount1): (Nat, Nat),
+        liquidity: Nat,
+    ) -> Result<(), BusinessError> {
+        let message = format!(
+            "*PairPositionMint* `range:{tick_range}, amount0:{amount0}, amount1:{amount1}, to:({}), liquidity:{liquidity}`",
+            display_account(&self.arg.arg.to),
+        );
+        // mint position
+        let transaction = SwapTransaction {
+            operation: SwapOperation::Pair(PairOperation::SwapV3(SwapV3Operation::Mint(SwapV3MintToken {
+                pa: self.arg.arg.pa,
+                from: self.arg.arg.from,
+                tick_lower: tick_range.lower,
+                tick_upper: tick_range.upper,
+                token0,
+                token1,
+                amount0,
+                amount1,
+                liquidity,
+                to: self.arg.arg.to,
+            }))),
+            memo: self.arg.memo.clone(),
+            created: self.arg.created,
+        };
+        self.swap_guard.mint_block(self.arg.now, transaction, |_| {
+            // position is recorded by market maker
+            Ok(())
+        })?;
+        self.trace(message); // * trace
+        Ok(())
+    }
+}
+
+impl InnerTokenPairSwapGuard<'_, '_, '_, TokenPairLiquidityRemoveArg> {
+    pub fn token_position_burn(
+        &mut self,
+        tick_range: TickRange,
+        liquidity: Nat,
+        (token0, token1): (CanisterId, CanisterId),
+        (amount0, amount1): (Nat, Nat),
+    ) -> Result<(), BusinessError> {
+        let message = format!(
+            "*PairPositionBurn* `range:{tick_range}, from:({}), liquidity:{liquidity}, amount0:{amount0}, amount1:{amount1}`",
+            display_account(&self.arg.arg.from),
+        );
+        // burn position
+        let transaction = SwapTransaction {
+            operation: SwapOperation::Pair(PairOperation::SwapV3(SwapV3Operation::Burn(SwapV3BurnToken {
+                pa: self.arg.arg.pa,
+                from: self.arg.arg.from,
+                tick_lower: tick_range.lower,
+                tick_upper: tick_range.upper,
+                liquidity,
+                token0,
+                token1,
+                amount0,
+                amount1,
+                to: self.arg.arg.to,
+            }))),
+            memo: self.arg.memo.clone(),
+            created: self.arg.created,
+        };
+        self.swap_guard.mint_block(self.arg.now, transaction, |_| {
+            // position is recorded by market maker
+            Ok(())
+        })?;
+        self.trace(message); // * trace
+        Ok(())
+    }
+}
@@ -3,8 +3,8 @@ use common::types::BusinessError;
 use ic_canister_kit::common::option::display_option_by;
 
 use super::super::{
-    ArgWithMeta, DepositToken, RequestTraceGuard, TokenBalancesGuard, TokenBlockChainGuard, TransferToken,
-    WithdrawToken, display_account,
+    ApproveToken, ArgWithMeta, DepositToken, RequestTraceGuard, TokenBalancesGuard, TokenBlockChainGuard,
+    TransferToken, WithdrawToken, display_account,
 };
 
 pub struct TokenGuard<'a> {
@@ -89,4 +89,23 @@ impl<'a> TokenGuard<'a> {
             |data| data.to_string(),
         )
     }
+
+    pub fn token_approve(&mut self, arg: ArgWithMeta<ApproveToken>) -> Result<(), BusinessError> {
+        self.trace_guard.handle(
+            |trace| {
+                trace.trace(format!(
+                    "*Approve* `token:[{}], from:({}), spender:({}), amount:{}, expires_at:{}`",
+                    arg.arg.token.to_text(),
+                    display_account(&arg.arg.from),
+                    display_account(&arg.arg.spender),
+                    arg.arg.amount,
+                    display_option_by(&arg.arg.expires_at, |expires_at| expires_at.to_string()),
+                )); // * trace
+                self.balances_guard.token_approve(&mut self.token_guard, arg)?; // do approve
+                trace.trace("Approve Done.".into()); // * trace
+                Ok(())
+            },
+            |_| "".into(),
+        )
+    }
 }
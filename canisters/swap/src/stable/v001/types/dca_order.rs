@@ -0,0 +1,228 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// Running dca orders, sorted by id
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DcaOrders {
+    next_id: u64,
+    orders: BTreeMap<u64, DcaOrder>,
+    #[serde(default)]
+    cursor: u64, // the id of order scheduled next
+}
+
+const MAX_DCA_ORDERS_PER_OWNER: usize = 100;
+const MAX_DCA_ORDERS: usize = 10_000;
+const SCHEDULE_DCA_ORDERS_BATCH: usize = 50; // ! bounded work of one schedule message
+
+impl DcaOrders {
+    pub fn query(&self, owner: Option<&Account>) -> Vec<DcaOrder> {
+        self.orders
+            .values()
+            .filter(|order| owner.is_none_or(|owner| order.owner == *owner))
+            .cloned()
+            .collect()
+    }
+
+    pub fn get(&self, id: u64) -> Option<&DcaOrder> {
+        self.orders.get(&id)
+    }
+
+    pub fn get_mut(&mut self, id: u64) -> Option<&mut DcaOrder> {
+        self.orders.get_mut(&id)
+    }
+
+    pub fn next_id(&self) -> u64 {
+        self.next_id
+    }
+
+    pub fn insert(&mut self, order: DcaOrder) {
+        self.next_id = self.next_id.max(order.id + 1);
+        self.orders.insert(order.id, order);
+    }
+
+    pub fn check_capacity(&self, owner: &Account) -> Result<(), BusinessError> {
+        if MAX_DCA_ORDERS <= self.orders.len()
+            || MAX_DCA_ORDERS_PER_OWNER <= self.orders.values().filter(|order| order.owner == *owner).count()
+        {
+            return Err(BusinessError::Swap("TOO_MANY_DCA_ORDERS".into()));
+        }
+        Ok(())
+    }
+
+    /// The next batch of due orders to be executed, starting from the saved cursor and wrapping around at the end.
+    pub fn next_batch(&mut self, now: TimestampNanos) -> Vec<DcaOrder> {
+        let scan = |start: u64| {
+            self.orders
+                .range(start..)
+                .filter(|(_, order)| order.is_due(now))
+                .take(SCHEDULE_DCA_ORDERS_BATCH)
+                .map(|(_, order)| order.clone())
+                .collect::<Vec<_>>()
+        };
+        let mut batch = scan(self.cursor);
+        if batch.is_empty() && 0 < self.cursor {
+            batch = scan(0);
+        }
+        self.cursor = batch.last().map(|order| order.id + 1).unwrap_or_default();
+        batch
+    }
+
+    pub fn remove(&mut self, id: u64) -> Option<DcaOrder> {
+        self.orders.remove(&id)
+    }
+}
+
+/// Transfer of placing, the token in of all slices is escrowed from owner
+pub fn dca_order_escrow_transfer(order: &DcaOrder, escrow: Account) -> TransferToken {
+    TransferToken {
+        token: order.path[0].token.0,
+        from: order.owner,
+        amount: order.amount_in.clone(),
+        to: escrow,
+        fee: None,
+        spender: None,
+    }
+}
+
+/// Transfer of cancelling, the unexecuted token in is returned to owner
+pub fn dca_order_refund_transfer(order: &DcaOrder, escrow: Account) -> TransferToken {
+    TransferToken {
+        token: order.path[0].token.0,
+        from: escrow,
+        amount: order.unexecuted_amount_in(),
+        to: order.owner,
+        fee: None,
+        spender: None,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::collections::HashMap;
+
+    use ::common::types::SwapTokenPair;
+    use ::common::utils::math::zero;
+
+    use super::super::testing::{pool, self_canister, token};
+    use super::*;
+
+    fn account(id: u8) -> Account {
+        Account {
+            owner: CanisterId::from_slice(&[id, id]),
+            subaccount: None,
+        }
+    }
+
+    // apply transfers to balances, none of them can be overdrawn
+    fn apply(balances: &mut HashMap<Account, Nat>, transfers: &[TransferToken]) -> Result<(), BusinessError> {
+        for transfer in transfers {
+            let from = balances.entry(transfer.from).or_insert_with(zero);
+            if *from < transfer.amount {
+                return Err(BusinessError::insufficient_balance(transfer.token, from.clone()));
+            }
+            *from -= transfer.amount.clone();
+            *balances.entry(transfer.to).or_insert_with(zero) += transfer.amount.clone();
+        }
+        Ok(())
+    }
+
+    // sell 10_001 of token 1 by 3 slices, every 10 nanos
+    fn order(id: u64, owner: Account) -> DcaOrder {
+        let (pa, _) = pool(1, 2, 1_000_000, 1_000_000);
+        DcaOrder {
+            id,
+            owner,
+            amount_in: Nat::from(10_001_u32),
+            slices: 3,
+            slice_amount_out_min: Nat::from(3_000_u32),
+            path: vec![SwapTokenPair {
+                token: (token(1), token(2)),
+                amm: pa.amm.into_text(),
+            }],
+            to: owner,
+            interval_ns: 10,
+            executed_slices: 0,
+            executed_amount_in: zero(),
+            executed_amount_out: zero(),
+            next_execution: TimestampNanos::from_inner(0),
+            placed: TimestampNanos::from_inner(0),
+        }
+    }
+
+    #[test]
+    fn test_dca_order_slices() {
+        let mut order = order(0, account(1));
+        assert_eq!(order.slice_amount_in(), Nat::from(3_333_u32));
+
+        // slice by slice, one slice in every interval
+        assert!(order.is_due(TimestampNanos::from_inner(0)));
+        assert_eq!(order.next_slice_amount_in(), Nat::from(3_333_u32));
+        assert_eq!(order.next_slice_amount_out_min(), Nat::from(3_000_u32));
+        let next = order.next_execution_after(TimestampNanos::from_inner(0)).unwrap();
+        assert_eq!(next, TimestampNanos::from_inner(10));
+        assert!(!order.executed(next, Nat::from(3_333_u32), Nat::from(3_200_u32)));
+        assert!(!order.is_due(TimestampNanos::from_inner(9)));
+        assert!(order.is_due(TimestampNanos::from_inner(10)));
+        assert!(!order.executed(
+            order.next_execution_after(TimestampNanos::from_inner(10)).unwrap(),
+            Nat::from(3_333_u32),
+            Nat::from(3_100_u32)
+        ));
+
+        // the last slice takes the rest, and its min got is scaled by its pay
+        assert_eq!(order.next_slice_amount_in(), Nat::from(3_335_u32));
+        assert_eq!(order.next_slice_amount_out_min(), Nat::from(3_001_u32));
+        assert!(order.executed(
+            order.next_execution_after(TimestampNanos::from_inner(20)).unwrap(),
+            Nat::from(3_335_u32),
+            Nat::from(3_000_u32)
+        ));
+        assert_eq!(order.executed_slices, 3);
+        assert_eq!(order.executed_amount_in, order.amount_in);
+        assert_eq!(order.executed_amount_out, Nat::from(9_300_u32));
+        assert_eq!(order.unexecuted_amount_in(), zero());
+        assert!(!order.is_due(TimestampNanos::from_inner(u64::MAX)));
+
+        // the next slice can not be scheduled after the end of time
+        assert!(
+            order
+                .next_execution_after(TimestampNanos::from_inner(u64::MAX - 9))
+                .is_err()
+        );
+
+        // only due orders are scheduled
+        let mut orders = DcaOrders::default();
+        orders.insert(order);
+        let mut waiting = self::order(1, account(1));
+        waiting.next_execution = TimestampNanos::from_inner(100);
+        orders.insert(waiting);
+        orders.insert(self::order(2, account(2)));
+        let batch = orders.next_batch(TimestampNanos::from_inner(50));
+        assert_eq!(batch.iter().map(|order| order.id).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(orders.next_batch(TimestampNanos::from_inner(100)).len(), 2);
+    }
+
+    #[test]
+    fn test_dca_order_cancel() {
+        let escrow = DcaOrder::escrow(self_canister().id());
+        let alice = account(1);
+        let mut order = order(0, alice);
+        let mut balances = HashMap::from([(alice, Nat::from(10_001_u32))]);
+        apply(&mut balances, &[dca_order_escrow_transfer(&order, escrow)]).unwrap();
+        assert_eq!(balances[&escrow], order.amount_in);
+
+        // one slice is swapped out of escrow, the rest is returned to owner
+        let slice = order.next_slice_amount_in();
+        *balances.get_mut(&escrow).unwrap() -= slice.clone();
+        order.executed(TimestampNanos::from_inner(10), slice, Nat::from(3_200_u32));
+        let refund = dca_order_refund_transfer(&order, escrow);
+        assert_eq!(refund.amount, Nat::from(6_668_u32));
+        apply(&mut balances, &[refund]).unwrap();
+        assert_eq!(balances[&alice], Nat::from(6_668_u32));
+        assert_eq!(balances[&escrow], zero());
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::BTreeMap;
+
+use ::common::utils::math::zero;
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// Precision of accumulated reward per staked lp
+fn precision() -> Nat {
+    Nat::from(1_000_000_000_000_000_000_u128)
+}
+
+/// Accrual status of a farm
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct FarmStatus {
+    pub farm: Farm,
+    pub total_staked: Nat,
+    pub acc_reward_per_share: Nat, // scaled by 1e18
+    pub last_reward: TimestampNanos,
+    pub allocated: Nat, // rewards accrued to stakers
+    pub claimed: Nat,
+    pub refunded: bool, // unallocated rewards are returned to funder
+}
+
+/// Staked lp and rewards of an account
+#[derive(Debug, Clone, Default, Serialize, Deserialize, CandidType)]
+pub struct FarmStaker {
+    pub staked: Nat,
+    pub reward_debt: Nat, // accrued before staked, scaled by 1e18
+    pub pending: Nat,     // accrued but not claimed
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FarmState {
+    pub status: FarmStatus,
+    stakers: BTreeMap<Account, FarmStaker>,
+}
+
+impl FarmState {
+    fn new(farm: Farm) -> Self {
+        let last_reward = farm.start;
+        Self {
+            status: FarmStatus {
+                farm,
+                total_staked: zero(),
+                acc_reward_per_share: zero(),
+                last_reward,
+                allocated: zero(),
+                claimed: zero(),
+                refunded: false,
+            },
+            stakers: BTreeMap::new(),
+        }
+    }
+
+    /// Accrue rewards released since last time, nothing is allocated while nothing staked
+    fn update(&mut self, now: TimestampNanos) {
+        let status = &mut self.status;
+        if now.into_inner() <= status.last_reward.into_inner() {
+            return;
+        }
+        if status.total_staked != zero() {
+            let reward = status.farm.reward_between(status.last_reward, now);
+            status.acc_reward_per_share += reward.clone() * precision() / status.total_staked.clone();
+            status.allocated += reward;
+        }
+        status.last_reward = now;
+    }
+
+    fn settle(&mut self, account: &Account) -> &mut FarmStaker {
+        let acc_reward_per_share = self.status.acc_reward_per_share.clone();
+        let staker = self.stakers.entry(*account).or_default();
+        let accrued = staker.staked.clone() * acc_reward_per_share;
+        staker.pending += (accrued.clone() - staker.reward_debt.clone()) / precision();
+        staker.reward_debt = accrued;
+        staker
+    }
+
+    pub fn staker(&self, account: &Account, now: TimestampNanos) -> Option<FarmStaker> {
+        let mut state = FarmState {
+            status: self.status.clone(),
+            stakers: BTreeMap::from([(*account, self.stakers.get(account)?.clone())]),
+        };
+        state.update(now);
+        Some(state.settle(account).clone())
+    }
+
+    pub fn stake(&mut self, account: &Account, amount: &Nat, now: TimestampNanos) -> Result<(), BusinessError> {
+        if self.status.farm.end.into_inner() <= now.into_inner() {
+            return Err(BusinessError::Swap("FARM_ENDED".into()));
+        }
+        self.update(now);
+        let acc_reward_per_share = self.status.acc_reward_per_share.clone();
+        let staker = self.settle(account);
+        staker.staked += amount.clone();
+        staker.reward_debt = staker.staked.clone() * acc_reward_per_share;
+        self.status.total_staked += amount.clone();
+        Ok(())
+    }
+
+    pub fn unstake(&mut self, account: &Account, amount: &Nat, now: TimestampNanos) -> Result<(), BusinessError> {
+        if self.stakers.get(account).is_none_or(|staker| staker.staked < *amount) {
+            return Err(BusinessError::Swap("INSUFFICIENT_STAKED".into()));
+        }
+        self.update(now);
+        let acc_reward_per_share = self.status.acc_reward_per_share.clone();
+        let staker = self.settle(account);
+        staker.staked -= amount.clone();
+        staker.reward_debt = staker.staked.clone() * acc_reward_per_share;
+        self.status.total_staked -= amount.clone();
+        self.remove_if_empty(account);
+        Ok(())
+    }
+
+    pub fn claim(&mut self, account: &Account, now: TimestampNanos) -> Result<Nat, BusinessError> {
+        if !self.stakers.contains_key(account) {
+            return Err(BusinessError::Swap("FARM_STAKER_NOT_EXIST".into()));
+        }
+        self.update(now);
+        let staker = self.settle(account);
+        let reward = std::mem::replace(&mut staker.pending, zero());
+        self.status.claimed += reward.clone();
+        self.remove_if_empty(account);
+        Ok(reward)
+    }
+
+    /// Unallocated rewards after end, returned to funder once
+    pub fn refund(&mut self, now: TimestampNanos) -> Result<Nat, BusinessError> {
+        if now.into_inner() < self.status.farm.end.into_inner() {
+            return Err(BusinessError::Swap("FARM_NOT_ENDED".into()));
+        }
+        if self.status.refunded {
+            return Err(BusinessError::Swap("FARM_REFUNDED".into()));
+        }
+        self.update(now);
+        self.status.refunded = true;
+        Ok(self.status.farm.total_reward() - self.status.allocated.clone())
+    }
+
+    fn remove_if_empty(&mut self, account: &Account) {
+        if self
+            .stakers
+            .get(account)
+            .is_some_and(|s| s.staked == zero() && s.pending == zero())
+        {
+            self.stakers.remove(account);
+        }
+    }
+
+    /// The farm is done, nothing staked or claimable, and refunded
+    pub fn is_finished(&self) -> bool {
+        self.status.refunded && self.stakers.is_empty()
+    }
+}
+
+/// Reward farms, sorted by id
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Farms {
+    next_id: u64,
+    farms: BTreeMap<u64, FarmState>,
+}
+
+impl Farms {
+    pub fn query(&self, pa: Option<&TokenPairAmm>) -> Vec<FarmStatus> {
+        self.farms
+            .values()
+            .filter(|state| pa.is_none_or(|pa| state.status.farm.pa == *pa))
+            .map(|state| state.status.clone())
+            .collect()
+    }
+
+    pub fn get(&self, id: u64) -> Option<&FarmState> {
+        self.farms.get(&id)
+    }
+
+    /// Lp of pool staked by the account in all farms
+    pub fn staked(&self, pa: &TokenPairAmm, account: &Account) -> Nat {
+        self.farms
+            .values()
+            .filter(|state| state.status.farm.pa == *pa)
+            .filter_map(|state| state.stakers.get(account))
+            .fold(zero(), |staked, staker| staked + staker.staked.clone())
+    }
+
+    pub fn next_id(&self) -> u64 {
+        self.next_id
+    }
+
+    pub fn create(&mut self, farm: Farm) {
+        self.next_id = self.next_id.max(farm.id + 1);
+        self.farms.insert(farm.id, FarmState::new(farm));
+    }
+
+    /// Put back the changed farm
+    pub fn replace(&mut self, state: FarmState) {
+        if state.is_finished() {
+            self.farms.remove(&state.status.farm.id);
+            return;
+        }
+        self.farms.insert(state.status.farm.id, state);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::super::testing::{pool, token};
+    use super::*;
+
+    fn account(id: u8) -> Account {
+        Account {
+            owner: CanisterId::from_slice(&[id, id]),
+            subaccount: None,
+        }
+    }
+
+    fn seconds(seconds: u64) -> TimestampNanos {
+        TimestampNanos::from_inner(seconds * 1_000_000_000)
+    }
+
+    // rewarding over 100 seconds
+    fn farm(reward_per_second: u64) -> FarmState {
+        FarmState::new(Farm {
+            id: 0,
+            pa: pool(1, 2, 1_000_000, 1_000_000).0,
+            lp_token: token(9),
+            reward_token: token(3),
+            reward_per_second: Nat::from(reward_per_second),
+            start: seconds(0),
+            end: seconds(100),
+            funder: account(9),
+            created: seconds(0),
+        })
+    }
+
+    #[test]
+    fn test_farm_acc_reward_per_share() {
+        let (alice, bob) = (account(1), account(2));
+        let mut state = farm(1_000);
+        assert_eq!(state.status.farm.total_reward(), Nat::from(100_000_u32));
+
+        // nothing is allocated before staked
+        state.stake(&alice, &Nat::from(100_u32), seconds(5)).unwrap();
+        assert_eq!(state.status.acc_reward_per_share, zero());
+        assert_eq!(state.status.allocated, zero());
+
+        // 5_000 to 100 staked, then 10_000 to 400 staked
+        state.stake(&bob, &Nat::from(300_u32), seconds(10)).unwrap();
+        assert_eq!(state.status.acc_reward_per_share, Nat::from(50_u32) * precision());
+        assert_eq!(state.staker(&alice, seconds(20)).unwrap().pending, Nat::from(7_500_u32));
+        assert_eq!(state.staker(&bob, seconds(20)).unwrap().pending, Nat::from(7_500_u32));
+        assert_eq!(state.status.last_reward, seconds(10)); // viewing changes nothing
+
+        // rounded down, never more than allocated
+        let mut state = farm(1);
+        state.stake(&alice, &Nat::from(3_u32), seconds(0)).unwrap();
+        state.stake(&bob, &Nat::from(3_u32), seconds(0)).unwrap();
+        let pending =
+            state.staker(&alice, seconds(1)).unwrap().pending + state.staker(&bob, seconds(1)).unwrap().pending;
+        assert_eq!(pending, zero());
+        let pending =
+            state.staker(&alice, seconds(7)).unwrap().pending + state.staker(&bob, seconds(7)).unwrap().pending;
+        assert_eq!(pending, Nat::from(6_u32));
+    }
+
+    #[test]
+    fn test_farm_stake_unstake_claim_refund() {
+        let (alice, bob) = (account(1), account(2));
+        let mut state = farm(1_000);
+        state.stake(&alice, &Nat::from(100_u32), seconds(5)).unwrap();
+        state.stake(&bob, &Nat::from(400_u32), seconds(10)).unwrap();
+
+        // unstaked lp stops accruing, the pending is kept
+        assert!(state.unstake(&alice, &Nat::from(101_u32), seconds(20)).is_err());
+        state.unstake(&alice, &Nat::from(100_u32), seconds(20)).unwrap();
+        assert_eq!(state.status.total_staked, Nat::from(400_u32));
+        assert_eq!(state.claim(&alice, seconds(30)).unwrap(), Nat::from(7_000_u32));
+        assert!(state.claim(&alice, seconds(30)).is_err()); // nothing staked or pending
+
+        // bob gets all released after alice left
+        assert_eq!(state.claim(&bob, seconds(30)).unwrap(), Nat::from(18_000_u32));
+        state.unstake(&bob, &Nat::from(400_u32), seconds(40)).unwrap();
+        assert!(state.stake(&bob, &Nat::from(1_u32), seconds(100)).is_err()); // ended
+
+        // unallocated: 5 seconds before staked and 60 seconds after all unstaked
+        assert!(state.refund(seconds(99)).is_err());
+        assert_eq!(state.refund(seconds(100)).unwrap(), Nat::from(65_000_u32));
+        assert!(state.refund(seconds(100)).is_err());
+        assert!(!state.is_finished());
+
+        assert_eq!(state.claim(&bob, seconds(100)).unwrap(), Nat::from(10_000_u32));
+        assert!(state.is_finished());
+        assert_eq!(state.status.claimed, state.status.allocated);
+        assert_eq!(
+            state.status.allocated + Nat::from(65_000_u32),
+            state.status.farm.total_reward()
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// Fee tier registered by maintainers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct FeeTier {
+    /// swap fee of the tier
+    pub fee_rate: SwapRatio,
+    /// default protocol fee of new pools
+    pub protocol_fee: Option<SwapRatio>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FeeTiers(BTreeMap<Amm, FeeTier>);
+
+impl FeeTiers {
+    pub fn query(&self) -> Vec<(AmmText, FeeTier)> {
+        self.0
+            .iter()
+            .map(|(amm, tier)| (amm.into_text(), tier.clone()))
+            .collect()
+    }
+
+    pub fn get(&self, amm: &Amm) -> Option<&FeeTier> {
+        self.0.get(amm)
+    }
+
+    pub fn register(&mut self, amm: Amm, protocol_fee: Option<SwapRatio>) -> Result<FeeTier, BusinessError> {
+        if !amm.is_tier() {
+            return Err(BusinessError::Swap("FIXED_AMM_CAN_NOT_BE_REGISTERED".into()));
+        }
+        if self.0.contains_key(&amm) {
+            return Err(BusinessError::Swap("FEE_TIER_EXIST".into()));
+        }
+        let tier = FeeTier {
+            fee_rate: amm.fee_rate(),
+            protocol_fee,
+        };
+        self.0.insert(amm, tier.clone());
+        Ok(tier)
+    }
+}
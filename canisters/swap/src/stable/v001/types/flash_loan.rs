@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+
+use ::common::utils::math::zero;
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// Outstanding flash loans, sorted by id
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FlashLoans {
+    next_id: u64,
+    loans: BTreeMap<u64, FlashLoan>,
+}
+
+impl FlashLoans {
+    pub fn query(&self, borrower: Option<&Account>) -> Vec<FlashLoan> {
+        self.loans
+            .values()
+            .filter(|loan| borrower.is_none_or(|borrower| loan.borrower == *borrower))
+            .cloned()
+            .collect()
+    }
+
+    pub fn get(&self, id: u64) -> Option<&FlashLoan> {
+        self.loans.get(&id)
+    }
+
+    /// Is the account borrowing, no more loan until settled
+    pub fn is_borrowing(&self, account: &Account) -> bool {
+        self.loans.values().any(|loan| loan.borrower == *account)
+    }
+
+    /// Is the pool lent and not settled, the reserves of pool are not synced
+    pub fn is_lending(&self, pa: &TokenPairAmm) -> bool {
+        self.loans.values().any(|loan| loan.pa == *pa)
+    }
+
+    pub fn next_id(&self) -> u64 {
+        self.next_id
+    }
+
+    pub fn insert(&mut self, loan: FlashLoan) {
+        self.next_id = self.next_id.max(loan.id + 1);
+        self.loans.insert(loan.id, loan);
+    }
+
+    pub fn remove(&mut self, id: u64) -> Option<FlashLoan> {
+        self.loans.remove(&id)
+    }
+}
+
+/// A loan takes at most 1/10 of the reserve, which bounds the loss of liquidity providers if it is defaulted
+pub const FLASH_LOAN_MAX_RESERVE_DIVISOR: u32 = 10;
+
+/// Check the loan against the pool before lending.
+/// The amount is capped by the reserve, and must be held by the pool besides the unsettled loans.
+pub fn check_flash_loan_reserve(loan: &FlashLoan, reserve: &Nat, balance: &Nat) -> Result<(), BusinessError> {
+    if *reserve < loan.amount.clone() * FLASH_LOAN_MAX_RESERVE_DIVISOR {
+        return Err(BusinessError::Swap("FLASH_LOAN_EXCEEDS_CAP".into()));
+    }
+    if *balance < loan.amount {
+        return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+    }
+    Ok(())
+}
+
+/// Transfer of lending, the amount goes to borrower without collateral.
+/// The reserves are not changed, the lent amount is counted in the balance of pool until settled.
+pub fn flash_loan_lend_transfer(loan: &FlashLoan, pool_account: Account) -> TransferToken {
+    TransferToken {
+        token: loan.token,
+        from: pool_account,
+        amount: loan.amount.clone(),
+        to: loan.borrower,
+        fee: None,
+        spender: None,
+    }
+}
+
+/// The fee in the amount paid by borrower, the amount lent is repaid first
+pub fn flash_loan_paid_fee(loan: &FlashLoan, paid: &Nat) -> Nat {
+    if loan.amount < *paid {
+        paid.clone() - loan.amount.clone()
+    } else {
+        zero()
+    }
+}
+
+/// Transfers of settling, the paid amount is taken from borrower, which is the repayment unless defaulted.
+/// The protocol fee goes to fee_to and the rest goes to pool.
+pub fn flash_loan_settle_transfers(
+    loan: &FlashLoan,
+    pool_account: Account,
+    fee_to: Option<Account>,
+    protocol_fee: &Nat,
+    paid: &Nat,
+) -> Vec<TransferToken> {
+    let fee_to = fee_to.filter(|_| zero() < *protocol_fee);
+    let mut transfers = vec![TransferToken {
+        token: loan.token,
+        from: loan.borrower,
+        amount: match fee_to {
+            Some(_) => paid.clone() - protocol_fee.clone(),
+            None => paid.clone(),
+        },
+        to: pool_account,
+        fee: None,
+        spender: None,
+    }];
+    if let Some(fee_to) = fee_to {
+        transfers.push(TransferToken {
+            token: loan.token,
+            from: loan.borrower,
+            amount: protocol_fee.clone(),
+            to: fee_to,
+            fee: None,
+            spender: None,
+        });
+    }
+    transfers.retain(|transfer| zero() < transfer.amount);
+    transfers
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::super::testing::{pool, token};
+    use super::*;
+
+    fn account(id: u8) -> Account {
+        Account {
+            owner: CanisterId::from_slice(&[id, id]),
+            subaccount: None,
+        }
+    }
+
+    // apply transfers to balances, none of them can be overdrawn
+    fn apply(balances: &mut HashMap<Account, Nat>, transfers: &[TransferToken]) -> Result<(), BusinessError> {
+        for transfer in transfers {
+            let from = balances.entry(transfer.from).or_insert_with(zero);
+            if *from < transfer.amount {
+                return Err(BusinessError::insufficient_balance(transfer.token, from.clone()));
+            }
+            *from -= transfer.amount.clone();
+            *balances.entry(transfer.to).or_insert_with(zero) += transfer.amount.clone();
+        }
+        Ok(())
+    }
+
+    fn loan(amount: u64, fee: u64) -> FlashLoan {
+        let (pa, _) = pool(1, 2, 1_000_000, 1_000_000);
+        FlashLoan {
+            id: 0,
+            pa,
+            token: token(1),
+            borrower: account(3),
+            amount: Nat::from(amount),
+            fee: Nat::from(fee),
+            lent: TimestampNanos::from_inner(0),
+        }
+    }
+
+    #[test]
+    fn test_flash_loan_transfers() {
+        let (pool_account, fee_to) = (account(1), account(2));
+        let loan = loan(10_000, 30);
+        let protocol_fee = Nat::from(5_u32);
+        let balances = HashMap::from([(pool_account, Nat::from(1_000_000_u32))]);
+
+        // capped by reserve, and the pool must hold the amount
+        let reserve = Nat::from(100_000_u32);
+        check_flash_loan_reserve(&loan, &reserve, &reserve).unwrap();
+        assert!(check_flash_loan_reserve(&loan, &Nat::from(99_999_u32), &reserve).is_err());
+        assert!(check_flash_loan_reserve(&loan, &reserve, &Nat::from(9_999_u32)).is_err());
+
+        // lend, nothing is taken from borrower
+        let mut lent = balances.clone();
+        apply(&mut lent, &[flash_loan_lend_transfer(&loan, pool_account)]).unwrap();
+        assert_eq!(lent[&loan.borrower], loan.amount);
+        assert_eq!(lent[&pool_account], Nat::from(990_000_u32));
+
+        // repaid, the pool gets the amount and the fee except protocol fee
+        let mut repaid = lent.clone();
+        *repaid.get_mut(&loan.borrower).unwrap() += Nat::from(30_u32); // profit of callback
+        let paid = loan.repayment();
+        assert_eq!(flash_loan_paid_fee(&loan, &paid), loan.fee);
+        let transfers = flash_loan_settle_transfers(&loan, pool_account, Some(fee_to), &protocol_fee, &paid);
+        apply(&mut repaid, &transfers).unwrap();
+        assert_eq!(repaid[&pool_account], Nat::from(1_000_025_u32));
+        assert_eq!(repaid[&fee_to], protocol_fee);
+        assert_eq!(repaid[&loan.borrower], zero());
+
+        // defaulted, what is left is taken and no fee is paid
+        let mut defaulted = lent.clone();
+        *defaulted.get_mut(&loan.borrower).unwrap() = Nat::from(4_000_u32); // lost in callback
+        let paid = Nat::from(4_000_u32);
+        assert_eq!(flash_loan_paid_fee(&loan, &paid), zero());
+        let transfers = flash_loan_settle_transfers(&loan, pool_account, Some(fee_to), &zero(), &paid);
+        assert_eq!(transfers.len(), 1);
+        apply(&mut defaulted, &transfers).unwrap();
+        assert_eq!(defaulted[&pool_account], Nat::from(994_000_u32));
+        assert_eq!(defaulted[&loan.borrower], zero());
+
+        // the amount is repaid but the fee is not paid in full
+        assert_eq!(flash_loan_paid_fee(&loan, &Nat::from(10_010_u32)), Nat::from(10_u32));
+
+        // nothing is left, nothing is transferred
+        assert!(flash_loan_settle_transfers(&loan, pool_account, Some(fee_to), &zero(), &zero()).is_empty());
+
+        // no fee to, all the fee goes to pool
+        let mut repaid = lent.clone();
+        *repaid.get_mut(&loan.borrower).unwrap() += Nat::from(30_u32);
+        let transfers = flash_loan_settle_transfers(&loan, pool_account, None, &protocol_fee, &loan.repayment());
+        assert_eq!(transfers.len(), 1);
+        apply(&mut repaid, &transfers).unwrap();
+        assert_eq!(repaid[&pool_account], Nat::from(1_000_030_u32));
+    }
+
+    #[test]
+    fn test_flash_loans() {
+        let mut loans = FlashLoans::default();
+        let mut lent = loan(10_000, 30);
+        lent.id = loans.next_id();
+        loans.insert(lent.clone());
+        assert_eq!(loans.next_id(), 1);
+        assert!(loans.is_lending(&lent.pa));
+        assert!(loans.is_borrowing(&lent.borrower));
+        assert!(!loans.is_borrowing(&account(4)));
+        assert_eq!(loans.query(Some(&account(4))).len(), 0);
+        assert_eq!(loans.query(None).len(), 1);
+
+        // settled, the pool can be lent again
+        loans.remove(lent.id).unwrap();
+        assert!(!loans.is_lending(&lent.pa));
+        assert!(!loans.is_borrowing(&lent.borrower));
+        assert_eq!(loans.next_id(), 1);
+    }
+}
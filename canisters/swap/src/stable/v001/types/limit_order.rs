@@ -0,0 +1,236 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// Resting limit orders, sorted by id
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LimitOrders {
+    next_id: u64,
+    orders: BTreeMap<u64, LimitOrder>,
+    #[serde(default)]
+    cursor: u64, // the id of order scheduled next
+}
+
+const MAX_LIMIT_ORDERS_PER_OWNER: usize = 100;
+const MAX_LIMIT_ORDERS: usize = 10_000;
+const SCHEDULE_LIMIT_ORDERS_BATCH: usize = 50; // ! bounded work of one schedule message
+
+impl LimitOrders {
+    pub fn query(&self, owner: Option<&Account>) -> Vec<LimitOrder> {
+        self.orders
+            .values()
+            .filter(|order| owner.is_none_or(|owner| order.owner == *owner))
+            .cloned()
+            .collect()
+    }
+
+    pub fn get(&self, id: u64) -> Option<&LimitOrder> {
+        self.orders.get(&id)
+    }
+
+    pub fn next_id(&self) -> u64 {
+        self.next_id
+    }
+
+    pub fn insert(&mut self, order: LimitOrder) {
+        self.next_id = self.next_id.max(order.id + 1);
+        self.orders.insert(order.id, order);
+    }
+
+    pub fn check_capacity(&self, owner: &Account) -> Result<(), BusinessError> {
+        if MAX_LIMIT_ORDERS <= self.orders.len()
+            || MAX_LIMIT_ORDERS_PER_OWNER <= self.orders.values().filter(|order| order.owner == *owner).count()
+        {
+            return Err(BusinessError::Swap("TOO_MANY_LIMIT_ORDERS".into()));
+        }
+        Ok(())
+    }
+
+    /// The next batch of orders to be filled or expired, starting from the saved cursor and wrapping around at the end.
+    pub fn next_batch(&mut self) -> Vec<LimitOrder> {
+        let scan = |start: u64| {
+            self.orders
+                .range(start..)
+                .take(SCHEDULE_LIMIT_ORDERS_BATCH)
+                .map(|(_, order)| order.clone())
+                .collect::<Vec<_>>()
+        };
+        let mut batch = scan(self.cursor);
+        if batch.is_empty() && 0 < self.cursor {
+            batch = scan(0);
+        }
+        self.cursor = batch.last().map(|order| order.id + 1).unwrap_or_default();
+        batch
+    }
+
+    pub fn remove(&mut self, id: u64) -> Option<LimitOrder> {
+        self.orders.remove(&id)
+    }
+}
+
+/// Transfer of placing, the token in is escrowed from owner
+pub fn limit_order_escrow_transfer(order: &LimitOrder, escrow: Account) -> TransferToken {
+    TransferToken {
+        token: order.path[0].token.0,
+        from: order.owner,
+        amount: order.amount_in.clone(),
+        to: escrow,
+        fee: None,
+        spender: None,
+    }
+}
+
+/// Transfer of cancelling or expiring, the escrowed token in is returned to owner
+pub fn limit_order_refund_transfer(order: &LimitOrder, escrow: Account) -> TransferToken {
+    TransferToken {
+        token: order.path[0].token.0,
+        from: escrow,
+        amount: order.amount_in.clone(),
+        to: order.owner,
+        fee: None,
+        spender: None,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::collections::HashMap;
+
+    use ::common::types::SwapTokenPair;
+    use ::common::utils::math::zero;
+
+    use super::super::testing::{pool, self_canister, token};
+    use super::*;
+
+    fn account(id: u8) -> Account {
+        Account {
+            owner: CanisterId::from_slice(&[id, id]),
+            subaccount: None,
+        }
+    }
+
+    // apply transfers to balances, none of them can be overdrawn
+    fn apply(balances: &mut HashMap<Account, Nat>, transfers: &[TransferToken]) -> Result<(), BusinessError> {
+        for transfer in transfers {
+            let from = balances.entry(transfer.from).or_insert_with(zero);
+            if *from < transfer.amount {
+                return Err(BusinessError::insufficient_balance(transfer.token, from.clone()));
+            }
+            *from -= transfer.amount.clone();
+            *balances.entry(transfer.to).or_insert_with(zero) += transfer.amount.clone();
+        }
+        Ok(())
+    }
+
+    // sell 10_000 of token 1 for at least 20_000 of token 2
+    fn order(id: u64, owner: Account, expiration: u64) -> LimitOrder {
+        let (pa, _) = pool(1, 2, 1_000_000, 1_000_000);
+        LimitOrder {
+            id,
+            owner,
+            amount_in: Nat::from(10_000_u32),
+            amount_out_min: Nat::from(20_000_u32),
+            path: vec![SwapTokenPair {
+                token: (token(1), token(2)),
+                amm: pa.amm.into_text(),
+            }],
+            to: owner,
+            expiration: TimestampNanos::from_inner(expiration),
+            placed: TimestampNanos::from_inner(0),
+        }
+    }
+
+    #[test]
+    fn test_limit_order_place() {
+        let escrow = LimitOrder::escrow(self_canister().id());
+        let (alice, bob) = (account(1), account(2));
+        let mut orders = LimitOrders::default();
+
+        // the token in is escrowed, the owner must hold it
+        let placed = order(orders.next_id(), alice, 100);
+        let mut balances = HashMap::from([(alice, Nat::from(10_000_u32))]);
+        apply(&mut balances, &[limit_order_escrow_transfer(&placed, escrow)]).unwrap();
+        assert_eq!(balances[&alice], zero());
+        assert_eq!(balances[&escrow], placed.amount_in);
+        assert!(apply(&mut balances, &[limit_order_escrow_transfer(&placed, escrow)]).is_err());
+
+        orders.check_capacity(&alice).unwrap();
+        orders.insert(placed);
+        orders.insert(order(orders.next_id(), bob, 100));
+        assert_eq!(orders.next_id(), 2);
+        assert_eq!(orders.query(Some(&alice)).len(), 1);
+        assert_eq!(orders.query(None).len(), 2);
+
+        // too many orders of one owner
+        for _ in 1..MAX_LIMIT_ORDERS_PER_OWNER {
+            orders.insert(order(orders.next_id(), alice, 100));
+        }
+        assert!(orders.check_capacity(&alice).is_err());
+        orders.check_capacity(&bob).unwrap();
+    }
+
+    #[test]
+    fn test_limit_order_fill() {
+        let self_canister = self_canister();
+        let order = order(0, account(1), 100);
+        let pas = vec![pool(1, 2, 0, 0).0];
+        let amounts_out = |reserve_in: u64, reserve_out: u64| {
+            let (pa, maker) = pool(1, 2, reserve_in, reserve_out);
+            TokenPairs::inner_get_amounts_out(
+                |p| {
+                    if *p == pa {
+                        Ok(maker.clone())
+                    } else {
+                        Err(p.not_exist())
+                    }
+                },
+                &self_canister,
+                &order.amount_in,
+                &order.amount_out_min,
+                &order.path,
+                &pas,
+            )
+        };
+
+        // price is 1, not crossed yet
+        assert!(amounts_out(1_000_000, 1_000_000).is_err());
+
+        // price is 3, crossed and filled at no less than the target price
+        let (amounts, _) = amounts_out(1_000_000, 3_000_000).unwrap();
+        assert!(order.amount_out_min <= amounts[1]);
+
+        // the filled order is not scheduled again
+        let mut orders = LimitOrders::default();
+        orders.insert(order.clone());
+        assert_eq!(orders.next_batch().len(), 1);
+        assert!(orders.remove(order.id).is_some());
+        assert!(orders.next_batch().is_empty());
+    }
+
+    #[test]
+    fn test_limit_order_expire_and_cancel() {
+        let escrow = LimitOrder::escrow(self_canister().id());
+        let alice = account(1);
+        let order = order(0, alice, 100);
+
+        assert!(!order.is_expired(TimestampNanos::from_inner(99)));
+        assert!(order.is_expired(TimestampNanos::from_inner(100)));
+
+        // expired or cancelled, the escrowed token in is all returned to owner
+        let mut balances = HashMap::from([(alice, Nat::from(10_000_u32))]);
+        apply(&mut balances, &[limit_order_escrow_transfer(&order, escrow)]).unwrap();
+        apply(&mut balances, &[limit_order_refund_transfer(&order, escrow)]).unwrap();
+        assert_eq!(balances[&alice], Nat::from(10_000_u32));
+        assert_eq!(balances[&escrow], zero());
+
+        // refunded only once
+        assert!(apply(&mut balances, &[limit_order_refund_transfer(&order, escrow)]).is_err());
+        let mut orders = LimitOrders::default();
+        orders.insert(order.clone());
+        assert!(orders.remove(order.id).is_some());
+        assert!(orders.remove(order.id).is_none());
+    }
+}
@@ -0,0 +1,258 @@
+use std::borrow::Cow;
+
+use ::common::utils::math::zero;
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// The max count of deposits kept of a position, the oldest is dropped
+pub const MAX_LP_DEPOSITS: usize = 100;
+
+/// Liquidity minted to the account, at the reserves of pool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct LpDeposit {
+    pub block: BlockIndex,    // swap block of mint
+    pub liquidity: Nat,       // lp minted
+    pub amount: (Nat, Nat),   // token0 and token1 deposited
+    pub reserves: (Nat, Nat), // reserves of pool after deposit
+    pub total_supply: Nat,    // lp supply after deposit
+}
+
+/// Cost basis of the liquidity held by the account, reduced proportionally by burning
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct LpCostBasis {
+    pub liquidity: Nat,   // minted minus burned
+    pub cost: (Nat, Nat), // token0 and token1 deposited of the remaining liquidity
+    pub root_k: Nat,      // share of sqrt(k) when deposited, which only grows by fees later
+    pub deposits: Vec<LpDeposit>,
+}
+
+/// Fees earned and impermanent loss of a position, values are in token1
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct LpEarnings {
+    pub pa: TokenPairAmm,
+    pub basis: LpCostBasis,
+    pub amount: (Nat, Nat), // current share of reserves
+    pub fees: (Nat, Nat),   // part of amount earned by fees
+    pub hodl_value: Nat,    // value of cost if tokens were held
+    pub value: Nat,         // value of amount
+    pub impermanent_loss: Nat,
+}
+
+impl LpCostBasis {
+    fn mint(&mut self, deposit: LpDeposit) {
+        let root_k = Nat::from((deposit.reserves.0.clone() * deposit.reserves.1.clone()).0.sqrt());
+        self.root_k += deposit.liquidity.clone() * root_k / deposit.total_supply.clone();
+        self.cost.0 += deposit.amount.0.clone();
+        self.cost.1 += deposit.amount.1.clone();
+        self.liquidity += deposit.liquidity.clone();
+        self.deposits.push(deposit);
+        if MAX_LP_DEPOSITS < self.deposits.len() {
+            self.deposits.remove(0);
+        }
+    }
+
+    fn burn(&mut self, liquidity: &Nat) {
+        let reduce = |value: &Nat| value.clone() * liquidity.clone() / self.liquidity.clone();
+        let (cost0, cost1, root_k) = (reduce(&self.cost.0), reduce(&self.cost.1), reduce(&self.root_k));
+        self.cost.0 -= cost0;
+        self.cost.1 -= cost1;
+        self.root_k -= root_k;
+        self.liquidity -= liquidity.clone();
+    }
+
+    /// Only the liquidity still held is counted, the rest is transferred away and its cost goes with it
+    pub fn earnings(&self, pa: TokenPairAmm, maker: &SwapV2MarketMaker, held: &Nat) -> LpEarnings {
+        if *held < self.liquidity {
+            let mut basis = self.clone();
+            basis.burn(&(self.liquidity.clone() - held.clone()));
+            return basis.held_earnings(pa, maker);
+        }
+        self.held_earnings(pa, maker)
+    }
+
+    fn held_earnings(&self, pa: TokenPairAmm, maker: &SwapV2MarketMaker) -> LpEarnings {
+        let (reserve0, reserve1) = (&maker.reserve0, &maker.reserve1);
+        let total_supply = maker.lp.get_total_supply();
+        if total_supply == zero() || *reserve0 == zero() {
+            return LpEarnings {
+                pa,
+                basis: self.clone(),
+                amount: (zero(), zero()),
+                fees: (zero(), zero()),
+                hodl_value: zero(),
+                value: zero(),
+                impermanent_loss: zero(),
+            };
+        }
+        let value =
+            |amount0: &Nat, amount1: &Nat| amount0.clone() * reserve1.clone() / reserve0.clone() + amount1.clone();
+        let share = |value: &Nat| self.liquidity.clone() * value.clone() / total_supply.clone();
+
+        let amount = (share(reserve0), share(reserve1));
+        // without fees, the share of sqrt(k) would be the same as deposited
+        let root_k = share(&Nat::from((reserve0.clone() * reserve1.clone()).0.sqrt()));
+        let principal = |amount: &Nat| {
+            if root_k == zero() || root_k <= self.root_k {
+                return amount.clone();
+            }
+            amount.clone() * self.root_k.clone() / root_k.clone()
+        };
+        let principal = (principal(&amount.0), principal(&amount.1));
+        let fees = (
+            amount.0.clone() - principal.0.clone(),
+            amount.1.clone() - principal.1.clone(),
+        );
+
+        let hodl_value = value(&self.cost.0, &self.cost.1);
+        let principal_value = value(&principal.0, &principal.1);
+        let impermanent_loss = if principal_value < hodl_value {
+            hodl_value.clone() - principal_value
+        } else {
+            zero()
+        };
+
+        LpEarnings {
+            pa,
+            basis: self.clone(),
+            value: value(&amount.0, &amount.1),
+            amount,
+            fees,
+            hodl_value,
+            impermanent_loss,
+        }
+    }
+}
+
+impl Storable for LpCostBasis {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        use ic_canister_kit::common::trap;
+        Cow::Owned(trap(ic_canister_kit::functions::stable::to_bytes(self)))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        use ic_canister_kit::common::trap;
+        trap(ic_canister_kit::functions::stable::from_bytes(&bytes))
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// Cost basis of liquidity by account and lp token of pool
+#[derive(Serialize, Deserialize)]
+pub struct LpCostBases {
+    #[serde(skip, default = "init_lp_cost_bases")]
+    bases: StableBTreeMap<TokenAccount, LpCostBasis>,
+}
+
+impl Default for LpCostBases {
+    fn default() -> Self {
+        Self {
+            bases: init_lp_cost_bases(),
+        }
+    }
+}
+
+fn lp_token_account(account: Account, pa: &TokenPairAmm) -> TokenAccount {
+    TokenAccount::new(pa.get_subaccount_and_dummy_canister_id().1.id(), account)
+}
+
+impl LpCostBases {
+    pub fn query(&self, account: &Account, pa: &TokenPairAmm) -> Option<LpCostBasis> {
+        self.bases.get(&lp_token_account(*account, pa))
+    }
+
+    pub fn mint(&mut self, account: Account, pa: TokenPairAmm, deposit: LpDeposit) {
+        let key = lp_token_account(account, &pa);
+        let mut basis = self.bases.get(&key).unwrap_or_default();
+        basis.mint(deposit);
+        self.bases.insert(key, basis);
+    }
+
+    /// Liquidity not minted by the account, such as transferred in, is not tracked
+    pub fn burn(&mut self, account: &Account, pa: &TokenPairAmm, liquidity: &Nat) {
+        let key = lp_token_account(*account, pa);
+        let Some(mut basis) = self.bases.get(&key) else {
+            return;
+        };
+        if basis.liquidity <= *liquidity {
+            self.bases.remove(&key);
+        } else {
+            basis.burn(liquidity);
+            self.bases.insert(key, basis);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::common::types::PoolLp;
+
+    use super::*;
+
+    fn maker(reserve: u32, total_supply: u32) -> SwapV2MarketMaker {
+        let token0 = CanisterId::from_slice(&[1]);
+        let token1 = CanisterId::from_slice(&[2]);
+        let token = |canister_id: CanisterId| TokenInfo {
+            canister_id,
+            name: "Token".into(),
+            symbol: "T".into(),
+            decimals: 8,
+            fee: Nat::from(10_000_u32),
+            is_lp_token: false,
+        };
+        let dummy_canister_id = DummyCanisterId::new(CanisterId::from_slice(&[3]));
+        let mut lp = PoolLp::new_inner_lp(dummy_canister_id, &token(token0), &token(token1));
+        if let PoolLp::InnerLP(inner) = &mut lp {
+            inner.total_supply = Nat::from(total_supply);
+        }
+        let mut maker = SwapV2MarketMaker::new([0; 32], SwapRatio::new(3, 1_000), token0, token1, lp, None);
+        maker.reserve0 = Nat::from(reserve);
+        maker.reserve1 = Nat::from(reserve);
+        maker
+    }
+
+    #[test]
+    fn test_lp_cost_basis() {
+        let pa = TokenPairAmm {
+            pair: TokenPair::new(CanisterId::from_slice(&[1]), CanisterId::from_slice(&[2])),
+            amm: Amm::SwapV2M500,
+        };
+        let mut basis = LpCostBasis::default();
+        basis.mint(LpDeposit {
+            block: 0,
+            liquidity: Nat::from(100_u32),
+            amount: (Nat::from(100_u32), Nat::from(100_u32)),
+            reserves: (Nat::from(1_000_u32), Nat::from(1_000_u32)),
+            total_supply: Nat::from(1_000_u32),
+        });
+        assert_eq!(basis.root_k, Nat::from(100_u32));
+
+        // reserves grow by fees only
+        let maker = maker(1_210, 1_000);
+        let earnings = basis.earnings(pa, &maker, &Nat::from(100_u32));
+        assert_eq!(earnings.amount, (Nat::from(121_u32), Nat::from(121_u32)));
+        assert_eq!(earnings.fees, (Nat::from(21_u32), Nat::from(21_u32)));
+        assert_eq!(earnings.hodl_value, Nat::from(200_u32));
+        assert_eq!(earnings.value, Nat::from(242_u32));
+        assert_eq!(earnings.impermanent_loss, zero());
+
+        // half of lp is transferred away, so is its cost
+        let earnings = basis.earnings(pa, &maker, &Nat::from(50_u32));
+        assert_eq!(earnings.basis.liquidity, Nat::from(50_u32));
+        assert_eq!(earnings.basis.cost, (Nat::from(50_u32), Nat::from(50_u32)));
+        assert_eq!(earnings.amount, (Nat::from(60_u32), Nat::from(60_u32)));
+        assert_eq!(earnings.fees, (Nat::from(10_u32), Nat::from(10_u32)));
+        assert_eq!(earnings.hodl_value, Nat::from(100_u32));
+
+        // burning reduces cost proportionally
+        basis.burn(&Nat::from(40_u32));
+        assert_eq!(basis.liquidity, Nat::from(60_u32));
+        assert_eq!(basis.cost, (Nat::from(60_u32), Nat::from(60_u32)));
+        assert_eq!(basis.root_k, Nat::from(60_u32));
+        assert_eq!(basis.deposits.len(), 1);
+        let earnings = basis.earnings(pa, &maker, &Nat::from(60_u32));
+        assert_eq!(earnings.amount, (Nat::from(72_u32), Nat::from(72_u32)));
+        assert_eq!(earnings.hodl_value, Nat::from(120_u32));
+    }
+}
@@ -1,5 +1,5 @@
 use ic_canister_kit::common::trap;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::RwLock;
 
 use ::common::utils::math::zero;
@@ -10,15 +10,23 @@ use super::*;
 use super::super::super::with_mut_state;
 
 use super::{
-    BusinessError, InnerTokenPairSwapGuard, MarketMaker, PairRemove, PairSwapToken, SelfCanister, TokenBalances,
-    TokenInfo, TokenPairLiquidityAddArg, TokenPairLiquidityAddSuccess, TokenPairLiquidityRemoveArg,
-    TokenPairLiquidityRemoveSuccess, TokenPairSwapTokensSuccess,
+    BusinessError, InnerTokenPairSwapGuard, MarketMaker, PairRemove, PairSwapToken, PmmV1Operation, PmmV1Price,
+    SelfCanister, StablePoolOperation, StablePoolState, StableSwapOperation, StableSwapState, TokenBalances, TokenInfo,
+    TokenPairLiquidityAddArg, TokenPairLiquidityAddSuccess, TokenPairLiquidityRemoveArg,
+    TokenPairLiquidityRemoveSuccess, TokenPairSwapTokensSuccess, TokenPool, TokenPoolLiquidityAddArg,
+    TokenPoolLiquidityAddSuccess, TokenPoolLiquidityRemoveArg, TokenPoolLiquidityRemoveSuccess,
 };
 
 #[derive(Serialize, Deserialize)]
 pub struct TokenPairs {
     #[serde(skip, default = "init_token_pairs")]
     pairs: StableBTreeMap<TokenPairAmm, MarketMaker>,
+    #[serde(skip, default = "init_token_pools")]
+    pools: StableBTreeMap<TokenPoolAmm, MarketMaker>,
+    #[serde(skip, default = "init_swap_v3_ticks")]
+    v3_ticks: StableBTreeMap<TokenPairAmm, SwapV3Ticks>,
+    #[serde(skip, default = "init_swap_v3_positions")]
+    v3_positions: StableBTreeMap<TokenPairAmm, SwapV3Positions>,
     #[serde(default = "Default::default")]
     locks: RwLock<HashMap<TokenPairAmm, bool>>,
 }
@@ -27,16 +35,69 @@ impl Default for TokenPairs {
     fn default() -> Self {
         Self {
             pairs: init_token_pairs(),
+            pools: init_token_pools(),
+            v3_ticks: init_swap_v3_ticks(),
+            v3_positions: init_swap_v3_positions(),
             locks: Default::default(),
         }
     }
 }
 
+// ticks and positions of swap v3 pool grow with positions, so they are kept in their own maps
+fn load_pair_maker(
+    pairs: &StableBTreeMap<TokenPairAmm, MarketMaker>,
+    v3_ticks: &StableBTreeMap<TokenPairAmm, SwapV3Ticks>,
+    v3_positions: &StableBTreeMap<TokenPairAmm, SwapV3Positions>,
+    pa: &TokenPairAmm,
+) -> Option<MarketMaker> {
+    let mut maker = pairs.get(pa)?;
+    if let MarketMaker::SwapV3(value) = &mut maker {
+        // ! pools stored before have ticks and positions inside the market maker
+        if let (Some(ticks), Some(positions)) = (v3_ticks.get(pa), v3_positions.get(pa)) {
+            value.restore_ticks_and_positions(ticks, positions);
+        }
+    }
+    Some(maker)
+}
+fn store_pair_maker(
+    pairs: &mut StableBTreeMap<TokenPairAmm, MarketMaker>,
+    v3_ticks: &mut StableBTreeMap<TokenPairAmm, SwapV3Ticks>,
+    v3_positions: &mut StableBTreeMap<TokenPairAmm, SwapV3Positions>,
+    pa: TokenPairAmm,
+    mut maker: MarketMaker,
+) {
+    if let MarketMaker::SwapV3(value) = &mut maker {
+        let (ticks, positions) = value.take_ticks_and_positions();
+        v3_ticks.insert(pa, ticks);
+        v3_positions.insert(pa, positions);
+    }
+    pairs.insert(pa, maker);
+}
+fn remove_pair_maker(
+    pairs: &mut StableBTreeMap<TokenPairAmm, MarketMaker>,
+    v3_ticks: &mut StableBTreeMap<TokenPairAmm, SwapV3Ticks>,
+    v3_positions: &mut StableBTreeMap<TokenPairAmm, SwapV3Positions>,
+    pa: &TokenPairAmm,
+) {
+    pairs.remove(pa);
+    v3_ticks.remove(pa);
+    v3_positions.remove(pa);
+}
+
 impl TokenPairs {
     pub fn query_all_token_pair_pools(&self) -> Vec<(TokenPairAmm, MarketMaker)> {
         self.pairs
             .keys()
-            .filter_map(|pa| self.pairs.get(&pa).map(|maker| (pa, maker)))
+            .filter_map(|pa| {
+                load_pair_maker(&self.pairs, &self.v3_ticks, &self.v3_positions, &pa).map(|maker| (pa, maker))
+            })
+            .collect()
+    }
+
+    pub fn query_all_token_pools(&self) -> Vec<(TokenPoolAmm, MarketMaker)> {
+        self.pools
+            .keys()
+            .filter_map(|pool| self.pools.get(&pool).map(|maker| (pool, maker)))
             .collect()
     }
 
@@ -46,19 +107,41 @@ impl TokenPairs {
     ) -> HashMap<CanisterId, TokenInfo> {
         self.query_all_token_pair_pools()
             .into_iter()
+            .chain(
+                self.query_all_token_pools()
+                    .into_iter()
+                    .map(|(pool, maker)| (pool.anchor(), maker)),
+            )
             .flat_map(|(pa, maker)| maker.dummy_tokens(tokens, &pa))
             .map(|info| (info.canister_id, info))
             .collect()
     }
 
+    /// Lp tokens kept by outer ledgers, which can be deposited and withdrawn
+    pub fn query_outer_lp_tokens(
+        &self,
+        tokens: &HashMap<CanisterId, Cow<'_, TokenInfo>>,
+    ) -> HashMap<CanisterId, TokenInfo> {
+        let outer = self
+            .query_all_token_pair_pools()
+            .into_iter()
+            .map(|(_, maker)| maker)
+            .chain(self.query_all_token_pools().into_iter().map(|(_, maker)| maker))
+            .filter_map(|maker| maker.outer_lp_canister())
+            .collect::<HashSet<_>>();
+        self.query_dummy_tokens(tokens)
+            .into_iter()
+            .filter(|(canister_id, _)| outer.contains(canister_id))
+            .collect()
+    }
+
     pub fn query_dummy_token_info(
         &self,
         tokens: &HashMap<CanisterId, Cow<'_, TokenInfo>>,
         pa: &TokenPairAmm,
     ) -> Option<TokenInfo> {
         let mut tokens = self
-            .pairs
-            .get(pa)
+            .get_token_pair_pool(pa)
             .map(|maker| maker.dummy_tokens(tokens, pa))
             .unwrap_or_default();
         if tokens.is_empty() {
@@ -72,15 +155,40 @@ impl TokenPairs {
 
     /// Query the accounts involved in this coin pair pool
     pub fn get_token_pair_pool(&self, pa: &TokenPairAmm) -> Option<MarketMaker> {
-        self.pairs.get(pa)
+        match self.find_token_pool(pa) {
+            Some(pool) => self.pools.get(&pool), // member pair of multi-asset pool
+            None => load_pair_maker(&self.pairs, &self.v3_ticks, &self.v3_positions, pa),
+        }
+    }
+
+    /// Find the multi-asset pool which the token pair belongs to
+    pub fn find_token_pool(&self, pa: &TokenPairAmm) -> Option<TokenPoolAmm> {
+        if !pa.amm.is_multi_asset() {
+            return None;
+        }
+        self.pools.keys().find(|pool| pool.contains_pair(pa))
+    }
+
+    pub fn get_token_pool(&self, pool: &TokenPoolAmm) -> Option<MarketMaker> {
+        self.pools.get(pool)
     }
 
     // locks
     pub fn lock(&mut self, required: Vec<TokenPairAmm>) -> Result<TokenPairsLock, Vec<TokenPairAmm>> {
+        // all member pairs of multi-asset pool are locked together
+        let pools = required
+            .iter()
+            .filter_map(|pa| self.find_token_pool(pa))
+            .collect::<HashSet<_>>();
+
         let mut locks = trap(self.locks.write()); // ! what if failed ?
 
         // duplicate removal
-        let locked = required.iter().cloned().collect::<HashSet<_>>();
+        let locked = required
+            .iter()
+            .cloned()
+            .chain(pools.iter().flat_map(|pool| pool.member_pairs()))
+            .collect::<HashSet<_>>();
 
         // 1. check first
         let mut already_locked: Vec<TokenPairAmm> = vec![];
@@ -102,7 +210,11 @@ impl TokenPairs {
             ic_cdk::println!("🔒 Locked token pair: {pa}",);
         }
 
-        Ok(TokenPairsLock { required, locked })
+        Ok(TokenPairsLock {
+            required,
+            locked,
+            pools,
+        })
     }
 
     pub fn unlock(&mut self, locked: &HashSet<TokenPairAmm>) {
@@ -125,12 +237,19 @@ impl TokenPairs {
     }
 
     pub fn be_guard<'a>(&'a mut self, lock: &'a TokenPairsLock) -> TokenPairsGuard<'a> {
-        TokenPairsGuard::new(&mut self.pairs, lock)
+        TokenPairsGuard::new(
+            &mut self.pairs,
+            &mut self.pools,
+            &mut self.v3_ticks,
+            &mut self.v3_positions,
+            lock,
+        )
     }
 
     // ============================= create pair pool =============================
 
     /// * does not need guard
+    #[allow(clippy::too_many_arguments)]
     pub fn create_token_pair_pool(
         &mut self,
         swap_guard: &mut SwapBlockChainGuard,
@@ -138,6 +257,9 @@ impl TokenPairs {
         arg: ArgWithMeta<TokenPairAmm>,
         token0: &TokenInfo,
         token1: &TokenInfo,
+        weights: Option<(u32, u32)>,
+        sqrt_price_x96: Option<Nat>,
+        protocol_fee: Option<SwapRatio>,
     ) -> Result<MarketMaker, BusinessError> {
         if self.get_token_pair_pool(&arg.arg).is_some() {
             return Err(BusinessError::TokenPairAmmExist(arg.arg));
@@ -148,6 +270,7 @@ impl TokenPairs {
             operation: SwapOperation::Pair(PairOperation::Create(PairCreate {
                 pa: arg.arg,
                 creator: arg.caller.id(),
+                pool: None,
             })),
             memo: arg.memo,
             created: arg.created,
@@ -156,10 +279,21 @@ impl TokenPairs {
         let maker = swap_guard.mint_block(arg.now, transaction, |_| {
             let TokenPairAmm { amm, .. } = &arg.arg;
             let (subaccount, dummy_canister_id) = arg.arg.get_subaccount_and_dummy_canister_id();
-            let maker = MarketMaker::new_by_pair(amm, subaccount, dummy_canister_id, token0, token1);
+            let mut maker = MarketMaker::new_by_pair(amm, subaccount, dummy_canister_id, token0, token1, weights);
+            maker.replace_protocol_fee(protocol_fee); // default protocol fee of fee tier
+            if let (MarketMaker::SwapV3(value), Some(sqrt_price_x96)) = (&mut maker, sqrt_price_x96) {
+                value.initialize(sqrt_price_x96.0)?; // initial price is given by creator
+            }
             let maker = trace_guard.handle(
                 |trace| {
-                    self.pairs.insert(arg.arg, maker.clone()); // do insert token pair pool
+                    // do insert token pair pool
+                    store_pair_maker(
+                        &mut self.pairs,
+                        &mut self.v3_ticks,
+                        &mut self.v3_positions,
+                        arg.arg,
+                        maker.clone(),
+                    );
                     trace.trace(format!(
                         "*TokenPairCreate* `token0:[{}], token1:[{}], amm:{}, subaccount:({}), dummyCanisterId:[{}]`",
                         arg.arg.pair.get_token0().to_text(),
@@ -180,6 +314,64 @@ impl TokenPairs {
         Ok(maker)
     }
 
+    /// * does not need guard
+    pub fn create_token_pool(
+        &mut self,
+        swap_guard: &mut SwapBlockChainGuard,
+        trace_guard: &mut RequestTraceGuard,
+        arg: ArgWithMeta<TokenPoolAmm>,
+        tokens: &[&TokenInfo],
+        protocol_fee: Option<SwapRatio>,
+    ) -> Result<MarketMaker, BusinessError> {
+        if self.get_token_pool(&arg.arg).is_some() {
+            return Err(BusinessError::TokenPoolAmmExist(arg.arg));
+        }
+        // a token pair can only belong to one pool of the same amm
+        if arg
+            .arg
+            .member_pairs()
+            .iter()
+            .any(|pa| self.find_token_pool(pa).is_some())
+        {
+            return Err(BusinessError::Swap("POOL_MEMBERS_OVERLAP".into()));
+        }
+
+        // 1. get token block
+        let transaction = SwapTransaction {
+            operation: SwapOperation::Pair(PairOperation::Create(PairCreate {
+                pa: arg.arg.anchor(),
+                creator: arg.caller.id(),
+                pool: Some(arg.arg.clone()),
+            })),
+            memo: arg.memo.clone(),
+            created: arg.created,
+        };
+        // 2. do create and mint block
+        let maker = swap_guard.mint_block(arg.now, transaction, |_| {
+            let (subaccount, dummy_canister_id) = arg.arg.get_subaccount_and_dummy_canister_id();
+            let mut maker = MarketMaker::new_by_pool(&arg.arg.amm, subaccount, dummy_canister_id, tokens);
+            maker.replace_protocol_fee(protocol_fee); // default protocol fee of fee tier
+            let maker = trace_guard.handle(
+                |trace| {
+                    self.pools.insert(arg.arg.clone(), maker.clone()); // do insert token pool
+                    trace.trace(format!(
+                        "*TokenPoolCreate* `pool:({}), subaccount:({}), dummyCanisterId:[{}]`",
+                        arg.arg,
+                        hex::encode(subaccount),
+                        dummy_canister_id.id().to_text()
+                    ));
+                    Ok(maker)
+                },
+                |data| {
+                    let view: MarketMakerView = data.clone().into();
+                    serde_json::to_string(&view).unwrap_or_default()
+                },
+            )?;
+            Ok(maker)
+        })?;
+        Ok(maker)
+    }
+
     // ============================= liquidity =============================
 
     pub fn check_liquidity_removable(
@@ -190,7 +382,7 @@ impl TokenPairs {
         liquidity_without_fee: &Nat,
         fee_to: Option<Account>,
     ) -> Result<(), BusinessError> {
-        let maker = self.pairs.get(pa).ok_or_else(|| pa.not_exist())?;
+        let maker = self.get_token_pair_pool(pa).ok_or_else(|| pa.not_exist())?;
         maker.check_liquidity_removable(
             |token, account| token_balances.token_balance_of(token, account),
             from,
@@ -202,7 +394,7 @@ impl TokenPairs {
     // ============================= swap =============================
 
     // Fixed input to calculate the intermediate number of each coin pair
-    fn inner_get_amounts_out<F: Fn(&TokenPairAmm) -> Result<MarketMaker, BusinessError>>(
+    pub(super) fn inner_get_amounts_out<F: Fn(&TokenPairAmm) -> Result<MarketMaker, BusinessError>>(
         get_pair: F,
         self_canister: &SelfCanister,
         amount_in: &Nat,
@@ -248,7 +440,7 @@ impl TokenPairs {
         pas: &[TokenPairAmm],
     ) -> Result<(Vec<Nat>, Vec<Account>), BusinessError> {
         Self::inner_get_amounts_out(
-            |pa| self.pairs.get(pa).ok_or_else(|| pa.not_exist()),
+            |pa| self.get_token_pair_pool(pa).ok_or_else(|| pa.not_exist()),
             self_canister,
             amount_in,
             amount_out_min,
@@ -305,7 +497,7 @@ impl TokenPairs {
         pas: &[TokenPairAmm],
     ) -> Result<(Vec<Nat>, Vec<Account>), BusinessError> {
         Self::inner_get_amounts_in(
-            |pa| self.pairs.get(pa).ok_or_else(|| pa.not_exist()),
+            |pa| self.get_token_pair_pool(pa).ok_or_else(|| pa.not_exist()),
             self_canister,
             amount_out,
             amount_in_max,
@@ -345,7 +537,6 @@ impl TokenPairs {
         }
 
         // 1. restore total supply
-        #[allow(irrefutable_let_patterns)]
         if let MarketMaker::SwapV2(maker) = &mut maker {
             if let ::common::types::PoolLp::InnerLP(lp) = &mut maker.lp {
                 lp.total_supply = Nat::from(31_622_770_277_112_u64); // ! now value is 70_277_112, wrong
@@ -385,7 +576,6 @@ impl TokenPairs {
         }
 
         // 1. restore total supply
-        #[allow(irrefutable_let_patterns)]
         if let MarketMaker::SwapV2(maker) = &mut maker {
             maker.reserve0 = icp_balance;
             maker.reserve1 = bg_balance;
@@ -402,6 +592,7 @@ impl TokenPairs {
 pub struct TokenPairsLock {
     required: Vec<TokenPairAmm>,   // The target requires locked account, print it to display
     locked: HashSet<TokenPairAmm>, // fee_to must be included
+    pools: HashSet<TokenPoolAmm>,  // multi-asset pools of required, all member pairs are locked
 }
 impl Drop for TokenPairsLock {
     fn drop(&mut self) {
@@ -421,10 +612,15 @@ mod guard {
     use super::*;
     pub struct TokenPairsGuard<'a> {
         stable_pairs: &'a mut StableBTreeMap<TokenPairAmm, MarketMaker>,
+        stable_pools: &'a mut StableBTreeMap<TokenPoolAmm, MarketMaker>,
+        stable_v3_ticks: &'a mut StableBTreeMap<TokenPairAmm, SwapV3Ticks>,
+        stable_v3_positions: &'a mut StableBTreeMap<TokenPairAmm, SwapV3Positions>,
         lock: &'a TokenPairsLock,
         // stack data
         stack_pairs: HashMap<TokenPairAmm, MarketMaker>,
+        stack_pools: HashMap<TokenPoolAmm, MarketMaker>,
         removed_pairs: HashSet<TokenPairAmm>,
+        removed_pools: HashSet<TokenPoolAmm>,
     }
     impl Drop for TokenPairsGuard<'_> {
         fn drop(&mut self) {
@@ -435,27 +631,47 @@ mod guard {
     impl<'a> TokenPairsGuard<'a> {
         pub(super) fn new(
             stable_pairs: &'a mut StableBTreeMap<TokenPairAmm, MarketMaker>,
+            stable_pools: &'a mut StableBTreeMap<TokenPoolAmm, MarketMaker>,
+            stable_v3_ticks: &'a mut StableBTreeMap<TokenPairAmm, SwapV3Ticks>,
+            stable_v3_positions: &'a mut StableBTreeMap<TokenPairAmm, SwapV3Positions>,
             lock: &'a TokenPairsLock,
         ) -> Self {
             let stack_pairs = lock
                 .locked
                 .iter()
+                .filter(|pa| !pa.amm.is_multi_asset()) // member pairs of pool are stored in pools
                 .map(|pa| {
                     (
                         *pa,
                         trap(
-                            stable_pairs
-                                .get(pa)
+                            load_pair_maker(stable_pairs, stable_v3_ticks, stable_v3_positions, pa)
                                 .ok_or_else(|| BusinessError::SystemError(format!("can not find maker by pa: {pa}"))),
                         ),
                     )
                 })
                 .collect();
+            let stack_pools =
+                lock.pools
+                    .iter()
+                    .map(|pool| {
+                        (
+                            pool.clone(),
+                            trap(stable_pools.get(pool).ok_or_else(|| {
+                                BusinessError::SystemError(format!("can not find maker by pool: {pool}"))
+                            })),
+                        )
+                    })
+                    .collect();
             Self {
                 stable_pairs,
+                stable_pools,
+                stable_v3_ticks,
+                stable_v3_positions,
                 lock,
                 stack_pairs,
+                stack_pools,
                 removed_pairs: Default::default(),
+                removed_pools: Default::default(),
             }
         }
 
@@ -463,6 +679,14 @@ mod guard {
             if !self.lock.locked.contains(pa) {
                 return Err(BusinessError::unlocked_token_pair(*pa));
             }
+            if pa.amm.is_multi_asset() {
+                return self
+                    .stack_pools
+                    .iter()
+                    .find(|(pool, _)| pool.contains_pair(pa))
+                    .map(|(_, maker)| maker)
+                    .ok_or_else(|| pa.not_exist());
+            }
             let maker = trap(
                 self.stack_pairs
                     .get(pa)
@@ -474,6 +698,14 @@ mod guard {
             if !self.lock.locked.contains(pa) {
                 return Err(BusinessError::unlocked_token_pair(*pa));
             }
+            if pa.amm.is_multi_asset() {
+                return self
+                    .stack_pools
+                    .iter_mut()
+                    .find(|(pool, _)| pool.contains_pair(pa))
+                    .map(|(_, maker)| maker)
+                    .ok_or_else(|| pa.not_exist());
+            }
             let maker = trap(
                 self.stack_pairs
                     .get_mut(pa)
@@ -490,6 +722,14 @@ mod guard {
             self.removed_pairs.insert(*pa);
         }
 
+        pub(super) fn get_token_pool_mut(&mut self, pool: &TokenPoolAmm) -> Result<&mut MarketMaker, BusinessError> {
+            self.stack_pools.get_mut(pool).ok_or_else(|| pool.not_exist())
+        }
+
+        pub(super) fn remove_pool(&mut self, pool: &TokenPoolAmm) {
+            self.removed_pools.insert(pool.clone());
+        }
+
         pub fn get_amounts_out(
             &self,
             self_canister: &SelfCanister,
@@ -499,12 +739,7 @@ mod guard {
             pas: &[TokenPairAmm],
         ) -> Result<(Vec<Nat>, Vec<Account>), BusinessError> {
             TokenPairs::inner_get_amounts_out(
-                |pa| {
-                    self.stack_pairs
-                        .get(pa)
-                        .cloned()
-                        .ok_or_else(|| BusinessError::unlocked_token_pair(*pa))
-                },
+                |pa| self.get_market_maker(pa).cloned(),
                 self_canister,
                 amount_in,
                 amount_out_min,
@@ -521,12 +756,7 @@ mod guard {
             pas: &[TokenPairAmm],
         ) -> Result<(Vec<Nat>, Vec<Account>), BusinessError> {
             TokenPairs::inner_get_amounts_in(
-                |pa| {
-                    self.stack_pairs
-                        .get(pa)
-                        .cloned()
-                        .ok_or_else(|| BusinessError::unlocked_token_pair(*pa))
-                },
+                |pa| self.get_market_maker(pa).cloned(),
                 self_canister,
                 amount_out,
                 amount_in_max,
@@ -537,10 +767,22 @@ mod guard {
 
         pub fn dump(self) {
             for (pa, maker) in self.stack_pairs.iter() {
-                self.stable_pairs.insert(*pa, maker.clone());
+                store_pair_maker(
+                    self.stable_pairs,
+                    self.stable_v3_ticks,
+                    self.stable_v3_positions,
+                    *pa,
+                    maker.clone(),
+                );
             }
             for pa in self.removed_pairs.iter() {
-                self.stable_pairs.remove(pa);
+                remove_pair_maker(self.stable_pairs, self.stable_v3_ticks, self.stable_v3_positions, pa);
+            }
+            for (pool, maker) in self.stack_pools.iter() {
+                self.stable_pools.insert(pool.clone(), maker.clone());
+            }
+            for pool in self.removed_pools.iter() {
+                self.stable_pools.remove(pool);
             }
         }
     }
@@ -553,6 +795,110 @@ impl TokenPairsGuard<'_> {
         maker.replace_protocol_fee(protocol_fee)
     }
 
+    // ============================= config dynamic fee =============================
+    pub fn replace_dynamic_fee(
+        &mut self,
+        pa: &TokenPairAmm,
+        dynamic_fee: Option<SwapV2DynamicFeeArg>,
+    ) -> Result<Option<SwapV2DynamicFee>, BusinessError> {
+        let maker = self.get_market_maker_mut(pa)?;
+        maker.replace_dynamic_fee(dynamic_fee)
+    }
+
+    // ============================= config outer lp =============================
+    pub fn replace_outer_lp(&mut self, pa: &TokenPairAmm, token_canister_id: CanisterId) -> Result<(), BusinessError> {
+        let maker = self.get_market_maker_mut(pa)?;
+        maker.replace_outer_lp(token_canister_id)
+    }
+
+    // ============================= config amplification =============================
+    pub fn replace_amplification(
+        &mut self,
+        swap_guard: &mut SwapBlockChainGuard,
+        now: TimestampNanos,
+        pa: &TokenPairAmm,
+        amplification: u64,
+    ) -> Result<u64, BusinessError> {
+        let maker = self.get_market_maker_mut(pa)?;
+        let old = maker.replace_amplification(amplification)?;
+        let operation = match maker {
+            MarketMaker::StableSwap(value) => {
+                let invariant = value.get_invariant(&value.reserve0, &value.reserve1)?;
+                PairOperation::StableSwap(StableSwapOperation::State(StableSwapState {
+                    pa: *pa,
+                    block_timestamp: now,
+                    amplification: value.amplification,
+                    supply: value.lp.get_total_supply(),
+                    reserve0: value.reserve0.clone(),
+                    reserve1: value.reserve1.clone(),
+                    invariant,
+                }))
+            }
+            MarketMaker::StablePool(value) => {
+                let invariant = value.get_invariant(&value.reserves)?;
+                PairOperation::StablePool(StablePoolOperation::State(StablePoolState {
+                    pool: TokenPoolAmm {
+                        pool: TokenPool::new(&value.tokens)?,
+                        amm: pa.amm,
+                    },
+                    block_timestamp: now,
+                    amplification: value.amplification,
+                    supply: value.lp.get_total_supply(),
+                    reserves: value.reserves.clone(),
+                    invariant,
+                }))
+            }
+            _ => return Err(BusinessError::Swap("AMPLIFICATION_NOT_SUPPORTED".into())),
+        };
+
+        // record the new state
+        let transaction = SwapTransaction {
+            operation: SwapOperation::Pair(operation),
+            memo: None,
+            created: None,
+        };
+        swap_guard.mint_block(now, transaction, |_| {
+            // do nothing
+            Ok(())
+        })?;
+
+        Ok(old)
+    }
+
+    // ============================= config pmm price =============================
+    pub fn replace_pmm_v1_price(
+        &mut self,
+        swap_guard: &mut SwapBlockChainGuard,
+        now: TimestampNanos,
+        pa: &TokenPairAmm,
+        i: Nat,
+        k: Option<Nat>,
+    ) -> Result<(Nat, Nat), BusinessError> {
+        let maker = self.get_market_maker_mut(pa)?;
+        let old = maker.replace_price(i, k)?;
+        let MarketMaker::PmmV1(value) = maker else {
+            return Err(BusinessError::Swap("PRICE_NOT_SUPPORTED".into()));
+        };
+
+        // record the new price
+        let transaction = SwapTransaction {
+            operation: SwapOperation::Pair(PairOperation::PmmV1(PmmV1Operation::Price(PmmV1Price {
+                pa: *pa,
+                block_timestamp: now,
+                i: value.i.clone(),
+                k: value.k.clone(),
+            }))),
+            memo: None,
+            created: None,
+        };
+        swap_guard.mint_block(now, transaction, |_| {
+            // do nothing
+            Ok(())
+        })?;
+
+        Ok(old)
+    }
+
     // ============================= remove pair pool =============================
 
     pub fn remove_token_pair_pool(
@@ -572,6 +918,7 @@ impl TokenPairsGuard<'_> {
             operation: SwapOperation::Pair(PairOperation::Remove(PairRemove {
                 pa: arg.arg,
                 remover: arg.caller.id(),
+                pool: None,
             })),
             memo: arg.memo,
             created: arg.created,
@@ -602,6 +949,54 @@ impl TokenPairsGuard<'_> {
         Ok(maker)
     }
 
+    // ============================= remove pool =============================
+
+    pub fn remove_token_pool(
+        &mut self,
+        swap_guard: &mut SwapBlockChainGuard,
+        trace_guard: &mut RequestTraceGuard,
+        arg: ArgWithMeta<TokenPoolAmm>,
+    ) -> Result<MarketMaker, BusinessError> {
+        let maker = self.get_token_pool_mut(&arg.arg)?;
+        if !maker.removable() {
+            return Err(BusinessError::TokenPoolAmmStillAlive(arg.arg));
+        }
+        let maker = maker.clone();
+
+        // 1. get token block
+        let transaction = SwapTransaction {
+            operation: SwapOperation::Pair(PairOperation::Remove(PairRemove {
+                pa: arg.arg.anchor(),
+                remover: arg.caller.id(),
+                pool: Some(arg.arg.clone()),
+            })),
+            memo: arg.memo.clone(),
+            created: arg.created,
+        };
+        // 2. do remove and mint block
+        let maker = swap_guard.mint_block(arg.now, transaction, |_| {
+            let (subaccount, dummy_canister_id) = arg.arg.get_subaccount_and_dummy_canister_id();
+            let maker = trace_guard.handle(
+                |trace| {
+                    self.remove_pool(&arg.arg); // ! do remove token pool when dump
+                    trace.trace(format!(
+                        "*TokenPoolRemove* `pool:({}), subaccount:({}), dummyCanisterId:[{}]`",
+                        arg.arg,
+                        hex::encode(subaccount),
+                        dummy_canister_id.id().to_text()
+                    ));
+                    Ok(maker)
+                },
+                |data| {
+                    let view: MarketMakerView = data.clone().into();
+                    serde_json::to_string(&view).unwrap_or_default()
+                },
+            )?;
+            Ok(maker)
+        })?;
+        Ok(maker)
+    }
+
     // ============================= liquidity =============================
 
     fn handle_maker<T, F>(&mut self, pa: TokenPairAmm, handle: F) -> Result<T, BusinessError>
@@ -628,6 +1023,24 @@ impl TokenPairsGuard<'_> {
         self.handle_maker(pa, |maker| super::common::remove_liquidity(maker, guard))
     }
 
+    pub fn add_pool_liquidity(
+        &mut self,
+        guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, TokenPoolLiquidityAddArg>,
+        pool: &TokenPoolAmm,
+    ) -> Result<TokenPoolLiquidityAddSuccess, BusinessError> {
+        let maker = self.get_token_pool_mut(pool)?;
+        super::common::add_pool_liquidity(maker, guard)
+    }
+
+    pub fn remove_pool_liquidity(
+        &mut self,
+        guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, TokenPoolLiquidityRemoveArg>,
+        pool: &TokenPoolAmm,
+    ) -> Result<TokenPoolLiquidityRemoveSuccess, BusinessError> {
+        let maker = self.get_token_pool_mut(pool)?;
+        super::common::remove_pool_liquidity(maker, guard)
+    }
+
     // ============================= swap =============================
 
     fn swap<T: SelfCanisterArg + TokenPairSwapArg + Clone>(
@@ -654,6 +1067,9 @@ impl TokenPairsGuard<'_> {
                 (amount_out.clone(), zero())
             };
             let to = if i < path.len() - 1 { pool_accounts[i + 1] } else { _to };
+            // the fee charged by this swap, dynamic fee may be changed after each swap
+            let fee_rate = self.get_market_maker(&pa)?.swap_fee_rate();
+            let fee = last_from_amount.clone() * fee_rate.numerator / fee_rate.denominator;
 
             let transaction = SwapTransaction {
                 operation: SwapOperation::Pair(PairOperation::Swap(PairSwapToken {
@@ -664,6 +1080,7 @@ impl TokenPairsGuard<'_> {
                     to,
                     amount_a: last_from_amount.clone(),
                     amount_b: amount_out.clone(),
+                    fee: Some(fee),
                 })),
                 memo: guard.arg.memo.clone(),
                 created: guard.arg.created,
@@ -732,6 +1149,7 @@ impl TokenPairsGuard<'_> {
             amount: amounts[0].clone(),
             to: pool_accounts[0],
             fee: None,
+            spender: None,
         })?;
 
         // do swap
@@ -764,6 +1182,7 @@ impl TokenPairsGuard<'_> {
             amount: amounts[0].clone(),
             to: pool_accounts[0],
             fee: None,
+            spender: None,
         })?;
 
         // do swap
@@ -773,6 +1192,44 @@ impl TokenPairsGuard<'_> {
         Ok(TokenPairSwapTokensSuccess { amounts })
     }
 
+    // pair swap by split paths, every leg is a swap of pay exact tokens
+    pub fn swap_split(
+        &mut self,
+        guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, TokenPairSwapSplitArg>,
+    ) -> Result<TokenPairSwapSplitSuccess, BusinessError> {
+        let arg = guard.arg.arg.clone();
+        let mut legs = Vec::with_capacity(arg.legs.len());
+        for leg in arg.legs {
+            let leg_arg = ArgWithMeta {
+                now: guard.arg.now,
+                caller: guard.arg.caller,
+                arg: TokenPairSwapExactTokensForTokensArg {
+                    self_canister: arg.self_canister,
+                    pas: leg.pas.clone(),
+                    from: arg.from,
+                    amount_in: leg.amount_in,
+                    amount_out_min: zero(), // check all legs together
+                    path: leg.path,
+                    to: arg.to,
+                },
+                memo: guard.arg.memo.clone(),
+                created: guard.arg.created,
+            };
+            // later legs see the reserves changed by earlier legs
+            let success = guard.handle_guard(leg_arg, |guard| self.swap_exact_tokens_for_tokens(guard, leg.pas))?;
+            legs.push(success);
+        }
+
+        // ! check the output of all legs
+        let amounts_out = legs
+            .iter()
+            .map(|leg| leg.amounts[leg.amounts.len() - 1].clone())
+            .collect::<Vec<_>>();
+        let amount_out = check_split_amount_out(&amounts_out, &arg.amount_out_min)?;
+
+        Ok(TokenPairSwapSplitSuccess { amount_out, legs })
+    }
+
     // pair swap by loan
     pub fn swap_by_loan(
         &mut self,
@@ -813,8 +1270,260 @@ impl TokenPairsGuard<'_> {
             from: arg.to,
             amount: arg.loan.clone(),
             to: loaner,
+            to_account_id: None,
         })?;
 
         Ok(TokenPairSwapTokensSuccess { amounts })
     }
+
+    // ============================= flash loan =============================
+
+    pub fn flash_loan_lend(
+        &mut self,
+        guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, FlashLoan>,
+    ) -> Result<(), BusinessError> {
+        let pa = guard.arg.arg.pa;
+        let self_canister_id = ic_canister_kit::identity::self_canister_id();
+        self.handle_maker(pa, |maker| {
+            super::common::flash_loan_lend(maker, guard, self_canister_id)
+        })
+    }
+
+    pub fn flash_loan_repay(
+        &mut self,
+        guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, FlashLoan>,
+    ) -> Result<(), BusinessError> {
+        let pa = guard.arg.arg.pa;
+        let self_canister_id = ic_canister_kit::identity::self_canister_id();
+        self.handle_maker(pa, |maker| {
+            super::common::flash_loan_repay(maker, guard, self_canister_id)
+        })
+    }
+
+    pub fn flash_loan_default(
+        &mut self,
+        guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, FlashLoan>,
+    ) -> Result<(), BusinessError> {
+        let pa = guard.arg.arg.pa;
+        let self_canister_id = ic_canister_kit::identity::self_canister_id();
+        self.handle_maker(pa, |maker| {
+            super::common::flash_loan_default(maker, guard, self_canister_id)
+        })
+    }
+
+    // ============================= protocol fees =============================
+
+    pub fn protocol_fees_collect(
+        &mut self,
+        guard: &mut InnerTokenPairSwapGuard<'_, '_, '_, ProtocolFeesCollectArg>,
+    ) -> Result<ProtocolFeesCollectSuccess, BusinessError> {
+        let arg = guard.arg.arg.clone();
+
+        // 1. burn all lp of swap fee to, the pool tokens are got by swap fee to
+        let mut burned = Vec::with_capacity(arg.pas.len());
+        let mut sources: Vec<(CanisterId, Account)> = vec![];
+        for pa in arg.pas {
+            let (token0, token1) = (pa.pair.get_token0(), pa.pair.get_token1());
+            for token in [token0, token1] {
+                if !sources.contains(&(token, arg.swap_fee_to)) {
+                    sources.push((token, arg.swap_fee_to));
+                }
+            }
+            let maker = self.get_market_maker(&pa)?;
+            if !matches!(maker, MarketMaker::SwapV2(_)) {
+                return Err(BusinessError::Swap("PROTOCOL_FEES_NOT_SUPPORTED".into()));
+            }
+            let Some(lp) = maker.dummy_canisters().first().copied() else {
+                continue; // outer lp is not held by this canister
+            };
+            let liquidity = guard.token_balance_of(lp, arg.swap_fee_to)?;
+            if liquidity == zero() {
+                continue;
+            }
+            let remove_arg = ArgWithMeta {
+                now: guard.arg.now,
+                caller: guard.arg.caller,
+                arg: TokenPairLiquidityRemoveArg {
+                    self_canister: arg.self_canister,
+                    pa,
+                    from: arg.swap_fee_to,
+                    token_a: token0,
+                    token_b: token1,
+                    liquidity_without_fee: liquidity.clone(),
+                    amount_a_min: zero(),
+                    amount_b_min: zero(),
+                    to: arg.swap_fee_to,
+                    fee: None, // ! protocol does not pay burn fee to itself
+                    tick_range: None,
+                    exit_token: None,
+                },
+                memo: guard.arg.memo.clone(),
+                created: guard.arg.created,
+            };
+            let success = guard.handle_guard(remove_arg, |guard| self.remove_liquidity(guard, pa))?;
+            burned.push(ProtocolFeeBurned {
+                pa,
+                liquidity,
+                amount: success.amount,
+            });
+        }
+
+        // 2. transfer fees of tokens are held by token fee to
+        if let Some(token_fee_to) = arg.token_fee_to {
+            for &token in &arg.tokens {
+                if !sources.contains(&(token, token_fee_to)) {
+                    sources.push((token, token_fee_to));
+                }
+            }
+        }
+
+        // 3. split all balances by weights, the last recipient takes the remainder
+        let mut split = vec![];
+        for (token, from) in sources {
+            let amount = guard.token_balance_of(token, from)?;
+            let shares = split_protocol_fee(&amount, &arg.recipients);
+            for (recipient, share) in arg.recipients.iter().zip(shares) {
+                if share == zero() || recipient.account == from {
+                    continue;
+                }
+                guard.token_transfer(TransferToken {
+                    token,
+                    from,
+                    amount: share.clone(),
+                    to: recipient.account,
+                    fee: None,
+                    spender: None,
+                })?;
+                split.push(ProtocolFeeSplit {
+                    token,
+                    from,
+                    to: recipient.account,
+                    amount: share,
+                });
+            }
+        }
+
+        Ok(ProtocolFeesCollectSuccess { burned, split })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::super::testing::{pool, self_canister, token};
+    use super::*;
+
+    fn path(pools: &[(TokenPairAmm, MarketMaker)], tokens: &[u8]) -> Vec<SwapTokenPair> {
+        tokens
+            .windows(2)
+            .zip(pools)
+            .map(|(tokens, (pa, _))| SwapTokenPair {
+                token: (token(tokens[0]), token(tokens[1])),
+                amm: pa.amm.into_text(),
+            })
+            .collect()
+    }
+
+    fn get_pair(
+        pools: &[(TokenPairAmm, MarketMaker)],
+    ) -> impl Fn(&TokenPairAmm) -> Result<MarketMaker, BusinessError> + '_ {
+        |pa| {
+            pools
+                .iter()
+                .find(|(p, _)| p == pa)
+                .map(|(_, maker)| maker.clone())
+                .ok_or_else(|| pa.not_exist())
+        }
+    }
+
+    #[test]
+    fn test_amounts_by_hops() {
+        let self_canister = self_canister();
+        let pools = vec![pool(1, 2, 1_000_000, 2_000_000), pool(2, 3, 2_000_000, 500_000)];
+        let pas = pools.iter().map(|(pa, _)| *pa).collect::<Vec<_>>();
+        let path = path(&pools, &[1, 2, 3]);
+        let amount_in = Nat::from(10_000_u32);
+
+        // output of the path is the output of single hops one by one
+        let (amounts, accounts) =
+            TokenPairs::inner_get_amounts_out(get_pair(&pools), &self_canister, &amount_in, &zero(), &path, &pas)
+                .unwrap();
+        let (_, hop0) = pools[0]
+            .1
+            .get_amount_out(&self_canister, &amount_in, token(1), token(2))
+            .unwrap();
+        let (_, hop1) = pools[1]
+            .1
+            .get_amount_out(&self_canister, &hop0, token(2), token(3))
+            .unwrap();
+        assert_eq!(amounts, vec![amount_in.clone(), hop0, hop1.clone()]);
+        assert_eq!(accounts.len(), 2);
+
+        // minimum output is checked by the last hop
+        let min = hop1.clone() + 1_u32;
+        assert!(
+            TokenPairs::inner_get_amounts_out(get_pair(&pools), &self_canister, &amount_in, &min, &path, &pas).is_err()
+        );
+
+        // paying the input got by exact output gets at least the output
+        let (amounts, _) = TokenPairs::inner_get_amounts_in(
+            get_pair(&pools),
+            &self_canister,
+            &hop1,
+            &Nat::from(u64::MAX),
+            &path,
+            &pas,
+        )
+        .unwrap();
+        let (paid, _) =
+            TokenPairs::inner_get_amounts_out(get_pair(&pools), &self_canister, &amounts[0], &zero(), &path, &pas)
+                .unwrap();
+        assert!(hop1 <= paid[2]);
+        assert!(amounts[0] <= amount_in + 2_u32); // rounded up by each hop
+    }
+
+    #[test]
+    fn test_swap_split() {
+        // split by weights, the last leg takes the rest of rounding
+        let amount_in = Nat::from(10_001_u32);
+        let amounts = split_amount_in(&amount_in, &[1, 1, 1]);
+        assert_eq!(
+            amounts,
+            vec![Nat::from(3_333_u32), Nat::from(3_333_u32), Nat::from(3_335_u32)]
+        );
+        assert_eq!(
+            split_amount_in(&amount_in, &[3, 1]),
+            vec![Nat::from(7_500_u32), Nat::from(2_501_u32)]
+        );
+        assert_eq!(split_amount_in(&amount_in, &[7]), vec![amount_in.clone()]);
+
+        // legs through 2 pools of the same tokens
+        let self_canister = self_canister();
+        let pools = vec![
+            pool(1, 2, 1_000_000, 2_000_000),
+            pool(1, 3, 1_000_000, 1_000_000),
+            pool(3, 2, 1_000_000, 2_000_000),
+        ];
+        let direct = (path(&pools[..1], &[1, 2]), vec![pools[0].0]);
+        let hops = (path(&pools[1..], &[1, 3, 2]), vec![pools[1].0, pools[2].0]);
+        let amounts = split_amount_in(&amount_in, &[1, 1]);
+        let amounts_out = [(&direct, &amounts[0]), (&hops, &amounts[1])]
+            .into_iter()
+            .map(|((path, pas), amount_in)| {
+                let (amounts, _) =
+                    TokenPairs::inner_get_amounts_out(get_pair(&pools), &self_canister, amount_in, &zero(), path, pas)
+                        .unwrap();
+                amounts[amounts.len() - 1].clone()
+            })
+            .collect::<Vec<_>>();
+
+        // the minimum is checked by the sum of all legs, not by each leg
+        let amount_out = amounts_out[0].clone() + amounts_out[1].clone();
+        assert!(amounts_out.iter().all(|out| *out < amount_out));
+        assert_eq!(check_split_amount_out(&amounts_out, &amount_out).unwrap(), amount_out);
+        assert!(matches!(
+            check_split_amount_out(&amounts_out, &(amount_out.clone() + 1_u32)),
+            Err(BusinessError::Swap(_))
+        ));
+    }
 }
@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use candid::Nat;
+use ic_canister_kit::types::CanisterId;
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// The result of comparing internal balances with the real ledger of token
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenReconcileReport {
+    pub token: CanisterId,
+    /// Sum of internal balances of accounts, pool accounts excluded
+    pub accounts: Nat,
+    /// Sum of internal balances of pool accounts
+    pub pools: Nat,
+    /// Balance of swap canister on the ledger
+    pub ledger: Nat,
+    /// How much the ledger is short of internal balances
+    pub deficit: Nat,
+    /// Frozen by this check
+    pub frozen: bool,
+    pub checked_at: TimestampNanos,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenReconciliation {
+    /// Last report of each token
+    reports: HashMap<CanisterId, TokenReconcileReport>,
+    /// The token is frozen if the deficit exceeds this ratio of internal balances
+    pub deficit_threshold: SwapRatio,
+    /// Check interval ns
+    pub checking_interval_ns: u64,
+    /// Last check time ns
+    pub last_checked_timestamp: TimestampNanos,
+    /// Start time ns of the check in progress
+    #[serde(default)]
+    checking: Option<TimestampNanos>,
+}
+
+impl Default for TokenReconciliation {
+    fn default() -> Self {
+        Self {
+            reports: HashMap::new(),
+            deficit_threshold: SwapRatio::new(1, 1000),    // 0.1%
+            checking_interval_ns: 1_000_000 * 1000 * 3600, // Check every hour
+            last_checked_timestamp: TimestampNanos::from_inner(0),
+            checking: None,
+        }
+    }
+}
+
+impl TokenReconciliation {
+    pub fn update_config(&mut self, config: TokenReconcileConfig) {
+        self.deficit_threshold =
+            SwapRatio::new(config.deficit_threshold.numerator, config.deficit_threshold.denominator);
+        self.checking_interval_ns = config.checking_interval_ns;
+    }
+
+    pub fn is_trigger(&self, now: TimestampNanos) -> bool {
+        self.last_checked_timestamp.into_inner() + self.checking_interval_ns < now.into_inner()
+    }
+
+    /// Only one check runs at a time, the check lost by trap is given up after the interval
+    pub fn start(&mut self, now: TimestampNanos) -> bool {
+        if self
+            .checking
+            .is_some_and(|started| now.into_inner() < started.into_inner() + self.checking_interval_ns)
+        {
+            return false;
+        }
+        self.checking = Some(now);
+        true
+    }
+
+    /// Only a completed check moves the trigger forward
+    pub fn checked(&mut self, now: TimestampNanos) {
+        self.last_checked_timestamp = now;
+        self.checking = None;
+    }
+
+    pub fn stop(&mut self) {
+        self.checking = None;
+    }
+
+    pub fn is_deficit_exceeded(&self, internal: &Nat, deficit: &Nat) -> bool {
+        let threshold = internal.clone() * Nat::from(self.deficit_threshold.numerator)
+            / Nat::from(self.deficit_threshold.denominator);
+        threshold < *deficit
+    }
+
+    pub fn get_reports(&self) -> Vec<TokenReconcileReport> {
+        let mut reports = self.reports.values().cloned().collect::<Vec<_>>();
+        reports.sort_by_key(|report| report.token);
+        reports
+    }
+
+    pub fn report(&mut self, report: TokenReconcileReport) {
+        self.reports.insert(report.token, report);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn ns(seconds: u64) -> TimestampNanos {
+        TimestampNanos::from_inner(seconds * 1_000_000_000)
+    }
+
+    #[test]
+    fn test_reconcile_trigger() {
+        let mut reconciliation = TokenReconciliation::default();
+        reconciliation.update_config(TokenReconcileConfig {
+            deficit_threshold: SwapRatio::new(1, 1000),
+            checking_interval_ns: ns(60).into_inner(),
+        });
+
+        assert!(reconciliation.is_trigger(ns(61)));
+        assert!(reconciliation.start(ns(61)));
+
+        // the check in progress is not started again
+        assert!(reconciliation.is_trigger(ns(70)));
+        assert!(!reconciliation.start(ns(70)));
+
+        // the failed check is triggered again
+        reconciliation.stop();
+        assert!(reconciliation.start(ns(80)));
+        reconciliation.checked(ns(80));
+        assert!(!reconciliation.is_trigger(ns(140)));
+        assert!(reconciliation.is_trigger(ns(141)));
+
+        // the check lost by trap is given up after the interval
+        assert!(reconciliation.start(ns(150)));
+        assert!(!reconciliation.start(ns(209)));
+        assert!(reconciliation.start(ns(210)));
+    }
+
+    #[test]
+    fn test_deficit_exceeded() {
+        let reconciliation = TokenReconciliation::default(); // 0.1%
+        let internal = Nat::from(1_000_000_u64);
+        assert!(!reconciliation.is_deficit_exceeded(&internal, &Nat::from(0_u64)));
+        assert!(!reconciliation.is_deficit_exceeded(&internal, &Nat::from(1_000_u64)));
+        assert!(reconciliation.is_deficit_exceeded(&internal, &Nat::from(1_001_u64)));
+    }
+}
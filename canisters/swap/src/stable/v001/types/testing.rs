@@ -0,0 +1,50 @@
+#![allow(clippy::unwrap_used)]
+
+use super::*;
+
+/// Self canister of tests
+pub fn self_canister() -> SelfCanister {
+    let id = CanisterId::from_text("lvfsa-2aaaa-aaaaq-aaeyq-cai").unwrap();
+    candid::decode_one(&candid::encode_one(id).unwrap()).unwrap()
+}
+
+/// Token canister of tests
+pub fn token(id: u8) -> CanisterId {
+    CanisterId::from_slice(&[id])
+}
+
+/// Swap v2 pool of 2 tokens with reserves in the order of arguments
+pub fn pool(token_a: u8, token_b: u8, reserve_a: u64, reserve_b: u64) -> (TokenPairAmm, MarketMaker) {
+    let info = |canister_id: CanisterId| TokenInfo {
+        canister_id,
+        name: "Token".into(),
+        symbol: "T".into(),
+        decimals: 8,
+        fee: Nat::from(10_000_u32),
+        is_lp_token: false,
+    };
+    let pa = TokenPairAmm {
+        pair: TokenPair::new(token(token_a), token(token_b)),
+        amm: Amm::SwapV2T3,
+    };
+    let (token0, token1) = (pa.pair.get_token0(), pa.pair.get_token1());
+    let (subaccount, dummy_canister_id) = pa.get_subaccount_and_dummy_canister_id();
+    let mut maker = MarketMaker::new_by_pair(
+        &pa.amm,
+        subaccount,
+        dummy_canister_id,
+        &info(token0),
+        &info(token1),
+        None,
+    );
+    if let MarketMaker::SwapV2(maker) = &mut maker {
+        let (reserve0, reserve1) = if token0 == token(token_a) {
+            (reserve_a, reserve_b)
+        } else {
+            (reserve_b, reserve_a)
+        };
+        maker.reserve0 = Nat::from(reserve0);
+        maker.reserve1 = Nat::from(reserve1);
+    }
+    (pa, maker)
+}
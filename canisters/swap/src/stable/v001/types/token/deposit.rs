@@ -0,0 +1,73 @@
+use super::*;
+
+/// The sweep from deposit account, which is sent to ledger but not credited yet.
+/// The same transfer sent again is deduplicated by ledger, so the deposit is never credited twice.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenDepositSweeping {
+    /// The amount arrived at self, fee excluded
+    pub amount: Nat,
+    pub fee: Nat,
+    /// The created_at_time of transfer, which makes it deduplicated by ledger
+    pub created_at_time: u64,
+    /// The length of ledger transactions before the sweep is sent, the lookup of the sweep starts here
+    #[serde(default)]
+    pub log_length: Option<Nat>,
+}
+
+impl TokenDepositSweeping {
+    /// Whether the transfer found in ledger is this sweep
+    pub fn is_swept_by(&self, from: &Account, to: &Account, transfer: (&Account, &Account, &Nat, Option<u64>)) -> bool {
+        let same =
+            |a: &Account, b: &Account| a.owner == b.owner && a.effective_subaccount() == b.effective_subaccount();
+        let (transfer_from, transfer_to, amount, created_at_time) = transfer;
+        same(from, transfer_from)
+            && same(to, transfer_to)
+            && self.amount == *amount
+            && Some(self.created_at_time) == created_at_time
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_swept_by() {
+        let owner = candid::Principal::from_text("aaaaa-aa").unwrap();
+        let self_canister = candid::Principal::management_canister();
+        let from = Account {
+            owner,
+            subaccount: Some([1; 32]),
+        };
+        let to = Account {
+            owner: self_canister,
+            subaccount: None,
+        };
+        let sweeping = TokenDepositSweeping {
+            amount: Nat::from(1_000_u64),
+            fee: Nat::from(10_u64),
+            created_at_time: 7,
+            log_length: Some(Nat::from(100_u64)),
+        };
+
+        assert!(sweeping.is_swept_by(&from, &to, (&from, &to, &Nat::from(1_000_u64), Some(7))));
+        // the default subaccount is the same as none
+        let to_default = Account {
+            owner: self_canister,
+            subaccount: Some([0; 32]),
+        };
+        assert!(sweeping.is_swept_by(&from, &to, (&from, &to_default, &Nat::from(1_000_u64), Some(7))));
+
+        // other transfers are not the sweep
+        assert!(!sweeping.is_swept_by(&from, &to, (&from, &to, &Nat::from(999_u64), Some(7))));
+        assert!(!sweeping.is_swept_by(&from, &to, (&from, &to, &Nat::from(1_000_u64), Some(8))));
+        assert!(!sweeping.is_swept_by(&from, &to, (&from, &to, &Nat::from(1_000_u64), None)));
+        assert!(!sweeping.is_swept_by(&from, &to, (&to, &from, &Nat::from(1_000_u64), Some(7))));
+        let other = Account {
+            owner,
+            subaccount: Some([2; 32]),
+        };
+        assert!(!sweeping.is_swept_by(&from, &to, (&other, &to, &Nat::from(1_000_u64), Some(7))));
+    }
+}
@@ -0,0 +1,28 @@
+use super::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenFeeChecking {
+    /// Check interval ns
+    pub checking_interval_ns: u64,
+    /// Last check time ns
+    pub last_checked_timestamp: TimestampNanos,
+}
+
+impl Default for TokenFeeChecking {
+    fn default() -> Self {
+        Self {
+            checking_interval_ns: 1_000_000 * 1000 * 3600, // Check every hour
+            last_checked_timestamp: TimestampNanos::from_inner(0),
+        }
+    }
+}
+
+impl TokenFeeChecking {
+    pub fn is_trigger(&mut self, now: TimestampNanos) -> bool {
+        if self.last_checked_timestamp.into_inner() + self.checking_interval_ns < now.into_inner() {
+            self.last_checked_timestamp = now;
+            return true;
+        }
+        false
+    }
+}
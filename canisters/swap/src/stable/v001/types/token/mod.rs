@@ -4,6 +4,12 @@ mod preset;
 
 use preset::PRESET_TOKENS;
 
+mod fee;
+pub use fee::*;
+
+mod deposit;
+pub use deposit::*;
+
 use super::*;
 
 #[derive(Serialize, Deserialize)]
@@ -13,6 +19,8 @@ pub struct Tokens {
     #[serde(skip)]
     custom_tokens: Option<HashMap<CanisterId, TokenInfo>>,
     frozen_tokens: HashSet<CanisterId>, // frozen token
+    #[serde(default)]
+    ledger_fees: HashMap<CanisterId, Nat>, // fee changed by ledger, overrides the listed fee
 }
 impl Default for Tokens {
     fn default() -> Self {
@@ -20,6 +28,7 @@ impl Default for Tokens {
             stable_custom_tokens: init_custom_tokens(),
             custom_tokens: Default::default(),
             frozen_tokens: Default::default(),
+            ledger_fees: Default::default(),
         }
     }
 }
@@ -70,9 +79,46 @@ impl Tokens {
         for (_, token) in PRESET_TOKENS.iter() {
             all_tokens.insert(token.canister_id, Cow::Borrowed(token));
         }
+        for (canister_id, fee) in &self.ledger_fees {
+            if let Some(token) = all_tokens.get_mut(canister_id) {
+                token.to_mut().fee = fee.clone();
+            }
+        }
         all_tokens
     }
 
+    pub fn get_ledger_fees(&self) -> &HashMap<CanisterId, Nat> {
+        &self.ledger_fees
+    }
+
+    fn get_listed_fee(&self, canister_id: &CanisterId) -> Option<Nat> {
+        PRESET_TOKENS
+            .get(canister_id)
+            .map(|token| token.fee.clone())
+            .or_else(|| self.stable_custom_tokens.get(canister_id).map(|token| token.fee))
+    }
+
+    /// The current fee of token, if it is different from the fee of ledger
+    pub fn changed_fee(&self, canister_id: &CanisterId, fee: &Nat) -> Option<Nat> {
+        let old = self
+            .ledger_fees
+            .get(canister_id)
+            .cloned()
+            .or_else(|| self.get_listed_fee(canister_id))?;
+        (old != *fee).then_some(old)
+    }
+
+    /// The fee of ledger is changed, return the old fee if changed
+    pub fn update_fee(&mut self, canister_id: CanisterId, fee: Nat) -> Option<Nat> {
+        let old = self.changed_fee(&canister_id, &fee)?;
+        if self.get_listed_fee(&canister_id).is_some_and(|listed| listed == fee) {
+            self.ledger_fees.remove(&canister_id);
+        } else {
+            self.ledger_fees.insert(canister_id, fee);
+        }
+        Some(old)
+    }
+
     pub fn token_alive(&self, canister_id: &CanisterId) -> Result<(), BusinessError> {
         if self.frozen_tokens.contains(canister_id) {
             return Err(BusinessError::FrozenToken(*canister_id));
@@ -93,6 +139,7 @@ impl Tokens {
     }
 
     pub fn put_custom_token(&mut self, token: TokenInfo) {
+        self.ledger_fees.remove(&token.canister_id); // the put fee is the latest
         self.stable_custom_tokens.insert(token.canister_id, token.clone());
         if let Some(custom_tokens) = self.custom_tokens.as_mut() {
             custom_tokens.insert(token.canister_id, token);
@@ -101,9 +148,44 @@ impl Tokens {
 
     pub fn remove_custom_token(&mut self, canister_id: &CanisterId) -> Option<TokenInfo> {
         let removed = self.stable_custom_tokens.remove(canister_id);
+        self.ledger_fees.remove(canister_id);
         if let Some(custom_tokens) = self.custom_tokens.as_mut() {
             custom_tokens.remove(canister_id);
         }
         removed
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ledger_fees() {
+        let icp = CanisterId::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai").unwrap();
+        let unknown = CanisterId::from_slice(&[1]);
+        let nat = |n: u64| Nat::from(n);
+        let mut tokens = Tokens::default();
+
+        // the polled fee is the same as the listed one, nothing changed
+        assert_eq!(tokens.changed_fee(&icp, &nat(10_000)), None);
+        assert_eq!(tokens.update_fee(icp, nat(10_000)), None);
+        assert!(tokens.get_ledger_fees().is_empty());
+
+        // the fee is changed by ledger, which overrides the listed fee
+        assert_eq!(tokens.update_fee(icp, nat(20_000)), Some(nat(10_000)));
+        assert_eq!(tokens.get_ledger_fees().get(&icp), Some(&nat(20_000)));
+        assert_eq!(tokens.update_fee(icp, nat(20_000)), None);
+        let all = tokens.get_all_tokens();
+        assert_eq!(all.get(&icp).unwrap().fee, nat(20_000));
+
+        // back to the listed fee, the changed fee is removed
+        assert_eq!(tokens.update_fee(icp, nat(10_000)), Some(nat(20_000)));
+        assert!(tokens.get_ledger_fees().is_empty());
+
+        // the fee of unknown token is never recorded
+        assert_eq!(tokens.update_fee(unknown, nat(1)), None);
+        assert!(tokens.get_ledger_fees().is_empty());
+    }
+}
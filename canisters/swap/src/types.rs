@@ -24,26 +24,33 @@ pub use business::*;
 
 #[allow(unused)]
 pub use ::common::archive::swap::{
-    PairCreate, PairOperation, PairRemove, PairSwapToken, QuerySwapBlockResult, SwapBlock, SwapOperation,
-    SwapTransaction, SwapV2BurnToken, SwapV2MintFeeToken, SwapV2MintToken, SwapV2Operation, SwapV2State,
-    SwapV2TransferToken,
+    PairCreate, PairFlashLoan, PairOperation, PairRemove, PairSwapToken, PmmV1Operation, PmmV1Price, PmmV1State,
+    QuerySwapBlockResult, StablePoolBurnToken, StablePoolMintToken, StablePoolOperation, StablePoolState,
+    StableSwapOperation, StableSwapState, SwapBlock, SwapOperation, SwapTransaction, SwapV2BurnToken,
+    SwapV2MintFeeToken, SwapV2MintToken, SwapV2Operation, SwapV2State, SwapV2TransferToken, SwapV3BurnToken,
+    SwapV3MintToken, SwapV3Operation, SwapV3SwapToken, WeightedOperation, WeightedState,
 };
 #[allow(unused)]
 pub use ::common::archive::token::{
-    DepositToken, GetTokenBlocksResult, QueryTokenBlockResult, TokenBlock, TokenBlockRange, TokenOperation,
-    TokenTransaction, TransferToken, WithdrawToken,
+    ApproveToken, DepositToken, GetTokenBlocksResult, QueryTokenBlockResult, TokenBlock, TokenBlockRange,
+    TokenOperation, TokenTransaction, TransferToken, WithdrawToken,
 };
 #[allow(unused)]
 pub use ::common::proto;
 #[allow(unused)]
 pub use ::common::types::{
-    Amm, AmmText, ArgWithMeta, BlockIndex, BurnFee, BusinessError, Caller, CandidBlock, CheckArgs, DoHash,
-    DummyCanisterId, EncodedBlock, GetBlocksArgs, GetBlocksError, GetEncodedBlocksResult, HashOf,
-    MAX_BLOCKS_PER_REQUEST, MarketMaker, MarketMakerView, QueryBlockResult, QueryBlocksResult, RequestArgs,
-    RequestIndex, RequestTrace, SelfCanister, SwapRatio, SwapTokenPair, SwapV2MarketMaker, TimestampNanos,
-    TokenAccount, TokenFrozenArg, TokenInfo, TokenPair, TokenPairAmm, TokenPairLiquidityAddArg,
-    TokenPairLiquidityRemoveArg, TokenPairPool, TokenPairSwapByLoanArg, TokenPairSwapExactTokensForTokensArg,
-    TokenPairSwapTokensForExactTokensArg, TransferFee, check_caller, check_meta, display_account,
+    AccountIdentifier, Amm, AmmText, ArgWithMeta, BlockIndex, BurnFee, BusinessError, Caller, CandidBlock, CheckArgs,
+    DcaOrder, DoHash, DummyCanisterId, EncodedBlock, Farm, FarmStakeArg, FlashLoan, GetBlocksArgs, GetBlocksError,
+    GetEncodedBlocksResult, HashOf, LimitOrder, MAX_BLOCKS_PER_REQUEST, MarketMaker, MarketMakerView, PmmV1MarketMaker,
+    PmmV1RStatus, ProtocolFeeRecipient, ProtocolFeesCollectArg, QueryBlockResult, QueryBlocksResult, RequestArgs,
+    RequestIndex, RequestTrace, SelfCanister, StablePoolMarketMaker, StableSwapMarketMaker, SwapRatio, SwapTokenPair,
+    SwapV2DynamicFee, SwapV2DynamicFeeArg, SwapV2MarketMaker, SwapV2Twap, SwapV3MarketMaker, SwapV3Positions,
+    SwapV3Ticks, TickRange, TimestampNanos, TokenAccount, TokenFeeUpdateArg, TokenFrozenArg, TokenInfo, TokenPair,
+    TokenPairAmm, TokenPairLiquidityAddArg, TokenPairLiquidityRemoveArg, TokenPairPool, TokenPairSwapByLoanArg,
+    TokenPairSwapExactTokensForTokensArg, TokenPairSwapSplitArg, TokenPairSwapSplitLeg,
+    TokenPairSwapTokensForExactTokensArg, TokenPool, TokenPoolAmm, TokenPoolLiquidityAddArg,
+    TokenPoolLiquidityRemoveArg, TransferFee, WeightedMarketMaker, check_caller, check_meta, check_self_canister,
+    display_account,
 };
 #[allow(unused)]
 pub use ::common::utils::pb::{from_proto_bytes, to_proto_bytes};
@@ -51,6 +58,26 @@ pub use ::common::utils::pb::{from_proto_bytes, to_proto_bytes};
 #[allow(unused)]
 pub use ic_canister_kit::common::trap;
 
+// ==================== icrc ====================
+
+#[allow(unused)]
+pub use icrc_ledger_types::{
+    icrc::{generic_metadata_value::MetadataValue, generic_value::ICRC3Value},
+    icrc1::transfer::{TransferArg, TransferError},
+    icrc2::{
+        allowance::{Allowance, AllowanceArgs},
+        approve::{ApproveArgs, ApproveError},
+        transfer_from::{TransferFromArgs, TransferFromError},
+    },
+    icrc3::{
+        archive::{GetArchivesArgs, ICRC3ArchiveInfo, QueryArchiveFn},
+        blocks::{BlockWithId, GetBlocksRequest, GetBlocksResult, ICRC3DataCertificate, SupportedBlockType},
+    },
+};
+
+#[allow(unused)]
+pub use ic_certification::HashTree;
+
 pub type AllLocks = (
     TokenBlockChainLock,
     SwapBlockChainLock,
@@ -117,3 +117,13 @@ impl From<Result<SwapBlockResponse, BusinessError>> for SwapBlockResult {
         Self(value)
     }
 }
+
+// ========================== token reconcile config ==========================
+
+#[derive(Debug, Serialize, Deserialize, CandidType)]
+pub struct TokenReconcileConfig {
+    /// The token is frozen if the deficit exceeds this ratio of internal balances
+    pub deficit_threshold: common::types::SwapRatio,
+    /// Check interval ns
+    pub checking_interval_ns: u64,
+}
@@ -0,0 +1,130 @@
+use ic_canister_kit::common::option::display_option_by;
+
+use super::*;
+
+// ========================= dca order =========================
+
+/// The max count of slices of a dca order
+pub const MAX_DCA_ORDER_SLICES: u32 = 1000;
+/// The min interval between slices
+pub const MIN_DCA_ORDER_INTERVAL_SECONDS: u64 = 60;
+/// The max interval between slices, 1 year
+pub const MAX_DCA_ORDER_INTERVAL_SECONDS: u64 = 3600 * 24 * 365;
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct DcaOrderPlaceArgs {
+    pub from: Account, // make caller, caller must be consistent with from
+
+    pub amount_in: Nat, // pay of all slices, escrowed until executed or cancelled
+    pub slices: u32,
+    pub slice_amount_out_min: Nat, // min got of each slice, price guard
+    pub path: Vec<SwapTokenPair>,
+    pub to: Account,
+    pub interval_seconds: u64,
+
+    pub memo: Option<Vec<u8>>,
+    pub created: Option<TimestampNanos>,
+}
+
+impl Display for DcaOrderPlaceArgs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "DcaOrderPlaceArgs {{ from: ({}), amount_in: {}, slices: {}, slice_amount_out_min: {}, path: [{}], to: ({}), interval_seconds: {}, memo: {}, created: {} }}",
+            display_account(&self.from),
+            self.amount_in,
+            self.slices,
+            self.slice_amount_out_min,
+            self.path.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(", "),
+            display_account(&self.to),
+            self.interval_seconds,
+            display_option_by(&self.memo, |memo| hex::encode(memo)),
+            display_option_by(&self.created, |created| created.into_inner().to_string()),
+        )
+    }
+}
+
+impl DcaOrderPlaceArgs {
+    /// The interval in nanos, all slices must be scheduled before the end of time
+    pub fn interval_ns(&self, now: TimestampNanos) -> Result<u64, BusinessError> {
+        if self.interval_seconds < MIN_DCA_ORDER_INTERVAL_SECONDS
+            || MAX_DCA_ORDER_INTERVAL_SECONDS < self.interval_seconds
+        {
+            return Err(BusinessError::Swap("INVALID_DCA_ORDER_INTERVAL".into()));
+        }
+        let interval_ns = self
+            .interval_seconds
+            .checked_mul(1_000_000_000)
+            .ok_or_else(|| BusinessError::Swap("INVALID_DCA_ORDER_INTERVAL".into()))?;
+        interval_ns
+            .checked_mul(self.slices as u64)
+            .and_then(|duration| now.into_inner().checked_add(duration))
+            .ok_or_else(|| BusinessError::Swap("INVALID_DCA_ORDER_INTERVAL".into()))?;
+        Ok(interval_ns)
+    }
+}
+
+#[derive(Debug, Deserialize, CandidType)]
+pub struct DcaOrderResult(Result<DcaOrder, BusinessError>);
+
+impl From<Result<DcaOrder, BusinessError>> for DcaOrderResult {
+    fn from(value: Result<DcaOrder, BusinessError>) -> Self {
+        Self(value)
+    }
+}
+impl From<DcaOrderResult> for Result<DcaOrder, BusinessError> {
+    fn from(value: DcaOrderResult) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn args(slices: u32, interval_seconds: u64) -> DcaOrderPlaceArgs {
+        let account = Account {
+            owner: CanisterId::from_slice(&[1]),
+            subaccount: None,
+        };
+        DcaOrderPlaceArgs {
+            from: account,
+            amount_in: Nat::from(1_000_u32),
+            slices,
+            slice_amount_out_min: Nat::from(0_u32),
+            path: vec![],
+            to: account,
+            interval_seconds,
+            memo: None,
+            created: None,
+        }
+    }
+
+    #[test]
+    fn test_dca_order_interval_ns() {
+        let now = TimestampNanos::from_inner(1_000);
+        assert_eq!(args(3, 60).interval_ns(now).unwrap(), 60_000_000_000);
+        assert_eq!(
+            args(3, MAX_DCA_ORDER_INTERVAL_SECONDS).interval_ns(now).unwrap(),
+            MAX_DCA_ORDER_INTERVAL_SECONDS * 1_000_000_000
+        );
+
+        // out of bounds, no overflow
+        assert!(args(3, MIN_DCA_ORDER_INTERVAL_SECONDS - 1).interval_ns(now).is_err());
+        assert!(args(3, MAX_DCA_ORDER_INTERVAL_SECONDS + 1).interval_ns(now).is_err());
+        assert!(args(3, u64::MAX).interval_ns(now).is_err());
+
+        // the last slice must be scheduled in time
+        assert!(
+            args(MAX_DCA_ORDER_SLICES, MAX_DCA_ORDER_INTERVAL_SECONDS)
+                .interval_ns(now)
+                .is_err()
+        );
+        assert!(
+            args(3, 60)
+                .interval_ns(TimestampNanos::from_inner(u64::MAX - 1))
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use ::common::types::TimestampNanos;
+use ic_canister_kit::common::option::display_option_by;
+
+use super::*;
+
+// ========================= farm =========================
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct FarmCreateArgs {
+    pub from: Account, // make caller, caller must be consistent with from, pays all rewards
+
+    pub swap_pair: SwapTokenPair, // lp of pool is staked
+    pub reward_token: CanisterId,
+    pub reward_per_second: Nat,
+    pub start: TimestampNanos,
+    pub end: TimestampNanos,
+
+    pub memo: Option<Vec<u8>>,
+    pub created: Option<TimestampNanos>,
+}
+
+impl Display for FarmCreateArgs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "FarmCreateArgs {{ from: ({}), swap_pair: {}, reward_token: [{}], reward_per_second: {}, start: {}, end: {}, memo: {}, created: {} }}",
+            display_account(&self.from),
+            self.swap_pair,
+            self.reward_token.to_text(),
+            self.reward_per_second,
+            self.start.into_inner(),
+            self.end.into_inner(),
+            display_option_by(&self.memo, |memo| hex::encode(memo)),
+            display_option_by(&self.created, |created| created.into_inner().to_string()),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct FarmStakeArgs {
+    pub farm: u64,
+    pub from: Account, // make caller, caller must be consistent with from
+    pub amount: Nat,   // lp to stake or unstake
+
+    pub memo: Option<Vec<u8>>,
+    pub created: Option<TimestampNanos>,
+}
+
+impl Display for FarmStakeArgs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "FarmStakeArgs {{ farm: {}, from: ({}), amount: {}, memo: {}, created: {} }}",
+            self.farm,
+            display_account(&self.from),
+            self.amount,
+            display_option_by(&self.memo, |memo| hex::encode(memo)),
+            display_option_by(&self.created, |created| created.into_inner().to_string()),
+        )
+    }
+}
+
+#[derive(Debug, Deserialize, CandidType)]
+pub struct FarmResult(Result<Farm, BusinessError>);
+
+impl From<Result<Farm, BusinessError>> for FarmResult {
+    fn from(value: Result<Farm, BusinessError>) -> Self {
+        Self(value)
+    }
+}
+impl From<FarmResult> for Result<Farm, BusinessError> {
+    fn from(value: FarmResult) -> Self {
+        value.0
+    }
+}
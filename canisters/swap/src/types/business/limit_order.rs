@@ -0,0 +1,51 @@
+use ::common::types::TimestampNanos;
+use ic_canister_kit::common::option::display_option_by;
+
+use super::*;
+
+// ========================= limit order =========================
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct LimitOrderPlaceArgs {
+    pub from: Account, // make caller, caller must be consistent with from
+
+    pub amount_in: Nat,      // pay, escrowed until filled or cancelled
+    pub amount_out_min: Nat, // target price is amount_out_min / amount_in
+    pub path: Vec<SwapTokenPair>,
+    pub to: Account,
+    pub expiration: Deadline, // returned to from if not filled before expiration
+
+    pub memo: Option<Vec<u8>>,
+    pub created: Option<TimestampNanos>,
+}
+
+impl Display for LimitOrderPlaceArgs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "LimitOrderPlaceArgs {{ from: ({}), amount_in: {}, amount_out_min: {}, path: [{}], to: ({}), expiration: {}, memo: {}, created: {} }}",
+            display_account(&self.from),
+            self.amount_in,
+            self.amount_out_min,
+            self.path.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(", "),
+            display_account(&self.to),
+            self.expiration.as_ref(),
+            display_option_by(&self.memo, |memo| hex::encode(memo)),
+            display_option_by(&self.created, |created| created.into_inner().to_string()),
+        )
+    }
+}
+
+#[derive(Debug, Deserialize, CandidType)]
+pub struct LimitOrderResult(Result<LimitOrder, BusinessError>);
+
+impl From<Result<LimitOrder, BusinessError>> for LimitOrderResult {
+    fn from(value: Result<LimitOrder, BusinessError>) -> Self {
+        Self(value)
+    }
+}
+impl From<LimitOrderResult> for Result<LimitOrder, BusinessError> {
+    fn from(value: LimitOrderResult) -> Self {
+        value.0
+    }
+}
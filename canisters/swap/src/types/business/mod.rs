@@ -15,6 +15,31 @@ mod pair;
 #[allow(unused)]
 pub use pair::*;
 
+// pool
+mod pool;
+#[allow(unused)]
+pub use pool::*;
+
+// limit order
+mod limit_order;
+#[allow(unused)]
+pub use limit_order::*;
+
+// dca order
+mod dca_order;
+#[allow(unused)]
+pub use dca_order::*;
+
+// protocol fees
+mod protocol_fees;
+#[allow(unused)]
+pub use protocol_fees::*;
+
+// farm
+mod farm;
+#[allow(unused)]
+pub use farm::*;
+
 #[derive(Debug, Deserialize, CandidType)]
 pub struct BusinessResult(Result<(), BusinessError>);
 
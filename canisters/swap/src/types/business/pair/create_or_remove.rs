@@ -4,6 +4,10 @@ use super::super::*;
 #[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
 pub struct TokenPairCreateOrRemoveArgs {
     pub pool: TokenPairPool,
+    /// weights of pool.token0 and pool.token1, only for weighted pool
+    pub weights: Option<(u32, u32)>,
+    /// initial sqrt price of pool.token1 in pool.token0 as Q64.96, only for swap v3 pool
+    pub sqrt_price_x96: Option<Nat>,
 
     pub memo: Option<Vec<u8>>,
     pub created: Option<TimestampNanos>,
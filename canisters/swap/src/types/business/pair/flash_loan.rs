@@ -0,0 +1,45 @@
+use super::super::*;
+
+// ========================= flash loan =========================
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenPairFlashLoanArgs {
+    pub from: Account, // make caller, caller must be consistent with from, the owner is called back
+
+    pub swap_pair: SwapTokenPair,
+    pub token: CanisterId, // lent token, must be one of the pair, only swap v2 pools lend
+    pub amount: Nat,
+    pub callback: String, // update method of from.owner, called with TokenPairFlashLoanCallbackArgs
+    pub data: Option<Vec<u8>>, // passed to callback
+
+    pub memo: Option<Vec<u8>>,
+    pub created: Option<TimestampNanos>,
+}
+
+/// The tokens are lent to loan.borrower without collateral, repayment and fee must be left in it after callback.
+/// Otherwise the loan is defaulted, what is left in it goes to the pool.
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenPairFlashLoanCallbackArgs {
+    pub loan: FlashLoan,
+    pub data: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Deserialize, CandidType)]
+pub struct TokenPairFlashLoanResult(Result<FlashLoan, BusinessError>);
+
+impl From<Result<FlashLoan, BusinessError>> for TokenPairFlashLoanResult {
+    fn from(value: Result<FlashLoan, BusinessError>) -> Self {
+        Self(value)
+    }
+}
+impl From<TokenPairFlashLoanResult> for Result<FlashLoan, BusinessError> {
+    fn from(value: TokenPairFlashLoanResult) -> Self {
+        value.0
+    }
+}
+
+impl TokenPairArg for FlashLoan {
+    fn get_pa(&self) -> &TokenPairAmm {
+        &self.pa
+    }
+}
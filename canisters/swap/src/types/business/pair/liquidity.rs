@@ -9,6 +9,7 @@ pub struct TokenPairLiquidityAddArgs {
     pub swap_pair: SwapTokenPair,
     pub amount_desired: (Nat, Nat),
     pub amount_min: (Nat, Nat),
+    pub tick_range: Option<TickRange>, // position range, required by swap v3
     pub to: Account,
     pub deadline: Option<Deadline>,
 
@@ -72,6 +73,8 @@ pub struct TokenPairLiquidityRemoveArgs {
     pub swap_pair: SwapTokenPair,
     pub liquidity_without_fee: Nat, // Removing liquidity will directly destroy a fee, restricting users from witch attacks
     pub amount_min: (Nat, Nat),
+    pub tick_range: Option<TickRange>,  // position range, required by swap v3
+    pub exit_token: Option<CanisterId>, // single-asset exit, supported by weighted pool
     pub to: Account,
     pub deadline: Option<Deadline>,
 
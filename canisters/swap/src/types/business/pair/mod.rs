@@ -6,6 +6,9 @@ pub use create_or_remove::*;
 mod liquidity;
 pub use liquidity::*;
 
+mod flash_loan;
+pub use flash_loan::*;
+
 mod swap;
 pub use swap::*;
 
@@ -12,6 +12,15 @@ pub use pay_exact_by_loan::*;
 mod pay_exact_with_deposit;
 pub use pay_exact_with_deposit::*;
 
+mod pay_exact_split;
+pub use pay_exact_split::*;
+
+mod quote;
+pub use quote::*;
+
+mod router;
+pub use router::*;
+
 // ================================== general ==================================
 
 #[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
@@ -70,5 +79,20 @@ pub fn check_path(path: &[SwapTokenPair]) -> Result<(), BusinessError> {
             i += 1;
         }
     }
+    // A multi-asset pool can only be passed once in the path
+    let mut pools = std::collections::HashSet::new();
+    for pair in path {
+        let amm: Amm = pair.amm.as_ref().try_into()?; // parse amm
+        if !amm.is_multi_asset() {
+            continue;
+        }
+        let pa = TokenPairAmm {
+            pair: TokenPair::new(pair.token.0, pair.token.1),
+            amm,
+        };
+        if with_state(|s| s.business_token_pool_find(&pa)).is_some_and(|pool| !pools.insert(pool)) {
+            return Err(BusinessError::Swap("INVALID_PATH".into()));
+        }
+    }
     Ok(())
 }
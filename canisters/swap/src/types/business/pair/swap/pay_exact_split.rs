@@ -0,0 +1,122 @@
+use ic_canister_kit::common::option::display_option_by;
+
+use super::*;
+
+// ========================= swap by split paths =========================
+
+/// The max count of paths in a split swap
+pub const MAX_SPLIT_PATHS: usize = 8;
+
+/// One of the split paths, amount in is split by weights
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenPairSwapSplitPath {
+    pub weight: u32,
+    pub path: Vec<SwapTokenPair>,
+}
+
+/// Split amount in by weights, the last path takes the rest
+pub fn split_amount_in(amount_in: &Nat, weights: &[u32]) -> Vec<Nat> {
+    let total_weight: u64 = weights.iter().map(|&w| w as u64).sum();
+    let mut rest = amount_in.clone();
+    let mut amounts = Vec::with_capacity(weights.len());
+    for (i, &weight) in weights.iter().enumerate() {
+        let amount = if i < weights.len() - 1 {
+            amount_in.clone() * weight / total_weight
+        } else {
+            rest.clone()
+        };
+        rest -= amount.clone();
+        amounts.push(amount);
+    }
+    amounts
+}
+
+/// The got of all paths, which must be no less than amount out min
+pub fn check_split_amount_out(amounts_out: &[Nat], amount_out_min: &Nat) -> Result<Nat, BusinessError> {
+    let amount_out = amounts_out
+        .iter()
+        .fold(Nat::from(0_u32), |sum, amount| sum + amount.clone());
+    if amount_out < *amount_out_min {
+        return Err(BusinessError::Swap("INSUFFICIENT_OUTPUT_AMOUNT".into()));
+    }
+    Ok(amount_out)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenPairSwapSplitArgs {
+    pub from: Account, // make caller, caller must be consistent with from
+
+    pub amount_in: Nat,      // pay, split by weights
+    pub amount_out_min: Nat, // min got of all paths
+    pub paths: Vec<TokenPairSwapSplitPath>,
+    pub to: Account,
+    pub deadline: Option<Deadline>,
+
+    pub memo: Option<Vec<u8>>,
+    pub created: Option<TimestampNanos>,
+}
+
+impl SelfCanisterArg for TokenPairSwapSplitArg {
+    fn get_self_canister(&self) -> SelfCanister {
+        self.self_canister
+    }
+}
+
+impl Display for TokenPairSwapSplitArgs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "TokenPairSwapSplitArgs {{ from: ({}), amount_in: {}, amount_out_min: {}, paths: [{}], to: ({}), deadline: {}, memo: {}, created: {} }}",
+            display_account(&self.from),
+            self.amount_in,
+            self.amount_out_min,
+            self.paths
+                .iter()
+                .map(|p| format!(
+                    "{}:[{}]",
+                    p.weight,
+                    p.path.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(", ")
+                ))
+                .collect::<Vec<_>>()
+                .join(", "),
+            display_account(&self.to),
+            display_option_by(&self.deadline, |deadline| deadline.as_ref().to_string()),
+            display_option_by(&self.memo, |memo| hex::encode(memo)),
+            display_option_by(&self.created, |created| created.into_inner().to_string()),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenPairSwapSplitSuccess {
+    pub amount_out: Nat,                       // got of all paths
+    pub legs: Vec<TokenPairSwapTokensSuccess>, // in order of paths
+}
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenPairSwapSplitSuccessView {
+    pub amount_out: String,
+    pub legs: Vec<TokenPairSwapTokensSuccessView>,
+}
+impl From<&TokenPairSwapSplitSuccess> for TokenPairSwapSplitSuccessView {
+    fn from(value: &TokenPairSwapSplitSuccess) -> Self {
+        Self {
+            amount_out: value.amount_out.to_string(),
+            legs: value.legs.iter().map(|l| l.into()).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, CandidType, Clone)]
+pub struct TokenPairSwapSplitResult(Result<TokenPairSwapSplitSuccess, BusinessError>);
+
+impl From<Result<TokenPairSwapSplitSuccess, BusinessError>> for TokenPairSwapSplitResult {
+    fn from(value: Result<TokenPairSwapSplitSuccess, BusinessError>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<TokenPairSwapSplitResult> for Result<TokenPairSwapSplitSuccess, BusinessError> {
+    fn from(value: TokenPairSwapSplitResult) -> Self {
+        value.0
+    }
+}
@@ -0,0 +1,35 @@
+use super::*;
+
+// ========================= swap quote =========================
+
+/// One hop of the swap path
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenPairSwapQuoteHop {
+    pub pool: SwapTokenPair,
+    pub amount_in: Nat,       // pay of this hop
+    pub amount_out: Nat,      // got of this hop
+    pub fee: Nat,             // fee charged, in token in
+    pub reserves: (Nat, Nat), // reserves of token in and token out after swap
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenPairSwapQuote {
+    pub amounts: Vec<Nat>, // same as the amounts of swap success
+    pub hops: Vec<TokenPairSwapQuoteHop>,
+    pub price_impact: f64, // relative to the mid price without fee, 0.01 means 1%
+}
+
+#[derive(Debug, Deserialize, CandidType, Clone)]
+pub struct TokenPairSwapQuoteResult(Result<TokenPairSwapQuote, BusinessError>);
+
+impl From<Result<TokenPairSwapQuote, BusinessError>> for TokenPairSwapQuoteResult {
+    fn from(value: Result<TokenPairSwapQuote, BusinessError>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<TokenPairSwapQuoteResult> for Result<TokenPairSwapQuote, BusinessError> {
+    fn from(value: TokenPairSwapQuoteResult) -> Self {
+        value.0
+    }
+}
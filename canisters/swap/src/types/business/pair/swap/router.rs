@@ -0,0 +1,67 @@
+use ic_canister_kit::common::option::display_option_by;
+
+use super::*;
+
+// ========================= router =========================
+
+/// The hard limit of hops, the searching grows exponentially with hops
+pub const ROUTER_MAX_HOPS_LIMIT: u8 = 4;
+
+/// The best path found by router
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenPairRoute {
+    pub path: Vec<SwapTokenPair>,
+    pub amounts: Vec<Nat>, // same as the amounts of swap success
+}
+
+#[derive(Debug, Deserialize, CandidType, Clone)]
+pub struct TokenPairRouteResult(Result<TokenPairRoute, BusinessError>);
+
+impl From<Result<TokenPairRoute, BusinessError>> for TokenPairRouteResult {
+    fn from(value: Result<TokenPairRoute, BusinessError>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<TokenPairRouteResult> for Result<TokenPairRoute, BusinessError> {
+    fn from(value: TokenPairRouteResult) -> Self {
+        value.0
+    }
+}
+
+// ========================= swap by router =========================
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenPairRouteSwapArgs {
+    pub from: Account, // make caller, caller must be consistent with from
+
+    pub token_in: CanisterId,
+    pub token_out: CanisterId,
+    pub amount_in: Nat,      // pay
+    pub amount_out_min: Nat, // min got
+    pub max_hops: Option<u8>,
+    pub to: Account,
+    pub deadline: Option<Deadline>,
+
+    pub memo: Option<Vec<u8>>,
+    pub created: Option<TimestampNanos>,
+}
+
+impl Display for TokenPairRouteSwapArgs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "TokenPairRouteSwapArgs {{ from: ({}), token_in: [{}], token_out: [{}], amount_in: {}, amount_out_min: {}, max_hops: {}, to: ({}), deadline: {}, memo: {}, created: {} }}",
+            display_account(&self.from),
+            self.token_in.to_text(),
+            self.token_out.to_text(),
+            self.amount_in,
+            self.amount_out_min,
+            display_option_by(&self.max_hops, |max_hops| max_hops.to_string()),
+            display_account(&self.to),
+            display_option_by(&self.deadline, |deadline| deadline.as_ref().to_string()),
+            display_option_by(&self.memo, |memo| hex::encode(memo)),
+            display_option_by(&self.created, |created| created.into_inner().to_string()),
+        )
+    }
+}
@@ -0,0 +1,138 @@
+use super::*;
+
+// ========================= pool create or remove =========================
+
+// create or remove multi-asset pool
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenPoolCreateOrRemoveArgs {
+    pub tokens: Vec<CanisterId>,
+    pub amm: AmmText,
+
+    pub memo: Option<Vec<u8>>,
+    pub created: Option<TimestampNanos>,
+}
+
+// ========================= pool liquidity add =========================
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenPoolLiquidityAddArgs {
+    pub from: Account, // make caller, caller must be consistent with from
+
+    pub tokens: Vec<CanisterId>,
+    pub amm: AmmText,
+    pub amounts_desired: Vec<Nat>, // in order of tokens
+    pub liquidity_min: Nat,
+    pub to: Account,
+    pub deadline: Option<Deadline>,
+
+    pub memo: Option<Vec<u8>>,
+    pub created: Option<TimestampNanos>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenPoolLiquidityAddSuccess {
+    pub amounts: Vec<Nat>, // in order of pool tokens
+    pub liquidity: Nat,
+}
+
+#[derive(Debug, Deserialize, CandidType, Clone)]
+pub struct TokenPoolLiquidityAddResult(Result<TokenPoolLiquidityAddSuccess, BusinessError>);
+
+impl From<Result<TokenPoolLiquidityAddSuccess, BusinessError>> for TokenPoolLiquidityAddResult {
+    fn from(value: Result<TokenPoolLiquidityAddSuccess, BusinessError>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<TokenPoolLiquidityAddResult> for Result<TokenPoolLiquidityAddSuccess, BusinessError> {
+    fn from(value: TokenPoolLiquidityAddResult) -> Self {
+        value.0
+    }
+}
+
+impl SelfCanisterArg for TokenPoolLiquidityAddArg {
+    fn get_self_canister(&self) -> SelfCanister {
+        self.self_canister
+    }
+}
+
+impl TokenPairArg for TokenPoolLiquidityAddArg {
+    fn get_pa(&self) -> &TokenPairAmm {
+        &self.pa
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenPoolLiquidityAddSuccessView {
+    pub amounts: Vec<String>,
+    pub liquidity: String,
+}
+impl From<&TokenPoolLiquidityAddSuccess> for TokenPoolLiquidityAddSuccessView {
+    fn from(value: &TokenPoolLiquidityAddSuccess) -> Self {
+        Self {
+            amounts: value.amounts.iter().map(|a| a.to_string()).collect(),
+            liquidity: value.liquidity.to_string(),
+        }
+    }
+}
+
+// ========================= pool liquidity remove =========================
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenPoolLiquidityRemoveArgs {
+    pub from: Account, // make caller, caller must be consistent with from
+
+    pub tokens: Vec<CanisterId>,
+    pub amm: AmmText,
+    pub liquidity_without_fee: Nat, // Removing liquidity will directly destroy a fee, restricting users from witch attacks
+    pub amounts_min: Vec<Nat>,      // in order of tokens
+    pub to: Account,
+    pub deadline: Option<Deadline>,
+
+    pub memo: Option<Vec<u8>>,
+    pub created: Option<TimestampNanos>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenPoolLiquidityRemoveSuccess {
+    pub amounts: Vec<Nat>, // in order of pool tokens
+}
+
+#[derive(Debug, Deserialize, CandidType, Clone)]
+pub struct TokenPoolLiquidityRemoveResult(Result<TokenPoolLiquidityRemoveSuccess, BusinessError>);
+
+impl From<Result<TokenPoolLiquidityRemoveSuccess, BusinessError>> for TokenPoolLiquidityRemoveResult {
+    fn from(value: Result<TokenPoolLiquidityRemoveSuccess, BusinessError>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<TokenPoolLiquidityRemoveResult> for Result<TokenPoolLiquidityRemoveSuccess, BusinessError> {
+    fn from(value: TokenPoolLiquidityRemoveResult) -> Self {
+        value.0
+    }
+}
+
+impl SelfCanisterArg for TokenPoolLiquidityRemoveArg {
+    fn get_self_canister(&self) -> SelfCanister {
+        self.self_canister
+    }
+}
+
+impl TokenPairArg for TokenPoolLiquidityRemoveArg {
+    fn get_pa(&self) -> &TokenPairAmm {
+        &self.pa
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenPoolLiquidityRemoveSuccessView {
+    pub amounts: Vec<String>,
+}
+impl From<&TokenPoolLiquidityRemoveSuccess> for TokenPoolLiquidityRemoveSuccessView {
+    fn from(value: &TokenPoolLiquidityRemoveSuccess) -> Self {
+        Self {
+            amounts: value.amounts.iter().map(|a| a.to_string()).collect(),
+        }
+    }
+}
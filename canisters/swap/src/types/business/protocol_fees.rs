@@ -0,0 +1,168 @@
+use ic_canister_kit::common::option::display_option_by;
+
+use super::*;
+
+// ========================= protocol fees =========================
+
+/// The max count of recipients of protocol fees
+pub const MAX_PROTOCOL_FEE_RECIPIENTS: usize = 16;
+
+/// Split the amount by weights of recipients, rounded down, the last recipient takes the remainder
+pub fn split_protocol_fee(amount: &Nat, recipients: &[ProtocolFeeRecipient]) -> Vec<Nat> {
+    let total = Nat::from(recipients.iter().map(|r| r.weight as u64).sum::<u64>());
+    let mut remain = amount.clone();
+    let mut shares = Vec::with_capacity(recipients.len());
+    for (i, recipient) in recipients.iter().enumerate() {
+        let share = if i + 1 == recipients.len() {
+            remain.clone()
+        } else {
+            amount.clone() * Nat::from(recipient.weight) / total.clone()
+        };
+        remain -= share.clone();
+        shares.push(share);
+    }
+    shares
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct ProtocolFeesCollectArgs {
+    pub pools: Vec<SwapTokenPair>, // burn lp of swap fee to, and split the pool tokens
+    pub tokens: Vec<CanisterId>,   // split the transfer fees received by token fee to
+
+    pub memo: Option<Vec<u8>>,
+    pub created: Option<TimestampNanos>,
+}
+
+impl Display for ProtocolFeesCollectArgs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ProtocolFeesCollectArgs {{ pools: [{}], tokens: [{}], memo: {}, created: {} }}",
+            self.pools.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(", "),
+            self.tokens.iter().map(|t| t.to_text()).collect::<Vec<_>>().join(", "),
+            display_option_by(&self.memo, |memo| hex::encode(memo)),
+            display_option_by(&self.created, |created| created.into_inner().to_string()),
+        )
+    }
+}
+
+impl SelfCanisterArg for ProtocolFeesCollectArg {
+    fn get_self_canister(&self) -> SelfCanister {
+        self.self_canister
+    }
+}
+
+/// lp of swap fee to burned from a pool
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct ProtocolFeeBurned {
+    pub pa: TokenPairAmm,
+    pub liquidity: Nat,
+    pub amount: (Nat, Nat), // token0 and token1 got by swap fee to
+}
+
+/// fee token transferred to one of recipients
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct ProtocolFeeSplit {
+    pub token: CanisterId,
+    pub from: Account,
+    pub to: Account,
+    pub amount: Nat,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct ProtocolFeesCollectSuccess {
+    pub burned: Vec<ProtocolFeeBurned>,
+    pub split: Vec<ProtocolFeeSplit>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct ProtocolFeesCollectSuccessView {
+    pub burned: Vec<(String, String, (String, String))>,
+    pub split: Vec<(String, String, String, String)>,
+}
+impl From<&ProtocolFeesCollectSuccess> for ProtocolFeesCollectSuccessView {
+    fn from(value: &ProtocolFeesCollectSuccess) -> Self {
+        Self {
+            burned: value
+                .burned
+                .iter()
+                .map(|b| {
+                    (
+                        b.pa.to_string(),
+                        b.liquidity.to_string(),
+                        (b.amount.0.to_string(), b.amount.1.to_string()),
+                    )
+                })
+                .collect(),
+            split: value
+                .split
+                .iter()
+                .map(|s| {
+                    (
+                        s.token.to_text(),
+                        display_account(&s.from),
+                        display_account(&s.to),
+                        s.amount.to_string(),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, CandidType, Clone)]
+pub struct ProtocolFeesCollectResult(Result<ProtocolFeesCollectSuccess, BusinessError>);
+
+impl From<Result<ProtocolFeesCollectSuccess, BusinessError>> for ProtocolFeesCollectResult {
+    fn from(value: Result<ProtocolFeesCollectSuccess, BusinessError>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<ProtocolFeesCollectResult> for Result<ProtocolFeesCollectSuccess, BusinessError> {
+    fn from(value: ProtocolFeesCollectResult) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recipients(weights: &[u32]) -> Vec<ProtocolFeeRecipient> {
+        weights
+            .iter()
+            .enumerate()
+            .map(|(i, &weight)| ProtocolFeeRecipient {
+                account: Account {
+                    owner: CanisterId::from_slice(&[i as u8]),
+                    subaccount: None,
+                },
+                weight,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_split_protocol_fee() {
+        let nat = |n: u64| Nat::from(n);
+
+        // split by ratio of weights
+        assert_eq!(
+            split_protocol_fee(&nat(1_000), &recipients(&[3, 1])),
+            vec![nat(750), nat(250)]
+        );
+        assert_eq!(split_protocol_fee(&nat(1_000), &recipients(&[7])), vec![nat(1_000)]);
+
+        // rounded down, the last recipient takes the remainder, nothing is lost
+        let shares = split_protocol_fee(&nat(1_001), &recipients(&[1, 1, 1]));
+        assert_eq!(shares, vec![nat(333), nat(333), nat(335)]);
+        assert_eq!(shares.into_iter().fold(nat(0), |sum, share| sum + share), nat(1_001));
+
+        // dust is all given to the last one
+        assert_eq!(
+            split_protocol_fee(&nat(2), &recipients(&[1, 1, 1])),
+            vec![nat(0), nat(0), nat(2)]
+        );
+        assert_eq!(split_protocol_fee(&nat(0), &recipients(&[1, 2])), vec![nat(0), nat(0)]);
+    }
+}
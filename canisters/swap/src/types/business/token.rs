@@ -65,6 +65,18 @@ impl Display for TokenDepositArgs {
     }
 }
 
+/// The subaccount of swap canister, which receives the tokens transferred by icrc1 for the account to deposit
+pub fn token_deposit_account(self_canister_id: CanisterId, account: &Account) -> Account {
+    let mut data = b"deposit".to_vec();
+    data.push(account.owner.as_slice().len() as u8);
+    data.extend(account.owner.as_slice());
+    data.extend(account.effective_subaccount());
+    Account {
+        owner: self_canister_id,
+        subaccount: Some(::common::utils::hash::hash_sha256(&data)),
+    }
+}
+
 // withdraw
 #[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
 pub struct TokenWithdrawArgs {
@@ -94,6 +106,38 @@ impl Display for TokenWithdrawArgs {
     }
 }
 
+/// The legacy ledger of ICP, which supports transfer to account identifier
+pub const ICP_LEDGER_CANISTER_ID: &str = "ryjl3-tyaaa-aaaaa-aaaba-cai";
+
+// withdraw to account identifier
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenWithdrawToAccountIdArgs {
+    pub token: CanisterId, // must be ICP ledger
+    pub from: Account,     // make caller, caller must be consistent with from
+    pub withdraw_amount_without_fee: candid::Nat,
+    pub to: AccountIdentifier,
+    pub fee: Option<candid::Nat>,
+
+    pub memo: Option<Vec<u8>>,
+    pub created: Option<TimestampNanos>,
+}
+
+impl Display for TokenWithdrawToAccountIdArgs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "TokenWithdrawToAccountIdArgs {{ token: [{}], from: ({}), withdraw_amount_without_fee: {}, to: {}, fee: {}, memo: {}, created: {} }}",
+            self.token.to_text(),
+            display_account(&self.from),
+            self.withdraw_amount_without_fee,
+            self.to,
+            display_option(&self.fee),
+            display_option_by(&self.memo, |memo| hex::encode(memo)),
+            display_option_by(&self.created, |created| created.into_inner().to_string()),
+        )
+    }
+}
+
 // inner transfer
 
 #[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
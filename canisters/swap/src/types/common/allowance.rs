@@ -0,0 +1,191 @@
+use candid::Nat;
+use common::types::{ArgWithMeta, BlockIndex, BusinessError, Caller, TimestampNanos, TokenAccount};
+use ic_canister_kit::{
+    identity::{caller, self_canister_id},
+    types::CanisterId,
+};
+use icrc_ledger_types::icrc1::account::Account;
+
+use crate::types::{Business, LockResult, with_mut_state, with_state};
+
+/// The allowance spent by the spender before calling on behalf of the owner
+pub struct AllowanceSpending {
+    from: Account,
+    spender: Account,
+    spent: Vec<(CanisterId, Nat, Option<BlockIndex>)>, // token, amount and the approve block of the rest allowance
+}
+
+/// The caller who is not the owner must be an approved spender, by the default subaccount of caller.
+/// The amounts moved are spent at once, the fees paid by owner must be counted in,
+/// and the swap canister does the business on behalf of the owner.
+/// The spending is written to token block chain as the approve of the rest allowance.
+pub fn spend_allowance(
+    from: &Account,
+    spends: Vec<(CanisterId, Nat)>,
+) -> Result<Option<AllowanceSpending>, BusinessError> {
+    let caller = caller();
+    if caller == from.owner || caller == self_canister_id() {
+        return Ok(None);
+    }
+    let spender = Account {
+        owner: caller,
+        subaccount: None,
+    };
+    let now = TimestampNanos::now();
+    let approves = with_state(|s| {
+        // ! caller without any allowance is not owner
+        if spends.is_empty()
+            || spends
+                .iter()
+                .all(|(token, _)| s.business_token_allowance_get(token, from, &spender, now).allowance == 0_u8)
+        {
+            return Err(BusinessError::NotOwner(from.owner));
+        }
+        spends
+            .iter()
+            .map(|(token, amount)| s.business_token_allowance_spending(token, from, &spender, amount, now))
+            .collect::<Result<Vec<_>, _>>()
+    })?;
+
+    // lock without retry, the spender is told to try again
+    let required = spends
+        .iter()
+        .map(|(token, _)| TokenAccount::new(*token, *from))
+        .collect();
+    let locks = match crate::business::lock_token_block_chain_and_token_balances(vec![], required, 0)? {
+        LockResult::Locked(locks) => locks,
+        LockResult::Retry(_) => unreachable!(),
+    };
+    let caller = Caller::get();
+    let spent = with_mut_state(|s| {
+        spends
+            .into_iter()
+            .zip(approves)
+            .map(|((token, amount), approve)| {
+                let rest = approve.amount.clone();
+                let block = s.business_token_approve(&locks, ArgWithMeta::simple(now, caller, approve))?;
+                Ok((token, amount, (rest != 0_u8).then_some(block)))
+            })
+            .collect::<Result<Vec<_>, BusinessError>>()
+    })?;
+    Ok(Some(AllowanceSpending {
+        from: *from,
+        spender,
+        spent,
+    }))
+}
+
+/// The failed call to self, which is never run by callee if the reject code tells so.
+/// The call never run is a known failure, so the allowance spent for it is given back by `settle`.
+pub fn self_call_failed(err: ic_cdk::call::CallFailed) -> BusinessError {
+    use ic_cdk::call::{CallFailed, RejectCode};
+    let never_run = match &err {
+        CallFailed::InsufficientLiquidCycleBalance(_) | CallFailed::CallPerformFailed(_) => true,
+        // ! the methods of swap canister reject only by guard, before anything is done
+        CallFailed::CallRejected(rejected) => matches!(
+            rejected.reject_code(),
+            Ok(RejectCode::SysFatal
+                | RejectCode::SysTransient
+                | RejectCode::DestinationInvalid
+                | RejectCode::CanisterReject)
+        ),
+    };
+    if never_run {
+        return BusinessError::system_error(format!("call is never run: {err}"));
+    }
+    err.into()
+}
+
+impl AllowanceSpending {
+    /// Give back the allowance not used, which is in order of spent.
+    /// Only the allowance unchanged since spending is given back, by the approve written to token block chain.
+    /// ! If the call failed after the callee ran, it is unknown whether the business is done, nothing is given back.
+    /// ! If the token block chain or the balance of owner is locked now, nothing is given back.
+    pub fn settle<T>(
+        self,
+        result: Result<T, BusinessError>,
+        used: impl FnOnce(&T) -> Vec<Nat>,
+    ) -> Result<T, BusinessError> {
+        let used = match &result {
+            Ok(success) => used(success),
+            Err(BusinessError::CallCanisterError(_)) => return result,
+            Err(_) => vec![],
+        };
+        let unused = self
+            .spent
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, (token, amount, block))| {
+                let block = block?; // ! the removed allowance is never given back
+                let used = used.get(i).cloned().unwrap_or_default();
+                (used < amount).then(|| (token, amount - used, block))
+            })
+            .collect::<Vec<_>>();
+        if unused.is_empty() {
+            return result;
+        }
+
+        let required = unused
+            .iter()
+            .map(|(token, _, _)| TokenAccount::new(*token, self.from))
+            .collect();
+        let Ok(LockResult::Locked(locks)) =
+            crate::business::lock_token_block_chain_and_token_balances(vec![], required, 0)
+        else {
+            return result;
+        };
+        let now = TimestampNanos::now();
+        let caller = Caller::get();
+        with_mut_state(|s| {
+            for (token, unused, block) in unused {
+                let Some(approve) =
+                    s.business_token_allowance_restoring(&token, &self.from, &self.spender, &unused, block, now)
+                else {
+                    continue; // revoked, expired or approved again by owner during the call
+                };
+                if let Err(err) = s.business_token_approve(&locks, ArgWithMeta::simple(now, caller, approve)) {
+                    ic_cdk::println!("restore allowance failed: {err:?}");
+                }
+            }
+        });
+        drop(locks);
+
+        // Asynchronously triggers synchronization tasks
+        crate::business::config::push::inner_push_blocks(true, false);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ic_cdk::call::{CallFailed, CallPerformFailed, CallRejected, RejectCode};
+
+    fn rejected(code: RejectCode) -> CallFailed {
+        CallFailed::CallRejected(CallRejected::with_rejection(code as u32, "rejected".into()))
+    }
+
+    #[test]
+    fn test_self_call_failed() {
+        // never run, the allowance is given back
+        for err in [
+            CallFailed::CallPerformFailed(CallPerformFailed),
+            rejected(RejectCode::SysFatal),
+            rejected(RejectCode::SysTransient),
+            rejected(RejectCode::DestinationInvalid),
+            rejected(RejectCode::CanisterReject),
+        ] {
+            assert!(matches!(self_call_failed(err), BusinessError::SystemError(_)));
+        }
+
+        // the callee may have done the business
+        for err in [
+            rejected(RejectCode::CanisterError),
+            rejected(RejectCode::SysUnknown),
+            CallFailed::CallRejected(CallRejected::with_rejection(100, "unknown".into())),
+        ] {
+            assert!(matches!(self_call_failed(err), BusinessError::CallCanisterError(_)));
+        }
+    }
+}
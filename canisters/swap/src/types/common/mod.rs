@@ -15,6 +15,11 @@ mod pool;
 #[allow(unused)]
 pub use pool::*;
 
+// spender of owner
+mod allowance;
+#[allow(unused)]
+pub use allowance::*;
+
 #[allow(unused)]
 pub use candid::Principal;
 #[allow(unused)]
@@ -1,4 +1,4 @@
-use common::types::{BusinessError, TokenPair};
+use common::types::{Amm, AmmText, BusinessError, TokenPair};
 
 use crate::types::{Business, with_state};
 
@@ -22,3 +22,12 @@ pub fn check_token_pair_args(_self: &TokenPair) -> Result<(), BusinessError> {
         Ok(())
     })
 }
+
+pub fn check_amm_args(amm: &AmmText) -> Result<Amm, BusinessError> {
+    let amm: Amm = amm.as_ref().try_into()?; // parse amm
+    // ! fee tier must be registered by maintainers
+    if amm.is_tier() && with_state(|s| s.business_config_fee_tier_get(&amm)).is_none() {
+        return Err(BusinessError::InvalidAmm(amm.into()));
+    }
+    Ok(amm)
+}
@@ -1,4 +1,7 @@
-use common::types::{Amm, BusinessError, SelfCanister, SwapTokenPair, TokenAccount, TokenPair, TokenPairAmm};
+use common::types::{
+    Amm, AmmText, BusinessError, SelfCanister, SwapTokenPair, TokenAccount, TokenPair, TokenPairAmm, TokenPool,
+    TokenPoolAmm,
+};
 use ic_canister_kit::types::CanisterId;
 use icrc_ledger_types::icrc1::account::Account;
 
@@ -20,9 +23,20 @@ pub fn check_pool(
     check_token_pair_args(&pair)?; // check supported token
     let amm: Amm = amm.as_ref().try_into()?; // parse amm
     let pa = TokenPairAmm { pair, amm };
+
+    let (dummy_tokens, required) = check_pool_accounts(&pa, self_canister, liquidity)?;
+    Ok((pa, dummy_tokens, required))
+}
+
+// for pool exist checking and lock accounts, the pool is parsed already
+pub fn check_pool_accounts(
+    pa: &TokenPairAmm,
+    self_canister: &SelfCanister,
+    liquidity: Option<&Account>,
+) -> Result<(Vec<CanisterId>, Vec<TokenAccount>), BusinessError> {
     // check pool exist
     let (required, dummy_tokens) = with_state(|s| {
-        s.business_token_pair_pool_get(&pa)
+        s.business_token_pair_pool_get(pa)
             .map(|maker| (maker.accounts(self_canister), maker.dummy_canisters()))
     })
     .ok_or(pa.not_exist())?;
@@ -43,5 +57,44 @@ pub fn check_pool(
         }
     }
 
-    Ok((pa, dummy_tokens, required))
+    Ok((dummy_tokens, required))
+}
+
+// for multi-asset pool exist checking and lock accounts
+pub fn check_token_pool(
+    tokens: &[CanisterId],
+    amm: &AmmText,
+    self_canister: &SelfCanister,
+    liquidity: Option<&Account>,
+) -> Result<(TokenPoolAmm, Vec<CanisterId>, Vec<TokenAccount>), BusinessError> {
+    let pool = TokenPool::new(tokens)?;
+    for pair in pool.pairs() {
+        check_token_pair_args(&pair)?; // check supported token
+    }
+    let amm: Amm = amm.as_ref().try_into()?; // parse amm
+    let pool = TokenPoolAmm { pool, amm };
+    // check pool exist
+    let (required, dummy_tokens) = with_state(|s| {
+        s.business_token_pool_get(&pool)
+            .map(|maker| (maker.accounts(self_canister), maker.dummy_canisters()))
+    })
+    .ok_or(pool.not_exist())?;
+
+    let mut required: Vec<TokenAccount> = required
+        .into_iter()
+        .flat_map(|account| {
+            pool.pool
+                .get_tokens()
+                .iter()
+                .map(move |token| TokenAccount::new(*token, account)) // self pool account of each token
+        })
+        .collect();
+
+    if let Some(liquidity) = liquidity {
+        for token in &dummy_tokens {
+            required.push(TokenAccount::new(*token, *liquidity)); // liquidity of account would be changed
+        }
+    }
+
+    Ok((pool, dummy_tokens, required))
 }
@@ -0,0 +1,171 @@
+//! https://github.com/dfinity/pocketic
+
+use candid::{Nat, Principal, encode_one};
+use ic_cdk::management_canister::CanisterSettings;
+use pocket_ic::PocketIc;
+
+mod archive_swap;
+mod archive_token;
+mod icrc2;
+mod swap;
+
+// 2T cycles
+const INIT_CYCLES: u128 = 2_000_000_000_000;
+
+const WASM_MODULE: &[u8] = include_bytes!("../sources/source_opt.wasm.gz");
+const ICRC2_WASM_MODULE: &[u8] = include_bytes!("../../../ledger/ic-icrc1-ledger.wasm");
+const ARCHIVE_TOKEN_WASM_MODULE: &[u8] = include_bytes!("../../archive-token/sources/source_opt.wasm.gz");
+const ARCHIVE_SWAP_WASM_MODULE: &[u8] = include_bytes!("../../archive-swap/sources/source_opt.wasm.gz");
+
+#[ignore]
+#[test]
+#[rustfmt::skip]
+fn test_swap_business_outer_lp_apis() {
+    let pic = PocketIc::new();
+
+    let default_identity = Principal::from_text("2ibo7-dia").unwrap();
+    let alice_identity = Principal::from_text("uuc56-gyb").unwrap();
+
+    let canister_id = Principal::from_text("piwiu-wiaaa-aaaaj-azzka-cai").unwrap();
+    let token_ck_eth_canister_id = Principal::from_text("ss2fx-dyaaa-aaaar-qacoq-cai").unwrap();
+    let token_ck_usdt_canister_id = Principal::from_text("cngnf-vqaaa-aaaar-qag4q-cai").unwrap();
+    let token_outer_lp_canister_id = Principal::from_text("rrkah-fqaaa-aaaaa-aaaaq-cai").unwrap();
+    let archive_token_canister_id = Principal::from_text("ykio2-paaaa-aaaaj-az5ka-cai").unwrap();
+    let archive_swap_canister_id = Principal::from_text("hcnys-xiaaa-aaaai-q3w4q-cai").unwrap();
+
+    fn account(owner: Principal) -> Account {
+        Account { owner, subaccount: None }
+    }
+    fn icrc2_account(owner: Principal) -> icrc2::Account {
+        icrc2::Account { owner, subaccount: None }
+    }
+    fn nat(value: u64) -> candid::Nat {
+        Nat::from(value)
+    }
+
+    pic.create_canister_with_id(Some(default_identity), None, canister_id).unwrap();
+    pic.add_cycles(canister_id, 20_000_000_000_000);
+    pic.install_canister(canister_id, WASM_MODULE.to_vec(), encode_one(Some(InitArgs::V1(InitArgV1 { maintainers: None, schedule: None, current_archiving_token: Some(CurrentArchiving { canister_id: archive_token_canister_id, length: 0, max_length: 100_000_000, block_height_offset: 0 }), current_archiving_swap: Some(CurrentArchiving { canister_id: archive_swap_canister_id, length: 0, max_length: 100_000_000, block_height_offset: 0 }) }))).unwrap(), Some(default_identity));
+
+    pic.create_canister_with_id(Some(default_identity), Some(CanisterSettings { controllers: Some(vec![default_identity, canister_id]), ..CanisterSettings::default() }), archive_token_canister_id).unwrap();
+    pic.add_cycles(archive_token_canister_id, INIT_CYCLES);
+    pic.install_canister(archive_token_canister_id, ARCHIVE_TOKEN_WASM_MODULE.to_vec(), encode_one(Some(archive_token::InitArgs::V1(archive_token::InitArgV1{ maintainers: Some(vec![default_identity]), block_offset: None, host_canister_id: Some(canister_id), max_memory_size_bytes: None }))).unwrap(), Some(default_identity));
+
+    pic.create_canister_with_id(Some(default_identity), Some(CanisterSettings { controllers: Some(vec![default_identity, canister_id]), ..CanisterSettings::default() }), archive_swap_canister_id).unwrap();
+    pic.add_cycles(archive_swap_canister_id, INIT_CYCLES);
+    pic.install_canister(archive_swap_canister_id, ARCHIVE_SWAP_WASM_MODULE.to_vec(), encode_one(Some(archive_swap::InitArgs::V1(archive_swap::InitArgV1{ maintainers: None, block_offset: None, host_canister_id: Some(canister_id), max_memory_size_bytes: None }))).unwrap(), Some(default_identity));
+
+    // ! 0. deploy tokens, the minting account of outer lp ledger is swap canister
+    #[allow(unused)] let token_ck_eth = deploy_icrc2(&pic, default_identity, token_ck_eth_canister_id, "ckETH", 18, 2_000_000_000_000, vec![(default_identity, 10_000_000_000_000_000_000), (alice_identity, 8_000_000_000_000_000_000)], Principal::from_text("aaaaa-aa").unwrap(), Some(alice_identity));
+    #[allow(unused)] let token_ck_usdt = deploy_icrc2(&pic, default_identity, token_ck_usdt_canister_id, "ckUSDT", 6, 10000, vec![(default_identity, 100_000_000_000_000)], Principal::from_text("aaaaa-aa").unwrap(), Some(alice_identity));
+    #[allow(unused)] let token_outer_lp = deploy_icrc2(&pic, default_identity, token_outer_lp_canister_id, "ckETH_ckUSDT_LP", 12, 100_000_000, vec![], canister_id, None);
+
+    use swap::*;
+
+    let pocketed_canister_id = PocketedCanisterId::new(canister_id, &pic);
+    #[allow(unused)] let default = pocketed_canister_id.sender(default_identity);
+    #[allow(unused)] let alice = pocketed_canister_id.sender(alice_identity);
+
+    default.config_token_block_chain_update(BlockChainArgs::WasmModuleUpdate(serde_bytes::ByteBuf::from(ARCHIVE_TOKEN_WASM_MODULE.to_vec()))).unwrap();
+    default.config_swap_block_chain_update(BlockChainArgs::WasmModuleUpdate(serde_bytes::ByteBuf::from(ARCHIVE_SWAP_WASM_MODULE.to_vec()))).unwrap();
+
+    // 🚩 1 deposit tokens
+    assert_eq!(token_ck_eth.sender(default_identity).icrc2_approve(icrc2::ApproveArgs::new(icrc2_account(canister_id), nat(10_000_000_000_000_000_000))).unwrap(), icrc2::Result2::Ok(nat(2)));
+    assert_eq!(default.token_deposit(TokenDepositArgs { token: token_ck_eth_canister_id, from: account(default_identity), deposit_amount_without_fee: nat(3_000_000_000_000_000_000), to: account(default_identity), fee: None, created: None, memo: None }, None).unwrap(), TokenChangedResult::Ok(nat(3)));
+    assert_eq!(token_ck_usdt.sender(default_identity).icrc2_approve(icrc2::ApproveArgs::new(icrc2_account(canister_id), nat(900_000_000_000))).unwrap(), icrc2::Result2::Ok(nat(1)));
+    assert_eq!(default.token_deposit(TokenDepositArgs { token: token_ck_usdt_canister_id, from: account(default_identity), deposit_amount_without_fee: nat(800_000_000_000), to: account(default_identity), fee: None, created: None, memo: None }, None).unwrap(), TokenChangedResult::Ok(nat(2)));
+
+    // 🚩 2 create pair and hand lp to outer ledger
+    let token_ck_eth_token_ck_usdt_subaccount = hex::decode("11dffa35fd42810f9b249c39749d4adc73a397f799f90703bf6d8fcc1ef7d92c").unwrap();
+    assert_eq!(matches!(default.pair_create(TokenPairCreateOrRemoveArgs { pool: TokenPairPool { token0: token_ck_eth_canister_id, token1: token_ck_usdt_canister_id, amm: "swap_v2_0.3%".to_string() }, memo: None, created: None }).unwrap(), TokenPairCreateOrRemoveResult::Ok(_)), true);
+    assert_eq!(alice.config_outer_lp_replace(token_ck_eth_token_ck_usdt_subaccount.clone().into(), token_outer_lp_canister_id).unwrap_err().reject_message, "Permission 'BusinessConfigMaintaining' is required".to_string());
+    assert_eq!(default.config_outer_lp_replace(token_ck_eth_token_ck_usdt_subaccount.clone().into(), token_ck_usdt_canister_id).unwrap(), OuterLpReplaceResult::Err(BusinessError::Swap("INVALID_OUTER_LP_MINTING_ACCOUNT".to_string())));
+    assert_eq!(default.config_outer_lp_replace(token_ck_eth_token_ck_usdt_subaccount.clone().into(), token_outer_lp_canister_id).unwrap(), OuterLpReplaceResult::Ok);
+    assert_eq!(default.config_outer_lp_replace(token_ck_eth_token_ck_usdt_subaccount.clone().into(), token_outer_lp_canister_id).unwrap(), OuterLpReplaceResult::Err(BusinessError::Liquidity("OUTER_LP_EXIST".to_string())));
+
+    // 🚩 3 add liquidity, lp is kept by swap canister before withdrawal
+    assert_eq!(default.pair_liquidity_add(TokenPairLiquidityAddArgs { swap_pair: SwapTokenPair { token: (token_ck_eth_canister_id, token_ck_usdt_canister_id), amm: "swap_v2_0.3%".to_string() }, from: account(default_identity), to: account(default_identity), amount_desired: (nat(2_000_000_000_000_000_000), nat(400_000_000_000)), amount_min: (nat(1), nat(1)), deadline:None, created: None, memo: None}, None).unwrap(), TokenPairLiquidityAddResult::Ok(TokenPairLiquidityAddSuccess { liquidity: nat(894_427_190_999_915), amount: (nat(2_000_000_000_000_000_000), nat(400_000_000_000)) }));
+    assert_eq!(default.token_balance_of(token_outer_lp_canister_id, account(default_identity)).unwrap(), nat(894_427_190_999_915));
+    assert_eq!(token_outer_lp.sender(default_identity).icrc1_balance_of(icrc2_account(default_identity)).unwrap(), nat(0));
+    assert_eq!(token_outer_lp.sender(default_identity).icrc_1_total_supply().unwrap(), nat(0));
+
+    // 🚩 4 withdraw lp, which is minted on the ledger without fee
+    assert_eq!(default.token_withdraw(TokenWithdrawArgs { token: token_outer_lp_canister_id, from: account(default_identity), withdraw_amount_without_fee: nat(1_000_000_000_000), to: account(default_identity), fee: None, created: None, memo: None }, None).unwrap(), TokenChangedResult::Ok(nat(0)));
+    assert_eq!(default.token_balance_of(token_outer_lp_canister_id, account(default_identity)).unwrap(), nat(893_427_190_999_915));
+    assert_eq!(token_outer_lp.sender(default_identity).icrc1_balance_of(icrc2_account(default_identity)).unwrap(), nat(1_000_000_000_000));
+    assert_eq!(token_outer_lp.sender(default_identity).icrc_1_total_supply().unwrap(), nat(1_000_000_000_000));
+
+    // 🚩 5 deposit lp, which is burned on the ledger
+    assert_eq!(token_outer_lp.sender(default_identity).icrc2_approve(icrc2::ApproveArgs::new(icrc2_account(canister_id), nat(1_000_000_000_000))).unwrap(), icrc2::Result2::Ok(nat(1)));
+    assert_eq!(token_outer_lp.sender(default_identity).icrc1_balance_of(icrc2_account(default_identity)).unwrap(), nat(999_900_000_000));
+    assert_eq!(token_outer_lp.sender(default_identity).icrc_1_total_supply().unwrap(), nat(999_900_000_000)); // the fee of approve is burned
+    assert_eq!(default.token_deposit(TokenDepositArgs { token: token_outer_lp_canister_id, from: account(default_identity), deposit_amount_without_fee: nat(999_900_000_000), to: account(default_identity), fee: None, created: None, memo: None }, None).unwrap(), TokenChangedResult::Ok(nat(2)));
+    assert_eq!(default.token_balance_of(token_outer_lp_canister_id, account(default_identity)).unwrap(), nat(894_327_190_999_915));
+    assert_eq!(token_outer_lp.sender(default_identity).icrc1_balance_of(icrc2_account(default_identity)).unwrap(), nat(0));
+    assert_eq!(token_outer_lp.sender(default_identity).icrc_1_total_supply().unwrap(), nat(0));
+}
+
+#[allow(clippy::too_many_arguments)]
+fn deploy_icrc2<'a>(
+    pic: &'a PocketIc,
+    sender: Principal,
+    canister_id: Principal,
+    symbol: &str,
+    decimals: u8,
+    transfer_fee: u64,
+    initial_balances: Vec<(Principal, u64)>,
+    minting_owner: Principal,
+    fee_collector: Option<Principal>,
+) -> icrc2::PocketedCanisterId<'a> {
+    use icrc2::*;
+
+    pic.create_canister_with_id(Some(sender), None, canister_id).unwrap();
+    pic.add_cycles(canister_id, INIT_CYCLES);
+    pic.install_canister(
+        canister_id,
+        ICRC2_WASM_MODULE.to_vec(),
+        encode_one(LedgerArgument::Init(InitArgs {
+            token_name: symbol.to_string(),
+            token_symbol: symbol.to_string(),
+            decimals: Some(decimals),
+            transfer_fee: Nat::from(transfer_fee),
+            metadata: vec![],
+            minting_account: Account {
+                owner: minting_owner,
+                subaccount: None,
+            },
+            initial_balances: initial_balances
+                .into_iter()
+                .map(|(owner, balance)| {
+                    (
+                        Account {
+                            owner,
+                            subaccount: None,
+                        },
+                        Nat::from(balance),
+                    )
+                })
+                .collect(),
+            fee_collector_account: fee_collector.map(|owner| Account {
+                owner,
+                subaccount: None,
+            }),
+            archive_options: ArchiveOptions {
+                num_blocks_to_archive: 1000,
+                max_transactions_per_response: None,
+                trigger_threshold: 1000,
+                more_controller_ids: None,
+                max_message_size_bytes: None,
+                cycles_for_archive_creation: None,
+                node_max_memory_size_bytes: None,
+                controller_id: Principal::from_text("aaaaa-aa").unwrap(),
+            },
+            max_memo_length: None,
+            feature_flags: Some(FeatureFlags { icrc2: true }),
+        }))
+        .unwrap(),
+        Some(sender),
+    );
+
+    icrc2::PocketedCanisterId::new(canister_id, pic)
+}
@@ -586,6 +586,12 @@ pub enum TokenPairLiquidityRemoveResult {
     Err(BusinessError),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, CandidType, Deserialize)]
+pub enum OuterLpReplaceResult {
+    Ok,
+    Err(BusinessError),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, CandidType, Deserialize)]
 pub enum TokenChangedResult {
     Ok(candid::Nat),
@@ -1058,6 +1064,9 @@ impl Service<'_> {
     pub fn config_fee_to_replace(&self, arg0: FeeTo) -> Result<FeeTo> {
         self.update_call("config_fee_to_replace", encode_one(arg0).unwrap())
     }
+    pub fn config_outer_lp_replace(&self, arg0: serde_bytes::ByteBuf, arg1: Principal) -> Result<OuterLpReplaceResult> {
+        self.update_call("config_outer_lp_replace", encode_args((&arg0, &arg1)).unwrap())
+    }
     pub fn config_protocol_fee_replace(
         &self,
         arg0: serde_bytes::ByteBuf,
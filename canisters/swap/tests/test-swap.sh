@@ -36,6 +36,7 @@ cargo test test_swap_business_apis -- --ignored
 cargo test test_swap_business_fee_apis -- --ignored
 cargo test test_swap_business_to_apis -- --ignored
 cargo test test_swap_business_to_fee_apis -- --ignored
+cargo test test_swap_business_outer_lp_apis -- --ignored
 # cargo test test_swap_business_bg_apis -- --ignored
 
 end_time=$(date +%H:%M:%S)
@@ -12,12 +12,30 @@ pub use create::*;
 mod remove;
 pub use remove::*;
 
+mod flash_loan;
+pub use flash_loan::*;
+
 mod swap;
 pub use swap::*;
 
 mod swap_v2;
 pub use swap_v2::*;
 
+mod swap_v3;
+pub use swap_v3::*;
+
+mod stable_swap;
+pub use stable_swap::*;
+
+mod pmm_v1;
+pub use pmm_v1::*;
+
+mod weighted;
+pub use weighted::*;
+
+mod stable_pool;
+pub use stable_pool::*;
+
 /// pair operation
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
 pub enum PairOperation {
@@ -27,12 +45,30 @@ pub enum PairOperation {
     /// remove pair
     #[serde(rename = "remove")]
     Remove(PairRemove),
+    /// flash loan
+    #[serde(rename = "flash_loan")]
+    FlashLoan(PairFlashLoan),
     /// swap
     #[serde(rename = "swap")]
     Swap(PairSwapToken),
     /// swap v2
     #[serde(rename = "swap_v2")]
     SwapV2(SwapV2Operation),
+    /// swap v3
+    #[serde(rename = "swap_v3")]
+    SwapV3(SwapV3Operation),
+    /// stable swap
+    #[serde(rename = "stable_swap")]
+    StableSwap(StableSwapOperation),
+    /// pmm v1
+    #[serde(rename = "pmm_v1")]
+    PmmV1(PmmV1Operation),
+    /// weighted
+    #[serde(rename = "weighted")]
+    Weighted(WeightedOperation),
+    /// stable pool
+    #[serde(rename = "stable_pool")]
+    StablePool(StablePoolOperation),
 }
 
 impl TryFrom<PairOperation> for proto::PairOperation {
@@ -44,8 +80,14 @@ impl TryFrom<PairOperation> for proto::PairOperation {
         let pair_operation = match value {
             PairOperation::Create(value) => Create(value.into()),
             PairOperation::Remove(value) => Remove(value.into()),
+            PairOperation::FlashLoan(value) => FlashLoan(value.try_into()?),
             PairOperation::Swap(value) => Swap(value.try_into()?),
             PairOperation::SwapV2(value) => SwapV2(value.try_into()?),
+            PairOperation::SwapV3(value) => SwapV3(value.try_into()?),
+            PairOperation::StableSwap(value) => StableSwap(value.try_into()?),
+            PairOperation::PmmV1(value) => PmmV1(value.try_into()?),
+            PairOperation::Weighted(value) => Weighted(value.try_into()?),
+            PairOperation::StablePool(value) => StablePool(value.try_into()?),
         };
 
         Ok(Self {
@@ -67,8 +109,14 @@ impl TryFrom<proto::PairOperation> for PairOperation {
         let value = match value {
             Create(value) => PairOperation::Create(value.try_into()?),
             Remove(value) => PairOperation::Remove(value.try_into()?),
+            FlashLoan(value) => PairOperation::FlashLoan(value.try_into()?),
             Swap(value) => PairOperation::Swap(value.try_into()?),
             SwapV2(value) => PairOperation::SwapV2(value.try_into()?),
+            SwapV3(value) => PairOperation::SwapV3(value.try_into()?),
+            StableSwap(value) => PairOperation::StableSwap(value.try_into()?),
+            PmmV1(value) => PairOperation::PmmV1(value.try_into()?),
+            Weighted(value) => PairOperation::Weighted(value.try_into()?),
+            StablePool(value) => PairOperation::StablePool(value.try_into()?),
         };
 
         Ok(value)
@@ -1,6 +1,6 @@
 use crate::{
     proto,
-    types::{TokenPair, TokenPairAmm},
+    types::{TokenPair, TokenPairAmm, TokenPool, TokenPoolAmm},
 };
 
 // ======================== token pair ========================
@@ -54,3 +54,44 @@ impl TryFrom<proto::TokenPairAmm> for TokenPairAmm {
         Ok(Self { pair, amm })
     }
 }
+
+// ======================== token pool ========================
+
+impl From<TokenPool> for proto::TokenPool {
+    fn from(value: TokenPool) -> Self {
+        let tokens = value.get_tokens().iter().map(|token| (*token).into()).collect();
+        Self { tokens }
+    }
+}
+
+impl TryFrom<proto::TokenPool> for TokenPool {
+    type Error = String;
+
+    fn try_from(value: proto::TokenPool) -> Result<Self, Self::Error> {
+        let tokens = value.tokens.into_iter().map(|token| token.into()).collect::<Vec<_>>();
+        Self::new(&tokens).map_err(|err| format!("{err:?}"))
+    }
+}
+
+// ======================== token pool amm ========================
+
+impl From<TokenPoolAmm> for proto::TokenPoolAmm {
+    fn from(value: TokenPoolAmm) -> Self {
+        let pool = value.pool.into();
+        let amm = value.amm.into();
+        Self { pool: Some(pool), amm }
+    }
+}
+
+impl TryFrom<proto::TokenPoolAmm> for TokenPoolAmm {
+    type Error = String;
+
+    fn try_from(value: proto::TokenPoolAmm) -> Result<Self, Self::Error> {
+        let pool = value
+            .pool
+            .ok_or_else(|| "pool of token pool amm can not be none".to_string())?
+            .try_into()?;
+        let amm = value.amm.as_str().try_into().map_err(|err| format!("{err:?}"))?;
+        Ok(Self { pool, amm })
+    }
+}
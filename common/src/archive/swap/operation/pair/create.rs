@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     proto,
-    types::{TokenPairAmm, UserId},
+    types::{TokenPairAmm, TokenPoolAmm, UserId},
 };
 
 /// Create a pool
@@ -13,16 +13,21 @@ pub struct PairCreate {
     pub pa: TokenPairAmm,
     /// Creator
     pub creator: UserId,
+    /// Multi-asset pool, pa is the first two tokens of pool
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool: Option<TokenPoolAmm>,
 }
 
 impl From<PairCreate> for proto::PairCreate {
     fn from(value: PairCreate) -> Self {
         let pa = value.pa.into();
         let creator = value.creator.into();
+        let pool = value.pool.map(|pool| pool.into());
 
         Self {
             pa: Some(pa),
             creator: Some(creator),
+            pool,
         }
     }
 }
@@ -39,7 +44,8 @@ impl TryFrom<proto::PairCreate> for PairCreate {
             .creator
             .ok_or_else(|| "creator of pair create can not be none".to_string())?
             .into();
+        let pool = value.pool.map(|pool| pool.try_into()).transpose()?;
 
-        Ok(Self { pa, creator })
+        Ok(Self { pa, creator, pool })
     }
 }
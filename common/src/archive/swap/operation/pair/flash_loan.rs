@@ -0,0 +1,107 @@
+use candid::{CandidType, Nat};
+use icrc_ledger_types::icrc1::account::Account;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    proto,
+    types::{CanisterId, TokenPairAmm},
+};
+
+/// flash loan, repaid with fee or defaulted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct PairFlashLoan {
+    /// token pair pool
+    pub pa: TokenPairAmm,
+    /// account borrowed and repaid
+    pub borrower: Account,
+    /// token lent
+    pub token: CanisterId,
+    /// amount lent
+    pub amount: Nat,
+    /// fee charged, in token, include protocol fee
+    pub fee: Nat,
+    /// protocol part of fee is transferred to fee_to, the rest goes to liquidity providers
+    pub fee_to: Option<Account>,
+    /// protocol part of fee
+    pub protocol_fee: Option<Nat>,
+    /// the part of amount and fee not repaid, the loss is taken by liquidity providers
+    #[serde(default)]
+    pub defaulted: Option<Nat>,
+}
+
+impl TryFrom<PairFlashLoan> for proto::PairFlashLoan {
+    type Error = candid::Error;
+
+    fn try_from(value: PairFlashLoan) -> Result<Self, Self::Error> {
+        let pa = value.pa.into();
+        let borrower = value.borrower.into();
+        let token = value.token.into();
+        let amount = value.amount.try_into()?;
+        let fee = value.fee.try_into()?;
+        let fee_to = value.fee_to.map(|fee_to| fee_to.into());
+        let protocol_fee = value.protocol_fee.map(|fee| fee.try_into()).transpose()?;
+        let defaulted = value.defaulted.map(|defaulted| defaulted.try_into()).transpose()?;
+
+        Ok(Self {
+            pa: Some(pa),
+            borrower: Some(borrower),
+            token: Some(token),
+            amount: Some(amount),
+            fee: Some(fee),
+            fee_to,
+            protocol_fee,
+            defaulted,
+        })
+    }
+}
+
+impl TryFrom<proto::PairFlashLoan> for PairFlashLoan {
+    type Error = String;
+
+    fn try_from(value: proto::PairFlashLoan) -> Result<Self, Self::Error> {
+        let pa = value
+            .pa
+            .ok_or_else(|| "pa of pair flash loan can not be none".to_string())?
+            .try_into()?;
+        let borrower = value
+            .borrower
+            .ok_or_else(|| "borrower of pair flash loan can not be none".to_string())?
+            .try_into()?;
+        let token = value
+            .token
+            .ok_or_else(|| "token of pair flash loan can not be none".to_string())?
+            .into();
+        let amount = value
+            .amount
+            .ok_or_else(|| "amount of pair flash loan can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore amount of pair flash loan failed".to_string())?;
+        let fee = value
+            .fee
+            .ok_or_else(|| "fee of pair flash loan can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore fee of pair flash loan failed".to_string())?;
+        let fee_to = value.fee_to.map(|fee_to| fee_to.try_into()).transpose()?;
+        let protocol_fee = value
+            .protocol_fee
+            .map(|fee| fee.try_into())
+            .transpose()
+            .map_err(|_| "restore protocol_fee of pair flash loan failed".to_string())?;
+        let defaulted = value
+            .defaulted
+            .map(|defaulted| defaulted.try_into())
+            .transpose()
+            .map_err(|_| "restore defaulted of pair flash loan failed".to_string())?;
+
+        Ok(Self {
+            pa,
+            borrower,
+            token,
+            amount,
+            fee,
+            fee_to,
+            protocol_fee,
+            defaulted,
+        })
+    }
+}
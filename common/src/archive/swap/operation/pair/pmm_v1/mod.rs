@@ -0,0 +1,57 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::proto;
+
+mod price;
+pub use price::*;
+
+mod state;
+pub use state::*;
+
+/// pmm v1
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub enum PmmV1Operation {
+    /// Record the reference price after changed
+    #[serde(rename = "price")]
+    Price(PmmV1Price),
+    /// Record the balance and targets after changed
+    #[serde(rename = "state")]
+    State(PmmV1State),
+}
+
+impl TryFrom<PmmV1Operation> for proto::PmmV1Operation {
+    type Error = candid::Error;
+
+    fn try_from(value: PmmV1Operation) -> Result<Self, Self::Error> {
+        use proto::pmm_v1_operation::PmmV1Operation::*;
+
+        let pmm_v1_operation = match value {
+            PmmV1Operation::Price(value) => Price(value.try_into()?),
+            PmmV1Operation::State(value) => State(value.try_into()?),
+        };
+
+        Ok(Self {
+            pmm_v1_operation: Some(pmm_v1_operation),
+        })
+    }
+}
+
+impl TryFrom<proto::PmmV1Operation> for PmmV1Operation {
+    type Error = String;
+
+    fn try_from(value: proto::PmmV1Operation) -> Result<Self, Self::Error> {
+        use proto::pmm_v1_operation::PmmV1Operation::*;
+
+        let value = value
+            .pmm_v1_operation
+            .ok_or_else(|| "pmm_v1_operation can not be none".to_string())?;
+
+        let value = match value {
+            Price(value) => PmmV1Operation::Price(value.try_into()?),
+            State(value) => PmmV1Operation::State(value.try_into()?),
+        };
+
+        Ok(value)
+    }
+}
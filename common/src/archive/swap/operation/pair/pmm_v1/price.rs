@@ -0,0 +1,67 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    proto,
+    types::{TimestampNanos, TokenPairAmm},
+};
+
+/// Pmm v1 reference price
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct PmmV1Price {
+    /// Token pairs and algorithms
+    pub pa: TokenPairAmm,
+    /// Timestamp
+    pub block_timestamp: TimestampNanos,
+    /// reference price of token0, how many token1 per token0, scaled by 1e18
+    pub i: Nat,
+    /// slippage parameter, scaled by 1e18
+    pub k: Nat,
+}
+
+impl TryFrom<PmmV1Price> for proto::PmmV1Price {
+    type Error = candid::Error;
+
+    fn try_from(value: PmmV1Price) -> Result<Self, Self::Error> {
+        let pa = value.pa.into();
+        let block_timestamp = value.block_timestamp.into_inner();
+        let i = value.i.try_into()?;
+        let k = value.k.try_into()?;
+
+        Ok(Self {
+            pa: Some(pa),
+            block_timestamp,
+            i: Some(i),
+            k: Some(k),
+        })
+    }
+}
+
+impl TryFrom<proto::PmmV1Price> for PmmV1Price {
+    type Error = String;
+
+    fn try_from(value: proto::PmmV1Price) -> Result<Self, Self::Error> {
+        let pa = value
+            .pa
+            .ok_or_else(|| "pa of pmm v1 price can not be none".to_string())?
+            .try_into()?;
+        let block_timestamp = TimestampNanos::from_inner(value.block_timestamp);
+        let i = value
+            .i
+            .ok_or_else(|| "i of pmm v1 price can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore i of pmm v1 price failed".to_string())?;
+        let k = value
+            .k
+            .ok_or_else(|| "k of pmm v1 price can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore k of pmm v1 price failed".to_string())?;
+
+        Ok(Self {
+            pa,
+            block_timestamp,
+            i,
+            k,
+        })
+    }
+}
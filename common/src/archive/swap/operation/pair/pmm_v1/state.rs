@@ -0,0 +1,97 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    proto,
+    types::{TimestampNanos, TokenPairAmm},
+};
+
+/// Pmm v1 state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct PmmV1State {
+    /// Token pairs and algorithms
+    pub pa: TokenPairAmm,
+    /// Timestamp
+    pub block_timestamp: TimestampNanos,
+    /// total supply of lp token
+    pub supply: Nat,
+    /// balance of token0
+    pub reserve0: Nat,
+    /// balance of token1
+    pub reserve1: Nat,
+    /// target of token0
+    pub target0: Nat,
+    /// target of token1
+    pub target1: Nat,
+}
+
+impl TryFrom<PmmV1State> for proto::PmmV1State {
+    type Error = candid::Error;
+
+    fn try_from(value: PmmV1State) -> Result<Self, Self::Error> {
+        let pa = value.pa.into();
+        let block_timestamp = value.block_timestamp.into_inner();
+        let supply = value.supply.try_into()?;
+        let reserve0 = value.reserve0.try_into()?;
+        let reserve1 = value.reserve1.try_into()?;
+        let target0 = value.target0.try_into()?;
+        let target1 = value.target1.try_into()?;
+
+        Ok(Self {
+            pa: Some(pa),
+            block_timestamp,
+            supply: Some(supply),
+            reserve0: Some(reserve0),
+            reserve1: Some(reserve1),
+            target0: Some(target0),
+            target1: Some(target1),
+        })
+    }
+}
+
+impl TryFrom<proto::PmmV1State> for PmmV1State {
+    type Error = String;
+
+    fn try_from(value: proto::PmmV1State) -> Result<Self, Self::Error> {
+        let pa = value
+            .pa
+            .ok_or_else(|| "pa of pmm v1 state can not be none".to_string())?
+            .try_into()?;
+        let block_timestamp = TimestampNanos::from_inner(value.block_timestamp);
+        let supply = value
+            .supply
+            .ok_or_else(|| "supply of pmm v1 state can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore supply of pmm v1 state failed".to_string())?;
+        let reserve0 = value
+            .reserve0
+            .ok_or_else(|| "reserve0 of pmm v1 state can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore reserve0 of pmm v1 state failed".to_string())?;
+        let reserve1 = value
+            .reserve1
+            .ok_or_else(|| "reserve1 of pmm v1 state can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore reserve1 of pmm v1 state failed".to_string())?;
+        let target0 = value
+            .target0
+            .ok_or_else(|| "target0 of pmm v1 state can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore target0 of pmm v1 state failed".to_string())?;
+        let target1 = value
+            .target1
+            .ok_or_else(|| "target1 of pmm v1 state can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore target1 of pmm v1 state failed".to_string())?;
+
+        Ok(Self {
+            pa,
+            block_timestamp,
+            supply,
+            reserve0,
+            reserve1,
+            target0,
+            target1,
+        })
+    }
+}
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     proto,
-    types::{TokenPairAmm, UserId},
+    types::{TokenPairAmm, TokenPoolAmm, UserId},
 };
 
 /// Remove a pool
@@ -13,16 +13,21 @@ pub struct PairRemove {
     pub pa: TokenPairAmm,
     /// Remover
     pub remover: UserId,
+    /// Multi-asset pool, pa is the first two tokens of pool
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool: Option<TokenPoolAmm>,
 }
 
 impl From<PairRemove> for proto::PairRemove {
     fn from(value: PairRemove) -> Self {
         let pa = value.pa.into();
         let remover = value.remover.into();
+        let pool = value.pool.map(|pool| pool.into());
 
         Self {
             pa: Some(pa),
             remover: Some(remover),
+            pool,
         }
     }
 }
@@ -39,7 +44,8 @@ impl TryFrom<proto::PairRemove> for PairRemove {
             .remover
             .ok_or_else(|| "remover of pair remove can not be none".to_string())?
             .into();
+        let pool = value.pool.map(|pool| pool.try_into()).transpose()?;
 
-        Ok(Self { pa, remover })
+        Ok(Self { pa, remover, pool })
     }
 }
@@ -0,0 +1,108 @@
+use candid::{CandidType, Nat};
+use icrc_ledger_types::icrc1::account::Account;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    proto,
+    types::{BurnFee, CanisterId, TokenPoolAmm},
+};
+
+// ==================== stable pool burn ====================
+
+/// StablePool Burn
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct StablePoolBurnToken {
+    /// token pool
+    pub pool: TokenPoolAmm,
+    /// Operation account
+    pub from: Account,
+
+    // pay
+    /// token
+    pub token: CanisterId,
+    /// amount
+    pub amount: Nat,
+
+    // got
+    /// amounts, in order of pool tokens
+    pub amounts: Vec<Nat>,
+
+    /// to account
+    pub to: Account,
+
+    /// burn fee
+    pub fee: Option<BurnFee>,
+}
+
+impl TryFrom<StablePoolBurnToken> for proto::StablePoolBurnToken {
+    type Error = candid::Error;
+
+    fn try_from(value: StablePoolBurnToken) -> Result<Self, Self::Error> {
+        let pool = value.pool.into();
+        let from = value.from.into();
+        let token = value.token.into();
+        let amount = value.amount.try_into()?;
+        let amounts = value
+            .amounts
+            .into_iter()
+            .map(|amount| amount.try_into())
+            .collect::<Result<Vec<_>, _>>()?;
+        let to = value.to.into();
+        let fee = value.fee.map(|fee| fee.try_into()).transpose()?;
+
+        Ok(Self {
+            pool: Some(pool),
+            from: Some(from),
+            token: Some(token),
+            amount: Some(amount),
+            amounts,
+            to: Some(to),
+            fee,
+        })
+    }
+}
+
+impl TryFrom<proto::StablePoolBurnToken> for StablePoolBurnToken {
+    type Error = String;
+
+    fn try_from(value: proto::StablePoolBurnToken) -> Result<Self, Self::Error> {
+        let pool = value
+            .pool
+            .ok_or_else(|| "pool of stable pool burn token can not be none".to_string())?
+            .try_into()?;
+        let from = value
+            .from
+            .ok_or_else(|| "from of stable pool burn token can not be none".to_string())?
+            .try_into()?;
+        let token = value
+            .token
+            .ok_or_else(|| "token of stable pool burn token can not be none".to_string())?
+            .into();
+        let amount = value
+            .amount
+            .ok_or_else(|| "amount of stable pool burn token can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore amount of stable pool burn token failed".to_string())?;
+        let amounts = value
+            .amounts
+            .into_iter()
+            .map(|amount| amount.try_into())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| "restore amounts of stable pool burn token failed".to_string())?;
+        let to = value
+            .to
+            .ok_or_else(|| "to of stable pool burn token can not be none".to_string())?
+            .try_into()?;
+        let fee = value.fee.map(|fee| fee.try_into()).transpose()?;
+
+        Ok(Self {
+            pool,
+            from,
+            token,
+            amount,
+            amounts,
+            to,
+            fee,
+        })
+    }
+}
@@ -0,0 +1,101 @@
+use candid::{CandidType, Nat};
+use icrc_ledger_types::icrc1::account::Account;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    proto,
+    types::{CanisterId, TokenPoolAmm},
+};
+
+// ==================== stable pool mint ====================
+
+/// StablePool Mint
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct StablePoolMintToken {
+    /// token pool
+    pub pool: TokenPoolAmm,
+    /// Operation account
+    pub from: Account,
+
+    // pay
+    /// amounts, in order of pool tokens
+    pub amounts: Vec<Nat>,
+
+    // got
+    /// token
+    pub token: CanisterId,
+    /// amount
+    pub amount: Nat,
+
+    /// to account
+    pub to: Account,
+}
+
+impl TryFrom<StablePoolMintToken> for proto::StablePoolMintToken {
+    type Error = candid::Error;
+
+    fn try_from(value: StablePoolMintToken) -> Result<Self, Self::Error> {
+        let pool = value.pool.into();
+        let from = value.from.into();
+        let amounts = value
+            .amounts
+            .into_iter()
+            .map(|amount| amount.try_into())
+            .collect::<Result<Vec<_>, _>>()?;
+        let token = value.token.into();
+        let amount = value.amount.try_into()?;
+        let to = value.to.into();
+
+        Ok(Self {
+            pool: Some(pool),
+            from: Some(from),
+            amounts,
+            token: Some(token),
+            amount: Some(amount),
+            to: Some(to),
+        })
+    }
+}
+
+impl TryFrom<proto::StablePoolMintToken> for StablePoolMintToken {
+    type Error = String;
+
+    fn try_from(value: proto::StablePoolMintToken) -> Result<Self, Self::Error> {
+        let pool = value
+            .pool
+            .ok_or_else(|| "pool of stable pool mint token can not be none".to_string())?
+            .try_into()?;
+        let from = value
+            .from
+            .ok_or_else(|| "from of stable pool mint token can not be none".to_string())?
+            .try_into()?;
+        let amounts = value
+            .amounts
+            .into_iter()
+            .map(|amount| amount.try_into())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| "restore amounts of stable pool mint token failed".to_string())?;
+        let token = value
+            .token
+            .ok_or_else(|| "token of stable pool mint token can not be none".to_string())?
+            .into();
+        let amount = value
+            .amount
+            .ok_or_else(|| "amount of stable pool mint token can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore amount of stable pool mint token failed".to_string())?;
+        let to = value
+            .to
+            .ok_or_else(|| "to of stable pool mint token can not be none".to_string())?
+            .try_into()?;
+
+        Ok(Self {
+            pool,
+            from,
+            amounts,
+            token,
+            amount,
+            to,
+        })
+    }
+}
@@ -0,0 +1,65 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::proto;
+
+mod state;
+pub use state::*;
+
+mod mint;
+pub use mint::*;
+
+mod burn;
+pub use burn::*;
+
+/// stable pool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub enum StablePoolOperation {
+    /// Record the balances after changed
+    #[serde(rename = "state")]
+    State(StablePoolState),
+    /// add liquidity
+    #[serde(rename = "mint")]
+    Mint(StablePoolMintToken),
+    /// remove liquidity
+    #[serde(rename = "burn")]
+    Burn(StablePoolBurnToken),
+}
+
+impl TryFrom<StablePoolOperation> for proto::StablePoolOperation {
+    type Error = candid::Error;
+
+    fn try_from(value: StablePoolOperation) -> Result<Self, Self::Error> {
+        use proto::stable_pool_operation::StablePoolOperation::*;
+
+        let stable_pool_operation = match value {
+            StablePoolOperation::State(value) => State(value.try_into()?),
+            StablePoolOperation::Mint(value) => Mint(value.try_into()?),
+            StablePoolOperation::Burn(value) => Burn(value.try_into()?),
+        };
+
+        Ok(Self {
+            stable_pool_operation: Some(stable_pool_operation),
+        })
+    }
+}
+
+impl TryFrom<proto::StablePoolOperation> for StablePoolOperation {
+    type Error = String;
+
+    fn try_from(value: proto::StablePoolOperation) -> Result<Self, Self::Error> {
+        use proto::stable_pool_operation::StablePoolOperation::*;
+
+        let value = value
+            .stable_pool_operation
+            .ok_or_else(|| "stable_pool_operation can not be none".to_string())?;
+
+        let value = match value {
+            State(value) => StablePoolOperation::State(value.try_into()?),
+            Mint(value) => StablePoolOperation::Mint(value.try_into()?),
+            Burn(value) => StablePoolOperation::Burn(value.try_into()?),
+        };
+
+        Ok(value)
+    }
+}
@@ -0,0 +1,86 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    proto,
+    types::{TimestampNanos, TokenPoolAmm},
+};
+
+/// Stable pool state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct StablePoolState {
+    /// Tokens of pool and algorithms
+    pub pool: TokenPoolAmm,
+    /// Timestamp
+    pub block_timestamp: TimestampNanos,
+    /// amplification coefficient
+    pub amplification: u64,
+    /// total supply of lp token
+    pub supply: Nat,
+    /// balances of tokens, in order of pool tokens
+    pub reserves: Vec<Nat>,
+    /// invariant D
+    pub invariant: Nat,
+}
+
+impl TryFrom<StablePoolState> for proto::StablePoolState {
+    type Error = candid::Error;
+
+    fn try_from(value: StablePoolState) -> Result<Self, Self::Error> {
+        let pool = value.pool.into();
+        let block_timestamp = value.block_timestamp.into_inner();
+        let supply = value.supply.try_into()?;
+        let reserves = value
+            .reserves
+            .into_iter()
+            .map(|reserve| reserve.try_into())
+            .collect::<Result<Vec<_>, _>>()?;
+        let invariant = value.invariant.try_into()?;
+
+        Ok(Self {
+            pool: Some(pool),
+            block_timestamp,
+            amplification: value.amplification,
+            supply: Some(supply),
+            reserves,
+            invariant: Some(invariant),
+        })
+    }
+}
+
+impl TryFrom<proto::StablePoolState> for StablePoolState {
+    type Error = String;
+
+    fn try_from(value: proto::StablePoolState) -> Result<Self, Self::Error> {
+        let pool = value
+            .pool
+            .ok_or_else(|| "pool of stable pool state can not be none".to_string())?
+            .try_into()?;
+        let block_timestamp = TimestampNanos::from_inner(value.block_timestamp);
+        let supply = value
+            .supply
+            .ok_or_else(|| "supply of stable pool state can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore supply of stable pool state failed".to_string())?;
+        let reserves = value
+            .reserves
+            .into_iter()
+            .map(|reserve| reserve.try_into())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| "restore reserves of stable pool state failed".to_string())?;
+        let invariant = value
+            .invariant
+            .ok_or_else(|| "invariant of stable pool state can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore invariant of stable pool state failed".to_string())?;
+
+        Ok(Self {
+            pool,
+            block_timestamp,
+            amplification: value.amplification,
+            supply,
+            reserves,
+            invariant,
+        })
+    }
+}
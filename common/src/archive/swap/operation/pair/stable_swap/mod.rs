@@ -0,0 +1,49 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::proto;
+
+mod state;
+pub use state::*;
+
+/// stable swap
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub enum StableSwapOperation {
+    /// Record the balance and invariant after changed
+    #[serde(rename = "state")]
+    State(StableSwapState),
+}
+
+impl TryFrom<StableSwapOperation> for proto::StableSwapOperation {
+    type Error = candid::Error;
+
+    fn try_from(value: StableSwapOperation) -> Result<Self, Self::Error> {
+        use proto::stable_swap_operation::StableSwapOperation::*;
+
+        let stable_swap_operation = match value {
+            StableSwapOperation::State(value) => State(value.try_into()?),
+        };
+
+        Ok(Self {
+            stable_swap_operation: Some(stable_swap_operation),
+        })
+    }
+}
+
+impl TryFrom<proto::StableSwapOperation> for StableSwapOperation {
+    type Error = String;
+
+    fn try_from(value: proto::StableSwapOperation) -> Result<Self, Self::Error> {
+        use proto::stable_swap_operation::StableSwapOperation::*;
+
+        let value = value
+            .stable_swap_operation
+            .ok_or_else(|| "stable_swap_operation can not be none".to_string())?;
+
+        let value = match value {
+            State(value) => StableSwapOperation::State(value.try_into()?),
+        };
+
+        Ok(value)
+    }
+}
@@ -0,0 +1,91 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    proto,
+    types::{TimestampNanos, TokenPairAmm},
+};
+
+/// Stable swap state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct StableSwapState {
+    /// Token pairs and algorithms
+    pub pa: TokenPairAmm,
+    /// Timestamp
+    pub block_timestamp: TimestampNanos,
+    /// amplification coefficient
+    pub amplification: u64,
+    /// total supply of lp token
+    pub supply: Nat,
+    /// balance of token0
+    pub reserve0: Nat,
+    /// balance of token1
+    pub reserve1: Nat,
+    /// invariant D of normalized balances
+    pub invariant: Nat,
+}
+
+impl TryFrom<StableSwapState> for proto::StableSwapState {
+    type Error = candid::Error;
+
+    fn try_from(value: StableSwapState) -> Result<Self, Self::Error> {
+        let pa = value.pa.into();
+        let block_timestamp = value.block_timestamp.into_inner();
+        let supply = value.supply.try_into()?;
+        let reserve0 = value.reserve0.try_into()?;
+        let reserve1 = value.reserve1.try_into()?;
+        let invariant = value.invariant.try_into()?;
+
+        Ok(Self {
+            pa: Some(pa),
+            block_timestamp,
+            amplification: value.amplification,
+            supply: Some(supply),
+            reserve0: Some(reserve0),
+            reserve1: Some(reserve1),
+            invariant: Some(invariant),
+        })
+    }
+}
+
+impl TryFrom<proto::StableSwapState> for StableSwapState {
+    type Error = String;
+
+    fn try_from(value: proto::StableSwapState) -> Result<Self, Self::Error> {
+        let pa = value
+            .pa
+            .ok_or_else(|| "pa of stable swap state can not be none".to_string())?
+            .try_into()?;
+        let block_timestamp = TimestampNanos::from_inner(value.block_timestamp);
+        let supply = value
+            .supply
+            .ok_or_else(|| "supply of stable swap state can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore supply a of stable swap state failed".to_string())?;
+        let reserve0 = value
+            .reserve0
+            .ok_or_else(|| "reserve0 of stable swap state can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore reserve0 a of stable swap state failed".to_string())?;
+        let reserve1 = value
+            .reserve1
+            .ok_or_else(|| "reserve1 of stable swap state can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore reserve1 a of stable swap state failed".to_string())?;
+        let invariant = value
+            .invariant
+            .ok_or_else(|| "invariant of stable swap state can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore invariant a of stable swap state failed".to_string())?;
+
+        Ok(Self {
+            pa,
+            block_timestamp,
+            amplification: value.amplification,
+            supply,
+            reserve0,
+            reserve1,
+            invariant,
+        })
+    }
+}
@@ -24,6 +24,8 @@ pub struct PairSwapToken {
     pub amount_a: Nat,
     /// amount got
     pub amount_b: Nat,
+    /// fee charged, in token_a
+    pub fee: Option<Nat>,
 }
 
 impl TryFrom<PairSwapToken> for proto::PairSwapToken {
@@ -37,6 +39,7 @@ impl TryFrom<PairSwapToken> for proto::PairSwapToken {
         let to = value.to.into();
         let amount_a = value.amount_a.try_into()?;
         let amount_b = value.amount_b.try_into()?;
+        let fee = value.fee.map(|fee| fee.try_into()).transpose()?;
 
         Ok(Self {
             token_a: Some(token_a),
@@ -46,6 +49,7 @@ impl TryFrom<PairSwapToken> for proto::PairSwapToken {
             to: Some(to),
             amount_a: Some(amount_a),
             amount_b: Some(amount_b),
+            fee,
         })
     }
 }
@@ -81,6 +85,11 @@ impl TryFrom<proto::PairSwapToken> for PairSwapToken {
             .ok_or_else(|| "amount_b of pair swap token can not be none".to_string())?
             .try_into()
             .map_err(|_| "restore amount_b b of pair swap token failed".to_string())?;
+        let fee = value
+            .fee
+            .map(|fee| fee.try_into())
+            .transpose()
+            .map_err(|_| "restore fee of pair swap token failed".to_string())?;
 
         Ok(Self {
             token_a,
@@ -90,6 +99,7 @@ impl TryFrom<proto::PairSwapToken> for PairSwapToken {
             to,
             amount_a,
             amount_b,
+            fee,
         })
     }
 }
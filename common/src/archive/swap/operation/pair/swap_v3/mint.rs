@@ -0,0 +1,125 @@
+use candid::{CandidType, Nat};
+use icrc_ledger_types::icrc1::account::Account;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    proto,
+    types::{CanisterId, TokenPairAmm},
+};
+
+// ==================== swap v3 mint ====================
+
+/// SwapV3 Mint
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct SwapV3MintToken {
+    /// token pair pool
+    pub pa: TokenPairAmm,
+    /// Operation account
+    pub from: Account,
+
+    // range
+    /// lower tick of position
+    pub tick_lower: i32,
+    /// upper tick of position
+    pub tick_upper: i32,
+
+    // pay
+    /// token0
+    pub token0: CanisterId,
+    /// token1
+    pub token1: CanisterId,
+    /// amount0
+    pub amount0: Nat,
+    /// amount1
+    pub amount1: Nat,
+
+    // got
+    /// liquidity
+    pub liquidity: Nat,
+
+    /// position owner
+    pub to: Account,
+}
+
+impl TryFrom<SwapV3MintToken> for proto::SwapV3MintToken {
+    type Error = candid::Error;
+
+    fn try_from(value: SwapV3MintToken) -> Result<Self, Self::Error> {
+        let pa = value.pa.into();
+        let from = value.from.into();
+        let token0 = value.token0.into();
+        let token1 = value.token1.into();
+        let amount0 = value.amount0.try_into()?;
+        let amount1 = value.amount1.try_into()?;
+        let liquidity = value.liquidity.try_into()?;
+        let to = value.to.into();
+
+        Ok(Self {
+            pa: Some(pa),
+            from: Some(from),
+            tick_lower: value.tick_lower,
+            tick_upper: value.tick_upper,
+            token0: Some(token0),
+            token1: Some(token1),
+            amount0: Some(amount0),
+            amount1: Some(amount1),
+            liquidity: Some(liquidity),
+            to: Some(to),
+        })
+    }
+}
+
+impl TryFrom<proto::SwapV3MintToken> for SwapV3MintToken {
+    type Error = String;
+
+    fn try_from(value: proto::SwapV3MintToken) -> Result<Self, Self::Error> {
+        let pa = value
+            .pa
+            .ok_or_else(|| "pa of swap v3 mint token can not be none".to_string())?
+            .try_into()?;
+        let from = value
+            .from
+            .ok_or_else(|| "from of swap v3 mint token can not be none".to_string())?
+            .try_into()?;
+        let token0 = value
+            .token0
+            .ok_or_else(|| "token0 of swap v3 mint token can not be none".to_string())?
+            .into();
+        let token1 = value
+            .token1
+            .ok_or_else(|| "token1 of swap v3 mint token can not be none".to_string())?
+            .into();
+        let amount0 = value
+            .amount0
+            .ok_or_else(|| "amount0 of swap v3 mint token can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore amount0 a of swap v3 mint token failed".to_string())?;
+        let amount1 = value
+            .amount1
+            .ok_or_else(|| "amount1 of swap v3 mint token can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore amount1 a of swap v3 mint token failed".to_string())?;
+        let liquidity = value
+            .liquidity
+            .ok_or_else(|| "liquidity of swap v3 mint token can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore liquidity a of swap v3 mint token failed".to_string())?;
+        let to = value
+            .to
+            .ok_or_else(|| "to of swap v3 mint token can not be none".to_string())?
+            .try_into()?;
+
+        Ok(Self {
+            pa,
+            from,
+            tick_lower: value.tick_lower,
+            tick_upper: value.tick_upper,
+            token0,
+            token1,
+            amount0,
+            amount1,
+            liquidity,
+            to,
+        })
+    }
+}
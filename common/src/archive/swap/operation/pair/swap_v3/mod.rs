@@ -0,0 +1,65 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::proto;
+
+mod mint;
+pub use mint::*;
+
+mod burn;
+pub use burn::*;
+
+mod swap;
+pub use swap::*;
+
+/// swap v3
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub enum SwapV3Operation {
+    /// Add liquidity of position
+    #[serde(rename = "mint")]
+    Mint(SwapV3MintToken),
+    /// Remove liquidity of position
+    #[serde(rename = "burn")]
+    Burn(SwapV3BurnToken),
+    /// Swap in ranges, record the state after swap
+    #[serde(rename = "swap")]
+    Swap(SwapV3SwapToken),
+}
+
+impl TryFrom<SwapV3Operation> for proto::SwapV3Operation {
+    type Error = candid::Error;
+
+    fn try_from(value: SwapV3Operation) -> Result<Self, Self::Error> {
+        use proto::swap_v3_operation::SwapV3Operation::*;
+
+        let swap_v3_operation = match value {
+            SwapV3Operation::Mint(value) => Mint(value.try_into()?),
+            SwapV3Operation::Burn(value) => Burn(value.try_into()?),
+            SwapV3Operation::Swap(value) => Swap(value.try_into()?),
+        };
+
+        Ok(Self {
+            swap_v3_operation: Some(swap_v3_operation),
+        })
+    }
+}
+
+impl TryFrom<proto::SwapV3Operation> for SwapV3Operation {
+    type Error = String;
+
+    fn try_from(value: proto::SwapV3Operation) -> Result<Self, Self::Error> {
+        use proto::swap_v3_operation::SwapV3Operation::*;
+
+        let value = value
+            .swap_v3_operation
+            .ok_or_else(|| "swap_v3_operation can not be none".to_string())?;
+
+        let value = match value {
+            Mint(value) => SwapV3Operation::Mint(value.try_into()?),
+            Burn(value) => SwapV3Operation::Burn(value.try_into()?),
+            Swap(value) => SwapV3Operation::Swap(value.try_into()?),
+        };
+
+        Ok(value)
+    }
+}
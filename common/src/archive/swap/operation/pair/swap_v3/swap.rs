@@ -0,0 +1,130 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    proto,
+    types::{TimestampNanos, TokenPairAmm},
+};
+
+// ==================== swap v3 swap ====================
+
+/// SwapV3 Swap
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct SwapV3SwapToken {
+    /// Token pairs and algorithms
+    pub pa: TokenPairAmm,
+    /// Timestamp
+    pub block_timestamp: TimestampNanos,
+
+    /// token0 -> token1 or not
+    pub zero_for_one: bool,
+    /// amount in, include fee
+    pub amount_in: Nat,
+    /// amount out
+    pub amount_out: Nat,
+    /// swap fee, include protocol fee
+    pub fee: Nat,
+
+    // state after swap
+    /// sqrt price, Q64.96
+    pub sqrt_price_x96: Nat,
+    /// current tick
+    pub tick: i32,
+    /// in range liquidity
+    pub liquidity: Nat,
+    /// balance of token0
+    pub reserve0: Nat,
+    /// balance of token1
+    pub reserve1: Nat,
+}
+
+impl TryFrom<SwapV3SwapToken> for proto::SwapV3SwapToken {
+    type Error = candid::Error;
+
+    fn try_from(value: SwapV3SwapToken) -> Result<Self, Self::Error> {
+        let pa = value.pa.into();
+        let block_timestamp = value.block_timestamp.into_inner();
+        let amount_in = value.amount_in.try_into()?;
+        let amount_out = value.amount_out.try_into()?;
+        let fee = value.fee.try_into()?;
+        let sqrt_price_x96 = value.sqrt_price_x96.try_into()?;
+        let liquidity = value.liquidity.try_into()?;
+        let reserve0 = value.reserve0.try_into()?;
+        let reserve1 = value.reserve1.try_into()?;
+
+        Ok(Self {
+            pa: Some(pa),
+            block_timestamp,
+            zero_for_one: value.zero_for_one,
+            amount_in: Some(amount_in),
+            amount_out: Some(amount_out),
+            fee: Some(fee),
+            sqrt_price_x96: Some(sqrt_price_x96),
+            tick: value.tick,
+            liquidity: Some(liquidity),
+            reserve0: Some(reserve0),
+            reserve1: Some(reserve1),
+        })
+    }
+}
+
+impl TryFrom<proto::SwapV3SwapToken> for SwapV3SwapToken {
+    type Error = String;
+
+    fn try_from(value: proto::SwapV3SwapToken) -> Result<Self, Self::Error> {
+        let pa = value
+            .pa
+            .ok_or_else(|| "pa of swap v3 swap token can not be none".to_string())?
+            .try_into()?;
+        let block_timestamp = TimestampNanos::from_inner(value.block_timestamp);
+        let amount_in = value
+            .amount_in
+            .ok_or_else(|| "amount_in of swap v3 swap token can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore amount_in a of swap v3 swap token failed".to_string())?;
+        let amount_out = value
+            .amount_out
+            .ok_or_else(|| "amount_out of swap v3 swap token can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore amount_out a of swap v3 swap token failed".to_string())?;
+        let fee = value
+            .fee
+            .ok_or_else(|| "fee of swap v3 swap token can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore fee a of swap v3 swap token failed".to_string())?;
+        let sqrt_price_x96 = value
+            .sqrt_price_x96
+            .ok_or_else(|| "sqrt_price_x96 of swap v3 swap token can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore sqrt_price_x96 a of swap v3 swap token failed".to_string())?;
+        let liquidity = value
+            .liquidity
+            .ok_or_else(|| "liquidity of swap v3 swap token can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore liquidity a of swap v3 swap token failed".to_string())?;
+        let reserve0 = value
+            .reserve0
+            .ok_or_else(|| "reserve0 of swap v3 swap token can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore reserve0 a of swap v3 swap token failed".to_string())?;
+        let reserve1 = value
+            .reserve1
+            .ok_or_else(|| "reserve1 of swap v3 swap token can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore reserve1 a of swap v3 swap token failed".to_string())?;
+
+        Ok(Self {
+            pa,
+            block_timestamp,
+            zero_for_one: value.zero_for_one,
+            amount_in,
+            amount_out,
+            fee,
+            sqrt_price_x96,
+            tick: value.tick,
+            liquidity,
+            reserve0,
+            reserve1,
+        })
+    }
+}
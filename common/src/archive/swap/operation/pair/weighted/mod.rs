@@ -0,0 +1,49 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::proto;
+
+mod state;
+pub use state::*;
+
+/// weighted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub enum WeightedOperation {
+    /// Record the balance and weights after changed
+    #[serde(rename = "state")]
+    State(WeightedState),
+}
+
+impl TryFrom<WeightedOperation> for proto::WeightedOperation {
+    type Error = candid::Error;
+
+    fn try_from(value: WeightedOperation) -> Result<Self, Self::Error> {
+        use proto::weighted_operation::WeightedOperation::*;
+
+        let weighted_operation = match value {
+            WeightedOperation::State(value) => State(value.try_into()?),
+        };
+
+        Ok(Self {
+            weighted_operation: Some(weighted_operation),
+        })
+    }
+}
+
+impl TryFrom<proto::WeightedOperation> for WeightedOperation {
+    type Error = String;
+
+    fn try_from(value: proto::WeightedOperation) -> Result<Self, Self::Error> {
+        use proto::weighted_operation::WeightedOperation::*;
+
+        let value = value
+            .weighted_operation
+            .ok_or_else(|| "weighted_operation can not be none".to_string())?;
+
+        let value = match value {
+            State(value) => WeightedOperation::State(value.try_into()?),
+        };
+
+        Ok(value)
+    }
+}
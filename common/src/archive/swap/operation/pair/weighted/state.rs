@@ -0,0 +1,85 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    proto,
+    types::{TimestampNanos, TokenPairAmm},
+};
+
+/// Weighted state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct WeightedState {
+    /// Token pairs and algorithms
+    pub pa: TokenPairAmm,
+    /// Timestamp
+    pub block_timestamp: TimestampNanos,
+    /// weight of token0
+    pub weight0: u32,
+    /// weight of token1
+    pub weight1: u32,
+    /// total supply of lp token
+    pub supply: Nat,
+    /// balance of token0
+    pub reserve0: Nat,
+    /// balance of token1
+    pub reserve1: Nat,
+}
+
+impl TryFrom<WeightedState> for proto::WeightedState {
+    type Error = candid::Error;
+
+    fn try_from(value: WeightedState) -> Result<Self, Self::Error> {
+        let pa = value.pa.into();
+        let block_timestamp = value.block_timestamp.into_inner();
+        let supply = value.supply.try_into()?;
+        let reserve0 = value.reserve0.try_into()?;
+        let reserve1 = value.reserve1.try_into()?;
+
+        Ok(Self {
+            pa: Some(pa),
+            block_timestamp,
+            weight0: value.weight0,
+            weight1: value.weight1,
+            supply: Some(supply),
+            reserve0: Some(reserve0),
+            reserve1: Some(reserve1),
+        })
+    }
+}
+
+impl TryFrom<proto::WeightedState> for WeightedState {
+    type Error = String;
+
+    fn try_from(value: proto::WeightedState) -> Result<Self, Self::Error> {
+        let pa = value
+            .pa
+            .ok_or_else(|| "pa of weighted state can not be none".to_string())?
+            .try_into()?;
+        let block_timestamp = TimestampNanos::from_inner(value.block_timestamp);
+        let supply = value
+            .supply
+            .ok_or_else(|| "supply of weighted state can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore supply of weighted state failed".to_string())?;
+        let reserve0 = value
+            .reserve0
+            .ok_or_else(|| "reserve0 of weighted state can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore reserve0 of weighted state failed".to_string())?;
+        let reserve1 = value
+            .reserve1
+            .ok_or_else(|| "reserve1 of weighted state can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore reserve1 of weighted state failed".to_string())?;
+
+        Ok(Self {
+            pa,
+            block_timestamp,
+            weight0: value.weight0,
+            weight1: value.weight1,
+            supply,
+            reserve0,
+            reserve1,
+        })
+    }
+}
@@ -0,0 +1,100 @@
+use icrc_ledger_types::{
+    icrc::generic_value::{ICRC3Map, ICRC3Value},
+    icrc1::account::Account,
+};
+use serde_bytes::ByteBuf;
+
+use candid::Nat;
+
+use super::{TokenBlock, TokenOperation};
+
+fn icrc3_account(account: &Account) -> ICRC3Value {
+    let mut value = vec![ICRC3Value::Blob(ByteBuf::from(account.owner.as_slice().to_vec()))];
+    if let Some(subaccount) = account.subaccount {
+        value.push(ICRC3Value::Blob(ByteBuf::from(subaccount.to_vec())));
+    }
+    ICRC3Value::Array(value)
+}
+
+/// Token block in the shape of icrc3 value.
+/// The hash of parent block in token block chain is kept as parent_hash, there is no icrc3 phash
+impl From<TokenBlock> for ICRC3Value {
+    fn from(block: TokenBlock) -> Self {
+        let block = block.0;
+        let transaction = block.transaction;
+
+        let mut tx = ICRC3Map::new();
+        let btype = match transaction.operation {
+            TokenOperation::Deposit(deposit) => {
+                tx.insert(
+                    "token".into(),
+                    ICRC3Value::Blob(ByteBuf::from(deposit.token.as_slice().to_vec())),
+                );
+                tx.insert("to".into(), icrc3_account(&deposit.to));
+                tx.insert("amt".into(), ICRC3Value::Nat(deposit.amount));
+                "1mint"
+            }
+            TokenOperation::Withdraw(withdraw) => {
+                tx.insert(
+                    "token".into(),
+                    ICRC3Value::Blob(ByteBuf::from(withdraw.token.as_slice().to_vec())),
+                );
+                tx.insert("from".into(), icrc3_account(&withdraw.from));
+                tx.insert("amt".into(), ICRC3Value::Nat(withdraw.amount));
+                "1burn"
+            }
+            TokenOperation::Transfer(transfer) => {
+                tx.insert(
+                    "token".into(),
+                    ICRC3Value::Blob(ByteBuf::from(transfer.token.as_slice().to_vec())),
+                );
+                tx.insert("from".into(), icrc3_account(&transfer.from));
+                tx.insert("to".into(), icrc3_account(&transfer.to));
+                tx.insert("amt".into(), ICRC3Value::Nat(transfer.amount));
+                if let Some(fee) = transfer.fee {
+                    tx.insert("fee".into(), ICRC3Value::Nat(fee.fee));
+                    tx.insert("fee_to".into(), icrc3_account(&fee.fee_to));
+                }
+                match transfer.spender {
+                    Some(spender) => {
+                        tx.insert("spender".into(), icrc3_account(&spender));
+                        "2xfer"
+                    }
+                    None => "1xfer",
+                }
+            }
+            TokenOperation::Approve(approve) => {
+                tx.insert(
+                    "token".into(),
+                    ICRC3Value::Blob(ByteBuf::from(approve.token.as_slice().to_vec())),
+                );
+                tx.insert("from".into(), icrc3_account(&approve.from));
+                tx.insert("spender".into(), icrc3_account(&approve.spender));
+                tx.insert("amt".into(), ICRC3Value::Nat(approve.amount));
+                if let Some(expected_allowance) = approve.expected_allowance {
+                    tx.insert("expected_allowance".into(), ICRC3Value::Nat(expected_allowance));
+                }
+                if let Some(expires_at) = approve.expires_at {
+                    tx.insert("expires_at".into(), ICRC3Value::Nat(Nat::from(expires_at)));
+                }
+                "2approve"
+            }
+        };
+        if let Some(memo) = transaction.memo {
+            tx.insert("memo".into(), ICRC3Value::Blob(ByteBuf::from(memo)));
+        }
+        if let Some(created) = transaction.created {
+            tx.insert("ts".into(), ICRC3Value::Nat(Nat::from(created.into_inner())));
+        }
+
+        let mut value = ICRC3Map::new();
+        value.insert("btype".into(), ICRC3Value::Text(btype.into()));
+        value.insert("ts".into(), ICRC3Value::Nat(Nat::from(block.timestamp.into_inner())));
+        value.insert(
+            "parent_hash".into(),
+            ICRC3Value::Blob(ByteBuf::from(block.parent_hash.as_slice().to_vec())),
+        );
+        value.insert("tx".into(), ICRC3Value::Map(tx));
+        ICRC3Value::Map(value)
+    }
+}
@@ -17,3 +17,6 @@ pub use query::*;
 /// initialization and upgrade
 mod args;
 pub use args::*;
+
+/// icrc3 value
+mod icrc3;
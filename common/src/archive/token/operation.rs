@@ -20,6 +20,10 @@ pub use withdraw::*;
 mod transfer;
 pub use transfer::*;
 
+/// Approve tokens to spender
+mod approve;
+pub use approve::*;
+
 /// Token operation
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
 pub enum TokenOperation {
@@ -32,6 +36,9 @@ pub enum TokenOperation {
     /// Transfer
     #[serde(rename = "transfer")]
     Transfer(TransferToken),
+    /// Approve
+    #[serde(rename = "approve")]
+    Approve(ApproveToken),
 }
 
 impl TryFrom<TokenOperation> for proto::TokenOperation {
@@ -44,6 +51,7 @@ impl TryFrom<TokenOperation> for proto::TokenOperation {
             TokenOperation::Deposit(value) => Deposit(value.try_into()?),
             TokenOperation::Withdraw(value) => Withdraw(value.try_into()?),
             TokenOperation::Transfer(value) => Transfer(value.try_into()?),
+            TokenOperation::Approve(value) => Approve(value.try_into()?),
         };
 
         Ok(Self {
@@ -66,6 +74,7 @@ impl TryFrom<proto::TokenOperation> for TokenOperation {
             Deposit(value) => TokenOperation::Deposit(value.try_into()?),
             Withdraw(value) => TokenOperation::Withdraw(value.try_into()?),
             Transfer(value) => TokenOperation::Transfer(value.try_into()?),
+            Approve(value) => TokenOperation::Approve(value.try_into()?),
         };
 
         Ok(value)
@@ -79,6 +88,7 @@ impl TokenOperation {
             TokenOperation::Deposit(value) => value.token,
             TokenOperation::Withdraw(value) => value.token,
             TokenOperation::Transfer(value) => value.token,
+            TokenOperation::Approve(value) => value.token,
         }
     }
 
@@ -88,6 +98,7 @@ impl TokenOperation {
             TokenOperation::Deposit(value) => value.from,
             TokenOperation::Withdraw(value) => value.from,
             TokenOperation::Transfer(value) => value.from,
+            TokenOperation::Approve(value) => value.from,
         }
     }
 
@@ -97,6 +108,7 @@ impl TokenOperation {
             TokenOperation::Deposit(value) => &value.amount,
             TokenOperation::Withdraw(value) => &value.amount,
             TokenOperation::Transfer(value) => &value.amount,
+            TokenOperation::Approve(value) => &value.amount,
         }
     }
 
@@ -106,6 +118,7 @@ impl TokenOperation {
             TokenOperation::Deposit(value) => value.to,
             TokenOperation::Withdraw(value) => value.to,
             TokenOperation::Transfer(value) => value.to,
+            TokenOperation::Approve(value) => value.spender,
         }
     }
 
@@ -115,6 +128,7 @@ impl TokenOperation {
             TokenOperation::Deposit(_) => None,
             TokenOperation::Withdraw(_) => None,
             TokenOperation::Transfer(value) => value.fee.as_ref(),
+            TokenOperation::Approve(_) => None,
         }
     }
 }
@@ -0,0 +1,83 @@
+use candid::{CandidType, Nat};
+use icrc_ledger_types::icrc1::account::Account;
+use serde::{Deserialize, Serialize};
+
+use crate::{proto, types::CanisterId};
+
+/// Approve transactions, the allowance of spender is replaced
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct ApproveToken {
+    /// Tokens
+    pub token: CanisterId,
+    /// Owner account
+    pub from: Account,
+    /// The allowance after approved
+    pub amount: Nat,
+    /// Spender account
+    pub spender: Account,
+    /// The allowance must be matched before approved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_allowance: Option<Nat>,
+    /// The allowance is expired after this time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+}
+
+impl TryFrom<ApproveToken> for proto::ApproveToken {
+    type Error = candid::Error;
+
+    fn try_from(value: ApproveToken) -> Result<Self, Self::Error> {
+        let token = value.token.into();
+        let from = value.from.into();
+        let amount = value.amount.try_into()?;
+        let spender = value.spender.into();
+        let expected_allowance = value.expected_allowance.map(|v| v.try_into()).transpose()?;
+
+        Ok(Self {
+            token: Some(token),
+            from: Some(from),
+            amount: Some(amount),
+            spender: Some(spender),
+            expected_allowance,
+            expires_at: value.expires_at,
+        })
+    }
+}
+
+impl TryFrom<proto::ApproveToken> for ApproveToken {
+    type Error = String;
+
+    fn try_from(value: proto::ApproveToken) -> Result<Self, Self::Error> {
+        let token = value
+            .token
+            .ok_or_else(|| "token of approve can not be none".to_string())?
+            .into();
+        let from = value
+            .from
+            .ok_or_else(|| "from of approve can not be none".to_string())?
+            .try_into()?;
+        let amount = value
+            .amount
+            .ok_or_else(|| "amount of approve can not be none".to_string())?
+            .try_into()
+            .map_err(|_| "restore amount of approve failed".to_string())?;
+        let spender = value
+            .spender
+            .ok_or_else(|| "spender of approve can not be none".to_string())?
+            .try_into()?;
+        let expected_allowance = value
+            .expected_allowance
+            .map(|v| v.try_into())
+            .transpose()
+            .map_err(|_| "restore expected allowance of approve failed".to_string())?;
+
+        Ok(Self {
+            token,
+            from,
+            amount,
+            spender,
+            expected_allowance,
+            expires_at: value.expires_at,
+        })
+    }
+}
@@ -21,6 +21,9 @@ pub struct TransferToken {
     /// Transfer fees and account collection
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fee: Option<TransferFee>,
+    /// The spender of allowance, if transferred by icrc2 transfer_from
+    #[serde(default)]
+    pub spender: Option<Account>,
 }
 
 impl TryFrom<TransferToken> for proto::TransferToken {
@@ -32,6 +35,7 @@ impl TryFrom<TransferToken> for proto::TransferToken {
         let amount = value.amount.try_into()?;
         let to = value.to.into();
         let fee = value.fee.map(|fee| fee.try_into()).transpose()?;
+        let spender = value.spender.map(|spender| spender.into());
 
         Ok(Self {
             token: Some(token),
@@ -39,6 +43,7 @@ impl TryFrom<TransferToken> for proto::TransferToken {
             amount: Some(amount),
             to: Some(to),
             fee,
+            spender,
         })
     }
 }
@@ -65,6 +70,7 @@ impl TryFrom<proto::TransferToken> for TransferToken {
             .ok_or_else(|| "to of withdraw can not be none".to_string())?
             .try_into()?;
         let fee = value.fee.map(|fee| fee.try_into()).transpose()?;
+        let spender = value.spender.map(|spender| spender.try_into()).transpose()?;
 
         Ok(Self {
             token,
@@ -72,6 +78,42 @@ impl TryFrom<proto::TransferToken> for TransferToken {
             amount,
             to,
             fee,
+            spender,
         })
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_token_spender() {
+        let account = |id: u8| Account {
+            owner: CanisterId::from_slice(&[id]),
+            subaccount: None,
+        };
+        let transfer = TransferToken {
+            token: CanisterId::from_slice(&[9]),
+            from: account(1),
+            amount: Nat::from(100_u32),
+            to: account(2),
+            fee: None,
+            spender: Some(account(3)),
+        };
+
+        // the spender is kept in block
+        let block: proto::TransferToken = transfer.clone().try_into().unwrap();
+        assert_eq!(TransferToken::try_from(block).unwrap(), transfer);
+
+        // transferred by owner
+        let transfer = TransferToken {
+            spender: None,
+            ..transfer
+        };
+        let block: proto::TransferToken = transfer.clone().try_into().unwrap();
+        assert!(block.spender.is_none());
+        assert_eq!(TransferToken::try_from(block).unwrap(), transfer);
+    }
+}
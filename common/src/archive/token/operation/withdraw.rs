@@ -2,7 +2,10 @@ use candid::{CandidType, Nat};
 use icrc_ledger_types::icrc1::account::Account;
 use serde::{Deserialize, Serialize};
 
-use crate::{proto, types::CanisterId};
+use crate::{
+    proto,
+    types::{AccountIdentifier, CanisterId},
+};
 
 /// Withdrawal transactions
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
@@ -15,6 +18,9 @@ pub struct WithdrawToken {
     pub amount: Nat,
     /// to account
     pub to: Account,
+    /// The legacy account identifier received the tokens, if withdraw to it, to account is the from account
+    #[serde(default)]
+    pub to_account_id: Option<AccountIdentifier>,
 }
 
 impl TryFrom<WithdrawToken> for proto::WithdrawToken {
@@ -25,12 +31,14 @@ impl TryFrom<WithdrawToken> for proto::WithdrawToken {
         let from = value.from.into();
         let amount = value.amount.try_into()?;
         let to = value.to.into();
+        let to_account_id = value.to_account_id.map(|id| id.as_bytes().to_vec().into());
 
         Ok(Self {
             token: Some(token),
             from: Some(from),
             amount: Some(amount),
             to: Some(to),
+            to_account_id,
         })
     }
 }
@@ -56,12 +64,21 @@ impl TryFrom<proto::WithdrawToken> for WithdrawToken {
             .to
             .ok_or_else(|| "to of withdraw can not be none".to_string())?
             .try_into()?;
+        let to_account_id = value
+            .to_account_id
+            .map(|id| {
+                <[u8; 32]>::try_from(&id[..])
+                    .map(AccountIdentifier::from)
+                    .map_err(|_| "length of to account id of withdraw must be 32".to_string())
+            })
+            .transpose()?;
 
         Ok(Self {
             from,
             token,
             amount,
             to,
+            to_account_id,
         })
     }
 }
@@ -15,6 +15,239 @@ pub struct TokenPairAmm {
     #[prost(string, tag = "2")]
     pub amm: ::prost::alloc::string::String,
 }
+/// sorted tokens of multi-asset pool
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TokenPool {
+    #[prost(message, repeated, tag = "1")]
+    pub tokens: ::prost::alloc::vec::Vec<super::common::CanisterId>,
+}
+/// multi-asset pool
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TokenPoolAmm {
+    #[prost(message, optional, tag = "1")]
+    pub pool: ::core::option::Option<TokenPool>,
+    #[prost(string, tag = "2")]
+    pub amm: ::prost::alloc::string::String,
+}
+/// state
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StableSwapState {
+    #[prost(message, optional, tag = "1")]
+    pub pa: ::core::option::Option<TokenPairAmm>,
+    #[prost(uint64, tag = "2")]
+    pub block_timestamp: u64,
+    /// amplification coefficient
+    #[prost(uint64, tag = "3")]
+    pub amplification: u64,
+    /// total supply of lp token
+    #[prost(message, optional, tag = "4")]
+    pub supply: ::core::option::Option<super::common::Nat>,
+    /// balance of token0
+    #[prost(message, optional, tag = "5")]
+    pub reserve0: ::core::option::Option<super::common::Nat>,
+    /// balance of token1
+    #[prost(message, optional, tag = "6")]
+    pub reserve1: ::core::option::Option<super::common::Nat>,
+    /// invariant D of normalized balances
+    #[prost(message, optional, tag = "7")]
+    pub invariant: ::core::option::Option<super::common::Nat>,
+}
+/// stable swap operation
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StableSwapOperation {
+    #[prost(oneof = "stable_swap_operation::StableSwapOperation", tags = "1")]
+    pub stable_swap_operation: ::core::option::Option<
+        stable_swap_operation::StableSwapOperation,
+    >,
+}
+/// Nested message and enum types in `StableSwapOperation`.
+pub mod stable_swap_operation {
+    #[non_exhaustive]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum StableSwapOperation {
+        #[prost(message, tag = "1")]
+        State(super::StableSwapState),
+    }
+}
+/// reference price
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PmmV1Price {
+    #[prost(message, optional, tag = "1")]
+    pub pa: ::core::option::Option<TokenPairAmm>,
+    #[prost(uint64, tag = "2")]
+    pub block_timestamp: u64,
+    /// reference price of token0, how many token1 per token0, scaled by 1e18
+    #[prost(message, optional, tag = "3")]
+    pub i: ::core::option::Option<super::common::Nat>,
+    /// slippage parameter, scaled by 1e18
+    #[prost(message, optional, tag = "4")]
+    pub k: ::core::option::Option<super::common::Nat>,
+}
+/// state
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PmmV1State {
+    #[prost(message, optional, tag = "1")]
+    pub pa: ::core::option::Option<TokenPairAmm>,
+    #[prost(uint64, tag = "2")]
+    pub block_timestamp: u64,
+    /// total supply of lp token
+    #[prost(message, optional, tag = "3")]
+    pub supply: ::core::option::Option<super::common::Nat>,
+    /// balance of token0
+    #[prost(message, optional, tag = "4")]
+    pub reserve0: ::core::option::Option<super::common::Nat>,
+    /// balance of token1
+    #[prost(message, optional, tag = "5")]
+    pub reserve1: ::core::option::Option<super::common::Nat>,
+    /// target of token0
+    #[prost(message, optional, tag = "6")]
+    pub target0: ::core::option::Option<super::common::Nat>,
+    /// target of token1
+    #[prost(message, optional, tag = "7")]
+    pub target1: ::core::option::Option<super::common::Nat>,
+}
+/// pmm v1 operation
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PmmV1Operation {
+    #[prost(oneof = "pmm_v1_operation::PmmV1Operation", tags = "1, 2")]
+    pub pmm_v1_operation: ::core::option::Option<pmm_v1_operation::PmmV1Operation>,
+}
+/// Nested message and enum types in `PmmV1Operation`.
+pub mod pmm_v1_operation {
+    #[non_exhaustive]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum PmmV1Operation {
+        #[prost(message, tag = "1")]
+        Price(super::PmmV1Price),
+        #[prost(message, tag = "2")]
+        State(super::PmmV1State),
+    }
+}
+/// state
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WeightedState {
+    #[prost(message, optional, tag = "1")]
+    pub pa: ::core::option::Option<TokenPairAmm>,
+    #[prost(uint64, tag = "2")]
+    pub block_timestamp: u64,
+    /// weights of tokens
+    #[prost(uint32, tag = "3")]
+    pub weight0: u32,
+    #[prost(uint32, tag = "4")]
+    pub weight1: u32,
+    /// total supply of lp token
+    #[prost(message, optional, tag = "5")]
+    pub supply: ::core::option::Option<super::common::Nat>,
+    /// balance of token0
+    #[prost(message, optional, tag = "6")]
+    pub reserve0: ::core::option::Option<super::common::Nat>,
+    /// balance of token1
+    #[prost(message, optional, tag = "7")]
+    pub reserve1: ::core::option::Option<super::common::Nat>,
+}
+/// weighted operation
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WeightedOperation {
+    #[prost(oneof = "weighted_operation::WeightedOperation", tags = "1")]
+    pub weighted_operation: ::core::option::Option<
+        weighted_operation::WeightedOperation,
+    >,
+}
+/// Nested message and enum types in `WeightedOperation`.
+pub mod weighted_operation {
+    #[non_exhaustive]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum WeightedOperation {
+        #[prost(message, tag = "1")]
+        State(super::WeightedState),
+    }
+}
+/// stable pool state
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StablePoolState {
+    #[prost(message, optional, tag = "1")]
+    pub pool: ::core::option::Option<TokenPoolAmm>,
+    #[prost(uint64, tag = "2")]
+    pub block_timestamp: u64,
+    /// amplification coefficient
+    #[prost(uint64, tag = "3")]
+    pub amplification: u64,
+    /// total supply of lp token
+    #[prost(message, optional, tag = "4")]
+    pub supply: ::core::option::Option<super::common::Nat>,
+    /// balances of tokens, in order of pool tokens
+    #[prost(message, repeated, tag = "5")]
+    pub reserves: ::prost::alloc::vec::Vec<super::common::Nat>,
+    /// invariant D
+    #[prost(message, optional, tag = "6")]
+    pub invariant: ::core::option::Option<super::common::Nat>,
+}
+/// mint
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StablePoolMintToken {
+    /// which pool
+    #[prost(message, optional, tag = "1")]
+    pub pool: ::core::option::Option<TokenPoolAmm>,
+    /// from account
+    #[prost(message, optional, tag = "2")]
+    pub from: ::core::option::Option<super::common::Account>,
+    /// token transfer in, in order of pool tokens
+    #[prost(message, repeated, tag = "3")]
+    pub amounts: ::prost::alloc::vec::Vec<super::common::Nat>,
+    /// lp token mint
+    #[prost(message, optional, tag = "4")]
+    pub token: ::core::option::Option<super::common::CanisterId>,
+    #[prost(message, optional, tag = "5")]
+    pub amount: ::core::option::Option<super::common::Nat>,
+    /// to account
+    #[prost(message, optional, tag = "6")]
+    pub to: ::core::option::Option<super::common::Account>,
+}
+/// burn
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StablePoolBurnToken {
+    /// which pool
+    #[prost(message, optional, tag = "1")]
+    pub pool: ::core::option::Option<TokenPoolAmm>,
+    /// from account
+    #[prost(message, optional, tag = "2")]
+    pub from: ::core::option::Option<super::common::Account>,
+    /// lp token burn
+    #[prost(message, optional, tag = "3")]
+    pub token: ::core::option::Option<super::common::CanisterId>,
+    #[prost(message, optional, tag = "4")]
+    pub amount: ::core::option::Option<super::common::Nat>,
+    /// token transfer out, in order of pool tokens
+    #[prost(message, repeated, tag = "5")]
+    pub amounts: ::prost::alloc::vec::Vec<super::common::Nat>,
+    /// to account
+    #[prost(message, optional, tag = "6")]
+    pub to: ::core::option::Option<super::common::Account>,
+    /// maybe fee
+    #[prost(message, optional, tag = "7")]
+    pub fee: ::core::option::Option<super::common::BurnFee>,
+}
+/// stable pool operation
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StablePoolOperation {
+    #[prost(oneof = "stable_pool_operation::StablePoolOperation", tags = "1, 2, 3")]
+    pub stable_pool_operation: ::core::option::Option<
+        stable_pool_operation::StablePoolOperation,
+    >,
+}
+/// Nested message and enum types in `StablePoolOperation`.
+pub mod stable_pool_operation {
+    #[non_exhaustive]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum StablePoolOperation {
+        #[prost(message, tag = "1")]
+        State(super::StablePoolState),
+        #[prost(message, tag = "2")]
+        Mint(super::StablePoolMintToken),
+        #[prost(message, tag = "3")]
+        Burn(super::StablePoolBurnToken),
+    }
+}
 /// create
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PairCreate {
@@ -22,6 +255,9 @@ pub struct PairCreate {
     pub pa: ::core::option::Option<TokenPairAmm>,
     #[prost(message, optional, tag = "2")]
     pub creator: ::core::option::Option<super::common::UserId>,
+    /// multi-asset pool, pa is the first two tokens of pool
+    #[prost(message, optional, tag = "3")]
+    pub pool: ::core::option::Option<TokenPoolAmm>,
 }
 /// remove
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -30,6 +266,9 @@ pub struct PairRemove {
     pub pa: ::core::option::Option<TokenPairAmm>,
     #[prost(message, optional, tag = "2")]
     pub remover: ::core::option::Option<super::common::UserId>,
+    /// multi-asset pool, pa is the first two tokens of pool
+    #[prost(message, optional, tag = "3")]
+    pub pool: ::core::option::Option<TokenPoolAmm>,
 }
 /// swap
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -53,6 +292,35 @@ pub struct PairSwapToken {
     /// amount got
     #[prost(message, optional, tag = "7")]
     pub amount_b: ::core::option::Option<super::common::Nat>,
+    /// fee charged, in token_a
+    #[prost(message, optional, tag = "8")]
+    pub fee: ::core::option::Option<super::common::Nat>,
+}
+/// flash loan, repaid with fee or defaulted
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PairFlashLoan {
+    /// which token pair
+    #[prost(message, optional, tag = "1")]
+    pub pa: ::core::option::Option<TokenPairAmm>,
+    /// account borrowed and repaid
+    #[prost(message, optional, tag = "2")]
+    pub borrower: ::core::option::Option<super::common::Account>,
+    /// token lent
+    #[prost(message, optional, tag = "3")]
+    pub token: ::core::option::Option<super::common::CanisterId>,
+    #[prost(message, optional, tag = "4")]
+    pub amount: ::core::option::Option<super::common::Nat>,
+    /// fee charged, in token
+    #[prost(message, optional, tag = "5")]
+    pub fee: ::core::option::Option<super::common::Nat>,
+    /// protocol part of fee
+    #[prost(message, optional, tag = "6")]
+    pub fee_to: ::core::option::Option<super::common::Account>,
+    #[prost(message, optional, tag = "7")]
+    pub protocol_fee: ::core::option::Option<super::common::Nat>,
+    /// part of amount and fee not repaid, taken by liquidity providers
+    #[prost(message, optional, tag = "8")]
+    pub defaulted: ::core::option::Option<super::common::Nat>,
 }
 /// reserve and cumulative price
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -190,10 +458,124 @@ pub mod swap_v2_operation {
         Transfer(super::SwapV2TransferToken),
     }
 }
+/// mint position
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SwapV3MintToken {
+    /// which token pair
+    #[prost(message, optional, tag = "1")]
+    pub pa: ::core::option::Option<TokenPairAmm>,
+    /// from account
+    #[prost(message, optional, tag = "2")]
+    pub from: ::core::option::Option<super::common::Account>,
+    /// price range of position
+    #[prost(sint32, tag = "3")]
+    pub tick_lower: i32,
+    #[prost(sint32, tag = "4")]
+    pub tick_upper: i32,
+    /// token transfer in
+    #[prost(message, optional, tag = "5")]
+    pub token0: ::core::option::Option<super::common::CanisterId>,
+    #[prost(message, optional, tag = "6")]
+    pub token1: ::core::option::Option<super::common::CanisterId>,
+    #[prost(message, optional, tag = "7")]
+    pub amount0: ::core::option::Option<super::common::Nat>,
+    #[prost(message, optional, tag = "8")]
+    pub amount1: ::core::option::Option<super::common::Nat>,
+    /// liquidity added
+    #[prost(message, optional, tag = "9")]
+    pub liquidity: ::core::option::Option<super::common::Nat>,
+    /// position owner
+    #[prost(message, optional, tag = "10")]
+    pub to: ::core::option::Option<super::common::Account>,
+}
+/// burn position
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SwapV3BurnToken {
+    /// which token pair
+    #[prost(message, optional, tag = "1")]
+    pub pa: ::core::option::Option<TokenPairAmm>,
+    /// position owner
+    #[prost(message, optional, tag = "2")]
+    pub from: ::core::option::Option<super::common::Account>,
+    /// price range of position
+    #[prost(sint32, tag = "3")]
+    pub tick_lower: i32,
+    #[prost(sint32, tag = "4")]
+    pub tick_upper: i32,
+    /// liquidity removed
+    #[prost(message, optional, tag = "5")]
+    pub liquidity: ::core::option::Option<super::common::Nat>,
+    /// token transfer out, include fees
+    #[prost(message, optional, tag = "6")]
+    pub token0: ::core::option::Option<super::common::CanisterId>,
+    #[prost(message, optional, tag = "7")]
+    pub token1: ::core::option::Option<super::common::CanisterId>,
+    #[prost(message, optional, tag = "8")]
+    pub amount0: ::core::option::Option<super::common::Nat>,
+    #[prost(message, optional, tag = "9")]
+    pub amount1: ::core::option::Option<super::common::Nat>,
+    /// to account
+    #[prost(message, optional, tag = "10")]
+    pub to: ::core::option::Option<super::common::Account>,
+}
+/// swap in ranges
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SwapV3SwapToken {
+    /// which token pair
+    #[prost(message, optional, tag = "1")]
+    pub pa: ::core::option::Option<TokenPairAmm>,
+    #[prost(uint64, tag = "2")]
+    pub block_timestamp: u64,
+    /// swap direction
+    #[prost(bool, tag = "3")]
+    pub zero_for_one: bool,
+    /// amount in include fee
+    #[prost(message, optional, tag = "4")]
+    pub amount_in: ::core::option::Option<super::common::Nat>,
+    #[prost(message, optional, tag = "5")]
+    pub amount_out: ::core::option::Option<super::common::Nat>,
+    #[prost(message, optional, tag = "6")]
+    pub fee: ::core::option::Option<super::common::Nat>,
+    /// state after swap
+    #[prost(message, optional, tag = "7")]
+    pub sqrt_price_x96: ::core::option::Option<super::common::Nat>,
+    #[prost(sint32, tag = "8")]
+    pub tick: i32,
+    #[prost(message, optional, tag = "9")]
+    pub liquidity: ::core::option::Option<super::common::Nat>,
+    /// balance of token0
+    #[prost(message, optional, tag = "10")]
+    pub reserve0: ::core::option::Option<super::common::Nat>,
+    /// balance of token1
+    #[prost(message, optional, tag = "11")]
+    pub reserve1: ::core::option::Option<super::common::Nat>,
+}
+/// swap v3 operation
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SwapV3Operation {
+    #[prost(oneof = "swap_v3_operation::SwapV3Operation", tags = "1, 2, 3")]
+    pub swap_v3_operation: ::core::option::Option<swap_v3_operation::SwapV3Operation>,
+}
+/// Nested message and enum types in `SwapV3Operation`.
+pub mod swap_v3_operation {
+    #[non_exhaustive]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum SwapV3Operation {
+        #[prost(message, tag = "1")]
+        Mint(super::SwapV3MintToken),
+        #[prost(message, tag = "2")]
+        Burn(super::SwapV3BurnToken),
+        #[prost(message, tag = "3")]
+        Swap(super::SwapV3SwapToken),
+    }
+}
 /// pair operation
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PairOperation {
-    #[prost(oneof = "pair_operation::PairOperation", tags = "1, 2, 16, 32")]
+    #[prost(
+        oneof = "pair_operation::PairOperation",
+        tags = "1, 2, 3, 16, 32, 48, 64, 80, 96, 112"
+    )]
     pub pair_operation: ::core::option::Option<pair_operation::PairOperation>,
 }
 /// Nested message and enum types in `PairOperation`.
@@ -205,12 +587,29 @@ pub mod pair_operation {
         Create(super::PairCreate),
         #[prost(message, tag = "2")]
         Remove(super::PairRemove),
+        #[prost(message, tag = "3")]
+        FlashLoan(super::PairFlashLoan),
         /// swap // * start at 16
         #[prost(message, tag = "16")]
         Swap(super::PairSwapToken),
         /// swap v2 // * start at 32
         #[prost(message, tag = "32")]
         SwapV2(super::SwapV2Operation),
+        /// swap v3 // * start at 48
+        #[prost(message, tag = "48")]
+        SwapV3(super::SwapV3Operation),
+        /// stable swap // * start at 64
+        #[prost(message, tag = "64")]
+        StableSwap(super::StableSwapOperation),
+        /// pmm v1 // * start at 80
+        #[prost(message, tag = "80")]
+        PmmV1(super::PmmV1Operation),
+        /// weighted // * start at 96
+        #[prost(message, tag = "96")]
+        Weighted(super::WeightedOperation),
+        /// stable pool // * start at 112
+        #[prost(message, tag = "112")]
+        StablePool(super::StablePoolOperation),
     }
 }
 /// operation
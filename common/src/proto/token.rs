@@ -22,6 +22,8 @@ pub struct WithdrawToken {
     pub amount: ::core::option::Option<super::common::Nat>,
     #[prost(message, optional, tag = "4")]
     pub to: ::core::option::Option<super::common::Account>,
+    #[prost(bytes = "bytes", optional, tag = "5")]
+    pub to_account_id: ::core::option::Option<::prost::bytes::Bytes>,
 }
 /// transfer
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -36,11 +38,29 @@ pub struct TransferToken {
     pub to: ::core::option::Option<super::common::Account>,
     #[prost(message, optional, tag = "5")]
     pub fee: ::core::option::Option<super::common::TransferFee>,
+    #[prost(message, optional, tag = "6")]
+    pub spender: ::core::option::Option<super::common::Account>,
+}
+/// approve
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ApproveToken {
+    #[prost(message, optional, tag = "1")]
+    pub token: ::core::option::Option<super::common::CanisterId>,
+    #[prost(message, optional, tag = "2")]
+    pub from: ::core::option::Option<super::common::Account>,
+    #[prost(message, optional, tag = "3")]
+    pub amount: ::core::option::Option<super::common::Nat>,
+    #[prost(message, optional, tag = "4")]
+    pub spender: ::core::option::Option<super::common::Account>,
+    #[prost(message, optional, tag = "5")]
+    pub expected_allowance: ::core::option::Option<super::common::Nat>,
+    #[prost(uint64, optional, tag = "6")]
+    pub expires_at: ::core::option::Option<u64>,
 }
 /// operation
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TokenOperation {
-    #[prost(oneof = "token_operation::TokenOperation", tags = "1, 2, 3")]
+    #[prost(oneof = "token_operation::TokenOperation", tags = "1, 2, 3, 4")]
     pub token_operation: ::core::option::Option<token_operation::TokenOperation>,
 }
 /// Nested message and enum types in `TokenOperation`.
@@ -54,6 +74,8 @@ pub mod token_operation {
         Withdraw(super::WithdrawToken),
         #[prost(message, tag = "3")]
         Transfer(super::TransferToken),
+        #[prost(message, tag = "4")]
+        Approve(super::ApproveToken),
     }
 }
 /// transaction
@@ -102,3 +102,88 @@ impl Storable for TokenAccount {
         is_fixed_size: false,
     };
 }
+
+// ============================ account identifier ============================
+
+/// Legacy account identifier of ICP ledger.
+/// The first 4 bytes is a big-endian encoding of a CRC32 checksum of the last 28 bytes.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash, Serialize, Deserialize, CandidType)]
+pub struct AccountIdentifier([u8; 32]);
+
+impl AccountIdentifier {
+    /// The account identifier of icrc1 account
+    pub fn new(account: &Account) -> Self {
+        use sha2::{Digest, Sha224};
+
+        let mut hasher = Sha224::new();
+        hasher.update(b"\x0Aaccount-id");
+        hasher.update(account.owner.as_slice());
+        hasher.update(account.effective_subaccount());
+        let hash: [u8; 28] = hasher.finalize().into();
+
+        let mut bytes = [0; 32];
+        bytes[0..4].copy_from_slice(&crc32fast::hash(&hash).to_be_bytes());
+        bytes[4..32].copy_from_slice(&hash);
+        Self(bytes)
+    }
+
+    /// Bytes of account identifier
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Check the checksum
+    pub fn is_valid(&self) -> bool {
+        self.0[0..4] == crc32fast::hash(&self.0[4..]).to_be_bytes()
+    }
+}
+
+impl From<[u8; 32]> for AccountIdentifier {
+    fn from(value: [u8; 32]) -> Self {
+        Self(value)
+    }
+}
+
+impl Display for AccountIdentifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", hex::encode(self.0))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_identifier() {
+        // the well-known account identifier of anonymous principal
+        let anonymous = Account {
+            owner: Principal::anonymous(),
+            subaccount: None,
+        };
+        let account_id = AccountIdentifier::new(&anonymous);
+        assert_eq!(
+            account_id.to_string(),
+            "1c7a48ba6a562aa9eaa2481a9049cdf0433b9738c992d698c31d8abf89cadc79"
+        );
+        assert!(account_id.is_valid());
+
+        // default subaccount is the same as none
+        let default = Account {
+            owner: Principal::anonymous(),
+            subaccount: Some(DEFAULT_SUBACCOUNT.to_owned()),
+        };
+        assert_eq!(AccountIdentifier::new(&default), account_id);
+        let other = Account {
+            owner: Principal::anonymous(),
+            subaccount: Some([1; 32]),
+        };
+        assert_ne!(AccountIdentifier::new(&other), account_id);
+
+        // broken checksum
+        let mut bytes = *account_id.as_bytes();
+        bytes[0] ^= 1;
+        assert!(!AccountIdentifier::from(bytes).is_valid());
+    }
+}
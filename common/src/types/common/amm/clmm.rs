@@ -0,0 +1,1196 @@
+/// Concentrated liquidity market maker（Concentrated Liquidity AMM）
+/// - Formula：(x + L / √Pb) * (y + L * √Pa) = L²（liquidity L is only active between price Pa and Pb）
+/// - Representative Project：Uniswap V3、PancakeSwap V3
+/// - Features
+///   - LPs choose a price range (tick range), capital is only used while the price is inside the range.
+///   - Fees are accumulated per unit of liquidity (fee growth) and are only earned by in-range positions.
+///   - Positions are not fungible, so no lp token is minted.
+#[cfg(feature = "cdk")]
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+#[cfg(feature = "cdk")]
+use std::collections::HashMap;
+
+use candid::{CandidType, Int, Nat};
+#[cfg(feature = "cdk")]
+use ic_canister_kit::types::{Bound, Storable};
+use icrc_ledger_types::icrc1::account::{Account, Subaccount};
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{ToPrimitive, Zero};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+#[allow(unused)]
+use crate::{
+    types::{BusinessError, CanisterId, SelfCanister, SwapRatio, SwapRatioView, TokenInfo, TokenPairAmm},
+    utils::{
+        math::{ZERO, zero},
+        principal::sort_tokens,
+    },
+};
+
+/// The minimum tick that may be passed to `get_sqrt_ratio_at_tick`
+pub const MIN_TICK: i32 = -887272;
+/// The maximum tick that may be passed to `get_sqrt_ratio_at_tick`
+pub const MAX_TICK: i32 = -MIN_TICK;
+
+/// sqrt price of MIN_TICK, Q64.96
+pub static MIN_SQRT_RATIO: Lazy<BigUint> = Lazy::new(|| BigUint::from(4_295_128_739_u64));
+/// sqrt price of MAX_TICK, Q64.96
+pub static MAX_SQRT_RATIO: Lazy<BigUint> = Lazy::new(|| {
+    // 1461446703485210103287273052203988822378723970342
+    (BigUint::from(0xfffd8963_u32) << 128) | BigUint::from(0xefd1fc6a506488495d951d5263988d26_u128)
+});
+
+static Q96: Lazy<BigUint> = Lazy::new(|| BigUint::from(1_u8) << 96);
+static Q128: Lazy<BigUint> = Lazy::new(|| BigUint::from(1_u8) << 128);
+
+// ========================== math ==========================
+
+pub(super) fn div_rounding_up(a: &BigUint, b: &BigUint) -> BigUint {
+    let (q, r) = (a / b, a % b);
+    if r.is_zero() { q } else { q + 1_u8 }
+}
+
+fn mul_div(a: &BigUint, b: &BigUint, denominator: &BigUint, round_up: bool) -> BigUint {
+    let product = a * b;
+    if round_up {
+        div_rounding_up(&product, denominator)
+    } else {
+        product / denominator
+    }
+}
+
+/// Calculates sqrt(1.0001^tick) * 2^96, same as uniswap v3 TickMath
+pub fn get_sqrt_ratio_at_tick(tick: i32) -> Result<BigUint, BusinessError> {
+    if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+        return Err(BusinessError::Swap("TICK_OUT_OF_RANGE".into()));
+    }
+    const MAGIC: [(u32, u128); 19] = [
+        (0x2, 0xfff97272373d413259a46990580e213a),
+        (0x4, 0xfff2e50f5f656932ef12357cf3c7fdcc),
+        (0x8, 0xffe5caca7e10e4e61c3624eaa0941cd0),
+        (0x10, 0xffcb9843d60f6159c9db58835c926644),
+        (0x20, 0xff973b41fa98c081472e6896dfb254c0),
+        (0x40, 0xff2ea16466c96a3843ec78b326b52861),
+        (0x80, 0xfe5dee046a99a2a811c461f1969c3053),
+        (0x100, 0xfcbe86c7900a88aedcffc83b479aa3a4),
+        (0x200, 0xf987a7253ac413176f2b074cf7815e54),
+        (0x400, 0xf3392b0822b70005940c7a398e4b70f3),
+        (0x800, 0xe7159475a2c29b7443b29c7fa6e889d9),
+        (0x1000, 0xd097f3bdfd2022b8845ad8f792aa5825),
+        (0x2000, 0xa9f746462d870fdf8a65dc1f90e061e5),
+        (0x4000, 0x70d869a156d2a1b890bb3df62baf32f7),
+        (0x8000, 0x31be135f97d08fd981231505542fcfa6),
+        (0x10000, 0x9aa508b5b7a84e1c677de54f3e99bc9),
+        (0x20000, 0x5d6af8dedb81196699c329225ee604),
+        (0x40000, 0x2216e584f5fa1ea926041bedfe98),
+        (0x80000, 0x48a170391f7dc42444e8fa2),
+    ];
+    let abs_tick = tick.unsigned_abs();
+    let mut ratio = if abs_tick & 0x1 != 0 {
+        BigUint::from(0xfffcb933bd6fad37aa2d162d1a594001_u128)
+    } else {
+        Q128.clone()
+    };
+    for (bit, magic) in MAGIC {
+        if abs_tick & bit != 0 {
+            ratio = (ratio * magic) >> 128;
+        }
+    }
+    if tick > 0 {
+        let max = (BigUint::from(1_u8) << 256) - 1_u8;
+        ratio = max / ratio;
+    }
+
+    // back to Q64.96, rounding up
+    let shift = BigUint::from(1_u8) << 32;
+    Ok(div_rounding_up(&ratio, &shift))
+}
+
+/// Calculates the greatest tick value such that get_sqrt_ratio_at_tick(tick) <= sqrt_price_x96
+pub fn get_tick_at_sqrt_ratio(sqrt_price_x96: &BigUint) -> Result<i32, BusinessError> {
+    if *sqrt_price_x96 < *MIN_SQRT_RATIO || *MAX_SQRT_RATIO <= *sqrt_price_x96 {
+        return Err(BusinessError::Swap("SQRT_PRICE_OUT_OF_RANGE".into()));
+    }
+    let (mut low, mut high) = (MIN_TICK, MAX_TICK);
+    while low < high {
+        let middle = low + (high - low + 1) / 2;
+        if get_sqrt_ratio_at_tick(middle)? <= *sqrt_price_x96 {
+            low = middle;
+        } else {
+            high = middle - 1;
+        }
+    }
+    Ok(low)
+}
+
+/// amount0 = L * (√Pb - √Pa) / (√Pa * √Pb)
+fn get_amount0_delta(sqrt_a: &BigUint, sqrt_b: &BigUint, liquidity: &BigUint, round_up: bool) -> BigUint {
+    let (sqrt_a, sqrt_b) = if sqrt_a > sqrt_b {
+        (sqrt_b, sqrt_a)
+    } else {
+        (sqrt_a, sqrt_b)
+    };
+    if sqrt_a.is_zero() {
+        return BigUint::zero();
+    }
+    let numerator1 = liquidity << 96;
+    let numerator2 = sqrt_b - sqrt_a;
+    if round_up {
+        div_rounding_up(&mul_div(&numerator1, &numerator2, sqrt_b, true), sqrt_a)
+    } else {
+        mul_div(&numerator1, &numerator2, sqrt_b, false) / sqrt_a
+    }
+}
+
+/// amount1 = L * (√Pb - √Pa)
+fn get_amount1_delta(sqrt_a: &BigUint, sqrt_b: &BigUint, liquidity: &BigUint, round_up: bool) -> BigUint {
+    let (sqrt_a, sqrt_b) = if sqrt_a > sqrt_b {
+        (sqrt_b, sqrt_a)
+    } else {
+        (sqrt_a, sqrt_b)
+    };
+    mul_div(liquidity, &(sqrt_b - sqrt_a), &Q96, round_up)
+}
+
+fn get_next_sqrt_price_from_amount0_rounding_up(
+    sqrt_price: &BigUint,
+    liquidity: &BigUint,
+    amount: &BigUint,
+    add: bool,
+) -> Result<BigUint, BusinessError> {
+    if amount.is_zero() {
+        return Ok(sqrt_price.clone());
+    }
+    let numerator1 = liquidity << 96;
+    let product = amount * sqrt_price;
+    let denominator = if add {
+        &numerator1 + product
+    } else {
+        if numerator1 <= product {
+            return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+        }
+        &numerator1 - product
+    };
+    Ok(mul_div(&numerator1, sqrt_price, &denominator, true))
+}
+
+fn get_next_sqrt_price_from_amount1_rounding_down(
+    sqrt_price: &BigUint,
+    liquidity: &BigUint,
+    amount: &BigUint,
+    add: bool,
+) -> Result<BigUint, BusinessError> {
+    if add {
+        Ok(sqrt_price + (amount << 96) / liquidity)
+    } else {
+        let quotient = div_rounding_up(&(amount << 96), liquidity);
+        if *sqrt_price <= quotient {
+            return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+        }
+        Ok(sqrt_price - quotient)
+    }
+}
+
+fn get_next_sqrt_price_from_input(
+    sqrt_price: &BigUint,
+    liquidity: &BigUint,
+    amount_in: &BigUint,
+    zero_for_one: bool,
+) -> Result<BigUint, BusinessError> {
+    if zero_for_one {
+        get_next_sqrt_price_from_amount0_rounding_up(sqrt_price, liquidity, amount_in, true)
+    } else {
+        get_next_sqrt_price_from_amount1_rounding_down(sqrt_price, liquidity, amount_in, true)
+    }
+}
+
+fn get_next_sqrt_price_from_output(
+    sqrt_price: &BigUint,
+    liquidity: &BigUint,
+    amount_out: &BigUint,
+    zero_for_one: bool,
+) -> Result<BigUint, BusinessError> {
+    if zero_for_one {
+        get_next_sqrt_price_from_amount1_rounding_down(sqrt_price, liquidity, amount_out, false)
+    } else {
+        get_next_sqrt_price_from_amount0_rounding_up(sqrt_price, liquidity, amount_out, false)
+    }
+}
+
+/// liquidity for amount0 between price a and b
+fn get_liquidity_for_amount0(sqrt_a: &BigUint, sqrt_b: &BigUint, amount0: &BigUint) -> BigUint {
+    let intermediate = mul_div(sqrt_a, sqrt_b, &Q96, false);
+    mul_div(amount0, &intermediate, &(sqrt_b - sqrt_a), false)
+}
+
+/// liquidity for amount1 between price a and b
+fn get_liquidity_for_amount1(sqrt_a: &BigUint, sqrt_b: &BigUint, amount1: &BigUint) -> BigUint {
+    mul_div(amount1, &Q96, &(sqrt_b - sqrt_a), false)
+}
+
+struct SwapStep {
+    sqrt_price_next: BigUint,
+    amount_in: BigUint,
+    amount_out: BigUint,
+    fee_amount: BigUint,
+}
+
+/// Computes the result of swapping some amount in, or amount out, inside a single tick range
+fn compute_swap_step(
+    sqrt_price_current: &BigUint,
+    sqrt_price_target: &BigUint,
+    liquidity: &BigUint,
+    amount_remaining: &BigUint,
+    exact_in: bool,
+    fee_rate: &SwapRatio,
+) -> Result<SwapStep, BusinessError> {
+    let n = BigUint::from(fee_rate.numerator);
+    let d = BigUint::from(fee_rate.denominator);
+    let zero_for_one = sqrt_price_current >= sqrt_price_target;
+
+    let mut amount_in = BigUint::zero();
+    let mut amount_out = BigUint::zero();
+    let sqrt_price_next = if exact_in {
+        let amount_remaining_less_fee = amount_remaining * (&d - &n) / &d;
+        amount_in = if zero_for_one {
+            get_amount0_delta(sqrt_price_target, sqrt_price_current, liquidity, true)
+        } else {
+            get_amount1_delta(sqrt_price_current, sqrt_price_target, liquidity, true)
+        };
+        if amount_in <= amount_remaining_less_fee {
+            sqrt_price_target.clone()
+        } else {
+            get_next_sqrt_price_from_input(sqrt_price_current, liquidity, &amount_remaining_less_fee, zero_for_one)?
+        }
+    } else {
+        amount_out = if zero_for_one {
+            get_amount1_delta(sqrt_price_target, sqrt_price_current, liquidity, false)
+        } else {
+            get_amount0_delta(sqrt_price_current, sqrt_price_target, liquidity, false)
+        };
+        if amount_out <= *amount_remaining {
+            sqrt_price_target.clone()
+        } else {
+            get_next_sqrt_price_from_output(sqrt_price_current, liquidity, amount_remaining, zero_for_one)?
+        }
+    };
+
+    let max = *sqrt_price_target == sqrt_price_next;
+    if zero_for_one {
+        if !max || !exact_in {
+            amount_in = get_amount0_delta(&sqrt_price_next, sqrt_price_current, liquidity, true);
+        }
+        if !max || exact_in {
+            amount_out = get_amount1_delta(&sqrt_price_next, sqrt_price_current, liquidity, false);
+        }
+    } else {
+        if !max || !exact_in {
+            amount_in = get_amount1_delta(sqrt_price_current, &sqrt_price_next, liquidity, true);
+        }
+        if !max || exact_in {
+            amount_out = get_amount0_delta(sqrt_price_current, &sqrt_price_next, liquidity, false);
+        }
+    }
+
+    // cap the output amount to not exceed the remaining output amount
+    if !exact_in && amount_out > *amount_remaining {
+        amount_out = amount_remaining.clone();
+    }
+
+    let fee_amount = if exact_in && sqrt_price_next != *sqrt_price_target {
+        // we didn't reach the target, so take the remainder of the maximum input as fee
+        amount_remaining - &amount_in
+    } else {
+        mul_div(&amount_in, &n, &(&d - &n), true)
+    };
+
+    Ok(SwapStep {
+        sqrt_price_next,
+        amount_in,
+        amount_out,
+        fee_amount,
+    })
+}
+
+fn to_int(value: &BigUint) -> BigInt {
+    BigInt::from_biguint(Sign::Plus, value.clone())
+}
+
+// ========================== types ==========================
+
+/// price range of a position, both ticks must be multiple of tick spacing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct TickRange {
+    pub lower: i32,
+    pub upper: i32,
+}
+
+impl std::fmt::Display for TickRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}, {})", self.lower, self.upper)
+    }
+}
+
+/// Initialized tick
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct SwapV3Tick {
+    pub liquidity_gross: Nat,     // the total position liquidity that references this tick
+    pub liquidity_net: Int,       // amount of net liquidity added when tick is crossed from left to right
+    pub fee_growth_outside0: Int, // fee growth per unit of liquidity on the other side of this tick, Q128
+    pub fee_growth_outside1: Int,
+}
+
+/// Range position of an account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct SwapV3Position {
+    pub owner: Account,
+    pub tick_range: TickRange,
+    pub liquidity: Nat,
+    pub fee_growth_inside0_last: Int, // Q128
+    pub fee_growth_inside1_last: Int, // Q128
+    pub tokens_owed0: Nat,
+    pub tokens_owed1: Nat,
+}
+
+/// Initialized ticks of a swap v3 pool, which are stored apart from the market maker
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwapV3Ticks(pub BTreeMap<i32, SwapV3Tick>);
+
+#[cfg(feature = "cdk")]
+impl Storable for SwapV3Ticks {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        use ic_canister_kit::common::trap;
+        Cow::Owned(trap(ic_canister_kit::functions::stable::to_bytes(self)))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        use ic_canister_kit::common::trap;
+        trap(ic_canister_kit::functions::stable::from_bytes(&bytes))
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// Positions of a swap v3 pool, which are stored apart from the market maker
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwapV3Positions(pub Vec<SwapV3Position>);
+
+#[cfg(feature = "cdk")]
+impl Storable for SwapV3Positions {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        use ic_canister_kit::common::trap;
+        Cow::Owned(trap(ic_canister_kit::functions::stable::to_bytes(self)))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        use ic_canister_kit::common::trap;
+        trap(ic_canister_kit::functions::stable::from_bytes(&bytes))
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// The amounts of a position changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapV3PositionChanged {
+    pub amount0: Nat,
+    pub amount1: Nat,
+}
+
+/// The result of a swap
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapV3Swapped {
+    pub amount_in: Nat, // include fee
+    pub amount_out: Nat,
+    pub fee: Nat,          // include protocol fee
+    pub protocol_fee: Nat, // which should be transferred to swap fee to
+}
+
+/// The required data under the current algorithm processing fee
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct SwapV3MarketMaker {
+    pub subaccount: Subaccount, // ! fixed. Fund balance storage location self_canister_id.subaccount
+    pub fee_rate: SwapRatio,    // ! fixed. Transaction rates
+    pub tick_spacing: i32,      // ! fixed. Positions can only use ticks that are multiple of this
+
+    pub token0: CanisterId, // ! Canister_id of the current token0
+    pub token1: CanisterId, // ! Canister_id of the current token1
+    pub reserve0: Nat,      // ! The current balance deposited by token0, include fees owed to positions
+    pub reserve1: Nat,      // ! The current balance deposited by token1, include fees owed to positions
+    pub block_timestamp_last: u64,
+
+    pub sqrt_price_x96: Nat, // current sqrt price, Q64.96. zero means not initialized
+    pub tick: i32,           // current tick
+    pub liquidity: Nat,      // in range liquidity
+
+    pub fee_growth_global0: Nat, // Q128
+    pub fee_growth_global1: Nat, // Q128
+
+    pub ticks: BTreeMap<i32, SwapV3Tick>,
+    pub positions: Vec<SwapV3Position>,
+
+    pub protocol_fee: Option<SwapRatio>, // The ratio of swap fee which belongs to swap fee to
+}
+
+impl SwapV3MarketMaker {
+    pub fn new(
+        subaccount: Subaccount,
+        fee_rate: SwapRatio,
+        tick_spacing: i32,
+        token0: CanisterId,
+        token1: CanisterId,
+        protocol_fee: Option<SwapRatio>,
+    ) -> Self {
+        Self {
+            subaccount,
+            fee_rate,
+            tick_spacing,
+            token0,
+            token1,
+            reserve0: zero(),
+            reserve1: zero(),
+            block_timestamp_last: 0,
+            sqrt_price_x96: zero(),
+            tick: 0,
+            liquidity: zero(),
+            fee_growth_global0: zero(),
+            fee_growth_global1: zero(),
+            ticks: Default::default(),
+            positions: vec![],
+            protocol_fee,
+        }
+    }
+
+    pub fn replace_protocol_fee(&mut self, protocol_fee: Option<SwapRatio>) -> Option<SwapRatio> {
+        std::mem::replace(&mut self.protocol_fee, protocol_fee)
+    }
+
+    /// Take out ticks and positions before the market maker is stored
+    pub fn take_ticks_and_positions(&mut self) -> (SwapV3Ticks, SwapV3Positions) {
+        (
+            SwapV3Ticks(std::mem::take(&mut self.ticks)),
+            SwapV3Positions(std::mem::take(&mut self.positions)),
+        )
+    }
+
+    /// Put back ticks and positions after the market maker is loaded
+    pub fn restore_ticks_and_positions(&mut self, ticks: SwapV3Ticks, positions: SwapV3Positions) {
+        self.ticks = ticks.0;
+        self.positions = positions.0;
+    }
+
+    #[cfg(feature = "cdk")]
+    pub fn dummy_tokens(
+        &self,
+        _tokens: &HashMap<CanisterId, Cow<'_, TokenInfo>>,
+        _pa: &TokenPairAmm,
+    ) -> Vec<TokenInfo> {
+        vec![] // positions are not fungible
+    }
+
+    pub fn accounts(&self, self_canister: &SelfCanister) -> Vec<Account> {
+        vec![Account {
+            owner: self_canister.id(),
+            subaccount: Some(self.subaccount),
+        }]
+    }
+
+    pub fn dummy_canisters(&self) -> Vec<CanisterId> {
+        vec![]
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.sqrt_price_x96 != *ZERO
+    }
+
+    pub fn check_tick_range(&self, tick_range: &TickRange) -> Result<(), BusinessError> {
+        if tick_range.upper <= tick_range.lower {
+            return Err(BusinessError::Liquidity("TICK_RANGE_INVALID".into()));
+        }
+        if tick_range.lower < MIN_TICK || MAX_TICK < tick_range.upper {
+            return Err(BusinessError::Liquidity("TICK_OUT_OF_RANGE".into()));
+        }
+        if tick_range.lower % self.tick_spacing != 0 || tick_range.upper % self.tick_spacing != 0 {
+            return Err(BusinessError::Liquidity("TICK_SPACING".into()));
+        }
+        Ok(())
+    }
+
+    pub fn get_position(&self, owner: &Account, tick_range: &TickRange) -> Option<&SwapV3Position> {
+        self.positions
+            .iter()
+            .find(|p| p.owner == *owner && p.tick_range == *tick_range)
+    }
+
+    pub fn check_position_removable(
+        &self,
+        from: &Account,
+        tick_range: &TickRange,
+        liquidity: &Nat,
+    ) -> Result<(), BusinessError> {
+        let position = self
+            .get_position(from, tick_range)
+            .ok_or_else(|| BusinessError::Liquidity("POSITION_NOT_EXIST".into()))?;
+        if position.liquidity < *liquidity {
+            return Err(BusinessError::Liquidity("INSUFFICIENT_LIQUIDITY".into()));
+        }
+        Ok(())
+    }
+
+    /// Initial price given by the pool creator, inverted if the creator's token order is reversed
+    pub fn initial_sqrt_price(sqrt_price_x96: &Nat, inverted: bool) -> Result<BigUint, BusinessError> {
+        if *sqrt_price_x96 == *ZERO {
+            return Err(BusinessError::Liquidity("SQRT_PRICE_OUT_OF_RANGE".into()));
+        }
+        let sqrt_price_x96 = if inverted {
+            (BigUint::from(1_u8) << 192) / &sqrt_price_x96.0
+        } else {
+            sqrt_price_x96.0.clone()
+        };
+        if sqrt_price_x96 < *MIN_SQRT_RATIO || *MAX_SQRT_RATIO <= sqrt_price_x96 {
+            return Err(BusinessError::Liquidity("SQRT_PRICE_OUT_OF_RANGE".into()));
+        }
+        Ok(sqrt_price_x96)
+    }
+
+    pub fn initialize(&mut self, sqrt_price_x96: BigUint) -> Result<(), BusinessError> {
+        if self.is_initialized() {
+            return Err(BusinessError::Liquidity("ALREADY_INITIALIZED".into()));
+        }
+        self.tick = get_tick_at_sqrt_ratio(&sqrt_price_x96)?;
+        self.sqrt_price_x96 = Nat::from(sqrt_price_x96);
+        Ok(())
+    }
+
+    /// The max liquidity by desired amounts at the current price
+    pub fn get_liquidity_for_amounts(
+        &self,
+        tick_range: &TickRange,
+        amount0: &Nat,
+        amount1: &Nat,
+    ) -> Result<Nat, BusinessError> {
+        let sqrt_price = &self.sqrt_price_x96.0;
+        let sqrt_a = get_sqrt_ratio_at_tick(tick_range.lower)?;
+        let sqrt_b = get_sqrt_ratio_at_tick(tick_range.upper)?;
+        let liquidity = if *sqrt_price <= sqrt_a {
+            get_liquidity_for_amount0(&sqrt_a, &sqrt_b, &amount0.0)
+        } else if *sqrt_price < sqrt_b {
+            let liquidity0 = get_liquidity_for_amount0(sqrt_price, &sqrt_b, &amount0.0);
+            let liquidity1 = get_liquidity_for_amount1(&sqrt_a, sqrt_price, &amount1.0);
+            liquidity0.min(liquidity1)
+        } else {
+            get_liquidity_for_amount1(&sqrt_a, &sqrt_b, &amount1.0)
+        };
+        Ok(Nat::from(liquidity))
+    }
+
+    /// The token amounts of liquidity at the current price
+    pub fn get_amounts_for_liquidity(
+        &self,
+        tick_range: &TickRange,
+        liquidity: &Nat,
+        round_up: bool,
+    ) -> Result<SwapV3PositionChanged, BusinessError> {
+        let sqrt_price = &self.sqrt_price_x96.0;
+        let sqrt_a = get_sqrt_ratio_at_tick(tick_range.lower)?;
+        let sqrt_b = get_sqrt_ratio_at_tick(tick_range.upper)?;
+        let liquidity = &liquidity.0;
+        let (amount0, amount1) = if self.tick < tick_range.lower {
+            (
+                get_amount0_delta(&sqrt_a, &sqrt_b, liquidity, round_up),
+                BigUint::zero(),
+            )
+        } else if self.tick < tick_range.upper {
+            (
+                get_amount0_delta(sqrt_price, &sqrt_b, liquidity, round_up),
+                get_amount1_delta(&sqrt_a, sqrt_price, liquidity, round_up),
+            )
+        } else {
+            (
+                BigUint::zero(),
+                get_amount1_delta(&sqrt_a, &sqrt_b, liquidity, round_up),
+            )
+        };
+        Ok(SwapV3PositionChanged {
+            amount0: Nat::from(amount0),
+            amount1: Nat::from(amount1),
+        })
+    }
+
+    fn update_tick(&mut self, tick: i32, liquidity_delta: &BigInt, upper: bool) -> Result<(), BusinessError> {
+        let (tick_current, global0, global1) = (
+            self.tick,
+            to_int(&self.fee_growth_global0.0),
+            to_int(&self.fee_growth_global1.0),
+        );
+        let info = self.ticks.entry(tick).or_insert_with(|| SwapV3Tick {
+            liquidity_gross: zero(),
+            liquidity_net: Int::from(0),
+            fee_growth_outside0: Int::from(0),
+            fee_growth_outside1: Int::from(0),
+        });
+        let gross_before = to_int(&info.liquidity_gross.0);
+        let gross_after = &gross_before + liquidity_delta;
+        let gross_after = gross_after
+            .to_biguint()
+            .ok_or_else(|| BusinessError::Liquidity("INSUFFICIENT_LIQUIDITY".into()))?;
+        if gross_before.is_zero() && tick <= tick_current {
+            // by convention, we assume that all growth before a tick was initialized happened below the tick
+            info.fee_growth_outside0 = Int::from(global0);
+            info.fee_growth_outside1 = Int::from(global1);
+        }
+        info.liquidity_gross = Nat::from(gross_after);
+        info.liquidity_net = if upper {
+            Int::from(info.liquidity_net.0.clone() - liquidity_delta)
+        } else {
+            Int::from(info.liquidity_net.0.clone() + liquidity_delta)
+        };
+        Ok(())
+    }
+
+    fn get_fee_growth_inside(&self, tick_range: &TickRange) -> (BigInt, BigInt) {
+        let global0 = to_int(&self.fee_growth_global0.0);
+        let global1 = to_int(&self.fee_growth_global1.0);
+        let outside = |tick: &i32| {
+            self.ticks
+                .get(tick)
+                .map(|info| (info.fee_growth_outside0.0.clone(), info.fee_growth_outside1.0.clone()))
+                .unwrap_or_default()
+        };
+        let (lower0, lower1) = outside(&tick_range.lower);
+        let (upper0, upper1) = outside(&tick_range.upper);
+        let (below0, below1) = if tick_range.lower <= self.tick {
+            (lower0, lower1)
+        } else {
+            (&global0 - lower0, &global1 - lower1)
+        };
+        let (above0, above1) = if self.tick < tick_range.upper {
+            (upper0, upper1)
+        } else {
+            (&global0 - upper0, &global1 - upper1)
+        };
+        (global0 - below0 - above0, global1 - below1 - above1)
+    }
+
+    /// Add or remove liquidity of position, return the token amounts should be transferred
+    pub fn modify_position(
+        &mut self,
+        owner: Account,
+        tick_range: TickRange,
+        liquidity_delta: BigInt,
+    ) -> Result<SwapV3PositionChanged, BusinessError> {
+        self.check_tick_range(&tick_range)?;
+        if !self.is_initialized() {
+            return Err(BusinessError::Liquidity("NOT_INITIALIZED".into()));
+        }
+        let add = liquidity_delta.sign() != Sign::Minus;
+        let liquidity_abs = Nat::from(liquidity_delta.magnitude().clone());
+
+        // 1. update ticks
+        if !liquidity_delta.is_zero() {
+            self.update_tick(tick_range.lower, &liquidity_delta, false)?;
+            self.update_tick(tick_range.upper, &liquidity_delta, true)?;
+        }
+
+        // 2. update position and fees
+        let (inside0, inside1) = self.get_fee_growth_inside(&tick_range);
+        let index = match self
+            .positions
+            .iter()
+            .position(|p| p.owner == owner && p.tick_range == tick_range)
+        {
+            Some(index) => index,
+            None => {
+                if !add || liquidity_delta.is_zero() {
+                    return Err(BusinessError::Liquidity("POSITION_NOT_EXIST".into()));
+                }
+                self.positions.push(SwapV3Position {
+                    owner,
+                    tick_range,
+                    liquidity: zero(),
+                    fee_growth_inside0_last: Int::from(inside0.clone()),
+                    fee_growth_inside1_last: Int::from(inside1.clone()),
+                    tokens_owed0: zero(),
+                    tokens_owed1: zero(),
+                });
+                self.positions.len() - 1
+            }
+        };
+        {
+            let position = &mut self.positions[index];
+            let liquidity = to_int(&position.liquidity.0);
+            let owed = |inside: &BigInt, last: &BigInt| {
+                let owed: BigInt = (inside - last) * &liquidity;
+                let owed: BigInt = owed >> 128_u32;
+                owed.to_biguint().unwrap_or_default()
+            };
+            position.tokens_owed0 += Nat::from(owed(&inside0, &position.fee_growth_inside0_last.0));
+            position.tokens_owed1 += Nat::from(owed(&inside1, &position.fee_growth_inside1_last.0));
+            position.fee_growth_inside0_last = Int::from(inside0);
+            position.fee_growth_inside1_last = Int::from(inside1);
+            let next = (liquidity + &liquidity_delta)
+                .to_biguint()
+                .ok_or_else(|| BusinessError::Liquidity("INSUFFICIENT_LIQUIDITY".into()))?;
+            position.liquidity = Nat::from(next);
+        }
+
+        // 3. clear ticks that are no longer needed
+        for tick in [tick_range.lower, tick_range.upper] {
+            if self.ticks.get(&tick).is_some_and(|info| info.liquidity_gross == *ZERO) {
+                self.ticks.remove(&tick);
+            }
+        }
+
+        // 4. update in range liquidity
+        if tick_range.lower <= self.tick && self.tick < tick_range.upper {
+            let next = (to_int(&self.liquidity.0) + &liquidity_delta)
+                .to_biguint()
+                .ok_or_else(|| BusinessError::Liquidity("INSUFFICIENT_LIQUIDITY".into()))?;
+            self.liquidity = Nat::from(next);
+        }
+
+        // mint rounding up and burn rounding down
+        self.get_amounts_for_liquidity(&tick_range, &liquidity_abs, add)
+    }
+
+    /// take all fees and principal owed to position, the empty position is removed
+    pub fn collect_position(&mut self, owner: &Account, tick_range: &TickRange) -> SwapV3PositionChanged {
+        let index = self
+            .positions
+            .iter()
+            .position(|p| p.owner == *owner && p.tick_range == *tick_range);
+        let Some(index) = index else {
+            return SwapV3PositionChanged {
+                amount0: zero(),
+                amount1: zero(),
+            };
+        };
+        let position = &mut self.positions[index];
+        let collected = SwapV3PositionChanged {
+            amount0: std::mem::replace(&mut position.tokens_owed0, zero()),
+            amount1: std::mem::replace(&mut position.tokens_owed1, zero()),
+        };
+        if position.liquidity == *ZERO {
+            self.positions.remove(index);
+        }
+        collected
+    }
+
+    /// Position burned amounts are owed to position until collected
+    pub fn owe_position(&mut self, owner: &Account, tick_range: &TickRange, changed: &SwapV3PositionChanged) {
+        if let Some(position) = self
+            .positions
+            .iter_mut()
+            .find(|p| p.owner == *owner && p.tick_range == *tick_range)
+        {
+            position.tokens_owed0 += changed.amount0.clone();
+            position.tokens_owed1 += changed.amount1.clone();
+        }
+    }
+
+    fn next_initialized_tick(&self, zero_for_one: bool) -> (i32, bool) {
+        let next = if zero_for_one {
+            self.ticks.range(..=self.tick).next_back()
+        } else {
+            self.ticks.range(self.tick + 1..).next()
+        };
+        match next {
+            Some((tick, _)) => (*tick, true),
+            None => (if zero_for_one { MIN_TICK } else { MAX_TICK }, false),
+        }
+    }
+
+    fn cross_tick(&mut self, tick: i32) -> BigInt {
+        let global0 = to_int(&self.fee_growth_global0.0);
+        let global1 = to_int(&self.fee_growth_global1.0);
+        match self.ticks.get_mut(&tick) {
+            Some(info) => {
+                info.fee_growth_outside0 = Int::from(&global0 - &info.fee_growth_outside0.0);
+                info.fee_growth_outside1 = Int::from(&global1 - &info.fee_growth_outside1.0);
+                info.liquidity_net.0.clone()
+            }
+            None => BigInt::zero(),
+        }
+    }
+
+    fn accrue_fee(&mut self, zero_for_one: bool, fee_amount: &BigUint) {
+        if self.liquidity == *ZERO || fee_amount.is_zero() {
+            return;
+        }
+        let growth = Nat::from((fee_amount << 128) / &self.liquidity.0);
+        if zero_for_one {
+            self.fee_growth_global0 += growth;
+        } else {
+            self.fee_growth_global1 += growth;
+        }
+    }
+
+    /// Swap inside ranges, exact_in means amount is the input amount or else it is the output amount
+    pub fn swap(
+        &mut self,
+        zero_for_one: bool,
+        exact_in: bool,
+        amount: &Nat,
+        fee_on: bool,
+    ) -> Result<SwapV3Swapped, BusinessError> {
+        if *amount == *ZERO {
+            return Err(BusinessError::Swap(
+                if exact_in {
+                    "INSUFFICIENT_INPUT_AMOUNT"
+                } else {
+                    "INSUFFICIENT_OUTPUT_AMOUNT"
+                }
+                .into(),
+            ));
+        }
+        if !self.is_initialized() || self.positions.is_empty() {
+            return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+        }
+        let sqrt_price_limit = if zero_for_one {
+            MIN_SQRT_RATIO.clone() + 1_u8
+        } else {
+            MAX_SQRT_RATIO.clone() - 1_u8
+        };
+
+        let mut remaining = amount.0.clone();
+        let mut amount_in = BigUint::zero();
+        let mut amount_out = BigUint::zero();
+        let mut fee = BigUint::zero();
+        let mut protocol_fee = BigUint::zero();
+        while !remaining.is_zero() && self.sqrt_price_x96.0 != sqrt_price_limit {
+            let sqrt_price_start = self.sqrt_price_x96.0.clone();
+            let (tick_next, initialized) = self.next_initialized_tick(zero_for_one);
+            let sqrt_price_next = get_sqrt_ratio_at_tick(tick_next)?;
+            let sqrt_price_target = if zero_for_one {
+                sqrt_price_next.clone().max(sqrt_price_limit.clone())
+            } else {
+                sqrt_price_next.clone().min(sqrt_price_limit.clone())
+            };
+
+            let step = compute_swap_step(
+                &sqrt_price_start,
+                &sqrt_price_target,
+                &self.liquidity.0,
+                &remaining,
+                exact_in,
+                &self.fee_rate,
+            )?;
+            if exact_in {
+                remaining -= &step.amount_in + &step.fee_amount;
+            } else {
+                remaining -= &step.amount_out;
+            }
+            amount_in += &step.amount_in + &step.fee_amount;
+            amount_out += &step.amount_out;
+            fee += &step.fee_amount;
+
+            // protocol fee is split from the swap fee
+            let mut lp_fee = step.fee_amount;
+            if let (true, Some(ratio)) = (fee_on, &self.protocol_fee) {
+                let delta = &lp_fee * ratio.numerator / ratio.denominator;
+                lp_fee -= &delta;
+                protocol_fee += delta;
+            }
+            self.accrue_fee(zero_for_one, &lp_fee);
+
+            self.sqrt_price_x96 = Nat::from(step.sqrt_price_next.clone());
+            if step.sqrt_price_next == sqrt_price_next {
+                if initialized {
+                    let mut liquidity_net = self.cross_tick(tick_next);
+                    if zero_for_one {
+                        liquidity_net = -liquidity_net;
+                    }
+                    let next = (to_int(&self.liquidity.0) + liquidity_net)
+                        .to_biguint()
+                        .ok_or_else(|| BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()))?;
+                    self.liquidity = Nat::from(next);
+                }
+                self.tick = if zero_for_one { tick_next - 1 } else { tick_next };
+            } else if step.sqrt_price_next != sqrt_price_start {
+                self.tick = get_tick_at_sqrt_ratio(&step.sqrt_price_next)?;
+            }
+        }
+
+        // all amount must be consumed
+        if !remaining.is_zero() {
+            return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+        }
+        let (reserve_out, _) = if zero_for_one {
+            (&self.reserve1, &self.reserve0)
+        } else {
+            (&self.reserve0, &self.reserve1)
+        };
+        if reserve_out.0 < amount_out {
+            return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+        }
+
+        Ok(SwapV3Swapped {
+            amount_in: Nat::from(amount_in),
+            amount_out: Nat::from(amount_out),
+            fee: Nat::from(fee),
+            protocol_fee: Nat::from(protocol_fee),
+        })
+    }
+
+    /// The extra paid input token is accrued as fee of in range positions
+    pub fn donate(&mut self, zero_for_one: bool, amount: &Nat) {
+        self.accrue_fee(zero_for_one, &amount.0);
+    }
+
+    // given an input amount of an asset and pair reserves, returns the maximum output amount of the other asset
+    pub fn get_amount_out(
+        &self,
+        self_canister: &SelfCanister,
+        amount_in: &Nat,
+        token_in: CanisterId,
+        token_out: CanisterId,
+    ) -> Result<(Account, Nat), BusinessError> {
+        let pool_account = Account {
+            owner: self_canister.id(),
+            subaccount: Some(self.subaccount),
+        };
+        if token_in == token_out || (token_in != self.token0 && token_in != self.token1) {
+            return Err(BusinessError::Swap("INVALID_TOKEN".into()));
+        }
+        let swapped = self.clone().swap(token_in == self.token0, true, amount_in, false)?;
+        Ok((pool_account, swapped.amount_out))
+    }
+
+    // given an output amount of an asset and pair reserves, returns a required input amount of the other asset
+    pub fn get_amount_in(
+        &self,
+        self_canister: &SelfCanister,
+        amount_out: &Nat,
+        token_in: CanisterId,
+        token_out: CanisterId,
+    ) -> Result<(Account, Nat), BusinessError> {
+        let pool_account = Account {
+            owner: self_canister.id(),
+            subaccount: Some(self.subaccount),
+        };
+        if token_in == token_out || (token_in != self.token0 && token_in != self.token1) {
+            return Err(BusinessError::Swap("INVALID_TOKEN".into()));
+        }
+        let swapped = self.clone().swap(token_in == self.token0, false, amount_out, false)?;
+        if swapped.amount_out < *amount_out {
+            return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+        }
+        Ok((pool_account, swapped.amount_in))
+    }
+
+    pub fn removable(&self) -> bool {
+        self.liquidity == *ZERO && self.positions.is_empty()
+    }
+
+    /// token1 per token0
+    fn price(&self) -> Option<f64> {
+        let sqrt_price = self.sqrt_price_x96.0.to_f64()? / Q96.to_f64()?;
+        Some(sqrt_price * sqrt_price)
+    }
+
+    pub fn swap_to(&self, from: &CanisterId, from_amount: f64) -> Option<(CanisterId, f64)> {
+        if !self.is_initialized() || self.liquidity == *ZERO {
+            return None;
+        }
+        let price = self.price()?;
+        if self.token0 == *from {
+            Some((self.token1, from_amount * price))
+        } else if self.token1 == *from {
+            Some((self.token0, from_amount / price))
+        } else {
+            None
+        }
+    }
+
+    pub fn get_reserve(&self, token: &CanisterId) -> Option<f64> {
+        if self.token0 == *token {
+            self.reserve0.0.to_f64()
+        } else if self.token1 == *token {
+            self.reserve1.0.to_f64()
+        } else {
+            None
+        }
+    }
+
+    pub fn get_fee(&self, amount_in: f64) -> f64 {
+        let n = self.fee_rate.numerator as f64;
+        let d = self.fee_rate.denominator as f64;
+        amount_in * n / d
+    }
+
+    pub fn get_subaccount(&self) -> &Subaccount {
+        &self.subaccount
+    }
+}
+
+// ========================== view ==========================
+
+/// tick view
+#[derive(Debug, Serialize, Deserialize, Clone, CandidType)]
+pub struct SwapV3TickView {
+    tick: i32,
+    liquidity_gross: String,
+    liquidity_net: String,
+}
+
+/// position view
+#[derive(Debug, Serialize, Deserialize, Clone, CandidType)]
+pub struct SwapV3PositionView {
+    owner: Account,
+    tick_range: TickRange,
+    liquidity: String,
+    tokens_owed0: String,
+    tokens_owed1: String,
+}
+
+/// swap v3
+#[derive(Debug, Serialize, Deserialize, Clone, CandidType)]
+pub struct SwapV3MarketMakerView {
+    subaccount: String,
+    fee_rate: SwapRatioView,
+    tick_spacing: i32,
+
+    token0: String,
+    token1: String,
+    reserve0: String,
+    reserve1: String,
+    block_timestamp_last: u64,
+
+    sqrt_price_x96: String,
+    tick: i32,
+    liquidity: String,
+
+    fee_growth_global0: String,
+    fee_growth_global1: String,
+
+    ticks: Vec<SwapV3TickView>,
+    positions: Vec<SwapV3PositionView>,
+
+    protocol_fee: Option<SwapRatioView>,
+}
+
+impl From<SwapV3MarketMaker> for SwapV3MarketMakerView {
+    fn from(value: SwapV3MarketMaker) -> Self {
+        Self {
+            subaccount: hex::encode(value.subaccount),
+            fee_rate: value.fee_rate.into(),
+            tick_spacing: value.tick_spacing,
+            token0: value.token0.to_string(),
+            token1: value.token1.to_string(),
+            reserve0: value.reserve0.to_string(),
+            reserve1: value.reserve1.to_string(),
+            block_timestamp_last: value.block_timestamp_last,
+            sqrt_price_x96: value.sqrt_price_x96.to_string(),
+            tick: value.tick,
+            liquidity: value.liquidity.to_string(),
+            fee_growth_global0: value.fee_growth_global0.to_string(),
+            fee_growth_global1: value.fee_growth_global1.to_string(),
+            ticks: value
+                .ticks
+                .into_iter()
+                .map(|(tick, info)| SwapV3TickView {
+                    tick,
+                    liquidity_gross: info.liquidity_gross.to_string(),
+                    liquidity_net: info.liquidity_net.to_string(),
+                })
+                .collect(),
+            positions: value
+                .positions
+                .into_iter()
+                .map(|p| SwapV3PositionView {
+                    owner: p.owner,
+                    tick_range: p.tick_range,
+                    liquidity: p.liquidity.to_string(),
+                    tokens_owed0: p.tokens_owed0.to_string(),
+                    tokens_owed1: p.tokens_owed1.to_string(),
+                })
+                .collect(),
+            protocol_fee: value.protocol_fee.map(|f| f.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick_math() {
+        assert_eq!(get_sqrt_ratio_at_tick(0).unwrap(), *Q96);
+        assert_eq!(get_sqrt_ratio_at_tick(MIN_TICK).unwrap(), *MIN_SQRT_RATIO);
+        assert_eq!(get_sqrt_ratio_at_tick(MAX_TICK).unwrap(), *MAX_SQRT_RATIO);
+        assert!(get_sqrt_ratio_at_tick(MAX_TICK + 1).is_err());
+
+        for tick in [-887_000, -60, -1, 0, 1, 60, 887_000] {
+            let sqrt_price = get_sqrt_ratio_at_tick(tick).unwrap();
+            assert_eq!(get_tick_at_sqrt_ratio(&sqrt_price).unwrap(), tick);
+            assert_eq!(get_tick_at_sqrt_ratio(&(sqrt_price + 1_u8)).unwrap(), tick);
+        }
+    }
+
+    #[test]
+    fn test_swap() {
+        let token0 = CanisterId::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai").unwrap();
+        let token1 = CanisterId::from_text("mxzaz-hqaaa-aaaar-qaada-cai").unwrap();
+        let owner = Account {
+            owner: token0,
+            subaccount: None,
+        };
+        let mut maker = SwapV3MarketMaker::new([0; 32], SwapRatio::new(3, 1_000), 60, token0, token1, None);
+
+        // price 1:1
+        let amount = Nat::from(1_000_000_000_u64);
+        let price = Nat::from(Q96.clone());
+        assert_eq!(SwapV3MarketMaker::initial_sqrt_price(&price, true).unwrap(), *Q96);
+        assert!(SwapV3MarketMaker::initial_sqrt_price(&Nat::from(1_u8), false).is_err());
+        maker
+            .initialize(SwapV3MarketMaker::initial_sqrt_price(&price, false).unwrap())
+            .unwrap();
+        assert_eq!(maker.tick, 0);
+
+        let tick_range = TickRange {
+            lower: -600,
+            upper: 600,
+        };
+        let liquidity = maker.get_liquidity_for_amounts(&tick_range, &amount, &amount).unwrap();
+        let minted = maker.modify_position(owner, tick_range, to_int(&liquidity.0)).unwrap();
+        assert!(minted.amount0 <= amount && minted.amount1 <= amount);
+        assert_eq!(maker.liquidity, liquidity);
+
+        // ticks and positions are stored apart and put back
+        let stored = maker.clone();
+        let (ticks, positions) = maker.take_ticks_and_positions();
+        assert!(maker.ticks.is_empty() && maker.positions.is_empty());
+        maker.restore_ticks_and_positions(ticks, positions);
+        assert_eq!(maker, stored);
+
+        maker.reserve0 = minted.amount0.clone();
+        maker.reserve1 = minted.amount1.clone();
+
+        // exact in and exact out should be consistent
+        let amount_in = Nat::from(10_000_000_u64);
+        let swapped = maker.clone().swap(true, true, &amount_in, false).unwrap();
+        assert_eq!(swapped.amount_in, amount_in);
+        let reverse = maker.clone().swap(true, false, &swapped.amount_out, false).unwrap();
+        assert!(reverse.amount_in <= amount_in);
+
+        // the range is concentrated, so the output is much better than the constant product
+        let v2_out = amount_in.clone() * 997_u32 * minted.amount1.clone()
+            / (minted.amount0.clone() * 1_000_u32 + amount_in.clone() * 997_u32);
+        assert!(v2_out < swapped.amount_out);
+
+        // swap out of range
+        assert!(maker.clone().swap(true, false, &minted.amount1, false).is_err());
+
+        // fee is earned by position
+        maker.swap(true, true, &amount_in, false).unwrap();
+        let burned = maker.modify_position(owner, tick_range, -to_int(&liquidity.0)).unwrap();
+        maker.owe_position(&owner, &tick_range, &burned);
+        let collected = maker.collect_position(&owner, &tick_range);
+        assert!(collected.amount0 > burned.amount0);
+        assert!(maker.removable());
+    }
+}
@@ -9,6 +9,7 @@
 use std::borrow::Cow;
 #[cfg(feature = "cdk")]
 use std::collections::HashMap;
+use std::collections::VecDeque;
 
 use candid::{CandidType, Nat};
 use icrc_ledger_types::icrc1::account::{Account, Subaccount};
@@ -30,7 +31,7 @@ use crate::{
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
 pub struct SwapV2MarketMaker {
     pub subaccount: Subaccount, // ! fixed. Fund balance storage location self_canister_id.subaccount
-    pub fee_rate: SwapRatio,    // ! fixed. Transaction rates
+    pub fee_rate: SwapRatio,    // ! fixed. Transaction rates, the base rate if no dynamic fee
 
     pub token0: CanisterId, // ! Canister_id of the current token0
     pub token1: CanisterId, // ! Canister_id of the current token1
@@ -45,6 +46,109 @@ pub struct SwapV2MarketMaker {
 
     pub lp: PoolLp, // lp token information, Once the new pool is successfully created, other data cannot be changed except for supply
     pub protocol_fee: Option<SwapRatio>, // The processing fee and lp fee for the agreement sharing should be equal to 1
+
+    #[serde(default)]
+    pub dynamic_fee: Option<SwapV2DynamicFee>, // The fee moves with recent price movement
+
+    #[serde(default)]
+    pub observations: VecDeque<SwapV2Observation>, // Ring buffer of cumulative prices for twap
+
+    #[serde(default)]
+    pub lent0: Nat, // ! The amount of token0 lent by flash loan and not settled, counted in reserve0
+    #[serde(default)]
+    pub lent1: Nat, // ! The amount of token1 lent by flash loan and not settled, counted in reserve1
+}
+
+/// The max count of observations of a pool
+pub const SWAP_V2_OBSERVATIONS_CAPACITY: usize = 128;
+/// The min interval between observations, 1 minute
+pub const SWAP_V2_OBSERVATION_INTERVAL_NS: u64 = 60_000_000_000;
+/// The max window of twap, which is always covered by observations of a busy pool
+pub const SWAP_V2_MAX_TWAP_WINDOW_NS: u64 =
+    (SWAP_V2_OBSERVATIONS_CAPACITY as u64 - 1) * SWAP_V2_OBSERVATION_INTERVAL_NS;
+
+/// Cumulative prices at the time
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct SwapV2Observation {
+    pub timestamp: u64,
+    pub price0_cumulative: Nat,
+    pub price1_cumulative: Nat,
+}
+
+/// Time weighted average price of a pool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct SwapV2Twap {
+    pub token0: CanisterId,
+    pub token1: CanisterId,
+    /// prices are scaled by 2^price_cumulative_exponent
+    pub price_cumulative_exponent: u8,
+    /// how many token1 per token0
+    pub price0: Nat,
+    /// how many token0 per token1
+    pub price1: Nat,
+    /// the actual window, from the observation to now, not longer than the asked window by more than 1 interval or 10%
+    pub window_ns: u64,
+}
+
+/// Arg of dynamic fee policy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct SwapV2DynamicFeeArg {
+    /// the lowest fee, charged when price is calm
+    pub floor: SwapRatio,
+    /// the highest fee, charged when price moves sharply
+    pub ceiling: SwapRatio,
+    /// the deviation of spot price from twap, at which ceiling is charged
+    pub max_deviation: SwapRatio,
+    /// the window of twap of observations
+    pub window_ns: u64,
+}
+
+/// Dynamic fee policy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct SwapV2DynamicFee {
+    pub floor: SwapRatio,
+    pub ceiling: SwapRatio,
+    pub max_deviation: SwapRatio,
+    pub window_ns: u64,
+
+    pub fee_rate: SwapRatio, // current fee rate, updated after each state changed
+}
+
+const DYNAMIC_FEE_DENOMINATOR: u64 = 1_000_000;
+
+impl SwapV2DynamicFee {
+    fn to_ppm(ratio: &SwapRatio) -> u64 {
+        ratio.numerator as u64 * DYNAMIC_FEE_DENOMINATOR / ratio.denominator as u64
+    }
+
+    /// check policy args
+    pub fn check_args(arg: &SwapV2DynamicFeeArg) -> Result<(), BusinessError> {
+        if arg.floor.denominator == 0 || arg.ceiling.denominator == 0 || arg.max_deviation.denominator == 0 {
+            return Err(BusinessError::Swap("INVALID_DYNAMIC_FEE".into()));
+        }
+        if arg.ceiling.numerator >= arg.ceiling.denominator {
+            return Err(BusinessError::Swap("INVALID_DYNAMIC_FEE_CEILING".into()));
+        }
+        // floor <= ceiling
+        if arg.floor.numerator as u64 * arg.ceiling.denominator as u64
+            > arg.ceiling.numerator as u64 * arg.floor.denominator as u64
+        {
+            return Err(BusinessError::Swap("INVALID_DYNAMIC_FEE_FLOOR".into()));
+        }
+        if arg.max_deviation.is_zero() || arg.window_ns == 0 || SWAP_V2_MAX_TWAP_WINDOW_NS < arg.window_ns {
+            return Err(BusinessError::Swap("INVALID_DYNAMIC_FEE".into()));
+        }
+        Ok(())
+    }
+
+    /// interpolate fee between floor and ceiling by deviation in millionths
+    fn interpolate(&self, deviation: u64) -> SwapRatio {
+        let floor = Self::to_ppm(&self.floor);
+        let ceiling = Self::to_ppm(&self.ceiling);
+        let max_deviation = Self::to_ppm(&self.max_deviation).max(1);
+        let fee = floor + (ceiling - floor) * deviation.min(max_deviation) / max_deviation;
+        SwapRatio::new(fee as u32, DYNAMIC_FEE_DENOMINATOR as u32)
+    }
 }
 
 impl SwapV2MarketMaker {
@@ -70,6 +174,10 @@ impl SwapV2MarketMaker {
             k_last: zero(),
             lp,
             protocol_fee,
+            dynamic_fee: None,
+            observations: VecDeque::new(),
+            lent0: zero(),
+            lent1: zero(),
         }
     }
 
@@ -77,6 +185,162 @@ impl SwapV2MarketMaker {
         std::mem::replace(&mut self.protocol_fee, protocol_fee)
     }
 
+    pub fn replace_dynamic_fee(
+        &mut self,
+        arg: Option<SwapV2DynamicFeeArg>,
+    ) -> Result<Option<SwapV2DynamicFee>, BusinessError> {
+        let dynamic_fee = match arg {
+            Some(arg) => {
+                SwapV2DynamicFee::check_args(&arg)?;
+                let mut dynamic_fee = SwapV2DynamicFee {
+                    floor: arg.floor,
+                    ceiling: arg.ceiling,
+                    max_deviation: arg.max_deviation,
+                    window_ns: arg.window_ns,
+                    fee_rate: self.fee_rate.clone(),
+                };
+                // start from base fee, limited by floor and ceiling
+                let base = SwapV2DynamicFee::to_ppm(&self.fee_rate);
+                let floor = SwapV2DynamicFee::to_ppm(&dynamic_fee.floor);
+                let ceiling = SwapV2DynamicFee::to_ppm(&dynamic_fee.ceiling);
+                if base < floor {
+                    dynamic_fee.fee_rate = dynamic_fee.floor.clone();
+                } else if ceiling < base {
+                    dynamic_fee.fee_rate = dynamic_fee.ceiling.clone();
+                }
+                Some(dynamic_fee)
+            }
+            None => None,
+        };
+        Ok(std::mem::replace(&mut self.dynamic_fee, dynamic_fee))
+    }
+
+    /// Record cumulative prices, should be called after state changed
+    pub fn observe(&mut self) {
+        let timestamp = self.block_timestamp_last;
+        if self
+            .observations
+            .back()
+            .is_some_and(|last| timestamp < last.timestamp + SWAP_V2_OBSERVATION_INTERVAL_NS)
+        {
+            return;
+        }
+        self.observations.push_back(SwapV2Observation {
+            timestamp,
+            price0_cumulative: self.price0_cumulative_last.clone(),
+            price1_cumulative: self.price1_cumulative_last.clone(),
+        });
+        while SWAP_V2_OBSERVATIONS_CAPACITY < self.observations.len() {
+            self.observations.pop_front();
+        }
+    }
+
+    /// Time weighted average price from the latest observation which is older than window.
+    /// If the reserves are not changed in the window, it is the spot price.
+    /// ! The observation too far before the window is stale, which is refused instead of averaging a longer period.
+    pub fn twap(&self, now: u64, window_ns: u64) -> Result<SwapV2Twap, BusinessError> {
+        let target = now
+            .checked_sub(window_ns)
+            .filter(|_| 0 < window_ns)
+            .ok_or_else(|| BusinessError::Swap("INVALID_TWAP_WINDOW".into()))?;
+
+        // idle pool, the current reserves lasts in the whole window
+        if self.block_timestamp_last <= target {
+            let (price0, price1) = self
+                .spot_prices()
+                .ok_or_else(|| BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()))?;
+            return Ok(SwapV2Twap {
+                token0: self.token0,
+                token1: self.token1,
+                price_cumulative_exponent: self.price_cumulative_exponent,
+                price0,
+                price1,
+                window_ns,
+            });
+        }
+
+        let observation = self
+            .observations
+            .iter()
+            .rev()
+            .find(|o| o.timestamp <= target)
+            .ok_or_else(|| BusinessError::Swap("INSUFFICIENT_OBSERVATIONS".into()))?;
+        let tolerance = SWAP_V2_OBSERVATION_INTERVAL_NS.max(window_ns / 10);
+        if tolerance < target - observation.timestamp {
+            return Err(BusinessError::Swap("STALE_OBSERVATIONS".into()));
+        }
+        let elapsed = now - observation.timestamp;
+
+        // cumulative prices of now, the current reserves lasts from last block
+        let mut price0_cumulative = self.price0_cumulative_last.clone();
+        let mut price1_cumulative = self.price1_cumulative_last.clone();
+        let time_elapsed = now.saturating_sub(self.block_timestamp_last);
+        if time_elapsed > 0 && self.reserve0 > *ZERO && self.reserve1 > *ZERO {
+            let e = Nat::from(time_elapsed);
+            let price_cumulative_unit = self.price_cumulative_unit();
+            price0_cumulative +=
+                e.clone() * self.reserve1.clone() * price_cumulative_unit.clone() / self.reserve0.clone();
+            price1_cumulative += e * self.reserve0.clone() * price_cumulative_unit / self.reserve1.clone();
+        }
+
+        Ok(SwapV2Twap {
+            token0: self.token0,
+            token1: self.token1,
+            price_cumulative_exponent: self.price_cumulative_exponent,
+            price0: (price0_cumulative - observation.price0_cumulative.clone()) / elapsed,
+            price1: (price1_cumulative - observation.price1_cumulative.clone()) / elapsed,
+            window_ns: elapsed,
+        })
+    }
+
+    /// Prices of current reserves, scaled same as cumulative prices
+    fn spot_prices(&self) -> Option<(Nat, Nat)> {
+        if self.reserve0 == *ZERO || self.reserve1 == *ZERO {
+            return None;
+        }
+        let unit = self.price_cumulative_unit();
+        Some((
+            self.reserve1.clone() * unit.clone() / self.reserve0.clone(),
+            self.reserve0.clone() * unit / self.reserve1.clone(),
+        ))
+    }
+
+    /// The fee rate charged by next swap
+    pub fn current_fee_rate(&self) -> SwapRatio {
+        match &self.dynamic_fee {
+            Some(dynamic_fee) => dynamic_fee.fee_rate.clone(),
+            None => self.fee_rate.clone(),
+        }
+    }
+
+    /// Move the dynamic fee with the deviation of spot price from twap, should be called after observed.
+    /// ! The fee is kept if twap of the window can not be told by observations
+    pub fn update_dynamic_fee(&mut self) {
+        let Some(window_ns) = self.dynamic_fee.as_ref().map(|dynamic_fee| dynamic_fee.window_ns) else {
+            return;
+        };
+        let Some((spot, _)) = self.spot_prices() else {
+            return;
+        };
+        let Ok(twap) = self.twap(self.block_timestamp_last, window_ns) else {
+            return;
+        };
+        let twap = twap.price0;
+        if twap == *ZERO {
+            return;
+        }
+        let delta = if spot > twap {
+            spot - twap.clone()
+        } else {
+            twap.clone() - spot
+        };
+        let deviation = delta * DYNAMIC_FEE_DENOMINATOR / twap;
+        let deviation = deviation.0.to_u64().unwrap_or(u64::MAX);
+        if let Some(dynamic_fee) = self.dynamic_fee.as_mut() {
+            dynamic_fee.fee_rate = dynamic_fee.interpolate(deviation);
+        }
+    }
+
     pub fn price_cumulative_unit(&self) -> Nat {
         let price_cumulative_unit = BigUint::from(2_u8).pow(self.price_cumulative_exponent as u32);
         Nat::from(price_cumulative_unit)
@@ -137,8 +401,9 @@ impl SwapV2MarketMaker {
         amount_in: &Nat,
         amount_out: &Nat,
     ) -> Result<(), BusinessError> {
-        let n = self.fee_rate.numerator;
-        let d = self.fee_rate.denominator;
+        let fee_rate = self.current_fee_rate();
+        let n = fee_rate.numerator;
+        let d = fee_rate.denominator;
         let (balance0, balance1, amount0_in, amount1_in) = if *token_out == self.token1 {
             (
                 self.reserve0.clone() + amount_in.clone(),
@@ -199,8 +464,9 @@ impl SwapV2MarketMaker {
         // amount_out = -----------------------------
         //              in * d + amount_in * (d-n)
 
-        let n = Nat::from(self.fee_rate.numerator);
-        let d = Nat::from(self.fee_rate.denominator);
+        let fee_rate = self.current_fee_rate();
+        let n = Nat::from(fee_rate.numerator);
+        let d = Nat::from(fee_rate.denominator);
         let amount_in_with_fee = amount_in.clone() * (d.clone() - n);
         let numerator = reserve_out * amount_in_with_fee.clone();
         let denominator = reserve_in * d + amount_in_with_fee;
@@ -255,8 +521,9 @@ impl SwapV2MarketMaker {
         // amount_in = -----------------------------
         //              (out - amount_out) * (d - n)
 
-        let n = Nat::from(self.fee_rate.numerator);
-        let d = Nat::from(self.fee_rate.denominator);
+        let fee_rate = self.current_fee_rate();
+        let n = Nat::from(fee_rate.numerator);
+        let d = Nat::from(fee_rate.denominator);
         let numerator = reserve_in * amount_out.clone() * d.clone();
         let denominator = (reserve_out - amount_out.clone()) * (d - n);
 
@@ -433,8 +700,9 @@ impl SwapV2MarketMaker {
     }
 
     pub fn get_fee(&self, amount_in: f64) -> f64 {
-        let n = self.fee_rate.numerator as f64;
-        let d = self.fee_rate.denominator as f64;
+        let fee_rate = self.current_fee_rate();
+        let n = fee_rate.numerator as f64;
+        let d = fee_rate.denominator as f64;
         amount_in * n / d
     }
 
@@ -464,6 +732,30 @@ pub struct SwapV2MarketMakerView {
 
     lp: PoolLpView,
     protocol_fee: Option<SwapRatioView>,
+
+    dynamic_fee: Option<SwapV2DynamicFeeView>,
+}
+
+/// dynamic fee of swap v2
+#[derive(Debug, Serialize, Deserialize, Clone, CandidType)]
+pub struct SwapV2DynamicFeeView {
+    floor: SwapRatioView,
+    ceiling: SwapRatioView,
+    max_deviation: SwapRatioView,
+    window_ns: u64,
+    fee_rate: SwapRatioView,
+}
+
+impl From<SwapV2DynamicFee> for SwapV2DynamicFeeView {
+    fn from(value: SwapV2DynamicFee) -> Self {
+        Self {
+            floor: value.floor.into(),
+            ceiling: value.ceiling.into(),
+            max_deviation: value.max_deviation.into(),
+            window_ns: value.window_ns,
+            fee_rate: value.fee_rate.into(),
+        }
+    }
 }
 
 impl From<SwapV2MarketMaker> for SwapV2MarketMakerView {
@@ -482,6 +774,162 @@ impl From<SwapV2MarketMaker> for SwapV2MarketMakerView {
             k_last: value.k_last.to_string(),
             lp: value.lp.into(),
             protocol_fee: value.protocol_fee.map(|f| f.into()),
+            dynamic_fee: value.dynamic_fee.map(|f| f.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dynamic_fee() {
+        let arg = SwapV2DynamicFeeArg {
+            floor: SwapRatio::new(1, 1_000),
+            ceiling: SwapRatio::new(1, 100),
+            max_deviation: SwapRatio::new(5, 100),
+            window_ns: 3_600_000_000_000,
+        };
+        SwapV2DynamicFee::check_args(&arg).unwrap();
+        let dynamic_fee = SwapV2DynamicFee {
+            floor: arg.floor.clone(),
+            ceiling: arg.ceiling.clone(),
+            max_deviation: arg.max_deviation.clone(),
+            window_ns: arg.window_ns,
+            fee_rate: arg.floor.clone(),
+        };
+        // calm, half way and sharp
+        assert_eq!(dynamic_fee.interpolate(0), SwapRatio::new(1_000, 1_000_000));
+        assert_eq!(dynamic_fee.interpolate(25_000), SwapRatio::new(5_500, 1_000_000));
+        assert_eq!(dynamic_fee.interpolate(500_000), SwapRatio::new(10_000, 1_000_000));
+
+        // floor must not be greater than ceiling
+        let invalid = SwapV2DynamicFeeArg {
+            floor: SwapRatio::new(2, 100),
+            ..arg
+        };
+        assert!(SwapV2DynamicFee::check_args(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_twap() {
+        let token0 = CanisterId::from_slice(&[1]);
+        let token1 = CanisterId::from_slice(&[2]);
+        let token = |canister_id: CanisterId| TokenInfo {
+            canister_id,
+            name: "Token".into(),
+            symbol: "T".into(),
+            decimals: 8,
+            fee: Nat::from(10_000_u32),
+            is_lp_token: false,
+        };
+        let dummy_canister_id = crate::types::DummyCanisterId::new(CanisterId::from_slice(&[3]));
+        let lp = PoolLp::new_inner_lp(dummy_canister_id, &token(token0), &token(token1));
+        let mut maker = SwapV2MarketMaker::new([0; 32], SwapRatio::new(3, 1_000), token0, token1, lp, None);
+        maker.reserve0 = Nat::from(1_000_u32);
+        maker.reserve1 = Nat::from(2_000_u32);
+        maker.block_timestamp_last = 1_000_000_000_000;
+        maker.observe();
+        // too close to be observed
+        maker.observe();
+        assert_eq!(maker.observations.len(), 1);
+
+        let unit = maker.price_cumulative_unit();
+        let now = maker.block_timestamp_last + 60_000_000_000;
+        let twap = maker.twap(now, 60_000_000_000).unwrap();
+        assert_eq!(twap.price0, unit.clone() * 2_u32);
+        assert_eq!(twap.price1, unit.clone() / 2_u32);
+        assert!(maker.twap(now, 3_600_000_000_000).is_err());
+
+        // idle pool, the spot price lasts in the whole window
+        let minute = 60_000_000_000;
+        let start = maker.block_timestamp_last;
+        let twap = maker.twap(start + 60 * minute, 10 * minute).unwrap();
+        assert_eq!(twap.price0, unit.clone() * 2_u32);
+        assert_eq!(twap.window_ns, 10 * minute);
+
+        // changed in the window, the only observation is too far before the window
+        trade(&mut maker, start + 30 * minute, 1_000, 4_000);
+        assert!(matches!(
+            maker.twap(start + 35 * minute, 10 * minute),
+            Err(BusinessError::Swap(err)) if err == "STALE_OBSERVATIONS"
+        ));
+        let twap = maker.twap(start + 35 * minute, 34 * minute).unwrap();
+        assert_eq!(twap.window_ns, 35 * minute);
+        assert_eq!(twap.price0, unit * 16_u32 / 7_u32); // 30 minutes of 2 and 5 minutes of 4
+    }
+
+    // same as the update after state changed
+    fn trade(maker: &mut SwapV2MarketMaker, now: u64, reserve0: u32, reserve1: u32) {
+        let e = Nat::from(now - maker.block_timestamp_last);
+        let unit = maker.price_cumulative_unit();
+        maker.price0_cumulative_last += e.clone() * maker.reserve1.clone() * unit.clone() / maker.reserve0.clone();
+        maker.price1_cumulative_last += e * maker.reserve0.clone() * unit / maker.reserve1.clone();
+        maker.reserve0 = Nat::from(reserve0);
+        maker.reserve1 = Nat::from(reserve1);
+        maker.block_timestamp_last = now;
+        maker.observe();
+        maker.update_dynamic_fee();
+    }
+
+    #[test]
+    fn test_dynamic_fee_by_observations() {
+        let token0 = CanisterId::from_slice(&[1]);
+        let token1 = CanisterId::from_slice(&[2]);
+        let token = |canister_id: CanisterId| TokenInfo {
+            canister_id,
+            name: "Token".into(),
+            symbol: "T".into(),
+            decimals: 8,
+            fee: Nat::from(10_000_u32),
+            is_lp_token: false,
+        };
+        let dummy_canister_id = crate::types::DummyCanisterId::new(CanisterId::from_slice(&[3]));
+        let lp = PoolLp::new_inner_lp(dummy_canister_id, &token(token0), &token(token1));
+        let mut maker = SwapV2MarketMaker::new([0; 32], SwapRatio::new(3, 1_000), token0, token1, lp, None);
+        let minute = 60_000_000_000;
+        let start = 1_000 * minute;
+        maker.reserve0 = Nat::from(1_000_u32);
+        maker.reserve1 = Nat::from(2_000_u32);
+        maker.block_timestamp_last = start;
+        maker.observe();
+
+        // the window is covered by observations
+        let arg = SwapV2DynamicFeeArg {
+            floor: SwapRatio::new(1, 1_000),
+            ceiling: SwapRatio::new(1, 100),
+            max_deviation: SwapRatio::new(5, 100),
+            window_ns: 10 * minute,
+        };
+        assert!(
+            SwapV2DynamicFee::check_args(&SwapV2DynamicFeeArg {
+                window_ns: SWAP_V2_MAX_TWAP_WINDOW_NS + 1,
+                ..arg.clone()
+            })
+            .is_err()
+        );
+        maker.replace_dynamic_fee(Some(arg)).unwrap();
+        assert_eq!(maker.current_fee_rate(), SwapRatio::new(3, 1_000)); // base fee
+
+        // calm, the spot price is the twap
+        for i in 1..=12 {
+            trade(&mut maker, start + i * minute, 1_000, 2_000);
         }
+        assert_eq!(maker.current_fee_rate(), SwapRatio::new(1_000, 1_000_000));
+
+        // sharp, the spot price moves 10% from the twap of the window
+        trade(&mut maker, start + 13 * minute, 1_000, 2_200);
+        assert_eq!(maker.current_fee_rate(), SwapRatio::new(10_000, 1_000_000));
+
+        // the same twap as query
+        let twap = maker.twap(start + 13 * minute, 10 * minute).unwrap();
+        assert_eq!(twap.price0, maker.price_cumulative_unit() * 2_u32);
+        assert_eq!(twap.window_ns, 10 * minute);
+
+        // after idle, the observations are stale and the fee is kept
+        trade(&mut maker, start + 60 * minute, 1_000, 2_000);
+        assert_eq!(maker.current_fee_rate(), SwapRatio::new(10_000, 1_000_000));
     }
 }
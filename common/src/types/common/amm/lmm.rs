@@ -1,7 +1,419 @@
-/// Logarithmic market maker（Logarithmic AMM）
-/// - Formula：y = k * ln(x)（x and y are the number of two assets, and k is the constant）
-/// - Representative Project：Bancor V3、Balancer（Some pools）
+/// Weighted product market maker（Weighted Product AMM）
+/// - Formula：x^wx * y^wy = k（x and y are the number of two assets, wx and wy are the weights, wx + wy = 1）
+/// - Representative Project：Balancer
 /// - Features
-///   - Provides a smoother price curve with slippage below the product of the constant.
-///   - Support multi-asset portfolios and adjust liquidity distribution through weights.
-///   - Bancor V3 introduces a "elastic supply" mechanism, allowing dynamic additional tokens.
+///   - The value of each side follows the weights, such as 80/20, instead of 50/50.
+///   - The weights are fixed when the pool is created.
+///   - Supports single-asset join and exit, the imbalanced part is charged as swap fee.
+#[cfg(feature = "cdk")]
+use std::borrow::Cow;
+#[cfg(feature = "cdk")]
+use std::collections::HashMap;
+
+use candid::{CandidType, Nat};
+use icrc_ledger_types::icrc1::account::{Account, Subaccount};
+use num_bigint::BigUint;
+use num_traits::{ToPrimitive, Zero};
+use serde::{Deserialize, Serialize};
+
+use super::div_rounding_up;
+use crate::types::PoolLpView;
+#[allow(unused)]
+use crate::{
+    types::{BusinessError, CanisterId, PoolLp, SelfCanister, SwapRatio, SwapRatioView, TokenInfo, TokenPairAmm},
+    utils::{
+        math::{ZERO, zero},
+        principal::sort_tokens,
+    },
+};
+
+/// The sum of weights
+pub const WEIGHT_TOTAL: u32 = 100;
+
+// ========================== math ==========================
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// base * (numerator / denominator) ^ (p / q), round down or up
+fn mul_pow(base: &BigUint, numerator: &BigUint, denominator: &BigUint, p: u32, q: u32, round_up: bool) -> BigUint {
+    let (p, q) = {
+        let g = gcd(p, q);
+        (p / g, q / g)
+    };
+    let value = base.pow(q) * numerator.pow(p);
+    let divisor = denominator.pow(p);
+    if round_up {
+        let value = div_rounding_up(&value, &divisor);
+        let root = value.nth_root(q);
+        if root.pow(q) < value { root + 1_u8 } else { root }
+    } else {
+        (value / divisor).nth_root(q)
+    }
+}
+
+/// The required data under the current algorithm processing fee
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct WeightedMarketMaker {
+    pub subaccount: Subaccount, // ! fixed. Fund balance storage location self_canister_id.subaccount
+    pub fee_rate: SwapRatio,    // ! fixed. Transaction rates
+    pub weight0: u32,           // ! fixed. Weight of token0, weight0 + weight1 = WEIGHT_TOTAL
+    pub weight1: u32,           // ! fixed. Weight of token1
+
+    pub token0: CanisterId, // ! Canister_id of the current token0
+    pub token1: CanisterId, // ! Canister_id of the current token1
+    pub reserve0: Nat,      // ! The current balance deposited by token0
+    pub reserve1: Nat,      // ! The current balance deposited by token1
+    pub block_timestamp_last: u64,
+
+    pub lp: PoolLp, // lp token information, Once the new pool is successfully created, other data cannot be changed except for supply
+    pub protocol_fee: Option<SwapRatio>, // The ratio of swap fee which belongs to swap fee to
+}
+
+impl WeightedMarketMaker {
+    pub fn check_weights(weights: (u32, u32)) -> Result<(), BusinessError> {
+        if weights.0 == 0 || weights.1 == 0 || weights.0.checked_add(weights.1) != Some(WEIGHT_TOTAL) {
+            return Err(BusinessError::Swap("INVALID_WEIGHTS".into()));
+        }
+        Ok(())
+    }
+
+    pub fn new(
+        subaccount: Subaccount,
+        fee_rate: SwapRatio,
+        weights: (u32, u32),
+        token0: CanisterId,
+        token1: CanisterId,
+        lp: PoolLp,
+        protocol_fee: Option<SwapRatio>,
+    ) -> Self {
+        Self {
+            subaccount,
+            fee_rate,
+            weight0: weights.0,
+            weight1: weights.1,
+            token0,
+            token1,
+            reserve0: zero(),
+            reserve1: zero(),
+            block_timestamp_last: 0,
+            lp,
+            protocol_fee,
+        }
+    }
+
+    pub fn replace_protocol_fee(&mut self, protocol_fee: Option<SwapRatio>) -> Option<SwapRatio> {
+        std::mem::replace(&mut self.protocol_fee, protocol_fee)
+    }
+
+    #[cfg(feature = "cdk")]
+    pub fn dummy_tokens(&self, tokens: &HashMap<CanisterId, Cow<'_, TokenInfo>>, pa: &TokenPairAmm) -> Vec<TokenInfo> {
+        self.lp.dummy_tokens(tokens, pa)
+    }
+
+    pub fn accounts(&self, self_canister: &SelfCanister) -> Vec<Account> {
+        vec![Account {
+            owner: self_canister.id(),
+            subaccount: Some(self.subaccount),
+        }]
+    }
+
+    pub fn dummy_canisters(&self) -> Vec<CanisterId> {
+        self.lp.dummy_canisters()
+    }
+
+    // fetches and sorts the reserves for a pair
+    pub fn get_reserves(&self, token_a: CanisterId, token_b: CanisterId) -> (Nat, Nat) {
+        let (token0, _) = sort_tokens(token_a, token_b);
+        let (reserve0, reserve1) = (self.reserve0.clone(), self.reserve1.clone());
+        if token_a == token0 {
+            (reserve0, reserve1)
+        } else {
+            (reserve1, reserve0)
+        }
+    }
+
+    pub fn check_liquidity_removable<F>(
+        &self,
+        token_balance_of: F,
+        from: &Account,
+        liquidity_without_fee: &Nat,
+        fee_to: Option<Account>,
+    ) -> Result<(), BusinessError>
+    where
+        F: Fn(CanisterId, Account) -> Result<Nat, BusinessError>,
+    {
+        self.lp
+            .check_liquidity_removable(|token| token_balance_of(token, *from), liquidity_without_fee, fee_to)
+    }
+
+    /// Reserve and weight of the token
+    fn get_side(&self, token: CanisterId) -> Result<(&Nat, u32), BusinessError> {
+        if token == self.token0 {
+            Ok((&self.reserve0, self.weight0))
+        } else if token == self.token1 {
+            Ok((&self.reserve1, self.weight1))
+        } else {
+            Err(BusinessError::Swap("INVALID_TOKEN".into()))
+        }
+    }
+
+    /// Calculate the output amount and the swap fee charged in token in
+    pub fn exchange(
+        &self,
+        amount_in: &Nat,
+        token_in: CanisterId,
+        token_out: CanisterId,
+    ) -> Result<(Nat, Nat), BusinessError> {
+        if *amount_in == *ZERO {
+            return Err(BusinessError::Swap("INSUFFICIENT_INPUT_AMOUNT".into()));
+        }
+        if token_in == token_out {
+            return Err(BusinessError::Swap("INVALID_TOKEN".into()));
+        }
+        let (reserve_in, weight_in) = self.get_side(token_in)?;
+        let (reserve_out, weight_out) = self.get_side(token_out)?;
+        if *reserve_in == *ZERO || *reserve_out == *ZERO {
+            return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+        }
+
+        // out = B_o * (1 - (B_i / (B_i + A_i))^(w_i / w_o))
+        let fee = div_rounding_up(
+            &(&amount_in.0 * self.fee_rate.numerator),
+            &BigUint::from(self.fee_rate.denominator),
+        );
+        let amount_in_with_fee = &amount_in.0 - &fee;
+        let balance_in = &reserve_in.0 + &amount_in_with_fee;
+        let remain = mul_pow(&reserve_out.0, &reserve_in.0, &balance_in, weight_in, weight_out, true);
+        let amount_out = if reserve_out.0 > remain {
+            &reserve_out.0 - remain
+        } else {
+            BigUint::zero()
+        };
+        Ok((Nat::from(amount_out), Nat::from(fee)))
+    }
+
+    // given an input amount of an asset and pair reserves, returns the maximum output amount of the other asset
+    pub fn get_amount_out(
+        &self,
+        self_canister: &SelfCanister,
+        amount_in: &Nat,
+        token_in: CanisterId,
+        token_out: CanisterId,
+    ) -> Result<(Account, Nat), BusinessError> {
+        let pool_account = Account {
+            owner: self_canister.id(),
+            subaccount: Some(self.subaccount),
+        };
+
+        let (amount_out, _) = self.exchange(amount_in, token_in, token_out)?;
+        if amount_out == *ZERO {
+            return Err(BusinessError::Swap("INSUFFICIENT_OUTPUT_AMOUNT".into()));
+        }
+
+        Ok((pool_account, amount_out))
+    }
+
+    // given an output amount of an asset and pair reserves, returns a required input amount of the other asset
+    pub fn get_amount_in(
+        &self,
+        self_canister: &SelfCanister,
+        amount_out: &Nat,
+        token_in: CanisterId,
+        token_out: CanisterId,
+    ) -> Result<(Account, Nat), BusinessError> {
+        let pool_account = Account {
+            owner: self_canister.id(),
+            subaccount: Some(self.subaccount),
+        };
+
+        if *amount_out == *ZERO {
+            return Err(BusinessError::Swap("INSUFFICIENT_OUTPUT_AMOUNT".into()));
+        }
+        if token_in == token_out {
+            return Err(BusinessError::Swap("INVALID_TOKEN".into()));
+        }
+        let (reserve_in, weight_in) = self.get_side(token_in)?;
+        let (reserve_out, weight_out) = self.get_side(token_out)?;
+        if reserve_out.0 <= amount_out.0 || *reserve_in == *ZERO {
+            return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+        }
+
+        // A_i = B_i * ((B_o / (B_o - A_o))^(w_o / w_i) - 1) / (1 - fee)
+        let remain = &reserve_out.0 - &amount_out.0;
+        let balance_in = mul_pow(&reserve_in.0, &reserve_out.0, &remain, weight_out, weight_in, true);
+        let amount_in_with_fee = balance_in - &reserve_in.0;
+        let n = self.fee_rate.numerator;
+        let d = self.fee_rate.denominator;
+        let amount_in = Nat::from(
+            div_rounding_up(&(amount_in_with_fee * d), &BigUint::from(d - n)) + 1_u8, // ! You must not miss the transfer, and you can get it upward
+        );
+
+        // check on calculate amount
+        let (max_out, _) = self.exchange(&amount_in, token_in, token_out)?;
+        if max_out < *amount_out {
+            return Err(BusinessError::Swap("K".into()));
+        }
+
+        Ok((pool_account, amount_in))
+    }
+
+    /// Liquidity of the first deposit, the weighted geometric mean of amounts
+    pub fn get_initial_liquidity(&self, amount0: &Nat, amount1: &Nat) -> Nat {
+        let g = gcd(self.weight0, self.weight1);
+        let (w0, w1) = (self.weight0 / g, self.weight1 / g);
+        Nat::from((amount0.0.pow(w0) * amount1.0.pow(w1)).nth_root(w0 + w1))
+    }
+
+    /// Liquidity minted when only token is deposited, fee is charged on the part should be swapped
+    pub fn get_single_join_liquidity(&self, token: CanisterId, amount: &Nat) -> Result<Nat, BusinessError> {
+        let (reserve, weight) = self.get_side(token)?;
+        let total_supply = self.lp.get_total_supply();
+        if *reserve == *ZERO || total_supply == *ZERO {
+            return Err(BusinessError::Liquidity("INSUFFICIENT_LIQUIDITY".into()));
+        }
+
+        // fee = amount * (1 - w) * fee_rate
+        let n = BigUint::from(WEIGHT_TOTAL - weight) * self.fee_rate.numerator;
+        let d = BigUint::from(WEIGHT_TOTAL) * self.fee_rate.denominator;
+        let amount_with_fee = &amount.0 - div_rounding_up(&(&amount.0 * n), &d);
+
+        // S' = S * ((B + A) / B)^w
+        let balance = &reserve.0 + amount_with_fee;
+        let supply = mul_pow(&total_supply.0, &balance, &reserve.0, weight, WEIGHT_TOTAL, false);
+        Ok(Nat::from(supply - total_supply.0))
+    }
+
+    /// Token amount returned when liquidity is burned and only token is withdrawn
+    pub fn get_single_exit_amount(&self, token: CanisterId, liquidity: &Nat) -> Result<Nat, BusinessError> {
+        let (reserve, weight) = self.get_side(token)?;
+        let total_supply = self.lp.get_total_supply();
+        if total_supply.0 <= liquidity.0 {
+            return Err(BusinessError::Liquidity("INSUFFICIENT_LIQUIDITY".into()));
+        }
+
+        // B' = B * ((S - L) / S)^(1 / w)
+        let remain = &total_supply.0 - &liquidity.0;
+        let balance = mul_pow(&reserve.0, &remain, &total_supply.0, WEIGHT_TOTAL, weight, true);
+        let amount = if reserve.0 > balance {
+            &reserve.0 - balance
+        } else {
+            BigUint::zero()
+        };
+
+        // fee = amount * (1 - w) * fee_rate
+        let n = BigUint::from(WEIGHT_TOTAL - weight) * self.fee_rate.numerator;
+        let d = BigUint::from(WEIGHT_TOTAL) * self.fee_rate.denominator;
+        let fee = div_rounding_up(&(&amount * n), &d);
+        Ok(Nat::from(amount - fee))
+    }
+
+    /// The amount of token b which has the same value of amount a
+    pub fn quote(&self, amount_a: &Nat, token_a: CanisterId, token_b: CanisterId) -> Result<Nat, BusinessError> {
+        let (reserve_a, _) = self.get_side(token_a)?;
+        let (reserve_b, _) = self.get_side(token_b)?;
+        if *reserve_a == *ZERO || *reserve_b == *ZERO {
+            return Err(BusinessError::Liquidity("INSUFFICIENT_LIQUIDITY".into()));
+        }
+        Ok(amount_a.clone() * reserve_b.clone() / reserve_a.clone())
+    }
+
+    pub fn removable(&self) -> bool {
+        self.lp.removable()
+    }
+
+    pub fn swap_to(&self, from: &CanisterId, from_amount: f64) -> Option<(CanisterId, f64)> {
+        if self.reserve0 == *ZERO || self.reserve1 == *ZERO {
+            return None;
+        }
+        // spot price = (B_o / w_o) / (B_i / w_i)
+        let (w0, w1) = (self.weight0 as f64, self.weight1 as f64);
+        let (r0, r1) = (self.reserve0.0.to_f64()?, self.reserve1.0.to_f64()?);
+        if self.token0 == *from {
+            Some((self.token1, from_amount * (r1 / w1) / (r0 / w0)))
+        } else if self.token1 == *from {
+            Some((self.token0, from_amount * (r0 / w0) / (r1 / w1)))
+        } else {
+            None
+        }
+    }
+
+    pub fn get_reserve(&self, token: &CanisterId) -> Option<f64> {
+        if self.token0 == *token {
+            self.reserve0.0.to_f64()
+        } else if self.token1 == *token {
+            self.reserve1.0.to_f64()
+        } else {
+            None
+        }
+    }
+
+    pub fn get_fee(&self, amount_in: f64) -> f64 {
+        let n = self.fee_rate.numerator as f64;
+        let d = self.fee_rate.denominator as f64;
+        amount_in * n / d
+    }
+
+    pub fn get_subaccount(&self) -> &Subaccount {
+        &self.subaccount
+    }
+}
+
+// ========================== view ==========================
+
+/// weighted
+#[derive(Debug, Serialize, Deserialize, Clone, CandidType)]
+pub struct WeightedMarketMakerView {
+    subaccount: String,
+    fee_rate: SwapRatioView,
+    weight0: u32,
+    weight1: u32,
+
+    token0: String,
+    token1: String,
+    reserve0: String,
+    reserve1: String,
+    block_timestamp_last: u64,
+
+    lp: PoolLpView,
+    protocol_fee: Option<SwapRatioView>,
+}
+
+impl From<WeightedMarketMaker> for WeightedMarketMakerView {
+    fn from(value: WeightedMarketMaker) -> Self {
+        Self {
+            subaccount: hex::encode(value.subaccount),
+            fee_rate: value.fee_rate.into(),
+            weight0: value.weight0,
+            weight1: value.weight1,
+            token0: value.token0.to_string(),
+            token1: value.token1.to_string(),
+            reserve0: value.reserve0.to_string(),
+            reserve1: value.reserve1.to_string(),
+            block_timestamp_last: value.block_timestamp_last,
+            lp: value.lp.into(),
+            protocol_fee: value.protocol_fee.map(|f| f.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mul_pow() {
+        // 80/20 pool, 1000 * (100 / 110)^(20 / 80)
+        let base = BigUint::from(1_000_000_000_u64);
+        let down = mul_pow(&base, &BigUint::from(100_u8), &BigUint::from(110_u8), 20, 80, false);
+        let up = mul_pow(&base, &BigUint::from(100_u8), &BigUint::from(110_u8), 20, 80, true);
+        assert_eq!(down, BigUint::from(976_454_089_u64));
+        assert_eq!(up, down + 1_u8);
+
+        // 50/50 pool is constant product
+        let remain = mul_pow(&base, &BigUint::from(100_u8), &BigUint::from(125_u8), 50, 50, false);
+        assert_eq!(remain, BigUint::from(800_000_000_u64));
+    }
+}
@@ -12,18 +12,37 @@ mod cpmm;
 #[allow(unused)]
 pub use cpmm::*;
 
+/// Concentrated Liquidity Market Maker
+mod clmm;
 #[allow(unused)]
-use crate::types::{Amm, CanisterId, TokenPairAmm};
+pub use clmm::*;
+
+/// Stablecoin Market Maker
+mod smm;
+#[allow(unused)]
+pub use smm::*;
+
+#[allow(unused)]
+use crate::types::{Amm, AmmAlgorithm, CanisterId, TokenPairAmm};
 
 use super::{BusinessError, DummyCanisterId, PoolLp, SelfCanister, SwapRatio, TokenInfo};
 
-// Proactive Market Maker
-// https://docs.dodoex.io/zh/product/pmm-algorithm/details-about-pmm
-// https://dodoex.github.io/cn/docs/
-// mod pmm_v1;
-// pmm v1
-// #[allow(unused)]
-// pub use pmm_v1::*;
+/// Weighted Market Maker
+mod lmm;
+#[allow(unused)]
+pub use lmm::*;
+
+/// Proactive Market Maker
+/// https://docs.dodoex.io/zh/product/pmm-algorithm/details-about-pmm
+/// https://dodoex.github.io/cn/docs/
+mod pmm_v1;
+#[allow(unused)]
+pub use pmm_v1::*;
+
+/// Multi-asset Stablecoin Market Maker
+mod msmm;
+#[allow(unused)]
+pub use msmm::*;
 
 /// market maker
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
@@ -31,6 +50,21 @@ pub enum MarketMaker {
     /// swap v2
     #[serde(rename = "swap_v2")]
     SwapV2(SwapV2MarketMaker),
+    /// swap v3
+    #[serde(rename = "swap_v3")]
+    SwapV3(SwapV3MarketMaker),
+    /// stable swap
+    #[serde(rename = "stable_swap")]
+    StableSwap(StableSwapMarketMaker),
+    /// pmm v1
+    #[serde(rename = "pmm_v1")]
+    PmmV1(PmmV1MarketMaker),
+    /// weighted
+    #[serde(rename = "weighted")]
+    Weighted(WeightedMarketMaker),
+    /// stable pool
+    #[serde(rename = "stable_pool")]
+    StablePool(StablePoolMarketMaker),
 }
 
 #[cfg(feature = "cdk")]
@@ -55,43 +89,143 @@ impl MarketMaker {
         dummy_canister_id: DummyCanisterId,
         token0: &TokenInfo,
         token1: &TokenInfo,
+        weights: Option<(u32, u32)>,
     ) -> Self {
         let lp = PoolLp::new_inner_lp(dummy_canister_id, token0, token1);
-        match amm {
-            Amm::SwapV2M100 => Self::SwapV2(new_swap_v2_market_maker(
+        let fee_rate = amm.fee_rate();
+        match amm.algorithm() {
+            AmmAlgorithm::SwapV2 => Self::SwapV2(new_swap_v2_market_maker(
                 subaccount,
-                SwapRatio::new(1, 10_000), // swap fee 0.01%
+                fee_rate,
                 token0.canister_id,
                 token1.canister_id,
                 lp,
             )),
-            Amm::SwapV2M500 => Self::SwapV2(new_swap_v2_market_maker(
-                subaccount,
-                SwapRatio::new(5, 10_000), // swap fee 0.05%
-                token0.canister_id,
-                token1.canister_id,
-                lp,
+            AmmAlgorithm::SwapV3 => {
+                // tick spacing grows with fee
+                let tick_spacing = match fee_rate.numerator as u64 * 1_000_000 / fee_rate.denominator as u64 {
+                    0..=100 => 1,
+                    101..=500 => 10,
+                    501..=3_000 => 60,
+                    _ => 200,
+                };
+                Self::SwapV3(new_swap_v3_market_maker(
+                    subaccount,
+                    fee_rate,
+                    tick_spacing,
+                    token0.canister_id,
+                    token1.canister_id,
+                ))
+            }
+            AmmAlgorithm::StableSwap => Self::StableSwap(StableSwapMarketMaker::new(
+                subaccount, fee_rate, token0, token1, lp, None,
             )),
-            Amm::SwapV2T3 => Self::SwapV2(new_swap_v2_market_maker(
+            AmmAlgorithm::PmmV1 => Self::PmmV1(PmmV1MarketMaker::new(
                 subaccount,
-                SwapRatio::new(3, 1_000), // swap fee 0.3%
+                fee_rate,
                 token0.canister_id,
                 token1.canister_id,
                 lp,
+                None,
             )),
-            Amm::SwapV2H1 => Self::SwapV2(new_swap_v2_market_maker(
+            AmmAlgorithm::Weighted => Self::Weighted(WeightedMarketMaker::new(
                 subaccount,
-                SwapRatio::new(1, 100), // swap fee 1%
+                fee_rate,
+                weights.unwrap_or((WEIGHT_TOTAL / 2, WEIGHT_TOTAL / 2)),
                 token0.canister_id,
                 token1.canister_id,
                 lp,
+                None,
             )),
+            AmmAlgorithm::StablePool => Self::new_by_pool(amm, subaccount, dummy_canister_id, &[token0, token1]),
         }
     }
 
+    pub fn new_by_pool(
+        amm: &Amm,
+        subaccount: Subaccount,
+        dummy_canister_id: DummyCanisterId,
+        tokens: &[&TokenInfo],
+    ) -> Self {
+        let lp = PoolLp::new_inner_pool_lp(dummy_canister_id, tokens);
+        Self::StablePool(StablePoolMarketMaker::new(subaccount, amm.fee_rate(), tokens, lp, None))
+    }
+
     pub fn replace_protocol_fee(&mut self, protocol_fee: Option<SwapRatio>) -> Option<SwapRatio> {
         match self {
             MarketMaker::SwapV2(value) => value.replace_protocol_fee(protocol_fee),
+            MarketMaker::SwapV3(value) => value.replace_protocol_fee(protocol_fee),
+            MarketMaker::StableSwap(value) => value.replace_protocol_fee(protocol_fee),
+            MarketMaker::PmmV1(value) => value.replace_protocol_fee(protocol_fee),
+            MarketMaker::Weighted(value) => value.replace_protocol_fee(protocol_fee),
+            MarketMaker::StablePool(value) => value.replace_protocol_fee(protocol_fee),
+        }
+    }
+
+    pub fn replace_amplification(&mut self, amplification: u64) -> Result<u64, BusinessError> {
+        match self {
+            MarketMaker::StableSwap(value) => value.replace_amplification(amplification),
+            MarketMaker::StablePool(value) => value.replace_amplification(amplification),
+            _ => Err(BusinessError::Swap("AMPLIFICATION_NOT_SUPPORTED".into())),
+        }
+    }
+
+    pub fn replace_price(&mut self, i: Nat, k: Option<Nat>) -> Result<(Nat, Nat), BusinessError> {
+        match self {
+            MarketMaker::PmmV1(value) => value.replace_price(i, k),
+            _ => Err(BusinessError::Swap("PRICE_NOT_SUPPORTED".into())),
+        }
+    }
+
+    pub fn replace_dynamic_fee(
+        &mut self,
+        dynamic_fee: Option<SwapV2DynamicFeeArg>,
+    ) -> Result<Option<SwapV2DynamicFee>, BusinessError> {
+        match self {
+            MarketMaker::SwapV2(value) => value.replace_dynamic_fee(dynamic_fee),
+            _ => Err(BusinessError::Swap("DYNAMIC_FEE_NOT_SUPPORTED".into())),
+        }
+    }
+
+    pub fn replace_outer_lp(&mut self, token_canister_id: CanisterId) -> Result<(), BusinessError> {
+        match self {
+            MarketMaker::SwapV2(value) => value.lp.replace_outer_lp(token_canister_id),
+            MarketMaker::SwapV3(_) => Err(BusinessError::Swap("OUTER_LP_NOT_SUPPORTED".into())), // positions are not fungible
+            MarketMaker::StableSwap(value) => value.lp.replace_outer_lp(token_canister_id),
+            MarketMaker::PmmV1(value) => value.lp.replace_outer_lp(token_canister_id),
+            MarketMaker::Weighted(value) => value.lp.replace_outer_lp(token_canister_id),
+            MarketMaker::StablePool(value) => value.lp.replace_outer_lp(token_canister_id),
+        }
+    }
+
+    /// The ledger canister if lp is outer
+    pub fn outer_lp_canister(&self) -> Option<CanisterId> {
+        match self {
+            MarketMaker::SwapV2(value) => value.lp.outer_canister(),
+            MarketMaker::SwapV3(_) => None,
+            MarketMaker::StableSwap(value) => value.lp.outer_canister(),
+            MarketMaker::PmmV1(value) => value.lp.outer_canister(),
+            MarketMaker::Weighted(value) => value.lp.outer_canister(),
+            MarketMaker::StablePool(value) => value.lp.outer_canister(),
+        }
+    }
+
+    pub fn twap(&self, now: u64, window_ns: u64) -> Result<SwapV2Twap, BusinessError> {
+        match self {
+            MarketMaker::SwapV2(value) => value.twap(now, window_ns),
+            _ => Err(BusinessError::Swap("TWAP_NOT_SUPPORTED".into())),
+        }
+    }
+
+    /// The fee rate charged by next swap
+    pub fn swap_fee_rate(&self) -> SwapRatio {
+        match self {
+            MarketMaker::SwapV2(value) => value.current_fee_rate(),
+            MarketMaker::SwapV3(value) => value.fee_rate.clone(),
+            MarketMaker::StableSwap(value) => value.fee_rate.clone(),
+            MarketMaker::PmmV1(value) => value.fee_rate.clone(),
+            MarketMaker::Weighted(value) => value.fee_rate.clone(),
+            MarketMaker::StablePool(value) => value.fee_rate.clone(),
         }
     }
 
@@ -99,18 +233,33 @@ impl MarketMaker {
     pub fn dummy_tokens(&self, tokens: &HashMap<CanisterId, Cow<'_, TokenInfo>>, pa: &TokenPairAmm) -> Vec<TokenInfo> {
         match self {
             MarketMaker::SwapV2(value) => value.dummy_tokens(tokens, pa),
+            MarketMaker::SwapV3(value) => value.dummy_tokens(tokens, pa),
+            MarketMaker::StableSwap(value) => value.dummy_tokens(tokens, pa),
+            MarketMaker::PmmV1(value) => value.dummy_tokens(tokens, pa),
+            MarketMaker::Weighted(value) => value.dummy_tokens(tokens, pa),
+            MarketMaker::StablePool(value) => value.dummy_tokens(tokens, pa),
         }
     }
 
     pub fn accounts(&self, self_canister: &SelfCanister) -> Vec<Account> {
         match self {
             MarketMaker::SwapV2(value) => value.accounts(self_canister),
+            MarketMaker::SwapV3(value) => value.accounts(self_canister),
+            MarketMaker::StableSwap(value) => value.accounts(self_canister),
+            MarketMaker::PmmV1(value) => value.accounts(self_canister),
+            MarketMaker::Weighted(value) => value.accounts(self_canister),
+            MarketMaker::StablePool(value) => value.accounts(self_canister),
         }
     }
 
     pub fn dummy_canisters(&self) -> Vec<CanisterId> {
         match self {
             MarketMaker::SwapV2(value) => value.dummy_canisters(),
+            MarketMaker::SwapV3(value) => value.dummy_canisters(),
+            MarketMaker::StableSwap(value) => value.dummy_canisters(),
+            MarketMaker::PmmV1(value) => value.dummy_canisters(),
+            MarketMaker::Weighted(value) => value.dummy_canisters(),
+            MarketMaker::StablePool(value) => value.dummy_canisters(),
         }
     }
 
@@ -128,6 +277,35 @@ impl MarketMaker {
             MarketMaker::SwapV2(value) => {
                 value.check_liquidity_removable(token_balance_of, from, liquidity_without_fee, fee_to)
             }
+            MarketMaker::SwapV3(_) => Err(BusinessError::Liquidity("TICK_RANGE_REQUIRED".into())),
+            MarketMaker::StableSwap(value) => {
+                value.check_liquidity_removable(token_balance_of, from, liquidity_without_fee, fee_to)
+            }
+            MarketMaker::PmmV1(value) => {
+                value.check_liquidity_removable(token_balance_of, from, liquidity_without_fee, fee_to)
+            }
+            MarketMaker::Weighted(value) => {
+                value.check_liquidity_removable(token_balance_of, from, liquidity_without_fee, fee_to)
+            }
+            MarketMaker::StablePool(value) => {
+                value.check_liquidity_removable(token_balance_of, from, liquidity_without_fee, fee_to)
+            }
+        }
+    }
+
+    pub fn check_position_removable(
+        &self,
+        from: &Account,
+        tick_range: &TickRange,
+        liquidity: &Nat,
+    ) -> Result<(), BusinessError> {
+        match self {
+            MarketMaker::SwapV2(_)
+            | MarketMaker::StableSwap(_)
+            | MarketMaker::PmmV1(_)
+            | MarketMaker::Weighted(_)
+            | MarketMaker::StablePool(_) => Err(BusinessError::Liquidity("TICK_RANGE_NOT_SUPPORTED".into())),
+            MarketMaker::SwapV3(value) => value.check_position_removable(from, tick_range, liquidity),
         }
     }
 
@@ -140,6 +318,11 @@ impl MarketMaker {
     ) -> Result<(Account, Nat), BusinessError> {
         match self {
             MarketMaker::SwapV2(value) => value.get_amount_out(self_canister, amount_in, token_in, token_out),
+            MarketMaker::SwapV3(value) => value.get_amount_out(self_canister, amount_in, token_in, token_out),
+            MarketMaker::StableSwap(value) => value.get_amount_out(self_canister, amount_in, token_in, token_out),
+            MarketMaker::PmmV1(value) => value.get_amount_out(self_canister, amount_in, token_in, token_out),
+            MarketMaker::Weighted(value) => value.get_amount_out(self_canister, amount_in, token_in, token_out),
+            MarketMaker::StablePool(value) => value.get_amount_out(self_canister, amount_in, token_in, token_out),
         }
     }
 
@@ -152,36 +335,105 @@ impl MarketMaker {
     ) -> Result<(Account, Nat), BusinessError> {
         match self {
             MarketMaker::SwapV2(value) => value.get_amount_in(self_canister, amount_out, token_in, token_out),
+            MarketMaker::SwapV3(value) => value.get_amount_in(self_canister, amount_out, token_in, token_out),
+            MarketMaker::StableSwap(value) => value.get_amount_in(self_canister, amount_out, token_in, token_out),
+            MarketMaker::PmmV1(value) => value.get_amount_in(self_canister, amount_out, token_in, token_out),
+            MarketMaker::Weighted(value) => value.get_amount_in(self_canister, amount_out, token_in, token_out),
+            MarketMaker::StablePool(value) => value.get_amount_in(self_canister, amount_out, token_in, token_out),
         }
     }
 
     pub fn removable(&self) -> bool {
         match self {
             MarketMaker::SwapV2(value) => value.removable(),
+            MarketMaker::SwapV3(value) => value.removable(),
+            MarketMaker::StableSwap(value) => value.removable(),
+            MarketMaker::PmmV1(value) => value.removable(),
+            MarketMaker::Weighted(value) => value.removable(),
+            MarketMaker::StablePool(value) => value.removable(),
         }
     }
 
     pub fn swap_to(&self, from: &CanisterId, from_amount: f64) -> Option<(CanisterId, f64)> {
         match self {
             MarketMaker::SwapV2(value) => value.swap_to(from, from_amount),
+            MarketMaker::SwapV3(value) => value.swap_to(from, from_amount),
+            MarketMaker::StableSwap(value) => value.swap_to(from, from_amount),
+            MarketMaker::PmmV1(value) => value.swap_to(from, from_amount),
+            MarketMaker::Weighted(value) => value.swap_to(from, from_amount),
+            MarketMaker::StablePool(value) => value.swap_to(from, from_amount),
+        }
+    }
+
+    /// Estimate the output of the given token, members of multi-asset pool are not the only other token
+    pub fn swap_to_token(&self, from: &CanisterId, to: &CanisterId, from_amount: f64) -> Option<f64> {
+        match self {
+            MarketMaker::StablePool(value) => value.swap_to_token(from, to, from_amount),
+            _ => self
+                .swap_to(from, from_amount)
+                .filter(|(token, _)| token == to)
+                .map(|(_, to_amount)| to_amount),
         }
     }
 
     pub fn get_reserve(&self, token: &CanisterId) -> Option<f64> {
         match self {
             MarketMaker::SwapV2(value) => value.get_reserve(token),
+            MarketMaker::SwapV3(value) => value.get_reserve(token),
+            MarketMaker::StableSwap(value) => value.get_reserve(token),
+            MarketMaker::PmmV1(value) => value.get_reserve(token),
+            MarketMaker::Weighted(value) => value.get_reserve(token),
+            MarketMaker::StablePool(value) => value.get_reserve(token),
         }
     }
 
+    /// Exact reserves of 2 tokens, in the order of arguments
+    pub fn get_reserves(&self, token_a: &CanisterId, token_b: &CanisterId) -> Option<(Nat, Nat)> {
+        fn pick(
+            token0: &CanisterId,
+            token1: &CanisterId,
+            reserve0: &Nat,
+            reserve1: &Nat,
+            token: &CanisterId,
+        ) -> Option<Nat> {
+            if token0 == token {
+                Some(reserve0.clone())
+            } else if token1 == token {
+                Some(reserve1.clone())
+            } else {
+                None
+            }
+        }
+        let reserve_of = |token: &CanisterId| match self {
+            MarketMaker::SwapV2(v) => pick(&v.token0, &v.token1, &v.reserve0, &v.reserve1, token),
+            MarketMaker::SwapV3(v) => pick(&v.token0, &v.token1, &v.reserve0, &v.reserve1, token),
+            MarketMaker::StableSwap(v) => pick(&v.token0, &v.token1, &v.reserve0, &v.reserve1, token),
+            MarketMaker::PmmV1(v) => pick(&v.token0, &v.token1, &v.reserve0, &v.reserve1, token),
+            MarketMaker::Weighted(v) => pick(&v.token0, &v.token1, &v.reserve0, &v.reserve1, token),
+            MarketMaker::StablePool(v) => v.index_of(token).ok().map(|i| v.reserves[i].clone()),
+        };
+        Some((reserve_of(token_a)?, reserve_of(token_b)?))
+    }
+
     pub fn get_fee(&self, amount_in: f64) -> f64 {
         match self {
             MarketMaker::SwapV2(value) => value.get_fee(amount_in),
+            MarketMaker::SwapV3(value) => value.get_fee(amount_in),
+            MarketMaker::StableSwap(value) => value.get_fee(amount_in),
+            MarketMaker::PmmV1(value) => value.get_fee(amount_in),
+            MarketMaker::Weighted(value) => value.get_fee(amount_in),
+            MarketMaker::StablePool(value) => value.get_fee(amount_in),
         }
     }
 
     pub fn get_subaccount(&self) -> &Subaccount {
         match self {
             MarketMaker::SwapV2(value) => value.get_subaccount(),
+            MarketMaker::SwapV3(value) => value.get_subaccount(),
+            MarketMaker::StableSwap(value) => value.get_subaccount(),
+            MarketMaker::PmmV1(value) => value.get_subaccount(),
+            MarketMaker::Weighted(value) => value.get_subaccount(),
+            MarketMaker::StablePool(value) => value.get_subaccount(),
         }
     }
 }
@@ -196,6 +448,16 @@ fn new_swap_v2_market_maker(
     SwapV2MarketMaker::new(subaccount, fee_rate, token0, token1, lp, None)
 }
 
+fn new_swap_v3_market_maker(
+    subaccount: Subaccount,
+    fee_rate: SwapRatio,
+    tick_spacing: i32,
+    token0: CanisterId,
+    token1: CanisterId,
+) -> SwapV3MarketMaker {
+    SwapV3MarketMaker::new(subaccount, fee_rate, tick_spacing, token0, token1, None)
+}
+
 // ========================== view ==========================
 
 /// market maker view
@@ -204,12 +466,32 @@ pub enum MarketMakerView {
     /// swap v2
     #[serde(rename = "swap_v2")]
     SwapV2(SwapV2MarketMakerView),
+    /// swap v3
+    #[serde(rename = "swap_v3")]
+    SwapV3(SwapV3MarketMakerView),
+    /// stable swap
+    #[serde(rename = "stable_swap")]
+    StableSwap(StableSwapMarketMakerView),
+    /// pmm v1
+    #[serde(rename = "pmm_v1")]
+    PmmV1(PmmV1MarketMakerView),
+    /// weighted
+    #[serde(rename = "weighted")]
+    Weighted(WeightedMarketMakerView),
+    /// stable pool
+    #[serde(rename = "stable_pool")]
+    StablePool(StablePoolMarketMakerView),
 }
 
 impl From<MarketMaker> for MarketMakerView {
     fn from(value: MarketMaker) -> Self {
         match value {
             MarketMaker::SwapV2(value) => Self::SwapV2(value.into()),
+            MarketMaker::SwapV3(value) => Self::SwapV3(value.into()),
+            MarketMaker::StableSwap(value) => Self::StableSwap(value.into()),
+            MarketMaker::PmmV1(value) => Self::PmmV1(value.into()),
+            MarketMaker::Weighted(value) => Self::Weighted(value.into()),
+            MarketMaker::StablePool(value) => Self::StablePool(value.into()),
         }
     }
 }
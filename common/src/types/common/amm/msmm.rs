@@ -0,0 +1,489 @@
+/// Multi-asset stablecoin market maker（StableSwap AMM of n tokens）
+/// - Formula：A * n^n * Σx + D = A * D * n^n + D^(n+1) / (n^n * Πx)（A is the amplification coefficient）
+/// - Representative Project：Curve 3pool (USDC/USDT/DAI)
+/// - Features
+///   - All the tokens are kept in one pool, any two members can be swapped with each other.
+///   - Liquidity is added by any amounts of members and removed in proportion to balances.
+#[cfg(feature = "cdk")]
+use std::borrow::Cow;
+#[cfg(feature = "cdk")]
+use std::collections::HashMap;
+
+use candid::{CandidType, Nat};
+use icrc_ledger_types::icrc1::account::{Account, Subaccount};
+use num_bigint::BigUint;
+use num_traits::{ToPrimitive, Zero};
+use serde::{Deserialize, Serialize};
+
+use super::{DEFAULT_AMPLIFICATION, MAX_AMPLIFICATION, div_rounding_up};
+use crate::types::PoolLpView;
+#[allow(unused)]
+use crate::{
+    types::{BusinessError, CanisterId, PoolLp, SelfCanister, SwapRatio, SwapRatioView, TokenInfo, TokenPairAmm},
+    utils::math::{ZERO, zero},
+};
+
+const MAX_ITERATIONS: usize = 255;
+
+/// The invariant D of n normalized balances, solved by newton's method
+pub fn get_pool_d(amplification: u64, xp: &[BigUint]) -> Result<BigUint, BusinessError> {
+    let s = xp.iter().sum::<BigUint>();
+    if s.is_zero() {
+        return Ok(BigUint::zero());
+    }
+    if xp.iter().any(|x| x.is_zero()) {
+        return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+    }
+    let n = BigUint::from(xp.len());
+    let ann = BigUint::from(amplification) * n.pow(xp.len() as u32); // A * n^n
+    let mut d = s.clone();
+    for _ in 0..MAX_ITERATIONS {
+        // D^(n+1) / (n^n * Πx)
+        let mut d_p = d.clone();
+        for x in xp {
+            d_p = d_p * &d / (x * &n);
+        }
+        let d_prev = d.clone();
+        d = (&ann * &s + &d_p * &n) * &d / ((&ann - 1_u8) * &d + (&n + 1_u8) * &d_p);
+        let diff = if d > d_prev { &d - &d_prev } else { &d_prev - &d };
+        if diff <= BigUint::from(1_u8) {
+            return Ok(d);
+        }
+    }
+    Err(BusinessError::Swap("INVARIANT_NOT_CONVERGED".into()))
+}
+
+/// The balance of token j when the balance of token i is x and the invariant is D
+pub fn get_pool_y(
+    amplification: u64,
+    i: usize,
+    j: usize,
+    x: &BigUint,
+    xp: &[BigUint],
+    d: &BigUint,
+) -> Result<BigUint, BusinessError> {
+    if i == j || xp.len() <= i || xp.len() <= j {
+        return Err(BusinessError::Swap("INVALID_TOKEN".into()));
+    }
+    let n = BigUint::from(xp.len());
+    let ann = BigUint::from(amplification) * n.pow(xp.len() as u32); // A * n^n
+    // y^2 + (b - D) * y = c
+    let mut c = d.clone();
+    let mut s = BigUint::zero();
+    for (k, balance) in xp.iter().enumerate() {
+        let x = if k == i {
+            x
+        } else if k != j {
+            balance
+        } else {
+            continue;
+        };
+        if x.is_zero() {
+            return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+        }
+        s += x;
+        c = c * d / (x * &n);
+    }
+    let c = c * d / (&ann * &n);
+    let b = s + d / &ann;
+    let mut y = d.clone();
+    for _ in 0..MAX_ITERATIONS {
+        let y_prev = y.clone();
+        let denominator = &y * 2_u8 + &b;
+        if denominator <= *d {
+            return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+        }
+        y = (&y * &y + &c) / (denominator - d);
+        let diff = if y > y_prev { &y - &y_prev } else { &y_prev - &y };
+        if diff <= BigUint::from(1_u8) {
+            return Ok(y);
+        }
+    }
+    Err(BusinessError::Swap("INVARIANT_NOT_CONVERGED".into()))
+}
+
+/// The required data under the current algorithm processing fee
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct StablePoolMarketMaker {
+    pub subaccount: Subaccount, // ! fixed. Fund balance storage location self_canister_id.subaccount
+    pub fee_rate: SwapRatio,    // ! fixed. Transaction rates
+    pub amplification: u64,     // Amplification coefficient, set by admin
+
+    pub tokens: Vec<CanisterId>, // ! fixed. Sorted canister_id of tokens
+    pub multipliers: Vec<Nat>,   // ! fixed. Normalize balance of each token to the max decimals of tokens
+    pub reserves: Vec<Nat>,      // ! The current balance deposited by each token
+    pub block_timestamp_last: u64,
+
+    pub lp: PoolLp, // lp token information, Once the new pool is successfully created, other data cannot be changed except for supply
+    pub protocol_fee: Option<SwapRatio>, // The ratio of swap fee which belongs to swap fee to
+}
+
+impl StablePoolMarketMaker {
+    pub fn new(
+        subaccount: Subaccount,
+        fee_rate: SwapRatio,
+        tokens: &[&TokenInfo],
+        lp: PoolLp,
+        protocol_fee: Option<SwapRatio>,
+    ) -> Self {
+        let decimals = tokens.iter().map(|token| token.decimals).max().unwrap_or_default();
+        let multiplier = |d: u8| Nat::from(BigUint::from(10_u8).pow((decimals - d) as u32));
+        Self {
+            subaccount,
+            fee_rate,
+            amplification: DEFAULT_AMPLIFICATION,
+            tokens: tokens.iter().map(|token| token.canister_id).collect(),
+            multipliers: tokens.iter().map(|token| multiplier(token.decimals)).collect(),
+            reserves: tokens.iter().map(|_| zero()).collect(),
+            block_timestamp_last: 0,
+            lp,
+            protocol_fee,
+        }
+    }
+
+    pub fn replace_protocol_fee(&mut self, protocol_fee: Option<SwapRatio>) -> Option<SwapRatio> {
+        std::mem::replace(&mut self.protocol_fee, protocol_fee)
+    }
+
+    pub fn replace_amplification(&mut self, amplification: u64) -> Result<u64, BusinessError> {
+        if amplification == 0 || MAX_AMPLIFICATION < amplification {
+            return Err(BusinessError::Swap("INVALID_AMPLIFICATION".into()));
+        }
+        Ok(std::mem::replace(&mut self.amplification, amplification))
+    }
+
+    #[cfg(feature = "cdk")]
+    pub fn dummy_tokens(&self, tokens: &HashMap<CanisterId, Cow<'_, TokenInfo>>, pa: &TokenPairAmm) -> Vec<TokenInfo> {
+        self.lp.pool_dummy_tokens(tokens, &self.tokens, &pa.amm)
+    }
+
+    pub fn accounts(&self, self_canister: &SelfCanister) -> Vec<Account> {
+        vec![Account {
+            owner: self_canister.id(),
+            subaccount: Some(self.subaccount),
+        }]
+    }
+
+    pub fn dummy_canisters(&self) -> Vec<CanisterId> {
+        self.lp.dummy_canisters()
+    }
+
+    /// index of member token
+    pub fn index_of(&self, token: &CanisterId) -> Result<usize, BusinessError> {
+        self.tokens
+            .iter()
+            .position(|t| t == token)
+            .ok_or_else(|| BusinessError::Swap("INVALID_TOKEN".into()))
+    }
+
+    // fetches the reserves of 2 members
+    pub fn get_reserves(&self, token_a: CanisterId, token_b: CanisterId) -> (Nat, Nat) {
+        let reserve = |token: &CanisterId| {
+            self.index_of(token)
+                .map(|i| self.reserves[i].clone())
+                .unwrap_or_default()
+        };
+        (reserve(&token_a), reserve(&token_b))
+    }
+
+    pub fn check_liquidity_removable<F>(
+        &self,
+        token_balance_of: F,
+        from: &Account,
+        liquidity_without_fee: &Nat,
+        fee_to: Option<Account>,
+    ) -> Result<(), BusinessError>
+    where
+        F: Fn(CanisterId, Account) -> Result<Nat, BusinessError>,
+    {
+        self.lp
+            .check_liquidity_removable(|token| token_balance_of(token, *from), liquidity_without_fee, fee_to)
+    }
+
+    /// Normalized balances
+    fn get_normalized(&self, reserves: &[Nat]) -> Vec<BigUint> {
+        reserves
+            .iter()
+            .zip(self.multipliers.iter())
+            .map(|(reserve, multiplier)| &reserve.0 * &multiplier.0)
+            .collect()
+    }
+
+    /// The invariant of balances
+    pub fn get_invariant(&self, reserves: &[Nat]) -> Result<Nat, BusinessError> {
+        get_pool_d(self.amplification, &self.get_normalized(reserves)).map(Nat::from)
+    }
+
+    /// Calculate the output amount and the swap fee charged in token out
+    pub fn exchange(
+        &self,
+        amount_in: &Nat,
+        token_in: CanisterId,
+        token_out: CanisterId,
+    ) -> Result<(Nat, Nat), BusinessError> {
+        if *amount_in == *ZERO {
+            return Err(BusinessError::Swap("INSUFFICIENT_INPUT_AMOUNT".into()));
+        }
+        let (i, j) = (self.index_of(&token_in)?, self.index_of(&token_out)?);
+        let xp = self.get_normalized(&self.reserves);
+        let d = get_pool_d(self.amplification, &xp)?;
+        let x = &xp[i] + &amount_in.0 * &self.multipliers[i].0;
+        let y = get_pool_y(self.amplification, i, j, &x, &xp, &d)?;
+        if xp[j] <= &y + 1_u8 {
+            return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+        }
+        let dy = &xp[j] - y - 1_u8; // ! round down in case of rounding errors
+        let fee = &dy * self.fee_rate.numerator / self.fee_rate.denominator;
+        let amount_out = (dy - &fee) / &self.multipliers[j].0;
+        let fee = fee / &self.multipliers[j].0;
+        Ok((Nat::from(amount_out), Nat::from(fee)))
+    }
+
+    // given an input amount of an asset and pool reserves, returns the maximum output amount of the other asset
+    pub fn get_amount_out(
+        &self,
+        self_canister: &SelfCanister,
+        amount_in: &Nat,
+        token_in: CanisterId,
+        token_out: CanisterId,
+    ) -> Result<(Account, Nat), BusinessError> {
+        let pool_account = Account {
+            owner: self_canister.id(),
+            subaccount: Some(self.subaccount),
+        };
+
+        let (amount_out, _) = self.exchange(amount_in, token_in, token_out)?;
+        if amount_out == *ZERO {
+            return Err(BusinessError::Swap("INSUFFICIENT_OUTPUT_AMOUNT".into()));
+        }
+
+        Ok((pool_account, amount_out))
+    }
+
+    // given an output amount of an asset and pool reserves, returns a required input amount of the other asset
+    pub fn get_amount_in(
+        &self,
+        self_canister: &SelfCanister,
+        amount_out: &Nat,
+        token_in: CanisterId,
+        token_out: CanisterId,
+    ) -> Result<(Account, Nat), BusinessError> {
+        let pool_account = Account {
+            owner: self_canister.id(),
+            subaccount: Some(self.subaccount),
+        };
+
+        if *amount_out == *ZERO {
+            return Err(BusinessError::Swap("INSUFFICIENT_OUTPUT_AMOUNT".into()));
+        }
+        let (i, j) = (self.index_of(&token_in)?, self.index_of(&token_out)?);
+        let xp = self.get_normalized(&self.reserves);
+        let d = get_pool_d(self.amplification, &xp)?;
+
+        //                     amount_out * d
+        // dy_with_fee = ------------------------
+        //                        d - n
+        let n = self.fee_rate.numerator;
+        let denominator = self.fee_rate.denominator;
+        let dy_with_fee = div_rounding_up(
+            &(&amount_out.0 * &self.multipliers[j].0 * denominator),
+            &BigUint::from(denominator - n),
+        ) + 2_u8; // ! You must not miss the transfer, and you can get it upward
+        if xp[j] <= dy_with_fee {
+            return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+        }
+        let x = get_pool_y(self.amplification, j, i, &(&xp[j] - dy_with_fee), &xp, &d)?;
+        if x <= xp[i] {
+            return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+        }
+        let amount_in = Nat::from(div_rounding_up(&(x - &xp[i]), &self.multipliers[i].0));
+
+        // check on calculate amount
+        let (max_out, _) = self.exchange(&amount_in, token_in, token_out)?;
+        if max_out < *amount_out {
+            return Err(BusinessError::Swap("K".into()));
+        }
+
+        Ok((pool_account, amount_in))
+    }
+
+    /// Calculate liquidity minted by the amounts of all members, the imbalanced part is charged as fee
+    pub fn get_liquidity_minted(&self, amounts: &[Nat]) -> Result<Nat, BusinessError> {
+        if amounts.len() != self.tokens.len() {
+            return Err(BusinessError::Liquidity("INVALID_AMOUNTS".into()));
+        }
+        let old = self.get_normalized(&self.reserves);
+        let new = old
+            .iter()
+            .zip(amounts.iter().zip(self.multipliers.iter()))
+            .map(|(x, (amount, multiplier))| x + &amount.0 * &multiplier.0)
+            .collect::<Vec<_>>();
+        let total_supply = self.lp.get_total_supply();
+        let d0 = get_pool_d(self.amplification, &old)?;
+        let d1 = get_pool_d(self.amplification, &new)?;
+        if d1 <= d0 {
+            return Err(BusinessError::Liquidity("INSUFFICIENT_LIQUIDITY_MINTED".into()));
+        }
+        if total_supply == *ZERO || d0.is_zero() {
+            return Ok(Nat::from(d1)); // No fee for the first time
+        }
+
+        // fee = fee_rate * n / (4 * (n - 1)) for each token
+        let count = self.tokens.len() as u64;
+        let n = self.fee_rate.numerator as u64 * count;
+        let denominator = self.fee_rate.denominator as u64 * 4 * (count - 1);
+        let adjusted = old
+            .iter()
+            .zip(new.iter())
+            .map(|(old, new)| {
+                let ideal = &d1 * old / &d0;
+                let difference = if ideal > *new { ideal - new } else { new - ideal };
+                new - difference * n / denominator
+            })
+            .collect::<Vec<_>>();
+        let d2 = get_pool_d(self.amplification, &adjusted)?;
+        if d2 <= d0 {
+            return Err(BusinessError::Liquidity("INSUFFICIENT_LIQUIDITY_MINTED".into()));
+        }
+        Ok(Nat::from(total_supply.0 * (d2 - &d0) / d0))
+    }
+
+    pub fn removable(&self) -> bool {
+        self.lp.removable()
+    }
+
+    pub fn swap_to(&self, from: &CanisterId, from_amount: f64) -> Option<(CanisterId, f64)> {
+        // the first other member is the target
+        let to = *self.tokens.iter().find(|token| *token != from)?;
+        Some((to, self.swap_to_token(from, &to, from_amount)?))
+    }
+
+    /// Estimate the output of the given member by marginal price, fee is not deducted
+    pub fn swap_to_token(&self, from: &CanisterId, to: &CanisterId, from_amount: f64) -> Option<f64> {
+        if self.reserves.contains(&ZERO) || from == to {
+            return None;
+        }
+        // probe by a small part of the reserve, so the rounding error is small
+        let i = self.index_of(from).ok()?;
+        self.index_of(to).ok()?;
+        let unit = Nat::from((self.reserves[i].0.clone() / 10_000_u32).max(BigUint::from(1_u8)));
+        let (to_amount, fee) = self.exchange(&unit, *from, *to).ok()?;
+        Some(from_amount * (to_amount.0 + fee.0).to_f64()? / unit.0.to_f64()?)
+    }
+
+    pub fn get_reserve(&self, token: &CanisterId) -> Option<f64> {
+        let i = self.index_of(token).ok()?;
+        self.reserves[i].0.to_f64()
+    }
+
+    pub fn get_fee(&self, amount_in: f64) -> f64 {
+        let n = self.fee_rate.numerator as f64;
+        let d = self.fee_rate.denominator as f64;
+        amount_in * n / d
+    }
+
+    pub fn get_subaccount(&self) -> &Subaccount {
+        &self.subaccount
+    }
+}
+
+// ========================== view ==========================
+
+/// stable pool
+#[derive(Debug, Serialize, Deserialize, Clone, CandidType)]
+pub struct StablePoolMarketMakerView {
+    subaccount: String,
+    fee_rate: SwapRatioView,
+    amplification: u64,
+
+    tokens: Vec<String>,
+    multipliers: Vec<String>,
+    reserves: Vec<String>,
+    block_timestamp_last: u64,
+
+    lp: PoolLpView,
+    protocol_fee: Option<SwapRatioView>,
+}
+
+impl From<StablePoolMarketMaker> for StablePoolMarketMakerView {
+    fn from(value: StablePoolMarketMaker) -> Self {
+        Self {
+            subaccount: hex::encode(value.subaccount),
+            fee_rate: value.fee_rate.into(),
+            amplification: value.amplification,
+            tokens: value.tokens.iter().map(|token| token.to_string()).collect(),
+            multipliers: value.multipliers.iter().map(|m| m.to_string()).collect(),
+            reserves: value.reserves.iter().map(|r| r.to_string()).collect(),
+            block_timestamp_last: value.block_timestamp_last,
+            lp: value.lp.into(),
+            protocol_fee: value.protocol_fee.map(|f| f.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_invariant() {
+        // balanced pool, D is the sum of balances
+        let x = BigUint::from(1_000_000_000_u64);
+        let xp = vec![x.clone(), x.clone(), x.clone()];
+        let d = get_pool_d(100, &xp).unwrap();
+        assert_eq!(d, BigUint::from(3_000_000_000_u64));
+
+        // same as the stable swap of 2 tokens
+        let d2 = get_pool_d(100, &xp[..2]).unwrap();
+        assert_eq!(d2, super::super::get_d(100, &x, &x).unwrap());
+
+        // low slippage near the peg
+        let x_in = BigUint::from(1_010_000_000_u64);
+        let y = get_pool_y(100, 0, 2, &x_in, &xp, &d).unwrap();
+        let out = x - y;
+        assert!(BigUint::from(9_990_000_u64) < out && out < BigUint::from(10_000_000_u64));
+    }
+
+    #[test]
+    fn test_swap_to_token() {
+        use crate::types::DummyCanisterId;
+
+        let token = |id: u8| TokenInfo {
+            canister_id: CanisterId::from_slice(&[id]),
+            name: "Token".into(),
+            symbol: "T".into(),
+            decimals: 6,
+            fee: Nat::from(10_u8),
+            is_lp_token: false,
+        };
+        let tokens = [token(1), token(2), token(3)];
+        let tokens = tokens.iter().collect::<Vec<_>>();
+        let lp = PoolLp::new_inner_pool_lp(DummyCanisterId::new(CanisterId::from_slice(&[9])), &tokens);
+        let fee_rate = SwapRatio {
+            numerator: 4,
+            denominator: 10_000,
+        };
+        let mut maker = StablePoolMarketMaker::new([0; 32], fee_rate, &tokens, lp, None);
+        maker.reserves = vec![
+            Nat::from(1_000_000_000_000_u64),
+            Nat::from(1_000_000_000_000_u64),
+            Nat::from(100_000_000_000_u64), // the third member is scarce
+        ];
+        let (token1, token2, token3) = (tokens[0].canister_id, tokens[1].canister_id, tokens[2].canister_id);
+
+        // the estimation follows the given member, not the first other member
+        let to2 = maker.swap_to_token(&token1, &token2, 1_000_000_f64).unwrap();
+        let to3 = maker.swap_to_token(&token1, &token3, 1_000_000_f64).unwrap();
+        assert!(to3 < to2);
+        assert_eq!(maker.swap_to(&token1, 1_000_000_f64).unwrap(), (token2, to2));
+
+        // close to the exact output of a small trade, fee is not deducted
+        for (to, estimated) in [(token2, to2), (token3, to3)] {
+            let (amount_out, fee) = maker.exchange(&Nat::from(1_000_000_u64), token1, to).unwrap();
+            let exact = (amount_out.0 + fee.0).to_f64().unwrap();
+            assert!((estimated - exact).abs() / exact < 0.001);
+        }
+        assert!(maker.swap_to_token(&token1, &token1, 1_000_000_f64).is_none());
+    }
+}
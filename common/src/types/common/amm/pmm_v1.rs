@@ -1,5 +1,592 @@
-// for AMM, P = B0 / B
-// PMM v1
-// R = 1 - k + (B0 / B)^2 * k
-// if B
-// P = i * (1 - k +)
+/// Proactive market maker（PMM v1）
+/// - Formula：P = i * R, R = 1 - k + (B0 / B)^2 * k when base is short, R = 1 / (1 - k + (Q0 / Q)^2 * k) when quote is short
+/// - Representative Project：DODO
+/// - Features
+///   - The reference price i is provided by an oracle (maintainer), the pool price follows it.
+///   - The slippage parameter k (0 ~ 1) controls the depth around the reference price, k = 0 is constant price.
+///   - Base and quote have their own target (B0, Q0), so one-sided liquidity can be deposited.
+#[cfg(feature = "cdk")]
+use std::borrow::Cow;
+#[cfg(feature = "cdk")]
+use std::collections::HashMap;
+
+use candid::{CandidType, Nat};
+use icrc_ledger_types::icrc1::account::{Account, Subaccount};
+use num_bigint::BigUint;
+use num_traits::{ToPrimitive, Zero};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use super::div_rounding_up;
+use crate::types::PoolLpView;
+#[allow(unused)]
+use crate::{
+    types::{BusinessError, CanisterId, PoolLp, SelfCanister, SwapRatio, SwapRatioView, TokenInfo, TokenPairAmm},
+    utils::{
+        math::{ZERO, zero},
+        principal::sort_tokens,
+    },
+};
+
+/// The decimal one of i and k
+pub const PMM_V1_ONE: u64 = 1_000_000_000_000_000_000;
+/// The slippage parameter of new pool, 0.1
+pub const DEFAULT_K: u64 = 100_000_000_000_000_000;
+
+static ONE: Lazy<BigUint> = Lazy::new(|| BigUint::from(PMM_V1_ONE));
+static ONE2: Lazy<BigUint> = Lazy::new(|| BigUint::from(PMM_V1_ONE) * BigUint::from(PMM_V1_ONE));
+
+// ========================== math ==========================
+
+/// 1 / i
+fn reciprocal(i: &BigUint) -> BigUint {
+    &*ONE2 / i
+}
+
+/// Integrate the price curve from V1 to V2, V0 is the target
+/// res = i * (V1 - V2) * (1 - k + k * V0^2 / V1 / V2)
+fn general_integrate(
+    v0: &BigUint,
+    v1: &BigUint,
+    v2: &BigUint,
+    i: &BigUint,
+    k: &BigUint,
+) -> Result<BigUint, BusinessError> {
+    if v0.is_zero() || v2.is_zero() || v1 < v2 {
+        return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+    }
+    let fair_amount = i * (v1 - v2); // i * delta
+    if k.is_zero() {
+        return Ok(fair_amount / &*ONE);
+    }
+    let v0v0v1v2 = v0 * v0 / v1 * &*ONE / v2;
+    let penalty = k * v0v0v1v2 / &*ONE; // k * V0^2 / V1 / V2
+    Ok((&*ONE - k + penalty) * fair_amount / &*ONE2)
+}
+
+/// Solve the quadratic function for the amount out when delta is paid
+/// (1 - k) * V2^2 + (k * V0^2 / V1 - i * delta - (1 - k) * V1) * V2 - k * V0^2 = 0, res = V1 - V2
+fn solve_quadratic_function_for_trade(
+    v0: &BigUint,
+    v1: &BigUint,
+    delta: &BigUint,
+    i: &BigUint,
+    k: &BigUint,
+) -> Result<BigUint, BusinessError> {
+    if v0.is_zero() {
+        return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+    }
+    if delta.is_zero() || v1.is_zero() {
+        return Ok(BigUint::zero());
+    }
+
+    let idelta = i * delta;
+    if k.is_zero() {
+        let amount = idelta / &*ONE;
+        return Ok(if amount > *v1 { v1.clone() } else { amount });
+    }
+    if *k == *ONE {
+        // V2 = V1 / (1 + i * delta * V1 / V0^2)
+        let temp = idelta * v1 / (v0 * v0);
+        return Ok(v1 * &temp / (temp + &*ONE));
+    }
+
+    // b = k * V0^2 / V1 - i * delta - (1 - k) * V1
+    let part2 = k * v0 / v1 * v0 + idelta;
+    let part1 = (&*ONE - k) * v1;
+    let (b_abs, b_sig) = if part1 >= part2 {
+        (part1 - part2, false)
+    } else {
+        (part2 - part1, true)
+    };
+    let b_abs = b_abs / &*ONE;
+
+    // sqrt(b^2 + 4 * (1 - k) * k * V0^2)
+    let square_root = (&*ONE - k) * 4_u8 * (k * v0 / &*ONE * v0) / &*ONE;
+    let square_root = (&b_abs * &b_abs + square_root).sqrt();
+
+    let denominator = (&*ONE - k) * 2_u8;
+    let numerator = if b_sig {
+        square_root - b_abs
+    } else {
+        b_abs + square_root
+    };
+    let v2 = div_rounding_up(&(numerator * &*ONE), &denominator);
+    Ok(if v2 > *v1 { BigUint::zero() } else { v1 - v2 })
+}
+
+/// Solve the quadratic function for the target when the spare amount delta is sold back
+/// V0 = V1 * (1 + (sqrt(1 + 4 * k * i * delta / V1) - 1) / (2 * k))
+fn solve_quadratic_function_for_target(v1: &BigUint, delta: &BigUint, i: &BigUint, k: &BigUint) -> BigUint {
+    if k.is_zero() {
+        return v1 + i * delta / &*ONE;
+    }
+    if v1.is_zero() {
+        return BigUint::zero();
+    }
+    let ki = k * i * 4_u8;
+    let sqrt = if ki.is_zero() {
+        ONE.clone()
+    } else {
+        (ki * delta / v1 + &*ONE2).sqrt()
+    };
+    let premium = (sqrt - &*ONE) * &*ONE / (k * 2_u8) + &*ONE;
+    v1 * premium / &*ONE
+}
+
+// ========================== status ==========================
+
+/// The relation between balances and targets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub enum PmmV1RStatus {
+    /// B = B0, Q = Q0
+    #[serde(rename = "one")]
+    One,
+    /// B < B0, Q > Q0, base is short
+    #[serde(rename = "above_one")]
+    AboveOne,
+    /// B > B0, Q < Q0, quote is short
+    #[serde(rename = "below_one")]
+    BelowOne,
+}
+
+/// The result of exchange
+#[derive(Debug, Clone)]
+pub struct PmmV1Exchanged {
+    pub amount_out: Nat,     // amount out without fee
+    pub fee: Nat,            // fee charged in token out
+    pub r: PmmV1RStatus,     // new status
+    pub targets: (Nat, Nat), // targets before exchanged
+}
+
+/// The required data under the current algorithm processing fee
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct PmmV1MarketMaker {
+    pub subaccount: Subaccount, // ! fixed. Fund balance storage location self_canister_id.subaccount
+    pub fee_rate: SwapRatio,    // ! fixed. Transaction rates
+
+    pub token0: CanisterId, // ! Canister_id of the base token
+    pub token1: CanisterId, // ! Canister_id of the quote token
+    pub i: Nat,             // Reference price, how many token1 per token0, scaled by 1e18, set by admin
+    pub k: Nat,             // Slippage parameter, scaled by 1e18, set by admin
+    pub reserve0: Nat,      // ! The current balance deposited by token0
+    pub reserve1: Nat,      // ! The current balance deposited by token1
+    pub target0: Nat,       // ! The target of token0
+    pub target1: Nat,       // ! The target of token1
+    pub r: PmmV1RStatus,    // ! The relation between balances and targets
+    pub block_timestamp_last: u64,
+
+    pub lp: PoolLp, // lp token information, Once the new pool is successfully created, other data cannot be changed except for supply
+    pub protocol_fee: Option<SwapRatio>, // The ratio of swap fee which belongs to swap fee to
+}
+
+impl PmmV1MarketMaker {
+    pub fn new(
+        subaccount: Subaccount,
+        fee_rate: SwapRatio,
+        token0: CanisterId,
+        token1: CanisterId,
+        lp: PoolLp,
+        protocol_fee: Option<SwapRatio>,
+    ) -> Self {
+        Self {
+            subaccount,
+            fee_rate,
+            token0,
+            token1,
+            i: zero(), // ! must be set before liquidity is added
+            k: Nat::from(DEFAULT_K),
+            reserve0: zero(),
+            reserve1: zero(),
+            target0: zero(),
+            target1: zero(),
+            r: PmmV1RStatus::One,
+            block_timestamp_last: 0,
+            lp,
+            protocol_fee,
+        }
+    }
+
+    pub fn replace_protocol_fee(&mut self, protocol_fee: Option<SwapRatio>) -> Option<SwapRatio> {
+        std::mem::replace(&mut self.protocol_fee, protocol_fee)
+    }
+
+    /// Replace the reference price and the slippage parameter
+    pub fn replace_price(&mut self, i: Nat, k: Option<Nat>) -> Result<(Nat, Nat), BusinessError> {
+        if i == *ZERO {
+            return Err(BusinessError::Swap("INVALID_PRICE".into()));
+        }
+        if k.as_ref().is_some_and(|k| k.0 > *ONE) {
+            return Err(BusinessError::Swap("INVALID_K".into()));
+        }
+        let old_i = std::mem::replace(&mut self.i, i);
+        let old_k = match k {
+            Some(k) => std::mem::replace(&mut self.k, k),
+            None => self.k.clone(),
+        };
+        Ok((old_i, old_k))
+    }
+
+    #[cfg(feature = "cdk")]
+    pub fn dummy_tokens(&self, tokens: &HashMap<CanisterId, Cow<'_, TokenInfo>>, pa: &TokenPairAmm) -> Vec<TokenInfo> {
+        self.lp.dummy_tokens(tokens, pa)
+    }
+
+    pub fn accounts(&self, self_canister: &SelfCanister) -> Vec<Account> {
+        vec![Account {
+            owner: self_canister.id(),
+            subaccount: Some(self.subaccount),
+        }]
+    }
+
+    pub fn dummy_canisters(&self) -> Vec<CanisterId> {
+        self.lp.dummy_canisters()
+    }
+
+    // fetches and sorts the reserves for a pair
+    pub fn get_reserves(&self, token_a: CanisterId, token_b: CanisterId) -> (Nat, Nat) {
+        let (token0, _) = sort_tokens(token_a, token_b);
+        let (reserve0, reserve1) = (self.reserve0.clone(), self.reserve1.clone());
+        if token_a == token0 {
+            (reserve0, reserve1)
+        } else {
+            (reserve1, reserve0)
+        }
+    }
+
+    pub fn check_liquidity_removable<F>(
+        &self,
+        token_balance_of: F,
+        from: &Account,
+        liquidity_without_fee: &Nat,
+        fee_to: Option<Account>,
+    ) -> Result<(), BusinessError>
+    where
+        F: Fn(CanisterId, Account) -> Result<Nat, BusinessError>,
+    {
+        self.lp
+            .check_liquidity_removable(|token| token_balance_of(token, *from), liquidity_without_fee, fee_to)
+    }
+
+    /// The targets if the pool is traded back to R = 1 by the reference price
+    pub fn get_expected_targets(&self) -> (Nat, Nat) {
+        let (b, q) = (&self.reserve0.0, &self.reserve1.0);
+        let (b0, q0) = (&self.target0.0, &self.target1.0);
+        let (i, k) = (&self.i.0, &self.k.0);
+        match self.r {
+            PmmV1RStatus::One => (self.target0.clone(), self.target1.clone()),
+            PmmV1RStatus::BelowOne => {
+                let spare = if b > b0 { b - b0 } else { BigUint::zero() };
+                let q0 = solve_quadratic_function_for_target(q, &spare, i, k);
+                (self.target0.clone(), Nat::from(q0))
+            }
+            PmmV1RStatus::AboveOne => {
+                let spare = if q > q0 { q - q0 } else { BigUint::zero() };
+                let b0 = solve_quadratic_function_for_target(b, &spare, &reciprocal(i), k);
+                (Nat::from(b0), self.target1.clone())
+            }
+        }
+    }
+
+    /// The amount of token1 received when amount of token0 is paid, fee is not charged
+    fn sell_base(
+        &self,
+        amount: &BigUint,
+        b0: &BigUint,
+        q0: &BigUint,
+    ) -> Result<(BigUint, PmmV1RStatus), BusinessError> {
+        let (b, q) = (&self.reserve0.0, &self.reserve1.0);
+        let (i, k) = (&self.i.0, &self.k.0);
+        match self.r {
+            PmmV1RStatus::One => Ok((
+                solve_quadratic_function_for_trade(q0, q0, amount, i, k)?,
+                PmmV1RStatus::BelowOne,
+            )),
+            PmmV1RStatus::AboveOne => {
+                let back_to_one_pay_base = if b0 > b { b0 - b } else { BigUint::zero() };
+                let back_to_one_receive_quote = if q > q0 { q - q0 } else { BigUint::zero() };
+                if *amount < back_to_one_pay_base {
+                    let receive = general_integrate(b0, &(b + amount), b, i, k)?;
+                    let receive = receive.min(back_to_one_receive_quote);
+                    Ok((receive, PmmV1RStatus::AboveOne))
+                } else if *amount == back_to_one_pay_base {
+                    Ok((back_to_one_receive_quote, PmmV1RStatus::One))
+                } else {
+                    let rest = solve_quadratic_function_for_trade(q0, q0, &(amount - back_to_one_pay_base), i, k)?;
+                    Ok((back_to_one_receive_quote + rest, PmmV1RStatus::BelowOne))
+                }
+            }
+            PmmV1RStatus::BelowOne => Ok((
+                solve_quadratic_function_for_trade(q0, q, amount, i, k)?,
+                PmmV1RStatus::BelowOne,
+            )),
+        }
+    }
+
+    /// The amount of token0 received when amount of token1 is paid, fee is not charged
+    fn sell_quote(
+        &self,
+        amount: &BigUint,
+        b0: &BigUint,
+        q0: &BigUint,
+    ) -> Result<(BigUint, PmmV1RStatus), BusinessError> {
+        let (b, q) = (&self.reserve0.0, &self.reserve1.0);
+        let (i, k) = (reciprocal(&self.i.0), &self.k.0);
+        match self.r {
+            PmmV1RStatus::One => Ok((
+                solve_quadratic_function_for_trade(b0, b0, amount, &i, k)?,
+                PmmV1RStatus::AboveOne,
+            )),
+            PmmV1RStatus::AboveOne => Ok((
+                solve_quadratic_function_for_trade(b0, b, amount, &i, k)?,
+                PmmV1RStatus::AboveOne,
+            )),
+            PmmV1RStatus::BelowOne => {
+                let back_to_one_pay_quote = if q0 > q { q0 - q } else { BigUint::zero() };
+                let back_to_one_receive_base = if b > b0 { b - b0 } else { BigUint::zero() };
+                if *amount < back_to_one_pay_quote {
+                    let receive = general_integrate(q0, &(q + amount), q, &i, k)?;
+                    let receive = receive.min(back_to_one_receive_base);
+                    Ok((receive, PmmV1RStatus::BelowOne))
+                } else if *amount == back_to_one_pay_quote {
+                    Ok((back_to_one_receive_base, PmmV1RStatus::One))
+                } else {
+                    let rest = solve_quadratic_function_for_trade(b0, b0, &(amount - back_to_one_pay_quote), &i, k)?;
+                    Ok((back_to_one_receive_base + rest, PmmV1RStatus::AboveOne))
+                }
+            }
+        }
+    }
+
+    /// Calculate the output amount, the swap fee charged in token out and the new status
+    pub fn exchange(
+        &self,
+        amount_in: &Nat,
+        token_in: CanisterId,
+        token_out: CanisterId,
+    ) -> Result<PmmV1Exchanged, BusinessError> {
+        if *amount_in == *ZERO {
+            return Err(BusinessError::Swap("INSUFFICIENT_INPUT_AMOUNT".into()));
+        }
+        if self.i == *ZERO {
+            return Err(BusinessError::Swap("PRICE_NOT_SET".into()));
+        }
+        let (b0, q0) = self.get_expected_targets();
+        let (receive, r) = if token_in == self.token0 && token_out == self.token1 {
+            self.sell_base(&amount_in.0, &b0.0, &q0.0)?
+        } else if token_in == self.token1 && token_out == self.token0 {
+            self.sell_quote(&amount_in.0, &b0.0, &q0.0)?
+        } else {
+            return Err(BusinessError::Swap("INVALID_TOKEN".into()));
+        };
+        let reserve_out = if token_out == self.token0 {
+            &self.reserve0
+        } else {
+            &self.reserve1
+        };
+        if receive >= reserve_out.0 {
+            return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+        }
+        let fee = &receive * self.fee_rate.numerator / self.fee_rate.denominator;
+        Ok(PmmV1Exchanged {
+            amount_out: Nat::from(receive - &fee),
+            fee: Nat::from(fee),
+            r,
+            targets: (b0, q0),
+        })
+    }
+
+    // given an input amount of an asset and pair reserves, returns the maximum output amount of the other asset
+    pub fn get_amount_out(
+        &self,
+        self_canister: &SelfCanister,
+        amount_in: &Nat,
+        token_in: CanisterId,
+        token_out: CanisterId,
+    ) -> Result<(Account, Nat), BusinessError> {
+        let pool_account = Account {
+            owner: self_canister.id(),
+            subaccount: Some(self.subaccount),
+        };
+
+        let amount_out = self.exchange(amount_in, token_in, token_out)?.amount_out;
+        if amount_out == *ZERO {
+            return Err(BusinessError::Swap("INSUFFICIENT_OUTPUT_AMOUNT".into()));
+        }
+
+        Ok((pool_account, amount_out))
+    }
+
+    // given an output amount of an asset and pair reserves, returns a required input amount of the other asset
+    pub fn get_amount_in(
+        &self,
+        self_canister: &SelfCanister,
+        amount_out: &Nat,
+        token_in: CanisterId,
+        token_out: CanisterId,
+    ) -> Result<(Account, Nat), BusinessError> {
+        let pool_account = Account {
+            owner: self_canister.id(),
+            subaccount: Some(self.subaccount),
+        };
+
+        if *amount_out == *ZERO {
+            return Err(BusinessError::Swap("INSUFFICIENT_OUTPUT_AMOUNT".into()));
+        }
+
+        // The curve has no closed form for exact output, search the minimum input
+        let enough = |amount_in: &BigUint| -> Result<bool, BusinessError> {
+            Ok(self
+                .exchange(&Nat::from(amount_in.clone()), token_in, token_out)?
+                .amount_out
+                >= *amount_out)
+        };
+        let mut high = BigUint::from(1_u8);
+        while !enough(&high)? {
+            high <<= 1;
+        }
+        let mut low = &high >> 1;
+        while &low + 1_u8 < high {
+            let middle = (&low + &high) >> 1;
+            if enough(&middle)? {
+                high = middle;
+            } else {
+                low = middle;
+            }
+        }
+
+        Ok((pool_account, Nat::from(high)))
+    }
+
+    /// Calculate liquidity minted by the amounts, any side can be zero.
+    /// The value is measured in token1 by the reference price and the expected targets.
+    pub fn get_liquidity_minted(&self, amount0: &Nat, amount1: &Nat) -> Result<Nat, BusinessError> {
+        if self.i == *ZERO {
+            return Err(BusinessError::Liquidity("PRICE_NOT_SET".into()));
+        }
+        let i = &self.i.0;
+        let value = &amount0.0 * i / &*ONE + &amount1.0;
+        if value.is_zero() {
+            return Err(BusinessError::Liquidity("INSUFFICIENT_LIQUIDITY_MINTED".into()));
+        }
+        let total_supply = self.lp.get_total_supply();
+        let (b0, q0) = self.get_expected_targets();
+        let total_value = &b0.0 * i / &*ONE + &q0.0;
+        if total_supply == *ZERO || total_value.is_zero() {
+            return Ok(Nat::from(value));
+        }
+        Ok(Nat::from(total_supply.0 * value / total_value))
+    }
+
+    pub fn removable(&self) -> bool {
+        self.lp.removable()
+    }
+
+    pub fn swap_to(&self, from: &CanisterId, from_amount: f64) -> Option<(CanisterId, f64)> {
+        if self.reserve0 == *ZERO || self.reserve1 == *ZERO || self.i == *ZERO {
+            return None;
+        }
+        // follow the reference price
+        let price = self.i.0.to_f64()? / PMM_V1_ONE as f64;
+        if self.token0 == *from {
+            Some((self.token1, from_amount * price))
+        } else if self.token1 == *from {
+            Some((self.token0, from_amount / price))
+        } else {
+            None
+        }
+    }
+
+    pub fn get_reserve(&self, token: &CanisterId) -> Option<f64> {
+        if self.token0 == *token {
+            self.reserve0.0.to_f64()
+        } else if self.token1 == *token {
+            self.reserve1.0.to_f64()
+        } else {
+            None
+        }
+    }
+
+    pub fn get_fee(&self, amount_in: f64) -> f64 {
+        let n = self.fee_rate.numerator as f64;
+        let d = self.fee_rate.denominator as f64;
+        amount_in * n / d
+    }
+
+    pub fn get_subaccount(&self) -> &Subaccount {
+        &self.subaccount
+    }
+}
+
+// ========================== view ==========================
+
+/// pmm v1
+#[derive(Debug, Serialize, Deserialize, Clone, CandidType)]
+pub struct PmmV1MarketMakerView {
+    subaccount: String,
+    fee_rate: SwapRatioView,
+
+    token0: String,
+    token1: String,
+    i: String,
+    k: String,
+    reserve0: String,
+    reserve1: String,
+    target0: String,
+    target1: String,
+    r: PmmV1RStatus,
+    block_timestamp_last: u64,
+
+    lp: PoolLpView,
+    protocol_fee: Option<SwapRatioView>,
+}
+
+impl From<PmmV1MarketMaker> for PmmV1MarketMakerView {
+    fn from(value: PmmV1MarketMaker) -> Self {
+        Self {
+            subaccount: hex::encode(value.subaccount),
+            fee_rate: value.fee_rate.into(),
+            token0: value.token0.to_string(),
+            token1: value.token1.to_string(),
+            i: value.i.to_string(),
+            k: value.k.to_string(),
+            reserve0: value.reserve0.to_string(),
+            reserve1: value.reserve1.to_string(),
+            target0: value.target0.to_string(),
+            target1: value.target1.to_string(),
+            r: value.r,
+            block_timestamp_last: value.block_timestamp_last,
+            lp: value.lp.into(),
+            protocol_fee: value.protocol_fee.map(|f| f.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trade() {
+        let one = ONE.clone();
+        let q0 = BigUint::from(1_000_000_000_u64);
+
+        // k = 0, constant price
+        let out =
+            solve_quadratic_function_for_trade(&q0, &q0, &BigUint::from(1_000_u64), &one, &BigUint::zero()).unwrap();
+        assert_eq!(out, BigUint::from(1_000_u64));
+
+        // the more is sold, the worse is the price
+        let k = BigUint::from(DEFAULT_K);
+        let small = solve_quadratic_function_for_trade(&q0, &q0, &BigUint::from(1_000_u64), &one, &k).unwrap();
+        let large = solve_quadratic_function_for_trade(&q0, &q0, &BigUint::from(100_000_000_u64), &one, &k).unwrap();
+        assert!(small <= BigUint::from(1_000_u64) && BigUint::from(990_u64) < small);
+        assert!(large < BigUint::from(100_000_000_u64));
+
+        // selling back the spare base restores the target
+        let q = &q0 - &large;
+        let target = solve_quadratic_function_for_target(&q, &BigUint::from(100_000_000_u64), &one, &k);
+        assert!(target.clone() + 10_u8 >= q0 && target <= q0.clone() + 10_u8);
+    }
+}
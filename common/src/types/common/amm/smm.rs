@@ -1,7 +1,452 @@
 /// Stablecoin market maker（StableSwap AMM）
-/// - Formula：(x + y)^n = k（n is the regulation parameter）
+/// - Formula：A * n^n * Σx + D = A * D * n^n + D^(n+1) / (n^n * Πx)（A is the amplification coefficient）
 /// - Representative Project：Curve、Fei Protocol
 /// - Features
 ///   - Designed for stable coins or similar assets, the slippage is extremely low.
-///   - Reduce arbitrage opportunities through the "virtual price" mechanism.
+///   - Behaves like constant sum near the peg and like constant product far from it.
 ///   - Curve supports complex pools such as 3pool (USDC/USDT/DAI).
+#[cfg(feature = "cdk")]
+use std::borrow::Cow;
+#[cfg(feature = "cdk")]
+use std::collections::HashMap;
+
+use candid::{CandidType, Nat};
+use icrc_ledger_types::icrc1::account::{Account, Subaccount};
+use num_bigint::BigUint;
+use num_traits::{ToPrimitive, Zero};
+use serde::{Deserialize, Serialize};
+
+use super::div_rounding_up;
+use crate::types::PoolLpView;
+#[allow(unused)]
+use crate::{
+    types::{BusinessError, CanisterId, PoolLp, SelfCanister, SwapRatio, SwapRatioView, TokenInfo, TokenPairAmm},
+    utils::{
+        math::{ZERO, zero},
+        principal::sort_tokens,
+    },
+};
+
+/// The amplification of new pool
+pub const DEFAULT_AMPLIFICATION: u64 = 100;
+/// The max amplification can be set
+pub const MAX_AMPLIFICATION: u64 = 1_000_000;
+
+const N_COINS: u8 = 2;
+const MAX_ITERATIONS: usize = 255;
+
+/// The invariant D of normalized balances, solved by newton's method
+pub fn get_d(amplification: u64, x0: &BigUint, x1: &BigUint) -> Result<BigUint, BusinessError> {
+    let s = x0 + x1;
+    if s.is_zero() {
+        return Ok(BigUint::zero());
+    }
+    if x0.is_zero() || x1.is_zero() {
+        return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+    }
+    let n = BigUint::from(N_COINS);
+    let ann = BigUint::from(amplification) * &n * &n; // A * n^n
+    let mut d = s.clone();
+    for _ in 0..MAX_ITERATIONS {
+        // D^(n+1) / (n^n * Πx)
+        let mut d_p = d.clone();
+        d_p = d_p * &d / (x0 * &n);
+        d_p = d_p * &d / (x1 * &n);
+        let d_prev = d.clone();
+        d = (&ann * &s + &d_p * &n) * &d / ((&ann - 1_u8) * &d + (&n + 1_u8) * &d_p);
+        let diff = if d > d_prev { &d - &d_prev } else { &d_prev - &d };
+        if diff <= BigUint::from(1_u8) {
+            return Ok(d);
+        }
+    }
+    Err(BusinessError::Swap("INVARIANT_NOT_CONVERGED".into()))
+}
+
+/// The balance of the other coin when the balance of one coin is x and the invariant is D
+pub fn get_y(amplification: u64, x: &BigUint, d: &BigUint) -> Result<BigUint, BusinessError> {
+    if x.is_zero() {
+        return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+    }
+    let n = BigUint::from(N_COINS);
+    let ann = BigUint::from(amplification) * &n * &n; // A * n^n
+    // y^2 + (b - D) * y = c
+    let c = d * d / (x * &n);
+    let c = c * d / (&ann * &n);
+    let b = x + d / &ann;
+    let mut y = d.clone();
+    for _ in 0..MAX_ITERATIONS {
+        let y_prev = y.clone();
+        let denominator = &y * 2_u8 + &b;
+        if denominator <= *d {
+            return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+        }
+        y = (&y * &y + &c) / (denominator - d);
+        let diff = if y > y_prev { &y - &y_prev } else { &y_prev - &y };
+        if diff <= BigUint::from(1_u8) {
+            return Ok(y);
+        }
+    }
+    Err(BusinessError::Swap("INVARIANT_NOT_CONVERGED".into()))
+}
+
+/// The required data under the current algorithm processing fee
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct StableSwapMarketMaker {
+    pub subaccount: Subaccount, // ! fixed. Fund balance storage location self_canister_id.subaccount
+    pub fee_rate: SwapRatio,    // ! fixed. Transaction rates
+    pub amplification: u64,     // Amplification coefficient, set by admin
+
+    pub token0: CanisterId, // ! Canister_id of the current token0
+    pub token1: CanisterId, // ! Canister_id of the current token1
+    pub multiplier0: Nat,   // ! fixed. Normalize balance of token0 to the max decimals of 2 tokens
+    pub multiplier1: Nat,   // ! fixed. Normalize balance of token1 to the max decimals of 2 tokens
+    pub reserve0: Nat,      // ! The current balance deposited by token0
+    pub reserve1: Nat,      // ! The current balance deposited by token1
+    pub block_timestamp_last: u64,
+
+    pub lp: PoolLp, // lp token information, Once the new pool is successfully created, other data cannot be changed except for supply
+    pub protocol_fee: Option<SwapRatio>, // The ratio of swap fee which belongs to swap fee to
+}
+
+impl StableSwapMarketMaker {
+    pub fn new(
+        subaccount: Subaccount,
+        fee_rate: SwapRatio,
+        token0: &TokenInfo,
+        token1: &TokenInfo,
+        lp: PoolLp,
+        protocol_fee: Option<SwapRatio>,
+    ) -> Self {
+        let decimals = token0.decimals.max(token1.decimals);
+        let multiplier = |d: u8| Nat::from(BigUint::from(10_u8).pow((decimals - d) as u32));
+        Self {
+            subaccount,
+            fee_rate,
+            amplification: DEFAULT_AMPLIFICATION,
+            token0: token0.canister_id,
+            token1: token1.canister_id,
+            multiplier0: multiplier(token0.decimals),
+            multiplier1: multiplier(token1.decimals),
+            reserve0: zero(),
+            reserve1: zero(),
+            block_timestamp_last: 0,
+            lp,
+            protocol_fee,
+        }
+    }
+
+    pub fn replace_protocol_fee(&mut self, protocol_fee: Option<SwapRatio>) -> Option<SwapRatio> {
+        std::mem::replace(&mut self.protocol_fee, protocol_fee)
+    }
+
+    pub fn replace_amplification(&mut self, amplification: u64) -> Result<u64, BusinessError> {
+        if amplification == 0 || MAX_AMPLIFICATION < amplification {
+            return Err(BusinessError::Swap("INVALID_AMPLIFICATION".into()));
+        }
+        Ok(std::mem::replace(&mut self.amplification, amplification))
+    }
+
+    #[cfg(feature = "cdk")]
+    pub fn dummy_tokens(&self, tokens: &HashMap<CanisterId, Cow<'_, TokenInfo>>, pa: &TokenPairAmm) -> Vec<TokenInfo> {
+        self.lp.dummy_tokens(tokens, pa)
+    }
+
+    pub fn accounts(&self, self_canister: &SelfCanister) -> Vec<Account> {
+        vec![Account {
+            owner: self_canister.id(),
+            subaccount: Some(self.subaccount),
+        }]
+    }
+
+    pub fn dummy_canisters(&self) -> Vec<CanisterId> {
+        self.lp.dummy_canisters()
+    }
+
+    // fetches and sorts the reserves for a pair
+    pub fn get_reserves(&self, token_a: CanisterId, token_b: CanisterId) -> (Nat, Nat) {
+        let (token0, _) = sort_tokens(token_a, token_b);
+        let (reserve0, reserve1) = (self.reserve0.clone(), self.reserve1.clone());
+        if token_a == token0 {
+            (reserve0, reserve1)
+        } else {
+            (reserve1, reserve0)
+        }
+    }
+
+    pub fn check_liquidity_removable<F>(
+        &self,
+        token_balance_of: F,
+        from: &Account,
+        liquidity_without_fee: &Nat,
+        fee_to: Option<Account>,
+    ) -> Result<(), BusinessError>
+    where
+        F: Fn(CanisterId, Account) -> Result<Nat, BusinessError>,
+    {
+        self.lp
+            .check_liquidity_removable(|token| token_balance_of(token, *from), liquidity_without_fee, fee_to)
+    }
+
+    /// The invariant of balances
+    pub fn get_invariant(&self, reserve0: &Nat, reserve1: &Nat) -> Result<Nat, BusinessError> {
+        let x0 = &reserve0.0 * &self.multiplier0.0;
+        let x1 = &reserve1.0 * &self.multiplier1.0;
+        get_d(self.amplification, &x0, &x1).map(Nat::from)
+    }
+
+    /// Normalized reserves and multipliers: [x_in, x_out, rate_in, rate_out]
+    fn get_normalized(&self, token_in: CanisterId, token_out: CanisterId) -> Result<[BigUint; 4], BusinessError> {
+        if token_in == self.token0 && token_out == self.token1 {
+            Ok([
+                &self.reserve0.0 * &self.multiplier0.0,
+                &self.reserve1.0 * &self.multiplier1.0,
+                self.multiplier0.0.clone(),
+                self.multiplier1.0.clone(),
+            ])
+        } else if token_in == self.token1 && token_out == self.token0 {
+            Ok([
+                &self.reserve1.0 * &self.multiplier1.0,
+                &self.reserve0.0 * &self.multiplier0.0,
+                self.multiplier1.0.clone(),
+                self.multiplier0.0.clone(),
+            ])
+        } else {
+            Err(BusinessError::Swap("INVALID_TOKEN".into()))
+        }
+    }
+
+    /// Calculate the output amount and the swap fee charged in token out
+    pub fn exchange(
+        &self,
+        amount_in: &Nat,
+        token_in: CanisterId,
+        token_out: CanisterId,
+    ) -> Result<(Nat, Nat), BusinessError> {
+        if *amount_in == *ZERO {
+            return Err(BusinessError::Swap("INSUFFICIENT_INPUT_AMOUNT".into()));
+        }
+        let [x_in, x_out, rate_in, rate_out] = self.get_normalized(token_in, token_out)?;
+        let d = get_d(self.amplification, &x_in, &x_out)?;
+        let x = &x_in + &amount_in.0 * &rate_in;
+        let y = get_y(self.amplification, &x, &d)?;
+        if x_out <= &y + 1_u8 {
+            return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+        }
+        let dy = x_out - y - 1_u8; // ! round down in case of rounding errors
+        let fee = &dy * self.fee_rate.numerator / self.fee_rate.denominator;
+        let amount_out = (dy - &fee) / &rate_out;
+        let fee = fee / rate_out;
+        Ok((Nat::from(amount_out), Nat::from(fee)))
+    }
+
+    // given an input amount of an asset and pair reserves, returns the maximum output amount of the other asset
+    pub fn get_amount_out(
+        &self,
+        self_canister: &SelfCanister,
+        amount_in: &Nat,
+        token_in: CanisterId,
+        token_out: CanisterId,
+    ) -> Result<(Account, Nat), BusinessError> {
+        let pool_account = Account {
+            owner: self_canister.id(),
+            subaccount: Some(self.subaccount),
+        };
+
+        let (amount_out, _) = self.exchange(amount_in, token_in, token_out)?;
+        if amount_out == *ZERO {
+            return Err(BusinessError::Swap("INSUFFICIENT_OUTPUT_AMOUNT".into()));
+        }
+
+        Ok((pool_account, amount_out))
+    }
+
+    // given an output amount of an asset and pair reserves, returns a required input amount of the other asset
+    pub fn get_amount_in(
+        &self,
+        self_canister: &SelfCanister,
+        amount_out: &Nat,
+        token_in: CanisterId,
+        token_out: CanisterId,
+    ) -> Result<(Account, Nat), BusinessError> {
+        let pool_account = Account {
+            owner: self_canister.id(),
+            subaccount: Some(self.subaccount),
+        };
+
+        if *amount_out == *ZERO {
+            return Err(BusinessError::Swap("INSUFFICIENT_OUTPUT_AMOUNT".into()));
+        }
+        let [x_in, x_out, rate_in, rate_out] = self.get_normalized(token_in, token_out)?;
+        let d = get_d(self.amplification, &x_in, &x_out)?;
+
+        //                     amount_out * d
+        // dy_with_fee = ------------------------
+        //                        d - n
+        let n = self.fee_rate.numerator;
+        let denominator = self.fee_rate.denominator;
+        let dy_with_fee = div_rounding_up(
+            &(&amount_out.0 * &rate_out * denominator),
+            &BigUint::from(denominator - n),
+        ) + 2_u8; // ! You must not miss the transfer, and you can get it upward
+        if x_out <= dy_with_fee {
+            return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+        }
+        let x = get_y(self.amplification, &(x_out - dy_with_fee), &d)?;
+        if x <= x_in {
+            return Err(BusinessError::Swap("INSUFFICIENT_LIQUIDITY".into()));
+        }
+        let amount_in = Nat::from(div_rounding_up(&(x - x_in), &rate_in));
+
+        // check on calculate amount
+        let (max_out, _) = self.exchange(&amount_in, token_in, token_out)?;
+        if max_out < *amount_out {
+            return Err(BusinessError::Swap("K".into()));
+        }
+
+        Ok((pool_account, amount_in))
+    }
+
+    /// Calculate liquidity minted by the amounts, the imbalanced part is charged as fee
+    pub fn get_liquidity_minted(&self, amount0: &Nat, amount1: &Nat) -> Result<Nat, BusinessError> {
+        let old = (
+            &self.reserve0.0 * &self.multiplier0.0,
+            &self.reserve1.0 * &self.multiplier1.0,
+        );
+        let new = (
+            &old.0 + &amount0.0 * &self.multiplier0.0,
+            &old.1 + &amount1.0 * &self.multiplier1.0,
+        );
+        let total_supply = self.lp.get_total_supply();
+        let d0 = get_d(self.amplification, &old.0, &old.1)?;
+        let d1 = get_d(self.amplification, &new.0, &new.1)?;
+        if d1 <= d0 {
+            return Err(BusinessError::Liquidity("INSUFFICIENT_LIQUIDITY_MINTED".into()));
+        }
+        if total_supply == *ZERO || d0.is_zero() {
+            return Ok(Nat::from(d1)); // No fee for the first time
+        }
+
+        // fee = fee_rate * n / (4 * (n - 1)) for each token
+        let n = self.fee_rate.numerator as u64 * N_COINS as u64;
+        let denominator = self.fee_rate.denominator as u64 * 4 * (N_COINS as u64 - 1);
+        let adjust = |old: &BigUint, new: &BigUint| {
+            let ideal = &d1 * old / &d0;
+            let difference = if ideal > *new { ideal - new } else { new - ideal };
+            new - difference * n / denominator
+        };
+        let d2 = get_d(self.amplification, &adjust(&old.0, &new.0), &adjust(&old.1, &new.1))?;
+        if d2 <= d0 {
+            return Err(BusinessError::Liquidity("INSUFFICIENT_LIQUIDITY_MINTED".into()));
+        }
+        Ok(Nat::from(total_supply.0 * (d2 - &d0) / d0))
+    }
+
+    pub fn removable(&self) -> bool {
+        self.lp.removable()
+    }
+
+    pub fn swap_to(&self, from: &CanisterId, from_amount: f64) -> Option<(CanisterId, f64)> {
+        if self.reserve0 == *ZERO || self.reserve1 == *ZERO {
+            return None;
+        }
+        // marginal price is close to 1 near the peg
+        let to = if self.token0 == *from {
+            self.token1
+        } else if self.token1 == *from {
+            self.token0
+        } else {
+            return None;
+        };
+        // probe by a small part of the reserve, so the rounding error is small
+        let reserve = if self.token0 == *from {
+            &self.reserve0
+        } else {
+            &self.reserve1
+        };
+        let unit = Nat::from((reserve.0.clone() / 10_000_u32).max(BigUint::from(1_u8)));
+        let (to_amount, fee) = self.exchange(&unit, *from, to).ok()?;
+        Some((to, from_amount * (to_amount.0 + fee.0).to_f64()? / unit.0.to_f64()?))
+    }
+
+    pub fn get_reserve(&self, token: &CanisterId) -> Option<f64> {
+        if self.token0 == *token {
+            self.reserve0.0.to_f64()
+        } else if self.token1 == *token {
+            self.reserve1.0.to_f64()
+        } else {
+            None
+        }
+    }
+
+    pub fn get_fee(&self, amount_in: f64) -> f64 {
+        let n = self.fee_rate.numerator as f64;
+        let d = self.fee_rate.denominator as f64;
+        amount_in * n / d
+    }
+
+    pub fn get_subaccount(&self) -> &Subaccount {
+        &self.subaccount
+    }
+}
+
+// ========================== view ==========================
+
+/// stable swap
+#[derive(Debug, Serialize, Deserialize, Clone, CandidType)]
+pub struct StableSwapMarketMakerView {
+    subaccount: String,
+    fee_rate: SwapRatioView,
+    amplification: u64,
+
+    token0: String,
+    token1: String,
+    multiplier0: String,
+    multiplier1: String,
+    reserve0: String,
+    reserve1: String,
+    block_timestamp_last: u64,
+
+    lp: PoolLpView,
+    protocol_fee: Option<SwapRatioView>,
+}
+
+impl From<StableSwapMarketMaker> for StableSwapMarketMakerView {
+    fn from(value: StableSwapMarketMaker) -> Self {
+        Self {
+            subaccount: hex::encode(value.subaccount),
+            fee_rate: value.fee_rate.into(),
+            amplification: value.amplification,
+            token0: value.token0.to_string(),
+            token1: value.token1.to_string(),
+            multiplier0: value.multiplier0.to_string(),
+            multiplier1: value.multiplier1.to_string(),
+            reserve0: value.reserve0.to_string(),
+            reserve1: value.reserve1.to_string(),
+            block_timestamp_last: value.block_timestamp_last,
+            lp: value.lp.into(),
+            protocol_fee: value.protocol_fee.map(|f| f.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invariant() {
+        // balanced pool, D is the sum of balances
+        let x = BigUint::from(1_000_000_000_u64);
+        let d = get_d(100, &x, &x).unwrap();
+        assert_eq!(d, BigUint::from(2_000_000_000_u64));
+        let y = get_y(100, &x, &d).unwrap();
+        assert!(y.clone() + 1_u8 >= x && y <= x.clone() + 1_u8);
+
+        // low slippage near the peg
+        let x_in = BigUint::from(1_010_000_000_u64);
+        let y = get_y(100, &x_in, &d).unwrap();
+        let out = x - y;
+        assert!(BigUint::from(9_990_000_u64) < out && out < BigUint::from(10_000_000_u64));
+    }
+}
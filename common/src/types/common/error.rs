@@ -3,7 +3,7 @@ use icrc_ledger_types::{icrc1::transfer::TransferError, icrc2::transfer_from::Tr
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::types::{CanisterId, TokenPairAmm, UserId};
+use crate::types::{CanisterId, TokenPairAmm, TokenPoolAmm, UserId};
 
 use super::TokenAccount;
 
@@ -80,6 +80,28 @@ pub enum BusinessError {
     /// Unsupported token pairs
     #[error("token pair is not supported. ([{0}],[{1}])")]
     InvalidTokenPair(CanisterId, CanisterId),
+    /// The allowance of spender is not enough
+    #[error("insufficient allowance. (token: {token}. allowance: {allowance})")]
+    InsufficientAllowance {
+        /// token
+        token: CanisterId,
+        /// allowance
+        allowance: Nat,
+    },
+    /// The current allowance is not the expected one
+    #[error("allowance changed. (token: {token}. allowance: {allowance})")]
+    AllowanceChanged {
+        /// token
+        token: CanisterId,
+        /// allowance
+        allowance: Nat,
+    },
+    /// The same transaction is already done in the transaction window
+    #[error("duplicate transaction. (duplicate_of: {duplicate_of})")]
+    DuplicateTransaction {
+        /// block index of the done transaction
+        duplicate_of: Nat,
+    },
 
     // ================= Concurrency error =================
     /// Apply for a new Request Index error
@@ -117,6 +139,15 @@ pub enum BusinessError {
     /// The token pool is alive, can not remove
     #[error("token pair amm is still alive. ({0})")]
     TokenPairAmmStillAlive(TokenPairAmm),
+    /// Multi-asset pool already exists and cannot be created again
+    #[error("token pool amm is already exist. ({0})")]
+    TokenPoolAmmExist(TokenPoolAmm),
+    /// The multi-asset pool does not exist and cannot be operated
+    #[error("token pool amm is not exist. ({0})")]
+    TokenPoolAmmNotExist(TokenPoolAmm),
+    /// The multi-asset pool is alive, can not remove
+    #[error("token pool amm is still alive. ({0})")]
+    TokenPoolAmmStillAlive(TokenPoolAmm),
     /// Liquidity errors
     #[error("liquidity error: {0}.")]
     Liquidity(String),
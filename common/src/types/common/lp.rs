@@ -12,7 +12,7 @@ use super::*;
 use crate::utils::math::ZERO;
 #[allow(unused)]
 use crate::{
-    types::{AmmText, CanisterId, TokenPairAmm},
+    types::{Amm, AmmText, CanisterId, TokenPairAmm},
     utils::math::zero,
 };
 
@@ -32,14 +32,27 @@ impl PoolLp {
     pub fn dummy_tokens(&self, tokens: &HashMap<CanisterId, Cow<'_, TokenInfo>>, pa: &TokenPairAmm) -> Vec<TokenInfo> {
         match self {
             PoolLp::InnerLP(inner_lp) => inner_lp.dummy_tokens(tokens, pa),
-            PoolLp::OuterLP(_outer_lp) => vec![],
+            PoolLp::OuterLP(outer_lp) => outer_lp.dummy_tokens(tokens, pa),
+        }
+    }
+
+    #[cfg(feature = "cdk")]
+    pub fn pool_dummy_tokens(
+        &self,
+        tokens: &HashMap<CanisterId, Cow<'_, TokenInfo>>,
+        members: &[CanisterId],
+        amm: &Amm,
+    ) -> Vec<TokenInfo> {
+        match self {
+            PoolLp::InnerLP(inner_lp) => inner_lp.pool_dummy_tokens(tokens, members, amm),
+            PoolLp::OuterLP(outer_lp) => outer_lp.pool_dummy_tokens(tokens, members, amm),
         }
     }
 
     pub fn dummy_canisters(&self) -> Vec<CanisterId> {
         match self {
             PoolLp::InnerLP(inner_lp) => inner_lp.dummy_canisters(),
-            PoolLp::OuterLP(_outer_lp) => vec![],
+            PoolLp::OuterLP(outer_lp) => outer_lp.dummy_canisters(),
         }
     }
 
@@ -63,7 +76,7 @@ impl PoolLp {
     {
         match self {
             PoolLp::InnerLP(inner_lp) => inner_lp.mint_fee(token_liquidity_mint_fee, to, amount),
-            PoolLp::OuterLP(_outer_lp) => unimplemented!(),
+            PoolLp::OuterLP(outer_lp) => outer_lp.mint_fee(token_liquidity_mint_fee, to, amount),
         }
     }
 
@@ -73,7 +86,7 @@ impl PoolLp {
     {
         match self {
             PoolLp::InnerLP(inner_lp) => inner_lp.mint(token_liquidity_mint, to, amount),
-            PoolLp::OuterLP(_outer_lp) => unimplemented!(),
+            PoolLp::OuterLP(outer_lp) => outer_lp.mint(token_liquidity_mint, to, amount),
         }
     }
 
@@ -89,7 +102,7 @@ impl PoolLp {
     {
         match self {
             PoolLp::InnerLP(inner_lp) => inner_lp.burn(token_liquidity_burn, from, amount_without_fee, fee),
-            PoolLp::OuterLP(_outer_lp) => unimplemented!(),
+            PoolLp::OuterLP(outer_lp) => outer_lp.burn(token_liquidity_burn, from, amount_without_fee, fee),
         }
     }
 
@@ -104,7 +117,7 @@ impl PoolLp {
     {
         match self {
             PoolLp::InnerLP(inner_lp) => inner_lp.check_liquidity_removable(balance_of, liquidity_without_fee, fee_to),
-            PoolLp::OuterLP(_outer_lp) => unimplemented!(),
+            PoolLp::OuterLP(outer_lp) => outer_lp.check_liquidity_removable(balance_of, liquidity_without_fee, fee_to),
         }
     }
 
@@ -114,6 +127,32 @@ impl PoolLp {
             PoolLp::OuterLP(outer_lp) => outer_lp.total_supply == *ZERO,
         }
     }
+
+    /// The ledger canister of outer lp
+    pub fn outer_canister(&self) -> Option<CanisterId> {
+        match self {
+            PoolLp::InnerLP(_inner_lp) => None,
+            PoolLp::OuterLP(outer_lp) => Some(outer_lp.token_canister_id),
+        }
+    }
+
+    /// Hand the lp of an empty pool to an outer ledger, the other data is kept
+    pub fn replace_outer_lp(&mut self, token_canister_id: CanisterId) -> Result<(), BusinessError> {
+        let PoolLp::InnerLP(inner_lp) = self else {
+            return Err(BusinessError::Liquidity("OUTER_LP_EXIST".into()));
+        };
+        if inner_lp.total_supply != *ZERO {
+            return Err(BusinessError::Liquidity("LIQUIDITY_EXIST".into()));
+        }
+        *self = Self::OuterLP(OuterLP {
+            token_canister_id,
+            total_supply: zero(),
+            decimals: inner_lp.decimals,
+            fee: inner_lp.fee.clone(),
+            minimum_liquidity: inner_lp.minimum_liquidity.clone(),
+        });
+        Ok(())
+    }
 }
 
 /// Internal storage lp
@@ -135,19 +174,25 @@ pub struct InnerLP {
 impl InnerLP {
     #[cfg(feature = "cdk")]
     pub fn dummy_tokens(&self, tokens: &HashMap<CanisterId, Cow<'_, TokenInfo>>, pa: &TokenPairAmm) -> Vec<TokenInfo> {
-        use ic_canister_kit::common::trap;
         let TokenPairAmm { pair, amm } = pa;
-        let amm: AmmText = (*amm).into();
-        let token0 = trap(tokens.get(&pair.get_token0()).ok_or("can not be"));
-        let token1 = trap(tokens.get(&pair.get_token1()).ok_or("can not be"));
-        vec![TokenInfo {
-            canister_id: self.dummy_canister_id.id(),
-            name: format!("{}_{}_LP({})", token0.symbol, token1.symbol, amm.as_ref()),
-            symbol: format!("{}_{}_LP({})", token0.symbol, token1.symbol, amm.as_ref()),
-            decimals: self.decimals,
-            fee: self.fee.clone(),
-            is_lp_token: true,
-        }]
+        self.pool_dummy_tokens(tokens, &[pair.get_token0(), pair.get_token1()], amm)
+    }
+
+    #[cfg(feature = "cdk")]
+    pub fn pool_dummy_tokens(
+        &self,
+        tokens: &HashMap<CanisterId, Cow<'_, TokenInfo>>,
+        members: &[CanisterId],
+        amm: &Amm,
+    ) -> Vec<TokenInfo> {
+        vec![lp_token_info(
+            self.dummy_canister_id.id(),
+            tokens,
+            members,
+            amm,
+            self.decimals,
+            self.fee.clone(),
+        )]
     }
 
     pub fn dummy_canisters(&self) -> Vec<CanisterId> {
@@ -233,6 +278,7 @@ impl InnerLP {
 }
 
 /// External storage lp, is a separate canister, with permission to its mint and burn LP tokens，// ! The canister handling fee should not be destroyed
+/// The minting account of the ledger is the default account of swap canister, lp withdrawn is minted and lp deposited is burned
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
 pub struct OuterLP {
     /// outer canister id
@@ -248,11 +294,119 @@ pub struct OuterLP {
     pub minimum_liquidity: Nat, // Minimum liquidity needs to be recorded, and when removing liquidity, you need to check whether it is achieved.
 }
 
+impl OuterLP {
+    #[cfg(feature = "cdk")]
+    pub fn dummy_tokens(&self, tokens: &HashMap<CanisterId, Cow<'_, TokenInfo>>, pa: &TokenPairAmm) -> Vec<TokenInfo> {
+        let TokenPairAmm { pair, amm } = pa;
+        self.pool_dummy_tokens(tokens, &[pair.get_token0(), pair.get_token1()], amm)
+    }
+
+    #[cfg(feature = "cdk")]
+    pub fn pool_dummy_tokens(
+        &self,
+        tokens: &HashMap<CanisterId, Cow<'_, TokenInfo>>,
+        members: &[CanisterId],
+        amm: &Amm,
+    ) -> Vec<TokenInfo> {
+        vec![lp_token_info(
+            self.token_canister_id,
+            tokens,
+            members,
+            amm,
+            self.decimals,
+            self.fee.clone(),
+        )]
+    }
+
+    /// The balances of outer lp are also kept by the token canister id, the ledger is minted or burned by withdrawal or deposit
+    pub fn dummy_canisters(&self) -> Vec<CanisterId> {
+        vec![self.token_canister_id]
+    }
+
+    pub fn mint_fee<F>(&mut self, token_liquidity_mint_fee: F, to: Account, amount: Nat) -> Result<(), BusinessError>
+    where
+        F: FnOnce(CanisterId, Account, Nat) -> Result<(), BusinessError>,
+    {
+        token_liquidity_mint_fee(self.token_canister_id, to, amount.clone())?;
+        self.total_supply += amount; // Nat will not exceed the accuracy
+        Ok(())
+    }
+
+    pub fn mint<F>(&mut self, token_liquidity_mint: F, to: Account, amount: Nat) -> Result<(), BusinessError>
+    where
+        F: FnOnce(CanisterId, Account, Nat) -> Result<(), BusinessError>,
+    {
+        token_liquidity_mint(self.token_canister_id, to, amount.clone())?;
+        self.total_supply += amount; // Nat will not exceed the accuracy
+        Ok(())
+    }
+
+    pub fn burn<F>(
+        &mut self,
+        token_liquidity_burn: F,
+        from: Account,
+        amount_without_fee: Nat,
+        fee: Option<BurnFee>,
+    ) -> Result<(), BusinessError>
+    where
+        F: FnOnce(CanisterId, Account, Nat, Option<BurnFee>) -> Result<(), BusinessError>,
+    {
+        if let Some(fee) = fee.as_ref().filter(|fee| fee.fee != self.fee) {
+            return Err(BusinessError::Liquidity(format!(
+                "burn fee is not matched: {} != {}",
+                fee.fee, self.fee
+            )));
+        }
+        token_liquidity_burn(
+            self.token_canister_id,
+            from,
+            amount_without_fee.clone(), // will burn amount_without_fee + fee and mint fee to fee_to
+            fee.clone(),
+        )?;
+        let total = amount_without_fee; // only amount, fee would be minted after burned
+        if self.total_supply < total {
+            return Err(BusinessError::Liquidity("INSUFFICIENT_LIQUIDITY".into()));
+        }
+        self.total_supply -= total; // If it becomes negative, it will panic
+        Ok(())
+    }
+
+    pub fn check_liquidity_removable<F>(
+        &self,
+        balance_of: F,
+        liquidity_without_fee: &Nat,
+        fee_to: Option<Account>,
+    ) -> Result<(), BusinessError>
+    where
+        F: Fn(CanisterId) -> Result<Nat, BusinessError>,
+    {
+        // check balance, lp on the ledger must be deposited before removing
+        let required = liquidity_without_fee.clone() + fee_to.map(|_| self.fee.clone()).unwrap_or_default();
+        let balance = balance_of(self.token_canister_id)?;
+        if balance < required {
+            return Err(BusinessError::Liquidity("INSUFFICIENT_LIQUIDITY".into()));
+        }
+
+        // check minimum liquidity
+        let remain = self.total_supply.clone() - liquidity_without_fee.clone();
+        if *ZERO < remain && remain < self.minimum_liquidity {
+            return Err(BusinessError::Liquidity("REMAIN_TOTAL_LIQUIDITY_TOO_SMALL".into()));
+        }
+
+        Ok(())
+    }
+}
+
 impl PoolLp {
     /// new
     pub fn new_inner_lp(dummy_canister_id: DummyCanisterId, token0: &TokenInfo, token1: &TokenInfo) -> Self {
-        let decimals = get_decimals(token0.decimals, token1.decimals);
-        let fee = get_fee(&token0.fee, &token1.fee);
+        Self::new_inner_pool_lp(dummy_canister_id, &[token0, token1])
+    }
+
+    /// new lp of multi-asset pool
+    pub fn new_inner_pool_lp(dummy_canister_id: DummyCanisterId, tokens: &[&TokenInfo]) -> Self {
+        let decimals = get_decimals(&tokens.iter().map(|token| token.decimals).collect::<Vec<_>>());
+        let fee = get_fee(&tokens.iter().map(|token| &token.fee).collect::<Vec<_>>());
 
         // fee * 1000
         let minimum_liquidity = fee.clone() * Nat::from(1000_u64);
@@ -267,16 +421,44 @@ impl PoolLp {
     }
 }
 
-fn get_decimals(decimals0: u8, decimals1: u8) -> u8 {
-    let decimals = decimals0 + decimals1;
-    decimals / 2 + if decimals % 2 == 0 { 0 } else { 1 }
+#[cfg(feature = "cdk")]
+fn lp_token_info(
+    canister_id: CanisterId,
+    tokens: &HashMap<CanisterId, Cow<'_, TokenInfo>>,
+    members: &[CanisterId],
+    amm: &Amm,
+    decimals: u8,
+    fee: Nat,
+) -> TokenInfo {
+    use ic_canister_kit::common::trap;
+    let amm: AmmText = (*amm).into();
+    let symbols = members
+        .iter()
+        .map(|token| trap(tokens.get(token).ok_or("can not be")).symbol.clone())
+        .collect::<Vec<_>>()
+        .join("_");
+    TokenInfo {
+        canister_id,
+        name: format!("{symbols}_LP({})", amm.as_ref()),
+        symbol: format!("{symbols}_LP({})", amm.as_ref()),
+        decimals,
+        fee,
+        is_lp_token: true,
+    }
 }
 
-fn get_fee(fee1: &Nat, fee2: &Nat) -> Nat {
-    let fee1 = fee1.0.to_str_radix(10).len() - 1;
-    let fee2 = fee2.0.to_str_radix(10).len() - 1;
-    let size = fee1 + fee2;
-    let size = size / 2 + if size % 2 == 0 { 0 } else { 1 };
+// the average of decimals, round up
+fn get_decimals(decimals: &[u8]) -> u8 {
+    let count = decimals.len();
+    let sum = decimals.iter().map(|d| *d as usize).sum::<usize>();
+    (sum / count + if sum % count == 0 { 0 } else { 1 }) as u8
+}
+
+// the average of fee digits, round up
+fn get_fee(fees: &[&Nat]) -> Nat {
+    let count = fees.len();
+    let size = fees.iter().map(|fee| fee.0.to_str_radix(10).len() - 1).sum::<usize>();
+    let size = size / count + if size % count == 0 { 0 } else { 1 };
     Nat::from(10_u64.pow(size as u32))
 }
 
@@ -343,21 +525,98 @@ impl From<OuterLP> for OuterLPView {
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
 
     #[test]
     fn test() {
-        let decimals = get_decimals(6, 18);
+        let decimals = get_decimals(&[6, 18]);
         assert_eq!(decimals, 12);
 
-        let fee = get_fee(&Nat::from(10000_u64), &Nat::from(1000000_u64));
+        let fee = get_fee(&[&Nat::from(10000_u64), &Nat::from(1000000_u64)]);
         assert_eq!(fee, Nat::from(100000_u64));
 
-        let fee = get_fee(&Nat::from(10000_u64), &Nat::from(2000000_u64));
+        let fee = get_fee(&[&Nat::from(10000_u64), &Nat::from(2000000_u64)]);
         assert_eq!(fee, Nat::from(100000_u64));
 
-        let fee = get_fee(&Nat::from(10_000_u64), &Nat::from(2_000_000_000_000_u64));
+        let fee = get_fee(&[&Nat::from(10_000_u64), &Nat::from(2_000_000_000_000_u64)]);
         assert_eq!(fee, Nat::from(100_000_000_u64));
+
+        let decimals = get_decimals(&[6, 6, 8]);
+        assert_eq!(decimals, 7);
+    }
+
+    #[test]
+    fn test_outer_lp() {
+        let dummy = CanisterId::from_text("vbofh-iir37-5dl7k-cqehz-wje4h-f2j2s-w4oor-zp54z-7edqh-p3nr7-gb4").unwrap();
+        let ledger = CanisterId::from_text("rrkah-fqaaa-aaaaa-aaaaq-cai").unwrap();
+        let user = Account {
+            owner: CanisterId::from_text("2ibo7-dia").unwrap(),
+            subaccount: None,
+        };
+        let mut lp = PoolLp::InnerLP(InnerLP {
+            dummy_canister_id: DummyCanisterId::new(dummy),
+            total_supply: Nat::from(0_u64),
+            decimals: 12,
+            fee: Nat::from(100_000_000_u64),
+            minimum_liquidity: Nat::from(100_000_000_000_u64),
+        });
+
+        // only empty inner lp can be handed to the ledger
+        lp.replace_outer_lp(ledger).unwrap();
+        assert_eq!(lp.outer_canister(), Some(ledger));
+        assert_eq!(lp.dummy_canisters(), vec![ledger]);
+        assert!(lp.replace_outer_lp(ledger).is_err());
+
+        // minted lp is kept by the ledger canister id, which is minted on the ledger by withdrawal
+        let mut minted = vec![];
+        lp.mint(
+            |token, to, amount| {
+                minted.push((token, to, amount));
+                Ok(())
+            },
+            user,
+            Nat::from(1_000_000_000_000_u64),
+        )
+        .unwrap();
+        assert_eq!(minted, vec![(ledger, user, Nat::from(1_000_000_000_000_u64))]);
+
+        // lp must be deposited (burned on the ledger) before removing
+        let balance = |amount: u64| {
+            move |token: CanisterId| {
+                Ok(if token == ledger {
+                    Nat::from(amount)
+                } else {
+                    Nat::from(0_u64)
+                })
+            }
+        };
+        let liquidity = Nat::from(400_000_000_000_u64);
+        assert!(
+            lp.check_liquidity_removable(balance(399_999_999_999), &liquidity, None)
+                .is_err()
+        );
+        lp.check_liquidity_removable(balance(400_000_000_000), &liquidity, None)
+            .unwrap();
+        assert!(
+            lp.check_liquidity_removable(balance(1_000_000_000_000), &Nat::from(950_000_000_000_u64), None)
+                .is_err()
+        ); // remain too small
+
+        let mut burned = vec![];
+        lp.burn(
+            |token, from, amount, fee| {
+                burned.push((token, from, amount, fee));
+                Ok(())
+            },
+            user,
+            liquidity.clone(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(burned, vec![(ledger, user, liquidity, None)]);
+        let PoolLp::OuterLP(outer) = &lp else { unreachable!() };
+        assert_eq!(outer.total_supply, Nat::from(600_000_000_000_u64));
     }
 }
@@ -9,7 +9,7 @@ use super::{BusinessError, Caller, TimestampNanos};
 
 // Default 1 day time
 #[cfg(feature = "cdk")]
-const TRANSACTION_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+pub const TRANSACTION_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
 
 #[cfg(feature = "cdk")]
 pub fn check_meta(memo: &Option<Vec<u8>>, created: &Option<TimestampNanos>) -> Result<TimestampNanos, BusinessError> {
@@ -59,6 +59,13 @@ pub fn check_caller(owner: &UserId) -> Result<(SelfCanister, Caller), BusinessEr
     Ok((SelfCanister(self_canister_id), Caller(caller)))
 }
 
+/// The canister acts by itself, such as scheduled task.
+#[cfg(feature = "cdk")]
+pub fn check_self_canister() -> (SelfCanister, Caller) {
+    let self_canister_id = self_canister_id();
+    (SelfCanister(self_canister_id), Caller(self_canister_id))
+}
+
 /// Simulate the pool's LP tokens
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, CandidType)]
 pub struct DummyCanisterId(CanisterId);
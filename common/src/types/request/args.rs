@@ -2,18 +2,27 @@ use candid::CandidType;
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "archive-token")]
-use crate::archive::token::{DepositToken, TransferToken, WithdrawToken};
-use crate::types::{ArgWithMeta, CanisterId, TokenInfo, TokenPairAmm};
+use crate::archive::token::{ApproveToken, DepositToken, TransferToken, WithdrawToken};
+use crate::types::{ArgWithMeta, CanisterId, TokenInfo, TokenPairAmm, TokenPoolAmm};
 
 mod frozen;
 pub use frozen::*;
 
+mod token_fee;
+pub use token_fee::*;
+
 mod liquidity_add;
 pub use liquidity_add::*;
 
 mod liquidity_remove;
 pub use liquidity_remove::*;
 
+mod pool_liquidity_add;
+pub use pool_liquidity_add::*;
+
+mod pool_liquidity_remove;
+pub use pool_liquidity_remove::*;
+
 mod pay_exact;
 pub use pay_exact::*;
 
@@ -23,6 +32,24 @@ pub use got_exact::*;
 mod pay_exact_by_loan;
 pub use pay_exact_by_loan::*;
 
+mod pay_exact_split;
+pub use pay_exact_split::*;
+
+mod flash_loan;
+pub use flash_loan::*;
+
+mod limit_order;
+pub use limit_order::*;
+
+mod dca_order;
+pub use dca_order::*;
+
+mod protocol_fees;
+pub use protocol_fees::*;
+
+mod farm;
+pub use farm::*;
+
 #[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
 pub enum RequestArgs {
     // no arg
@@ -39,6 +66,8 @@ pub enum RequestArgs {
     TokenCustomPut(Box<TokenCustomPutArgWithMeta>),
     #[serde(rename = "token_custom_remove")]
     TokenCustomRemove(Box<TokenCustomRemoveArgWithMeta>),
+    #[serde(rename = "token_fee_update")]
+    TokenFeeUpdate(Box<TokenFeeUpdateArgWithMeta>),
     // token
     #[cfg(feature = "archive-token")]
     #[serde(rename = "token_deposit")]
@@ -49,6 +78,9 @@ pub enum RequestArgs {
     #[cfg(feature = "archive-token")]
     #[serde(rename = "token_transfer")]
     TokenTransfer(Box<TokenTransferArgWithMeta>),
+    #[cfg(feature = "archive-token")]
+    #[serde(rename = "token_approve")]
+    TokenApprove(Box<TokenApproveArgWithMeta>),
     // pair create
     #[serde(rename = "pair_create")]
     PairCreate(Box<PairCreateArgWithMeta>),
@@ -66,6 +98,49 @@ pub enum RequestArgs {
     PairSwapTokensForExactTokens(Box<PairSwapTokensForExactTokensArgWithMeta>),
     #[serde(rename = "pair_swap_by_loan")]
     PairSwapByLoan(Box<PairSwapByLoanArgWithMeta>),
+    #[serde(rename = "pair_swap_split")]
+    PairSwapSplit(Box<PairSwapSplitArgWithMeta>),
+    // pair flash loan
+    #[serde(rename = "pair_flash_loan_lend")]
+    PairFlashLoanLend(Box<PairFlashLoanLendArgWithMeta>),
+    #[serde(rename = "pair_flash_loan_repay")]
+    PairFlashLoanRepay(Box<PairFlashLoanRepayArgWithMeta>),
+    #[serde(rename = "pair_flash_loan_default")]
+    PairFlashLoanDefault(Box<PairFlashLoanDefaultArgWithMeta>),
+    // pool create
+    #[serde(rename = "pool_create")]
+    PoolCreate(Box<PoolCreateArgWithMeta>),
+    #[serde(rename = "pool_remove")]
+    PoolRemove(Box<PoolRemoveArgWithMeta>),
+    // pool liquidity
+    #[serde(rename = "pool_liquidity_add")]
+    PoolLiquidityAdd(Box<PoolLiquidityAddArgWithMeta>),
+    #[serde(rename = "pool_liquidity_remove")]
+    PoolLiquidityRemove(Box<PoolLiquidityRemoveArgWithMeta>),
+    // limit order
+    #[serde(rename = "limit_order_place")]
+    LimitOrderPlace(Box<LimitOrderPlaceArgWithMeta>),
+    #[serde(rename = "limit_order_cancel")]
+    LimitOrderCancel(Box<LimitOrderCancelArgWithMeta>),
+    // dca order
+    #[serde(rename = "dca_order_place")]
+    DcaOrderPlace(Box<DcaOrderPlaceArgWithMeta>),
+    #[serde(rename = "dca_order_cancel")]
+    DcaOrderCancel(Box<DcaOrderCancelArgWithMeta>),
+    // protocol fees
+    #[serde(rename = "protocol_fees_collect")]
+    ProtocolFeesCollect(Box<ProtocolFeesCollectArgWithMeta>),
+    // farm
+    #[serde(rename = "farm_create")]
+    FarmCreate(Box<FarmCreateArgWithMeta>),
+    #[serde(rename = "farm_refund")]
+    FarmRefund(Box<FarmRefundArgWithMeta>),
+    #[serde(rename = "farm_stake")]
+    FarmStake(Box<FarmStakeArgWithMeta>),
+    #[serde(rename = "farm_unstake")]
+    FarmUnstake(Box<FarmUnstakeArgWithMeta>),
+    #[serde(rename = "farm_claim")]
+    FarmClaim(Box<FarmClaimArgWithMeta>),
 }
 
 // ============================= wrap =============================
@@ -77,6 +152,8 @@ pub struct TokenFrozenArgWithMeta(ArgWithMeta<TokenFrozenArg>);
 pub struct TokenCustomPutArgWithMeta(ArgWithMeta<TokenInfo>);
 #[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
 pub struct TokenCustomRemoveArgWithMeta(ArgWithMeta<CanisterId>);
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenFeeUpdateArgWithMeta(ArgWithMeta<TokenFeeUpdateArg>);
 // token
 #[cfg(feature = "archive-token")]
 #[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
@@ -87,6 +164,9 @@ pub struct TokenWithdrawArgWithMeta(ArgWithMeta<WithdrawToken>);
 #[cfg(feature = "archive-token")]
 #[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
 pub struct TokenTransferArgWithMeta(ArgWithMeta<TransferToken>);
+#[cfg(feature = "archive-token")]
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenApproveArgWithMeta(ArgWithMeta<ApproveToken>);
 // pair create
 #[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
 pub struct PairCreateArgWithMeta(ArgWithMeta<TokenPairAmm>);
@@ -104,6 +184,49 @@ pub struct PairSwapExactTokensForTokensArgWithMeta(ArgWithMeta<TokenPairSwapExac
 pub struct PairSwapTokensForExactTokensArgWithMeta(ArgWithMeta<TokenPairSwapTokensForExactTokensArg>);
 #[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
 pub struct PairSwapByLoanArgWithMeta(ArgWithMeta<TokenPairSwapByLoanArg>);
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct PairSwapSplitArgWithMeta(ArgWithMeta<TokenPairSwapSplitArg>);
+// pair flash loan
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct PairFlashLoanLendArgWithMeta(ArgWithMeta<FlashLoan>);
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct PairFlashLoanRepayArgWithMeta(ArgWithMeta<FlashLoan>);
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct PairFlashLoanDefaultArgWithMeta(ArgWithMeta<FlashLoan>);
+// pool create
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct PoolCreateArgWithMeta(ArgWithMeta<TokenPoolAmm>);
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct PoolRemoveArgWithMeta(ArgWithMeta<TokenPoolAmm>);
+// pool liquidity
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct PoolLiquidityAddArgWithMeta(ArgWithMeta<TokenPoolLiquidityAddArg>);
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct PoolLiquidityRemoveArgWithMeta(ArgWithMeta<TokenPoolLiquidityRemoveArg>);
+// limit order
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct LimitOrderPlaceArgWithMeta(ArgWithMeta<LimitOrder>);
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct LimitOrderCancelArgWithMeta(ArgWithMeta<LimitOrder>);
+// dca order
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct DcaOrderPlaceArgWithMeta(ArgWithMeta<DcaOrder>);
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct DcaOrderCancelArgWithMeta(ArgWithMeta<DcaOrder>);
+// protocol fees
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct ProtocolFeesCollectArgWithMeta(ArgWithMeta<ProtocolFeesCollectArg>);
+// farm
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct FarmCreateArgWithMeta(ArgWithMeta<Farm>);
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct FarmRefundArgWithMeta(ArgWithMeta<Farm>);
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct FarmStakeArgWithMeta(ArgWithMeta<FarmStakeArg>);
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct FarmUnstakeArgWithMeta(ArgWithMeta<FarmStakeArg>);
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct FarmClaimArgWithMeta(ArgWithMeta<FarmStakeArg>);
 
 // ============================= from =============================
 
@@ -123,6 +246,11 @@ impl From<ArgWithMeta<CanisterId>> for RequestArgs {
         Self::TokenCustomRemove(Box::new(TokenCustomRemoveArgWithMeta(value)))
     }
 }
+impl From<ArgWithMeta<TokenFeeUpdateArg>> for RequestArgs {
+    fn from(value: ArgWithMeta<TokenFeeUpdateArg>) -> Self {
+        Self::TokenFeeUpdate(Box::new(TokenFeeUpdateArgWithMeta(value)))
+    }
+}
 
 // token
 #[cfg(feature = "archive-token")]
@@ -143,6 +271,12 @@ impl From<ArgWithMeta<TransferToken>> for RequestArgs {
         Self::TokenTransfer(Box::new(TokenTransferArgWithMeta(value)))
     }
 }
+#[cfg(feature = "archive-token")]
+impl From<ArgWithMeta<ApproveToken>> for RequestArgs {
+    fn from(value: ArgWithMeta<ApproveToken>) -> Self {
+        Self::TokenApprove(Box::new(TokenApproveArgWithMeta(value)))
+    }
+}
 
 // pair create
 impl ArgWithMeta<TokenPairAmm> {
@@ -182,3 +316,91 @@ impl From<ArgWithMeta<TokenPairSwapByLoanArg>> for RequestArgs {
         Self::PairSwapByLoan(Box::new(PairSwapByLoanArgWithMeta(value)))
     }
 }
+impl From<ArgWithMeta<TokenPairSwapSplitArg>> for RequestArgs {
+    fn from(value: ArgWithMeta<TokenPairSwapSplitArg>) -> Self {
+        Self::PairSwapSplit(Box::new(PairSwapSplitArgWithMeta(value)))
+    }
+}
+
+// pair flash loan
+impl ArgWithMeta<FlashLoan> {
+    pub fn into_pair_flash_loan_lend_request_args(self) -> RequestArgs {
+        RequestArgs::PairFlashLoanLend(Box::new(PairFlashLoanLendArgWithMeta(self)))
+    }
+    pub fn into_pair_flash_loan_repay_request_args(self) -> RequestArgs {
+        RequestArgs::PairFlashLoanRepay(Box::new(PairFlashLoanRepayArgWithMeta(self)))
+    }
+    pub fn into_pair_flash_loan_default_request_args(self) -> RequestArgs {
+        RequestArgs::PairFlashLoanDefault(Box::new(PairFlashLoanDefaultArgWithMeta(self)))
+    }
+}
+
+// pool create
+impl ArgWithMeta<TokenPoolAmm> {
+    pub fn into_pool_create_request_args(self) -> RequestArgs {
+        RequestArgs::PoolCreate(Box::new(PoolCreateArgWithMeta(self)))
+    }
+    pub fn into_pool_remove_request_args(self) -> RequestArgs {
+        RequestArgs::PoolRemove(Box::new(PoolRemoveArgWithMeta(self)))
+    }
+}
+
+// pool liquidity
+impl From<ArgWithMeta<TokenPoolLiquidityAddArg>> for RequestArgs {
+    fn from(value: ArgWithMeta<TokenPoolLiquidityAddArg>) -> Self {
+        Self::PoolLiquidityAdd(Box::new(PoolLiquidityAddArgWithMeta(value)))
+    }
+}
+impl From<ArgWithMeta<TokenPoolLiquidityRemoveArg>> for RequestArgs {
+    fn from(value: ArgWithMeta<TokenPoolLiquidityRemoveArg>) -> Self {
+        Self::PoolLiquidityRemove(Box::new(PoolLiquidityRemoveArgWithMeta(value)))
+    }
+}
+
+// limit order
+impl ArgWithMeta<LimitOrder> {
+    pub fn into_limit_order_place_request_args(self) -> RequestArgs {
+        RequestArgs::LimitOrderPlace(Box::new(LimitOrderPlaceArgWithMeta(self)))
+    }
+    pub fn into_limit_order_cancel_request_args(self) -> RequestArgs {
+        RequestArgs::LimitOrderCancel(Box::new(LimitOrderCancelArgWithMeta(self)))
+    }
+}
+
+// dca order
+impl ArgWithMeta<DcaOrder> {
+    pub fn into_dca_order_place_request_args(self) -> RequestArgs {
+        RequestArgs::DcaOrderPlace(Box::new(DcaOrderPlaceArgWithMeta(self)))
+    }
+    pub fn into_dca_order_cancel_request_args(self) -> RequestArgs {
+        RequestArgs::DcaOrderCancel(Box::new(DcaOrderCancelArgWithMeta(self)))
+    }
+}
+
+// protocol fees
+impl From<ArgWithMeta<ProtocolFeesCollectArg>> for RequestArgs {
+    fn from(value: ArgWithMeta<ProtocolFeesCollectArg>) -> Self {
+        Self::ProtocolFeesCollect(Box::new(ProtocolFeesCollectArgWithMeta(value)))
+    }
+}
+
+// farm
+impl ArgWithMeta<Farm> {
+    pub fn into_farm_create_request_args(self) -> RequestArgs {
+        RequestArgs::FarmCreate(Box::new(FarmCreateArgWithMeta(self)))
+    }
+    pub fn into_farm_refund_request_args(self) -> RequestArgs {
+        RequestArgs::FarmRefund(Box::new(FarmRefundArgWithMeta(self)))
+    }
+}
+impl ArgWithMeta<FarmStakeArg> {
+    pub fn into_farm_stake_request_args(self) -> RequestArgs {
+        RequestArgs::FarmStake(Box::new(FarmStakeArgWithMeta(self)))
+    }
+    pub fn into_farm_unstake_request_args(self) -> RequestArgs {
+        RequestArgs::FarmUnstake(Box::new(FarmUnstakeArgWithMeta(self)))
+    }
+    pub fn into_farm_claim_request_args(self) -> RequestArgs {
+        RequestArgs::FarmClaim(Box::new(FarmClaimArgWithMeta(self)))
+    }
+}
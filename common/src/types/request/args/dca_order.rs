@@ -0,0 +1,86 @@
+use candid::{CandidType, Nat};
+use icrc_ledger_types::icrc1::account::Account;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    types::{BusinessError, CanisterId, SwapTokenPair, TimestampNanos},
+    utils::hash::hash_sha256,
+};
+
+/// Time-weighted order, the escrowed token in is swapped by equal slices over time
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct DcaOrder {
+    pub id: u64,
+    pub owner: Account, // unexecuted token in is returned to owner if cancelled
+
+    pub amount_in: Nat, // pay of all slices
+    pub slices: u32,
+    pub slice_amount_out_min: Nat, // min got of each slice, the last slice is scaled by its pay
+    pub path: Vec<SwapTokenPair>,
+    pub to: Account,
+    pub interval_ns: u64,
+
+    // progress
+    pub executed_slices: u32,
+    pub executed_amount_in: Nat,
+    pub executed_amount_out: Nat,
+    pub next_execution: TimestampNanos,
+
+    pub placed: TimestampNanos,
+}
+
+impl DcaOrder {
+    /// The account which holds the escrowed tokens of all dca orders
+    pub fn escrow(self_canister_id: CanisterId) -> Account {
+        Account {
+            owner: self_canister_id,
+            subaccount: Some(hash_sha256(b"dca_order_escrow")),
+        }
+    }
+
+    /// Pay of each slice except the last one
+    pub fn slice_amount_in(&self) -> Nat {
+        self.amount_in.clone() / self.slices
+    }
+
+    /// Pay of the next slice, the last slice takes the rest
+    pub fn next_slice_amount_in(&self) -> Nat {
+        if self.executed_slices + 1 < self.slices {
+            self.slice_amount_in()
+        } else {
+            self.unexecuted_amount_in()
+        }
+    }
+
+    /// Min got of the next slice
+    pub fn next_slice_amount_out_min(&self) -> Nat {
+        self.slice_amount_out_min.clone() * self.next_slice_amount_in() / self.slice_amount_in()
+    }
+
+    /// The token in which is not swapped yet
+    pub fn unexecuted_amount_in(&self) -> Nat {
+        self.amount_in.clone() - self.executed_amount_in.clone()
+    }
+
+    /// Is it time to execute the next slice
+    pub fn is_due(&self, now: TimestampNanos) -> bool {
+        self.executed_slices < self.slices && self.next_execution.into_inner() <= now.into_inner()
+    }
+
+    /// The time of the slice after the one executed now
+    pub fn next_execution_after(&self, now: TimestampNanos) -> Result<TimestampNanos, BusinessError> {
+        now.into_inner()
+            .checked_add(self.interval_ns)
+            .map(TimestampNanos::from_inner)
+            .ok_or_else(|| BusinessError::Swap("INVALID_DCA_ORDER_INTERVAL".into()))
+    }
+
+    /// Record the executed slice, return true if all slices are executed
+    pub fn executed(&mut self, next_execution: TimestampNanos, amount_in: Nat, amount_out: Nat) -> bool {
+        self.executed_slices += 1;
+        self.executed_amount_in += amount_in;
+        self.executed_amount_out += amount_out;
+        self.next_execution = next_execution;
+        self.slices <= self.executed_slices
+    }
+}
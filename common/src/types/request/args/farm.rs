@@ -0,0 +1,57 @@
+use candid::{CandidType, Nat};
+use icrc_ledger_types::icrc1::account::Account;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    types::{CanisterId, TimestampNanos, TokenPairAmm},
+    utils::hash::hash_sha256,
+};
+
+/// Reward farm of lp token, rewards are escrowed when created and accrued by staked lp
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct Farm {
+    pub id: u64,
+    pub pa: TokenPairAmm,
+    pub lp_token: CanisterId, // inner lp dummy token to stake
+
+    pub reward_token: CanisterId,
+    pub reward_per_second: Nat,
+    pub start: TimestampNanos,
+    pub end: TimestampNanos,
+    pub funder: Account, // pays all rewards when created, and is refunded the unallocated after end
+
+    pub created: TimestampNanos,
+}
+
+impl Farm {
+    /// The account which holds the staked lp and the rewards of all farms
+    pub fn escrow(self_canister_id: CanisterId) -> Account {
+        Account {
+            owner: self_canister_id,
+            subaccount: Some(hash_sha256(b"farm_escrow")),
+        }
+    }
+
+    /// All rewards of the time window
+    pub fn total_reward(&self) -> Nat {
+        self.reward_between(self.start, self.end)
+    }
+
+    /// Rewards released in the time window, by rate
+    pub fn reward_between(&self, from: TimestampNanos, to: TimestampNanos) -> Nat {
+        let from = from.into_inner().max(self.start.into_inner());
+        let to = to.into_inner().min(self.end.into_inner());
+        if to <= from {
+            return Nat::from(0_u32);
+        }
+        self.reward_per_second.clone() * Nat::from(to - from) / Nat::from(1_000_000_000_u64)
+    }
+}
+
+/// Stake, unstake lp or claim rewards of a farm
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct FarmStakeArg {
+    pub farm: u64,
+    pub account: Account,
+    pub amount: Nat, // lp to stake or unstake, rewards claimed
+}
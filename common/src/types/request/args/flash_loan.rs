@@ -0,0 +1,26 @@
+use candid::{CandidType, Nat};
+use icrc_ledger_types::icrc1::account::Account;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{CanisterId, TimestampNanos, TokenPairAmm};
+
+/// Flash loan lent from the pool without collateral, must be repaid with fee after the callback of borrower
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct FlashLoan {
+    pub id: u64,
+    pub pa: TokenPairAmm,
+    pub token: CanisterId,
+    pub borrower: Account, // the owner is the canister called back, the repayment is taken from it after callback
+
+    pub amount: Nat,
+    pub fee: Nat, // charged by the fee rate of pool
+
+    pub lent: TimestampNanos,
+}
+
+impl FlashLoan {
+    /// The amount should be repaid to the pool
+    pub fn repayment(&self) -> Nat {
+        self.amount.clone() + self.fee.clone()
+    }
+}
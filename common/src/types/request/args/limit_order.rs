@@ -0,0 +1,38 @@
+use candid::{CandidType, Nat};
+use icrc_ledger_types::icrc1::account::Account;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    types::{CanisterId, SwapTokenPair, TimestampNanos},
+    utils::hash::hash_sha256,
+};
+
+/// Limit order, the token in is escrowed until filled or cancelled
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct LimitOrder {
+    pub id: u64,
+    pub owner: Account, // escrowed token is returned to owner if cancelled or expired
+
+    pub amount_in: Nat,      // pay
+    pub amount_out_min: Nat, // target price is amount_out_min / amount_in
+    pub path: Vec<SwapTokenPair>,
+    pub to: Account,
+    pub expiration: TimestampNanos,
+
+    pub placed: TimestampNanos,
+}
+
+impl LimitOrder {
+    /// The account which holds the escrowed tokens of all limit orders
+    pub fn escrow(self_canister_id: CanisterId) -> Account {
+        Account {
+            owner: self_canister_id,
+            subaccount: Some(hash_sha256(b"limit_order_escrow")),
+        }
+    }
+
+    /// Is the order expired
+    pub fn is_expired(&self, now: TimestampNanos) -> bool {
+        self.expiration.into_inner() <= now.into_inner()
+    }
+}
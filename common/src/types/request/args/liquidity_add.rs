@@ -3,7 +3,7 @@ use icrc_ledger_types::icrc1::account::Account;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    types::{BusinessError, CanisterId, CheckArgs, SelfCanister, TokenPairAmm},
+    types::{BusinessError, CanisterId, CheckArgs, SelfCanister, TickRange, TokenPairAmm},
     utils::math::ZERO,
 };
 
@@ -20,6 +20,9 @@ pub struct TokenPairLiquidityAddArg {
     pub amount_a_min: Nat,
     pub amount_b_min: Nat,
     pub to: Account,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tick_range: Option<TickRange>,
 }
 
 // check amount
@@ -28,11 +31,18 @@ impl CheckArgs for TokenPairLiquidityAddArg {
 
     fn check_args(&self) -> Result<Self::Result, BusinessError> {
         // check 0
-        if self.amount_a_desired == *ZERO {
-            return Err(BusinessError::Liquidity("INSUFFICIENT_A_AMOUNT_DESIRED".into()));
-        }
-        if self.amount_b_desired == *ZERO {
-            return Err(BusinessError::Liquidity("INSUFFICIENT_B_AMOUNT_DESIRED".into()));
+        if self.pa.amm.is_single_sided() {
+            // single-sided deposit is supported
+            if self.amount_a_desired == *ZERO && self.amount_b_desired == *ZERO {
+                return Err(BusinessError::Liquidity("INSUFFICIENT_AMOUNT_DESIRED".into()));
+            }
+        } else {
+            if self.amount_a_desired == *ZERO {
+                return Err(BusinessError::Liquidity("INSUFFICIENT_A_AMOUNT_DESIRED".into()));
+            }
+            if self.amount_b_desired == *ZERO {
+                return Err(BusinessError::Liquidity("INSUFFICIENT_B_AMOUNT_DESIRED".into()));
+            }
         }
         // ? useless checking
         // if self.amount_a_min == *ZERO {
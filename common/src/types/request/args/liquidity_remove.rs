@@ -3,7 +3,7 @@ use icrc_ledger_types::icrc1::account::Account;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    types::{BurnFee, BusinessError, CanisterId, CheckArgs, SelfCanister, TokenPairAmm},
+    types::{BurnFee, BusinessError, CanisterId, CheckArgs, SelfCanister, TickRange, TokenPairAmm},
     utils::math::ZERO,
 };
 
@@ -22,6 +22,12 @@ pub struct TokenPairLiquidityRemoveArg {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fee: Option<BurnFee>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tick_range: Option<TickRange>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_token: Option<CanisterId>,
 }
 
 // check amount
@@ -34,6 +40,16 @@ impl CheckArgs for TokenPairLiquidityRemoveArg {
             return Err(BusinessError::Liquidity("LIQUIDITY_TOO_SMALL".into()));
         }
 
+        // check single-asset exit
+        if let Some(exit_token) = &self.exit_token {
+            if !self.pa.amm.is_weighted() || self.tick_range.is_some() {
+                return Err(BusinessError::Liquidity("SINGLE_TOKEN_EXIT_NOT_SUPPORTED".into()));
+            }
+            if *exit_token != self.token_a && *exit_token != self.token_b {
+                return Err(BusinessError::Liquidity("INVALID_EXIT_TOKEN".into()));
+            }
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,24 @@
+use candid::{CandidType, Nat};
+use icrc_ledger_types::icrc1::account::Account;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{SelfCanister, SwapTokenPair, TokenPairAmm};
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenPairSwapSplitLeg {
+    pub pas: Vec<TokenPairAmm>,
+
+    pub amount_in: Nat, // pay by this leg
+    pub path: Vec<SwapTokenPair>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenPairSwapSplitArg {
+    pub self_canister: SelfCanister,
+
+    pub from: Account,
+    pub amount_in: Nat,      // pay, sum of all legs
+    pub amount_out_min: Nat, // min got, sum of all legs
+    pub legs: Vec<TokenPairSwapSplitLeg>,
+    pub to: Account,
+}
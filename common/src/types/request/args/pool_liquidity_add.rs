@@ -0,0 +1,36 @@
+use candid::{CandidType, Nat};
+use icrc_ledger_types::icrc1::account::Account;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    types::{BusinessError, CheckArgs, SelfCanister, TokenPairAmm, TokenPoolAmm},
+    utils::math::ZERO,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenPoolLiquidityAddArg {
+    pub self_canister: SelfCanister,
+    pub pool: TokenPoolAmm,
+    pub pa: TokenPairAmm, // the first two tokens of pool
+
+    pub from: Account,
+    pub amounts_desired: Vec<Nat>, // in order of pool tokens
+    pub liquidity_min: Nat,
+    pub to: Account,
+}
+
+// check amount
+impl CheckArgs for TokenPoolLiquidityAddArg {
+    type Result = ();
+
+    fn check_args(&self) -> Result<Self::Result, BusinessError> {
+        if self.amounts_desired.len() != self.pool.pool.get_tokens().len() {
+            return Err(BusinessError::Liquidity("INVALID_AMOUNTS".into()));
+        }
+        // check 0
+        if self.amounts_desired.iter().all(|amount| *amount == *ZERO) {
+            return Err(BusinessError::Liquidity("INSUFFICIENT_AMOUNT_DESIRED".into()));
+        }
+        Ok(())
+    }
+}
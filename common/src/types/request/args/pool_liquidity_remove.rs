@@ -0,0 +1,39 @@
+use candid::{CandidType, Nat};
+use icrc_ledger_types::icrc1::account::Account;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    types::{BurnFee, BusinessError, CheckArgs, SelfCanister, TokenPairAmm, TokenPoolAmm},
+    utils::math::ZERO,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenPoolLiquidityRemoveArg {
+    pub self_canister: SelfCanister,
+    pub pool: TokenPoolAmm,
+    pub pa: TokenPairAmm, // the first two tokens of pool
+
+    pub from: Account,
+    pub liquidity_without_fee: Nat,
+    pub amounts_min: Vec<Nat>, // in order of pool tokens
+    pub to: Account,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee: Option<BurnFee>,
+}
+
+// check amount
+impl CheckArgs for TokenPoolLiquidityRemoveArg {
+    type Result = ();
+
+    fn check_args(&self) -> Result<Self::Result, BusinessError> {
+        // check 0
+        if self.liquidity_without_fee == *ZERO {
+            return Err(BusinessError::Liquidity("LIQUIDITY_TOO_SMALL".into()));
+        }
+        if self.amounts_min.len() != self.pool.pool.get_tokens().len() {
+            return Err(BusinessError::Liquidity("INVALID_AMOUNTS".into()));
+        }
+        Ok(())
+    }
+}
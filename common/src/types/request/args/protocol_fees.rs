@@ -0,0 +1,23 @@
+use candid::CandidType;
+use icrc_ledger_types::icrc1::account::Account;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{CanisterId, SelfCanister, TokenPairAmm};
+
+/// recipient of collected protocol fees, shared by weight
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, CandidType)]
+pub struct ProtocolFeeRecipient {
+    pub account: Account,
+    pub weight: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct ProtocolFeesCollectArg {
+    pub self_canister: SelfCanister,
+
+    pub swap_fee_to: Account,          // burn lp of pools and split the pool tokens
+    pub token_fee_to: Option<Account>, // split the transfer fees of tokens
+    pub pas: Vec<TokenPairAmm>,
+    pub tokens: Vec<CanisterId>,
+    pub recipients: Vec<ProtocolFeeRecipient>,
+}
@@ -0,0 +1,10 @@
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+use crate::types::CanisterId;
+
+#[derive(Debug, Clone, Serialize, Deserialize, CandidType)]
+pub struct TokenFeeUpdateArg {
+    pub token: CanisterId,
+    pub fee: Nat,
+}
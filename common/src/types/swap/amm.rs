@@ -1,7 +1,7 @@
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
 
-use crate::types::BusinessError;
+use crate::types::{BusinessError, SwapRatio};
 
 /// amm algorithm
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, CandidType)]
@@ -12,6 +12,58 @@ impl AsRef<str> for AmmText {
     }
 }
 
+/// Algorithm of market maker, the fee rate is decided by fee tier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, CandidType)]
+pub enum AmmAlgorithm {
+    /// constant product
+    #[serde(rename = "swap_v2")]
+    SwapV2,
+    /// concentrated liquidity
+    #[serde(rename = "swap_v3")]
+    SwapV3,
+    /// stable swap
+    #[serde(rename = "stable_swap")]
+    StableSwap,
+    /// proactive market maker
+    #[serde(rename = "pmm_v1")]
+    PmmV1,
+    /// weighted product
+    #[serde(rename = "weighted")]
+    Weighted,
+    /// multi-asset stable pool
+    #[serde(rename = "stable_pool")]
+    StablePool,
+}
+
+impl AmmAlgorithm {
+    /// text prefix of amm
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SwapV2 => "swap_v2",
+            Self::SwapV3 => "swap_v3",
+            Self::StableSwap => "stable_swap",
+            Self::PmmV1 => "pmm_v1",
+            Self::Weighted => "weighted",
+            Self::StablePool => "stable_pool",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "swap_v2" => Some(Self::SwapV2),
+            "swap_v3" => Some(Self::SwapV3),
+            "stable_swap" => Some(Self::StableSwap),
+            "pmm_v1" => Some(Self::PmmV1),
+            "weighted" => Some(Self::Weighted),
+            "stable_pool" => Some(Self::StablePool),
+            _ => None,
+        }
+    }
+}
+
+/// Denominator of fee of fee tier, fee is in millionths
+pub const FEE_TIER_DENOMINATOR: u32 = 1_000_000;
+
 /// Amm algorithm
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, CandidType)]
 pub enum Amm {
@@ -27,19 +79,103 @@ pub enum Amm {
     /// fee 1%
     #[serde(rename = "swap_v2_1%")]
     SwapV2H1,
+    /// concentrated liquidity, fee 0.01%
+    #[serde(rename = "swap_v3_0.01%")]
+    SwapV3M100,
+    /// concentrated liquidity, fee 0.05%
+    #[serde(rename = "swap_v3_0.05%")]
+    SwapV3M500,
+    /// concentrated liquidity, fee 0.3%
+    #[serde(rename = "swap_v3_0.3%")]
+    SwapV3T3,
+    /// concentrated liquidity, fee 1%
+    #[serde(rename = "swap_v3_1%")]
+    SwapV3H1,
+    /// stable swap, fee 0.01%
+    #[serde(rename = "stable_swap_0.01%")]
+    StableSwapM100,
+    /// stable swap, fee 0.05%
+    #[serde(rename = "stable_swap_0.05%")]
+    StableSwapM500,
+    /// proactive market maker, fee 0.05%
+    #[serde(rename = "pmm_v1_0.05%")]
+    PmmV1M500,
+    /// proactive market maker, fee 0.3%
+    #[serde(rename = "pmm_v1_0.3%")]
+    PmmV1T3,
+    /// weighted product, fee 0.3%
+    #[serde(rename = "weighted_0.3%")]
+    WeightedT3,
+    /// weighted product, fee 1%
+    #[serde(rename = "weighted_1%")]
+    WeightedH1,
+    /// multi-asset stable pool, fee 0.01%
+    #[serde(rename = "stable_pool_0.01%")]
+    StablePoolM100,
+    /// multi-asset stable pool, fee 0.04%
+    #[serde(rename = "stable_pool_0.04%")]
+    StablePoolM400,
+    /// fee tier registered by maintainers, fee in millionths, text is like "swap_v2_0.25%"
+    #[serde(rename = "tier")]
+    Tier(AmmAlgorithm, u32),
 }
 
 impl TryFrom<&str> for Amm {
     type Error = BusinessError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value {
-            "swap_v2_0.01%" => Ok(Self::SwapV2M100),
-            "swap_v2_0.05%" => Ok(Self::SwapV2M500),
-            "swap_v2_0.3%" => Ok(Self::SwapV2T3),
-            "swap_v2_1%" => Ok(Self::SwapV2H1),
-            _ => Err(BusinessError::InvalidAmm(value.to_string())),
+        if let Some(amm) = Self::parse_fixed(value) {
+            return Ok(amm);
         }
+        let amm = Self::parse_tier(value).ok_or_else(|| BusinessError::InvalidAmm(value.to_string()))?;
+        // the same fee as fixed one, such as "swap_v2_0.30%"
+        let text: AmmText = amm.into();
+        Ok(Self::parse_fixed(text.as_ref()).unwrap_or(amm))
+    }
+}
+
+impl Amm {
+    fn parse_fixed(value: &str) -> Option<Self> {
+        let amm = match value {
+            "swap_v2_0.01%" => Self::SwapV2M100,
+            "swap_v2_0.05%" => Self::SwapV2M500,
+            "swap_v2_0.3%" => Self::SwapV2T3,
+            "swap_v2_1%" => Self::SwapV2H1,
+            "swap_v3_0.01%" => Self::SwapV3M100,
+            "swap_v3_0.05%" => Self::SwapV3M500,
+            "swap_v3_0.3%" => Self::SwapV3T3,
+            "swap_v3_1%" => Self::SwapV3H1,
+            "stable_swap_0.01%" => Self::StableSwapM100,
+            "stable_swap_0.05%" => Self::StableSwapM500,
+            "pmm_v1_0.05%" => Self::PmmV1M500,
+            "pmm_v1_0.3%" => Self::PmmV1T3,
+            "weighted_0.3%" => Self::WeightedT3,
+            "weighted_1%" => Self::WeightedH1,
+            "stable_pool_0.01%" => Self::StablePoolM100,
+            "stable_pool_0.04%" => Self::StablePoolM400,
+            _ => return None,
+        };
+        Some(amm)
+    }
+
+    fn parse_tier(value: &str) -> Option<Self> {
+        let (algorithm, fee) = value.strip_suffix('%')?.rsplit_once('_')?;
+        let algorithm = AmmAlgorithm::parse(algorithm)?;
+        let (integer, fraction) = fee.split_once('.').unwrap_or((fee, ""));
+        if integer.is_empty()
+            || 2 < integer.len()
+            || 4 < fraction.len()
+            || !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+        let integer: u32 = integer.parse().ok()?;
+        let fraction: u32 = format!("{fraction:0<4}").parse().ok()?;
+        let fee = integer * 10_000 + fraction;
+        if fee == 0 || FEE_TIER_DENOMINATOR <= fee {
+            return None;
+        }
+        Some(Self::Tier(algorithm, fee))
     }
 }
 
@@ -50,6 +186,27 @@ impl From<Amm> for AmmText {
             Amm::SwapV2M500 => Self("swap_v2_0.05%".to_string()),
             Amm::SwapV2T3 => Self("swap_v2_0.3%".to_string()),
             Amm::SwapV2H1 => Self("swap_v2_1%".to_string()),
+            Amm::SwapV3M100 => Self("swap_v3_0.01%".to_string()),
+            Amm::SwapV3M500 => Self("swap_v3_0.05%".to_string()),
+            Amm::SwapV3T3 => Self("swap_v3_0.3%".to_string()),
+            Amm::SwapV3H1 => Self("swap_v3_1%".to_string()),
+            Amm::StableSwapM100 => Self("stable_swap_0.01%".to_string()),
+            Amm::StableSwapM500 => Self("stable_swap_0.05%".to_string()),
+            Amm::PmmV1M500 => Self("pmm_v1_0.05%".to_string()),
+            Amm::PmmV1T3 => Self("pmm_v1_0.3%".to_string()),
+            Amm::WeightedT3 => Self("weighted_0.3%".to_string()),
+            Amm::WeightedH1 => Self("weighted_1%".to_string()),
+            Amm::StablePoolM100 => Self("stable_pool_0.01%".to_string()),
+            Amm::StablePoolM400 => Self("stable_pool_0.04%".to_string()),
+            Amm::Tier(algorithm, fee) => {
+                let fraction = format!("{:04}", fee % 10_000);
+                let fraction = fraction.trim_end_matches('0');
+                if fraction.is_empty() {
+                    Self(format!("{}_{}%", algorithm.as_str(), fee / 10_000))
+                } else {
+                    Self(format!("{}_{}.{}%", algorithm.as_str(), fee / 10_000, fraction))
+                }
+            }
         }
     }
 }
@@ -66,4 +223,96 @@ impl Amm {
     pub fn into_text(self) -> AmmText {
         self.into()
     }
+
+    /// algorithm of market maker
+    pub fn algorithm(&self) -> AmmAlgorithm {
+        match self {
+            Self::SwapV2M100 | Self::SwapV2M500 | Self::SwapV2T3 | Self::SwapV2H1 => AmmAlgorithm::SwapV2,
+            Self::SwapV3M100 | Self::SwapV3M500 | Self::SwapV3T3 | Self::SwapV3H1 => AmmAlgorithm::SwapV3,
+            Self::StableSwapM100 | Self::StableSwapM500 => AmmAlgorithm::StableSwap,
+            Self::PmmV1M500 | Self::PmmV1T3 => AmmAlgorithm::PmmV1,
+            Self::WeightedT3 | Self::WeightedH1 => AmmAlgorithm::Weighted,
+            Self::StablePoolM100 | Self::StablePoolM400 => AmmAlgorithm::StablePool,
+            Self::Tier(algorithm, _) => *algorithm,
+        }
+    }
+
+    /// swap fee rate
+    pub fn fee_rate(&self) -> SwapRatio {
+        match self {
+            Self::SwapV2M100 | Self::SwapV3M100 | Self::StableSwapM100 | Self::StablePoolM100 => {
+                SwapRatio::new(1, 10_000) // swap fee 0.01%
+            }
+            Self::StablePoolM400 => SwapRatio::new(4, 10_000), // swap fee 0.04%
+            Self::SwapV2M500 | Self::SwapV3M500 | Self::StableSwapM500 | Self::PmmV1M500 => {
+                SwapRatio::new(5, 10_000) // swap fee 0.05%
+            }
+            Self::SwapV2T3 | Self::SwapV3T3 | Self::PmmV1T3 | Self::WeightedT3 => SwapRatio::new(3, 1_000), // swap fee 0.3%
+            Self::SwapV2H1 | Self::SwapV3H1 | Self::WeightedH1 => SwapRatio::new(1, 100), // swap fee 1%
+            Self::Tier(_, fee) => SwapRatio::new(*fee, FEE_TIER_DENOMINATOR),
+        }
+    }
+
+    /// Whether the amm is a fee tier registered by maintainers
+    pub fn is_tier(&self) -> bool {
+        matches!(self, Self::Tier(..))
+    }
+
+    /// Whether liquidity can be added by only one token
+    pub fn is_single_sided(&self) -> bool {
+        matches!(self.algorithm(), AmmAlgorithm::PmmV1 | AmmAlgorithm::Weighted)
+    }
+
+    /// Whether the weights of tokens are required when the pool is created
+    pub fn is_weighted(&self) -> bool {
+        matches!(self.algorithm(), AmmAlgorithm::Weighted)
+    }
+
+    /// Whether the initial price is required when the pool is created
+    pub fn is_concentrated(&self) -> bool {
+        matches!(self.algorithm(), AmmAlgorithm::SwapV3)
+    }
+
+    /// Whether the pool holds more than two tokens, which is identified by TokenPoolAmm
+    pub fn is_multi_asset(&self) -> bool {
+        matches!(self.algorithm(), AmmAlgorithm::StablePool)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amm_text() {
+        let amm = Amm::try_from("swap_v2_0.25%").unwrap();
+        assert_eq!(amm, Amm::Tier(AmmAlgorithm::SwapV2, 2_500));
+        assert_eq!(amm.into_text().as_ref(), "swap_v2_0.25%");
+        assert_eq!(amm.fee_rate(), SwapRatio::new(2_500, 1_000_000));
+
+        let amm = Amm::try_from("stable_pool_2%").unwrap();
+        assert_eq!(amm, Amm::Tier(AmmAlgorithm::StablePool, 20_000));
+        assert_eq!(amm.into_text().as_ref(), "stable_pool_2%");
+        assert!(amm.is_multi_asset());
+
+        // fixed one first
+        assert_eq!(Amm::try_from("swap_v2_0.3%").unwrap(), Amm::SwapV2T3);
+        assert_eq!(Amm::try_from("swap_v2_0.30%").unwrap(), Amm::SwapV2T3);
+        assert_eq!(
+            Amm::Tier(AmmAlgorithm::SwapV3, 500).into_text().as_ref(),
+            "swap_v3_0.05%"
+        );
+
+        for text in [
+            "swap_v2_0%",
+            "swap_v2_100%",
+            "swap_v2_0.00001%",
+            "swap_v2_.1%",
+            "swap_v4_1%",
+            "swap_v2_1",
+        ] {
+            assert!(Amm::try_from(text).is_err(), "{text}");
+        }
+    }
 }
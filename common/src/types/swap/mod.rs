@@ -11,6 +11,10 @@ pub use amm::*;
 mod pair;
 pub use pair::*;
 
+/// multi-asset pool
+mod pool;
+pub use pool::*;
+
 use super::CanisterId;
 
 // =================== token pair pool ===================
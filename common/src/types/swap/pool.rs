@@ -0,0 +1,145 @@
+#[allow(unused)]
+use std::{borrow::Cow, fmt::Display};
+
+use candid::CandidType;
+#[cfg(feature = "cdk")]
+use ic_canister_kit::types::{Bound, Storable};
+use icrc_ledger_types::icrc1::account::Subaccount;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    types::{BusinessError, CanisterId, DummyCanisterId},
+    utils::hash::hash_sha256,
+};
+
+use super::{Amm, AmmText, TokenPair, TokenPairAmm};
+
+/// The max count of tokens in a multi-asset pool
+pub const MAX_POOL_TOKENS: usize = 8;
+
+/// Sequential tokens of multi-asset pool
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, CandidType)]
+pub struct TokenPool {
+    /// sorted tokens, small one first
+    tokens: Vec<CanisterId>,
+}
+
+impl TokenPool {
+    /// new
+    pub fn new(tokens: &[CanisterId]) -> Result<Self, BusinessError> {
+        let mut tokens = tokens.to_vec();
+        tokens.sort();
+        if tokens.windows(2).any(|w| w[0] == w[1]) {
+            return Err(BusinessError::Swap("DUPLICATE_POOL_TOKENS".into()));
+        }
+        if tokens.len() < 2 || MAX_POOL_TOKENS < tokens.len() {
+            return Err(BusinessError::Swap("INVALID_POOL_TOKENS".into()));
+        }
+        Ok(Self { tokens })
+    }
+    /// tokens
+    #[inline]
+    pub fn get_tokens(&self) -> &[CanisterId] {
+        &self.tokens
+    }
+    /// index of token
+    #[inline]
+    pub fn index_of(&self, token: &CanisterId) -> Option<usize> {
+        self.tokens.iter().position(|t| t == token)
+    }
+    /// Is token a member of pool
+    #[inline]
+    pub fn contains(&self, token: &CanisterId) -> bool {
+        self.tokens.contains(token)
+    }
+    /// All token pairs of members
+    pub fn pairs(&self) -> Vec<TokenPair> {
+        let mut pairs = Vec::new();
+        for (i, token_a) in self.tokens.iter().enumerate() {
+            for token_b in &self.tokens[i + 1..] {
+                pairs.push(TokenPair::new(*token_a, *token_b));
+            }
+        }
+        pairs
+    }
+}
+
+/// Sequential tokens of multi-asset pool and algorithms
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, CandidType)]
+pub struct TokenPoolAmm {
+    /// Tokens of pool
+    pub pool: TokenPool,
+    /// amm algorithm
+    pub amm: Amm,
+}
+
+#[cfg(feature = "cdk")]
+impl Storable for TokenPoolAmm {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        use ic_canister_kit::common::trap;
+        Cow::Owned(trap(ic_canister_kit::functions::stable::to_bytes(self)))
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        use ic_canister_kit::common::trap;
+        trap(ic_canister_kit::functions::stable::from_bytes(&bytes))
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl TokenPoolAmm {
+    /// Get the sub-account of the pool
+    pub fn get_subaccount(&self) -> [u8; 32] {
+        let amm: AmmText = self.amm.into();
+        let mut data = Vec::new();
+        for token in &self.pool.tokens {
+            data.extend_from_slice(token.as_slice());
+        }
+        data.extend_from_slice(amm.as_ref().as_bytes());
+        hash_sha256(&data)
+    }
+
+    /// Get the sub-account and simulated token address of the pool
+    pub fn get_subaccount_and_dummy_canister_id(&self) -> (Subaccount, DummyCanisterId) {
+        let subaccount = self.get_subaccount();
+        let canister_id = CanisterId::from_slice(&subaccount[..CanisterId::MAX_LENGTH_IN_BYTES]);
+        (subaccount, DummyCanisterId::new(canister_id))
+    }
+
+    /// The pair of the first two tokens, which stands for the pool in records of token pair
+    pub fn anchor(&self) -> TokenPairAmm {
+        TokenPairAmm {
+            pair: TokenPair::new(self.pool.tokens[0], self.pool.tokens[1]),
+            amm: self.amm,
+        }
+    }
+
+    /// All token pairs of members with the amm of pool
+    pub fn member_pairs(&self) -> Vec<TokenPairAmm> {
+        self.pool
+            .pairs()
+            .into_iter()
+            .map(|pair| TokenPairAmm { pair, amm: self.amm })
+            .collect()
+    }
+
+    /// Is the token pair amm a member pair of pool
+    pub fn contains_pair(&self, pa: &TokenPairAmm) -> bool {
+        self.amm == pa.amm && self.pool.contains(&pa.pair.get_token0()) && self.pool.contains(&pa.pair.get_token1())
+    }
+
+    /// not exist
+    pub fn not_exist(&self) -> BusinessError {
+        BusinessError::TokenPoolAmmNotExist(self.clone())
+    }
+}
+
+impl Display for TokenPoolAmm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for token in &self.pool.tokens {
+            write!(f, "[{}],", token.to_text())?;
+        }
+        write!(f, "{}", self.amm.into_text().as_ref())
+    }
+}
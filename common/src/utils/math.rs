@@ -1,4 +1,5 @@
 use candid::Nat;
+use num_traits::ToPrimitive;
 use once_cell::sync::Lazy;
 
 /// zero
@@ -10,6 +11,11 @@ pub fn zero() -> Nat {
 #[allow(unused)]
 pub static ZERO: Lazy<Nat> = Lazy::new(zero);
 
+/// approximate value, for display or estimation only
+pub fn nat_to_f64(value: &Nat) -> f64 {
+    value.0.to_f64().unwrap_or(f64::MAX)
+}
+
 #[test]
 fn test_nat() {
     let values: Vec<u32> = vec![3150766848, 7362];